@@ -0,0 +1,11 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum AuditError {
+    #[error("Id of the audited record is empty")]
+    IdEmpty,
+    #[error("The audited record can't be converted to a value: {0}")]
+    Content(String),
+    #[error("{0}")]
+    Db(#[from] surrealdb::Error),
+}
@@ -0,0 +1,314 @@
+//! Audit log of the `Table` writes
+//!
+//! `Audit` runs create, update and delete like the `Table` functions and writes an `AuditEntry` with the actor,
+//! the record before and after the change and the time to the audit table, `audit` by default.
+//! The write and its entry run as one statement, so either both are written or neither.
+//! `entries` and `table_entries` read the entries back with the record type.
+//!
+//! Updates are merged like `Table::update`, the version of `#[table(versioned)]` is not checked.
+//!
+//! # Example
+//!
+//! ```rust
+//! use serde::{Serialize, Deserialize};
+//! use surrealdb::engine::any::connect;
+//! use surrealdb::sql::Thing as RecordId;
+//! use surrealdb_extra::audit::{Audit, AuditAction};
+//! use surrealdb_extra::table::Table;
+//!
+//! #[derive(Table, Serialize, Deserialize, Clone)]
+//! #[table(name = "user")]
+//! struct User {
+//!     id: Option<RecordId>,
+//!     name: String
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let db = connect("mem://").await.unwrap();
+//!     db.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     let audit = Audit::new().actor("admin");
+//!
+//!     let mut user = audit.create(&db, User { id: None, name: "one".to_string() }).await.unwrap().unwrap();
+//!     user.name = "two".to_string();
+//!     let user = audit.update(&db, user).await.unwrap().unwrap();
+//!
+//!     let entries = audit.entries::<User, _>(&db, user.id.unwrap()).await.unwrap();
+//!     assert_eq!(entries.len(), 2);
+//!     assert_eq!(entries[1].action, AuditAction::Update);
+//!     assert_eq!(entries[1].actor.as_deref(), Some("admin"));
+//!     assert_eq!(entries[1].changed_fields(), ["name"]);
+//! }
+//! ```
+
+pub mod err;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use surrealdb::{Connection, Surreal};
+use surrealdb::sql::{Datetime, Thing, Value};
+use crate::table::{Table, TableError};
+use crate::table::id::{self, IntoTableId};
+use crate::table::write::write_content;
+pub use crate::audit::err::AuditError;
+
+pub const DEFAULT_TABLE: &str = "audit";
+
+const CREATE: &str = "RETURN {
+    LET $after = (CREATE ONLY type::table($table) CONTENT $content);
+    CREATE type::table($audit) CONTENT { table: $table, record: $after.id, action: 'create', actor: $actor, after: $after, timestamp: time::now() };
+    RETURN $after;
+}";
+
+const UPDATE: &str = "RETURN {
+    LET $before = (SELECT * FROM $id)[0];
+    LET $after = (UPDATE $id MERGE $content RETURN AFTER)[0];
+    IF $after {
+        CREATE type::table($audit) CONTENT { table: $table, record: $id, action: 'update', actor: $actor, before: $before, after: $after, timestamp: time::now() };
+    };
+    RETURN $after;
+}";
+
+const DELETE: &str = "RETURN {
+    LET $before = (DELETE $id RETURN BEFORE)[0];
+    IF $before {
+        CREATE type::table($audit) CONTENT { table: $table, record: $id, action: 'delete', actor: $actor, before: $before, timestamp: time::now() };
+    };
+    RETURN $before;
+}";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditAction {
+    Create,
+    Update,
+    Delete,
+}
+
+/// An entry of the audit table, `before` is empty for creates and `after` for deletes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry<T> {
+    pub id: Option<Thing>,
+    pub table: String,
+    pub record: Thing,
+    pub action: AuditAction,
+    pub actor: Option<String>,
+    pub before: Option<T>,
+    pub after: Option<T>,
+    pub timestamp: Datetime,
+}
+
+impl<T: Serialize + Clone + 'static> AuditEntry<T> {
+    /// The top level fields that differ between `before` and `after`, every field of the record for creates and deletes
+    pub fn changed_fields(&self) -> Vec<String> {
+        let fields = |record: &Option<T>| match record.clone().map(surrealdb::sql::to_value) {
+            Some(Ok(Value::Object(object))) => object.0,
+            _ => Default::default(),
+        };
+
+        let before = fields(&self.before);
+        let after = fields(&self.after);
+
+        let mut changed: Vec<String> = before.keys().chain(after.keys())
+            .filter(|field| before.get(*field).unwrap_or(&Value::None) != after.get(*field).unwrap_or(&Value::None))
+            .cloned()
+            .collect();
+
+        changed.sort();
+        changed.dedup();
+
+        changed
+    }
+}
+
+/// Writes the records together with their audit entries
+#[derive(Debug, Clone)]
+pub struct Audit {
+    pub table: String,
+    pub actor: Option<String>,
+}
+
+impl Default for Audit {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Audit {
+    pub fn new() -> Self {
+        Self {
+            table: DEFAULT_TABLE.to_string(),
+            actor: None,
+        }
+    }
+
+    /// The table the entries are written to
+    pub fn table(mut self, table: impl Into<String>) -> Self {
+        self.table = table.into();
+
+        self
+    }
+
+    /// Who made the change, e.g. the id of the signed in user
+    pub fn actor(mut self, actor: impl Into<String>) -> Self {
+        self.actor = Some(actor.into());
+
+        self
+    }
+
+    pub async fn create<T: Table, C: Connection>(&self, db: &Surreal<C>, mut t: T) -> Result<Option<T>> {
+        t.before_create(db).await?;
+        t.validate_record().map_err(TableError::from)?;
+        id::assign_id(&mut t, db).await?;
+
+        let t: Option<T> = db.query(CREATE)
+            .bind(("table", T::TABLE_NAME))
+            .bind(("content", write_content(t)?))
+            .bind(self.bindings())
+            .await.map_err(AuditError::from)?
+            .take(0).map_err(AuditError::from)?;
+
+        if let Some(t) = &t {
+            t.after_create(db).await?;
+        }
+
+        Ok(t)
+    }
+
+    /// Merges the record like `Table::update`, no entry is written when the record doesn't exist
+    pub async fn update<T: Table, C: Connection>(&self, db: &Surreal<C>, mut t: T) -> Result<Option<T>> {
+        t.before_update(db).await?;
+        t.validate_record().map_err(TableError::from)?;
+
+        let id = t.get_id().clone().ok_or(AuditError::IdEmpty)?;
+
+        let t: Option<T> = db.query(UPDATE)
+            .bind(("table", T::TABLE_NAME))
+            .bind(("id", id))
+            .bind(("content", write_content(t)?))
+            .bind(self.bindings())
+            .await.map_err(AuditError::from)?
+            .take(0).map_err(AuditError::from)?;
+
+        if let Some(t) = &t {
+            t.after_update(db).await?;
+        }
+
+        Ok(t)
+    }
+
+    /// No entry is written when the record doesn't exist
+    pub async fn delete<T: Table, C: Connection>(&self, db: &Surreal<C>, id: impl IntoTableId<T>) -> Result<Option<T>> {
+        let id = id.into_table_id()?;
+
+        T::before_delete(db, &id).await?;
+
+        let t: Option<T> = db.query(DELETE)
+            .bind(("table", T::TABLE_NAME))
+            .bind(("id", id))
+            .bind(self.bindings())
+            .await.map_err(AuditError::from)?
+            .take(0).map_err(AuditError::from)?;
+
+        Ok(t)
+    }
+
+    /// The entries of the record, oldest first
+    pub async fn entries<T: Table, C: Connection>(&self, db: &Surreal<C>, id: impl IntoTableId<T>) -> Result<Vec<AuditEntry<T>>> {
+        let entries: Vec<AuditEntry<T>> = db.query("SELECT * FROM type::table($audit) WHERE record = $id ORDER BY timestamp ASC")
+            .bind(("audit", self.table.clone()))
+            .bind(("id", id.into_table_id()?))
+            .await.map_err(AuditError::from)?
+            .take(0).map_err(AuditError::from)?;
+
+        Ok(entries)
+    }
+
+    /// The entries of every record of the table, oldest first
+    pub async fn table_entries<T: Table, C: Connection>(&self, db: &Surreal<C>) -> Result<Vec<AuditEntry<T>>> {
+        let entries: Vec<AuditEntry<T>> = db.query("SELECT * FROM type::table($audit) WHERE table = $table ORDER BY timestamp ASC")
+            .bind(("audit", self.table.clone()))
+            .bind(("table", T::TABLE_NAME))
+            .await.map_err(AuditError::from)?
+            .take(0).map_err(AuditError::from)?;
+
+        Ok(entries)
+    }
+
+    fn bindings(&self) -> AuditBindings {
+        AuditBindings {
+            audit: self.table.clone(),
+            actor: self.actor.clone(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct AuditBindings {
+    audit: String,
+    actor: Option<String>,
+}
+
+#[cfg(test)]
+mod test {
+    use surrealdb::sql::Thing as RecordId;
+    use crate::testing::db;
+    use super::*;
+
+    #[derive(Debug, Table, Serialize, Deserialize, Clone, PartialEq)]
+    #[table(name = "user")]
+    struct User {
+        id: Option<RecordId>,
+        name: String,
+        age: u8,
+    }
+
+    #[test]
+    fn changed_fields() {
+        let user = User { id: None, name: "one".to_string(), age: 1 };
+
+        let mut entry = AuditEntry {
+            id: None,
+            table: "user".to_string(),
+            record: RecordId::from(("user", "one")),
+            action: AuditAction::Update,
+            actor: None,
+            before: Some(user.clone()),
+            after: Some(User { age: 2, ..user.clone() }),
+            timestamp: Datetime::default(),
+        };
+
+        assert_eq!(entry.changed_fields(), ["age"]);
+
+        entry.after = None;
+
+        assert_eq!(entry.changed_fields(), ["age", "name"]);
+    }
+
+    #[tokio::test]
+    async fn audit_writes() {
+        let db = db().await;
+
+        let audit = Audit::new().table("log").actor("admin");
+
+        let user = audit.create(&db, User { id: None, name: "one".to_string(), age: 1 }).await.unwrap().unwrap();
+        let id = user.id.clone().unwrap();
+
+        let updated = audit.update(&db, User { age: 2, ..user.clone() }).await.unwrap().unwrap();
+        assert_eq!(updated.age, 2);
+
+        assert_eq!(audit.delete::<User, _>(&db, id.clone()).await.unwrap(), Some(updated.clone()));
+        assert_eq!(audit.delete::<User, _>(&db, id.clone()).await.unwrap(), None);
+
+        let entries = audit.entries::<User, _>(&db, id.clone()).await.unwrap();
+
+        assert_eq!(entries.iter().map(|e| e.action).collect::<Vec<_>>(), [AuditAction::Create, AuditAction::Update, AuditAction::Delete]);
+        assert_eq!(entries[0].after, Some(user.clone()));
+        assert_eq!((entries[1].before.clone(), entries[1].after.clone()), (Some(user), Some(updated.clone())));
+        assert_eq!((entries[2].before.clone(), entries[2].after.clone()), (Some(updated), None));
+        assert!(entries.iter().all(|e| e.actor.as_deref() == Some("admin") && e.record == id));
+
+        assert_eq!(audit.table_entries::<User, _>(&db).await.unwrap().len(), 3);
+    }
+}
@@ -0,0 +1,11 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum AuthError {
+    #[error("The connection has no namespace and/or database selected")]
+    NoDatabase,
+    #[error("The session has no record, the access method did not return one or the table permissions do not allow selecting it")]
+    NoRecord,
+    #[error("{0}")]
+    Db(#[from] surrealdb::Error),
+}
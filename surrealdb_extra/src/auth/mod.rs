@@ -0,0 +1,225 @@
+//! Record authentication with the `Table` structs
+//!
+//! `signup_record` and `signin_record` use the fields of the credentials struct as the parameters of the `SIGNUP`/`SIGNIN`
+//! clause of a `DEFINE ACCESS ... TYPE RECORD` (the scopes of SurrealDB 1), on the namespace and database of the connection.
+//! Both authenticate the connection and return a `Session` with the token.
+//!
+//! `Session::record` selects `$auth`, so the table needs a `select` permission for its own record,
+//! e.g. `#[table(permissions(select = "id = $auth.id"))]`.
+//!
+//! # Example
+//!
+//! ```rust
+//! use serde::{Serialize, Deserialize};
+//! use surrealdb::engine::any::connect;
+//! use surrealdb::sql::Thing as RecordId;
+//! use surrealdb_extra::auth;
+//! use surrealdb_extra::table::Table;
+//!
+//! #[derive(Table, Serialize, Deserialize, Clone)]
+//! #[table(name = "user", permissions(select = "id = $auth.id"))]
+//! struct User {
+//!     id: Option<RecordId>,
+//!     email: String,
+//!     pass: String
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let db = connect("mem://").await.unwrap();
+//!     db.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     User::init_schema(&db).await.unwrap();
+//!     db.query("
+//!         DEFINE ACCESS account ON DATABASE TYPE RECORD
+//!             SIGNUP (CREATE user SET email = $email, pass = crypto::argon2::generate($pass))
+//!             SIGNIN (SELECT * FROM user WHERE email = $email AND crypto::argon2::compare(pass, $pass));
+//!     ").await.unwrap();
+//!
+//!     let credentials = User { id: None, email: "user@example.com".to_string(), pass: "secret".to_string() };
+//!
+//!     let session = auth::signup_record(&db, "account", &credentials).await.unwrap();
+//!
+//!     let user = session.record(&db).await.unwrap();
+//!     assert_eq!(user.email, "user@example.com");
+//! }
+//! ```
+
+pub mod err;
+
+use std::fmt;
+use std::marker::PhantomData;
+use anyhow::Result;
+use surrealdb::opt::auth::{Jwt, Record};
+use surrealdb::{Connection, Surreal};
+use crate::table::Table;
+pub use crate::auth::err::AuthError;
+
+/// The token of a signed in record user of the table `T`
+pub struct Session<T> {
+    pub token: Jwt,
+    _record: PhantomData<fn() -> T>,
+}
+
+impl<T> Clone for Session<T> {
+    fn clone(&self) -> Self {
+        Self::new(self.token.clone())
+    }
+}
+
+impl<T> fmt::Debug for Session<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Session").field("token", &self.token).finish()
+    }
+}
+
+impl<T> From<Jwt> for Session<T> {
+    fn from(token: Jwt) -> Self {
+        Self::new(token)
+    }
+}
+
+impl<T> Session<T> {
+    pub fn new(token: Jwt) -> Self {
+        Self {
+            token,
+            _record: PhantomData,
+        }
+    }
+
+    pub fn token(&self) -> &Jwt {
+        &self.token
+    }
+
+    pub fn into_token(self) -> Jwt {
+        self.token
+    }
+
+    /// Authenticates another connection, or the same connection after `invalidate`, with the token
+    pub async fn authenticate<C: Connection>(&self, db: &Surreal<C>) -> Result<()> {
+        db.authenticate(self.token.clone()).await.map_err(AuthError::from)?;
+
+        Ok(())
+    }
+}
+
+impl<T: Table> Session<T> {
+    /// Selects the `$auth` record of the connection
+    pub async fn record<C: Connection>(&self, db: &Surreal<C>) -> Result<T> {
+        let record: Option<T> = db.query("SELECT * FROM ONLY $auth")
+            .await.map_err(AuthError::from)?
+            .take(0).map_err(AuthError::from)?;
+
+        record.ok_or(AuthError::NoRecord.into())
+    }
+}
+
+/// Signs up with the `SIGNUP` clause of the record access method
+pub async fn signup_record<C: Connection, T: Table>(db: &Surreal<C>, access: &str, credentials: &T) -> Result<Session<T>> {
+    let (namespace, database) = session_database(db).await?;
+
+    let token = db.signup(Record {
+        namespace: &namespace,
+        database: &database,
+        access,
+        params: credentials,
+    }).await.map_err(AuthError::from)?;
+
+    Ok(Session::new(token))
+}
+
+/// Signs in with the `SIGNIN` clause of the record access method
+pub async fn signin_record<C: Connection, T: Table>(db: &Surreal<C>, access: &str, credentials: &T) -> Result<Session<T>> {
+    let (namespace, database) = session_database(db).await?;
+
+    let token = db.signin(Record {
+        namespace: &namespace,
+        database: &database,
+        access,
+        params: credentials,
+    }).await.map_err(AuthError::from)?;
+
+    Ok(Session::new(token))
+}
+
+/// The namespace and database selected with `use_ns` and `use_db`
+async fn session_database<C: Connection>(db: &Surreal<C>) -> Result<(String, String)> {
+    let mut res = db.query("RETURN session::ns(); RETURN session::db()")
+        .await.map_err(AuthError::from)?;
+
+    let namespace: Option<String> = res.take(0).map_err(AuthError::from)?;
+    let database: Option<String> = res.take(1).map_err(AuthError::from)?;
+
+    match (namespace, database) {
+        (Some(namespace), Some(database)) => Ok((namespace, database)),
+        _ => Err(AuthError::NoDatabase.into()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde::{Deserialize, Serialize};
+    use surrealdb::engine::any::Any;
+    use surrealdb::sql::Thing;
+    use super::*;
+
+    #[derive(Debug, Table, Serialize, Deserialize, Clone)]
+    #[table(name = "user", permissions(select = "id = $auth.id"))]
+    struct User {
+        id: Option<Thing>,
+        email: String,
+        pass: String,
+    }
+
+    fn credentials(pass: &str) -> User {
+        User { id: None, email: "user@example.com".to_string(), pass: pass.to_string() }
+    }
+
+    async fn db() -> Surreal<Any> {
+        let db = crate::testing::db().await;
+
+        User::init_schema(&db).await.unwrap();
+        db.query("
+            DEFINE ACCESS account ON DATABASE TYPE RECORD
+                SIGNUP (CREATE user SET email = $email, pass = crypto::argon2::generate($pass))
+                SIGNIN (SELECT * FROM user WHERE email = $email AND crypto::argon2::compare(pass, $pass));
+        ").await.unwrap().check().unwrap();
+
+        db
+    }
+
+    #[tokio::test]
+    async fn signup_and_signin() {
+        let db = db().await;
+
+        let signed_up = signup_record(&db, "account", &credentials("secret")).await.unwrap();
+        let user = signed_up.record(&db).await.unwrap();
+
+        assert_eq!(user.email, "user@example.com");
+        assert_ne!(user.pass, "secret");
+
+        db.invalidate().await.unwrap();
+
+        assert!(signin_record(&db, "account", &credentials("wrong")).await.is_err());
+
+        let signed_in = signin_record(&db, "account", &credentials("secret")).await.unwrap();
+
+        assert_eq!(signed_in.record(&db).await.unwrap().id, user.id);
+    }
+
+    #[tokio::test]
+    async fn authenticate() {
+        let db = db().await;
+
+        let session = signup_record(&db, "account", &credentials("secret")).await.unwrap();
+
+        db.invalidate().await.unwrap();
+
+        let err = session.record(&db).await.unwrap_err();
+        assert!(matches!(err.downcast_ref::<AuthError>(), Some(AuthError::NoRecord)));
+
+        session.authenticate(&db).await.unwrap();
+
+        assert_eq!(session.record(&db).await.unwrap().email, "user@example.com");
+    }
+}
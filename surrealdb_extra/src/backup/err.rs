@@ -0,0 +1,13 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum BackupError {
+    #[error("The table name `{0}` can only have alphanumeric and/or `_` characters")]
+    InvalidTable(String),
+    #[error("Invalid backup: {0}")]
+    InvalidBackup(String),
+    #[error("{0}")]
+    Io(#[from] std::io::Error),
+    #[error("{0}")]
+    Db(#[from] surrealdb::Error),
+}
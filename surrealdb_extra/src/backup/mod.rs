@@ -0,0 +1,467 @@
+//! Logical backups of a database or of selected tables
+//!
+//! The backup is SurrealQL: the `DEFINE` statements of the analyzers, functions and params of the database,
+//! then of every table with its fields, indexes and events, followed by `INSERT` statements with the records in batches. It only uses queries,
+//! so it works on every engine, e.g. over WebSocket where the `export`/`import` of the sdk are not supported.
+//!
+//! With `compress` the backup is gzip compressed, `import` detects compressed backups by itself.
+//! `import` runs the statements of the backup in batches with `OPTION IMPORT`, so events don't run, and reports the progress after every batch.
+//! Every batch is one transaction, a failed batch changes nothing and stops the import, the batches before it stay imported.
+//!
+//! # Example
+//!
+//! ```rust
+//! use surrealdb::engine::any::connect;
+//! use surrealdb_extra::backup::{self, ExportConfig, ImportConfig};
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let db = connect("mem://").await.unwrap();
+//!     db.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     db.query("CREATE user:1 SET name = 'name'; CREATE order:1 SET user = user:1; CREATE log:1").await.unwrap();
+//!
+//!     let mut backup = vec![];
+//!     backup::export_tables(&db, &["user", "order"], &mut backup).await.unwrap();
+//!
+//!     let mut compressed = vec![];
+//!     let config = ExportConfig::new()
+//!         .compress(true)
+//!         .on_progress(|p| println!("{}: {}/{}", p.table, p.records, p.total));
+//!     backup::export(&db, config, &mut compressed).await.unwrap();
+//!
+//!     let restored = connect("mem://").await.unwrap();
+//!     restored.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     backup::import(&restored, ImportConfig::new(), backup.as_slice()).await.unwrap();
+//! }
+//! ```
+
+pub mod err;
+
+use std::io::{Read, Write};
+use anyhow::Result;
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use surrealdb::{Connection, Surreal};
+use surrealdb::sql::{parse, Part, Statement, Value};
+pub use crate::backup::err::BackupError;
+
+pub const DEFAULT_BATCH_SIZE: u64 = 1000;
+
+pub const DEFAULT_IMPORT_BATCH_SIZE: usize = 100;
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Progress of an export, `records` of `total` records of the table are written
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExportProgress {
+    pub table: String,
+    pub records: u64,
+    pub total: u64,
+}
+
+/// Progress of an import, `statements` of `total` statements are imported
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportProgress {
+    pub statements: usize,
+    pub total: usize,
+}
+
+type OnExportProgress = Box<dyn FnMut(&ExportProgress) + Send>;
+
+type OnImportProgress = Box<dyn FnMut(&ImportProgress) + Send>;
+
+pub struct ExportConfig {
+    /// Every table when `None`
+    pub tables: Option<Vec<String>>,
+    pub compress: bool,
+    pub batch_size: u64,
+    pub(crate) on_progress: Option<OnExportProgress>,
+}
+
+impl Default for ExportConfig {
+    fn default() -> Self {
+        Self {
+            tables: None,
+            compress: false,
+            batch_size: DEFAULT_BATCH_SIZE,
+            on_progress: None,
+        }
+    }
+}
+
+impl ExportConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn tables(mut self, tables: &[&str]) -> Self {
+        self.tables = Some(tables.iter().map(|t| t.to_string()).collect());
+
+        self
+    }
+
+    pub fn compress(mut self, compress: bool) -> Self {
+        self.compress = compress;
+
+        self
+    }
+
+    /// Records per `INSERT` statement
+    pub fn batch_size(mut self, batch_size: u64) -> Self {
+        self.batch_size = batch_size.max(1);
+
+        self
+    }
+
+    /// Called after every batch
+    pub fn on_progress(mut self, on_progress: impl FnMut(&ExportProgress) + Send + 'static) -> Self {
+        self.on_progress = Some(Box::new(on_progress));
+
+        self
+    }
+}
+
+pub struct ImportConfig {
+    pub batch_size: usize,
+    pub(crate) on_progress: Option<OnImportProgress>,
+}
+
+impl Default for ImportConfig {
+    fn default() -> Self {
+        Self {
+            batch_size: DEFAULT_IMPORT_BATCH_SIZE,
+            on_progress: None,
+        }
+    }
+}
+
+impl ImportConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Statements per transaction, `usize::MAX` imports the whole backup in one transaction
+    pub fn batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+
+        self
+    }
+
+    /// Called after every committed batch
+    pub fn on_progress(mut self, on_progress: impl FnMut(&ImportProgress) + Send + 'static) -> Self {
+        self.on_progress = Some(Box::new(on_progress));
+
+        self
+    }
+}
+
+/// Exports the tables with their definitions and records, returns the number of exported records
+pub async fn export_tables<C: Connection>(db: &Surreal<C>, tables: &[&str], writer: impl Write) -> Result<u64> {
+    export(db, ExportConfig::new().tables(tables), writer).await
+}
+
+/// Exports the tables of the config, returns the number of exported records
+pub async fn export<C: Connection>(db: &Surreal<C>, mut config: ExportConfig, writer: impl Write) -> Result<u64> {
+    if !config.compress {
+        return write_export(db, &mut config, writer).await;
+    }
+
+    let mut encoder = GzEncoder::new(writer, Compression::default());
+
+    let records = write_export(db, &mut config, &mut encoder).await?;
+
+    encoder.finish().map_err(BackupError::from)?;
+
+    Ok(records)
+}
+
+async fn write_export<C: Connection>(db: &Surreal<C>, config: &mut ExportConfig, mut writer: impl Write) -> Result<u64> {
+    let database = info(db, "INFO FOR DB").await?;
+    let definitions = database.pick(&[Part::from("tables")]);
+
+    let tables = match config.tables.take() {
+        Some(tables) => tables,
+        None => match &definitions {
+            Value::Object(tables) => tables.keys().cloned().collect(),
+            _ => vec![],
+        },
+    };
+
+    writeln!(writer, "OPTION IMPORT;").map_err(BackupError::from)?;
+
+    for kind in ["analyzers", "functions", "params"] {
+        write_defines(&database.pick(&[Part::from(kind)]), &mut writer)?;
+    }
+
+    let mut exported = 0;
+
+    for table in &tables {
+        check_table(table)?;
+
+        writeln!(writer, "\n-- TABLE: {table}").map_err(BackupError::from)?;
+
+        if let Value::Strand(define) = definitions.pick(&[Part::from(table.as_str())]) {
+            writeln!(writer, "{};", define.0).map_err(BackupError::from)?;
+        }
+
+        let table_info = info(db, &format!("INFO FOR TABLE {table}")).await?;
+
+        for kind in ["fields", "indexes", "events"] {
+            write_defines(&table_info.pick(&[Part::from(kind)]), &mut writer)?;
+        }
+
+        exported += export_records(db, config, table, &mut writer).await?;
+    }
+
+    writer.flush().map_err(BackupError::from)?;
+
+    Ok(exported)
+}
+
+fn write_defines(defines: &Value, mut writer: impl Write) -> Result<()> {
+    if let Value::Object(defines) = defines {
+        for define in defines.values() {
+            if let Value::Strand(define) = define {
+                writeln!(writer, "{};", define.0).map_err(BackupError::from)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn export_records<C: Connection>(db: &Surreal<C>, config: &mut ExportConfig, table: &str, mut writer: impl Write) -> Result<u64> {
+    let mut res = db.query(format!("RETURN count((SELECT id FROM {table}))"))
+        .await.map_err(BackupError::from)?;
+
+    let total: Option<u64> = res.take(0).map_err(BackupError::from)?;
+    let total = total.unwrap_or_default();
+
+    let mut records = 0;
+
+    while records < total {
+        let mut res = db.query(format!("SELECT * FROM {table} ORDER BY id START {records} LIMIT {}", config.batch_size))
+            .await.map_err(BackupError::from)?;
+
+        let batch: surrealdb::Value = res.take(0).map_err(BackupError::from)?;
+
+        let Value::Array(batch) = batch.into_inner() else {
+            break;
+        };
+
+        if batch.is_empty() {
+            break;
+        }
+
+        let (relations, others): (Vec<Value>, Vec<Value>) = batch.0.into_iter().partition(is_relation);
+
+        records += (relations.len() + others.len()) as u64;
+
+        if !others.is_empty() {
+            writeln!(writer, "INSERT {};", Value::from(others)).map_err(BackupError::from)?;
+        }
+
+        if !relations.is_empty() {
+            writeln!(writer, "INSERT RELATION {};", Value::from(relations)).map_err(BackupError::from)?;
+        }
+
+        if let Some(on_progress) = &mut config.on_progress {
+            on_progress(&ExportProgress { table: table.to_string(), records, total });
+        }
+    }
+
+    Ok(records)
+}
+
+/// Runs the statements of a backup in transactions of `batch_size` statements with `OPTION IMPORT`, compressed backups are decompressed
+pub async fn import<C: Connection>(db: &Surreal<C>, mut config: ImportConfig, mut reader: impl Read) -> Result<()> {
+    let mut bytes = vec![];
+    reader.read_to_end(&mut bytes).map_err(BackupError::from)?;
+
+    let mut backup = String::new();
+
+    if bytes.starts_with(&GZIP_MAGIC) {
+        GzDecoder::new(bytes.as_slice()).read_to_string(&mut backup).map_err(BackupError::from)?;
+    } else {
+        backup = String::from_utf8(bytes).map_err(|e| BackupError::InvalidBackup(e.to_string()))?;
+    }
+
+    let backup = parse(&backup).map_err(|e| BackupError::InvalidBackup(e.to_string()))?;
+
+    // The batches are transactions, so the options and transactions of the backup itself are left out
+    let statements: Vec<Statement> = backup.0.0.into_iter()
+        .filter(|s| !matches!(s, Statement::Option(_) | Statement::Begin(_) | Statement::Commit(_)))
+        .collect();
+
+    let total = statements.len();
+
+    // `BEGIN` and `COMMIT` are skipped while `OPTION IMPORT` is set, so the option is only set inside the transaction
+    let transaction = parse("BEGIN TRANSACTION; OPTION IMPORT; OPTION IMPORT = false; COMMIT TRANSACTION;").map_err(|e| BackupError::Db(e.into()))?;
+
+    let mut imported = 0;
+
+    for batch in statements.chunks(config.batch_size) {
+        let mut query = transaction.clone();
+        query.0.0.splice(2..2, batch.iter().cloned());
+
+        db.query(query)
+            .await.map_err(BackupError::from)?
+            .check().map_err(BackupError::from)?;
+
+        imported += batch.len();
+
+        if let Some(on_progress) = &mut config.on_progress {
+            on_progress(&ImportProgress { statements: imported, total });
+        }
+    }
+
+    Ok(())
+}
+
+async fn info<C: Connection>(db: &Surreal<C>, query: &str) -> Result<Value> {
+    let mut res = db.query(query).await.map_err(BackupError::from)?;
+
+    let info: surrealdb::Value = res.take(0).map_err(BackupError::from)?;
+
+    Ok(info.into_inner())
+}
+
+fn is_relation(record: &Value) -> bool {
+    matches!(
+        (record.pick(&[Part::from("in")]), record.pick(&[Part::from("out")])),
+        (Value::Thing(_), Value::Thing(_))
+    )
+}
+
+fn check_table(table: &str) -> Result<()> {
+    if table.is_empty() || !table.chars().all(|c| c.is_alphanumeric() || c == '_') {
+        return Err(BackupError::InvalidTable(table.to_string()).into());
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::{Arc, Mutex};
+    use surrealdb::engine::any::Any;
+    use crate::testing::db;
+    use super::*;
+
+    async fn count(db: &Surreal<Any>, table: &str) -> u64 {
+        let mut res = db.query(format!("RETURN count((SELECT id FROM {table}))")).await.unwrap();
+
+        res.take::<Option<u64>>(0).unwrap().unwrap_or_default()
+    }
+
+    async fn source() -> Surreal<Any> {
+        let db = db().await;
+
+        db.query("
+            DEFINE TABLE user SCHEMAFULL;
+            DEFINE FIELD name ON user TYPE string;
+            DEFINE INDEX name ON user FIELDS name UNIQUE;
+            DEFINE ANALYZER simple TOKENIZERS blank FILTERS lowercase;
+            DEFINE FUNCTION fn::greet($name: string) { RETURN 'hi ' + $name };
+            DEFINE PARAM $limit VALUE 10;
+            DEFINE EVENT audit ON order WHEN $event = 'CREATE' THEN (CREATE audit SET order = $after.id);
+            FOR $i IN 1..=5 { CREATE type::thing('user', $i) SET name = 'name ' + <string> $i };
+            CREATE order:1 SET user = user:1, note = 'line\nbreak';
+            RELATE user:1->follows->user:2;
+            CREATE log:1;
+        ").await.unwrap().check().unwrap();
+
+        db
+    }
+
+    #[tokio::test]
+    async fn round_trip_selected_tables() {
+        let source = source().await;
+
+        let mut backup = vec![];
+        let exported = export(&source, ExportConfig::new().tables(&["user", "order", "follows"]).batch_size(2), &mut backup).await.unwrap();
+
+        assert_eq!(exported, 7);
+
+        let restored = db().await;
+        import(&restored, ImportConfig::new(), backup.as_slice()).await.unwrap();
+
+        assert_eq!(count(&restored, "user").await, 5);
+        assert_eq!(count(&restored, "order").await, 1);
+        assert_eq!(count(&restored, "follows").await, 1);
+        assert_eq!(count(&restored, "log").await, 0);
+        assert_eq!(count(&restored, "audit").await, 0);
+
+        let mut res = restored.query("INFO FOR TABLE user; SELECT VALUE note FROM ONLY order:1; SELECT VALUE ->follows->user FROM ONLY user:1; RETURN fn::greet('you') + ' ' + <string> $limit; INFO FOR DB").await.unwrap();
+
+        let info: surrealdb::Value = res.take(0).unwrap();
+        assert!(info.into_inner().pick(&[Part::from("indexes"), Part::from("name")]).is_strand());
+
+        let note: Option<String> = res.take(1).unwrap();
+        assert_eq!(note, Some("line\nbreak".to_string()));
+
+        let follows: surrealdb::Value = res.take(2).unwrap();
+        assert_eq!(follows.into_inner().to_string(), "[user:2]");
+
+        let greeting: Option<String> = res.take(3).unwrap();
+        assert_eq!(greeting, Some("hi you 10".to_string()));
+
+        let info: surrealdb::Value = res.take(4).unwrap();
+        assert!(info.into_inner().pick(&[Part::from("analyzers"), Part::from("simple")]).is_strand());
+    }
+
+    #[tokio::test]
+    async fn failed_import_changes_nothing() {
+        let db = db().await;
+
+        let backup = "CREATE item:1; THROW 'failed';";
+
+        assert!(import(&db, ImportConfig::new(), backup.as_bytes()).await.is_err());
+        assert_eq!(count(&db, "item").await, 0);
+
+        assert!(import(&db, ImportConfig::new().batch_size(1), backup.as_bytes()).await.is_err());
+        assert_eq!(count(&db, "item").await, 1);
+    }
+
+    #[tokio::test]
+    async fn compressed_with_progress() {
+        let source = source().await;
+
+        let progress = Arc::new(Mutex::new(vec![]));
+        let export_progress = progress.clone();
+
+        let mut backup = vec![];
+        export(&source, ExportConfig::new().compress(true).batch_size(3).on_progress(move |p| export_progress.lock().unwrap().push(p.clone())), &mut backup).await.unwrap();
+
+        assert!(backup.starts_with(&GZIP_MAGIC));
+
+        let users: Vec<u64> = progress.lock().unwrap().iter().filter(|p| p.table == "user").map(|p| p.records).collect();
+        assert_eq!(users, vec![3, 5]);
+
+        let restored = db().await;
+
+        let progress = Arc::new(Mutex::new(vec![]));
+        let import_progress = progress.clone();
+
+        import(&restored, ImportConfig::new().batch_size(4).on_progress(move |p| import_progress.lock().unwrap().push(p.clone())), backup.as_slice()).await.unwrap();
+
+        let progress = progress.lock().unwrap().clone();
+        let total = progress[0].total;
+
+        assert_eq!(progress.len(), total.div_ceil(4));
+        assert!(progress.iter().all(|p| p.statements == total || p.statements % 4 == 0));
+        assert_eq!(progress.last().unwrap().statements, total);
+        assert_eq!(count(&restored, "log").await, 1);
+        assert_eq!(count(&restored, "user").await, 5);
+    }
+
+    #[tokio::test]
+    async fn invalid_table() {
+        let db = db().await;
+
+        assert!(export_tables(&db, &["user; REMOVE TABLE user"], vec![]).await.is_err());
+    }
+}
@@ -0,0 +1,9 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum BenchError {
+    #[error("A bench needs at least one iteration")]
+    NoIterations,
+    #[error("{0}")]
+    Db(#[from] surrealdb::Error),
+}
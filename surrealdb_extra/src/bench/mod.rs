@@ -0,0 +1,254 @@
+//! Latency of statements on your own data
+//!
+//! `Bench` runs every case a number of times one after the other and collects the latency of each run into a `BenchReport`
+//! with the percentiles, so e.g. a condition string, `cond_vec!` and a typed condition can be compared on the same data.
+//! Selects are also run once with `EXPLAIN` and the `QueryPlan` is added to the report.
+//!
+//! The runs are sequential and include the round trip to the database, so the numbers are only comparable on the same connection.
+//!
+//! # Example
+//!
+//! ```rust
+//! use surrealdb::engine::any::connect;
+//! use surrealdb::sql::{idiom, Field, Operator, Value};
+//! use surrealdb_extra::bench::Bench;
+//! use surrealdb_extra::cond_vec;
+//! use surrealdb_extra::query::statement::StatementBuilder;
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let db = connect("mem://").await.unwrap();
+//!     db.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     db.query("DEFINE INDEX email ON user FIELDS email; CREATE user SET email = 'a'").await.unwrap();
+//!
+//!     let reports = Bench::new(&db).iterations(20)
+//!         .case("string", db.select_builder().what("user").field(Field::All).condition("email = 'a'"))
+//!         .case("cond_vec", db.select_builder().what("user").field(Field::All)
+//!             .condition(cond_vec![(Value::Idiom(idiom("email").unwrap()), Operator::Equal, Value::from("a"))]))
+//!         .run().await.unwrap();
+//!
+//!     for report in &reports {
+//!         println!("{report}");
+//!     }
+//!
+//!     assert_eq!(reports[0].durations.len(), 20);
+//!     assert!(reports[1].plan.as_ref().unwrap().uses_index("email"));
+//! }
+//! ```
+
+pub mod err;
+
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+use anyhow::Result;
+use surrealdb::{Connection, Surreal};
+use surrealdb::sql::{Explain, Statement};
+use crate::query::explain::QueryPlan;
+use crate::query::parsing::statement::ExtraStatement;
+pub use crate::bench::err::BenchError;
+
+#[cfg(not(feature = "wasm"))]
+use std::time::Instant;
+
+#[cfg(feature = "wasm")]
+use web_time::Instant;
+
+pub const DEFAULT_ITERATIONS: usize = 100;
+
+/// The latencies of one case, sorted from fastest to slowest
+#[derive(Debug, Clone)]
+pub struct BenchReport {
+    pub name: String,
+    pub surql: String,
+    pub durations: Vec<Duration>,
+    /// Only for selects
+    pub plan: Option<QueryPlan>,
+}
+
+impl BenchReport {
+    /// The latency that `percentile` percent of the runs are faster than or equal to, `percentile` is clamped to `0..=100`
+    pub fn percentile(&self, percentile: f64) -> Duration {
+        let Some(last) = self.durations.len().checked_sub(1) else {
+            return Duration::ZERO;
+        };
+
+        let index = (percentile.clamp(0.0, 100.0) / 100.0 * last as f64).round() as usize;
+
+        self.durations[index]
+    }
+
+    pub fn p50(&self) -> Duration {
+        self.percentile(50.0)
+    }
+
+    pub fn p90(&self) -> Duration {
+        self.percentile(90.0)
+    }
+
+    pub fn p99(&self) -> Duration {
+        self.percentile(99.0)
+    }
+
+    pub fn min(&self) -> Duration {
+        self.durations.first().copied().unwrap_or_default()
+    }
+
+    pub fn max(&self) -> Duration {
+        self.durations.last().copied().unwrap_or_default()
+    }
+
+    pub fn mean(&self) -> Duration {
+        match u32::try_from(self.durations.len()) {
+            Ok(0) | Err(_) => Duration::ZERO,
+            Ok(len) => self.durations.iter().sum::<Duration>() / len,
+        }
+    }
+}
+
+impl Display for BenchReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: p50 {:?}, p90 {:?}, p99 {:?}, max {:?} ({} runs)", self.name, self.p50(), self.p90(), self.p99(), self.max(), self.durations.len())
+    }
+}
+
+/// Runs statements and measures their latency
+pub struct Bench<'r, Client>
+    where Client: Connection
+{
+    pub(crate) db: &'r Surreal<Client>,
+    pub iterations: usize,
+    /// Runs before the measured ones, e.g. to fill caches
+    pub warmup: usize,
+    pub cases: Vec<(String, Statement)>,
+}
+
+impl<'r, Client> Bench<'r, Client>
+    where Client: Connection
+{
+    pub fn new(db: &'r Surreal<Client>) -> Self {
+        Self {
+            db,
+            iterations: DEFAULT_ITERATIONS,
+            warmup: 0,
+            cases: vec![],
+        }
+    }
+
+    pub fn iterations(mut self, iterations: usize) -> Self {
+        self.iterations = iterations;
+
+        self
+    }
+
+    pub fn warmup(mut self, warmup: usize) -> Self {
+        self.warmup = warmup;
+
+        self
+    }
+
+    /// Adds a statement or a builder to compare
+    pub fn case(mut self, name: impl Into<String>, statement: impl Into<ExtraStatement>) -> Self {
+        self.cases.push((name.into(), statement.into().0));
+
+        self
+    }
+
+    /// Runs the cases in the order they were added and returns a report for each of them
+    pub async fn run(self) -> Result<Vec<BenchReport>> {
+        if self.iterations == 0 {
+            return Err(BenchError::NoIterations.into());
+        }
+
+        let mut reports = vec![];
+
+        for (name, statement) in self.cases {
+            for _ in 0..self.warmup {
+                self.db.query(statement.clone()).await.map_err(BenchError::from)?.check().map_err(BenchError::from)?;
+            }
+
+            let mut durations = Vec::with_capacity(self.iterations);
+
+            for _ in 0..self.iterations {
+                let start = Instant::now();
+
+                self.db.query(statement.clone()).await.map_err(BenchError::from)?.check().map_err(BenchError::from)?;
+
+                durations.push(start.elapsed());
+            }
+
+            durations.sort();
+
+            let plan = match &statement {
+                Statement::Select(select) => {
+                    let mut select = select.clone();
+                    select.explain = Some(Explain::default());
+
+                    let plan: surrealdb::Value = self.db.query(select).await.map_err(BenchError::from)?
+                        .take(0).map_err(BenchError::from)?;
+
+                    QueryPlan::try_from(plan.into_inner()).ok()
+                }
+                _ => None,
+            };
+
+            reports.push(BenchReport {
+                name,
+                surql: statement.to_string(),
+                durations,
+                plan,
+            });
+        }
+
+        Ok(reports)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use surrealdb::sql::{Field, Operator};
+    use crate::query::statement::StatementBuilder;
+    use crate::testing::db;
+    use super::*;
+
+    fn report(millis: &[u64]) -> BenchReport {
+        BenchReport {
+            name: "test".to_string(),
+            surql: String::new(),
+            durations: millis.iter().map(|m| Duration::from_millis(*m)).collect(),
+            plan: None,
+        }
+    }
+
+    #[test]
+    fn percentiles() {
+        assert_eq!(report(&[]).p99(), Duration::ZERO);
+
+        let report = report(&(1..=101).collect::<Vec<_>>());
+
+        assert_eq!(report.p50(), Duration::from_millis(51));
+        assert_eq!(report.p90(), Duration::from_millis(91));
+        assert_eq!(report.percentile(200.0), Duration::from_millis(101));
+        assert_eq!((report.min(), report.max(), report.mean()), (Duration::from_millis(1), Duration::from_millis(101), Duration::from_millis(51)));
+    }
+
+    #[tokio::test]
+    async fn run() {
+        let db = db().await;
+
+        assert!(Bench::new(&db).iterations(0).run().await.is_err());
+
+        let reports = Bench::new(&db).iterations(5).warmup(1)
+            .case("select", db.select_builder().what("user").field(Field::All))
+            .case("create", db.create_builder().what("user").set(vec![("n", Operator::Equal, 1)]))
+            .run().await.unwrap();
+
+        assert_eq!(reports[0].surql, "SELECT * FROM user");
+        assert_eq!(reports[0].durations.len(), 5);
+        assert!(reports[0].plan.as_ref().unwrap().is_table_scan());
+        assert!(reports[1].plan.is_none());
+
+        let created: Vec<i64> = db.select_builder().what("user").value("n").execute_values().await.unwrap();
+        assert_eq!(created.len(), 6);
+    }
+}
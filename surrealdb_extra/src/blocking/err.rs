@@ -0,0 +1,9 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum BlockingError {
+    #[error("Failed to start the runtime: {0}")]
+    Runtime(#[from] std::io::Error),
+    #[error("{0}")]
+    Db(#[from] surrealdb::Error),
+}
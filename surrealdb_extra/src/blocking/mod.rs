@@ -0,0 +1,210 @@
+//! Synchronous API for CLI tools and scripts
+//!
+//! `BlockingDb` owns a tokio runtime next to the connection and runs every call to completion on it.
+//! The `Table` methods have a `_blocking` version in `BlockingTable`,
+//! builders and any other future are run with `BlockingDb::block_on`.
+//!
+//! `BlockingDb` dereferences to the `Surreal` connection, so the builders are created on it as usual.
+//! Don't use it inside an async runtime, blocking inside a runtime panics.
+//!
+//! # Example
+//!
+//! ```rust
+//! use serde::{Serialize, Deserialize};
+//! use surrealdb::sql::{Field, Thing as RecordId};
+//! use surrealdb_extra::blocking::{BlockingDb, BlockingTable};
+//! use surrealdb_extra::query::statement::StatementBuilder;
+//! use surrealdb_extra::table::Table;
+//!
+//! #[derive(Table, Serialize, Deserialize, Clone)]
+//! #[table(name = "user")]
+//! struct User {
+//!     id: Option<RecordId>,
+//!     name: String
+//! }
+//!
+//! fn main() {
+//!     let db = BlockingDb::connect("mem://").unwrap();
+//!     db.use_ns_db("ns", "db").unwrap();
+//!
+//!     let user = User { id: None, name: "name".to_string() }.create_blocking(&db).unwrap().unwrap();
+//!
+//!     let users: Vec<User> = db.block_on(db.select_builder().what("user").field(Field::All).to_query())
+//!         .unwrap()
+//!         .take(0)
+//!         .unwrap();
+//!
+//!     assert_eq!(users.len(), 1);
+//! }
+//! ```
+
+pub mod err;
+
+use std::future::{Future, IntoFuture};
+use std::ops::Deref;
+use anyhow::Result;
+use surrealdb::engine::any::{connect, Any};
+use surrealdb::{Connection, Surreal};
+use tokio::runtime::{Builder, Runtime};
+pub use crate::blocking::err::BlockingError;
+use crate::table::changeset::Changeset;
+use crate::table::id::IntoTableId;
+use crate::table::Table;
+
+#[cfg(feature = "query")]
+use serde::Serialize;
+
+#[cfg(feature = "query")]
+use crate::query::parsing::cond::ExtraCond;
+
+/// A connection with the runtime it runs on
+pub struct BlockingDb<C: Connection = Any> {
+    runtime: Runtime,
+    db: Surreal<C>,
+}
+
+impl BlockingDb<Any> {
+    /// Connects to any engine, like `surrealdb::engine::any::connect`
+    pub fn connect(address: impl Into<String>) -> Result<Self> {
+        let address = address.into();
+
+        Self::connect_with(|| async move {
+            Ok(connect(address).await.map_err(BlockingError::from)?)
+        })
+    }
+}
+
+impl<C: Connection> BlockingDb<C> {
+    /// Starts the runtime and creates the connection on it, e.g. for a specific engine
+    pub fn connect_with<F, Fut>(f: F) -> Result<Self>
+        where F: FnOnce() -> Fut, Fut: Future<Output = Result<Surreal<C>>>
+    {
+        let runtime = Builder::new_multi_thread()
+            .worker_threads(1)
+            .enable_all()
+            .build()
+            .map_err(BlockingError::from)?;
+
+        let db = runtime.block_on(f())?;
+
+        Ok(Self { runtime, db })
+    }
+
+    pub fn use_ns_db(&self, ns: impl Into<String>, db: impl Into<String>) -> Result<()> {
+        self.block_on(self.db.use_ns(ns).use_db(db)).map_err(BlockingError::from)?;
+
+        Ok(())
+    }
+
+    /// Runs the future to completion, e.g. `to_query()` or `execute()` of a builder
+    pub fn block_on<F: IntoFuture>(&self, f: F) -> F::Output {
+        self.runtime.block_on(f.into_future())
+    }
+
+    pub fn db(&self) -> &Surreal<C> {
+        &self.db
+    }
+
+    pub fn into_inner(self) -> (Runtime, Surreal<C>) {
+        (self.runtime, self.db)
+    }
+}
+
+impl<C: Connection> Deref for BlockingDb<C> {
+    type Target = Surreal<C>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.db
+    }
+}
+
+/// Synchronous versions of the `Table` methods, implemented for every table
+pub trait BlockingTable: Table {
+    fn create_blocking<C: Connection>(self, db: &BlockingDb<C>) -> Result<Option<Self>> {
+        db.block_on(self.create(db.db()))
+    }
+
+    fn update_blocking<C: Connection>(self, db: &BlockingDb<C>) -> Result<Option<Self>> {
+        db.block_on(self.update(db.db()))
+    }
+
+    fn delete_blocking<C: Connection>(db: &BlockingDb<C>, id: impl IntoTableId<Self> + Send) -> Result<Option<Self>> {
+        db.block_on(Self::delete(db.db(), id))
+    }
+
+    fn get_all_blocking<C: Connection>(db: &BlockingDb<C>) -> Result<Vec<Self>> {
+        db.block_on(Self::get_all(db.db()))
+    }
+
+    fn get_by_id_blocking<C: Connection>(db: &BlockingDb<C>, id: impl IntoTableId<Self> + Send) -> Result<Option<Self>> {
+        db.block_on(Self::get_by_id(db.db(), id))
+    }
+
+    fn get_by_ids_blocking<C: Connection>(db: &BlockingDb<C>, ids: impl IntoIterator<Item = impl IntoTableId<Self>> + Send) -> Result<Vec<Option<Self>>> where Self: Clone {
+        db.block_on(Self::get_by_ids(db.db(), ids))
+    }
+
+    fn apply_changeset_blocking<C: Connection>(db: &BlockingDb<C>, id: impl IntoTableId<Self> + Send, changeset: impl Changeset<Self> + 'static) -> Result<Option<Self>> {
+        db.block_on(Self::apply_changeset(db.db(), id, changeset))
+    }
+
+    #[cfg(feature = "query")]
+    fn delete_where_blocking<C: Connection>(db: &BlockingDb<C>, cond: impl Into<ExtraCond> + Send) -> Result<Vec<Self>> {
+        db.block_on(Self::delete_where(db.db(), cond))
+    }
+
+    #[cfg(feature = "query")]
+    fn update_where_blocking<C: Connection>(db: &BlockingDb<C>, cond: impl Into<ExtraCond> + Send, data: impl Serialize + Send + 'static) -> Result<Vec<Self>> {
+        db.block_on(Self::update_where(db.db(), cond, data))
+    }
+
+    fn init_schema_blocking<C: Connection>(db: &BlockingDb<C>) -> Result<()> {
+        db.block_on(Self::init_schema(db.db()))
+    }
+}
+
+impl<T: Table> BlockingTable for T {}
+
+#[cfg(test)]
+mod test {
+    use serde::{Deserialize, Serialize};
+    use surrealdb::sql::Thing;
+    use super::*;
+
+    #[derive(Debug, Table, Serialize, Deserialize, Clone, PartialEq)]
+    #[table(name = "user")]
+    struct User {
+        id: Option<Thing>,
+        name: String,
+    }
+
+    #[test]
+    fn table_methods() {
+        let db = BlockingDb::connect("mem://").unwrap();
+        db.use_ns_db("test", "test").unwrap();
+
+        let mut user = User { id: None, name: "john".to_string() }.create_blocking(&db).unwrap().unwrap();
+        let id = user.id.clone().unwrap();
+
+        user.name = "johnny".to_string();
+        user.update_blocking(&db).unwrap();
+
+        let user = User::get_by_id_blocking(&db, id.clone()).unwrap().unwrap();
+        assert_eq!(user.name, "johnny");
+
+        assert_eq!(User::get_all_blocking(&db).unwrap().len(), 1);
+
+        User::delete_blocking(&db, id).unwrap();
+        assert!(User::get_all_blocking(&db).unwrap().is_empty());
+    }
+
+    #[test]
+    fn block_on_query() {
+        let db = BlockingDb::connect("mem://").unwrap();
+        db.use_ns_db("test", "test").unwrap();
+
+        let count: Option<i64> = db.block_on(db.query("RETURN 1 + 1")).unwrap().take(0).unwrap();
+
+        assert_eq!(count, Some(2));
+    }
+}
@@ -0,0 +1,16 @@
+use std::path::PathBuf;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum EmbeddedError {
+    #[error("Directory `{0}` does not exist")]
+    MissingDirectory(PathBuf),
+    #[error("Store at `{0}` is locked by another process")]
+    Locked(PathBuf),
+    #[error("Namespace and database must be set for this operation")]
+    NoNamespace,
+    #[error("{0}")]
+    Io(#[from] std::io::Error),
+    #[error("{0}")]
+    Db(#[from] surrealdb::Error),
+}
@@ -0,0 +1,274 @@
+//! Helpers for the file based engines (SurrealKV and RocksDB)
+//!
+//! The engine itself still has to be enabled on the `surrealdb` crate with `kv-surrealkv` or `kv-rocksdb`
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use surrealdb_extra::embedded::{connect_embedded, store_size, EmbeddedEngine, EmbeddedOptions};
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let opts = EmbeddedOptions::new(EmbeddedEngine::SurrealKv).namespace("ns").database("db");
+//!
+//!     let db = connect_embedded("./data/app.db", opts).await.unwrap();
+//!
+//!     let size = store_size("./data/app.db").unwrap();
+//! }
+//! ```
+
+pub mod err;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use anyhow::Result;
+use surrealdb::engine::any::{connect, Any};
+use surrealdb::{Connection, Surreal};
+pub use crate::embedded::err::EmbeddedError;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EmbeddedEngine {
+    #[default]
+    SurrealKv,
+    RocksDb,
+}
+
+impl EmbeddedEngine {
+    pub fn scheme(&self) -> &'static str {
+        match self {
+            Self::SurrealKv => "surrealkv",
+            Self::RocksDb => "rocksdb",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct EmbeddedOptions {
+    pub engine: EmbeddedEngine,
+    pub namespace: Option<String>,
+    pub database: Option<String>,
+    pub create_dir: bool,
+}
+
+impl Default for EmbeddedOptions {
+    fn default() -> Self {
+        Self {
+            engine: EmbeddedEngine::default(),
+            namespace: None,
+            database: None,
+            create_dir: true,
+        }
+    }
+}
+
+impl EmbeddedOptions {
+    pub fn new(engine: EmbeddedEngine) -> Self {
+        Self {
+            engine,
+            ..Self::default()
+        }
+    }
+
+    pub fn namespace(mut self, namespace: impl Into<String>) -> Self {
+        self.namespace = Some(namespace.into());
+
+        self
+    }
+
+    pub fn database(mut self, database: impl Into<String>) -> Self {
+        self.database = Some(database.into());
+
+        self
+    }
+
+    /// When false the directory of the store must already exist
+    pub fn create_dir(mut self, create_dir: bool) -> Self {
+        self.create_dir = create_dir;
+
+        self
+    }
+
+    fn endpoint(&self, path: &Path) -> String {
+        format!("{}://{}", self.engine.scheme(), path.display())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompactReport {
+    pub size_before: u64,
+    pub size_after: u64,
+}
+
+/// Connects to a file based store, creating the directory if needed
+///
+/// A store that is already opened by another process returns `EmbeddedError::Locked` instead of the raw engine error
+pub async fn connect_embedded(path: impl AsRef<Path>, opts: EmbeddedOptions) -> Result<Surreal<Any>> {
+    let path = path.as_ref();
+
+    if opts.create_dir {
+        fs::create_dir_all(path).map_err(EmbeddedError::from)?;
+    } else if !path.exists() {
+        return Err(EmbeddedError::MissingDirectory(path.to_path_buf()).into());
+    }
+
+    let db = connect(opts.endpoint(path)).await.map_err(|e| {
+        if is_lock_error(&e) {
+            return EmbeddedError::Locked(path.to_path_buf());
+        }
+
+        EmbeddedError::Db(e)
+    })?;
+
+    if let (Some(ns), Some(database)) = (&opts.namespace, &opts.database) {
+        db.use_ns(ns).use_db(database).await.map_err(EmbeddedError::from)?;
+    }
+
+    Ok(db)
+}
+
+/// Exports the current namespace and database into a SurrealQL file
+pub async fn backup<C: Connection>(db: &Surreal<C>, target: impl AsRef<Path>) -> Result<()> {
+    let target = target.as_ref();
+
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent).map_err(EmbeddedError::from)?;
+    }
+
+    db.export(target).await.map_err(EmbeddedError::from)?;
+
+    Ok(())
+}
+
+/// Rewrites the store by exporting it and importing it into a fresh directory
+///
+/// The store must not be opened elsewhere while compacting. If the import fails the original directory is put back
+pub async fn compact(path: impl AsRef<Path>, opts: EmbeddedOptions) -> Result<CompactReport> {
+    let path = path.as_ref();
+
+    if opts.namespace.is_none() || opts.database.is_none() {
+        return Err(EmbeddedError::NoNamespace.into());
+    }
+
+    let size_before = store_size(path).map_err(EmbeddedError::from)?;
+
+    let dump = sibling(path, "compact.surql");
+    let old = sibling(path, "compact.old");
+
+    let db = connect_embedded(path, opts.clone().create_dir(false)).await?;
+    backup(&db, &dump).await?;
+    drop(db);
+
+    fs::rename(path, &old).map_err(EmbeddedError::from)?;
+
+    let imported = async {
+        let db = connect_embedded(path, opts.clone().create_dir(true)).await?;
+        db.import(&dump).await.map_err(EmbeddedError::from)?;
+
+        Ok::<(), anyhow::Error>(())
+    }.await;
+
+    if let Err(e) = imported {
+        let _ = fs::remove_dir_all(path);
+        fs::rename(&old, path).map_err(EmbeddedError::from)?;
+
+        return Err(e);
+    }
+
+    fs::remove_dir_all(&old).map_err(EmbeddedError::from)?;
+    fs::remove_file(&dump).map_err(EmbeddedError::from)?;
+
+    let size_after = store_size(path).map_err(EmbeddedError::from)?;
+
+    Ok(CompactReport { size_before, size_after })
+}
+
+/// Total size in bytes of every file inside the store directory
+pub fn store_size(path: impl AsRef<Path>) -> std::io::Result<u64> {
+    let metadata = fs::metadata(path.as_ref())?;
+
+    if metadata.is_file() {
+        return Ok(metadata.len());
+    }
+
+    let mut size = 0;
+    for entry in fs::read_dir(path.as_ref())? {
+        size += store_size(entry?.path())?;
+    }
+
+    Ok(size)
+}
+
+fn sibling(path: &Path, extension: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".");
+    name.push(extension);
+
+    path.with_file_name(name)
+}
+
+/// The datastore error of opening a store that another process has open: RocksDB fails with `While lock file: <path>/LOCK`,
+/// SurrealKV fails with the os error of the file lock that is held
+fn is_lock_error(e: &surrealdb::Error) -> bool {
+    let surrealdb::Error::Db(surrealdb::error::Db::Ds(msg) | surrealdb::error::Db::Tx(msg)) = e else {
+        return false;
+    };
+
+    msg.contains("While lock file") || LOCK_HELD.iter().any(|held| msg.contains(held))
+}
+
+/// The messages of `EWOULDBLOCK` on unix and of `ERROR_LOCK_VIOLATION` on windows
+const LOCK_HELD: [&str; 2] = [
+    "Resource temporarily unavailable",
+    "another process has locked a portion of the file",
+];
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn scheme() {
+        assert_eq!(EmbeddedOptions::new(EmbeddedEngine::SurrealKv).endpoint(Path::new("data")), "surrealkv://data");
+        assert_eq!(EmbeddedOptions::new(EmbeddedEngine::RocksDb).endpoint(Path::new("data")), "rocksdb://data");
+    }
+
+    #[test]
+    fn lock_error() {
+        use surrealdb::error::{Api, Db};
+
+        assert!(is_lock_error(&Db::Tx("IO error: While lock file: data/LOCK: Resource temporarily unavailable".to_string()).into()));
+        assert!(is_lock_error(&Db::Ds("Resource temporarily unavailable (os error 11)".to_string()).into()));
+
+        assert!(!is_lock_error(&Db::Tx("Failed to acquire the lock of the record".to_string()).into()));
+        assert!(!is_lock_error(&Api::Query("Resource temporarily unavailable".to_string()).into()));
+    }
+
+    #[test]
+    fn size_of_directory() {
+        let dir = std::env::temp_dir().join("surrealdb_extra_store_size");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("nested")).unwrap();
+
+        fs::write(dir.join("a"), [0u8; 10]).unwrap();
+        fs::write(dir.join("nested").join("b"), [0u8; 5]).unwrap();
+
+        assert_eq!(store_size(&dir).unwrap(), 15);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn sibling_path() {
+        assert_eq!(sibling(Path::new("/tmp/app.db"), "compact.old"), PathBuf::from("/tmp/app.db.compact.old"));
+    }
+
+    #[tokio::test]
+    async fn missing_directory() {
+        let dir = std::env::temp_dir().join("surrealdb_extra_missing_dir");
+        let _ = fs::remove_dir_all(&dir);
+
+        let res = connect_embedded(&dir, EmbeddedOptions::default().create_dir(false)).await;
+
+        assert!(matches!(res.unwrap_err().downcast_ref::<EmbeddedError>(), Some(EmbeddedError::MissingDirectory(..))));
+    }
+}
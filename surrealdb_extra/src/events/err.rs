@@ -0,0 +1,9 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum EventError {
+    #[error("The event source has no tables")]
+    NoTables,
+    #[error("{0}")]
+    Db(#[from] surrealdb::Error),
+}
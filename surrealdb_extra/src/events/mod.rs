@@ -0,0 +1,335 @@
+//! Event sourcing on top of the change feeds
+//!
+//! `EventSource::stream` tails `SHOW CHANGES` of its tables and yields every change as an `Event` with a `TableEvent<T>`,
+//! ordered by versionstamp across the tables. The tables need a `CHANGEFEED`, e.g. `DEFINE TABLE user CHANGEFEED 7d`.
+//!
+//! The versionstamp of every table is checkpointed per consumer in the checkpoint table, `event_checkpoint` by default,
+//! and a new stream of the same consumer resumes after it.
+//! A batch is only checkpointed when the stream is polled after its last event, so every event is delivered at least once:
+//! events of a batch that was not fully processed are delivered again after a restart. Projections should be idempotent.
+//!
+//! Without a checkpoint the stream starts at the oldest change that is still in the change feed.
+//! When there are no new changes the stream waits `poll_interval` before reading the change feeds again, it never ends on its own.
+//!
+//! # Example
+//!
+//! ```rust
+//! use futures::TryStreamExt;
+//! use serde::{Serialize, Deserialize};
+//! use surrealdb::engine::any::connect;
+//! use surrealdb::sql::Thing as RecordId;
+//! use surrealdb_extra::events::{EventSource, TableEvent};
+//! use surrealdb_extra::table::Table;
+//!
+//! #[derive(Table, Serialize, Deserialize, Clone)]
+//! #[table(name = "user")]
+//! struct User {
+//!     id: Option<RecordId>,
+//!     name: String
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let db = connect("mem://").await.unwrap();
+//!     db.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     db.query("DEFINE TABLE user CHANGEFEED 7d").await.unwrap();
+//!     User { id: None, name: "John".to_string() }.create(&db).await.unwrap();
+//!
+//!     let source = EventSource::new("projection").table_of::<User>();
+//!     let mut events = source.stream::<User, _>(&db);
+//!
+//!     match events.try_next().await.unwrap().unwrap().event {
+//!         TableEvent::Update { record, .. } => assert_eq!(record.name, "John"),
+//!         TableEvent::Delete { .. } => unreachable!(),
+//!     }
+//! }
+//! ```
+
+pub mod err;
+
+use std::collections::{HashMap, VecDeque};
+use std::time::Duration;
+use anyhow::Result;
+use futures::stream::{self, BoxStream};
+use futures::StreamExt;
+use serde::de::DeserializeOwned;
+use surrealdb::sql::Thing;
+use surrealdb::{Connection, Surreal};
+use crate::query::changefeed::Change;
+use crate::query::statement::StatementBuilder;
+use crate::table::Table;
+pub use crate::events::err::EventError;
+
+pub const DEFAULT_CHECKPOINT_TABLE: &str = "event_checkpoint";
+
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Change sets read per table and poll
+pub const DEFAULT_BATCH_SIZE: u32 = 100;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TableEvent<T> {
+    /// The record after it was created or updated
+    Update { table: String, record: T },
+    /// The id of the deleted record
+    Delete { table: String, id: Thing },
+}
+
+impl<T> TableEvent<T> {
+    pub fn table(&self) -> &str {
+        match self {
+            Self::Update { table, .. } | Self::Delete { table, .. } => table,
+        }
+    }
+}
+
+/// A `TableEvent` with the versionstamp of its transaction
+#[derive(Debug, Clone, PartialEq)]
+pub struct Event<T> {
+    pub versionstamp: u64,
+    pub event: TableEvent<T>,
+}
+
+/// The tables of a consumer and where their checkpoints are stored
+#[derive(Debug, Clone)]
+pub struct EventSource {
+    pub consumer: String,
+    pub tables: Vec<String>,
+    pub checkpoint_table: String,
+    pub poll_interval: Duration,
+    pub batch_size: u32,
+}
+
+impl EventSource {
+    /// `consumer` names the checkpoints, every projection should have its own
+    pub fn new(consumer: impl Into<String>) -> Self {
+        Self {
+            consumer: consumer.into(),
+            tables: vec![],
+            checkpoint_table: DEFAULT_CHECKPOINT_TABLE.to_string(),
+            poll_interval: DEFAULT_POLL_INTERVAL,
+            batch_size: DEFAULT_BATCH_SIZE,
+        }
+    }
+
+    pub fn table(mut self, table: impl Into<String>) -> Self {
+        self.tables.push(table.into());
+
+        self
+    }
+
+    pub fn table_of<T: Table>(self) -> Self {
+        self.table(T::TABLE_NAME)
+    }
+
+    pub fn checkpoint_table(mut self, table: impl Into<String>) -> Self {
+        self.checkpoint_table = table.into();
+
+        self
+    }
+
+    pub fn poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+
+        self
+    }
+
+    pub fn batch_size(mut self, batch_size: u32) -> Self {
+        self.batch_size = batch_size.max(1);
+
+        self
+    }
+
+    /// The last checkpointed versionstamp of every table
+    pub async fn checkpoints<C: Connection>(&self, db: &Surreal<C>) -> Result<HashMap<String, u64>> {
+        let checkpoints: Option<HashMap<String, u64>> = db.query("SELECT VALUE tables FROM ONLY type::thing($checkpoints, $consumer)")
+            .bind(("checkpoints", self.checkpoint_table.clone()))
+            .bind(("consumer", self.consumer.clone()))
+            .await.map_err(EventError::from)?
+            .take(0).map_err(EventError::from)?;
+
+        Ok(checkpoints.unwrap_or_default())
+    }
+
+    /// Stores the versionstamps, the other tables keep their checkpoints
+    pub async fn save_checkpoints<C: Connection>(&self, db: &Surreal<C>, checkpoints: &HashMap<String, u64>) -> Result<()> {
+        db.query("UPSERT type::thing($checkpoints, $consumer) MERGE { tables: $tables }")
+            .bind(("checkpoints", self.checkpoint_table.clone()))
+            .bind(("consumer", self.consumer.clone()))
+            .bind(("tables", checkpoints.clone()))
+            .await.map_err(EventError::from)?
+            .check().map_err(EventError::from)?;
+
+        Ok(())
+    }
+
+    /// Removes the checkpoints, the next stream starts at the oldest change
+    pub async fn reset<C: Connection>(&self, db: &Surreal<C>) -> Result<()> {
+        db.query("DELETE type::thing($checkpoints, $consumer)")
+            .bind(("checkpoints", self.checkpoint_table.clone()))
+            .bind(("consumer", self.consumer.clone()))
+            .await.map_err(EventError::from)?
+            .check().map_err(EventError::from)?;
+
+        Ok(())
+    }
+
+    /// Tails the change feeds of the tables from the checkpoints, the records of every table are deserialized into `T`
+    pub fn stream<'r, T, C>(&self, db: &'r Surreal<C>) -> BoxStream<'r, Result<Event<T>>>
+        where T: DeserializeOwned + Send + 'r, C: Connection
+    {
+        let tail = Tail {
+            source: self.clone(),
+            db,
+            checkpoints: None,
+            pending: VecDeque::new(),
+            unsaved: false,
+        };
+
+        stream::try_unfold(tail, next_event).boxed()
+    }
+}
+
+struct Tail<'r, C: Connection, T> {
+    source: EventSource,
+    db: &'r Surreal<C>,
+    /// Loaded on the first poll
+    checkpoints: Option<HashMap<String, u64>>,
+    pending: VecDeque<Event<T>>,
+    /// The checkpoints include events that are not stored yet
+    unsaved: bool,
+}
+
+async fn next_event<C: Connection, T: DeserializeOwned>(mut tail: Tail<'_, C, T>) -> Result<Option<(Event<T>, Tail<'_, C, T>)>> {
+    if tail.source.tables.is_empty() {
+        return Err(EventError::NoTables.into());
+    }
+
+    loop {
+        if let Some(event) = tail.pending.pop_front() {
+            return Ok(Some((event, tail)));
+        }
+
+        let mut checkpoints = match tail.checkpoints.take() {
+            Some(checkpoints) => checkpoints,
+            None => tail.source.checkpoints(tail.db).await?,
+        };
+
+        // Every event of the previous batch was handed out and the stream is polled again
+        if tail.unsaved {
+            tail.source.save_checkpoints(tail.db, &checkpoints).await?;
+            tail.unsaved = false;
+        }
+
+        let mut events = vec![];
+
+        for table in &tail.source.tables {
+            let since = checkpoints.get(table).map_or(0, |versionstamp| versionstamp + 1);
+
+            let change_sets = tail.db.show_changes_builder()
+                .table(table)
+                .since(since)
+                .limit(tail.source.batch_size)
+                .execute::<T>().await?;
+
+            for change_set in change_sets {
+                checkpoints.insert(table.clone(), change_set.versionstamp);
+
+                for change in change_set.changes {
+                    let event = match change {
+                        Change::Update(record) => TableEvent::Update { table: table.clone(), record },
+                        Change::Delete(id) => TableEvent::Delete { table: table.clone(), id },
+                        Change::DefineTable(_) => continue,
+                    };
+
+                    events.push(Event { versionstamp: change_set.versionstamp, event });
+                }
+
+                tail.unsaved = true;
+            }
+        }
+
+        events.sort_by_key(|event| event.versionstamp);
+
+        tail.pending.extend(events);
+        tail.checkpoints = Some(checkpoints);
+
+        if tail.pending.is_empty() {
+            if tail.unsaved {
+                continue;
+            }
+
+            tokio::time::sleep(tail.source.poll_interval).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use futures::TryStreamExt;
+    use serde::{Deserialize, Serialize};
+    use surrealdb::sql::Thing as RecordId;
+    use crate::testing::db;
+    use super::*;
+
+    #[derive(Debug, Table, Serialize, Deserialize, Clone, PartialEq)]
+    #[table(name = "user")]
+    struct User {
+        id: Option<RecordId>,
+        name: String,
+    }
+
+    #[tokio::test]
+    async fn resume_from_checkpoint() {
+        let db = db().await;
+
+        db.query("DEFINE TABLE user CHANGEFEED 1h").await.unwrap().check().unwrap();
+        db.query("CREATE user:a SET name = 'a'; CREATE user:b SET name = 'b'; DELETE user:a;").await.unwrap().check().unwrap();
+
+        let source = EventSource::new("test").table_of::<User>().poll_interval(Duration::from_millis(10));
+
+        let events: Vec<Event<User>> = source.stream(&db).take(3).try_collect().await.unwrap();
+
+        assert!(events.windows(2).all(|w| w[0].versionstamp <= w[1].versionstamp));
+        assert_eq!(events.iter().map(|e| e.event.clone()).collect::<Vec<_>>(), [
+            TableEvent::Update { table: "user".to_string(), record: User { id: Some(RecordId::from(("user", "a"))), name: "a".to_string() } },
+            TableEvent::Update { table: "user".to_string(), record: User { id: Some(RecordId::from(("user", "b"))), name: "b".to_string() } },
+            TableEvent::Delete { table: "user".to_string(), id: RecordId::from(("user", "a")) },
+        ]);
+
+        // The last batch is only checkpointed on the next poll, so it is delivered again
+        assert!(source.checkpoints(&db).await.unwrap().is_empty());
+
+        let mut events = source.stream::<User, _>(&db);
+        for _ in 0..3 {
+            events.try_next().await.unwrap();
+        }
+
+        db.query("CREATE user:c SET name = 'c'").await.unwrap().check().unwrap();
+
+        let next = events.try_next().await.unwrap().unwrap();
+        assert!(matches!(next.event, TableEvent::Update { record, .. } if record.name == "c"));
+
+        let checkpoints = source.checkpoints(&db).await.unwrap();
+        assert!(checkpoints["user"] < next.versionstamp);
+
+        drop(events);
+
+        // Resumes after the checkpoint, only `c` is delivered again
+        let next = source.stream::<User, _>(&db).try_next().await.unwrap().unwrap();
+        assert!(matches!(next.event, TableEvent::Update { record, .. } if record.name == "c"));
+
+        source.reset(&db).await.unwrap();
+        assert!(source.checkpoints(&db).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn no_tables() {
+        let db = db().await;
+
+        let res = EventSource::new("test").stream::<User, _>(&db).try_next().await;
+
+        assert!(matches!(res.unwrap_err().downcast_ref::<EventError>(), Some(EventError::NoTables)));
+    }
+}
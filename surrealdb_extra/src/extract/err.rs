@@ -0,0 +1,9 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ExtractError {
+    #[error("`{0}` is not a valid sort field")]
+    InvalidSort(String),
+    #[error("`{0}` is not a field that can be sorted")]
+    UnknownField(String),
+}
@@ -0,0 +1,307 @@
+//! Pagination and sorting extractors for list endpoints
+//!
+//! `Pagination` reads `limit` and `start`, `SortParams` reads `sort=name,-age` from the query of the request.
+//! With the `axum` feature they are `FromRequestParts` extractors and with the `actix-web` feature `FromRequest` extractors,
+//! both reject the request with `400 Bad Request` when the query is invalid.
+//! `apply` adds them to a select builder, so every list endpoint pages and sorts the same way.
+//!
+//! - The limit defaults to `Pagination::DEFAULT_LIMIT` and is capped at `Pagination::MAX_LIMIT`
+//! - The sort fields can only contain letters, digits, `_` and `.`, `check_table` allows only the fields of `T::meta()`
+//!
+//! # Example
+//!
+//! With `axum`:
+//!
+//! ```rust
+//! use axum::extract::State;
+//! use axum::routing::get;
+//! use axum::Router;
+//! use surrealdb::engine::any::{connect, Any};
+//! use surrealdb::Surreal;
+//! use surrealdb_extra::extract::{Pagination, SortParams};
+//! use surrealdb_extra::query::statement::StatementBuilder;
+//!
+//! async fn users(State(db): State<Surreal<Any>>, pagination: Pagination, sort: SortParams) -> String {
+//!     // `/users?sort=-age&limit=10` becomes `SELECT VALUE name FROM user ORDER BY age DESC LIMIT 10`
+//!     let select = db.select_builder().what("user").value("name").apply(&sort).apply(&pagination);
+//!
+//!     let names: Vec<String> = select.execute_values().await.unwrap();
+//!
+//!     names.join(", ")
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let db = connect("mem://").await.unwrap();
+//!     db.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     let app: Router = Router::new().route("/users", get(users)).with_state(db);
+//! }
+//! ```
+
+pub mod err;
+
+use serde::Deserialize;
+use surrealdb::Connection;
+use crate::query::parsing::order::{ExtraOrder, OrderDirection};
+use crate::query::select::SelectBuilder;
+use crate::query::states::{FilledFields, FilledWhat};
+use crate::table::Table;
+pub use crate::extract::err::ExtractError;
+
+/// `limit` and `start` of the query
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+pub struct Pagination {
+    pub limit: Option<u64>,
+    pub start: Option<u64>,
+}
+
+impl Pagination {
+    pub const DEFAULT_LIMIT: u64 = 20;
+    pub const MAX_LIMIT: u64 = 100;
+
+    /// The limit of the query capped at `MAX_LIMIT`, `DEFAULT_LIMIT` without one
+    pub fn limit(&self) -> u64 {
+        self.limit.unwrap_or(Self::DEFAULT_LIMIT).min(Self::MAX_LIMIT)
+    }
+
+    pub fn start(&self) -> u64 {
+        self.start.unwrap_or_default()
+    }
+}
+
+/// One field of `sort`, `-` in front of the field orders descending
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SortField {
+    pub field: String,
+    pub desc: bool,
+}
+
+/// The fields of `sort` in order
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "RawSort")]
+pub struct SortParams {
+    pub fields: Vec<SortField>,
+}
+
+#[derive(Deserialize)]
+struct RawSort {
+    sort: Option<String>,
+}
+
+impl TryFrom<RawSort> for SortParams {
+    type Error = ExtractError;
+
+    fn try_from(value: RawSort) -> Result<Self, Self::Error> {
+        Self::parse(value.sort.as_deref().unwrap_or_default())
+    }
+}
+
+impl SortParams {
+    /// Parses the value of `sort`, e.g. `name,-age`
+    pub fn parse(sort: &str) -> Result<Self, ExtractError> {
+        let mut fields = vec![];
+
+        // A `+` of the query is decoded as a space
+        for field in sort.split(',').map(str::trim).filter(|f| !f.is_empty()) {
+            let (field, desc) = match field.strip_prefix('-') {
+                Some(field) => (field, true),
+                None => (field, false),
+            };
+
+            let valid = field.split('.').all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'));
+
+            if !valid {
+                return Err(ExtractError::InvalidSort(field.to_string()));
+            }
+
+            fields.push(SortField { field: field.to_string(), desc });
+        }
+
+        Ok(Self { fields })
+    }
+
+    /// Errors on the first field that is not a field or nested field of the table
+    pub fn check_table<T: Table>(&self) -> Result<(), ExtractError> {
+        let meta = T::meta();
+        let nested = meta.nested_fields();
+
+        for sort in &self.fields {
+            let known = meta.fields.iter().any(|f| f.name == sort.field) || nested.iter().any(|(path, _)| *path == sort.field);
+
+            if !known {
+                return Err(ExtractError::UnknownField(sort.field.clone()));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn orders(&self) -> Vec<ExtraOrder> {
+        self.fields.iter()
+            .map(|sort| {
+                let direction = if sort.desc { OrderDirection::DESC } else { OrderDirection::ASC };
+
+                ExtraOrder::from((sort.field.as_str(), direction))
+            })
+            .collect()
+    }
+}
+
+/// Parameters of a request that can be added to a select
+pub trait SelectParams {
+    fn apply_to<'r, Client: Connection, C>(&self, select: SelectBuilder<'r, Client, FilledWhat, FilledFields, C>) -> SelectBuilder<'r, Client, FilledWhat, FilledFields, C>;
+}
+
+impl SelectParams for Pagination {
+    /// `LIMIT` and `START`, the start is left out when it is 0
+    fn apply_to<'r, Client: Connection, C>(&self, select: SelectBuilder<'r, Client, FilledWhat, FilledFields, C>) -> SelectBuilder<'r, Client, FilledWhat, FilledFields, C> {
+        let start = self.start();
+
+        select.limit(self.limit() as i64).start_if((start > 0).then_some(start as i64))
+    }
+}
+
+impl SelectParams for SortParams {
+    /// `ORDER BY` the fields after the orders the select already has
+    fn apply_to<'r, Client: Connection, C>(&self, select: SelectBuilder<'r, Client, FilledWhat, FilledFields, C>) -> SelectBuilder<'r, Client, FilledWhat, FilledFields, C> {
+        self.orders().into_iter().fold(select, |select, order| select.order(order))
+    }
+}
+
+impl<P: SelectParams> SelectParams for &P {
+    fn apply_to<'r, Client: Connection, C>(&self, select: SelectBuilder<'r, Client, FilledWhat, FilledFields, C>) -> SelectBuilder<'r, Client, FilledWhat, FilledFields, C> {
+        (*self).apply_to(select)
+    }
+}
+
+impl<'r, Client, C> SelectBuilder<'r, Client, FilledWhat, FilledFields, C>
+    where Client: Connection
+{
+    /// Adds the `Pagination` or `SortParams` of the request
+    pub fn apply(self, params: impl SelectParams) -> Self {
+        params.apply_to(self)
+    }
+}
+
+macro_rules! extractor {
+    ($name:ident) => {
+        #[cfg(feature = "axum")]
+        #[axum::async_trait]
+        impl<S: Send + Sync> axum::extract::FromRequestParts<S> for $name {
+            type Rejection = axum::extract::rejection::QueryRejection;
+
+            async fn from_request_parts(parts: &mut axum::http::request::Parts, state: &S) -> Result<Self, Self::Rejection> {
+                let axum::extract::Query(params) = axum::extract::Query::<Self>::from_request_parts(parts, state).await?;
+
+                Ok(params)
+            }
+        }
+
+        #[cfg(feature = "actix-web")]
+        impl actix_web::FromRequest for $name {
+            type Error = actix_web::Error;
+            type Future = std::future::Ready<Result<Self, Self::Error>>;
+
+            fn from_request(req: &actix_web::HttpRequest, _: &mut actix_web::dev::Payload) -> Self::Future {
+                let params = actix_web::web::Query::<Self>::from_query(req.query_string())
+                    .map(actix_web::web::Query::into_inner)
+                    .map_err(Into::into);
+
+                std::future::ready(params)
+            }
+        }
+    };
+}
+
+extractor!(Pagination);
+extractor!(SortParams);
+
+#[cfg(test)]
+mod test {
+    use serde::Serialize;
+    use surrealdb::engine::any::connect;
+    use surrealdb::sql::{Field, Thing as RecordId};
+    use crate::query::statement::StatementBuilder;
+    use super::*;
+
+    #[derive(Debug, Table, Serialize, Deserialize, Clone)]
+    #[table(name = "user")]
+    struct User {
+        id: Option<RecordId>,
+        name: String,
+        age: i64,
+    }
+
+    #[test]
+    fn pagination_limit() {
+        assert_eq!(Pagination::default().limit(), Pagination::DEFAULT_LIMIT);
+        assert_eq!(Pagination { limit: Some(5), start: None }.limit(), 5);
+        assert_eq!(Pagination { limit: Some(1000), start: None }.limit(), Pagination::MAX_LIMIT);
+    }
+
+    #[test]
+    fn parse_sort() {
+        let sort = SortParams::parse("name, -age,,address.city").unwrap();
+
+        assert_eq!(sort.fields, [
+            SortField { field: "name".to_string(), desc: false },
+            SortField { field: "age".to_string(), desc: true },
+            SortField { field: "address.city".to_string(), desc: false },
+        ]);
+
+        assert!(matches!(SortParams::parse("name;DELETE user"), Err(ExtractError::InvalidSort(_))));
+        assert!(matches!(SortParams::parse("->knows"), Err(ExtractError::InvalidSort(_))));
+        assert!(matches!(SortParams::parse("-name,password").unwrap().check_table::<User>(), Err(ExtractError::UnknownField(f)) if f == "password"));
+    }
+
+    #[tokio::test]
+    async fn apply_params() {
+        let db = connect("mem://").await.unwrap();
+
+        let pagination = Pagination { limit: Some(10), start: Some(20) };
+        let sort = SortParams::parse("-age,name").unwrap();
+
+        let select = db.select_builder().what("user").field(Field::All).apply(&sort).apply(pagination);
+        assert_eq!(select.to_surql(), "SELECT * FROM user ORDER BY age DESC, name LIMIT 10 START 20");
+
+        let select = db.select_builder().what("user").field(Field::All).apply(Pagination::default());
+        assert_eq!(select.to_surql(), "SELECT * FROM user LIMIT 20");
+    }
+
+    #[cfg(feature = "axum")]
+    #[tokio::test]
+    async fn axum_extractors() {
+        use axum::extract::FromRequestParts;
+        use axum::http::Request;
+
+        let (mut parts, _) = Request::builder().uri("/users?sort=-age&limit=5&start=10").body(()).unwrap().into_parts();
+
+        let pagination = Pagination::from_request_parts(&mut parts, &()).await.unwrap();
+        let sort = SortParams::from_request_parts(&mut parts, &()).await.unwrap();
+
+        assert_eq!(pagination, Pagination { limit: Some(5), start: Some(10) });
+        assert_eq!(sort.fields, [SortField { field: "age".to_string(), desc: true }]);
+
+        let (mut parts, _) = Request::builder().uri("/users?sort=a%3Bb").body(()).unwrap().into_parts();
+        assert!(SortParams::from_request_parts(&mut parts, &()).await.is_err());
+
+        let (mut parts, _) = Request::builder().uri("/users?limit=-1").body(()).unwrap().into_parts();
+        assert!(Pagination::from_request_parts(&mut parts, &()).await.is_err());
+    }
+
+    #[cfg(feature = "actix-web")]
+    #[tokio::test]
+    async fn actix_extractors() {
+        use actix_web::FromRequest;
+        use actix_web::test::TestRequest;
+
+        let (req, mut payload) = TestRequest::with_uri("/users?sort=name&limit=5").to_http_parts();
+
+        let pagination = Pagination::from_request(&req, &mut payload).await.unwrap();
+        assert_eq!(pagination, Pagination { limit: Some(5), start: None });
+
+        let (req, mut payload) = TestRequest::with_uri("/users?sort=a%3Bb").to_http_parts();
+        assert!(SortParams::from_request(&req, &mut payload).await.is_err());
+    }
+}
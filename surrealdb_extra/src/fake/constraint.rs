@@ -0,0 +1,182 @@
+use surrealdb::sql::{Expression, Function, Number, Operator, Subquery, Value};
+
+/// The parts of an `ASSERT` clause that the generator understands
+///
+/// Anything else in the assertion is ignored, so generated values can still be rejected by more exotic assertions
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Constraint {
+    pub email: bool,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    /// `$value > min`, the bound itself is not allowed
+    pub min_exclusive: bool,
+    /// `$value < max`, the bound itself is not allowed
+    pub max_exclusive: bool,
+    pub min_len: Option<usize>,
+    pub max_len: Option<usize>,
+    pub choices: Option<Vec<Value>>,
+}
+
+impl Constraint {
+    pub fn from_assert(value: &Value) -> Self {
+        let mut constraint = Self::default();
+        constraint.collect(value);
+
+        constraint
+    }
+
+    fn collect(&mut self, value: &Value) {
+        match value {
+            Value::Subquery(s) => {
+                if let Subquery::Value(v) = s.as_ref() {
+                    self.collect(v);
+                }
+            }
+            Value::Function(f) => {
+                if let Function::Normal(name, args) = f.as_ref() {
+                    if name == "string::is::email" && args.first().is_some_and(is_value_param) {
+                        self.email = true;
+                    }
+                }
+            }
+            Value::Expression(e) => {
+                let Expression::Binary { l, o, r } = e.as_ref() else {
+                    return;
+                };
+
+                match o {
+                    Operator::And => {
+                        self.collect(l);
+                        self.collect(r);
+                    }
+                    Operator::Inside => {
+                        if let (true, Value::Array(arr)) = (is_value_param(l), r) {
+                            self.choices = Some(arr.0.clone());
+                        }
+                    }
+                    Operator::Contain => {
+                        if let (Value::Array(arr), true) = (l, is_value_param(r)) {
+                            self.choices = Some(arr.0.clone());
+                        }
+                    }
+                    o => self.collect_bound(l, o, r),
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn collect_bound(&mut self, l: &Value, o: &Operator, r: &Value) {
+        // Normalise `5 < $value` into `$value > 5`
+        let (subject, o, bound) = match (l, r) {
+            (_, Value::Number(n)) => (l, o.clone(), n),
+            (Value::Number(n), _) => (r, flip(o), n),
+            _ => return,
+        };
+
+        let bound = bound.clone().as_float();
+
+        if is_value_param(subject) {
+            // The kind of the field decides what the next value after a strict bound is
+            match o {
+                Operator::MoreThan | Operator::MoreThanOrEqual => {
+                    self.min = Some(bound);
+                    self.min_exclusive = o == Operator::MoreThan;
+                }
+                Operator::LessThan | Operator::LessThanOrEqual => {
+                    self.max = Some(bound);
+                    self.max_exclusive = o == Operator::LessThan;
+                }
+                Operator::Equal | Operator::Exact => self.choices = Some(vec![Value::Number(Number::Float(bound))]),
+                _ => {}
+            }
+
+            return;
+        }
+
+        if is_len_of_value(subject) {
+            let bound = bound.max(0.0) as usize;
+
+            match o {
+                Operator::MoreThan => self.min_len = Some(bound + 1),
+                Operator::MoreThanOrEqual => self.min_len = Some(bound),
+                Operator::LessThan => self.max_len = Some(bound.saturating_sub(1)),
+                Operator::LessThanOrEqual => self.max_len = Some(bound),
+                Operator::Equal | Operator::Exact => {
+                    self.min_len = Some(bound);
+                    self.max_len = Some(bound);
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+fn flip(o: &Operator) -> Operator {
+    match o {
+        Operator::MoreThan => Operator::LessThan,
+        Operator::MoreThanOrEqual => Operator::LessThanOrEqual,
+        Operator::LessThan => Operator::MoreThan,
+        Operator::LessThanOrEqual => Operator::MoreThanOrEqual,
+        o => o.clone(),
+    }
+}
+
+fn is_value_param(value: &Value) -> bool {
+    matches!(value, Value::Param(p) if p.0.0 == "value")
+}
+
+fn is_len_of_value(value: &Value) -> bool {
+    let Value::Function(f) = value else {
+        return false;
+    };
+
+    matches!(f.as_ref(), Function::Normal(name, args) if (name == "string::len" || name == "array::len") && args.first().is_some_and(is_value_param))
+}
+
+#[cfg(test)]
+mod test {
+    use surrealdb::sql::value;
+    use super::*;
+
+    fn constraint(assert: &str) -> Constraint {
+        Constraint::from_assert(&value(assert).unwrap())
+    }
+
+    #[test]
+    fn email() {
+        assert!(constraint("string::is::email($value)").email);
+    }
+
+    #[test]
+    fn range() {
+        let c = constraint("$value >= 18 AND $value <= 99");
+
+        assert_eq!(c.min, Some(18.0));
+        assert_eq!(c.max, Some(99.0));
+    }
+
+    #[test]
+    fn flipped_range() {
+        let c = constraint("0 < $value");
+
+        assert_eq!(c.min, Some(0.0));
+        assert!(c.min_exclusive);
+        assert!(!c.max_exclusive);
+    }
+
+    #[test]
+    fn inside() {
+        let c = constraint("$value INSIDE ['a', 'b']");
+
+        assert_eq!(c.choices, Some(vec![Value::from("a"), Value::from("b")]));
+    }
+
+    #[test]
+    fn length() {
+        let c = constraint("string::len($value) > 3 AND string::len($value) <= 10");
+
+        assert_eq!(c.min_len, Some(4));
+        assert_eq!(c.max_len, Some(10));
+    }
+}
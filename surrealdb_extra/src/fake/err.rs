@@ -0,0 +1,15 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum FakeError {
+    #[error("Field definition could not be parsed: {0}")]
+    InvalidDefinition(String),
+    #[error("Table name `{0}` is not a valid identifier")]
+    InvalidTable(String),
+    #[error("Table `{0}` has no field definitions")]
+    NoFields(String),
+    #[error("No integer between {0} and {1}")]
+    EmptyRange(f64, f64),
+    #[error("{0}")]
+    Db(#[from] surrealdb::Error),
+}
@@ -0,0 +1,362 @@
+//! Random records generated from the field definitions of a table
+//!
+//! The generator reads the `TYPE` and the recognizable parts of the `ASSERT` clause of every field
+//! (`string::is::email($value)`, numeric ranges, `string::len`/`array::len` ranges and `INSIDE` lists),
+//! so the generated records can be inserted into a `SCHEMAFULL` table.
+//!
+//! Fields with a `VALUE` clause or a record type are left out.
+//!
+//! # Example
+//!
+//! ```rust
+//! use surrealdb::engine::any::connect;
+//! use surrealdb_extra::fake::FakeGenerator;
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let db = connect("mem://").await.unwrap();
+//!     db.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     db.query("
+//!         DEFINE TABLE user SCHEMAFULL;
+//!         DEFINE FIELD email ON user TYPE string ASSERT string::is::email($value);
+//!         DEFINE FIELD age ON user TYPE int ASSERT $value >= 18 AND $value <= 99;
+//!     ").await.unwrap();
+//!
+//!     let generator = FakeGenerator::from_table(&db, "user").await.unwrap();
+//!
+//!     let inserted = generator.seed(&db, "user", 10).await.unwrap();
+//!     assert_eq!(inserted, 10);
+//! }
+//! ```
+
+pub mod err;
+pub mod constraint;
+
+use anyhow::Result;
+use chrono::{Duration as ChronoDuration, Utc};
+use rand::Rng;
+use rand::seq::SliceRandom;
+use surrealdb::sql::statements::{DefineFieldStatement, DefineStatement};
+use surrealdb::sql::{parse, Datetime, Duration, Idiom, Kind, Number, Object, Part, Statement, Strand, Uuid, Value};
+use surrealdb::{Connection, Surreal};
+pub use crate::fake::constraint::Constraint;
+pub use crate::fake::err::FakeError;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldSpec {
+    pub name: Idiom,
+    pub kind: Option<Kind>,
+    pub constraint: Constraint,
+}
+
+impl FieldSpec {
+    /// Returns `None` for fields that should not be generated
+    pub fn from_definition(def: &DefineFieldStatement) -> Option<Self> {
+        if def.value.is_some() || def.name.0.iter().any(|p| !matches!(p, Part::Field(_))) {
+            return None;
+        }
+
+        if let Some(Kind::Record(_)) = strip_option(def.kind.as_ref()) {
+            return None;
+        }
+
+        Some(Self {
+            name: def.name.clone(),
+            kind: def.kind.clone(),
+            constraint: def.assert.as_ref().map(Constraint::from_assert).unwrap_or_default(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FakeGenerator {
+    pub fields: Vec<FieldSpec>,
+}
+
+impl FakeGenerator {
+    /// Builds the generator from `DEFINE FIELD` statements
+    pub fn from_definitions(definitions: &[&str]) -> Result<Self> {
+        let mut fields = vec![];
+
+        for definition in definitions {
+            let query = parse(definition).map_err(|e| FakeError::InvalidDefinition(e.to_string()))?;
+
+            for statement in query.0 .0 {
+                let Statement::Define(DefineStatement::Field(def)) = statement else {
+                    return Err(FakeError::InvalidDefinition(definition.to_string()).into());
+                };
+
+                fields.extend(FieldSpec::from_definition(&def));
+            }
+        }
+
+        Ok(Self { fields })
+    }
+
+    /// Reads the field definitions with `INFO FOR TABLE`
+    pub async fn from_table<C: Connection>(db: &Surreal<C>, table: &str) -> Result<Self> {
+        check_table(table)?;
+
+        let mut res = db.query(format!("INFO FOR TABLE {table}"))
+            .await.map_err(FakeError::from)?;
+
+        let info: surrealdb::Value = res.take(0).map_err(FakeError::from)?;
+
+        let Value::Object(fields) = info.into_inner().pick(&[Part::from("fields")]) else {
+            return Err(FakeError::NoFields(table.to_string()).into());
+        };
+
+        let definitions: Vec<String> = fields.values().filter_map(|v| match v {
+            Value::Strand(s) => Some(s.0.clone()),
+            _ => None,
+        }).collect();
+
+        let definitions: Vec<&str> = definitions.iter().map(|d| d.as_str()).collect();
+
+        Self::from_definitions(&definitions)
+    }
+
+    /// Generates one record as an object, an `int` field without an integer in its range is an error
+    pub fn generate(&self, rng: &mut impl Rng) -> Result<Value> {
+        let mut record = Value::Object(Object::default());
+
+        for field in &self.fields {
+            let value = generate_value(rng, strip_option(field.kind.as_ref()), &field.constraint)?;
+
+            if !value.is_none() {
+                record.put(&field.name, value);
+            }
+        }
+
+        Ok(record)
+    }
+
+    /// Generates and creates `count` records in the table, returns how many were created
+    pub async fn seed<C: Connection>(&self, db: &Surreal<C>, table: &str, count: usize) -> Result<usize> {
+        let records: Vec<Value> = {
+            let mut rng = rand::thread_rng();
+            (0..count).map(|_| self.generate(&mut rng)).collect::<Result<_>>()?
+        };
+
+        check_table(table)?;
+
+        let mut res = db.query(format!("INSERT INTO {table} $records"))
+            .bind(("records", Value::from(records)))
+            .await.map_err(FakeError::from)?
+            .check().map_err(FakeError::from)?;
+
+        let created: surrealdb::Value = res.take(0).map_err(FakeError::from)?;
+
+        match created.into_inner() {
+            Value::Array(arr) => Ok(arr.len()),
+            _ => Ok(0),
+        }
+    }
+}
+
+fn check_table(table: &str) -> Result<()> {
+    if !table.chars().all(|c| c.is_alphanumeric() || c == '_') {
+        return Err(FakeError::InvalidTable(table.to_string()).into());
+    }
+
+    Ok(())
+}
+
+fn strip_option(kind: Option<&Kind>) -> Option<&Kind> {
+    match kind {
+        Some(Kind::Option(k)) => strip_option(Some(k)),
+        Some(Kind::Either(k)) => strip_option(k.first()),
+        k => k,
+    }
+}
+
+fn generate_value(rng: &mut impl Rng, kind: Option<&Kind>, constraint: &Constraint) -> Result<Value> {
+    if let Some(choices) = &constraint.choices {
+        return Ok(choices.choose(rng).cloned().unwrap_or_default());
+    }
+
+    let kind = match kind {
+        Some(kind) => kind,
+        None if constraint.email || constraint.min_len.is_some() || constraint.max_len.is_some() => &Kind::String,
+        None if constraint.min.is_some() || constraint.max.is_some() => &Kind::Int,
+        None => &Kind::String,
+    };
+
+    let value = match kind {
+        Kind::Bool => Value::Bool(rng.gen()),
+        Kind::Int => {
+            let (min, max) = range(constraint, 0.0, 1000.0, true);
+
+            // e.g. `$value > 0.2 AND $value < 0.8`
+            if min.ceil() > max.floor() {
+                return Err(FakeError::EmptyRange(min, max).into());
+            }
+
+            Value::Number(Number::Int(rng.gen_range(min.ceil() as i64..=max.floor() as i64)))
+        }
+        Kind::Float | Kind::Number | Kind::Decimal => {
+            let (min, max) = range(constraint, 0.0, 1000.0, false);
+
+            Value::Number(Number::Float(rng.gen_range(min..=max)))
+        }
+        Kind::String => Value::Strand(Strand::from(generate_string(rng, constraint))),
+        Kind::Datetime => {
+            let seconds = rng.gen_range(0..60 * 60 * 24 * 365);
+
+            Value::Datetime(Datetime::from(Utc::now() - ChronoDuration::seconds(seconds)))
+        }
+        Kind::Duration => Value::Duration(Duration::from_secs(rng.gen_range(1..60 * 60 * 24))),
+        Kind::Uuid => Value::Uuid(Uuid::new_v4()),
+        Kind::Object => Value::Object(Object::default()),
+        Kind::Array(inner, max) | Kind::Set(inner, max) => {
+            let max = max.map(|m| m as usize).unwrap_or(3).min(constraint.max_len.unwrap_or(usize::MAX));
+            let min = constraint.min_len.unwrap_or(0).min(max);
+            let len = rng.gen_range(min..=max);
+
+            let values: Vec<Value> = (0..len)
+                .map(|_| generate_value(rng, strip_option(Some(inner)), &Constraint::default()))
+                .collect::<Result<_>>()?;
+
+            Value::from(values)
+        }
+        _ => Value::None,
+    };
+
+    Ok(value)
+}
+
+/// A strict bound moves to the next whole number for ints and to the next float otherwise
+fn range(constraint: &Constraint, min: f64, max: f64, int: bool) -> (f64, f64) {
+    let min = constraint.min.unwrap_or(constraint.max.map(|m| m - max).unwrap_or(min));
+    let max = constraint.max.unwrap_or(min + max);
+
+    let min = match (constraint.min_exclusive, int) {
+        (false, _) => min,
+        (true, true) => min.floor() + 1.0,
+        (true, false) => next_up(min),
+    };
+
+    let max = match (constraint.max_exclusive, int) {
+        (false, _) => max,
+        (true, true) => max.ceil() - 1.0,
+        (true, false) => -next_up(-max),
+    };
+
+    if max < min {
+        return (min, min);
+    }
+
+    (min, max)
+}
+
+/// The next float after `value`, `f64::next_up` needs a newer rust
+fn next_up(value: f64) -> f64 {
+    if value.is_nan() || value == f64::INFINITY {
+        return value;
+    }
+
+    if value == 0.0 {
+        return f64::from_bits(1);
+    }
+
+    let bits = value.to_bits();
+
+    f64::from_bits(if value > 0.0 { bits + 1 } else { bits - 1 })
+}
+
+fn generate_string(rng: &mut impl Rng, constraint: &Constraint) -> String {
+    const LETTERS: &[u8] = b"abcdefghijklmnopqrstuvwxyz";
+
+    let suffix = if constraint.email { "@example.com" } else { "" };
+
+    let max = constraint.max_len.unwrap_or(16).saturating_sub(suffix.len()).max(1);
+    let min = constraint.min_len.unwrap_or(8).saturating_sub(suffix.len()).clamp(1, max);
+    let len = rng.gen_range(min..=max);
+
+    let mut s: String = (0..len).map(|_| LETTERS[rng.gen_range(0..LETTERS.len())] as char).collect();
+    s.push_str(suffix);
+
+    s
+}
+
+#[cfg(test)]
+mod test {
+    use crate::testing::db;
+    use super::*;
+
+    #[test]
+    fn respects_asserts() {
+        let generator = FakeGenerator::from_definitions(&[
+            "DEFINE FIELD email ON user TYPE string ASSERT string::is::email($value)",
+            "DEFINE FIELD age ON user TYPE int ASSERT $value >= 18 AND $value <= 20",
+            "DEFINE FIELD role ON user TYPE string ASSERT $value INSIDE ['admin', 'user']",
+            "DEFINE FIELD created ON user VALUE time::now()",
+        ]).unwrap();
+
+        assert_eq!(generator.fields.len(), 3);
+
+        let mut rng = rand::thread_rng();
+        for _ in 0..20 {
+            let record = generator.generate(&mut rng).unwrap();
+
+            let Value::Strand(email) = record.pick(&[Part::from("email")]) else { panic!() };
+            assert!(email.0.ends_with("@example.com"));
+
+            let Value::Number(age) = record.pick(&[Part::from("age")]) else { panic!() };
+            assert!((18..=20).contains(&age.as_int()));
+
+            let role = record.pick(&[Part::from("role")]);
+            assert!(role == Value::from("admin") || role == Value::from("user"));
+        }
+    }
+
+    #[test]
+    fn empty_int_range() {
+        let generator = FakeGenerator::from_definitions(&[
+            "DEFINE FIELD ratio ON user TYPE int ASSERT $value >= 0.2 AND $value <= 0.8",
+        ]).unwrap();
+
+        let err = generator.generate(&mut rand::thread_rng()).unwrap_err();
+        assert!(matches!(err.downcast_ref::<FakeError>(), Some(FakeError::EmptyRange(..))));
+    }
+
+    #[test]
+    fn strict_bounds() {
+        let generator = FakeGenerator::from_definitions(&[
+            "DEFINE FIELD ratio ON user TYPE float ASSERT $value > 0.2 AND $value < 0.8",
+            "DEFINE FIELD count ON user TYPE int ASSERT $value > 1 AND $value < 3",
+        ]).unwrap();
+
+        let mut rng = rand::thread_rng();
+        for _ in 0..20 {
+            let record = generator.generate(&mut rng).unwrap();
+
+            let Value::Number(ratio) = record.pick(&[Part::from("ratio")]) else { panic!() };
+            let ratio = ratio.as_float();
+            assert!(ratio > 0.2 && ratio < 0.8);
+
+            assert_eq!(record.pick(&[Part::from("count")]), Value::from(2));
+        }
+    }
+
+    #[tokio::test]
+    async fn seed_schemafull_table() {
+        let db = db().await;
+
+        db.query("
+            DEFINE TABLE user SCHEMAFULL;
+            DEFINE FIELD email ON user TYPE string ASSERT string::is::email($value);
+            DEFINE FIELD age ON user TYPE int ASSERT $value >= 18 AND $value <= 99;
+            DEFINE FIELD name ON user TYPE string ASSERT string::len($value) >= 3 AND string::len($value) <= 5;
+            DEFINE FIELD tags ON user TYPE array<string, 2>;
+            DEFINE FIELD nickname ON user TYPE option<string>;
+        ").await.unwrap().check().unwrap();
+
+        let generator = FakeGenerator::from_table(&db, "user").await.unwrap();
+
+        let inserted = generator.seed(&db, "user", 25).await.unwrap();
+
+        assert_eq!(inserted, 25);
+    }
+}
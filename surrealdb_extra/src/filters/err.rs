@@ -0,0 +1,13 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum FilterError {
+    #[error("`{0}` is not a field that can be filtered or sorted")]
+    UnknownField(String),
+    #[error("`{0}` is not a filter operator")]
+    UnknownOperator(String),
+    #[error("`{value}` is not a valid value for `{field}`")]
+    InvalidValue { field: String, value: String },
+    #[error("`{value}` is not a valid `{param}`, it has to be a positive number")]
+    InvalidNumber { param: String, value: String },
+}
@@ -0,0 +1,419 @@
+//! Filters, sorting and paging from HTTP query strings
+//!
+//! `Filters::parse` reads a query string like `name[eq]=bob&age[gte]=20&sort=-created_at&limit=50` into conditions,
+//! orders, a limit and a start that `apply` adds to a select builder.
+//! Only the fields of the `FilterConfig` can be filtered and sorted, `FilterConfig::for_table` allows the fields of `T::meta()`
+//! with their nested fields, an unknown field or operator is an error.
+//! The values are typed by the Rust type of the field and are never parsed as SurrealQL, so a query string can't change the statement.
+//!
+//! - `field=value` and `field[eq]=value` is `field = value`, the other operators are `ne`, `gt`, `gte`, `lt`, `lte`,
+//!   `like` (`~`), `contains` (`CONTAINS`) and `in` (`INSIDE`) with comma separated values
+//! - `sort=name,-age` orders by `name ASC, age DESC`
+//! - `limit=50` and `start=100` page the rows, `max_limit` caps the limit
+//!
+//! # Example
+//!
+//! ```rust
+//! use serde::{Serialize, Deserialize};
+//! use surrealdb::engine::any::connect;
+//! use surrealdb::sql::{Field, Thing as RecordId};
+//! use surrealdb_extra::filters::{FilterConfig, Filters};
+//! use surrealdb_extra::query::statement::StatementBuilder;
+//! use surrealdb_extra::table::Table;
+//!
+//! #[derive(Table, Serialize, Deserialize, Clone)]
+//! #[table(name = "user")]
+//! struct User {
+//!     id: Option<RecordId>,
+//!     name: String,
+//!     age: i64
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let db = connect("mem://").await.unwrap();
+//!     db.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     let config = FilterConfig::for_table::<User>().max_limit(100);
+//!     let filters = Filters::parse("name[ne]=bob&age[gte]=20&sort=-age&limit=500", &config).unwrap();
+//!
+//!     let select = filters.apply(db.select_builder().what("user").field(Field::All));
+//!     assert_eq!(select.to_surql(), "SELECT * FROM user WHERE name != 'bob' AND age >= 20 ORDER BY age DESC LIMIT 100");
+//!
+//!     // `password` is not a field of `User`
+//!     assert!(Filters::parse("password=secret", &config).is_err());
+//! }
+//! ```
+
+pub mod err;
+
+use surrealdb::Connection;
+use surrealdb::sql::{thing, Array, Datetime, Operator, Order, Value};
+use crate::query::dyn_select::DynSelectBuilder;
+use crate::query::parsing::cond::{Condition, ExtraCond};
+use crate::query::parsing::idiom::ExtraIdiom;
+use crate::query::parsing::order::{ExtraOrder, OrderDirection};
+use crate::query::select::SelectBuilder;
+use crate::query::states::{FilledCond, FilledFields, FilledWhat, NoCond};
+use crate::table::Table;
+pub use crate::filters::err::FilterError;
+
+pub const SORT: &str = "sort";
+pub const LIMIT: &str = "limit";
+pub const START: &str = "start";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterOp {
+    Eq,
+    Ne,
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+    Like,
+    Contains,
+    In,
+}
+
+impl FilterOp {
+    /// The operator of the query string, e.g. `gte` of `age[gte]=20`
+    pub fn parse(op: &str) -> Option<Self> {
+        let op = match op {
+            "eq" => Self::Eq,
+            "ne" => Self::Ne,
+            "gt" => Self::Gt,
+            "gte" => Self::Gte,
+            "lt" => Self::Lt,
+            "lte" => Self::Lte,
+            "like" => Self::Like,
+            "contains" => Self::Contains,
+            "in" => Self::In,
+            _ => return None,
+        };
+
+        Some(op)
+    }
+
+    pub fn operator(&self) -> Operator {
+        match self {
+            Self::Eq => Operator::Equal,
+            Self::Ne => Operator::NotEqual,
+            Self::Gt => Operator::MoreThan,
+            Self::Gte => Operator::MoreThanOrEqual,
+            Self::Lt => Operator::LessThan,
+            Self::Lte => Operator::LessThanOrEqual,
+            Self::Like => Operator::Like,
+            Self::Contains => Operator::Contain,
+            Self::In => Operator::Inside,
+        }
+    }
+}
+
+/// One `field[op]=value` of the query string
+#[derive(Debug, Clone, PartialEq)]
+pub struct Filter {
+    pub field: String,
+    pub op: FilterOp,
+    pub value: Value,
+}
+
+impl Filter {
+    pub fn to_condition(&self) -> Condition {
+        Condition::ValOpVal(Value::Idiom(ExtraIdiom::from(self.field.as_str()).0), self.op.operator(), self.value.clone())
+    }
+}
+
+/// The fields that can be filtered and sorted with their Rust types, an empty type guesses the type from the value
+#[derive(Debug, Clone, Default)]
+pub struct FilterConfig {
+    pub fields: Vec<(String, &'static str)>,
+    pub max_limit: Option<u64>,
+}
+
+impl FilterConfig {
+    /// Fields without a type, `20` is a number and `true` a bool, everything else is a string
+    pub fn new<S: Into<String>>(fields: impl IntoIterator<Item = S>) -> Self {
+        Self {
+            fields: fields.into_iter().map(|field| (field.into(), "")).collect(),
+            max_limit: None,
+        }
+    }
+
+    /// The fields and nested fields of the table with their types
+    pub fn for_table<T: Table>() -> Self {
+        let meta = T::meta();
+
+        let mut fields: Vec<(String, &'static str)> = meta.fields.iter().map(|f| (f.name.to_string(), f.rust_type)).collect();
+        fields.extend(meta.nested_fields().into_iter().map(|(path, f)| (path, f.rust_type)));
+
+        Self {
+            fields,
+            max_limit: None,
+        }
+    }
+
+    /// Caps the `limit` of the query string, also used when it has none
+    pub fn max_limit(mut self, max_limit: u64) -> Self {
+        self.max_limit = Some(max_limit);
+
+        self
+    }
+
+    fn rust_type(&self, field: &str) -> Result<&'static str, FilterError> {
+        self.fields.iter()
+            .find(|(name, _)| name == field)
+            .map(|(_, rust_type)| *rust_type)
+            .ok_or_else(|| FilterError::UnknownField(field.to_string()))
+    }
+}
+
+/// The conditions, orders and paging of a query string
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Filters {
+    pub filters: Vec<Filter>,
+    pub order: Vec<Order>,
+    pub limit: Option<u64>,
+    pub start: Option<u64>,
+}
+
+impl Filters {
+    /// Parses a url encoded query string, with or without the leading `?`
+    pub fn parse(query: &str, config: &FilterConfig) -> Result<Self, FilterError> {
+        let mut filters = Self::default();
+
+        for (key, value) in form_urlencoded::parse(query.trim_start_matches('?').as_bytes()) {
+            match key.as_ref() {
+                SORT => {
+                    // A `+` of the query string is decoded as a space
+                    for field in value.split(',').map(str::trim).filter(|f| !f.is_empty()) {
+                        let (field, direction) = match field.strip_prefix('-') {
+                            Some(field) => (field, OrderDirection::DESC),
+                            None => (field, OrderDirection::ASC),
+                        };
+
+                        config.rust_type(field)?;
+
+                        filters.order.push(ExtraOrder::from((field, direction)).0);
+                    }
+                }
+                LIMIT => filters.limit = Some(number(LIMIT, &value)?),
+                START => filters.start = Some(number(START, &value)?),
+                key => {
+                    let (field, op) = match key.split_once('[') {
+                        Some((field, op)) => {
+                            let op = op.strip_suffix(']').unwrap_or(op);
+
+                            (field, FilterOp::parse(op).ok_or_else(|| FilterError::UnknownOperator(op.to_string()))?)
+                        }
+                        None => (key, FilterOp::Eq),
+                    };
+
+                    let rust_type = config.rust_type(field)?;
+
+                    let value = match op {
+                        FilterOp::Like => Value::from(value.as_ref()),
+                        FilterOp::In => Value::Array(Array::from(
+                            value.split(',').map(|v| typed_value(field, rust_type, v)).collect::<Result<Vec<_>, _>>()?
+                        )),
+                        _ => typed_value(field, rust_type, &value)?,
+                    };
+
+                    filters.filters.push(Filter { field: field.to_string(), op, value });
+                }
+            }
+        }
+
+        if let Some(max_limit) = config.max_limit {
+            filters.limit = Some(filters.limit.map_or(max_limit, |limit| limit.min(max_limit)));
+        }
+
+        Ok(filters)
+    }
+
+    /// `parse` with the fields of the table
+    pub fn parse_for<T: Table>(query: &str) -> Result<Self, FilterError> {
+        Self::parse(query, &FilterConfig::for_table::<T>())
+    }
+
+    /// The filters joined with `AND`, `None` without filters
+    pub fn cond(&self) -> Option<ExtraCond> {
+        if self.filters.is_empty() {
+            return None;
+        }
+
+        let mut conditions = vec![];
+
+        for (i, filter) in self.filters.iter().enumerate() {
+            if i > 0 {
+                conditions.push(Condition::from(Operator::And));
+            }
+
+            conditions.push(filter.to_condition());
+        }
+
+        Some(ExtraCond::from(conditions))
+    }
+
+    /// Adds the conditions, orders, limit and start to the select
+    pub fn apply<'r, C: Connection>(&self, select: SelectBuilder<'r, C, FilledWhat, FilledFields, NoCond>) -> SelectBuilder<'r, C, FilledWhat, FilledFields, FilledCond> {
+        let mut select = select.condition_if(self.cond());
+
+        for order in &self.order {
+            select = select.order(order.clone());
+        }
+
+        select.limit_if(self.limit.map(|limit| limit as i64)).start_if(self.start.map(|start| start as i64))
+    }
+
+    /// Same as `apply` for the dyn builder, the conditions are added to its conditions with `AND`
+    pub fn apply_dyn<'r, C: Connection>(&self, select: DynSelectBuilder<'r, C>) -> DynSelectBuilder<'r, C> {
+        let mut select = select.condition_if(self.cond());
+
+        for order in &self.order {
+            select = select.order(order.clone());
+        }
+
+        select.limit_if(self.limit.map(|limit| limit as i64)).start_if(self.start.map(|start| start as i64))
+    }
+}
+
+fn number(param: &str, value: &str) -> Result<u64, FilterError> {
+    value.parse().map_err(|_| FilterError::InvalidNumber { param: param.to_string(), value: value.to_string() })
+}
+
+/// The name of the type without `Option`, `Box` and collection wrappers, e.g. `i64` of `Option<Vec<i64>>`
+fn scalar_type(rust_type: &str) -> String {
+    let mut rust_type: String = rust_type.chars().filter(|c| !c.is_whitespace()).collect();
+
+    while let Some((wrapper, inner)) = rust_type.split_once('<') {
+        let wrapper = wrapper.rsplit("::").next().unwrap_or(wrapper);
+
+        match (wrapper, inner.strip_suffix('>')) {
+            ("Option" | "Box" | "Vec" | "VecDeque" | "HashSet" | "BTreeSet", Some(inner)) => rust_type = inner.to_string(),
+            _ => return wrapper.to_string(),
+        }
+    }
+
+    rust_type.rsplit("::").next().unwrap_or_default().to_string()
+}
+
+fn typed_value(field: &str, rust_type: &str, value: &str) -> Result<Value, FilterError> {
+    let invalid = || FilterError::InvalidValue { field: field.to_string(), value: value.to_string() };
+
+    let value = match scalar_type(rust_type).as_str() {
+        "i8" | "i16" | "i32" | "i64" | "isize" | "u8" | "u16" | "u32" | "u64" | "usize" => Value::from(value.parse::<i64>().map_err(|_| invalid())?),
+        "f32" | "f64" => Value::from(value.parse::<f64>().map_err(|_| invalid())?),
+        "bool" => Value::from(value.parse::<bool>().map_err(|_| invalid())?),
+        "Thing" | "RecordId" | "Link" => Value::Thing(thing(value).map_err(|_| invalid())?),
+        "Datetime" | "DateTime" => Value::Datetime(Datetime::try_from(value).map_err(|_| invalid())?),
+        "" => {
+            if let Ok(n) = value.parse::<i64>() {
+                Value::from(n)
+            } else if let Ok(n) = value.parse::<f64>() {
+                Value::from(n)
+            } else if let Ok(b) = value.parse::<bool>() {
+                Value::from(b)
+            } else {
+                Value::from(value)
+            }
+        }
+        _ => Value::from(value),
+    };
+
+    Ok(value)
+}
+
+#[cfg(test)]
+mod test {
+    use serde::{Deserialize, Serialize};
+    use surrealdb::sql::{Field, Thing as RecordId};
+    use crate::query::statement::StatementBuilder;
+    use crate::table::link::Link;
+    use crate::testing::db;
+    use super::*;
+
+    #[derive(Debug, Table, Serialize, Deserialize, Clone, PartialEq)]
+    #[table(name = "user")]
+    struct User {
+        id: Option<RecordId>,
+        name: String,
+        age: Option<i64>,
+        tags: Vec<String>,
+        active: bool,
+        friend: Option<Link<Friend>>,
+    }
+
+    #[derive(Debug, Table, Serialize, Deserialize, Clone, PartialEq)]
+    #[table(name = "friend")]
+    struct Friend {
+        id: Option<RecordId>,
+        name: String,
+    }
+
+    #[test]
+    fn scalar_types() {
+        assert_eq!(scalar_type("Option<Vec<i64>>"), "i64");
+        assert_eq!(scalar_type("std::option::Option<String>"), "String");
+        assert_eq!(scalar_type("Option<Link<Friend>>"), "Link");
+        assert_eq!(scalar_type(""), "");
+    }
+
+    #[test]
+    fn parse_filters() {
+        let filters = Filters::parse_for::<User>("?name=bob&age[gte]=20&tags[contains]=rust&active=true&friend=friend:one&age[in]=1,2").unwrap();
+
+        assert_eq!(filters.filters, [
+            Filter { field: "name".to_string(), op: FilterOp::Eq, value: Value::from("bob") },
+            Filter { field: "age".to_string(), op: FilterOp::Gte, value: Value::from(20) },
+            Filter { field: "tags".to_string(), op: FilterOp::Contains, value: Value::from("rust") },
+            Filter { field: "active".to_string(), op: FilterOp::Eq, value: Value::from(true) },
+            Filter { field: "friend".to_string(), op: FilterOp::Eq, value: Value::Thing(RecordId::from(("friend", "one"))) },
+            Filter { field: "age".to_string(), op: FilterOp::In, value: Value::from(vec![1, 2]) },
+        ]);
+
+        assert_eq!(filters.cond().unwrap().0.to_string(), "WHERE name = 'bob' AND age >= 20 AND tags CONTAINS 'rust' AND active = true AND friend = friend:one AND age INSIDE [1, 2]");
+    }
+
+    #[test]
+    fn parse_errors() {
+        let config = FilterConfig::for_table::<User>();
+
+        assert!(matches!(Filters::parse("password=1", &config), Err(FilterError::UnknownField(f)) if f == "password"));
+        assert!(matches!(Filters::parse("sort=-password", &config), Err(FilterError::UnknownField(_))));
+        assert!(matches!(Filters::parse("name[drop]=1", &config), Err(FilterError::UnknownOperator(_))));
+        assert!(matches!(Filters::parse("age=old", &config), Err(FilterError::InvalidValue { .. })));
+        assert!(matches!(Filters::parse("limit=-1", &config), Err(FilterError::InvalidNumber { .. })));
+    }
+
+    #[test]
+    fn values_are_not_surql() {
+        let config = FilterConfig::new(["name", "n"]);
+
+        let filters = Filters::parse("name=%27a%27%20OR%20true&n=5", &config).unwrap();
+
+        assert_eq!(filters.filters[0].value, Value::from("'a' OR true"));
+        assert_eq!(filters.filters[1].value, Value::from(5));
+    }
+
+    #[tokio::test]
+    async fn apply_filters() {
+        let db = db().await;
+
+        for (name, age) in [("a", 10), ("b", 20), ("c", 30)] {
+            User { id: None, name: name.to_string(), age: Some(age), tags: vec![], active: true, friend: None }.create(&db).await.unwrap();
+        }
+
+        let config = FilterConfig::for_table::<User>().max_limit(1);
+
+        let filters = Filters::parse("age[gt]=10&sort=-age,name&start=1", &config).unwrap();
+
+        let select = filters.apply(db.select_builder().what("user").field(Field::All));
+        assert_eq!(select.to_surql(), "SELECT * FROM user WHERE age > 10 ORDER BY age DESC, name LIMIT 1 START 1");
+
+        let users: Vec<User> = select.execute_values().await.unwrap();
+        assert_eq!(users.iter().map(|u| u.name.as_str()).collect::<Vec<_>>(), ["b"]);
+
+        let select = filters.apply_dyn(db.dyn_select_builder().what("user").field(Field::All).condition("active = true"));
+        assert_eq!(select.to_surql().unwrap(), "SELECT * FROM user WHERE active = true AND (age > 10) ORDER BY age DESC, name LIMIT 1 START 1");
+    }
+}
@@ -0,0 +1,9 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum FixtureError {
+    #[error("Record of table `{0}` was not created")]
+    NotCreated(String),
+    #[error("{0}")]
+    Db(#[from] surrealdb::Error),
+}
@@ -0,0 +1,243 @@
+//! Test records from factories and clean tables around tests
+//!
+//! A `Factory` builds a record from a sequence number, `Fixture` counts the sequence up for every record,
+//! applies the overrides and creates the records with `Table::create`, so the hooks and validation run like in the app.
+//! `with_clean_table` deletes every record of the table before and after the test.
+//!
+//! # Example
+//!
+//! ```rust
+//! use serde::{Serialize, Deserialize};
+//! use surrealdb::engine::any::connect;
+//! use surrealdb::sql::Thing as RecordId;
+//! use surrealdb_extra::fixtures::{with_clean_table, Fixture};
+//! use surrealdb_extra::table::Table;
+//!
+//! #[derive(Table, Serialize, Deserialize, Clone)]
+//! #[table(name = "user")]
+//! struct User {
+//!     id: Option<RecordId>,
+//!     email: String,
+//!     admin: bool
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let db = connect("mem://").await.unwrap();
+//!     db.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     let users = Fixture::new(|n| User { id: None, email: format!("user{n}@example.com"), admin: false });
+//!
+//!     with_clean_table::<User, _, _, _>(&db, || async {
+//!         let user = users.create(&db).await.unwrap();
+//!         assert_eq!(user.email, "user1@example.com");
+//!
+//!         let admin = users.create_with(&db, |u| u.admin = true).await.unwrap();
+//!         assert_eq!((admin.email.as_str(), admin.admin), ("user2@example.com", true));
+//!
+//!         assert_eq!(User::get_all(&db).await.unwrap().len(), 2);
+//!     }).await.unwrap();
+//!
+//!     assert!(User::get_all(&db).await.unwrap().is_empty());
+//! }
+//! ```
+
+pub mod err;
+
+use std::future::Future;
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicU64, Ordering};
+use anyhow::Result;
+use surrealdb::{Connection, Surreal};
+use crate::table::Table;
+pub use crate::fixtures::err::FixtureError;
+
+/// Builds a record from its sequence number, which starts at `1`
+pub trait Factory<T: Table>: Send + Sync {
+    fn build(&self, sequence: u64) -> T;
+}
+
+impl<T: Table, F> Factory<T> for F
+    where F: Fn(u64) -> T + Send + Sync
+{
+    fn build(&self, sequence: u64) -> T {
+        self(sequence)
+    }
+}
+
+type Override<T> = Box<dyn Fn(&mut T) + Send + Sync>;
+
+/// Counts the sequence of a factory and applies overrides to every record
+pub struct Fixture<T, F>
+    where T: Table, F: Factory<T>
+{
+    pub factory: F,
+    pub(crate) sequence: AtomicU64,
+    pub(crate) overrides: Vec<Override<T>>,
+    pub(crate) table: PhantomData<T>,
+}
+
+impl<T, F> Fixture<T, F>
+    where T: Table, F: Factory<T>
+{
+    pub fn new(factory: F) -> Self {
+        Self {
+            factory,
+            sequence: AtomicU64::new(0),
+            overrides: vec![],
+            table: PhantomData,
+        }
+    }
+
+    /// Applied to every record after it is built, in the order they were added
+    pub fn with(mut self, f: impl Fn(&mut T) + Send + Sync + 'static) -> Self {
+        self.overrides.push(Box::new(f));
+
+        self
+    }
+
+    /// The sequence number of the last built record, `0` before the first
+    pub fn sequence(&self) -> u64 {
+        self.sequence.load(Ordering::Relaxed)
+    }
+
+    /// Starts the sequence at `1` again
+    pub fn reset(&self) {
+        self.sequence.store(0, Ordering::Relaxed);
+    }
+
+    pub fn build(&self) -> T {
+        self.build_with(|_| {})
+    }
+
+    /// Builds the record and applies `f` after the overrides of the fixture
+    pub fn build_with(&self, f: impl FnOnce(&mut T)) -> T {
+        let sequence = self.sequence.fetch_add(1, Ordering::Relaxed) + 1;
+
+        let mut t = self.factory.build(sequence);
+
+        for o in &self.overrides {
+            o(&mut t);
+        }
+
+        f(&mut t);
+
+        t
+    }
+
+    pub fn build_many(&self, count: usize) -> Vec<T> {
+        (0..count).map(|_| self.build()).collect()
+    }
+
+    pub async fn create<C: Connection>(&self, db: &Surreal<C>) -> Result<T> {
+        self.create_with(db, |_| {}).await
+    }
+
+    /// Creates the record with `Table::create`, `f` is applied like in `build_with`
+    pub async fn create_with<C: Connection>(&self, db: &Surreal<C>, f: impl FnOnce(&mut T)) -> Result<T> {
+        let t = self.build_with(f).create(db).await?;
+
+        Ok(t.ok_or_else(|| FixtureError::NotCreated(T::TABLE_NAME.to_string()))?)
+    }
+
+    /// Creates the records one after the other
+    pub async fn create_many<C: Connection>(&self, db: &Surreal<C>, count: usize) -> Result<Vec<T>> {
+        let mut records = Vec::with_capacity(count);
+
+        for _ in 0..count {
+            records.push(self.create(db).await?);
+        }
+
+        Ok(records)
+    }
+}
+
+/// Deletes every record of the table
+pub async fn truncate<C: Connection>(db: &Surreal<C>, table: &str) -> Result<()> {
+    db.query("DELETE type::table($table)")
+        .bind(("table", table.to_string()))
+        .await.map_err(FixtureError::from)?
+        .check().map_err(FixtureError::from)?;
+
+    Ok(())
+}
+
+/// Truncates the table of `T` before and after `f`, the table is left as it is when `f` panics
+pub async fn with_clean_table<T, C, F, Fut>(db: &Surreal<C>, f: F) -> Result<Fut::Output>
+    where T: Table, C: Connection, F: FnOnce() -> Fut, Fut: Future
+{
+    with_clean_tables(db, &[T::TABLE_NAME], f).await
+}
+
+/// Truncates the tables before and after `f`, the tables are left as they are when `f` panics
+pub async fn with_clean_tables<C, F, Fut>(db: &Surreal<C>, tables: &[&str], f: F) -> Result<Fut::Output>
+    where C: Connection, F: FnOnce() -> Fut, Fut: Future
+{
+    for table in tables {
+        truncate(db, table).await?;
+    }
+
+    let output = f().await;
+
+    for table in tables {
+        truncate(db, table).await?;
+    }
+
+    Ok(output)
+}
+
+#[cfg(test)]
+mod test {
+    use serde::{Deserialize, Serialize};
+    use surrealdb::sql::Thing as RecordId;
+    use crate::testing::db;
+    use super::*;
+
+    #[derive(Debug, Table, Serialize, Deserialize, Clone, PartialEq)]
+    #[table(name = "user")]
+    struct User {
+        id: Option<RecordId>,
+        name: String,
+        age: u8,
+    }
+
+    struct UserFactory;
+
+    impl Factory<User> for UserFactory {
+        fn build(&self, sequence: u64) -> User {
+            User { id: None, name: format!("user {sequence}"), age: 20 }
+        }
+    }
+
+    #[test]
+    fn build() {
+        let users = Fixture::new(UserFactory).with(|u| u.age = 30);
+
+        assert_eq!(users.build(), User { id: None, name: "user 1".to_string(), age: 30 });
+        assert_eq!(users.build_with(|u| u.age = 40).age, 40);
+        assert_eq!(users.build_many(2).iter().map(|u| u.name.as_str()).collect::<Vec<_>>(), ["user 3", "user 4"]);
+        assert_eq!(users.sequence(), 4);
+
+        users.reset();
+        assert_eq!(users.build().name, "user 1");
+    }
+
+    #[tokio::test]
+    async fn create_and_clean() {
+        let db = db().await;
+
+        let users = Fixture::new(UserFactory);
+
+        users.create(&db).await.unwrap();
+
+        let created = with_clean_table::<User, _, _, _>(&db, || async {
+            assert!(User::get_all(&db).await.unwrap().is_empty());
+
+            users.create_many(&db, 3).await.unwrap()
+        }).await.unwrap();
+
+        assert_eq!(created.iter().map(|u| u.name.as_str()).collect::<Vec<_>>(), ["user 2", "user 3", "user 4"]);
+        assert!(created.iter().all(|u| u.id.is_some()));
+        assert!(User::get_all(&db).await.unwrap().is_empty());
+    }
+}
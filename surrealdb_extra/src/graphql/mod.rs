@@ -0,0 +1,253 @@
+//! Selecting only the fields of a GraphQL query
+//!
+//! `Projection::for_table` reads the selection set of an `async-graphql` field and keeps the fields of `T::meta()` the client asked for,
+//! `apply` adds them as `field` and the selected relations as `fetch` to a select builder.
+//! The GraphQL names are matched with the names of the fields and their camelCase form, so the default renaming of `async-graphql` works.
+//! Selections that are not fields of the table, e.g. `__typename` or computed resolvers, are left out, the id is always selected.
+//!
+//! - A `Link<T>` or `#[fetch]` field with a selection set is selected and fetched
+//! - A `#[field(flatten)]` field with a selection set selects only the nested fields, e.g. `address.city`
+//!
+//! # Example
+//!
+//! ```rust
+//! use async_graphql::{value, Context, EmptyMutation, EmptySubscription, Object, Schema, SimpleObject};
+//! use serde::{Serialize, Deserialize};
+//! use surrealdb::engine::any::{connect, Any};
+//! use surrealdb::sql::Thing as RecordId;
+//! use surrealdb::Surreal;
+//! use surrealdb_extra::graphql::Projection;
+//! use surrealdb_extra::query::statement::StatementBuilder;
+//! use surrealdb_extra::table::Table;
+//!
+//! #[derive(Table, SimpleObject, Serialize, Deserialize, Clone)]
+//! #[table(name = "user")]
+//! struct User {
+//!     #[graphql(skip)]
+//!     id: Option<RecordId>,
+//!     display_name: String,
+//!     email: Option<String>
+//! }
+//!
+//! struct Query;
+//!
+//! #[Object]
+//! impl Query {
+//!     async fn users(&self, ctx: &Context<'_>) -> Vec<User> {
+//!         let db = ctx.data_unchecked::<Surreal<Any>>();
+//!
+//!         // This becomes `SELECT id, display_name FROM user`
+//!         let select = Projection::for_table::<User>(&ctx.field()).apply(db.select_builder().what("user"));
+//!
+//!         select.execute_values().await.unwrap()
+//!     }
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let db = connect("mem://").await.unwrap();
+//!     db.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     db.query("CREATE user SET display_name = 'John', email = 'john@example.com'").await.unwrap();
+//!
+//!     let schema = Schema::build(Query, EmptyMutation, EmptySubscription).data(db).finish();
+//!
+//!     let res = schema.execute("{ users { displayName } }").await;
+//!     assert_eq!(res.data, value!({ "users": [{ "displayName": "John" }] }));
+//! }
+//! ```
+
+use async_graphql::SelectionField;
+use surrealdb::Connection;
+use surrealdb::sql::Value;
+use crate::query::parsing::fetch::ExtraFetch;
+use crate::query::parsing::field::ExtraField;
+use crate::query::parsing::idiom::ExtraIdiom;
+use crate::query::select::SelectBuilder;
+use crate::query::states::{FilledFields, FilledWhat};
+use crate::table::meta::FieldMeta;
+use crate::table::Table;
+
+/// The field paths and fetches of a selection set
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Projection {
+    pub fields: Vec<String>,
+    pub fetch: Vec<String>,
+}
+
+impl Projection {
+    /// The fields of the table in the selection set of `field`, usually `ctx.field()` of the resolver
+    pub fn for_table<T: Table>(field: &SelectionField<'_>) -> Self {
+        let meta = T::meta();
+
+        let mut projection = Self::default();
+        projection.push(meta.id.field.to_string());
+
+        for selected in field.selection_set() {
+            let Some(field) = find(meta.fields, selected.name()) else {
+                continue;
+            };
+
+            let has_selection = selected.selection_set().next().is_some();
+
+            if has_selection && (field.fetch || meta.relation(field.name).is_some()) {
+                projection.push(field.name.to_string());
+
+                if !projection.fetch.iter().any(|f| f == field.name) {
+                    projection.fetch.push(field.name.to_string());
+                }
+            } else if has_selection && !field.nested.is_empty() {
+                projection.push_nested(field.name, field.nested, &selected);
+            } else {
+                projection.push(field.name.to_string());
+            }
+        }
+
+        projection
+    }
+
+    fn push(&mut self, path: String) {
+        if !self.fields.contains(&path) {
+            self.fields.push(path);
+        }
+    }
+
+    fn push_nested(&mut self, prefix: &str, fields: &'static [FieldMeta], selection: &SelectionField<'_>) {
+        for selected in selection.selection_set() {
+            let Some(field) = find(fields, selected.name()) else {
+                continue;
+            };
+
+            let path = format!("{prefix}.{}", field.name);
+
+            if !field.nested.is_empty() && selected.selection_set().next().is_some() {
+                self.push_nested(&path, field.nested, &selected);
+            } else {
+                self.push(path);
+            }
+        }
+    }
+
+    /// Adds the fields and fetches to the select, the fields are appended to the fields it already has
+    pub fn apply<'r, Client, F, C>(&self, select: SelectBuilder<'r, Client, FilledWhat, F, C>) -> SelectBuilder<'r, Client, FilledWhat, FilledFields, C>
+        where Client: Connection
+    {
+        let mut paths = self.fields.iter();
+
+        // The id is always the first field
+        let first = paths.next().map(String::as_str).unwrap_or("id");
+        let mut select = select.field(path_field(first));
+
+        for path in paths {
+            select = select.field(path_field(path));
+        }
+
+        for fetch in &self.fetch {
+            select = select.fetch(ExtraFetch::from(fetch.as_str()));
+        }
+
+        select
+    }
+}
+
+/// The path as an idiom, so the names are not parsed as SurrealQL
+fn path_field(path: &str) -> ExtraField {
+    ExtraField::from(Value::Idiom(ExtraIdiom::from(path).0))
+}
+
+/// The field with the GraphQL name, either the same name or its camelCase form
+fn find(fields: &'static [FieldMeta], name: &str) -> Option<&'static FieldMeta> {
+    fields.iter().find(|f| f.name == name).or_else(|| fields.iter().find(|f| camel_case(f.name) == name))
+}
+
+fn camel_case(name: &str) -> String {
+    let mut camel = String::with_capacity(name.len());
+    let mut upper = false;
+
+    for c in name.trim_start_matches('_').chars() {
+        if c == '_' {
+            upper = true;
+        } else if upper {
+            camel.extend(c.to_uppercase());
+            upper = false;
+        } else {
+            camel.push(c);
+        }
+    }
+
+    camel
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::{Arc, Mutex};
+    use async_graphql::{Context, EmptyMutation, EmptySubscription, Object, Schema, SimpleObject};
+    use serde::{Deserialize, Serialize};
+    use surrealdb::engine::any::connect;
+    use surrealdb::sql::Thing as RecordId;
+    use crate::query::statement::StatementBuilder;
+    use crate::table::{Embedded, Table};
+    use super::*;
+
+    #[derive(Debug, Embedded, SimpleObject, Serialize, Deserialize, Clone)]
+    struct Address {
+        city: String,
+        zip_code: String,
+    }
+
+    #[derive(Debug, Table, SimpleObject, Serialize, Deserialize, Clone)]
+    #[table(name = "team")]
+    struct Team {
+        #[graphql(skip)]
+        id: Option<RecordId>,
+        name: String,
+    }
+
+    #[derive(Debug, Table, SimpleObject, Serialize, Deserialize, Clone)]
+    #[table(name = "user")]
+    struct User {
+        #[graphql(skip)]
+        id: Option<RecordId>,
+        display_name: String,
+        #[field(flatten)]
+        address: Address,
+        #[fetch]
+        team: Team,
+    }
+
+    struct Query;
+
+    #[Object]
+    impl Query {
+        async fn users(&self, ctx: &Context<'_>) -> Vec<User> {
+            *ctx.data_unchecked::<Arc<Mutex<Projection>>>().lock().unwrap() = Projection::for_table::<User>(&ctx.field());
+
+            vec![]
+        }
+    }
+
+    #[test]
+    fn camel_case_names() {
+        assert_eq!(camel_case("display_name"), "displayName");
+        assert_eq!(camel_case("name"), "name");
+    }
+
+    #[tokio::test]
+    async fn selection_projection() {
+        let state = Arc::new(Mutex::new(Projection::default()));
+        let schema = Schema::build(Query, EmptyMutation, EmptySubscription).data(state.clone()).finish();
+
+        let res = schema.execute("{ users { __typename displayName address { city } team { name } } }").await;
+        assert!(res.errors.is_empty(), "{:?}", res.errors);
+
+        let projection = state.lock().unwrap().clone();
+
+        assert_eq!(projection.fields, ["id", "display_name", "address.city", "team"]);
+        assert_eq!(projection.fetch, ["team"]);
+
+        let db = connect("mem://").await.unwrap();
+
+        let select = projection.apply(db.select_builder().what("user"));
+        assert_eq!(select.to_surql(), "SELECT id, display_name, address.city, team FROM user FETCH team");
+    }
+}
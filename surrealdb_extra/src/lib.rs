@@ -1,5 +1,8 @@
 #![cfg_attr(docsrs, feature(doc_cfg))]
 
+// The `Table` derive refers to `::surrealdb_extra`, this makes it work inside the crate as well
+extern crate self as surrealdb_extra;
+
 #[cfg(feature = "table")]
 pub mod table;
 
@@ -7,6 +10,109 @@ pub mod table;
 #[cfg(feature = "query")]
 pub mod query;
 
+#[cfg_attr(docsrs, doc(cfg(feature = "embedded")))]
+#[cfg(feature = "embedded")]
+pub mod embedded;
+
+#[cfg_attr(docsrs, doc(cfg(feature = "sync")))]
+#[cfg(feature = "sync")]
+pub mod sync;
+
+#[cfg_attr(docsrs, doc(cfg(feature = "migration")))]
+#[cfg(feature = "migration")]
+pub mod migration;
+
+#[cfg_attr(docsrs, doc(cfg(feature = "fake")))]
+#[cfg(feature = "fake")]
+pub mod fake;
+
+#[cfg_attr(docsrs, doc(cfg(feature = "sample")))]
+#[cfg(feature = "sample")]
+pub mod sample;
+
+#[cfg_attr(docsrs, doc(cfg(feature = "shard")))]
+#[cfg(feature = "shard")]
+pub mod shard;
+
+#[cfg_attr(docsrs, doc(cfg(feature = "pipeline")))]
+#[cfg(feature = "pipeline")]
+pub mod pipeline;
+
+#[cfg_attr(docsrs, doc(cfg(feature = "search")))]
+#[cfg(feature = "search")]
+pub mod search;
+
+#[cfg_attr(docsrs, doc(cfg(feature = "backup")))]
+#[cfg(feature = "backup")]
+pub mod backup;
+
+#[cfg_attr(docsrs, doc(cfg(feature = "auth")))]
+#[cfg(feature = "auth")]
+pub mod auth;
+
+#[cfg_attr(docsrs, doc(cfg(feature = "blocking")))]
+#[cfg(feature = "blocking")]
+pub mod blocking;
+
+#[cfg_attr(docsrs, doc(cfg(feature = "pool")))]
+#[cfg(feature = "pool")]
+pub mod pool;
+
+#[cfg_attr(docsrs, doc(cfg(feature = "audit")))]
+#[cfg(feature = "audit")]
+pub mod audit;
+
+#[cfg_attr(docsrs, doc(cfg(feature = "bench")))]
+#[cfg(feature = "bench")]
+pub mod bench;
+
+#[cfg_attr(docsrs, doc(cfg(feature = "fixtures")))]
+#[cfg(feature = "fixtures")]
+pub mod fixtures;
+
+#[cfg_attr(docsrs, doc(cfg(feature = "seed")))]
+#[cfg(feature = "seed")]
+pub mod seed;
+
+#[cfg_attr(docsrs, doc(cfg(feature = "filters")))]
+#[cfg(feature = "filters")]
+pub mod filters;
+
+#[cfg_attr(docsrs, doc(cfg(feature = "async-graphql")))]
+#[cfg(feature = "async-graphql")]
+pub mod graphql;
+
+#[cfg_attr(docsrs, doc(cfg(any(feature = "axum", feature = "actix-web"))))]
+#[cfg(any(feature = "axum", feature = "actix-web"))]
+pub mod extract;
+
+#[cfg_attr(docsrs, doc(cfg(feature = "events")))]
+#[cfg(feature = "events")]
+pub mod events;
+
+#[cfg_attr(docsrs, doc(cfg(feature = "outbox")))]
+#[cfg(feature = "outbox")]
+pub mod outbox;
+
+#[cfg(test)]
+pub(crate) mod testing;
+
+#[cfg_attr(docsrs, doc(cfg(feature = "query")))]
+#[cfg(feature = "query")]
+pub use ::paste::item;
+
 #[cfg_attr(docsrs, doc(cfg(feature = "query")))]
 #[cfg(feature = "query")]
-pub use ::paste::item;
\ No newline at end of file
+pub use ::surrealdb_extra_derive::cond;
+
+#[cfg_attr(docsrs, doc(cfg(feature = "schemars")))]
+#[cfg(feature = "schemars")]
+pub use ::schemars;
+
+#[doc(hidden)]
+#[cfg(feature = "derive")]
+pub use ::anyhow;
+
+#[doc(hidden)]
+#[cfg(feature = "derive")]
+pub use ::async_trait;
@@ -0,0 +1,11 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum MigrationError {
+    #[error("Migration `{0}` is registered more than once")]
+    DuplicateName(String),
+    #[error("Migration `{0}` is applied but not registered")]
+    Unknown(String),
+    #[error("{0}")]
+    Db(#[from] surrealdb::Error),
+}
@@ -0,0 +1,337 @@
+//! Migration runner
+//!
+//! Every migration has a unique name, migrations are applied in the order of their names.
+//! Applied migrations are recorded in the `_migrations` table.
+//!
+//! # Example
+//!
+//! ```rust
+//! use surrealdb::engine::any::connect;
+//! use surrealdb_extra::migration::{Migration, MigrationStep, Migrator};
+//!
+//! struct CreateUser;
+//!
+//! impl Migration for CreateUser {
+//!     fn name(&self) -> &str {
+//!         "0001_create_user"
+//!     }
+//!
+//!     fn up(&self) -> MigrationStep {
+//!         "DEFINE TABLE user SCHEMAFULL; DEFINE FIELD name ON user TYPE string".into()
+//!     }
+//!
+//!     fn down(&self) -> MigrationStep {
+//!         "REMOVE TABLE user".into()
+//!     }
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let db = connect("mem://").await.unwrap();
+//!     db.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     let migrator = Migrator::new(&db).migration(CreateUser);
+//!
+//!     let applied = migrator.migrate_up().await.unwrap();
+//!     assert_eq!(applied, vec!["0001_create_user".to_string()]);
+//!
+//!     let reverted = migrator.migrate_down(1).await.unwrap();
+//!     assert_eq!(reverted, vec!["0001_create_user".to_string()]);
+//! }
+//! ```
+
+pub mod err;
+
+use std::collections::HashSet;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use surrealdb::sql::Statement;
+use surrealdb::sql::statements::{CreateStatement, DefineStatement, DeleteStatement, RelateStatement, RemoveStatement, SelectStatement, UpdateStatement};
+use surrealdb::{Connection, Surreal};
+pub use crate::migration::err::MigrationError;
+
+pub const MIGRATION_TABLE: &str = "_migrations";
+
+/// SurrealQL that is run by a migration
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MigrationStep(pub String);
+
+impl From<&str> for MigrationStep {
+    fn from(value: &str) -> Self {
+        Self(value.to_string())
+    }
+}
+
+impl From<String> for MigrationStep {
+    fn from(value: String) -> Self {
+        Self(value)
+    }
+}
+
+impl From<Vec<Statement>> for MigrationStep {
+    fn from(value: Vec<Statement>) -> Self {
+        let value: Vec<String> = value.iter().map(|s| s.to_string()).collect();
+
+        Self(value.join(";\n"))
+    }
+}
+
+macro_rules! create_from_statement {
+    ($x:ty) => {
+        impl From<$x> for MigrationStep {
+            fn from(value: $x) -> Self {
+                Self(value.to_string())
+            }
+        }
+    };
+}
+
+create_from_statement!(Statement);
+create_from_statement!(SelectStatement);
+create_from_statement!(UpdateStatement);
+create_from_statement!(CreateStatement);
+create_from_statement!(RelateStatement);
+create_from_statement!(DeleteStatement);
+create_from_statement!(DefineStatement);
+create_from_statement!(RemoveStatement);
+
+/// The builders can be used by passing their `statement` field, e.g. `db.update_builder().what("user").set(...).statement.into()`
+pub trait Migration: Send + Sync {
+    /// Unique name of the migration, the name also decides the order
+    fn name(&self) -> &str;
+
+    fn up(&self) -> MigrationStep;
+
+    fn down(&self) -> MigrationStep;
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MigrationStatus {
+    pub name: String,
+    pub applied_at: Option<DateTime<Utc>>,
+}
+
+impl MigrationStatus {
+    pub fn is_applied(&self) -> bool {
+        self.applied_at.is_some()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Applied {
+    name: String,
+    applied_at: DateTime<Utc>,
+}
+
+pub struct Migrator<'r, C>
+    where C: Connection
+{
+    pub(crate) db: &'r Surreal<C>,
+    pub(crate) migrations: Vec<Box<dyn Migration>>,
+}
+
+impl<'r, C> Migrator<'r, C>
+    where C: Connection
+{
+    pub fn new(db: &'r Surreal<C>) -> Self {
+        Self {
+            db,
+            migrations: vec![],
+        }
+    }
+
+    pub fn migration(mut self, migration: impl Migration + 'static) -> Self {
+        self.migrations.push(Box::new(migration));
+        self.migrations.sort_by(|a, b| a.name().cmp(b.name()));
+
+        self
+    }
+
+    /// Every registered migration with the time it was applied
+    pub async fn status(&self) -> Result<Vec<MigrationStatus>> {
+        self.validate()?;
+
+        let applied = self.applied().await?;
+
+        let status = self.migrations.iter()
+            .map(|m| MigrationStatus {
+                name: m.name().to_string(),
+                applied_at: applied.iter().find(|a| a.name == m.name()).map(|a| a.applied_at),
+            })
+            .collect();
+
+        Ok(status)
+    }
+
+    /// Applies every pending migration, each migration runs inside its own transaction
+    ///
+    /// Returns the names of the applied migrations
+    pub async fn migrate_up(&self) -> Result<Vec<String>> {
+        let status = self.status().await?;
+
+        let mut names = vec![];
+        for (migration, status) in self.migrations.iter().zip(status) {
+            if status.is_applied() {
+                continue;
+            }
+
+            let query = format!(
+                "BEGIN TRANSACTION;\n{};\nCREATE type::thing('{MIGRATION_TABLE}', $name) SET name = $name, applied_at = time::now();\nCOMMIT TRANSACTION;",
+                migration.up().0
+            );
+
+            self.db.query(query)
+                .bind(("name", migration.name().to_string()))
+                .await.map_err(MigrationError::from)?
+                .check().map_err(MigrationError::from)?;
+
+            names.push(status.name);
+        }
+
+        Ok(names)
+    }
+
+    /// Reverts the last `steps` applied migrations, newest first
+    ///
+    /// Returns the names of the reverted migrations
+    pub async fn migrate_down(&self, steps: usize) -> Result<Vec<String>> {
+        self.validate()?;
+
+        let mut applied = self.applied().await?;
+        applied.sort_by(|a, b| b.name.cmp(&a.name));
+
+        let mut names = vec![];
+        for applied in applied.into_iter().take(steps) {
+            let migration = self.migrations.iter()
+                .find(|m| m.name() == applied.name)
+                .ok_or(MigrationError::Unknown(applied.name.clone()))?;
+
+            let query = format!(
+                "BEGIN TRANSACTION;\n{};\nDELETE type::thing('{MIGRATION_TABLE}', $name);\nCOMMIT TRANSACTION;",
+                migration.down().0
+            );
+
+            self.db.query(query)
+                .bind(("name", applied.name.clone()))
+                .await.map_err(MigrationError::from)?
+                .check().map_err(MigrationError::from)?;
+
+            names.push(applied.name);
+        }
+
+        Ok(names)
+    }
+
+    fn validate(&self) -> Result<()> {
+        let mut names = HashSet::new();
+
+        for m in &self.migrations {
+            if !names.insert(m.name()) {
+                return Err(MigrationError::DuplicateName(m.name().to_string()).into());
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn applied(&self) -> Result<Vec<Applied>> {
+        let mut res = self.db.query(format!("SELECT name, applied_at FROM {MIGRATION_TABLE}"))
+            .await.map_err(MigrationError::from)?;
+
+        let applied: Vec<Applied> = res.take(0).map_err(MigrationError::from)?;
+
+        Ok(applied)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::testing::db;
+    use super::*;
+
+    struct Named(&'static str, &'static str, &'static str);
+
+    impl Migration for Named {
+        fn name(&self) -> &str {
+            self.0
+        }
+
+        fn up(&self) -> MigrationStep {
+            self.1.into()
+        }
+
+        fn down(&self) -> MigrationStep {
+            self.2.into()
+        }
+    }
+
+    #[tokio::test]
+    async fn up_in_order() {
+        let db = db().await;
+
+        let migrator = Migrator::new(&db)
+            .migration(Named("0002_field", "DEFINE FIELD name ON test TYPE string", "REMOVE FIELD name ON test"))
+            .migration(Named("0001_table", "DEFINE TABLE test SCHEMAFULL", "REMOVE TABLE test"));
+
+        let applied = migrator.migrate_up().await.unwrap();
+
+        assert_eq!(applied, vec!["0001_table".to_string(), "0002_field".to_string()]);
+
+        assert!(migrator.migrate_up().await.unwrap().is_empty());
+
+        let status = migrator.status().await.unwrap();
+
+        assert!(status.iter().all(|s| s.is_applied()));
+    }
+
+    #[tokio::test]
+    async fn down_newest_first() {
+        let db = db().await;
+
+        let migrator = Migrator::new(&db)
+            .migration(Named("0001_table", "DEFINE TABLE test SCHEMAFULL", "REMOVE TABLE test"))
+            .migration(Named("0002_field", "DEFINE FIELD name ON test TYPE string", "REMOVE FIELD name ON test"));
+
+        migrator.migrate_up().await.unwrap();
+
+        let reverted = migrator.migrate_down(1).await.unwrap();
+
+        assert_eq!(reverted, vec!["0002_field".to_string()]);
+
+        let status = migrator.status().await.unwrap();
+
+        assert!(status[0].is_applied());
+        assert!(!status[1].is_applied());
+    }
+
+    #[tokio::test]
+    async fn failed_migration_is_not_recorded() {
+        let db = db().await;
+
+        let migrator = Migrator::new(&db)
+            .migration(Named("0001_broken", "THROW 'broken'", ""));
+
+        assert!(migrator.migrate_up().await.is_err());
+
+        assert!(!migrator.status().await.unwrap()[0].is_applied());
+    }
+
+    #[tokio::test]
+    async fn duplicate_name() {
+        let db = db().await;
+
+        let migrator = Migrator::new(&db)
+            .migration(Named("0001", "", ""))
+            .migration(Named("0001", "", ""));
+
+        assert!(migrator.status().await.is_err());
+    }
+
+    #[test]
+    fn step_from_statement() {
+        let step = MigrationStep::from(SelectStatement::default());
+
+        assert!(step.0.starts_with("SELECT"));
+    }
+}
@@ -0,0 +1,9 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum OutboxError {
+    #[error("Could not serialize the payload: {0}")]
+    Payload(String),
+    #[error("{0}")]
+    Db(#[from] surrealdb::Error),
+}
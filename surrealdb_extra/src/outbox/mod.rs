@@ -0,0 +1,383 @@
+//! Outbox messages written together with the records
+//!
+//! `Table::create_with_outbox` creates the record and an `OutboxMessage` in the outbox table, `outbox` by default, in one statement,
+//! so the message exists exactly when the record was written.
+//! A publisher reads the messages with `Outbox::poll` or `Outbox::messages`, sends them to the broker and calls `ack`,
+//! or `retry` when sending failed.
+//!
+//! Polled messages are leased for `lease`, other pollers don't get them until the lease ran out, so a message that is
+//! neither acked nor retried, e.g. because the publisher crashed, is delivered again. Publishing is at least once.
+//! `retry` delays the message by `retry_delay`, doubled after every attempt, after `max_attempts` the message is dead and not polled anymore.
+//!
+//! # Example
+//!
+//! ```rust
+//! use serde::{Serialize, Deserialize};
+//! use surrealdb::engine::any::connect;
+//! use surrealdb::sql::Thing as RecordId;
+//! use surrealdb_extra::outbox::{Outbox, OutboxEvent};
+//! use surrealdb_extra::table::Table;
+//!
+//! #[derive(Table, Serialize, Deserialize, Clone)]
+//! #[table(name = "user")]
+//! struct User {
+//!     id: Option<RecordId>,
+//!     name: String
+//! }
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct UserCreated {
+//!     name: String
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let db = connect("mem://").await.unwrap();
+//!     db.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     let event = OutboxEvent::new("user.created", UserCreated { name: "John".to_string() }).unwrap();
+//!     User { id: None, name: "John".to_string() }.create_with_outbox(&db, event).await.unwrap();
+//!
+//!     let outbox = Outbox::new();
+//!
+//!     let messages = outbox.poll(&db, 10).await.unwrap();
+//!     assert_eq!(messages[0].topic, "user.created");
+//!     assert_eq!(messages[0].payload::<UserCreated>().unwrap().name, "John");
+//!
+//!     // After the message was sent to the broker
+//!     outbox.ack(&db, &messages[0]).await.unwrap();
+//!
+//!     assert!(outbox.poll(&db, 10).await.unwrap().is_empty());
+//! }
+//! ```
+
+pub mod err;
+
+use std::time::Duration;
+use anyhow::Result;
+use futures::stream::{self, BoxStream};
+use futures::{StreamExt, TryStreamExt};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use surrealdb::sql::{from_value, to_value, Datetime, Thing, Value};
+use surrealdb::{Connection, Surreal};
+use crate::table::id;
+use crate::table::write::write_content;
+use crate::table::{Table, TableError};
+pub use crate::outbox::err::OutboxError;
+
+pub const DEFAULT_TABLE: &str = "outbox";
+
+pub const DEFAULT_LEASE: Duration = Duration::from_secs(30);
+
+pub const DEFAULT_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+pub const MAX_RETRY_DELAY: Duration = Duration::from_secs(60 * 60);
+
+pub const DEFAULT_MAX_ATTEMPTS: u32 = 10;
+
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+const CREATE: &str = "RETURN {
+    LET $after = (CREATE ONLY type::table($table) CONTENT $content);
+    CREATE type::table($outbox) CONTENT { topic: $topic, payload: $payload, record: $after.id, attempts: 0, dead: false, created_at: time::now(), available_at: time::now() };
+    RETURN $after;
+}";
+
+const POLL: &str = "RETURN {
+    LET $ids = (SELECT id, created_at FROM type::table($outbox) WHERE published_at = NONE AND dead = false AND available_at <= time::now() ORDER BY created_at LIMIT $limit).id;
+    RETURN (UPDATE $ids SET available_at = time::now() + $lease RETURN AFTER);
+}";
+
+/// The topic and payload of a message
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutboxEvent {
+    pub topic: String,
+    pub payload: Value,
+}
+
+impl OutboxEvent {
+    pub fn new(topic: impl Into<String>, payload: impl Serialize + 'static) -> Result<Self> {
+        Ok(Self {
+            topic: topic.into(),
+            payload: to_value(payload).map_err(|e| OutboxError::Payload(e.to_string()))?,
+        })
+    }
+}
+
+/// A message of the outbox table
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OutboxMessage {
+    pub id: Thing,
+    pub topic: String,
+    /// Read separately from the other fields, a `Value` can't be deserialized from a response
+    #[serde(default, skip_deserializing)]
+    pub payload: Value,
+    /// The record that was written with the message
+    pub record: Option<Thing>,
+    pub attempts: u32,
+    pub dead: bool,
+    pub last_error: Option<String>,
+    pub created_at: Datetime,
+    /// The message isn't polled before this time
+    pub available_at: Datetime,
+    pub published_at: Option<Datetime>,
+}
+
+impl OutboxMessage {
+    fn from_values(value: surrealdb::Value) -> Result<Vec<Self>> {
+        let Value::Array(messages) = value.into_inner() else {
+            return Ok(vec![]);
+        };
+
+        messages.into_iter().map(|message| {
+            let Value::Object(mut message) = message else {
+                return Err(OutboxError::Payload(format!("expected a message, found {message}")).into());
+            };
+
+            let payload = message.remove("payload").unwrap_or_default();
+            let mut message: Self = from_value(Value::Object(message)).map_err(|e| OutboxError::Db(e.into()))?;
+            message.payload = payload;
+
+            Ok(message)
+        }).collect()
+    }
+
+    pub fn payload<T: DeserializeOwned>(&self) -> Result<T> {
+        Ok(from_value(self.payload.clone()).map_err(|e| OutboxError::Payload(e.to_string()))?)
+    }
+}
+
+/// The outbox table and how its messages are retried
+#[derive(Debug, Clone)]
+pub struct Outbox {
+    pub table: String,
+    pub lease: Duration,
+    pub retry_delay: Duration,
+    pub max_attempts: u32,
+    pub poll_interval: Duration,
+}
+
+impl Default for Outbox {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Outbox {
+    pub fn new() -> Self {
+        Self {
+            table: DEFAULT_TABLE.to_string(),
+            lease: DEFAULT_LEASE,
+            retry_delay: DEFAULT_RETRY_DELAY,
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            poll_interval: DEFAULT_POLL_INTERVAL,
+        }
+    }
+
+    pub fn table(mut self, table: impl Into<String>) -> Self {
+        self.table = table.into();
+
+        self
+    }
+
+    /// How long a polled message is hidden from other pollers
+    pub fn lease(mut self, lease: Duration) -> Self {
+        self.lease = lease;
+
+        self
+    }
+
+    /// The delay of the first retry, doubled after every attempt up to `MAX_RETRY_DELAY`
+    pub fn retry_delay(mut self, retry_delay: Duration) -> Self {
+        self.retry_delay = retry_delay;
+
+        self
+    }
+
+    pub fn max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+
+        self
+    }
+
+    /// How long `messages` waits when there are no messages
+    pub fn poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+
+        self
+    }
+
+    /// Creates the record and the message in one statement, like `Table::create` with its hooks
+    pub async fn create<T: Table, C: Connection>(&self, db: &Surreal<C>, mut t: T, event: OutboxEvent) -> Result<Option<T>> {
+        t.before_create(db).await?;
+        t.validate_record().map_err(TableError::from)?;
+        id::assign_id(&mut t, db).await?;
+
+        let t: Option<T> = db.query(CREATE)
+            .bind(("table", T::TABLE_NAME))
+            .bind(("content", write_content(t)?))
+            .bind(("outbox", self.table.clone()))
+            .bind(("topic", event.topic))
+            .bind(("payload", event.payload))
+            .await.map_err(OutboxError::from)?
+            .take(0).map_err(OutboxError::from)?;
+
+        if let Some(t) = &t {
+            t.after_create(db).await?;
+        }
+
+        Ok(t)
+    }
+
+    /// Leases up to `limit` messages that are not published, oldest first
+    pub async fn poll<C: Connection>(&self, db: &Surreal<C>, limit: u32) -> Result<Vec<OutboxMessage>> {
+        let messages: surrealdb::Value = db.query(POLL)
+            .bind(("outbox", self.table.clone()))
+            .bind(("limit", limit))
+            .bind(("lease", surrealdb::sql::Duration::from(self.lease)))
+            .await.map_err(OutboxError::from)?
+            .take(0).map_err(OutboxError::from)?;
+
+        let mut messages = OutboxMessage::from_values(messages)?;
+
+        messages.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+
+        Ok(messages)
+    }
+
+    /// Marks the message as published
+    pub async fn ack<C: Connection>(&self, db: &Surreal<C>, message: &OutboxMessage) -> Result<()> {
+        db.query("UPDATE $id SET published_at = time::now()")
+            .bind(("id", message.id.clone()))
+            .await.map_err(OutboxError::from)?
+            .check().map_err(OutboxError::from)?;
+
+        Ok(())
+    }
+
+    /// Delays the message after a failed attempt, it is dead after `max_attempts`
+    pub async fn retry<C: Connection>(&self, db: &Surreal<C>, message: &OutboxMessage, error: impl Into<String>) -> Result<()> {
+        let attempts = message.attempts + 1;
+
+        db.query("UPDATE $id SET attempts = $attempts, dead = $dead, last_error = $error, available_at = time::now() + $delay")
+            .bind(("id", message.id.clone()))
+            .bind(("attempts", attempts))
+            .bind(("dead", attempts >= self.max_attempts))
+            .bind(("error", error.into()))
+            .bind(("delay", surrealdb::sql::Duration::from(self.delay(attempts))))
+            .await.map_err(OutboxError::from)?
+            .check().map_err(OutboxError::from)?;
+
+        Ok(())
+    }
+
+    /// The dead messages, oldest first
+    pub async fn dead<C: Connection>(&self, db: &Surreal<C>) -> Result<Vec<OutboxMessage>> {
+        let messages: surrealdb::Value = db.query("SELECT * FROM type::table($outbox) WHERE dead = true ORDER BY created_at")
+            .bind(("outbox", self.table.clone()))
+            .await.map_err(OutboxError::from)?
+            .take(0).map_err(OutboxError::from)?;
+
+        OutboxMessage::from_values(messages)
+    }
+
+    /// Polls the messages `limit` at a time and waits `poll_interval` when there are none, the stream never ends on its own
+    pub fn messages<'r, C: Connection>(&self, db: &'r Surreal<C>, limit: u32) -> BoxStream<'r, Result<OutboxMessage>> {
+        stream::try_unfold(self.clone(), move |outbox| next_batch(outbox, db, limit))
+            .map_ok(|messages| stream::iter(messages.into_iter().map(Ok)))
+            .try_flatten()
+            .boxed()
+    }
+
+    fn delay(&self, attempts: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempts.saturating_sub(1));
+
+        self.retry_delay.saturating_mul(factor).min(MAX_RETRY_DELAY)
+    }
+}
+
+async fn next_batch<C: Connection>(outbox: Outbox, db: &Surreal<C>, limit: u32) -> Result<Option<(Vec<OutboxMessage>, Outbox)>> {
+    loop {
+        let messages = outbox.poll(db, limit).await?;
+
+        if !messages.is_empty() {
+            return Ok(Some((messages, outbox)));
+        }
+
+        tokio::time::sleep(outbox.poll_interval).await;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use surrealdb::sql::Thing as RecordId;
+    use crate::testing::db;
+    use super::*;
+
+    #[derive(Debug, Table, Serialize, Deserialize, Clone, PartialEq)]
+    #[table(name = "user")]
+    struct User {
+        id: Option<RecordId>,
+        name: String,
+    }
+
+    #[test]
+    fn retry_delays() {
+        let outbox = Outbox::new().retry_delay(Duration::from_secs(1));
+
+        assert_eq!(outbox.delay(1), Duration::from_secs(1));
+        assert_eq!(outbox.delay(3), Duration::from_secs(4));
+        assert_eq!(outbox.delay(100), MAX_RETRY_DELAY);
+    }
+
+    #[tokio::test]
+    async fn outbox_messages() {
+        let db = db().await;
+
+        let outbox = Outbox::new().retry_delay(Duration::ZERO).max_attempts(2);
+
+        let user = User { id: None, name: "a".to_string() }
+            .create_with_outbox(&db, OutboxEvent::new("user.created", "a").unwrap())
+            .await.unwrap().unwrap();
+
+        let messages = outbox.poll(&db, 10).await.unwrap();
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].record, user.id);
+        assert_eq!(messages[0].payload::<String>().unwrap(), "a");
+
+        // Leased by the first poll
+        assert!(outbox.poll(&db, 10).await.unwrap().is_empty());
+
+        outbox.retry(&db, &messages[0], "broker down").await.unwrap();
+
+        let retried = outbox.poll(&db, 10).await.unwrap();
+        assert_eq!((retried[0].attempts, retried[0].last_error.as_deref()), (1, Some("broker down")));
+
+        outbox.retry(&db, &retried[0], "broker down").await.unwrap();
+
+        assert!(outbox.poll(&db, 10).await.unwrap().is_empty());
+        assert_eq!(outbox.dead(&db).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn stream_and_ack() {
+        let db = db().await;
+
+        let outbox = Outbox::new().table("messages").poll_interval(Duration::from_millis(10));
+
+        for name in ["a", "b"] {
+            outbox.create(&db, User { id: None, name: name.to_string() }, OutboxEvent::new("user.created", name).unwrap()).await.unwrap();
+        }
+
+        let messages: Vec<OutboxMessage> = outbox.messages(&db, 1).take(2).try_collect().await.unwrap();
+        assert_eq!(messages.iter().map(|m| m.payload::<String>().unwrap()).collect::<Vec<_>>(), ["a", "b"]);
+
+        for message in &messages {
+            outbox.ack(&db, message).await.unwrap();
+        }
+
+        assert!(outbox.poll(&db, 10).await.unwrap().is_empty());
+        assert_eq!(User::get_all(&db).await.unwrap().len(), 2);
+    }
+}
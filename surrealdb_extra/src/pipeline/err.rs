@@ -0,0 +1,11 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum PipelineError {
+    #[error("The pipeline queue is full")]
+    Full,
+    #[error("The pipeline is shut down")]
+    Closed,
+    #[error("{0}")]
+    Db(#[from] surrealdb::Error),
+}
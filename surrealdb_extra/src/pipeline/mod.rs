@@ -0,0 +1,457 @@
+//! Batched writes with back-pressure
+//!
+//! `WritePipeline` queues records in a bounded channel and a background task writes them in batches.
+//! A batch is written when it is full or when the flush interval passed since its first record,
+//! with at most `concurrency` batches written at the same time.
+//!
+//! When the writes can't keep up the queue fills up, `send` then waits for room and `try_send` returns `PipelineError::Full`,
+//! so producers slow down instead of growing memory.
+//!
+//! Records are written like `Table::create` writes them, validated with `validate_record`, without the skipped and readonly fields
+//! and with the global config or the deadline applied to the `INSERT`.
+//! A record that fails validation and the records of a batch that fails to write are kept with the error,
+//! `take_failed` returns them so they can be sent again or logged, and `shutdown` returns the ones that were not taken.
+//!
+//! # Example
+//!
+//! ```rust
+//! use std::time::Duration;
+//! use serde::{Serialize, Deserialize};
+//! use surrealdb::engine::any::connect;
+//! use surrealdb::sql::Thing as RecordId;
+//! use surrealdb_extra::table::Table;
+//! use surrealdb_extra::pipeline::{PipelineConfig, WritePipeline};
+//!
+//! #[derive(Table, Serialize, Deserialize)]
+//! #[table(name = "event")]
+//! struct Event {
+//!     id: Option<RecordId>,
+//!     value: f64
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let db = connect("mem://").await.unwrap();
+//!     db.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     let config = PipelineConfig::default().batch_size(500).flush_interval(Duration::from_millis(50));
+//!     let pipeline = WritePipeline::<Event>::spawn(db.clone(), config);
+//!
+//!     for i in 0..1000 {
+//!         pipeline.send(Event { id: None, value: i as f64 }).await.unwrap();
+//!     }
+//!
+//!     let (stats, failed) = pipeline.shutdown().await;
+//!     assert_eq!(stats.written, 1000);
+//!     assert!(failed.is_empty());
+//! }
+//! ```
+
+pub mod err;
+
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use anyhow::Result;
+use surrealdb::{Connection, Surreal};
+use tokio::sync::{mpsc, Semaphore};
+use tokio::sync::mpsc::error::TrySendError;
+use tokio::task::{JoinHandle, JoinSet};
+use tokio::time::timeout_at;
+use surrealdb::sql::Value;
+use crate::table::{config, Table, TableError};
+use crate::table::write::write_shared_content;
+pub use crate::pipeline::err::PipelineError;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PipelineConfig {
+    pub batch_size: usize,
+    pub flush_interval: Duration,
+    pub queue_capacity: usize,
+    pub concurrency: usize,
+}
+
+impl Default for PipelineConfig {
+    fn default() -> Self {
+        Self {
+            batch_size: 100,
+            flush_interval: Duration::from_millis(100),
+            queue_capacity: 10_000,
+            concurrency: 4,
+        }
+    }
+}
+
+impl PipelineConfig {
+    pub fn batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+
+        self
+    }
+
+    pub fn flush_interval(mut self, flush_interval: Duration) -> Self {
+        self.flush_interval = flush_interval;
+
+        self
+    }
+
+    /// Number of queued records at which producers get back-pressure
+    pub fn queue_capacity(mut self, queue_capacity: usize) -> Self {
+        self.queue_capacity = queue_capacity.max(1);
+
+        self
+    }
+
+    /// Number of batches written at the same time
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+
+        self
+    }
+}
+
+#[derive(Debug, Default)]
+struct Metrics {
+    written: AtomicU64,
+    failed: AtomicU64,
+    batches: AtomicU64,
+    last_flush_micros: AtomicU64,
+    total_flush_micros: AtomicU64,
+}
+
+impl Metrics {
+    fn stats(&self, queue_depth: usize) -> PipelineStats {
+        let batches = self.batches.load(Ordering::Relaxed);
+        let total = self.total_flush_micros.load(Ordering::Relaxed);
+
+        PipelineStats {
+            queue_depth,
+            written: self.written.load(Ordering::Relaxed),
+            failed: self.failed.load(Ordering::Relaxed),
+            batches,
+            last_flush_latency: Duration::from_micros(self.last_flush_micros.load(Ordering::Relaxed)),
+            avg_flush_latency: Duration::from_micros(total.checked_div(batches).unwrap_or_default()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PipelineStats {
+    /// Records waiting in the queue
+    pub queue_depth: usize,
+    pub written: u64,
+    /// Records of batches that failed to write
+    pub failed: u64,
+    pub batches: u64,
+    pub last_flush_latency: Duration,
+    pub avg_flush_latency: Duration,
+}
+
+/// The records of a batch that failed to write, or a record that failed validation
+#[derive(Debug)]
+pub struct FailedBatch<T> {
+    pub records: Vec<T>,
+    pub error: anyhow::Error,
+}
+
+type Failed<T> = Arc<Mutex<Vec<FailedBatch<T>>>>;
+
+pub struct WritePipeline<T: Table> {
+    sender: mpsc::Sender<T>,
+    metrics: Arc<Metrics>,
+    failed: Failed<T>,
+    handle: JoinHandle<()>,
+}
+
+impl<T: Table> WritePipeline<T> {
+    /// Starts the background task on the current tokio runtime
+    pub fn spawn<C: Connection>(db: Surreal<C>, config: PipelineConfig) -> Self {
+        let (sender, receiver) = mpsc::channel(config.queue_capacity);
+        let metrics = Arc::new(Metrics::default());
+        let failed = Failed::default();
+
+        let handle = tokio::spawn(run(db, config, receiver, metrics.clone(), failed.clone()));
+
+        Self {
+            sender,
+            metrics,
+            failed,
+            handle,
+        }
+    }
+
+    /// A sender for producers on other tasks
+    pub fn sender(&self) -> mpsc::Sender<T> {
+        self.sender.clone()
+    }
+
+    /// Queues the record, waits while the queue is full
+    pub async fn send(&self, t: T) -> Result<()> {
+        self.sender.send(t).await.map_err(|_| PipelineError::Closed)?;
+
+        Ok(())
+    }
+
+    /// Queues the record or returns `PipelineError::Full` right away
+    pub fn try_send(&self, t: T) -> Result<()> {
+        self.sender.try_send(t).map_err(|e| match e {
+            TrySendError::Full(_) => PipelineError::Full,
+            TrySendError::Closed(_) => PipelineError::Closed,
+        })?;
+
+        Ok(())
+    }
+
+    pub fn stats(&self) -> PipelineStats {
+        self.metrics.stats(self.sender.max_capacity() - self.sender.capacity())
+    }
+
+    /// The batches that failed to write since the last call, e.g. to send the records again
+    pub fn take_failed(&self) -> Vec<FailedBatch<T>> {
+        std::mem::take(&mut *self.failed.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+
+    /// Writes everything that is queued and stops the background task, returns the batches that failed and were not taken.
+    /// Senders returned by `sender` have to be dropped first or this waits for them
+    pub async fn shutdown(self) -> (PipelineStats, Vec<FailedBatch<T>>) {
+        let Self { sender, metrics, failed, handle } = self;
+
+        drop(sender);
+        let _ = handle.await;
+
+        let failed = std::mem::take(&mut *failed.lock().unwrap_or_else(|e| e.into_inner()));
+
+        (metrics.stats(0), failed)
+    }
+}
+
+async fn run<T: Table, C: Connection>(db: Surreal<C>, config: PipelineConfig, mut receiver: mpsc::Receiver<T>, metrics: Arc<Metrics>, failed: Failed<T>) {
+    let semaphore = Arc::new(Semaphore::new(config.concurrency));
+    let mut writes = JoinSet::new();
+
+    while let Some(first) = receiver.recv().await {
+        let deadline = tokio::time::Instant::now() + config.flush_interval;
+
+        let mut batch = Vec::with_capacity(config.batch_size);
+        batch.push(first);
+
+        while batch.len() < config.batch_size {
+            match timeout_at(deadline, receiver.recv()).await {
+                Ok(Some(t)) => batch.push(t),
+                Ok(None) | Err(_) => break,
+            }
+        }
+
+        let Ok(permit) = semaphore.clone().acquire_owned().await else {
+            break;
+        };
+
+        let db = db.clone();
+        let metrics = metrics.clone();
+        let failed = failed.clone();
+
+        writes.spawn(async move {
+            write(&db, batch, &metrics, &failed).await;
+
+            drop(permit);
+        });
+
+        while writes.try_join_next().is_some() {}
+    }
+
+    while writes.join_next().await.is_some() {}
+}
+
+async fn write<T: Table, C: Connection>(db: &Surreal<C>, batch: Vec<T>, metrics: &Metrics, failed: &Mutex<Vec<FailedBatch<T>>>) {
+    let started = Instant::now();
+
+    let mut records = Vec::with_capacity(batch.len());
+    let mut contents = Vec::with_capacity(batch.len());
+    let mut invalid = Vec::new();
+
+    // The records are shared with their content, so they are only kept and not copied for a failed batch
+    for record in batch {
+        let record = Arc::new(record);
+
+        let content = match record.validate_record() {
+            Ok(()) => write_shared_content(record.clone()),
+            Err(e) => Err(TableError::from(e).into()),
+        };
+
+        match content {
+            Ok(content) => {
+                records.push(record);
+                contents.push(content);
+            }
+            Err(error) => invalid.push(FailedBatch { records: unshare(vec![record]), error }),
+        }
+    }
+
+    let res = match contents.is_empty() {
+        true => Ok(()),
+        false => insert::<T, C>(db, contents).await,
+    };
+
+    let micros = started.elapsed().as_micros() as u64;
+    let len = records.len() as u64;
+
+    metrics.failed.fetch_add(invalid.len() as u64, Ordering::Relaxed);
+
+    let mut failed = failed.lock().unwrap_or_else(|e| e.into_inner());
+    failed.extend(invalid);
+
+    match res {
+        Ok(()) => {
+            metrics.written.fetch_add(len, Ordering::Relaxed);
+        }
+        Err(error) => {
+            metrics.failed.fetch_add(len, Ordering::Relaxed);
+            failed.push(FailedBatch { records: unshare(records), error: error.into() });
+        }
+    }
+
+    metrics.batches.fetch_add(1, Ordering::Relaxed);
+    metrics.last_flush_micros.store(micros, Ordering::Relaxed);
+    metrics.total_flush_micros.fetch_add(micros, Ordering::Relaxed);
+}
+
+async fn insert<T: Table, C: Connection>(db: &Surreal<C>, contents: Vec<Value>) -> surrealdb::Result<()> {
+    db.query(config::insert(T::TABLE_NAME, contents)).await?.check()?;
+
+    Ok(())
+}
+
+/// The contents are written or dropped, so the records are not shared anymore
+fn unshare<T>(records: Vec<Arc<T>>) -> Vec<T> {
+    records.into_iter().filter_map(Arc::into_inner).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use serde::{Deserialize, Serialize};
+    use surrealdb::sql::Thing as RecordId;
+    use crate::table::validate::{Validate, ValidationError};
+    use crate::testing::db;
+    use super::*;
+
+    #[derive(Debug, Table, Serialize, Deserialize, Clone, PartialEq)]
+    #[table(name = "event")]
+    struct Event {
+        id: Option<RecordId>,
+        n: i64,
+    }
+
+    #[derive(Debug, Table, Serialize, Deserialize, PartialEq)]
+    #[table(name = "reading", validate)]
+    struct Reading {
+        id: Option<RecordId>,
+        n: i64,
+        #[field(readonly)]
+        #[serde(default)]
+        owner: Option<String>,
+    }
+
+    impl Validate for Reading {
+        fn validate(&self) -> Result<(), ValidationError> {
+            match self.n >= 0 {
+                true => Ok(()),
+                false => Err(ValidationError::default().field("n", "must not be negative")),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn batches_and_flushes() {
+        let db = db().await;
+
+        let pipeline = WritePipeline::<Event>::spawn(db.clone(), PipelineConfig::default().batch_size(10).flush_interval(Duration::from_millis(10)));
+
+        for n in 0..25 {
+            pipeline.send(Event { id: None, n }).await.unwrap();
+        }
+
+        let (stats, failed) = pipeline.shutdown().await;
+
+        assert_eq!(stats.written, 25);
+        assert_eq!(stats.failed, 0);
+        assert!(failed.is_empty());
+        assert!(stats.batches >= 3);
+        assert_eq!(Event::get_all(&db).await.unwrap().len(), 25);
+    }
+
+    #[tokio::test]
+    async fn keeps_failed_batches() {
+        let db = db().await;
+
+        db.query("DEFINE TABLE event SCHEMAFULL; DEFINE FIELD n ON event TYPE int ASSERT $value >= 0").await.unwrap().check().unwrap();
+
+        let pipeline = WritePipeline::<Event>::spawn(db.clone(), PipelineConfig::default().batch_size(5).flush_interval(Duration::from_millis(10)));
+
+        for n in [1, 2, -3, 4, 5] {
+            pipeline.send(Event { id: None, n }).await.unwrap();
+        }
+
+        while pipeline.stats().batches == 0 {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+
+        let failed = pipeline.take_failed();
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].records.iter().map(|e| e.n).collect::<Vec<_>>(), [1, 2, -3, 4, 5]);
+        assert!(pipeline.take_failed().is_empty());
+
+        for event in failed.into_iter().flat_map(|f| f.records) {
+            pipeline.send(Event { n: event.n.abs(), ..event }).await.unwrap();
+        }
+
+        let (stats, failed) = pipeline.shutdown().await;
+
+        assert_eq!((stats.written, stats.failed), (5, 5));
+        assert!(failed.is_empty());
+        assert_eq!(Event::get_all(&db).await.unwrap().len(), 5);
+    }
+
+    #[tokio::test]
+    async fn writes_like_create() {
+        let db = db().await;
+
+        let pipeline = WritePipeline::<Reading>::spawn(db.clone(), PipelineConfig::default().batch_size(3).flush_interval(Duration::from_millis(10)));
+
+        for n in [1, -1, 2] {
+            pipeline.send(Reading { id: None, n, owner: Some("owner".to_string()) }).await.unwrap();
+        }
+
+        let (stats, failed) = pipeline.shutdown().await;
+
+        assert_eq!((stats.written, stats.failed), (2, 1));
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].records[0].n, -1);
+        assert!(matches!(failed[0].error.downcast_ref::<TableError>(), Some(TableError::Validation(..))));
+
+        let readings = Reading::get_all(&db).await.unwrap();
+
+        assert_eq!(readings.len(), 2);
+        assert!(readings.iter().all(|r| r.owner.is_none()));
+    }
+
+    #[tokio::test]
+    async fn back_pressure() {
+        let db = db().await;
+
+        let config = PipelineConfig::default().queue_capacity(2).batch_size(100).flush_interval(Duration::from_secs(60)).concurrency(1);
+        let pipeline = WritePipeline::<Event>::spawn(db.clone(), config);
+
+        let mut full = false;
+
+        for n in 0..10 {
+            if let Err(e) = pipeline.try_send(Event { id: None, n }) {
+                assert!(matches!(e.downcast_ref::<PipelineError>(), Some(PipelineError::Full)));
+                full = true;
+                break;
+            }
+        }
+
+        assert!(full);
+        assert!(pipeline.stats().queue_depth <= 2);
+    }
+}
@@ -0,0 +1,11 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum PoolError {
+    #[error("A pool needs at least one connection")]
+    Empty,
+    #[error("The pool was created from connections, it can only reconnect when it was created with `SurrealPool::connect`")]
+    NoConnect,
+    #[error("{0}")]
+    Db(#[from] surrealdb::Error),
+}
@@ -0,0 +1,404 @@
+//! Pool of connections for high throughput deployments
+//!
+//! `SurrealPool` spreads the requests over several connections, e.g. for the HTTP engine where every connection
+//! handles one request at a time. Every checkout takes the healthy connection with the least checked out requests.
+//!
+//! `get` checks out a connection and counts it as busy until the guard is dropped, the guard dereferences to a `Surreal<C>`
+//! so it can be passed to every builder that takes a `&Surreal<C>`.
+//! The pool implements `table::database::Database`, so the `Table` methods take the pool itself and check out a connection for the call.
+//!
+//! `use_ns_db` and `signin` are run on every connection and remembered, so they are run again on new connections.
+//!
+//! `health_check` marks the connections that don't respond as unhealthy, they are skipped until a later health check succeeds.
+//! A pool created with `SurrealPool::connect` can replace them with new connections with `reconnect`.
+//!
+//! # Example
+//!
+//! ```rust
+//! use serde::{Serialize, Deserialize};
+//! use surrealdb::engine::any::connect;
+//! use surrealdb::sql::{Field, Thing as RecordId};
+//! use surrealdb_extra::pool::SurrealPool;
+//! use surrealdb_extra::query::statement::StatementBuilder;
+//! use surrealdb_extra::table::Table;
+//!
+//! #[derive(Table, Serialize, Deserialize, Clone)]
+//! #[table(name = "user")]
+//! struct User {
+//!     id: Option<RecordId>,
+//!     name: String
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     // e.g. connect("http://localhost:8000") for every connection
+//!     let db = connect("mem://").await.unwrap();
+//!
+//!     let pool = SurrealPool::connect(4, move || {
+//!         let db = db.clone();
+//!
+//!         async move { Ok(db) }
+//!     }).await.unwrap();
+//!
+//!     pool.use_ns_db("ns", "db").await.unwrap();
+//!
+//!     User { id: None, name: "name".to_string() }.create(&pool).await.unwrap();
+//!
+//!     let users: Vec<User> = pool.get().select_builder().what("user").field(Field::All).to_query().await.unwrap().take(0).unwrap();
+//!
+//!     assert_eq!(users.len(), 1);
+//!     assert_eq!(pool.health_check().await, 4);
+//! }
+//! ```
+
+pub mod err;
+
+use std::future::Future;
+use std::ops::Deref;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock};
+use anyhow::Result;
+use serde::Serialize;
+use surrealdb::opt::auth::{Credentials, Jwt, Signin};
+use surrealdb::{Connection, Surreal};
+pub use crate::pool::err::PoolError;
+
+type ConnectFn<C> = Arc<dyn Fn() -> Pin<Box<dyn Future<Output = Result<Surreal<C>>> + Send>> + Send + Sync>;
+
+struct Slot<C: Connection> {
+    db: RwLock<Surreal<C>>,
+    in_flight: AtomicUsize,
+    healthy: AtomicBool,
+}
+
+impl<C: Connection> Slot<C> {
+    fn new(db: Surreal<C>) -> Self {
+        Self {
+            db: RwLock::new(db),
+            in_flight: AtomicUsize::new(0),
+            healthy: AtomicBool::new(true),
+        }
+    }
+
+    fn db(&self) -> Surreal<C> {
+        self.db.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+}
+
+/// The credentials of `signin`, kept as json so they can be sent again
+#[derive(Clone, Serialize)]
+#[serde(transparent)]
+struct SigninCredentials(serde_json::Value);
+
+impl Credentials<Signin, Jwt> for SigninCredentials {}
+
+/// What was run on every connection, a new connection runs it again
+#[derive(Clone, Default)]
+struct Session {
+    ns_db: Option<(String, String)>,
+    signin: Option<SigninCredentials>,
+}
+
+impl Session {
+    async fn restore<C: Connection>(&self, db: &Surreal<C>) -> Result<()> {
+        if let Some((ns, database)) = &self.ns_db {
+            db.use_ns(ns.clone()).use_db(database.clone()).await.map_err(PoolError::from)?;
+        }
+
+        if let Some(credentials) = &self.signin {
+            db.signin(credentials.clone()).await.map_err(PoolError::from)?;
+        }
+
+        Ok(())
+    }
+}
+
+pub struct SurrealPool<C: Connection> {
+    slots: Vec<Slot<C>>,
+    next: AtomicUsize,
+    connect: Option<ConnectFn<C>>,
+    session: RwLock<Session>,
+}
+
+impl<C: Connection> SurrealPool<C> {
+    /// Pool of existing connections, it can't reconnect
+    pub fn new(connections: impl IntoIterator<Item = Surreal<C>>) -> Result<Self> {
+        let slots: Vec<Slot<C>> = connections.into_iter().map(Slot::new).collect();
+
+        if slots.is_empty() {
+            return Err(PoolError::Empty.into());
+        }
+
+        Ok(Self {
+            slots,
+            next: AtomicUsize::new(0),
+            connect: None,
+            session: RwLock::new(Session::default()),
+        })
+    }
+
+    /// Opens `size` connections with `connect`, which is also used by `reconnect`
+    pub async fn connect<F, Fut>(size: usize, connect: F) -> Result<Self>
+        where F: Fn() -> Fut + Send + Sync + 'static, Fut: Future<Output = Result<Surreal<C>>> + Send + 'static
+    {
+        let connect: ConnectFn<C> = Arc::new(move || Box::pin(connect()));
+
+        let mut connections = Vec::with_capacity(size);
+
+        for _ in 0..size {
+            connections.push(connect().await?);
+        }
+
+        let mut pool = Self::new(connections)?;
+        pool.connect = Some(connect);
+
+        Ok(pool)
+    }
+
+    pub fn size(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Number of connections that passed the last health check
+    pub fn healthy(&self) -> usize {
+        self.slots.iter().filter(|s| s.healthy.load(Ordering::Relaxed)).count()
+    }
+
+    /// Checks out the healthy connection with the least checked out requests
+    pub fn get(&self) -> PooledConnection<'_, C> {
+        let slot = self.pick();
+
+        slot.in_flight.fetch_add(1, Ordering::Relaxed);
+
+        PooledConnection { slot, db: slot.db() }
+    }
+
+    /// Selects the namespace and database on every connection, new connections select them as well
+    pub async fn use_ns_db(&self, ns: impl Into<String>, db: impl Into<String>) -> Result<()> {
+        let (ns, db) = (ns.into(), db.into());
+
+        for slot in &self.slots {
+            slot.db().use_ns(ns.clone()).use_db(db.clone()).await.map_err(PoolError::from)?;
+        }
+
+        self.session.write().unwrap_or_else(|e| e.into_inner()).ns_db = Some((ns, db));
+
+        Ok(())
+    }
+
+    /// Signs in on every connection, new connections sign in with the same credentials.
+    /// The credentials are kept in memory for as long as the pool lives.
+    pub async fn signin(&self, credentials: impl Credentials<Signin, Jwt>) -> Result<Jwt> {
+        let credentials = SigninCredentials(serde_json::to_value(&credentials)?);
+
+        let mut token = None;
+
+        for slot in &self.slots {
+            token = Some(slot.db().signin(credentials.clone()).await.map_err(PoolError::from)?);
+        }
+
+        self.session.write().unwrap_or_else(|e| e.into_inner()).signin = Some(credentials);
+
+        token.ok_or(PoolError::Empty.into())
+    }
+
+    /// Pings every connection and marks the ones that fail as unhealthy, returns the number of healthy connections
+    pub async fn health_check(&self) -> usize {
+        for slot in &self.slots {
+            let healthy = slot.db().health().await.is_ok();
+
+            slot.healthy.store(healthy, Ordering::Relaxed);
+        }
+
+        self.healthy()
+    }
+
+    /// Replaces the unhealthy connections with new ones that select the namespace and database and sign in again,
+    /// returns how many were replaced. Checked out connections keep using the old connection until they are dropped.
+    pub async fn reconnect(&self) -> Result<usize> {
+        let connect = self.connect.clone().ok_or(PoolError::NoConnect)?;
+        let session = self.session.read().unwrap_or_else(|e| e.into_inner()).clone();
+
+        let mut replaced = 0;
+
+        for slot in &self.slots {
+            if slot.healthy.load(Ordering::Relaxed) {
+                continue;
+            }
+
+            let db = connect().await?;
+            session.restore(&db).await?;
+
+            *slot.db.write().unwrap_or_else(|e| e.into_inner()) = db;
+            slot.healthy.store(true, Ordering::Relaxed);
+            replaced += 1;
+        }
+
+        Ok(replaced)
+    }
+
+    /// The healthy slot with the least requests, the search starts at a rotating offset so ties are spread evenly.
+    /// Without healthy connections the next connection is used anyway, so the request fails with the connection error.
+    fn pick(&self) -> &Slot<C> {
+        let start = self.next.fetch_add(1, Ordering::Relaxed) % self.slots.len();
+
+        (0..self.slots.len())
+            .map(|i| &self.slots[(start + i) % self.slots.len()])
+            .filter(|s| s.healthy.load(Ordering::Relaxed))
+            .min_by_key(|s| s.in_flight.load(Ordering::Relaxed))
+            .unwrap_or(&self.slots[start])
+    }
+}
+
+/// A checked out connection, it counts as busy until it is dropped
+pub struct PooledConnection<'p, C: Connection> {
+    slot: &'p Slot<C>,
+    db: Surreal<C>,
+}
+
+impl<C: Connection> Deref for PooledConnection<'_, C> {
+    type Target = Surreal<C>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.db
+    }
+}
+
+#[cfg(feature = "table")]
+impl<C: Connection> crate::table::database::Database<C> for SurrealPool<C> {
+    type Connection<'a> = PooledConnection<'a, C>;
+
+    fn connection(&self) -> Self::Connection<'_> {
+        self.get()
+    }
+}
+
+#[cfg(feature = "table")]
+impl<C: Connection> crate::table::database::Database<C> for PooledConnection<'_, C> {
+    type Connection<'a> = &'a Surreal<C> where Self: 'a;
+
+    fn connection(&self) -> Self::Connection<'_> {
+        &self.db
+    }
+}
+
+impl<C: Connection> Drop for PooledConnection<'_, C> {
+    fn drop(&mut self) {
+        self.slot.in_flight.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde::{Deserialize, Serialize};
+    use surrealdb::engine::any::{connect, Any};
+    use surrealdb::opt::auth::Root;
+    use surrealdb::sql::Thing;
+    use crate::table::Table;
+    use super::*;
+
+    #[derive(Debug, Table, Serialize, Deserialize, Clone, PartialEq)]
+    #[table(name = "user")]
+    struct User {
+        id: Option<Thing>,
+        name: String,
+    }
+
+    async fn pool(size: usize) -> SurrealPool<Any> {
+        SurrealPool::connect(size, || async { Ok(connect("mem://").await?) }).await.unwrap()
+    }
+
+    #[test]
+    fn empty() {
+        assert!(SurrealPool::<Any>::new([]).is_err());
+    }
+
+    #[tokio::test]
+    async fn least_busy() {
+        let pool = pool(3).await;
+
+        let first = pool.get();
+        let second = pool.get();
+        let third = pool.get();
+
+        let slots = [&first, &second, &third].map(|c| c.slot as *const Slot<Any>);
+        assert!(slots[0] != slots[1] && slots[1] != slots[2] && slots[0] != slots[2]);
+
+        drop(second);
+
+        let again = pool.get();
+        assert_eq!(again.slot as *const Slot<Any>, slots[1]);
+    }
+
+    #[tokio::test]
+    async fn skips_unhealthy() {
+        let pool = pool(2).await;
+
+        pool.slots[0].healthy.store(false, Ordering::Relaxed);
+        assert_eq!(pool.healthy(), 1);
+
+        for _ in 0..4 {
+            assert!(std::ptr::eq(pool.get().slot, &pool.slots[1]));
+        }
+
+        assert_eq!(pool.reconnect().await.unwrap(), 1);
+        assert_eq!(pool.health_check().await, 2);
+    }
+
+    #[tokio::test]
+    async fn reconnect_restores_session() {
+        let pool = SurrealPool::connect(2, || async {
+            let db = connect("mem://").await?;
+            db.query("DEFINE USER root ON ROOT PASSWORD 'root' ROLES OWNER").await?.check()?;
+
+            Ok(db)
+        }).await.unwrap();
+
+        pool.use_ns_db("test", "test").await.unwrap();
+        pool.signin(Root { username: "root", password: "root" }).await.unwrap();
+
+        let old = pool.get();
+        old.slot.healthy.store(false, Ordering::Relaxed);
+
+        assert_eq!(pool.reconnect().await.unwrap(), 1);
+
+        for _ in 0..2 {
+            let db = pool.get();
+            let session: Option<String> = db.query("RETURN $session.ns + ':' + $session.db + ':' + $token.ID").await.unwrap().take(0).unwrap();
+
+            assert_eq!(session.as_deref(), Some("test:test:root"));
+        }
+
+        assert!(old.query("RETURN 1").await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn table_methods() {
+        let pool = SurrealPool::new([connect("mem://").await.unwrap(), connect("mem://").await.unwrap()]).unwrap();
+        pool.use_ns_db("test", "test").await.unwrap();
+
+        let db = pool.get();
+
+        let user = User { id: None, name: "john".to_string() }.create(&db).await.unwrap().unwrap();
+
+        assert_eq!(User::get_by_id(&db, user.id.clone().unwrap()).await.unwrap(), Some(user.clone()));
+        assert_eq!(User::get_all(&db).await.unwrap(), [user]);
+
+        let other = pool.get();
+
+        assert!(!std::ptr::eq(db.slot, other.slot));
+        assert_eq!(User::get_all(&other).await.unwrap(), []);
+    }
+
+    #[tokio::test]
+    async fn table_methods_on_pool() {
+        let pool = SurrealPool::new([connect("mem://").await.unwrap()]).unwrap();
+        pool.use_ns_db("test", "test").await.unwrap();
+
+        let user = User { id: None, name: "john".to_string() }.create(&pool).await.unwrap().unwrap();
+
+        assert_eq!(User::get_by_id(&pool, user.id.clone().unwrap()).await.unwrap(), Some(user.clone()));
+        assert_eq!(pool.slots[0].in_flight.load(Ordering::Relaxed), 0);
+    }
+}
@@ -0,0 +1,184 @@
+//! Independent statements in one request
+//!
+//! `Batch` collects the statements of builders, or SurrealQL strings, and runs them with one query.
+//! `add` returns the batch, `push` returns a `BatchIndex` to take the result with later.
+//! Unlike a transaction the statements don't depend on each other, a failed statement only fails when its result is taken.
+//!
+//! ```rust
+//! use serde::Deserialize;
+//! use surrealdb::engine::any::connect;
+//! use surrealdb::sql::Field;
+//! use surrealdb_extra::query::batch::Batch;
+//! use surrealdb_extra::query::statement::StatementBuilder;
+//!
+//! #[derive(Deserialize)]
+//! struct User {
+//!     name: String,
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let db = connect("mem://").await.unwrap();
+//!     db.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     db.query("CREATE user:john SET name = 'john'").await.unwrap();
+//!
+//!     let mut batch = Batch::new()
+//!         .add(db.select_builder().what("user").field(Field::All));
+//!
+//!     let count = batch.push("count(SELECT * FROM user)");
+//!
+//!     let mut res = batch.execute(&db).await.unwrap();
+//!
+//!     let users: Vec<User> = res.take_vec(0usize).unwrap();
+//!     let count: u64 = res.take_scalar(count).unwrap();
+//!
+//!     assert_eq!(users[0].name, "john");
+//!     assert_eq!(count, 1);
+//! }
+//! ```
+
+use serde::de::DeserializeOwned;
+use surrealdb::{Connection, Surreal};
+use surrealdb::method::Query;
+use surrealdb::sql::{Statement, Statements};
+use crate::query::parsing::statement::ExtraStatement;
+use crate::query::raw::{RawQueryError, TypedResponse};
+
+#[derive(Debug, Clone, Default)]
+pub struct Batch {
+    pub statements: Vec<Statement>,
+}
+
+/// Index of a statement of a batch
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BatchIndex(pub usize);
+
+impl From<BatchIndex> for usize {
+    fn from(value: BatchIndex) -> Self {
+        value.0
+    }
+}
+
+impl Batch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a statement, its index is the number of statements added before it
+    #[allow(clippy::should_implement_trait)]
+    pub fn add(mut self, statement: impl Into<ExtraStatement>) -> Self {
+        self.push(statement);
+
+        self
+    }
+
+    /// Same as `add` but returns the index of the statement
+    pub fn push(&mut self, statement: impl Into<ExtraStatement>) -> BatchIndex {
+        self.statements.push(statement.into().0);
+
+        BatchIndex(self.statements.len() - 1)
+    }
+
+    pub fn len(&self) -> usize {
+        self.statements.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.statements.is_empty()
+    }
+
+    /// The SurrealQL that `to_query` runs, parameters stay `$name` placeholders for the bindings
+    pub fn to_surql(&self) -> String {
+        let mut statements = Statements::default();
+        statements.0 = self.statements.clone();
+
+        statements.to_string()
+    }
+
+    /// Converts the batch to query type, e.g. to bind parameters
+    pub fn to_query<Client: Connection>(self, db: &Surreal<Client>) -> Query<'_, Client> {
+        db.query(self.statements)
+    }
+
+    /// Runs all statements in one request
+    pub async fn execute<Client: Connection>(self, db: &Surreal<Client>) -> anyhow::Result<BatchResult> {
+        let len = self.len();
+        let res = self.to_query(db).await.map_err(RawQueryError::from)?;
+
+        Ok(BatchResult {
+            len,
+            response: TypedResponse::from(res),
+        })
+    }
+}
+
+/// The results of a batch, taken by index or `BatchIndex`
+#[derive(Debug)]
+pub struct BatchResult {
+    len: usize,
+    pub response: TypedResponse,
+}
+
+impl BatchResult {
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Takes exactly one record, see `TypedResponse::take_one`
+    pub fn take_one<T: DeserializeOwned>(&mut self, index: impl Into<usize>) -> anyhow::Result<T> {
+        self.response.take_one(index.into())
+    }
+
+    /// Takes all records, see `TypedResponse::take_vec`
+    pub fn take_vec<T: DeserializeOwned>(&mut self, index: impl Into<usize>) -> anyhow::Result<Vec<T>> {
+        self.response.take_vec(index.into())
+    }
+
+    /// Takes a single value, see `TypedResponse::take_scalar`
+    pub fn take_scalar<T: DeserializeOwned>(&mut self, index: impl Into<usize>) -> anyhow::Result<T> {
+        self.response.take_scalar(index.into())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde::Deserialize;
+    use surrealdb::sql::{Field, Operator};
+    use crate::query::statement::StatementBuilder;
+    use crate::testing::db;
+    use super::*;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct User {
+        name: String,
+    }
+
+    #[tokio::test]
+    async fn batch() {
+        let db = db().await;
+
+        let mut batch = Batch::new()
+            .add(db.create_builder().what("user").set(vec![("name", Operator::Equal, "one")]))
+            .add("<int> 'a'");
+
+        let users = batch.push(db.select_builder().what("user").field(Field::All));
+        let names = batch.push(db.select_builder().what("user").value("name"));
+
+        assert_eq!(batch.len(), 4);
+        assert_eq!(users, BatchIndex(2));
+        assert_eq!(batch.to_surql(), "CREATE user SET name = 'one';\n<int> 'a';\nSELECT * FROM user;\nSELECT VALUE name FROM user;");
+
+        let mut res = batch.execute(&db).await.unwrap();
+
+        assert_eq!(res.len(), 4);
+        assert_eq!(res.take_one::<User>(0usize).unwrap(), User { name: "one".to_string() });
+        assert!(matches!(res.take_scalar::<String>(1usize).unwrap_err().downcast::<RawQueryError>(), Ok(RawQueryError::Statement { index: 1, .. })));
+        assert_eq!(res.take_vec::<User>(users).unwrap(), [User { name: "one".to_string() }]);
+        assert_eq!(res.take_vec::<String>(names).unwrap(), ["one"]);
+    }
+}
@@ -0,0 +1,273 @@
+//! # Several selects in one round-trip
+//!
+//! `CombinedSelect` collects labeled selects and runs them together, there are three ways to get the results:
+//!
+//! - `execute` runs every select as its own statement of one query, `CombinedResponse::take` takes the rows of a label
+//! - `execute_object` runs `RETURN { label: (SELECT ...), ... }` and deserializes the object, e.g. into a struct with a field per label
+//! - `execute_concat` runs `RETURN array::concat((SELECT ...), ...)`, the rows of every select in one `Vec` like a `UNION ALL`
+//!
+//! ```rust
+//! use serde::Deserialize;
+//! use surrealdb::engine::any::connect;
+//! use surrealdb_extra::query::combine::CombinedSelect;
+//! use surrealdb_extra::query::statement::StatementBuilder;
+//!
+//! #[derive(Deserialize)]
+//! struct Dashboard {
+//!     users: Vec<String>,
+//!     posts: Vec<String>,
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let db = connect("mem://").await.unwrap();
+//!     db.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     db.query("CREATE user SET name = 'john'; CREATE post SET title = 'hello';").await.unwrap();
+//!
+//!     let combined = CombinedSelect::new(&db)
+//!         .add("users", db.select_builder().what("user").value("name"))
+//!         .add("posts", db.select_builder().what("post").value("title"));
+//!
+//!     let mut res = combined.clone().execute().await.unwrap();
+//!     let users: Vec<String> = res.take("users").unwrap();
+//!     assert_eq!(users, ["john"]);
+//!
+//!     let dashboard: Dashboard = combined.execute_object().await.unwrap();
+//!     assert_eq!(dashboard.posts, ["hello"]);
+//! }
+//! ```
+
+use std::collections::BTreeMap;
+use serde::de::DeserializeOwned;
+use surrealdb::{Connection, Surreal};
+use surrealdb::method::Query;
+use surrealdb::sql::{Function, Object, Statement, Statements, Subquery, Value};
+use surrealdb::sql::statements::{OutputStatement, SelectStatement};
+use thiserror::Error;
+use crate::query::dyn_select::{DynSelectBuilder, DynSelectError};
+use crate::query::raw::{RawQueryError, TypedResponse};
+
+#[derive(Debug, Error)]
+pub enum CombineError {
+    #[error("There are no selects to combine")]
+    Empty,
+    #[error("The label `{0}` is used by more than one select")]
+    DuplicateLabel(String),
+    #[error("The label `{0}` is not one of the combined selects")]
+    UnknownLabel(String),
+    #[error("The select `{label}` is invalid: {error}")]
+    Select { label: String, error: DynSelectError },
+}
+
+#[derive(Debug, Clone)]
+pub struct CombinedSelect<'r, Client>
+    where Client: Connection
+{
+    pub selects: Vec<(String, DynSelectBuilder<'r, Client>)>,
+    pub(crate) db: &'r Surreal<Client>,
+}
+
+impl<'r, Client> CombinedSelect<'r, Client>
+    where Client: Connection
+{
+    pub fn new(db: &'r Surreal<Client>) -> Self {
+        Self {
+            selects: vec![],
+            db,
+        }
+    }
+
+    /// Adds a select with the label its results are taken by, a typed or a dyn select builder
+    pub fn add(mut self, label: impl Into<String>, select: impl Into<DynSelectBuilder<'r, Client>>) -> Self {
+        self.selects.push((label.into(), select.into()));
+
+        self
+    }
+
+    pub fn labels(&self) -> Vec<&str> {
+        self.selects.iter().map(|(label, _)| label.as_str()).collect()
+    }
+
+    /// Checks that there is a select, that the labels are unique and that every select is valid
+    pub fn validate(&self) -> Result<(), CombineError> {
+        if self.selects.is_empty() {
+            return Err(CombineError::Empty);
+        }
+
+        for (i, (label, select)) in self.selects.iter().enumerate() {
+            if self.selects[..i].iter().any(|(other, _)| other == label) {
+                return Err(CombineError::DuplicateLabel(label.clone()));
+            }
+
+            select.validate().map_err(|error| CombineError::Select { label: label.clone(), error })?;
+        }
+
+        Ok(())
+    }
+
+    fn statements(&self) -> Result<Vec<SelectStatement>, CombineError> {
+        self.validate()?;
+
+        Ok(self.selects.iter().map(|(_, select)| crate::query::config::apply(select.statement.clone())).collect())
+    }
+
+    /// The selects as one statement each
+    pub fn to_surql(&self) -> Result<String, CombineError> {
+        let mut statements = Statements::default();
+        statements.0 = self.statements()?.into_iter().map(Statement::Select).collect();
+
+        Ok(statements.to_string())
+    }
+
+    /// `RETURN { label: (SELECT ...), ... }`
+    pub fn to_object_surql(&self) -> Result<String, CombineError> {
+        Ok(self.object_statement()?.to_string())
+    }
+
+    /// `RETURN array::concat((SELECT ...), ...)`
+    pub fn to_concat_surql(&self) -> Result<String, CombineError> {
+        Ok(self.concat_statement()?.to_string())
+    }
+
+    fn object_statement(&self) -> Result<OutputStatement, CombineError> {
+        let values: BTreeMap<String, Value> = self.labels().into_iter()
+            .map(str::to_string)
+            .zip(self.statements()?.into_iter().map(subquery))
+            .collect();
+
+        let mut statement = OutputStatement::default();
+        statement.what = Value::Object(Object::from(values));
+
+        Ok(statement)
+    }
+
+    fn concat_statement(&self) -> Result<OutputStatement, CombineError> {
+        let args = self.statements()?.into_iter().map(subquery).collect();
+
+        let mut statement = OutputStatement::default();
+        statement.what = Value::Function(Box::new(Function::Normal("array::concat".to_string(), args)));
+
+        Ok(statement)
+    }
+
+    /// The selects as one statement each, converted to query type
+    pub fn to_query(self) -> Result<Query<'r, Client>, CombineError> {
+        let statements: Vec<Statement> = self.statements()?.into_iter().map(Statement::Select).collect();
+
+        Ok(self.db.query(statements))
+    }
+
+    /// Runs the selects in one query
+    pub async fn execute(self) -> anyhow::Result<CombinedResponse> {
+        let labels = self.labels().into_iter().map(str::to_string).collect();
+        let res = self.to_query()?.await.map_err(RawQueryError::from)?;
+
+        Ok(CombinedResponse {
+            labels,
+            response: TypedResponse::from(res),
+        })
+    }
+
+    /// Runs `RETURN { label: (SELECT ...), ... }` and deserializes the object
+    pub async fn execute_object<T: DeserializeOwned>(self) -> anyhow::Result<T> {
+        let statement = self.object_statement()?;
+        let res = self.db.query(Statement::Output(statement)).await.map_err(RawQueryError::from)?;
+
+        TypedResponse::from(res).take_scalar(0)
+    }
+
+    /// Runs `RETURN array::concat((SELECT ...), ...)` and deserializes the rows of all selects
+    pub async fn execute_concat<T: DeserializeOwned>(self) -> anyhow::Result<Vec<T>> {
+        let statement = self.concat_statement()?;
+        let res = self.db.query(Statement::Output(statement)).await.map_err(RawQueryError::from)?;
+
+        TypedResponse::from(res).take_vec(0)
+    }
+}
+
+fn subquery(statement: SelectStatement) -> Value {
+    Value::Subquery(Box::new(Subquery::Select(statement)))
+}
+
+/// The response of `CombinedSelect::execute`, the results are taken by the label of their select
+#[derive(Debug)]
+pub struct CombinedResponse {
+    pub labels: Vec<String>,
+    pub response: TypedResponse,
+}
+
+impl CombinedResponse {
+    pub fn take<T: DeserializeOwned>(&mut self, label: &str) -> anyhow::Result<Vec<T>> {
+        let index = self.labels.iter().position(|l| l == label)
+            .ok_or_else(|| CombineError::UnknownLabel(label.to_string()))?;
+
+        self.response.take_vec(index)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde::Deserialize;
+    use surrealdb::engine::any::connect;
+    use surrealdb::sql::Field;
+    use crate::query::statement::StatementBuilder;
+    use crate::testing::db;
+    use super::*;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Named {
+        name: String,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct Counts {
+        users: Vec<Named>,
+        teams: Vec<Named>,
+    }
+
+    #[tokio::test]
+    async fn combined() {
+        let db = db().await;
+
+        db.query("
+            CREATE user:a SET name = 'a';
+            CREATE user:b SET name = 'b';
+            CREATE team:x SET name = 'x';
+        ").await.unwrap().check().unwrap();
+
+        let combined = CombinedSelect::new(&db)
+            .add("users", db.select_builder().what("user").field("name").condition("name = 'a'"))
+            .add("teams", db.dyn_select_builder().what("team").field("name"));
+
+        assert_eq!(combined.to_surql().unwrap(), "SELECT name FROM user WHERE name = 'a';\nSELECT name FROM team;");
+        assert_eq!(combined.to_object_surql().unwrap(), "RETURN { teams: (SELECT name FROM team), users: (SELECT name FROM user WHERE name = 'a') }");
+        assert_eq!(combined.to_concat_surql().unwrap(), "RETURN array::concat((SELECT name FROM user WHERE name = 'a'), (SELECT name FROM team))");
+
+        let mut res = combined.clone().execute().await.unwrap();
+        assert_eq!(res.take::<Named>("teams").unwrap(), [Named { name: "x".to_string() }]);
+        assert_eq!(res.take::<Named>("users").unwrap(), [Named { name: "a".to_string() }]);
+        assert!(res.take::<Named>("posts").unwrap_err().downcast::<CombineError>().is_ok());
+
+        let counts: Counts = combined.clone().execute_object().await.unwrap();
+        assert_eq!((counts.users.len(), counts.teams.len()), (1, 1));
+
+        let all: Vec<Named> = combined.execute_concat().await.unwrap();
+        assert_eq!(all, [Named { name: "a".to_string() }, Named { name: "x".to_string() }]);
+    }
+
+    #[tokio::test]
+    async fn invalid() {
+        let db = connect("mem://").await.unwrap();
+
+        assert!(matches!(CombinedSelect::new(&db).validate(), Err(CombineError::Empty)));
+
+        let duplicate = CombinedSelect::new(&db)
+            .add("a", db.select_builder().what("user").field(Field::All))
+            .add("a", db.select_builder().what("team").field(Field::All));
+        assert!(matches!(duplicate.validate(), Err(CombineError::DuplicateLabel(l)) if l == "a"));
+
+        let no_fields = CombinedSelect::new(&db).add("a", db.dyn_select_builder().what("user"));
+        assert!(matches!(no_fields.validate(), Err(CombineError::Select { error: DynSelectError::NoFields, .. })));
+    }
+}
@@ -251,27 +251,52 @@ impl<'r, Client> CreateBuilder<'r, Client, FilledWhat, FilledData>
         }
     }
 
+    /// The SurrealQL that `to_query` runs, parameters stay `$name` placeholders for the bindings
+    pub fn to_surql(&self) -> String {
+        let statement = crate::query::config::apply(self.statement.clone());
+
+        statement.to_string()
+    }
+
     pub fn to_query(self) -> Query<'r, Client> {
-        self.db.query(self.statement)
+        let statement = crate::query::config::apply(self.statement);
+
+        self.db.query(statement)
+    }
+
+    /// Runs the query with the observers, see the `observer` module
+    pub fn observed(self) -> crate::query::observer::Observed<'r, Client> {
+        let info = crate::query::observer::QueryInfo::new("CREATE", &self.statement.what.0, self.to_surql());
+
+        crate::query::observer::Observed::new(info, self.to_query())
+    }
+
+    /// Runs only this statement in another namespace and database, see the `scope` module
+    pub fn use_ns_db(self, ns: impl Into<String>, database: impl Into<String>) -> crate::query::scope::Scoped<'r, Client> {
+        let statement = crate::query::config::apply(self.statement);
+
+        crate::query::scope::Scoped::new(self.db, ns, database, statement)
+    }
+
+    /// Same as `to_query` but drops the clauses the engine doesn't support, see `Capabilities`
+    #[cfg_attr(docsrs, doc(cfg(feature = "fallback")))]
+    #[cfg(feature = "fallback")]
+    pub fn to_query_with(mut self, capabilities: &crate::query::fallback::Capabilities) -> anyhow::Result<Query<'r, Client>> {
+        self.statement = crate::query::fallback::apply(self.statement, capabilities)?;
+
+        Ok(self.to_query())
     }
 }
 
 #[cfg(test)]
 mod test {
-    use surrealdb::engine::any::{Any, connect};
     use surrealdb::opt::IntoQuery;
+    use surrealdb::sql::Value;
     use crate::op;
     use crate::query::statement::StatementBuilder;
+    use crate::testing::db;
     use super::*;
 
-    async fn db() -> Surreal<Any> {
-        let db = connect("mem://").await.unwrap();
-
-        db.use_ns("test").use_db("test").await.unwrap();
-
-        db
-    }
-
     #[derive(Serialize)]
     struct Test {
         test1: String,
@@ -313,4 +338,13 @@ mod test {
 
         assert!(query.is_ok());
     }
+
+    #[tokio::test]
+    async fn create_to_surql() {
+        let db = db().await;
+
+        let create = db.create_builder().what("test").set(vec![("test", op!(=), Value::Param("test".into()))]).only();
+
+        assert_eq!(create.to_surql(), "CREATE ONLY test SET test = $test");
+    }
 }
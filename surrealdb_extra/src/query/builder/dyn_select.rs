@@ -0,0 +1,383 @@
+//! # Select builder without typestates
+//!
+//! `DynSelectBuilder` has the methods of `SelectBuilder` but keeps the same type after every call,
+//! so the select can be built step by step, e.g. adding a condition only when a filter is set.
+//! The missing `what` or fields are checked when the builder is converted with `to_query`, `to_surql` or `TryFrom`.
+//! Every `condition` is added to the previous ones with `AND`.
+//!
+//! `SelectBuilder` converts into it with `From` at any state and back with `TryFrom`.
+//!
+//! ```rust
+//! use surrealdb::engine::any::connect;
+//! use surrealdb_extra::query::parsing::order::OrderDirection;
+//! use surrealdb_extra::query::statement::StatementBuilder;
+//!
+//! struct Filter {
+//!     name: Option<String>,
+//!     min_age: Option<i64>,
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let db = connect("mem://").await.unwrap();
+//!     db.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     let filter = Filter { name: None, min_age: Some(18) };
+//!
+//!     // The values are bound as parameters and never written into the query
+//!     let select = db.dyn_select_builder().what("user").field("name")
+//!         .condition_if(filter.name.as_ref().map(|_| "name = $name"))
+//!         .condition_if(filter.min_age.map(|_| "age >= $min_age"))
+//!         .order(("name", OrderDirection::ASC));
+//!
+//!     assert_eq!(select.to_surql().unwrap(), "SELECT name FROM user WHERE age >= $min_age ORDER BY name");
+//!
+//!     let _ = select.to_query().unwrap()
+//!         .bind(("name", filter.name))
+//!         .bind(("min_age", filter.min_age))
+//!         .await.unwrap();
+//!
+//!     // Without a `what` the select fails when it is converted
+//!     assert!(db.dyn_select_builder().field("name").to_query().is_err());
+//! }
+//! ```
+
+use std::marker::PhantomData;
+use serde::de::DeserializeOwned;
+use surrealdb::{Connection, Surreal};
+use surrealdb::method::Query;
+use surrealdb::sql::{Cond, Explain, Expression, Fetchs, Field, Groups, Idiom, Idioms, Operator, Orders, Splits, Subquery, Value};
+use surrealdb::sql::statements::SelectStatement;
+use thiserror::Error;
+use crate::query::parsing::cond::ExtraCond;
+use crate::query::parsing::fetch::ExtraFetch;
+use crate::query::parsing::field::ExtraField;
+use crate::query::parsing::group::ExtraGroup;
+use crate::query::parsing::limit::ExtraLimit;
+use crate::query::parsing::omit::ExtraOmit;
+use crate::query::parsing::order::ExtraOrder;
+use crate::query::parsing::split::ExtraSplit;
+use crate::query::parsing::start::ExtraStart;
+use crate::query::parsing::timeout::ExtraTimeout;
+use crate::query::parsing::version::ExtraVersion;
+use crate::query::parsing::what::ExtraValue;
+use crate::query::raw::{RawQueryError, TypedResponse};
+use crate::query::select::SelectBuilder;
+use crate::query::states::{FilledCond, FilledFields, FilledWhat};
+
+#[derive(Debug, Error)]
+pub enum DynSelectError {
+    #[error("The select has nothing to select from, `what` is missing")]
+    NoWhat,
+    #[error("The select has no fields, `field` or `value` is missing")]
+    NoFields,
+}
+
+#[derive(Debug, Clone)]
+pub struct DynSelectBuilder<'r, Client>
+    where Client: Connection
+{
+    pub statement: SelectStatement,
+    pub(crate) db: &'r Surreal<Client>,
+}
+
+impl<'r, Client> DynSelectBuilder<'r, Client>
+    where Client: Connection
+{
+    pub fn new(db: &'r Surreal<Client>) -> Self {
+        Self {
+            statement: Default::default(),
+            db,
+        }
+    }
+
+    /// Replaces what is selected from
+    pub fn what(mut self, what: impl Into<ExtraValue>) -> Self {
+        self.statement.what = what.into().0;
+
+        self
+    }
+
+    pub fn field(mut self, field: impl Into<ExtraField>) -> Self {
+        self.statement.expr.0.push(field.into().0);
+
+        self
+    }
+
+    /// `SELECT VALUE`, the field replaces the other fields
+    pub fn value(mut self, field: impl Into<ExtraField>) -> Self {
+        let field = match field.into().0 {
+            // A plain name is parsed as a table
+            Field::Single { expr: Value::Table(table), alias } => Field::Single { expr: Value::Idiom(Idiom::from(table.0)), alias },
+            field => field,
+        };
+
+        self.statement.expr.0 = vec![field];
+        self.statement.expr.1 = true;
+
+        self
+    }
+
+    /// Adds the condition to the previous ones with `AND`, the first one is the condition
+    pub fn condition(mut self, cond: impl Into<ExtraCond>) -> Self {
+        let cond = cond.into().0;
+
+        self.statement.cond = Some(match self.statement.cond.take() {
+            Some(previous) => {
+                let mut combined = Cond::default();
+                combined.0 = Value::Expression(Box::new(Expression::Binary {
+                    l: previous.0,
+                    o: Operator::And,
+                    r: Value::Subquery(Box::new(Subquery::Value(cond.0))),
+                }));
+
+                combined
+            }
+            None => cond,
+        });
+
+        self
+    }
+
+    /// Same as `condition` when the condition is `Some`
+    pub fn condition_if(self, cond: Option<impl Into<ExtraCond>>) -> Self {
+        match cond {
+            Some(cond) => self.condition(cond),
+            None => self,
+        }
+    }
+
+    /// Removes the condition
+    pub fn clear_condition(mut self) -> Self {
+        self.statement.cond = None;
+
+        self
+    }
+
+    pub fn omit(mut self, omit: impl Into<ExtraOmit>) -> Self {
+        self.statement.omit.get_or_insert_with(Idioms::default).0.push(omit.into().0);
+
+        self
+    }
+
+    pub fn split(mut self, split: impl Into<ExtraSplit>) -> Self {
+        self.statement.split.get_or_insert_with(Splits::default).0.push(split.into().0);
+
+        self
+    }
+
+    pub fn group(mut self, group: impl Into<ExtraGroup>) -> Self {
+        self.statement.group.get_or_insert_with(Groups::default).0.push(group.into().0);
+
+        self
+    }
+
+    pub fn order(mut self, order: impl Into<ExtraOrder>) -> Self {
+        self.statement.order.get_or_insert_with(Orders::default).0.push(order.into().0);
+
+        self
+    }
+
+    /// Same as `order` when the order is `Some`
+    pub fn order_if(self, order: Option<impl Into<ExtraOrder>>) -> Self {
+        match order {
+            Some(order) => self.order(order),
+            None => self,
+        }
+    }
+
+    pub fn limit(mut self, limit: impl Into<ExtraLimit>) -> Self {
+        self.statement.limit = Some(limit.into().0);
+
+        self
+    }
+
+    /// Same as `limit` when the limit is `Some`
+    pub fn limit_if(self, limit: Option<impl Into<ExtraLimit>>) -> Self {
+        match limit {
+            Some(limit) => self.limit(limit),
+            None => self,
+        }
+    }
+
+    pub fn start(mut self, start: impl Into<ExtraStart>) -> Self {
+        self.statement.start = Some(start.into().0);
+
+        self
+    }
+
+    /// Same as `start` when the start is `Some`
+    pub fn start_if(self, start: Option<impl Into<ExtraStart>>) -> Self {
+        match start {
+            Some(start) => self.start(start),
+            None => self,
+        }
+    }
+
+    pub fn fetch(mut self, fetch: impl Into<ExtraFetch>) -> Self {
+        self.statement.fetch.get_or_insert_with(Fetchs::default).0.push(fetch.into().0);
+
+        self
+    }
+
+    pub fn version(mut self, version: impl Into<ExtraVersion>) -> Self {
+        self.statement.version = Some(version.into().0);
+
+        self
+    }
+
+    pub fn timeout(mut self, timeout: impl Into<ExtraTimeout>) -> Self {
+        self.statement.timeout = Some(timeout.into().0);
+
+        self
+    }
+
+    pub fn only(mut self) -> Self {
+        self.statement.only = true;
+
+        self
+    }
+
+    pub fn parallel(mut self) -> Self {
+        self.statement.parallel = true;
+
+        self
+    }
+
+    pub fn explain(mut self) -> Self {
+        self.statement.explain = Some(Explain::default());
+
+        self
+    }
+
+    /// Checks that the select has a `what` and fields
+    pub fn validate(&self) -> Result<(), DynSelectError> {
+        if self.statement.what.0.is_empty() {
+            return Err(DynSelectError::NoWhat);
+        }
+
+        if self.statement.expr.0.is_empty() {
+            return Err(DynSelectError::NoFields);
+        }
+
+        Ok(())
+    }
+
+    /// The SurrealQL that `to_query` runs, parameters stay `$name` placeholders for the bindings
+    pub fn to_surql(&self) -> Result<String, DynSelectError> {
+        self.validate()?;
+
+        let statement = crate::query::config::apply(self.statement.clone());
+
+        Ok(statement.to_string())
+    }
+
+    /// Converts the builder to query type
+    pub fn to_query(self) -> Result<Query<'r, Client>, DynSelectError> {
+        self.validate()?;
+
+        let statement = crate::query::config::apply(self.statement);
+
+        Ok(self.db.query(statement))
+    }
+
+    /// Runs the select and deserializes every row
+    pub async fn execute_values<V: DeserializeOwned>(self) -> anyhow::Result<Vec<V>> {
+        let res = self.to_query()?.await.map_err(RawQueryError::from)?;
+
+        TypedResponse::from(res).take_vec(0)
+    }
+}
+
+impl<'r, Client, W, F, C> From<SelectBuilder<'r, Client, W, F, C>> for DynSelectBuilder<'r, Client>
+    where Client: Connection
+{
+    fn from(value: SelectBuilder<'r, Client, W, F, C>) -> Self {
+        Self {
+            statement: value.statement,
+            db: value.db,
+        }
+    }
+}
+
+/// The condition of the dyn builder can't be replaced on the typed builder, it is in the `FilledCond` state with or without a condition
+impl<'r, Client> TryFrom<DynSelectBuilder<'r, Client>> for SelectBuilder<'r, Client, FilledWhat, FilledFields, FilledCond>
+    where Client: Connection
+{
+    type Error = DynSelectError;
+
+    fn try_from(value: DynSelectBuilder<'r, Client>) -> Result<Self, Self::Error> {
+        value.validate()?;
+
+        Ok(Self {
+            statement: value.statement,
+            db: value.db,
+            what_state: PhantomData,
+            fields_state: PhantomData,
+            cond_state: PhantomData,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use surrealdb::engine::any::connect;
+    use crate::query::parsing::order::OrderDirection;
+    use crate::query::statement::StatementBuilder;
+    use crate::testing::db;
+    use super::*;
+
+    #[tokio::test]
+    async fn dyn_select_conditions() {
+        let db = connect("mem://").await.unwrap();
+
+        let mut select = db.dyn_select_builder().what("user").field("name");
+
+        for (field, value) in [("name", "$name"), ("city", "$city")] {
+            select = select.condition(format!("{field} = {value}"));
+        }
+
+        assert_eq!(select.to_surql().unwrap(), "SELECT name FROM user WHERE name = $name AND (city = $city)");
+        assert_eq!(select.clear_condition().limit(5).to_surql().unwrap(), "SELECT name FROM user LIMIT 5");
+    }
+
+    #[tokio::test]
+    async fn dyn_select_optional_clauses() {
+        let db = connect("mem://").await.unwrap();
+
+        let select = db.dyn_select_builder().what("user").field("name")
+            .condition_if(Some("age > 18"))
+            .condition_if(None::<&str>)
+            .order_if(None::<(&str, OrderDirection)>)
+            .limit_if(Some(5))
+            .start_if(Some(10));
+
+        assert_eq!(select.to_surql().unwrap(), "SELECT name FROM user WHERE age > 18 LIMIT 5 START 10");
+    }
+
+    #[tokio::test]
+    async fn dyn_select_validation() {
+        let db = connect("mem://").await.unwrap();
+
+        assert!(matches!(db.dyn_select_builder().field("name").validate(), Err(DynSelectError::NoWhat)));
+        assert!(matches!(db.dyn_select_builder().what("user").to_surql(), Err(DynSelectError::NoFields)));
+        let typed: Result<SelectBuilder<_, FilledWhat, FilledFields, FilledCond>, _> = db.dyn_select_builder().what("user").try_into();
+        assert!(typed.is_err());
+    }
+
+    #[tokio::test]
+    async fn dyn_select_conversions() {
+        let db = db().await;
+
+        db.query("CREATE user:1 SET name = 'a'; CREATE user:2 SET name = 'b';").await.unwrap();
+
+        let select = DynSelectBuilder::from(db.select_builder().what("user").value("name")).condition("name != 'a'");
+
+        let typed: SelectBuilder<_, FilledWhat, FilledFields, FilledCond> = select.try_into().unwrap();
+        let typed = typed.order(("name", OrderDirection::ASC));
+        assert_eq!(typed.to_surql(), "SELECT VALUE name FROM user WHERE name != 'a' ORDER BY name");
+
+        let names: Vec<String> = DynSelectBuilder::from(typed).execute_values().await.unwrap();
+        assert_eq!(names, ["b"]);
+    }
+}
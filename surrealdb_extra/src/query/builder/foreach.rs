@@ -0,0 +1,208 @@
+//! # Starting the builder can be done in 2 ways
+//!
+//! ## Using the `Surrealdb<C>` type
+//! ```rust
+//! use surrealdb::engine::any::connect;
+//! use surrealdb_extra::query::statement::StatementBuilder;
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let db = connect("mem://").await.unwrap();
+//!     db.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     let builder = db.foreach_builder();
+//!
+//!     let query = builder.for_in("item", "[1, 2, 3]").statement("$item").to_query();
+//! }
+//! ```
+//!
+//! ## Using new function inside the builder and passing a reference of type `Surrealdb<C>`
+//! ```rust
+//! use surrealdb::engine::any::connect;
+//! use surrealdb_extra::query::foreach::ForEachBuilder;
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let db = connect("mem://").await.unwrap();
+//!     db.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     let builder = ForEachBuilder::new(&db);
+//!
+//!     let query = builder.for_in("item", "[1, 2, 3]").statement("$item").to_query();
+//! }
+//! ```
+//!
+//! # Backfilling a field inside a transaction
+//! ```rust
+//! use surrealdb::engine::any::connect;
+//! use surrealdb::sql::{Field, Operator, Param, Value};
+//! use surrealdb_extra::query::statement::StatementBuilder;
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let db = connect("mem://").await.unwrap();
+//!     db.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     let backfill = db.foreach_builder()
+//!         .for_in("user", db.select_builder().what("user").field(Field::All))
+//!         .statement(db.update_builder().what(Param::from("user")).set(vec![("active", Operator::Equal, Value::Bool(true))]));
+//!     // The above builder becomes `FOR $user IN (SELECT * FROM user) { UPDATE $user SET active = true; }`
+//!
+//!     db.script_builder().begin().statement(backfill).commit().to_query().await.unwrap();
+//! }
+//! ```
+//!
+//! # For binding first convert the builder to a `Query<>` type and do binding as usual
+//!
+//! ## Click on the struct for more info
+
+use std::marker::PhantomData;
+use surrealdb::{Connection, Surreal};
+use surrealdb::method::Query;
+use surrealdb::sql::Statement;
+use surrealdb::sql::statements::{ForeachStatement, SetStatement};
+use crate::query::parsing::statement::ExtraStatement;
+use crate::query::parsing::subquery::ExtraSubquery;
+use crate::query::states::{FilledWhat, NoWhat};
+
+#[derive(Debug, Clone)]
+pub struct ForEachBuilder<'r, Client, W>
+    where Client: Connection
+{
+    pub statement: ForeachStatement,
+    pub(crate) db: &'r Surreal<Client>,
+    pub(crate) what_state: PhantomData<W>,
+}
+
+impl<'r, Client> ForEachBuilder<'r, Client, NoWhat>
+    where Client: Connection
+{
+    pub fn new(db: &'r Surreal<Client>) -> Self {
+        Self {
+            statement: Default::default(),
+            db,
+            what_state: Default::default(),
+        }
+    }
+
+    /// This function is for `FOR $param IN ...`
+    ///
+    /// Example:
+    /// ```rust
+    /// use surrealdb::engine::any::connect;
+    /// use surrealdb_extra::query::statement::StatementBuilder;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let db = connect("mem://").await.unwrap();
+    ///
+    ///     db.foreach_builder().for_in("item", "$items");
+    ///     // The above builder becomes `FOR $item IN $items {}`
+    ///
+    ///     db.foreach_builder().for_in("item", db.select_builder().what("test").field("id"));
+    ///     // The above builder becomes `FOR $item IN (SELECT id FROM test) {}`
+    /// }
+    /// ```
+    pub fn for_in(self, param: impl Into<String>, range: impl Into<ExtraSubquery>) -> ForEachBuilder<'r, Client, FilledWhat> {
+        let Self { mut statement, db, .. } = self;
+
+        statement.param = param.into().trim_start_matches('$').into();
+        statement.range = range.into().0;
+
+        ForEachBuilder {
+            statement,
+            db,
+            what_state: Default::default(),
+        }
+    }
+}
+
+impl<'r, Client> ForEachBuilder<'r, Client, FilledWhat>
+    where Client: Connection
+{
+    /// Adds a statement to the block, statements run in the order they were added
+    ///
+    /// Example:
+    /// ```rust
+    /// use surrealdb::engine::any::connect;
+    /// use surrealdb::sql::{Operator, Value};
+    /// use surrealdb_extra::query::statement::StatementBuilder;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let db = connect("mem://").await.unwrap();
+    ///
+    ///     db.foreach_builder().for_in("item", "$items").statement(db.create_builder().what("test").set(vec![("value", Operator::Equal, Value::Param("item".into()))]));
+    ///     // The above builder becomes `FOR $item IN $items { CREATE test SET value = $item; }`
+    /// }
+    /// ```
+    pub fn statement(mut self, statement: impl Into<ExtraStatement>) -> Self {
+        self.statement.block.0.push(statement.into().into());
+
+        self
+    }
+
+    /// This function is for `LET $name = ...` inside the block
+    pub fn let_param(self, name: impl Into<String>, value: impl Into<ExtraSubquery>) -> Self {
+        let mut set = SetStatement::default();
+        set.name = name.into().trim_start_matches('$').to_string();
+        set.what = value.into().0;
+
+        self.statement(set)
+    }
+
+    /// The SurrealQL that `to_query` runs, parameters stay `$name` placeholders for the bindings
+    pub fn to_surql(&self) -> String {
+        self.statement.to_string()
+    }
+
+    pub fn to_query(self) -> Query<'r, Client> {
+        self.db.query(Statement::Foreach(self.statement))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use surrealdb::sql::{value, Field, Operator, Param, Value};
+    use crate::query::statement::StatementBuilder;
+    use crate::testing::db;
+
+    #[tokio::test]
+    async fn for_in() {
+        let db = db().await;
+
+        let query = db.foreach_builder()
+            .for_in("$item", "$items")
+            .let_param("double", "$item * 2")
+            .statement(db.create_builder().what("test").set(vec![("n", Operator::Equal, Value::Param("double".into()))]));
+
+        assert_eq!(query.statement.to_string(), "FOR $item IN $items {\nLET $double = $item * 2;\nCREATE test SET n = $double;\n}");
+    }
+
+    #[tokio::test]
+    async fn backfill() {
+        let db = db().await;
+
+        db.query("CREATE test:1 SET n = 1; CREATE test:2 SET n = 2").await.unwrap().check().unwrap();
+
+        let backfill = db.foreach_builder()
+            .for_in("record", db.select_builder().what("test").field(Field::All))
+            .statement(db.update_builder().what(Param::from("record")).set(vec![("doubled", Operator::Equal, value("$record.n * 2").unwrap())]));
+
+        db.script_builder().begin().statement(backfill).commit().to_query().await.unwrap().check().unwrap();
+
+        let mut res = db.query("SELECT VALUE doubled FROM test ORDER BY doubled").await.unwrap();
+        let double: Vec<i64> = res.take(0).unwrap();
+
+        assert_eq!(double, vec![2, 4]);
+    }
+
+    #[tokio::test]
+    async fn foreach_to_surql() {
+        let db = db().await;
+
+        let query = db.foreach_builder().for_in("$item", "$items").let_param("double", "$item * 2");
+
+        assert_eq!(query.to_surql(), "FOR $item IN $items { LET $double = $item * 2; }");
+    }
+}
@@ -0,0 +1,278 @@
+//! # Calling and defining `fn::` functions
+//!
+//! `FunctionBuilder` calls a function with arguments that are serialized from rust values, `execute` deserializes the return value.
+//! `DefineFunctionBuilder` defines the function with typed arguments.
+//!
+//! ```rust
+//! use surrealdb::engine::any::connect;
+//! use surrealdb_extra::query::statement::StatementBuilder;
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let db = connect("mem://").await.unwrap();
+//!     db.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     // This becomes `DEFINE FUNCTION OVERWRITE fn::greet($name: string, $times: int) -> string { RETURN string::repeat('Hello ' + $name + '! ', $times); } PERMISSIONS FULL`
+//!     db.define_function_builder("greet")
+//!         .arg("name", "string").unwrap()
+//!         .arg("times", "int").unwrap()
+//!         .returns("string").unwrap()
+//!         .body("RETURN string::repeat('Hello ' + $name + '! ', $times);").unwrap()
+//!         .overwrite()
+//!         .to_query().await.unwrap();
+//!
+//!     // This becomes `RETURN fn::greet('Tobie', 2)`
+//!     let greeting: String = db.function_builder("greet").arg("Tobie").unwrap().arg(2).unwrap().execute().await.unwrap();
+//!
+//!     assert_eq!(greeting, "Hello Tobie! Hello Tobie! ");
+//! }
+//! ```
+//!
+//! ## Click on the structs for more info
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use surrealdb::{Connection, Surreal};
+use surrealdb::method::Query;
+use surrealdb::sql::{from_value, to_value, Function, Ident, Statement, Strand, Value};
+use surrealdb::sql::statements::{DefineFunctionStatement, DefineStatement, OutputStatement};
+use thiserror::Error;
+use crate::query::parsing::kind::ExtraKind;
+
+#[derive(Debug, Error)]
+pub enum FunctionError {
+    #[error("{0}")]
+    Db(#[from] surrealdb::Error),
+}
+
+impl From<surrealdb::err::Error> for FunctionError {
+    fn from(value: surrealdb::err::Error) -> Self {
+        Self::Db(value.into())
+    }
+}
+
+/// The name without the `fn::` prefix
+fn function_name(name: &str) -> String {
+    name.strip_prefix("fn::").unwrap_or(name).to_string()
+}
+
+#[derive(Debug, Clone)]
+pub struct FunctionBuilder<'r, Client>
+    where Client: Connection
+{
+    pub statement: Function,
+    pub(crate) db: &'r Surreal<Client>,
+}
+
+impl<'r, Client> FunctionBuilder<'r, Client>
+    where Client: Connection
+{
+    /// The name can be with or without the `fn::` prefix
+    pub fn new(db: &'r Surreal<Client>, name: impl Into<String>) -> Self {
+        Self {
+            statement: Function::Custom(function_name(&name.into()), vec![]),
+            db,
+        }
+    }
+
+    /// Adds the next argument, it is serialized the same way as the content of a record, an argument that can't be serialized is an error
+    ///
+    /// Example:
+    /// ```rust
+    /// use surrealdb::engine::any::connect;
+    /// use surrealdb::sql::Thing;
+    /// use surrealdb_extra::query::statement::StatementBuilder;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let db = connect("mem://").await.unwrap();
+    ///
+    ///     db.function_builder("fn::find").arg(Thing::from(("user", "tobie"))).unwrap().arg(vec!["name", "email"]).unwrap();
+    ///     // The above builder becomes `RETURN fn::find(user:tobie, ['name', 'email'])`
+    /// }
+    /// ```
+    pub fn arg(mut self, arg: impl Serialize + 'static) -> anyhow::Result<Self> {
+        let val = to_value(arg).map_err(FunctionError::from)?;
+
+        if let Function::Custom(_, args) = &mut self.statement {
+            args.push(val);
+        }
+
+        Ok(self)
+    }
+
+    /// Adds the arguments in order
+    pub fn args<T: Serialize + 'static>(self, args: impl IntoIterator<Item = T>) -> anyhow::Result<Self> {
+        args.into_iter().try_fold(self, |builder, arg| builder.arg(arg))
+    }
+
+    /// The SurrealQL that `to_query` runs, parameters stay `$name` placeholders for the bindings
+    pub fn to_surql(&self) -> String {
+        format!("RETURN {}", self.statement)
+    }
+
+    pub fn to_query(self) -> Query<'r, Client> {
+        let mut statement = OutputStatement::default();
+        statement.what = Value::Function(Box::new(self.statement));
+
+        self.db.query(Statement::Output(statement))
+    }
+
+    /// Calls the function and deserializes the return value into `T`
+    pub async fn execute<T: DeserializeOwned>(self) -> anyhow::Result<T> {
+        let mut res = self.to_query().await.map_err(FunctionError::from)?;
+
+        let val: surrealdb::Value = res.take(0).map_err(FunctionError::from)?;
+
+        Ok(from_value(val.into_inner()).map_err(FunctionError::from)?)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DefineFunctionBuilder<'r, Client>
+    where Client: Connection
+{
+    pub statement: DefineFunctionStatement,
+    pub(crate) db: &'r Surreal<Client>,
+}
+
+impl<'r, Client> DefineFunctionBuilder<'r, Client>
+    where Client: Connection
+{
+    /// The name can be with or without the `fn::` prefix
+    pub fn new(db: &'r Surreal<Client>, name: impl Into<String>) -> Self {
+        let mut statement = DefineFunctionStatement::default();
+        statement.name = Ident::from(function_name(&name.into()));
+
+        Self {
+            statement,
+            db,
+        }
+    }
+
+    /// Adds the next argument with its type, the name is without `$`, a type that can't be parsed is an error
+    ///
+    /// Example:
+    /// ```rust
+    /// use surrealdb::engine::any::connect;
+    /// use surrealdb::sql::Kind;
+    /// use surrealdb_extra::query::statement::StatementBuilder;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let db = connect("mem://").await.unwrap();
+    ///
+    ///     db.define_function_builder("add").arg("a", "int").unwrap().arg("b", Kind::Int).unwrap().body("RETURN $a + $b;").unwrap();
+    ///     // The above builder becomes `DEFINE FUNCTION fn::add($a: int, $b: int) { RETURN $a + $b; } PERMISSIONS FULL`
+    /// }
+    /// ```
+    pub fn arg<K>(mut self, name: impl Into<String>, kind: K) -> anyhow::Result<Self>
+        where K: TryInto<ExtraKind>, K::Error: Into<anyhow::Error>
+    {
+        let name = name.into();
+        let name = name.strip_prefix('$').unwrap_or(&name).to_string();
+
+        self.statement.args.push((Ident::from(name), kind.try_into().map_err(Into::into)?.0));
+
+        Ok(self)
+    }
+
+    /// This function is for `-> type`, the return value is checked against the type
+    pub fn returns<K>(mut self, kind: K) -> anyhow::Result<Self>
+        where K: TryInto<ExtraKind>, K::Error: Into<anyhow::Error>
+    {
+        self.statement.returns = Some(kind.try_into().map_err(Into::into)?.0);
+
+        Ok(self)
+    }
+
+    /// The SurrealQL statements of the function, with or without the surrounding `{}`, a body that can't be parsed is an error
+    pub fn body(mut self, body: impl Into<String>) -> anyhow::Result<Self> {
+        let body = body.into();
+        let body = body.trim();
+
+        let block = match body.starts_with('{') {
+            true => surrealdb::syn::block(body),
+            false => surrealdb::syn::block(&format!("{{ {body} }}")),
+        };
+
+        self.statement.block = block.map_err(FunctionError::from)?;
+
+        Ok(self)
+    }
+
+    pub fn comment(mut self, comment: impl Into<String>) -> Self {
+        self.statement.comment = Some(Strand::from(comment.into()));
+
+        self
+    }
+
+    pub fn overwrite(mut self) -> Self {
+        self.statement.overwrite = true;
+        self.statement.if_not_exists = false;
+
+        self
+    }
+
+    pub fn if_not_exists(mut self) -> Self {
+        self.statement.if_not_exists = true;
+        self.statement.overwrite = false;
+
+        self
+    }
+
+    /// The SurrealQL that `to_query` runs, parameters stay `$name` placeholders for the bindings
+    pub fn to_surql(&self) -> String {
+        self.statement.to_string()
+    }
+
+    pub fn to_query(self) -> Query<'r, Client> {
+        self.db.query(Statement::Define(DefineStatement::Function(self.statement)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde::{Deserialize, Serialize};
+    use surrealdb::engine::any::connect;
+    use crate::query::statement::StatementBuilder;
+    use crate::testing::db;
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct Point {
+        x: i64,
+        y: i64,
+    }
+
+    #[tokio::test]
+    async fn define_function() {
+        let db = connect("mem://").await.unwrap();
+
+        let query = db.define_function_builder("fn::add").arg("$a", "int").unwrap().arg("b", "option<int>").unwrap().returns("int").unwrap()
+            .body("{ RETURN $a + ($b ?? 0); }").unwrap().comment("adds").if_not_exists();
+
+        assert_eq!(query.statement.to_string(), "DEFINE FUNCTION IF NOT EXISTS fn::add($a: int, $b: option<int>) -> int { RETURN $a + ($b ?? 0); } COMMENT 'adds' PERMISSIONS FULL");
+
+        assert!(db.define_function_builder("bad").arg("a", "not a kind").is_err());
+        assert!(db.define_function_builder("bad").returns("not a kind").is_err());
+        assert!(db.define_function_builder("bad").body("RETURN (;").is_err());
+    }
+
+    #[tokio::test]
+    async fn call_function() {
+        let db = db().await;
+
+        db.define_function_builder("move").arg("point", "object").unwrap().arg("by", "int").unwrap()
+            .body("RETURN { x: $point.x + $by, y: $point.y + $by };").unwrap()
+            .to_query().await.unwrap().check().unwrap();
+
+        let query = db.function_builder("move").arg(Point { x: 1, y: 2 }).unwrap().arg(3).unwrap();
+        assert_eq!(query.to_surql(), "RETURN fn::move({ x: 1, y: 2 }, 3)");
+
+        let point: Point = query.execute().await.unwrap();
+        assert_eq!(point, Point { x: 4, y: 5 });
+
+        let res: anyhow::Result<Point> = db.function_builder("move").args([1]).unwrap().execute().await;
+        assert!(res.is_err());
+    }
+}
@@ -0,0 +1,271 @@
+//! # Starting the builder can be done in 2 ways
+//!
+//! ## Using the `Surrealdb<C>` type
+//! ```rust
+//! use surrealdb::engine::any::connect;
+//! use surrealdb_extra::query::statement::StatementBuilder;
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let db = connect("mem://").await.unwrap();
+//!     db.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     let builder = db.ifelse_builder();
+//!
+//!     let query = builder.if_then("$n > 1", "'many'").else_then("'one'").to_query();
+//! }
+//! ```
+//!
+//! ## Using new function inside the builder and passing a reference of type `Surrealdb<C>`
+//! ```rust
+//! use surrealdb::engine::any::connect;
+//! use surrealdb_extra::query::ifelse::IfElseBuilder;
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let db = connect("mem://").await.unwrap();
+//!     db.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     let builder = IfElseBuilder::new(&db);
+//!
+//!     let query = builder.if_then("$n > 1", "'many'").else_then("'one'").to_query();
+//! }
+//! ```
+//!
+//! # The branches can be other builders and the builder can be used as a value inside other builders
+//! ```rust
+//! use surrealdb::engine::any::connect;
+//! use surrealdb::sql::{Operator, Value};
+//! use surrealdb_extra::query::statement::StatementBuilder;
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let db = connect("mem://").await.unwrap();
+//!     db.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     let status = db.ifelse_builder().if_then("$stock > 0", "'available'").else_then("'sold out'");
+//!
+//!     db.update_builder().what("product").set(vec![("status", Operator::Equal, Value::from(status))]).all_records().to_query();
+//!     // The above builder becomes `UPDATE product SET status = IF $stock > 0 THEN 'available' ELSE 'sold out' END`
+//!
+//!     let create = db.create_builder().what("audit").set(vec![("action", Operator::Equal, "restock")]);
+//!
+//!     db.ifelse_builder().if_then("$stock = 0", create).to_query();
+//!     // The above builder becomes `IF $stock = 0 THEN (CREATE audit SET action = 'restock') END`
+//! }
+//! ```
+//!
+//! # For binding first convert the builder to a `Query<>` type and do binding as usual
+//!
+//! ## Click on the struct for more info
+
+use std::marker::PhantomData;
+use surrealdb::{Connection, Surreal};
+use surrealdb::method::Query;
+use surrealdb::sql::{Subquery, Value};
+use surrealdb::sql::statements::IfelseStatement;
+use crate::query::parsing::cond::ExtraCond;
+use crate::query::parsing::subquery::ExtraSubquery;
+use crate::query::states::{FilledCond, NoCond};
+
+#[derive(Debug, Clone)]
+pub struct IfElseBuilder<'r, Client, C>
+    where Client: Connection
+{
+    pub statement: IfelseStatement,
+    pub(crate) db: &'r Surreal<Client>,
+    pub(crate) cond_state: PhantomData<C>,
+}
+
+impl<'r, Client> IfElseBuilder<'r, Client, NoCond>
+    where Client: Connection
+{
+    pub fn new(db: &'r Surreal<Client>) -> Self {
+        Self {
+            statement: Default::default(),
+            db,
+            cond_state: Default::default(),
+        }
+    }
+
+    /// This function is for `IF ... THEN ...`
+    ///
+    /// Example:
+    /// ```rust
+    /// use surrealdb::engine::any::connect;
+    /// use surrealdb_extra::query::statement::StatementBuilder;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let db = connect("mem://").await.unwrap();
+    ///
+    ///     db.ifelse_builder().if_then("$test", "$test1");
+    ///     // The above builder becomes `IF $test THEN $test1 END`
+    ///
+    ///     db.ifelse_builder().if_then("$test", db.select_builder().what("test").field("test"));
+    ///     // The above builder becomes `IF $test THEN (SELECT test FROM test) END`
+    /// }
+    /// ```
+    ///
+    /// You can also use the Cond/Value type inside surrealdb for more complex requests
+    pub fn if_then(self, cond: impl Into<ExtraCond>, then: impl Into<ExtraSubquery>) -> IfElseBuilder<'r, Client, FilledCond> {
+        let Self { mut statement, db, .. } = self;
+
+        statement.exprs.push((cond.into().0.0, then.into().0));
+
+        IfElseBuilder {
+            statement,
+            db,
+            cond_state: Default::default(),
+        }
+    }
+}
+
+impl<'r, Client> IfElseBuilder<'r, Client, FilledCond>
+    where Client: Connection
+{
+    /// This function is for `ELSE IF ... THEN ...`
+    ///
+    /// Example:
+    /// ```rust
+    /// use surrealdb::engine::any::connect;
+    /// use surrealdb_extra::query::statement::StatementBuilder;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let db = connect("mem://").await.unwrap();
+    ///
+    ///     db.ifelse_builder().if_then("$test", "$test1").else_if("$test2", "$test3");
+    ///     // The above builder becomes `IF $test THEN $test1 ELSE IF $test2 THEN $test3 END`
+    /// }
+    /// ```
+    pub fn else_if(self, cond: impl Into<ExtraCond>, then: impl Into<ExtraSubquery>) -> Self {
+        let Self { mut statement, db, .. } = self;
+
+        statement.exprs.push((cond.into().0.0, then.into().0));
+
+        Self {
+            statement,
+            db,
+            cond_state: Default::default(),
+        }
+    }
+
+    /// This function is for `ELSE ...`, calling it again replaces the else branch
+    ///
+    /// Example:
+    /// ```rust
+    /// use surrealdb::engine::any::connect;
+    /// use surrealdb_extra::query::statement::StatementBuilder;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let db = connect("mem://").await.unwrap();
+    ///
+    ///     db.ifelse_builder().if_then("$test", "$test1").else_then("$test2");
+    ///     // The above builder becomes `IF $test THEN $test1 ELSE $test2 END`
+    /// }
+    /// ```
+    pub fn else_then(self, then: impl Into<ExtraSubquery>) -> Self {
+        let Self { mut statement, db, .. } = self;
+
+        statement.close = Some(then.into().0);
+
+        Self {
+            statement,
+            db,
+            cond_state: Default::default(),
+        }
+    }
+
+    /// The SurrealQL that `to_query` runs, parameters stay `$name` placeholders for the bindings
+    pub fn to_surql(&self) -> String {
+        self.statement.to_string()
+    }
+
+    pub fn to_query(self) -> Query<'r, Client> {
+        self.db.query(self.statement)
+    }
+}
+
+impl<'r, Client> From<IfElseBuilder<'r, Client, FilledCond>> for Value
+    where Client: Connection
+{
+    fn from(value: IfElseBuilder<'r, Client, FilledCond>) -> Self {
+        Value::Subquery(Box::new(Subquery::Ifelse(value.statement)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use surrealdb::opt::IntoQuery;
+    use surrealdb::sql::{Operator, Statement};
+    use crate::query::statement::StatementBuilder;
+    use crate::testing::db;
+    use super::*;
+
+    #[tokio::test]
+    async fn if_then() {
+        let db = db().await;
+
+        let query = db.ifelse_builder().if_then("$test", "$test1");
+
+        let query_str = query.statement.into_query().unwrap();
+
+        let statement = &query_str[0];
+        assert!(matches!(statement, Statement::Ifelse(_)));
+        assert_eq!(statement.to_string(), "IF $test THEN $test1 END");
+    }
+
+    #[tokio::test]
+    async fn else_if_else() {
+        let db = db().await;
+
+        let query = db.ifelse_builder()
+            .if_then("$test", "$test1")
+            .else_if("$test2", "$test3")
+            .else_then("$test4");
+
+        assert_eq!(query.statement.to_string(), "IF $test THEN $test1 ELSE IF $test2 THEN $test3 ELSE $test4 END");
+    }
+
+    #[tokio::test]
+    async fn builder_branch() {
+        let db = db().await;
+
+        let create = db.create_builder().what("test").set(vec![("name", Operator::Equal, Value::Param("name".into()))]);
+
+        let query = db.ifelse_builder().if_then("$name != NONE", create).else_then("NONE");
+
+        query.to_query().bind(("name", "test")).await.unwrap().check().unwrap();
+
+        let mut res = db.query("SELECT VALUE name FROM test").await.unwrap();
+        let names: Vec<String> = res.take(0).unwrap();
+
+        assert_eq!(names, vec!["test".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn as_value() {
+        let db = db().await;
+
+        let status = db.ifelse_builder().if_then("$stock > 0", "'available'").else_then("'sold out'");
+
+        db.create_builder().what("product").set(vec![("status", Operator::Equal, Value::from(status))])
+            .to_query().bind(("stock", 0)).await.unwrap().check().unwrap();
+
+        let mut res = db.query("SELECT VALUE status FROM product").await.unwrap();
+        let status: Vec<String> = res.take(0).unwrap();
+
+        assert_eq!(status, vec!["sold out".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn ifelse_to_surql() {
+        let db = db().await;
+
+        let query = db.ifelse_builder().if_then("$test", "$test1").else_then("$test2");
+
+        assert_eq!(query.to_surql(), "IF $test THEN $test1 ELSE $test2 END");
+    }
+}
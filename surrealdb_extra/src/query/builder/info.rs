@@ -0,0 +1,245 @@
+//! # Starting the builder can be done in 2 ways
+//!
+//! ## Using the `Surrealdb<C>` type
+//! ```rust
+//! use surrealdb::engine::any::connect;
+//! use surrealdb_extra::query::statement::StatementBuilder;
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let db = connect("mem://").await.unwrap();
+//!     db.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     db.query("DEFINE TABLE user; DEFINE FIELD email ON user TYPE string; DEFINE INDEX user_email ON user FIELDS email UNIQUE").await.unwrap();
+//!
+//!     let db_info = db.info_builder().execute().await.unwrap();
+//!     assert!(db_info.tables.contains_key("user"));
+//!
+//!     let table_info = db.info_builder().table("user").execute().await.unwrap();
+//!     assert_eq!(table_info.fields["email"], "DEFINE FIELD email ON user TYPE string PERMISSIONS FULL");
+//!     assert!(table_info.indexes.contains_key("user_email"));
+//! }
+//! ```
+//!
+//! ## Using new function inside the builder and passing a reference of type `Surrealdb<C>`
+//! ```rust
+//! use surrealdb::engine::any::connect;
+//! use surrealdb_extra::query::info::InfoBuilder;
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let db = connect("mem://").await.unwrap();
+//!     db.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     db.query("DEFINE DATABASE other").await.unwrap();
+//!
+//!     let ns_info = InfoBuilder::new(&db).ns().execute().await.unwrap();
+//!     assert!(ns_info.databases.contains_key("other"));
+//! }
+//! ```
+//!
+//! ## Click on the struct for more info
+
+use std::collections::BTreeMap;
+use std::marker::PhantomData;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use surrealdb::{Connection, Surreal};
+use surrealdb::method::Query;
+use surrealdb::sql::{from_value, Ident, Statement};
+use surrealdb::sql::statements::InfoStatement;
+use thiserror::Error;
+use crate::query::states::{ForDb, ForNs, ForTable};
+
+#[derive(Debug, Error)]
+pub enum InfoError {
+    #[error("{0}")]
+    Db(#[from] surrealdb::Error),
+}
+
+impl From<surrealdb::err::Error> for InfoError {
+    fn from(value: surrealdb::err::Error) -> Self {
+        Self::Db(value.into())
+    }
+}
+
+/// `INFO FOR NS`, every map goes from the name to the `DEFINE` statement
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct NsInfo {
+    pub accesses: BTreeMap<String, String>,
+    pub databases: BTreeMap<String, String>,
+    pub users: BTreeMap<String, String>,
+}
+
+/// `INFO FOR DB`, every map goes from the name to the `DEFINE` statement
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct DbInfo {
+    pub accesses: BTreeMap<String, String>,
+    pub analyzers: BTreeMap<String, String>,
+    pub functions: BTreeMap<String, String>,
+    pub models: BTreeMap<String, String>,
+    pub params: BTreeMap<String, String>,
+    pub tables: BTreeMap<String, String>,
+    pub users: BTreeMap<String, String>,
+}
+
+/// `INFO FOR TABLE`, every map goes from the name to the `DEFINE` statement
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct TableInfo {
+    pub events: BTreeMap<String, String>,
+    pub fields: BTreeMap<String, String>,
+    pub indexes: BTreeMap<String, String>,
+    pub lives: BTreeMap<String, String>,
+    /// Foreign tables that are views of this table
+    pub tables: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct InfoBuilder<'r, Client, K>
+    where Client: Connection
+{
+    pub statement: InfoStatement,
+    pub(crate) db: &'r Surreal<Client>,
+    pub(crate) kind_state: PhantomData<K>,
+}
+
+impl<'r, Client> InfoBuilder<'r, Client, ForDb>
+    where Client: Connection
+{
+    /// Starts with `INFO FOR DB`
+    pub fn new(db: &'r Surreal<Client>) -> Self {
+        Self {
+            statement: InfoStatement::Db(false, None),
+            db,
+            kind_state: Default::default(),
+        }
+    }
+
+    /// Runs `INFO FOR DB`
+    pub async fn execute(self) -> anyhow::Result<DbInfo> {
+        self.execute_as().await
+    }
+}
+
+impl<'r, Client> InfoBuilder<'r, Client, ForNs>
+    where Client: Connection
+{
+    /// Runs `INFO FOR NS`
+    pub async fn execute(self) -> anyhow::Result<NsInfo> {
+        self.execute_as().await
+    }
+}
+
+impl<'r, Client> InfoBuilder<'r, Client, ForTable>
+    where Client: Connection
+{
+    /// Runs `INFO FOR TABLE`
+    pub async fn execute(self) -> anyhow::Result<TableInfo> {
+        self.execute_as().await
+    }
+}
+
+impl<'r, Client, K> InfoBuilder<'r, Client, K>
+    where Client: Connection
+{
+    /// This function is for `INFO FOR NS`
+    pub fn ns(self) -> InfoBuilder<'r, Client, ForNs> {
+        InfoBuilder {
+            statement: InfoStatement::Ns(false),
+            db: self.db,
+            kind_state: Default::default(),
+        }
+    }
+
+    /// This function is for `INFO FOR DB`
+    pub fn db(self) -> InfoBuilder<'r, Client, ForDb> {
+        InfoBuilder {
+            statement: InfoStatement::Db(false, None),
+            db: self.db,
+            kind_state: Default::default(),
+        }
+    }
+
+    /// This function is for `INFO FOR TABLE`
+    ///
+    /// Example:
+    /// ```rust
+    /// use surrealdb::engine::any::connect;
+    /// use surrealdb_extra::query::statement::StatementBuilder;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let db = connect("mem://").await.unwrap();
+    ///
+    ///     db.info_builder().table("user");
+    ///     // The above builder becomes `INFO FOR TABLE user`
+    /// }
+    /// ```
+    pub fn table(self, table: impl Into<String>) -> InfoBuilder<'r, Client, ForTable> {
+        InfoBuilder {
+            statement: InfoStatement::Tb(Ident::from(table.into()), false, None),
+            db: self.db,
+            kind_state: Default::default(),
+        }
+    }
+
+    /// The SurrealQL that `to_query` runs
+    pub fn to_surql(&self) -> String {
+        self.statement.to_string()
+    }
+
+    pub fn to_query(self) -> Query<'r, Client> {
+        self.db.query(Statement::Info(self.statement))
+    }
+
+    async fn execute_as<T: DeserializeOwned>(self) -> anyhow::Result<T> {
+        let mut res = self.to_query().await.map_err(InfoError::from)?;
+
+        let info: surrealdb::Value = res.take(0).map_err(InfoError::from)?;
+
+        Ok(from_value(info.into_inner()).map_err(InfoError::from)?)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use surrealdb::engine::any::connect;
+    use crate::query::statement::StatementBuilder;
+    use crate::testing::db;
+
+    #[tokio::test]
+    async fn info_to_surql() {
+        let db = connect("mem://").await.unwrap();
+
+        assert_eq!(db.info_builder().to_surql(), "INFO FOR DATABASE");
+        assert_eq!(db.info_builder().ns().to_surql(), "INFO FOR NAMESPACE");
+        assert_eq!(db.info_builder().table("user").to_surql(), "INFO FOR TABLE user");
+    }
+
+    #[tokio::test]
+    async fn table_info() {
+        let db = db().await;
+
+        db.query("
+            DEFINE TABLE user SCHEMAFULL;
+            DEFINE FIELD name ON user TYPE string;
+            DEFINE INDEX user_name ON user FIELDS name;
+            DEFINE EVENT user_created ON user WHEN $event = 'CREATE' THEN {};
+            DEFINE FUNCTION fn::noop() { RETURN NONE; };
+        ").await.unwrap().check().unwrap();
+
+        let info = db.info_builder().table("user").execute().await.unwrap();
+
+        assert_eq!(info.fields.keys().collect::<Vec<_>>(), ["name"]);
+        assert_eq!(info.indexes.keys().collect::<Vec<_>>(), ["user_name"]);
+        assert_eq!(info.events.keys().collect::<Vec<_>>(), ["user_created"]);
+
+        let info = db.info_builder().execute().await.unwrap();
+
+        assert_eq!(info.tables.keys().collect::<Vec<_>>(), ["user"]);
+        assert_eq!(info.functions.keys().collect::<Vec<_>>(), ["noop"]);
+    }
+}
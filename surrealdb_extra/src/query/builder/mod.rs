@@ -1,4 +1,13 @@
 pub mod select;
+pub mod dyn_select;
+pub mod report;
+pub mod combine;
 pub mod update;
 pub mod relate;
 pub mod create;
+pub mod ifelse;
+pub mod script;
+pub mod foreach;
+pub mod show;
+pub mod function;
+pub mod info;
@@ -204,31 +204,54 @@ impl<'r, Client, D> RelateBuilder<'r, Client, FilledRelation, D>
         }
     }
 
+    /// The SurrealQL that `to_query` runs, parameters stay `$name` placeholders for the bindings
+    pub fn to_surql(&self) -> String {
+        let statement = crate::query::config::apply(self.statement.clone());
+
+        statement.to_string()
+    }
+
     pub fn to_query(self) -> Query<'r, Client> {
-        self.db.query(self.statement)
+        let statement = crate::query::config::apply(self.statement);
+
+        self.db.query(statement)
+    }
+
+    /// Runs the query with the observers, see the `observer` module
+    pub fn observed(self) -> crate::query::observer::Observed<'r, Client> {
+        let info = crate::query::observer::QueryInfo::new("RELATE", [&self.statement.kind], self.to_surql());
+
+        crate::query::observer::Observed::new(info, self.to_query())
+    }
+
+    /// Runs only this statement in another namespace and database, see the `scope` module
+    pub fn use_ns_db(self, ns: impl Into<String>, database: impl Into<String>) -> crate::query::scope::Scoped<'r, Client> {
+        let statement = crate::query::config::apply(self.statement);
+
+        crate::query::scope::Scoped::new(self.db, ns, database, statement)
+    }
+
+    /// Same as `to_query` but drops the clauses the engine doesn't support, see `Capabilities`
+    #[cfg_attr(docsrs, doc(cfg(feature = "fallback")))]
+    #[cfg(feature = "fallback")]
+    pub fn to_query_with(mut self, capabilities: &crate::query::fallback::Capabilities) -> anyhow::Result<Query<'r, Client>> {
+        self.statement = crate::query::fallback::apply(self.statement, capabilities)?;
+
+        Ok(self.to_query())
     }
 
 }
 
 #[cfg(test)]
 mod test {
-    use surrealdb::engine::any::{Any, connect};
     use surrealdb::opt::IntoQuery;
     use surrealdb::sql::Thing as RecordId;
     use surrealdb::sql::Operator;
-    use surrealdb::Surreal;
     use crate::query::statement::StatementBuilder;
 
+    use crate::testing::db;
     use super::*;
 
-    async fn db() -> Surreal<Any> {
-        let db = connect("mem://").await.unwrap();
-
-        db.use_ns("test").use_db("test").await.unwrap();
-
-        db
-    }
-
     #[tokio::test]
     async fn relate_table() {
         let db = db().await;
@@ -272,4 +295,13 @@ mod test {
 
         assert!(query.is_ok());
     }
+
+    #[tokio::test]
+    async fn relate_to_surql() {
+        let db = db().await;
+
+        let relate = db.relate_builder().relation(RecordId::from(("test", "test")), "test", RecordId::from(("test2", "test2")));
+
+        assert_eq!(relate.to_surql(), "RELATE test:test -> test -> test2:test2");
+    }
 }
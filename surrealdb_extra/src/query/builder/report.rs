@@ -0,0 +1,414 @@
+//! # Aggregate reports with typed rows
+//!
+//! `ReportBuilder` selects the `group_by` fields and the named `aggregate`s of a table and groups the rows by the fields,
+//! without `group_by` every row is aggregated into one with `GROUP ALL`.
+//! `execute` checks that the fields of the row struct are exactly the columns of the report before it runs,
+//! so a renamed column is an error that names the column instead of a failed deserialization.
+//! The check needs a struct with `#[derive(Deserialize)]` without `#[serde(flatten)]`.
+//!
+//! `top_n_per_group` keeps whole records instead, the first `n` records of every group in the given order, as `TopN { key, rows }`.
+//!
+//! ```rust
+//! use serde::Deserialize;
+//! use surrealdb::engine::any::connect;
+//! use surrealdb_extra::query::parsing::aggregate::Agg;
+//! use surrealdb_extra::query::parsing::order::OrderDirection;
+//! use surrealdb_extra::query::statement::StatementBuilder;
+//!
+//! #[derive(Deserialize)]
+//! struct Revenue {
+//!     customer: String,
+//!     orders: i64,
+//!     revenue: f64,
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let db = connect("mem://").await.unwrap();
+//!     db.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     db.query("CREATE order SET customer = 'a', price = 10.0; CREATE order SET customer = 'a', price = 5.0;").await.unwrap();
+//!
+//!     let report = db.report_builder().from("order")
+//!         .group_by("customer")
+//!         .aggregate("orders", Agg::count())
+//!         .aggregate("revenue", Agg::sum("price"))
+//!         .order(("revenue", OrderDirection::DESC));
+//!
+//!     assert_eq!(report.to_surql().unwrap(), "SELECT customer, count() AS orders, math::sum(price) AS revenue FROM order GROUP BY customer ORDER BY revenue DESC");
+//!
+//!     let rows: Vec<Revenue> = report.execute().await.unwrap();
+//!     assert_eq!((rows[0].orders, rows[0].revenue), (2, 15.0));
+//! }
+//! ```
+
+use std::fmt::Display;
+use serde::de::{self, DeserializeOwned, Visitor};
+use serde::{forward_to_deserialize_any, Deserialize};
+use surrealdb::{Connection, Surreal};
+use surrealdb::sql::{Expression, Field, Groups, Operator, Part, Subquery, Value, Values};
+use thiserror::Error;
+use crate::query::dyn_select::{DynSelectBuilder, DynSelectError};
+use crate::query::parsing::aggregate::Agg;
+use crate::query::parsing::cond::ExtraCond;
+use crate::query::parsing::field::ExtraField;
+use crate::query::parsing::idiom::ExtraIdiom;
+use crate::query::parsing::limit::ExtraLimit;
+use crate::query::parsing::order::{ExtraOrder, OrderDirection};
+use crate::query::parsing::start::ExtraStart;
+use crate::query::parsing::str_to_value;
+use crate::query::parsing::what::ExtraValue;
+
+#[derive(Debug, Error)]
+pub enum ReportError {
+    #[error("The report has no columns, `group_by` or `aggregate` is missing")]
+    NoColumns,
+    #[error("The row type is not a struct with named fields")]
+    NotAStruct,
+    #[error("The row field `{0}` is not a column of the report")]
+    MissingColumn(String),
+    #[error("The column `{0}` is not a field of the row type")]
+    UnusedColumn(String),
+    #[error("{0}")]
+    Select(#[from] DynSelectError),
+}
+
+#[derive(Debug, Clone)]
+pub struct ReportBuilder<'r, Client>
+    where Client: Connection
+{
+    pub select: DynSelectBuilder<'r, Client>,
+    /// The group fields and aggregates in the order they were added
+    pub columns: Vec<String>,
+    grouped: bool,
+}
+
+impl<'r, Client> ReportBuilder<'r, Client>
+    where Client: Connection
+{
+    pub fn new(db: &'r Surreal<Client>) -> Self {
+        Self {
+            select: DynSelectBuilder::new(db),
+            columns: vec![],
+            grouped: false,
+        }
+    }
+
+    /// The table or records the report aggregates
+    pub fn from(mut self, what: impl Into<ExtraValue>) -> Self {
+        self.select = self.select.what(what);
+
+        self
+    }
+
+    /// Selects the field and groups by it, the column is the first part of the field e.g. `address` of `address.city`
+    pub fn group_by(mut self, field: &str) -> Self {
+        let idiom = ExtraIdiom::from(field).0;
+
+        self.columns.push(field.split('.').next().unwrap_or(field).to_string());
+        self.select = self.select.field(ExtraField::from(Value::Idiom(idiom))).group(field);
+        self.grouped = true;
+
+        self
+    }
+
+    /// `aggregate AS column`
+    pub fn aggregate(mut self, column: &str, aggregate: Agg) -> Self {
+        self.columns.push(column.to_string());
+        self.select = self.select.field(aggregate.alias(column));
+
+        self
+    }
+
+    /// Filters the rows before they are aggregated, added to the previous conditions with `AND`
+    pub fn condition(mut self, cond: impl Into<ExtraCond>) -> Self {
+        self.select = self.select.condition(cond);
+
+        self
+    }
+
+    pub fn order(mut self, order: impl Into<ExtraOrder>) -> Self {
+        self.select = self.select.order(order);
+
+        self
+    }
+
+    pub fn limit(mut self, limit: impl Into<ExtraLimit>) -> Self {
+        self.select = self.select.limit(limit);
+
+        self
+    }
+
+    pub fn start(mut self, start: impl Into<ExtraStart>) -> Self {
+        self.select = self.select.start(start);
+
+        self
+    }
+
+    /// Checks that the fields of `Row` are the columns of the report
+    pub fn validate<Row: DeserializeOwned>(&self) -> Result<(), ReportError> {
+        if self.columns.is_empty() {
+            return Err(ReportError::NoColumns);
+        }
+
+        let fields = struct_fields::<Row>().ok_or(ReportError::NotAStruct)?;
+
+        if let Some(field) = fields.iter().find(|field| !self.columns.iter().any(|column| column == *field)) {
+            return Err(ReportError::MissingColumn(field.to_string()));
+        }
+
+        if let Some(column) = self.columns.iter().find(|column| !fields.contains(&column.as_str())) {
+            return Err(ReportError::UnusedColumn(column.clone()));
+        }
+
+        Ok(())
+    }
+
+    pub fn to_surql(&self) -> Result<String, ReportError> {
+        if self.columns.is_empty() {
+            return Err(ReportError::NoColumns);
+        }
+
+        Ok(self.statement().to_surql()?)
+    }
+
+    /// Validates the row type and runs the report
+    pub async fn execute<Row: DeserializeOwned>(self) -> anyhow::Result<Vec<Row>> {
+        self.validate::<Row>()?;
+
+        self.statement().execute_values().await
+    }
+
+    /// The first `n` records of every value of `group_field` in the `order`, the conditions of the report filter the records.
+    /// The columns, order, limit and start of the report are not used.
+    ///
+    /// `SELECT group_field AS key, (SELECT * FROM what WHERE group_field = $parent.group_field ORDER BY order LIMIT n) AS rows FROM (SELECT group_field FROM what GROUP BY group_field) ORDER BY key`
+    pub fn top_n_per_group(self, group_field: &str, order: impl Into<ExtraOrder>, n: i64) -> TopNBuilder<'r, Client> {
+        let db = self.select.db;
+        let what = self.select.statement.what.clone();
+        let cond = self.select.statement.cond.clone();
+        let group = ExtraIdiom::from(group_field).0;
+
+        let mut groups = DynSelectBuilder::new(db).field(ExtraField::from(Value::Idiom(group.clone()))).group(group_field);
+        groups.statement.what = what.clone();
+        groups.statement.cond = cond.clone();
+
+        let mut parent = group.clone();
+        parent.0.insert(0, Part::Start(str_to_value("$parent")));
+
+        // The group condition goes first, the later conditions are in parentheses and a subquery binds `$parent` to its own record
+        let mut rows = DynSelectBuilder::new(db).field(Field::All)
+            .condition(Value::Expression(Box::new(Expression::Binary { l: Value::Idiom(group.clone()), o: Operator::Equal, r: Value::Idiom(parent) })));
+        rows.statement.what = what;
+        if let Some(cond) = cond {
+            rows = rows.condition(cond);
+        }
+        rows = rows.order(order).limit(n);
+
+        let mut from = Values::default();
+        from.0 = vec![Value::Subquery(Box::new(Subquery::Select(groups.statement)))];
+
+        let select = DynSelectBuilder::new(db)
+            .what(from)
+            .field((Value::Idiom(group), "key"))
+            .field((Value::Subquery(Box::new(Subquery::Select(rows.statement))), "rows"))
+            .order(("key", OrderDirection::ASC));
+
+        TopNBuilder { select }
+    }
+
+    /// `GROUP ALL` when there are no group fields
+    fn statement(&self) -> DynSelectBuilder<'r, Client> {
+        let mut select = DynSelectBuilder {
+            statement: self.select.statement.clone(),
+            db: self.select.db,
+        };
+
+        if !self.grouped {
+            select.statement.group = Some(Groups::default());
+        }
+
+        select
+    }
+}
+
+/// The records of one group of `top_n_per_group`
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct TopN<K, T> {
+    pub key: K,
+    pub rows: Vec<T>,
+}
+
+#[derive(Debug, Clone)]
+pub struct TopNBuilder<'r, Client>
+    where Client: Connection
+{
+    pub select: DynSelectBuilder<'r, Client>,
+}
+
+impl<'r, Client> TopNBuilder<'r, Client>
+    where Client: Connection
+{
+    pub fn to_surql(&self) -> Result<String, ReportError> {
+        Ok(self.select.to_surql()?)
+    }
+
+    /// Runs the query, the groups are ordered by their key
+    pub async fn execute<K: DeserializeOwned, T: DeserializeOwned>(self) -> anyhow::Result<Vec<TopN<K, T>>> {
+        self.select.execute_values().await
+    }
+}
+
+/// The field names that a derived `Deserialize` asks for, `None` for types that are not structs
+fn struct_fields<T: DeserializeOwned>() -> Option<&'static [&'static str]> {
+    let mut fields = None;
+
+    let _ = T::deserialize(StructFields(&mut fields));
+
+    fields
+}
+
+/// Deserializer that only records the fields of `deserialize_struct` and fails
+struct StructFields<'a>(&'a mut Option<&'static [&'static str]>);
+
+#[derive(Debug)]
+struct StructFieldsError;
+
+impl Display for StructFieldsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("only the struct fields are read")
+    }
+}
+
+impl std::error::Error for StructFieldsError {}
+
+impl de::Error for StructFieldsError {
+    fn custom<T: Display>(_: T) -> Self {
+        Self
+    }
+}
+
+impl<'de> de::Deserializer<'de> for StructFields<'_> {
+    type Error = StructFieldsError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _: V) -> Result<V::Value, Self::Error> {
+        Err(StructFieldsError)
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(self, _: &'static str, fields: &'static [&'static str], _: V) -> Result<V::Value, Self::Error> {
+        *self.0 = Some(fields);
+
+        Err(StructFieldsError)
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map enum identifier ignored_any
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde::Deserialize;
+    use crate::query::parsing::order::OrderDirection;
+    use crate::query::statement::StatementBuilder;
+    use crate::testing::db;
+    use super::*;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct CategoryStats {
+        category: String,
+        count: i64,
+        #[serde(rename = "max")]
+        max_price: f64,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Total {
+        total: f64,
+    }
+
+    #[test]
+    fn row_fields() {
+        assert_eq!(struct_fields::<CategoryStats>(), Some(&["category", "count", "max"][..]));
+        assert_eq!(struct_fields::<i64>(), None);
+    }
+
+    #[tokio::test]
+    async fn report_rows() {
+        let db = db().await;
+
+        db.query("
+            CREATE product SET category = 'a', price = 1.5;
+            CREATE product SET category = 'a', price = 2.5;
+            CREATE product SET category = 'b', price = 4.0;
+        ").await.unwrap().check().unwrap();
+
+        let report = db.report_builder().from("product")
+            .group_by("category")
+            .aggregate("count", Agg::count())
+            .aggregate("max", Agg::math_max("price"))
+            .order(("category", OrderDirection::ASC));
+
+        let rows: Vec<CategoryStats> = report.execute().await.unwrap();
+
+        assert_eq!(rows, [
+            CategoryStats { category: "a".to_string(), count: 2, max_price: 2.5 },
+            CategoryStats { category: "b".to_string(), count: 1, max_price: 4.0 },
+        ]);
+
+        let total = db.report_builder().from("product").aggregate("total", Agg::sum("price")).condition("category = 'a'");
+
+        assert_eq!(total.to_surql().unwrap(), "SELECT math::sum(price) AS total FROM product WHERE category = 'a' GROUP ALL");
+        assert_eq!(total.execute::<Total>().await.unwrap(), [Total { total: 4.0 }]);
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Product {
+        name: String,
+        price: f64,
+    }
+
+    #[tokio::test]
+    async fn top_n_per_group() {
+        let db = db().await;
+
+        db.query("
+            CREATE product SET name = 'a1', category = 'a', price = 1.0;
+            CREATE product SET name = 'a2', category = 'a', price = 3.0;
+            CREATE product SET name = 'a3', category = 'a', price = 2.0;
+            CREATE product SET name = 'b1', category = 'b', price = 5.0;
+            CREATE product SET name = 'c1', category = 'c', price = 9.0, hidden = true;
+        ").await.unwrap().check().unwrap();
+
+        let top = db.report_builder().from("product")
+            .condition("hidden != true")
+            .top_n_per_group("category", ("price", OrderDirection::DESC), 2);
+
+        assert_eq!(
+            top.to_surql().unwrap(),
+            "SELECT category AS key, (SELECT * FROM product WHERE category = $parent.category AND (hidden != true) ORDER BY price DESC LIMIT 2) AS rows \
+            FROM (SELECT category FROM product WHERE hidden != true GROUP BY category) ORDER BY key"
+        );
+
+        let groups: Vec<TopN<String, Product>> = top.execute().await.unwrap();
+
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].key, "a");
+        assert_eq!(groups[0].rows, [Product { name: "a2".to_string(), price: 3.0 }, Product { name: "a3".to_string(), price: 2.0 }]);
+        assert_eq!(groups[1].rows, [Product { name: "b1".to_string(), price: 5.0 }]);
+    }
+
+    #[test]
+    fn report_validation() {
+        let db = surrealdb::Surreal::<surrealdb::engine::any::Any>::init();
+
+        let report = db.report_builder().from("product").group_by("category").aggregate("count", Agg::count());
+
+        assert!(matches!(report.validate::<CategoryStats>(), Err(ReportError::MissingColumn(c)) if c == "max"));
+        assert!(matches!(report.clone().aggregate("max", Agg::math_max("price")).aggregate("min", Agg::math_min("price")).validate::<CategoryStats>(), Err(ReportError::UnusedColumn(c)) if c == "min"));
+        assert!(matches!(report.validate::<i64>(), Err(ReportError::NotAStruct)));
+        assert!(matches!(db.report_builder().from("product").validate::<Total>(), Err(ReportError::NoColumns)));
+    }
+}
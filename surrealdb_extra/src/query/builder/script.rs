@@ -0,0 +1,284 @@
+//! # Starting the builder can be done in 2 ways
+//!
+//! ## Using the `Surrealdb<C>` type
+//! ```rust
+//! use surrealdb::engine::any::connect;
+//! use surrealdb_extra::query::statement::StatementBuilder;
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let db = connect("mem://").await.unwrap();
+//!     db.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     let builder = db.script_builder();
+//!
+//!     let query = builder.let_param("name", "'test'").statement("$name").to_query();
+//! }
+//! ```
+//!
+//! ## Using new function inside the builder and passing a reference of type `Surrealdb<C>`
+//! ```rust
+//! use surrealdb::engine::any::connect;
+//! use surrealdb_extra::query::script::QueryScriptBuilder;
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let db = connect("mem://").await.unwrap();
+//!     db.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     let builder = QueryScriptBuilder::new(&db);
+//!
+//!     let query = builder.let_param("name", "'test'").statement("$name").to_query();
+//! }
+//! ```
+//!
+//! # Chaining `LET` statements and taking typed results
+//!
+//! The statements run in the order they were added, `add` returns the index of the statement in the response
+//! together with the type of its result.
+//!
+//! ```rust
+//! use surrealdb::engine::any::connect;
+//! use surrealdb::sql::Operator;
+//! use surrealdb_extra::query::statement::StatementBuilder;
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let db = connect("mem://").await.unwrap();
+//!     db.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     let mut script = db.script_builder()
+//!         .let_param("user", db.create_builder().what("user").set(vec![("name", Operator::Equal, "name")]));
+//!
+//!     let names = script.add::<Vec<String>>("$user.name");
+//!     // The above builder becomes `LET $user = (CREATE user SET name = 'name'); $user.name`
+//!
+//!     let mut res = script.to_query().await.unwrap();
+//!
+//!     assert_eq!(names.take(&mut res).unwrap(), vec!["name".to_string()]);
+//! }
+//! ```
+//!
+//! # For binding first convert the builder to a `Query<>` type and do binding as usual
+//!
+//! ## Click on the struct for more info
+
+use std::marker::PhantomData;
+use anyhow::Result;
+use surrealdb::{Connection, Response, Surreal};
+use surrealdb::method::Query;
+use serde::de::DeserializeOwned;
+use surrealdb::opt::QueryResult;
+use surrealdb::sql::{Statement, Statements, Value};
+use surrealdb::sql::statements::{BeginStatement, CommitStatement, SetStatement};
+use crate::query::parsing::statement::ExtraStatement;
+use crate::query::parsing::subquery::ExtraSubquery;
+
+#[derive(Debug, Clone)]
+pub struct QueryScriptBuilder<'r, Client>
+    where Client: Connection
+{
+    pub statements: Vec<Statement>,
+    pub(crate) db: &'r Surreal<Client>,
+}
+
+/// Index of a statement in the response of a script, `R` is the type its result is taken as
+#[derive(Debug)]
+pub struct ScriptIndex<R> {
+    pub index: usize,
+    pub(crate) result: PhantomData<fn() -> R>,
+}
+
+impl<R> Clone for ScriptIndex<R> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<R> Copy for ScriptIndex<R> {}
+
+impl<R> ScriptIndex<R>
+    where R: DeserializeOwned, usize: QueryResult<R>
+{
+    pub fn take(self, response: &mut Response) -> Result<R> {
+        Ok(response.take(self.index)?)
+    }
+}
+
+impl<'r, Client> QueryScriptBuilder<'r, Client>
+    where Client: Connection
+{
+    pub fn new(db: &'r Surreal<Client>) -> Self {
+        Self {
+            statements: vec![],
+            db,
+        }
+    }
+
+    /// This function is for `LET $name = ...`
+    ///
+    /// Example:
+    /// ```rust
+    /// use surrealdb::engine::any::connect;
+    /// use surrealdb_extra::query::statement::StatementBuilder;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let db = connect("mem://").await.unwrap();
+    ///
+    ///     db.script_builder().let_param("test", "$test1");
+    ///     // The above builder becomes `LET $test = $test1`
+    ///
+    ///     db.script_builder().let_param("test", db.select_builder().what("test").field("test"));
+    ///     // The above builder becomes `LET $test = (SELECT test FROM test)`
+    /// }
+    /// ```
+    pub fn let_param(mut self, name: impl Into<String>, value: impl Into<ExtraSubquery>) -> Self {
+        let mut set = SetStatement::default();
+        set.name = name.into().trim_start_matches('$').to_string();
+        set.what = value.into().0;
+
+        self.statements.push(Statement::Set(set));
+
+        self
+    }
+
+    /// Adds a statement, builders are added as statements and not as values
+    ///
+    /// Example:
+    /// ```rust
+    /// use surrealdb::engine::any::connect;
+    /// use surrealdb_extra::query::statement::StatementBuilder;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let db = connect("mem://").await.unwrap();
+    ///
+    ///     db.script_builder().let_param("test", "$test1").statement(db.select_builder().what("$test").field("test"));
+    ///     // The above builder becomes `LET $test = $test1; SELECT test FROM $test`
+    /// }
+    /// ```
+    pub fn statement(mut self, statement: impl Into<ExtraStatement>) -> Self {
+        self.add::<Value>(statement);
+
+        self
+    }
+
+    /// Same as `statement` but returns the index of the added statement to take its result from the response
+    pub fn add<R>(&mut self, statement: impl Into<ExtraStatement>) -> ScriptIndex<R> {
+        self.statements.push(statement.into().0);
+
+        self.last_index()
+    }
+
+    /// Index of the last added statement in the response,
+    /// `BEGIN`, `COMMIT`, `CANCEL` and `OPTION` have no result in the response and are not counted
+    pub fn last_index<R>(&self) -> ScriptIndex<R> {
+        let results = self.statements.iter()
+            .filter(|statement| !matches!(statement, Statement::Begin(_) | Statement::Commit(_) | Statement::Cancel(_) | Statement::Option(_)))
+            .count();
+
+        ScriptIndex {
+            index: results.saturating_sub(1),
+            result: PhantomData,
+        }
+    }
+
+    /// This function is for `BEGIN TRANSACTION`, the statements after it up to `commit` run in one transaction
+    pub fn begin(mut self) -> Self {
+        self.statements.push(Statement::Begin(BeginStatement::default()));
+
+        self
+    }
+
+    /// This function is for `COMMIT TRANSACTION`
+    pub fn commit(mut self) -> Self {
+        self.statements.push(Statement::Commit(CommitStatement::default()));
+
+        self
+    }
+
+    /// The SurrealQL that `to_query` runs, parameters stay `$name` placeholders for the bindings
+    pub fn to_surql(&self) -> String {
+        let mut statements = Statements::default();
+        statements.0 = self.statements.clone();
+
+        statements.to_string()
+    }
+
+    pub fn to_query(self) -> Query<'r, Client> {
+        self.db.query(self.statements)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use surrealdb::sql::{Operator, Statements};
+    use crate::query::statement::StatementBuilder;
+    use crate::testing::db;
+    use super::*;
+
+    fn to_string(statements: Vec<Statement>) -> String {
+        let mut s = Statements::default();
+        s.0 = statements;
+
+        s.to_string()
+    }
+
+    #[tokio::test]
+    async fn let_and_statement() {
+        let db = db().await;
+
+        let script = db.script_builder()
+            .let_param("$test", db.select_builder().what("test").field("test"))
+            .statement(db.select_builder().what("$test").field("test"));
+
+        assert_eq!(to_string(script.statements), "LET $test = (SELECT test FROM test);\nSELECT test FROM $test;");
+    }
+
+    #[tokio::test]
+    async fn typed_results() {
+        let db = db().await;
+
+        let mut script = db.script_builder()
+            .let_param("created", db.create_builder().what("test").set(vec![("n", Operator::Equal, 5)]));
+
+        let count = script.add::<Option<usize>>("array::len($created)");
+        let n = script.add::<Vec<i64>>("$created.n");
+
+        assert_eq!(count.index, 1);
+        assert_eq!(n.index, 2);
+
+        let mut res = script.to_query().await.unwrap().check().unwrap();
+
+        assert_eq!(n.take(&mut res).unwrap(), vec![5]);
+        assert_eq!(count.take(&mut res).unwrap(), Some(1));
+    }
+
+    #[tokio::test]
+    async fn index_in_transaction() {
+        let db = db().await;
+
+        let mut script = db.script_builder().let_param("n", "5").begin();
+
+        let n = script.add::<Option<i64>>("$n");
+        let doubled = script.add::<Option<i64>>("$n * 2");
+
+        assert_eq!((n.index, doubled.index), (1, 2));
+
+        let mut res = script.commit().to_query().await.unwrap().check().unwrap();
+
+        assert_eq!(res.num_statements(), 3);
+        assert_eq!(n.take(&mut res).unwrap(), Some(5));
+        assert_eq!(doubled.take(&mut res).unwrap(), Some(10));
+    }
+
+    #[tokio::test]
+    async fn script_to_surql() {
+        let db = db().await;
+
+        let script = db.script_builder().begin().let_param("test", "$test1").commit();
+
+        assert_eq!(script.to_surql(), "BEGIN TRANSACTION;\nLET $test = $test1;\nCOMMIT TRANSACTION;");
+    }
+}
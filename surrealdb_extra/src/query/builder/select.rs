@@ -38,24 +38,38 @@
 //! ## Click on the struct for more info
 
 use std::marker::PhantomData;
+use serde::de::DeserializeOwned;
 use surrealdb::{Connection, Surreal};
 use surrealdb::method::Query;
-use surrealdb::sql::{Explain, Fetchs, Groups, Idioms, Orders, Splits};
+use surrealdb::sql::{Explain, Fetchs, Field, Groups, Idiom, Idioms, Number, Orders, Splits, Value};
 use surrealdb::sql::statements::SelectStatement;
+use crate::query::explain::{ExplainError, QueryPlan};
 use crate::query::parsing::cond::ExtraCond;
 use crate::query::parsing::fetch::ExtraFetch;
 use crate::query::parsing::field::ExtraField;
+use crate::query::parsing::fulltext;
 use crate::query::parsing::group::ExtraGroup;
+use crate::query::parsing::idiom::ExtraIdiom;
 use crate::query::parsing::limit::ExtraLimit;
 use crate::query::parsing::omit::ExtraOmit;
-use crate::query::parsing::order::ExtraOrder;
+use crate::query::parsing::order::{ExtraOrder, OrderDirection};
 use crate::query::parsing::split::ExtraSplit;
 use crate::query::parsing::start::ExtraStart;
 use crate::query::parsing::timeout::ExtraTimeout;
+use crate::query::parsing::vector;
+use crate::query::parsing::vector::ExtraKnn;
 use crate::query::parsing::version::ExtraVersion;
 use crate::query::parsing::what::ExtraValue;
 use crate::query::parsing::with::ExtraWith;
+use crate::query::raw::{RawQueryError, TypedResponse};
 use crate::query::states::{FilledCond, FilledFields, FilledWhat, NoCond, NoFields, NoWhat};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum SelectError {
+    #[error("`last` needs an order, without one the rows have no last row")]
+    NoOrder,
+}
 
 #[derive(Debug, Clone)]
 pub struct SelectBuilder<'r, Client, W, F, C>
@@ -161,6 +175,55 @@ impl<'r, Client, F, C> SelectBuilder<'r, Client, FilledWhat, F, C>
     }
 }
 
+impl<'r, Client, F, C> SelectBuilder<'r, Client, FilledWhat, F, C>
+    where Client: Connection
+{
+    /// This function is for `SELECT VALUE`, the field replaces the other fields and every row is only its value
+    ///
+    /// Example:
+    /// ```rust
+    /// use surrealdb::engine::any::connect;
+    /// use surrealdb::sql::Thing as RecordId;
+    /// use surrealdb_extra::query::select::SelectBuilder;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let db = connect("mem://").await.unwrap();
+    ///     db.use_ns("ns").use_db("db").await.unwrap();
+    ///
+    ///     db.query("CREATE user:one SET name = 'one'; CREATE user:two SET name = 'two'").await.unwrap();
+    ///
+    ///     // This becomes `SELECT VALUE name FROM user`
+    ///     let names: Vec<String> = SelectBuilder::new(&db).what("user").value("name").execute_values().await.unwrap();
+    ///     assert_eq!(names, ["one", "two"]);
+    ///
+    ///     // This becomes `SELECT VALUE name FROM ONLY user:one`
+    ///     let name: Option<String> = SelectBuilder::new(&db).what(RecordId::from(("user", "one"))).value("name").only().execute_value().await.unwrap();
+    ///     assert_eq!(name.as_deref(), Some("one"));
+    /// }
+    /// ```
+    pub fn value(self, field: impl Into<ExtraField>) -> SelectBuilder<'r, Client, FilledWhat, FilledFields, C> {
+        let Self { mut statement, db, .. } = self;
+
+        let field = match field.into().0 {
+            // A plain name is parsed as a table
+            Field::Single { expr: Value::Table(table), alias } => Field::Single { expr: Value::Idiom(Idiom::from(table.0)), alias },
+            field => field,
+        };
+
+        statement.expr.0 = vec![field];
+        statement.expr.1 = true;
+
+        SelectBuilder {
+            statement,
+            db,
+            what_state: Default::default(),
+            fields_state: Default::default(),
+            cond_state: Default::default(),
+        }
+    }
+}
+
 impl<'r, Client> SelectBuilder<'r, Client, FilledWhat, FilledFields, NoCond>
     where Client: Connection
 {
@@ -234,6 +297,92 @@ impl<'r, Client> SelectBuilder<'r, Client, FilledWhat, FilledFields, NoCond>
         }
     }
 
+    /// Same as `condition` when the condition is `Some`, the builder is in the same state either way so optional filters don't change its type
+    ///
+    /// Example:
+    /// ```rust
+    /// use surrealdb::engine::any::connect;
+    /// use surrealdb_extra::query::select::SelectBuilder;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let db = connect("mem://").await.unwrap();
+    ///
+    ///     let name: Option<&str> = None;
+    ///     let limit = Some(10);
+    ///
+    ///     // This becomes `SELECT * FROM user LIMIT 10`
+    ///     let select = SelectBuilder::new(&db).what("user").field("*")
+    ///         .condition_if(name.map(|_| "name = $name"))
+    ///         .limit_if(limit);
+    /// }
+    /// ```
+    pub fn condition_if(self, cond: Option<impl Into<ExtraCond>>) -> SelectBuilder<'r, Client, FilledWhat, FilledFields, FilledCond> {
+        match cond {
+            Some(cond) => self.condition(cond),
+            None => {
+                let Self { statement, db, .. } = self;
+
+                SelectBuilder {
+                    statement,
+                    db,
+                    what_state: Default::default(),
+                    fields_state: Default::default(),
+                    cond_state: Default::default(),
+                }
+            }
+        }
+    }
+
+    /// Full-text search as `WHERE field @reference@ query`, the field needs a `SEARCH` index
+    ///
+    /// Use `fulltext::score(reference)` and `fulltext::highlight(.., reference)` as fields for the score and highlights of the match.
+    /// For more than one match use `fulltext::matches` inside `condition`
+    ///
+    /// Example:
+    /// ```rust
+    /// use surrealdb::engine::any::connect;
+    /// use surrealdb_extra::query::parsing::fulltext;
+    /// use surrealdb_extra::query::select::SelectBuilder;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let db = connect("mem://").await.unwrap();
+    ///
+    ///     SelectBuilder::new(&db).what("post").field((fulltext::score(1), "score")).search("title", "rust", 1);
+    ///     // The above builder becomes `SELECT search::score(1) AS score FROM post WHERE title @1@ 'rust'`
+    /// }
+    /// ```
+    pub fn search(self, field: impl Into<ExtraIdiom>, query: impl Into<Value>, reference: impl Into<Option<u8>>) -> SelectBuilder<'r, Client, FilledWhat, FilledFields, FilledCond> {
+        self.condition(fulltext::matches(field, query, reference))
+    }
+
+    /// Vector search for the `k` nearest neighbours of the embedding, ordered by their distance
+    ///
+    /// The distance is selected as `distance`, `k` can be:
+    /// - `k` for a field with a `MTREE` index
+    /// - `(k, ef)` for a field with a `HNSW` index
+    /// - `(k, Distance)` for a brute force search without an index
+    ///
+    /// Example:
+    /// ```rust
+    /// use surrealdb::engine::any::connect;
+    /// use surrealdb::sql::Field;
+    /// use surrealdb_extra::query::select::SelectBuilder;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let db = connect("mem://").await.unwrap();
+    ///
+    ///     SelectBuilder::new(&db).what("doc").field(Field::All).knn("embedding", [0.1, 0.2, 0.3], 5);
+    ///     // The above builder becomes `SELECT *, vector::distance::knn() AS distance FROM doc WHERE embedding <|5|> [0.1f, 0.2f, 0.3f] ORDER BY distance ASC`
+    /// }
+    /// ```
+    pub fn knn<N: Into<Number>>(self, field: impl Into<ExtraIdiom>, embedding: impl IntoIterator<Item = N>, k: impl Into<ExtraKnn>) -> SelectBuilder<'r, Client, FilledWhat, FilledFields, FilledCond> {
+        self.field((vector::distance(), "distance"))
+            .condition(vector::knn(field, embedding, k))
+            .order(("distance", OrderDirection::ASC))
+    }
 }
 
 impl<'r, Client, C> SelectBuilder<'r, Client, FilledWhat, FilledFields, C>
@@ -275,6 +424,37 @@ impl<'r, Client, C> SelectBuilder<'r, Client, FilledWhat, FilledFields, C>
         }
     }
 
+    /// This function is for `WITH INDEX`, the query planner only uses this index
+    ///
+    /// Example:
+    /// ```rust
+    /// use surrealdb::engine::any::connect;
+    /// use surrealdb_extra::query::select::SelectBuilder;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let db = connect("mem://").await.unwrap();
+    ///     SelectBuilder::new(&db).what("test").field("test").with_index("idx_name"); // This becomes `SELECT test FROM test WITH INDEX idx_name`
+    ///
+    ///     SelectBuilder::new(&db).what("test").field("test").with_indexes(["idx_name", "idx_email"]); // This becomes `SELECT test FROM test WITH INDEX idx_name,idx_email`
+    ///
+    ///     SelectBuilder::new(&db).what("test").field("test").no_index(); // This becomes `SELECT test FROM test WITH NOINDEX`
+    /// }
+    /// ```
+    pub fn with_index(self, index: impl Into<String>) -> Self {
+        self.with(vec![index.into()])
+    }
+
+    /// This function is for `WITH INDEX` with more indexes
+    pub fn with_indexes<I: Into<String>>(self, indexes: impl IntoIterator<Item = I>) -> Self {
+        self.with(indexes.into_iter().map(Into::into).collect::<Vec<String>>())
+    }
+
+    /// This function is for `WITH NOINDEX`, the query planner doesn't use any index
+    pub fn no_index(self) -> Self {
+        self.with(ExtraWith::no_index())
+    }
+
     /// You can also use the Split/Idiom type inside surrealdb for more complex requests
     pub fn split(self, split: impl Into<ExtraSplit>) -> Self {
         let Self { mut statement, db, .. } = self;
@@ -358,6 +538,46 @@ impl<'r, Client, C> SelectBuilder<'r, Client, FilledWhat, FilledFields, C>
         }
     }
 
+    /// Same as `order` when the order is `Some`
+    pub fn order_if(self, order: Option<impl Into<ExtraOrder>>) -> Self {
+        match order {
+            Some(order) => self.order(order),
+            None => self,
+        }
+    }
+
+    /// This function orders the rows randomly
+    ///
+    /// Example:
+    /// ```rust
+    /// use surrealdb::engine::any::connect;
+    /// use surrealdb_extra::query::parsing::order::{ExtraOrder, OrderDirection};
+    /// use surrealdb_extra::query::select::SelectBuilder;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let db = connect("mem://").await.unwrap();
+    ///     SelectBuilder::new(&db).what("test").field("test").order_random().limit(5); // This becomes `SELECT test FROM test ORDER BY RAND() LIMIT 5`
+    ///
+    ///     SelectBuilder::new(&db).what("test").field("test").order(ExtraOrder::from(("name", OrderDirection::ASC)).collate()); // This becomes `SELECT test FROM test ORDER BY name COLLATE ASC`
+    ///
+    ///     SelectBuilder::new(&db).what("test").field("test").order_numeric(("name", OrderDirection::ASC)); // This becomes `SELECT test FROM test ORDER BY name NUMERIC ASC`
+    /// }
+    /// ```
+    pub fn order_random(self) -> Self {
+        self.order(ExtraOrder::random())
+    }
+
+    /// Same as `order` with `COLLATE`
+    pub fn order_collate(self, order: impl Into<ExtraOrder>) -> Self {
+        self.order(order.into().collate())
+    }
+
+    /// Same as `order` with `NUMERIC`
+    pub fn order_numeric(self, order: impl Into<ExtraOrder>) -> Self {
+        self.order(order.into().numeric())
+    }
+
     /// This function limit amount of rows
     ///
     /// Example:
@@ -369,6 +589,8 @@ impl<'r, Client, C> SelectBuilder<'r, Client, FilledWhat, FilledFields, C>
     /// async fn main() {
     ///     let db = connect("mem://").await.unwrap();
     ///     SelectBuilder::new(&db).what("test").field("test").limit(5); // This becomes `SELECT test FROM test LIMIT 5`
+    ///
+    ///     SelectBuilder::new(&db).what("test").field("test").limit("$limit"); // This becomes `SELECT test FROM test LIMIT $limit`
     /// }
     /// ```
     /// You can also use the Limit/Value type inside surrealdb for more complex requests
@@ -388,6 +610,14 @@ impl<'r, Client, C> SelectBuilder<'r, Client, FilledWhat, FilledFields, C>
         }
     }
 
+    /// Same as `limit` when the limit is `Some`
+    pub fn limit_if(self, limit: Option<impl Into<ExtraLimit>>) -> Self {
+        match limit {
+            Some(limit) => self.limit(limit),
+            None => self,
+        }
+    }
+
     /// This function starts rows at x
     ///
     /// Example:
@@ -399,6 +629,8 @@ impl<'r, Client, C> SelectBuilder<'r, Client, FilledWhat, FilledFields, C>
     /// async fn main() {
     ///     let db = connect("mem://").await.unwrap();
     ///     SelectBuilder::new(&db).what("test").field("test").start(5); // This becomes `SELECT test FROM test START 5`
+    ///
+    ///     SelectBuilder::new(&db).what("test").field("test").start("$start"); // This becomes `SELECT test FROM test START $start`
     /// }
     /// ```
     /// You can also use the Start/Value type inside surrealdb for more complex requests
@@ -418,6 +650,14 @@ impl<'r, Client, C> SelectBuilder<'r, Client, FilledWhat, FilledFields, C>
         }
     }
 
+    /// Same as `start` when the start is `Some`
+    pub fn start_if(self, start: Option<impl Into<ExtraStart>>) -> Self {
+        match start {
+            Some(start) => self.start(start),
+            None => self,
+        }
+    }
+
     /// You can also use the Fetch/Idiom type inside surrealdb for more complex requests
     pub fn fetch(self, fetch: impl Into<ExtraFetch>) -> Self {
         let Self { mut statement, db, .. } = self;
@@ -501,9 +741,25 @@ impl<'r, Client, C> SelectBuilder<'r, Client, FilledWhat, FilledFields, C>
         }
     }
 
+    /// This becomes `EXPLAIN`, the query returns the plan instead of the records
     pub fn explain(self) -> Self {
         let Self { mut statement, db, .. } = self;
 
+        statement.explain = Some(Explain::default());
+
+        Self {
+            statement,
+            db,
+            what_state: Default::default(),
+            fields_state: Default::default(),
+            cond_state: Default::default(),
+        }
+    }
+
+    /// This becomes `EXPLAIN FULL`, the plan also has the number of fetched records
+    pub fn explain_full(self) -> Self {
+        let Self { mut statement, db, .. } = self;
+
         let mut explain = Explain::default();
         explain.0 = true;
         statement.explain = Some(explain);
@@ -517,28 +773,153 @@ impl<'r, Client, C> SelectBuilder<'r, Client, FilledWhat, FilledFields, C>
         }
     }
 
+    /// Runs the select with `EXPLAIN`, or `EXPLAIN FULL` when set, and parses the plan
+    pub async fn execute_explain(mut self) -> anyhow::Result<QueryPlan> {
+        if self.statement.explain.is_none() {
+            self.statement.explain = Some(Explain::default());
+        }
+
+        let plan: surrealdb::Value = self.to_query().await
+            .map_err(ExplainError::from)?
+            .take(0).map_err(ExplainError::from)?;
+
+        Ok(QueryPlan::try_from(plan.into_inner())?)
+    }
+
+    /// Runs the select and deserializes every row, mostly for `value`
+    pub async fn execute_values<V: DeserializeOwned>(self) -> anyhow::Result<Vec<V>> {
+        let res = self.to_query().await.map_err(RawQueryError::from)?;
+
+        TypedResponse::from(res).take_vec(0)
+    }
+
+    /// Runs the select and deserializes the single row, mostly for `value` with `only`, `NONE` or no rows is `None` and more rows is an error
+    pub async fn execute_value<V: DeserializeOwned>(self) -> anyhow::Result<Option<V>> {
+        let mut values: Vec<V> = self.execute_values().await?;
+
+        match values.len() {
+            0 | 1 => Ok(values.pop()),
+            count => Err(RawQueryError::TooManyResults { index: 0, count }.into()),
+        }
+    }
+
+    /// Runs the select with `LIMIT 1` and deserializes the first row
+    ///
+    /// Example:
+    /// ```rust
+    /// use surrealdb::engine::any::connect;
+    /// use surrealdb_extra::query::parsing::order::OrderDirection;
+    /// use surrealdb_extra::query::select::SelectBuilder;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let db = connect("mem://").await.unwrap();
+    ///     db.use_ns("ns").use_db("db").await.unwrap();
+    ///
+    ///     db.query("CREATE user:one SET age = 20; CREATE user:two SET age = 30").await.unwrap();
+    ///
+    ///     // This becomes `SELECT VALUE age FROM user ORDER BY age LIMIT 1`
+    ///     let youngest: Option<i64> = SelectBuilder::new(&db).what("user").value("age").order(("age", OrderDirection::ASC)).first().await.unwrap();
+    ///     assert_eq!(youngest, Some(20));
+    ///
+    ///     // This becomes `SELECT VALUE age FROM user ORDER BY age DESC LIMIT 1`
+    ///     let oldest: Option<i64> = SelectBuilder::new(&db).what("user").value("age").order(("age", OrderDirection::ASC)).last().await.unwrap();
+    ///     assert_eq!(oldest, Some(30));
+    /// }
+    /// ```
+    pub async fn first<T: DeserializeOwned>(self) -> anyhow::Result<Option<T>> {
+        let mut rows: Vec<T> = self.limit(1).execute_values().await?;
+
+        Ok(rows.pop())
+    }
+
+    /// Same as `first` with the order reversed, without an order it is `SelectError::NoOrder`.
+    /// With a `LIMIT` or `START` it is the last row of that page, so the page is selected as it is
+    pub async fn last<T: DeserializeOwned>(mut self) -> anyhow::Result<Option<T>> {
+        let Some(orders) = &mut self.statement.order else {
+            return Err(SelectError::NoOrder.into());
+        };
+
+        if self.statement.limit.is_some() || self.statement.start.is_some() {
+            return Ok(self.execute_values().await?.pop());
+        }
+
+        orders.0.iter_mut().for_each(|order| order.direction = !order.direction);
+
+        self.first().await
+    }
+
+    /// Runs the select with `LIMIT $limit START $start` and binds the values, so the page is not part of the statement text
+    pub async fn execute_paged<T: DeserializeOwned>(self, limit: u64, start: u64) -> anyhow::Result<Vec<T>> {
+        let res = self.limit("$limit").start("$start").to_query()
+            .bind(("limit", limit))
+            .bind(("start", start))
+            .await.map_err(RawQueryError::from)?;
+
+        TypedResponse::from(res).take_vec(0)
+    }
+
+    /// The SurrealQL that `to_query` runs, parameters stay `$name` placeholders for the bindings
+    pub fn to_surql(&self) -> String {
+        let statement = crate::query::config::apply(self.statement.clone());
+
+        statement.to_string()
+    }
+
     /// Converts the builder to query type
     pub fn to_query(self) -> Query<'r, Client> {
-        self.db.query(self.statement)
+        let statement = crate::query::config::apply(self.statement);
+
+        self.db.query(statement)
+    }
+
+    /// Runs the query with the observers, see the `observer` module
+    pub fn observed(self) -> crate::query::observer::Observed<'r, Client> {
+        let info = crate::query::observer::QueryInfo::new("SELECT", &self.statement.what.0, self.to_surql());
+
+        crate::query::observer::Observed::new(info, self.to_query())
+    }
+
+    /// Runs only this statement in another namespace and database, see the `scope` module
+    pub fn use_ns_db(self, ns: impl Into<String>, database: impl Into<String>) -> crate::query::scope::Scoped<'r, Client> {
+        let statement = crate::query::config::apply(self.statement);
+
+        crate::query::scope::Scoped::new(self.db, ns, database, statement)
+    }
+
+    /// Same as `to_query` but drops the clauses the engine doesn't support, see `Capabilities`
+    ///
+    /// Fails with a `FallbackError` for an unsupported clause that changes the result
+    #[cfg_attr(docsrs, doc(cfg(feature = "fallback")))]
+    #[cfg(feature = "fallback")]
+    pub fn to_query_with(mut self, capabilities: &crate::query::fallback::Capabilities) -> anyhow::Result<Query<'r, Client>> {
+        self.statement = crate::query::fallback::apply(self.statement, capabilities)?;
+
+        Ok(self.to_query())
+    }
+
+    /// Streams the deserialized rows, fetching `DEFAULT_CHUNK_SIZE` rows at a time, see the `stream` module
+    #[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
+    #[cfg(feature = "stream")]
+    pub fn stream<T: serde::de::DeserializeOwned + Send + 'r>(self) -> futures::stream::BoxStream<'r, anyhow::Result<T>> {
+        self.stream_chunks(crate::query::stream::DEFAULT_CHUNK_SIZE)
+    }
+
+    /// Streams the deserialized rows, fetching `chunk_size` rows at a time
+    #[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
+    #[cfg(feature = "stream")]
+    pub fn stream_chunks<T: serde::de::DeserializeOwned + Send + 'r>(self, chunk_size: u64) -> futures::stream::BoxStream<'r, anyhow::Result<T>> {
+        crate::query::stream::stream(self.db, self.statement, chunk_size)
     }
 }
 
 #[cfg(test)]
 mod test {
-    use surrealdb::engine::any::{Any, connect};
     use surrealdb::opt::IntoQuery;
     use surrealdb::sql::{Field, Idiom, Thing, Value};
-    use surrealdb::sql::Value::Thing;
+    use crate::testing::db;
     use super::*;
 
-    async fn db() -> Surreal<Any> {
-        let db = connect("mem://").await.unwrap();
-
-        db.use_ns("test").use_db("test").await.unwrap();
-
-        db
-    }
-
     #[tokio::test]
     async fn select_table() {
         let db = db().await;
@@ -648,4 +1029,105 @@ mod test {
 
         assert!(query.is_ok())
     }
+
+    #[tokio::test]
+    async fn select_value() {
+        let db = db().await;
+
+        db.query("CREATE test:1 SET n = 1; CREATE test:2 SET n = 2").await.unwrap();
+
+        let select = SelectBuilder::new(&db).what("test").field(Field::All).value("n");
+        assert_eq!(select.statement.to_string(), "SELECT VALUE n FROM test");
+
+        let values: Vec<i64> = select.execute_values().await.unwrap();
+        assert_eq!(values, [1, 2]);
+
+        let value: Option<i64> = SelectBuilder::new(&db).what(Thing::from(("test", "3"))).value("n").execute_value().await.unwrap();
+        assert_eq!(value, None);
+    }
+
+    #[tokio::test]
+    async fn select_order_options() {
+        let db = db().await;
+
+        let select = SelectBuilder::new(&db).what("test").field("test")
+            .order_collate(("a", OrderDirection::ASC))
+            .order_numeric(("b", OrderDirection::DESC))
+            .order_random();
+
+        assert_eq!(select.to_surql(), "SELECT test FROM test ORDER BY a COLLATE, b NUMERIC DESC, RAND()");
+    }
+
+    #[tokio::test]
+    async fn select_with_index() {
+        let db = db().await;
+
+        let select = SelectBuilder::new(&db).what("test").field("test");
+
+        assert_eq!(select.clone().with_index("a").to_surql(), "SELECT test FROM test WITH INDEX a");
+        assert_eq!(select.clone().with_indexes(["a", "b"]).to_surql(), "SELECT test FROM test WITH INDEX a,b");
+        assert_eq!(select.clone().no_index().to_surql(), "SELECT test FROM test WITH NOINDEX");
+        assert_eq!(select.with("noindex").to_surql(), "SELECT test FROM test WITH NOINDEX");
+    }
+
+    #[tokio::test]
+    async fn select_paged() {
+        let db = db().await;
+
+        db.query("CREATE test:1 SET n = 1; CREATE test:2 SET n = 2; CREATE test:3 SET n = 3").await.unwrap();
+
+        let select = SelectBuilder::new(&db).what("test").value("n");
+        assert_eq!(select.clone().limit("$limit").start("$start").to_surql(), "SELECT VALUE n FROM test LIMIT $limit START $start");
+
+        let page: Vec<i64> = select.execute_paged(2, 1).await.unwrap();
+        assert_eq!(page, [2, 3]);
+    }
+
+    #[tokio::test]
+    async fn select_first_last() {
+        let db = db().await;
+
+        db.query("CREATE test:1 SET n = 3; CREATE test:2 SET n = 1; CREATE test:3 SET n = 2").await.unwrap();
+
+        let first: Option<i64> = SelectBuilder::new(&db).what("test").value("n").first().await.unwrap();
+        assert_eq!(first, Some(3));
+
+        let err = SelectBuilder::new(&db).what("test").value("n").last::<i64>().await.unwrap_err();
+        assert!(matches!(err.downcast_ref::<SelectError>(), Some(SelectError::NoOrder)));
+
+        let select = SelectBuilder::new(&db).what("test").value("n").order(("n", OrderDirection::ASC));
+
+        assert_eq!(select.clone().first::<i64>().await.unwrap(), Some(1));
+        assert_eq!(select.clone().last::<i64>().await.unwrap(), Some(3));
+        assert_eq!(select.clone().limit(2).last::<i64>().await.unwrap(), Some(2));
+        assert_eq!(select.clone().start(1).last::<i64>().await.unwrap(), Some(3));
+        assert_eq!(select.start(3).last::<i64>().await.unwrap(), None);
+
+        let none: Option<i64> = SelectBuilder::new(&db).what("empty").value("n").first().await.unwrap();
+        assert_eq!(none, None);
+    }
+
+    #[tokio::test]
+    async fn select_to_surql() {
+        let db = db().await;
+
+        let select = SelectBuilder::new(&db).what("test").field("test").field(("$test", "alias")).limit(5).only();
+
+        assert_eq!(select.to_surql(), "SELECT test, $test AS alias FROM ONLY test LIMIT 5");
+    }
+
+    #[tokio::test]
+    async fn select_optional_clauses() {
+        let db = db().await;
+
+        let select = |name: Option<&str>, limit: Option<i64>| SelectBuilder::new(&db).what("user").field("name")
+            .condition_if(name.map(|_| "name = $name"))
+            .order_if(name.map(|_| ("name", OrderDirection::ASC)))
+            .limit_if(limit)
+            .start_if(None::<i64>)
+            .to_surql();
+
+        assert_eq!(select(None, None), "SELECT name FROM user");
+        assert_eq!(select(Some("one"), Some(5)), "SELECT name FROM user WHERE name = $name ORDER BY name LIMIT 5");
+    }
 }
@@ -0,0 +1,217 @@
+//! # Starting the builder can be done in 2 ways
+//!
+//! ## Using the `Surrealdb<C>` type
+//! ```rust
+//! use surrealdb::engine::any::connect;
+//! use surrealdb_extra::query::statement::StatementBuilder;
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let db = connect("mem://").await.unwrap();
+//!     db.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     let builder = db.show_changes_builder();
+//!
+//!     let query = builder.table("test").since(0).to_query();
+//! }
+//! ```
+//!
+//! ## Using new function inside the builder and passing a reference of type `Surrealdb<C>`
+//! ```rust
+//! use surrealdb::engine::any::connect;
+//! use surrealdb_extra::query::show::ShowChangesBuilder;
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let db = connect("mem://").await.unwrap();
+//!     db.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     let builder = ShowChangesBuilder::new(&db);
+//!
+//!     let query = builder.table("test").since(0).to_query();
+//! }
+//! ```
+//!
+//! ## Click on the struct for more info
+
+use std::marker::PhantomData;
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use surrealdb::{Connection, Surreal};
+use surrealdb::method::Query;
+use surrealdb::sql::{parse, Datetime, Statement, Table, Value};
+use surrealdb::sql::statements::ShowStatement;
+use crate::query::changefeed::{ChangeFeedError, ChangeSet};
+use crate::query::states::{FilledSince, NoSince};
+
+/// Start of the changes, a versionstamp or a timestamp
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExtraSince {
+    Versionstamp(u64),
+    Timestamp(Datetime),
+}
+
+impl From<u64> for ExtraSince {
+    fn from(value: u64) -> Self {
+        Self::Versionstamp(value)
+    }
+}
+
+impl From<Datetime> for ExtraSince {
+    fn from(value: Datetime) -> Self {
+        Self::Timestamp(value)
+    }
+}
+
+impl From<DateTime<Utc>> for ExtraSince {
+    fn from(value: DateTime<Utc>) -> Self {
+        Self::Timestamp(value.into())
+    }
+}
+
+/// `SHOW CHANGES` with the since clause and without table and limit
+fn show_statement(since: &ExtraSince) -> ShowStatement {
+    let since = match since {
+        ExtraSince::Versionstamp(versionstamp) => versionstamp.to_string(),
+        ExtraSince::Timestamp(timestamp) => timestamp.to_string(),
+    };
+
+    // The statement and its since clause can't be constructed outside of surrealdb
+    match parse(&format!("SHOW CHANGES FOR DATABASE SINCE {since}")).map(|q| q.0.0.into_iter().next()) {
+        Ok(Some(Statement::Show(statement))) => statement,
+        _ => unreachable!("SHOW CHANGES with a versionstamp or datetime always parses"),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ShowChangesBuilder<'r, Client, S>
+    where Client: Connection
+{
+    pub statement: ShowStatement,
+    pub(crate) db: &'r Surreal<Client>,
+    pub(crate) since_state: PhantomData<S>,
+}
+
+impl<'r, Client> ShowChangesBuilder<'r, Client, NoSince>
+    where Client: Connection
+{
+    pub fn new(db: &'r Surreal<Client>) -> Self {
+        Self {
+            statement: show_statement(&ExtraSince::Versionstamp(0)),
+            db,
+            since_state: Default::default(),
+        }
+    }
+
+    /// This function is for `SINCE`, the first change that is returned
+    ///
+    /// Example:
+    /// ```rust
+    /// use chrono::Utc;
+    /// use surrealdb::engine::any::connect;
+    /// use surrealdb_extra::query::statement::StatementBuilder;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let db = connect("mem://").await.unwrap();
+    ///
+    ///     db.show_changes_builder().table("test").since(5);
+    ///     // The above builder becomes `SHOW CHANGES FOR TABLE test SINCE 5`
+    ///
+    ///     db.show_changes_builder().table("test").since(Utc::now());
+    ///     // The above builder becomes `SHOW CHANGES FOR TABLE test SINCE d'...'`
+    /// }
+    /// ```
+    pub fn since(self, since: impl Into<ExtraSince>) -> ShowChangesBuilder<'r, Client, FilledSince> {
+        let Self { mut statement, db, .. } = self;
+
+        statement.since = show_statement(&since.into()).since;
+
+        ShowChangesBuilder {
+            statement,
+            db,
+            since_state: Default::default(),
+        }
+    }
+}
+
+impl<'r, Client, S> ShowChangesBuilder<'r, Client, S>
+    where Client: Connection
+{
+    /// This function is for `FOR TABLE`, the table needs a `CHANGEFEED`
+    pub fn table(mut self, table: impl Into<String>) -> Self {
+        self.statement.table = Some(Table::from(table.into()));
+
+        self
+    }
+
+    /// This function is for `FOR DATABASE`, the database needs a `CHANGEFEED`, this is the default
+    pub fn database(mut self) -> Self {
+        self.statement.table = None;
+
+        self
+    }
+
+    /// This function is for `LIMIT`, the maximum number of change sets
+    pub fn limit(mut self, limit: u32) -> Self {
+        self.statement.limit = Some(limit);
+
+        self
+    }
+}
+
+impl<'r, Client> ShowChangesBuilder<'r, Client, FilledSince>
+    where Client: Connection
+{
+    /// The SurrealQL that `to_query` runs, parameters stay `$name` placeholders for the bindings
+    pub fn to_surql(&self) -> String {
+        self.statement.to_string()
+    }
+
+    pub fn to_query(self) -> Query<'r, Client> {
+        self.db.query(Statement::Show(self.statement))
+    }
+
+    /// Runs the statement and parses the change sets, the records are deserialized into `T`
+    pub async fn execute<T: DeserializeOwned>(self) -> anyhow::Result<Vec<ChangeSet<T>>> {
+        let mut res = self.to_query().await.map_err(ChangeFeedError::from)?;
+
+        let changes: surrealdb::Value = res.take(0).map_err(ChangeFeedError::from)?;
+
+        let Value::Array(change_sets) = changes.into_inner() else {
+            return Err(ChangeFeedError::InvalidChange("SHOW CHANGES did not return an array".to_string()).into());
+        };
+
+        let mut parsed = vec![];
+
+        for change_set in change_sets.0 {
+            parsed.push(ChangeSet::try_from(change_set)?);
+        }
+
+        Ok(parsed)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use surrealdb::engine::any::connect;
+    use crate::query::statement::StatementBuilder;
+
+    #[tokio::test]
+    async fn show_changes() {
+        let db = connect("mem://").await.unwrap();
+
+        let query = db.show_changes_builder().table("test").since(5).limit(10);
+        assert_eq!(query.statement.to_string(), "SHOW CHANGES FOR TABLE test SINCE 5 LIMIT 10");
+
+        let query = db.show_changes_builder().since(5);
+        assert_eq!(query.statement.to_string(), "SHOW CHANGES FOR DATABASE SINCE 5");
+    }
+
+    #[tokio::test]
+    async fn show_changes_to_surql() {
+        let db = connect("mem://").await.unwrap();
+
+        assert_eq!(db.show_changes_builder().table("test").since(5).to_surql(), "SHOW CHANGES FOR TABLE test SINCE 5");
+    }
+}
@@ -3,7 +3,7 @@ use serde::Serialize;
 use surrealdb::sql::statements::UpdateStatement;
 use surrealdb::{Connection, Surreal};
 use surrealdb::method::Query;
-use surrealdb::sql::{Data, to_value};
+use surrealdb::sql::{Data, Idiom, Param, Thing as RecordId, Values, to_value};
 use crate::query::parsing::cond::ExtraCond;
 use crate::query::parsing::data::ExtraData;
 use crate::query::parsing::output::ExtraOutput;
@@ -11,8 +11,41 @@ use crate::query::parsing::set_expression::SetExpression;
 use crate::query::parsing::timeout::ExtraTimeout;
 use crate::query::parsing::unset_expression::UnsetExpression;
 use crate::query::parsing::what::ExtraValue;
-use crate::query::states::{FilledCond, FilledData, FilledWhat, NoCond, NoData, NoWhat};
+use crate::query::states::{AllRecords, CheckedCond, FilledCond, FilledData, FilledWhat, NoCond, NoData, NoTable, NoWhat};
 
+/// What `UpdateBuilder::what` takes, `Cond` is `NoCond` when it can be a table, so updating every record has to be confirmed with `all_records`
+pub trait UpdateWhat: Into<ExtraValue> {
+    type Cond;
+}
+
+impl UpdateWhat for &str {
+    type Cond = NoCond;
+}
+
+impl UpdateWhat for String {
+    type Cond = NoCond;
+}
+
+impl UpdateWhat for Values {
+    type Cond = NoCond;
+}
+
+impl UpdateWhat for ExtraValue {
+    type Cond = NoCond;
+}
+
+impl UpdateWhat for RecordId {
+    type Cond = NoTable;
+}
+
+impl UpdateWhat for Param {
+    type Cond = NoTable;
+}
+
+/// A path that starts with a parameter, e.g. `$record.id`
+impl UpdateWhat for Idiom {
+    type Cond = NoTable;
+}
 
 #[derive(Debug, Clone)]
 pub struct UpdateBuilder<'r, Client, T, D, C>
@@ -56,7 +89,9 @@ impl<'r, Client> UpdateBuilder<'r, Client, NoWhat, NoData, NoCond>
     /// ```
     ///
     /// You can also use the Value type inside surrealdb for more complex requests
-    pub fn what(self, what: impl Into<ExtraValue>) -> UpdateBuilder<'r, Client, FilledWhat, NoData, NoCond> {
+    ///
+    /// A record id, a `Param` or an `Idiom` is not a table, so the update can run without a condition or `all_records`
+    pub fn what<W: UpdateWhat>(self, what: W) -> UpdateBuilder<'r, Client, FilledWhat, NoData, W::Cond> {
         let Self { mut statement, db, .. } = self;
 
         statement.what = what.into().0;
@@ -71,12 +106,12 @@ impl<'r, Client> UpdateBuilder<'r, Client, NoWhat, NoData, NoCond>
     }
 }
 
-impl<'r, Client> UpdateBuilder<'r, Client, FilledWhat, NoData, NoCond>
+impl<'r, Client, C> UpdateBuilder<'r, Client, FilledWhat, NoData, C>
     where Client: Connection
 {
 
     /// This function is for `SET` || `UNSET` || `MERGE` and more
-    pub fn data(self, data: impl Into<ExtraData>) -> UpdateBuilder<'r, Client, FilledWhat, FilledData, NoCond> {
+    pub fn data(self, data: impl Into<ExtraData>) -> UpdateBuilder<'r, Client, FilledWhat, FilledData, C> {
         let Self { mut statement, db, .. } = self;
 
         let data = data.into().0;
@@ -111,7 +146,7 @@ impl<'r, Client> UpdateBuilder<'r, Client, FilledWhat, NoData, NoCond>
     ///     // The above builder becomes `UPDATE test SET test = 'test', test2 = 'test2'
     ///
     /// }
-    pub fn set(self, set: impl Into<SetExpression>) -> UpdateBuilder<'r, Client, FilledWhat, FilledData, NoCond> {
+    pub fn set(self, set: impl Into<SetExpression>) -> UpdateBuilder<'r, Client, FilledWhat, FilledData, C> {
         let Self { mut statement, db, .. } = self;
 
         let set = set.into().0;
@@ -145,7 +180,7 @@ impl<'r, Client> UpdateBuilder<'r, Client, FilledWhat, NoData, NoCond>
     ///     // The above builder becomes `UPDATE test UNSET test, test
     ///
     /// }
-    pub fn unset(self, set: impl Into<UnsetExpression>) -> UpdateBuilder<'r, Client, FilledWhat, FilledData, NoCond> {
+    pub fn unset(self, set: impl Into<UnsetExpression>) -> UpdateBuilder<'r, Client, FilledWhat, FilledData, C> {
         let Self { mut statement, db, .. } = self;
 
         let set = set.into().0;
@@ -182,7 +217,7 @@ impl<'r, Client> UpdateBuilder<'r, Client, FilledWhat, NoData, NoCond>
     ///     // The above builder becomes `UPDATE test CONTENT { test: "test", magic: true }
     ///
     /// }
-    pub fn content(self, content: impl Serialize + 'static) -> UpdateBuilder<'r, Client, FilledWhat, FilledData, NoCond> {
+    pub fn content(self, content: impl Serialize + 'static) -> UpdateBuilder<'r, Client, FilledWhat, FilledData, C> {
         let Self { mut statement, db, .. } = self;
 
         let val = to_value(content).unwrap_or_default();
@@ -263,6 +298,69 @@ impl<'r, Client> UpdateBuilder<'r, Client, FilledWhat, FilledData, NoCond>
             cond_state: Default::default(),
         }
     }
+
+    /// Confirms that the update has no `WHERE` and changes every record of `what`, without it the builder can't be run
+    ///
+    /// Example:
+    /// ```rust
+    /// use surrealdb::engine::any::connect;
+    /// use surrealdb::sql::Operator;
+    /// use surrealdb::sql::Thing as RecordId;
+    /// use surrealdb_extra::query::update::UpdateBuilder;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let db = connect("mem://").await.unwrap();
+    ///
+    ///     UpdateBuilder::new(&db).what("test").set(vec![("test", Operator::Equal, "test")]).all_records().to_query();
+    ///     // The above builder becomes `UPDATE test SET test = 'test'`
+    ///
+    ///     UpdateBuilder::new(&db).what(RecordId::from(("test", "test"))).set(vec![("test", Operator::Equal, "test")]).to_query();
+    ///     // A record is not a table, the above builder becomes `UPDATE test:test SET test = 'test'`
+    /// }
+    /// ```
+    ///
+    /// Forgetting the condition doesn't compile:
+    /// ```compile_fail
+    /// use surrealdb::engine::any::connect;
+    /// use surrealdb::sql::Operator;
+    /// use surrealdb_extra::query::update::UpdateBuilder;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let db = connect("mem://").await.unwrap();
+    ///
+    ///     UpdateBuilder::new(&db).what("test").set(vec![("test", Operator::Equal, "test")]).to_query();
+    /// }
+    /// ```
+    pub fn all_records(self) -> UpdateBuilder<'r, Client, FilledWhat, FilledData, AllRecords> {
+        let Self { statement, db, .. } = self;
+
+        UpdateBuilder {
+            statement,
+            db,
+            what_state: Default::default(),
+            data_state: Default::default(),
+            cond_state: Default::default(),
+        }
+    }
+}
+
+impl<'r, Client> UpdateBuilder<'r, Client, FilledWhat, FilledData, NoTable>
+    where Client: Connection
+{
+    /// This function is for `WHERE`, same as the `condition` of an update of a table
+    pub fn condition(self, cond: impl Into<ExtraCond>) -> UpdateBuilder<'r, Client, FilledWhat, FilledData, FilledCond> {
+        let Self { statement, db, .. } = self;
+
+        UpdateBuilder::<'r, Client, FilledWhat, FilledData, NoCond> {
+            statement,
+            db,
+            what_state: Default::default(),
+            data_state: Default::default(),
+            cond_state: Default::default(),
+        }.condition(cond)
+    }
 }
 
 impl<'r, Client, C> UpdateBuilder<'r, Client, FilledWhat, FilledData, C>
@@ -316,6 +414,13 @@ impl<'r, Client, C> UpdateBuilder<'r, Client, FilledWhat, FilledData, C>
         }
     }
 
+    /// The SurrealQL that `to_query` runs, parameters stay `$name` placeholders for the bindings
+    pub fn to_surql(&self) -> String {
+        let statement = crate::query::config::apply(self.statement.clone());
+
+        statement.to_string()
+    }
+
     pub fn parallel(self) -> Self {
         let Self { mut statement, db, .. } = self;
 
@@ -330,27 +435,50 @@ impl<'r, Client, C> UpdateBuilder<'r, Client, FilledWhat, FilledData, C>
         }
     }
 
+}
+
+impl<'r, Client, C> UpdateBuilder<'r, Client, FilledWhat, FilledData, C>
+    where Client: Connection, C: CheckedCond
+{
     pub fn to_query(self) -> Query<'r, Client> {
-        self.db.query(self.statement)
+        let statement = crate::query::config::apply(self.statement);
+
+        self.db.query(statement)
+    }
+
+    /// Runs the query with the observers, see the `observer` module
+    pub fn observed(self) -> crate::query::observer::Observed<'r, Client> {
+        let info = crate::query::observer::QueryInfo::new("UPDATE", &self.statement.what.0, self.to_surql());
+
+        crate::query::observer::Observed::new(info, self.to_query())
+    }
+
+    /// Runs only this statement in another namespace and database, see the `scope` module
+    pub fn use_ns_db(self, ns: impl Into<String>, database: impl Into<String>) -> crate::query::scope::Scoped<'r, Client> {
+        let statement = crate::query::config::apply(self.statement);
+
+        crate::query::scope::Scoped::new(self.db, ns, database, statement)
+    }
+
+    /// Same as `to_query` but drops the clauses the engine doesn't support, see `Capabilities`
+    #[cfg_attr(docsrs, doc(cfg(feature = "fallback")))]
+    #[cfg(feature = "fallback")]
+    pub fn to_query_with(mut self, capabilities: &crate::query::fallback::Capabilities) -> anyhow::Result<Query<'r, Client>> {
+        self.statement = crate::query::fallback::apply(self.statement, capabilities)?;
+
+        Ok(self.to_query())
     }
 }
 
 #[cfg(test)]
 mod test {
-    use surrealdb::engine::any::{Any, connect};
     use surrealdb::opt::IntoQuery;
-    use surrealdb::sql::Operator;
+    use surrealdb::sql::{Operator, Value};
     use serde::Serialize;
+    use crate::query::statement::StatementBuilder;
+    use crate::testing::db;
     use super::*;
 
-    async fn db() -> Surreal<Any> {
-        let db = connect("mem://").await.unwrap();
-
-        db.use_ns("test").use_db("test").await.unwrap();
-
-        db
-    }
-
     #[derive(Serialize)]
     struct Test {
         test1: String,
@@ -405,4 +533,33 @@ mod test {
 
         assert!(query.is_ok())
     }
+
+    #[tokio::test]
+    async fn update_to_surql() {
+        let db = db().await;
+
+        let update = UpdateBuilder::new(&db).what("test").set(vec![("test", Operator::Equal, Value::Param("test".into()))]);
+
+        assert_eq!(update.to_surql(), "UPDATE test SET test = $test");
+        assert_eq!(update.condition("$cond").to_surql(), "UPDATE test SET test = $test WHERE $cond");
+    }
+
+    #[tokio::test]
+    async fn record_without_confirmation() {
+        let db = db().await;
+
+        db.query("CREATE test:one SET n = 1; CREATE test:two SET n = 2").await.unwrap().check().unwrap();
+
+        db.update_builder().what(RecordId::from(("test", "one"))).set(vec![("n", Operator::Equal, 5)]).to_query().await.unwrap().check().unwrap();
+
+        let update = db.update_builder().what(Param::from("id")).set(vec![("n", Operator::Equal, 6)]).condition("n = 2");
+        assert_eq!(update.to_surql(), "UPDATE $id SET n = 6 WHERE n = 2");
+
+        update.to_query().bind(("id", RecordId::from(("test", "two")))).await.unwrap().check().unwrap();
+
+        let mut res = db.query("SELECT VALUE n FROM test ORDER BY n").await.unwrap();
+        let n: Vec<i64> = res.take(0).unwrap();
+
+        assert_eq!(n, vec![5, 6]);
+    }
 }
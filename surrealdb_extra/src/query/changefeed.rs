@@ -0,0 +1,222 @@
+//! Typed `SHOW CHANGES` output
+//!
+//! `ShowChangesBuilder::execute` reads the change feed of a table or of the database and returns one `ChangeSet` per transaction.
+//! The table or database needs a `CHANGEFEED`, e.g. `DEFINE TABLE user CHANGEFEED 1h`.
+//!
+//! Continue from the last versionstamp plus one to only read the new changes.
+//! The versionstamps are in the unit of `SINCE`, the raw versionstamps of the output are shifted by 16 bits.
+//!
+//! # Example
+//!
+//! ```rust
+//! use serde::Deserialize;
+//! use surrealdb::engine::any::connect;
+//! use surrealdb::sql::Thing;
+//! use surrealdb_extra::query::changefeed::Change;
+//! use surrealdb_extra::query::statement::StatementBuilder;
+//!
+//! #[derive(Debug, Deserialize)]
+//! struct User {
+//!     id: Thing,
+//!     name: String,
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let db = connect("mem://").await.unwrap();
+//!     db.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     db.query("DEFINE TABLE user CHANGEFEED 1h; CREATE user:1 SET name = 'a';").await.unwrap();
+//!
+//!     // This becomes `SHOW CHANGES FOR TABLE user SINCE 0 LIMIT 100`
+//!     let change_sets = db.show_changes_builder().table("user").since(0).limit(100).execute::<User>().await.unwrap();
+//!
+//!     let users: Vec<User> = change_sets.into_iter()
+//!         .flat_map(|c| c.changes)
+//!         .filter_map(|c| match c {
+//!             Change::Update(user) => Some(user),
+//!             _ => None,
+//!         })
+//!         .collect();
+//!
+//!     assert_eq!(users[0].name, "a");
+//! }
+//! ```
+
+use serde::de::DeserializeOwned;
+use surrealdb::sql::{from_value, Thing, Value};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ChangeFeedError {
+    #[error("Unexpected change feed output `{0}`")]
+    InvalidChange(String),
+    #[error("Could not deserialize the record `{0}`")]
+    Deserialize(String),
+    #[error("{0}")]
+    Db(#[from] surrealdb::Error),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Change<T> {
+    /// The record after it was created or updated
+    Update(T),
+    /// The id of the deleted record
+    Delete(Thing),
+    /// Name of the table that was defined
+    DefineTable(String),
+}
+
+/// The changes of one transaction
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChangeSet<T> {
+    /// Versionstamp of the transaction as it is used by `SINCE`
+    pub versionstamp: u64,
+    pub changes: Vec<Change<T>>,
+}
+
+impl<T: DeserializeOwned> TryFrom<Value> for Change<T> {
+    type Error = ChangeFeedError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        let Value::Object(mut change) = value else {
+            return Err(ChangeFeedError::InvalidChange(value.to_string()));
+        };
+
+        // With `INCLUDE ORIGINAL` the record is in `current` and `update` has the patches to the original
+        if let Some(current) = change.remove("current") {
+            return record(current).map(Self::Update).map_err(ChangeFeedError::Deserialize);
+        }
+
+        if let Some(update) = change.remove("update") {
+            return record(update).map(Self::Update).map_err(ChangeFeedError::Deserialize);
+        }
+
+        match (change.remove("delete"), change.remove("define_table")) {
+            (Some(Value::Object(mut delete)), _) => match delete.remove("id") {
+                Some(Value::Thing(id)) => Ok(Self::Delete(id)),
+                _ => Err(ChangeFeedError::InvalidChange(delete.to_string())),
+            },
+            (_, Some(Value::Object(mut table))) => match table.remove("name") {
+                Some(Value::Strand(name)) => Ok(Self::DefineTable(name.0)),
+                _ => Err(ChangeFeedError::InvalidChange(table.to_string())),
+            },
+            _ => Err(ChangeFeedError::InvalidChange(change.to_string())),
+        }
+    }
+}
+
+impl<T: DeserializeOwned> TryFrom<Value> for ChangeSet<T> {
+    type Error = ChangeFeedError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        let Value::Object(mut change_set) = value else {
+            return Err(ChangeFeedError::InvalidChange(value.to_string()));
+        };
+
+        let versionstamp = match change_set.remove("versionstamp") {
+            Some(Value::Number(versionstamp)) => u64::try_from(versionstamp.to_int())
+                .map_err(|_| ChangeFeedError::InvalidChange(versionstamp.to_string()))? >> 16,
+            _ => return Err(ChangeFeedError::InvalidChange(change_set.to_string())),
+        };
+
+        let Some(Value::Array(changes)) = change_set.remove("changes") else {
+            return Err(ChangeFeedError::InvalidChange(change_set.to_string()));
+        };
+
+        let mut parsed = vec![];
+
+        for change in changes.0 {
+            parsed.push(Change::try_from(change)?);
+        }
+
+        Ok(Self { versionstamp, changes: parsed })
+    }
+}
+
+/// The record or its display when it could not be deserialized
+fn record<T: DeserializeOwned>(value: Value) -> Result<T, String> {
+    let display = value.to_string();
+
+    from_value(value).map_err(|_| display)
+}
+
+#[cfg(test)]
+mod test {
+    use serde::Deserialize;
+    use surrealdb::engine::any::Any;
+    use surrealdb::Surreal;
+    use crate::query::statement::StatementBuilder;
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq, Deserialize)]
+    struct User {
+        id: Thing,
+        name: String,
+    }
+
+    fn user(name: &str) -> User {
+        User { id: Thing::from(("user", "1")), name: name.to_string() }
+    }
+
+    async fn db(definition: &str) -> Surreal<Any> {
+        let db = crate::testing::db().await;
+
+        db.query(definition).await.unwrap().check().unwrap();
+        db.query("
+            CREATE user:⟨1⟩ SET name = 'a';
+            UPDATE user:⟨1⟩ SET name = 'b';
+            DELETE user:⟨1⟩;
+        ").await.unwrap().check().unwrap();
+
+        db
+    }
+
+    fn changes(change_sets: Vec<ChangeSet<User>>) -> Vec<Change<User>> {
+        change_sets.into_iter().flat_map(|c| c.changes).collect()
+    }
+
+    #[tokio::test]
+    async fn table_changes() {
+        let db = db("DEFINE TABLE user CHANGEFEED 1h").await;
+
+        let change_sets = db.show_changes_builder().table("user").since(0).execute::<User>().await.unwrap();
+
+        assert!(change_sets.windows(2).all(|w| w[0].versionstamp < w[1].versionstamp));
+        assert_eq!(changes(change_sets), vec![
+            Change::DefineTable("user".to_string()),
+            Change::Update(user("a")),
+            Change::Update(user("b")),
+            Change::Delete(Thing::from(("user", "1"))),
+        ]);
+    }
+
+    #[tokio::test]
+    async fn include_original() {
+        let db = db("DEFINE TABLE user CHANGEFEED 1h INCLUDE ORIGINAL").await;
+
+        let change_sets = db.show_changes_builder().table("user").since(0).execute::<User>().await.unwrap();
+
+        assert_eq!(changes(change_sets)[2], Change::Update(user("b")));
+    }
+
+    #[tokio::test]
+    async fn since_and_limit() {
+        let db = db("DEFINE TABLE user CHANGEFEED 1h").await;
+
+        let first = db.show_changes_builder().table("user").since(0).limit(2).execute::<User>().await.unwrap();
+        assert_eq!(first.len(), 2);
+
+        let rest = db.show_changes_builder().table("user").since(first[1].versionstamp + 1).execute::<User>().await.unwrap();
+        assert_eq!(changes(rest), vec![Change::Update(user("b")), Change::Delete(Thing::from(("user", "1")))]);
+    }
+
+    #[tokio::test]
+    async fn database_changes() {
+        let db = db("DEFINE DATABASE test CHANGEFEED 1h; DEFINE TABLE user").await;
+
+        let change_sets = db.show_changes_builder().database().since(0).execute::<User>().await.unwrap();
+
+        assert!(changes(change_sets).contains(&Change::Update(user("a"))));
+    }
+}
@@ -0,0 +1,286 @@
+//! Defaults for the statements of the builders
+//!
+//! A `QueryConfig` has a default `TIMEOUT` and turns on `PARALLEL` and `EXPLAIN` (only `SELECT` has it),
+//! e.g. so every statement of the app has a maximum run time.
+//!
+//! - `set_global_config` applies it to the select, create, update and relate builders when they are converted with `to_query`
+//!   or into an `ExtraStatement`/`ExtraSubquery` (e.g. for a script, `Batch` or `PreparedStatement`),
+//!   and to the CRUD methods of `Table`. A timeout set on the builder or by a `Deadline` wins
+//! - `db.with_config(config)` from `QueryConfigExt` starts builders with the config already set on the statement,
+//!   so the config of the handle wins over the deadline and the global config
+//!
+//! # Example
+//!
+//! ```rust
+//! use std::time::Duration;
+//! use surrealdb::engine::any::connect;
+//! use surrealdb_extra::query::config::{QueryConfig, QueryConfigExt, set_global_config};
+//! use surrealdb_extra::query::parsing::statement::ExtraStatement;
+//! use surrealdb_extra::query::statement::StatementBuilder;
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let db = connect("mem://").await.unwrap();
+//!     db.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     set_global_config(QueryConfig::new().timeout(Duration::from_secs(10)));
+//!
+//!     // This becomes `SELECT * FROM test TIMEOUT 10s`
+//!     db.select_builder().what("test").field("*").to_query().await.unwrap();
+//!
+//!     // Also when the builder is converted into a statement, e.g. for a script or a batch
+//!     let statement = ExtraStatement::from(db.create_builder().what("test").content(1));
+//!     assert_eq!(statement.0.to_string(), "CREATE test CONTENT 1 TIMEOUT 10s");
+//!
+//!     // This becomes `SELECT * FROM test TIMEOUT 1s PARALLEL`
+//!     let reports = db.with_config(QueryConfig::new().timeout(Duration::from_secs(1)).parallel());
+//!     reports.select_builder().what("test").field("*").to_query().await.unwrap();
+//! }
+//! ```
+
+use std::marker::PhantomData;
+use std::sync::RwLock;
+use std::time::Duration;
+use surrealdb::{Connection, Surreal};
+use surrealdb::sql::{Explain, Timeout};
+use surrealdb::sql::statements::{CreateStatement, DeleteStatement, InsertStatement, RelateStatement, SelectStatement, UpdateStatement};
+use crate::query::create::CreateBuilder;
+use crate::query::relate::RelateBuilder;
+use crate::query::select::SelectBuilder;
+use crate::query::states::{NoCond, NoData, NoFields, NoRelation, NoWhat};
+use crate::query::update::UpdateBuilder;
+
+static GLOBAL: RwLock<Option<QueryConfig>> = RwLock::new(None);
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct QueryConfig {
+    pub timeout: Option<Duration>,
+    pub parallel: bool,
+    pub explain: bool,
+}
+
+impl QueryConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// `TIMEOUT` of the statements without one
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+
+        self
+    }
+
+    /// `PARALLEL` on every statement
+    pub fn parallel(mut self) -> Self {
+        self.parallel = true;
+
+        self
+    }
+
+    /// `EXPLAIN` on every select, the selects return the query plan instead of the records
+    pub fn explain(mut self) -> Self {
+        self.explain = true;
+
+        self
+    }
+
+    /// Sets the clauses of the config that are not set on the statement
+    pub(crate) fn fill<S: ConfigStatement>(&self, mut statement: S) -> S {
+        if let Some(duration) = self.timeout {
+            let timeout = statement.timeout_mut();
+
+            if timeout.is_none() {
+                let mut t = Timeout::default();
+                t.0 = duration.into();
+
+                *timeout = Some(t);
+            }
+        }
+
+        if self.parallel {
+            *statement.parallel_mut() = true;
+        }
+
+        if let Some(explain) = statement.explain_mut().filter(|e| self.explain && e.is_none()) {
+            *explain = Some(Explain::default());
+        }
+
+        statement
+    }
+}
+
+/// Sets the config of all builders, replaces the previous one
+pub fn set_global_config(config: QueryConfig) {
+    *GLOBAL.write().unwrap_or_else(|e| e.into_inner()) = Some(config);
+}
+
+pub fn clear_global_config() {
+    *GLOBAL.write().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+pub fn global_config() -> Option<QueryConfig> {
+    GLOBAL.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// The statements the config and the deadline are applied to
+pub(crate) trait ConfigStatement {
+    fn timeout_mut(&mut self) -> &mut Option<Timeout>;
+
+    fn parallel_mut(&mut self) -> &mut bool;
+
+    fn explain_mut(&mut self) -> Option<&mut Option<Explain>> {
+        None
+    }
+}
+
+macro_rules! create_config_statement {
+    ($x:ty) => {
+        impl ConfigStatement for $x {
+            fn timeout_mut(&mut self) -> &mut Option<Timeout> {
+                &mut self.timeout
+            }
+
+            fn parallel_mut(&mut self) -> &mut bool {
+                &mut self.parallel
+            }
+        }
+    };
+}
+
+create_config_statement!(UpdateStatement);
+create_config_statement!(CreateStatement);
+create_config_statement!(RelateStatement);
+create_config_statement!(DeleteStatement);
+create_config_statement!(InsertStatement);
+
+impl ConfigStatement for SelectStatement {
+    fn timeout_mut(&mut self) -> &mut Option<Timeout> {
+        &mut self.timeout
+    }
+
+    fn parallel_mut(&mut self) -> &mut bool {
+        &mut self.parallel
+    }
+
+    fn explain_mut(&mut self) -> Option<&mut Option<Explain>> {
+        Some(&mut self.explain)
+    }
+}
+
+/// Applies the deadline of the task and then the global config to the statement of a builder
+pub(crate) fn apply<S: ConfigStatement>(statement: S) -> S {
+    #[cfg(feature = "deadline")]
+    let statement = crate::query::deadline::apply(statement);
+
+    match global_config() {
+        Some(config) => config.fill(statement),
+        None => statement,
+    }
+}
+
+/// Starts builders with a config from a `Surreal` handle
+pub trait QueryConfigExt<Client>
+    where Client: Connection
+{
+    fn with_config(&self, config: QueryConfig) -> Configured<'_, Client>;
+}
+
+impl<Client: Connection> QueryConfigExt<Client> for Surreal<Client> {
+    fn with_config(&self, config: QueryConfig) -> Configured<'_, Client> {
+        Configured {
+            db: self,
+            config,
+        }
+    }
+}
+
+/// A handle that starts every builder with its config
+#[derive(Debug, Clone)]
+pub struct Configured<'r, Client>
+    where Client: Connection
+{
+    pub(crate) db: &'r Surreal<Client>,
+    pub config: QueryConfig,
+}
+
+impl<'r, Client> Configured<'r, Client>
+    where Client: Connection
+{
+    pub fn select_builder(&self) -> SelectBuilder<'r, Client, NoWhat, NoFields, NoCond> {
+        SelectBuilder {
+            statement: self.config.fill(SelectStatement::default()),
+            db: self.db,
+            what_state: PhantomData,
+            fields_state: PhantomData,
+            cond_state: PhantomData,
+        }
+    }
+
+    pub fn update_builder(&self) -> UpdateBuilder<'r, Client, NoWhat, NoData, NoCond> {
+        UpdateBuilder {
+            statement: self.config.fill(UpdateStatement::default()),
+            db: self.db,
+            what_state: PhantomData,
+            data_state: PhantomData,
+            cond_state: PhantomData,
+        }
+    }
+
+    pub fn relate_builder(&self) -> RelateBuilder<'r, Client, NoRelation, NoData> {
+        RelateBuilder {
+            statement: self.config.fill(RelateStatement::default()),
+            db: self.db,
+            relate_state: PhantomData,
+            data_state: PhantomData,
+        }
+    }
+
+    pub fn create_builder(&self) -> CreateBuilder<'r, Client, NoWhat, NoData> {
+        CreateBuilder {
+            statement: self.config.fill(CreateStatement::default()),
+            db: self.db,
+            what_state: PhantomData,
+            data_state: PhantomData,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use surrealdb::engine::any::connect;
+    use surrealdb::sql::Field;
+    use crate::query::statement::StatementBuilder;
+    use super::*;
+
+    #[test]
+    fn fill_keeps_statement_clauses() {
+        let config = QueryConfig::new().timeout(Duration::from_secs(10)).parallel().explain();
+
+        let statement = config.fill(SelectStatement::default());
+        assert_eq!(statement.timeout.unwrap().0.0, Duration::from_secs(10));
+        assert!(statement.parallel);
+        assert!(statement.explain.is_some());
+
+        let mut timeout = Timeout::default();
+        timeout.0 = Duration::from_secs(1).into();
+
+        let mut statement = UpdateStatement::default();
+        statement.timeout = Some(timeout);
+
+        let statement = config.fill(statement);
+        assert_eq!(statement.timeout.unwrap().0.0, Duration::from_secs(1));
+        assert!(statement.parallel);
+    }
+
+    #[tokio::test]
+    async fn configured_builders() {
+        let db = connect("mem://").await.unwrap();
+
+        let configured = db.with_config(QueryConfig::new().timeout(Duration::from_secs(5)).parallel());
+
+        assert_eq!(configured.select_builder().what("test").field(Field::All).to_surql(), "SELECT * FROM test TIMEOUT 5s PARALLEL");
+        assert_eq!(configured.select_builder().what("test").field(Field::All).timeout(Duration::from_secs(1)).to_surql(), "SELECT * FROM test TIMEOUT 1s PARALLEL");
+        assert_eq!(db.select_builder().what("test").field(Field::All).to_surql(), "SELECT * FROM test");
+    }
+}
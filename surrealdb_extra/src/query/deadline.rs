@@ -0,0 +1,134 @@
+//! Request deadlines for the builders
+//!
+//! Run a future inside `Deadline::scope` and every builder that is converted with `to_query` inside it gets a `TIMEOUT`
+//! of the remaining time, unless a timeout was set on the builder.
+//!
+//! # Example
+//!
+//! ```rust
+//! use std::time::Duration;
+//! use surrealdb::engine::any::connect;
+//! use surrealdb_extra::query::deadline::Deadline;
+//! use surrealdb_extra::query::statement::StatementBuilder;
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let db = connect("mem://").await.unwrap();
+//!     db.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     // e.g. in a middleware around the request handler
+//!     Deadline::after(Duration::from_secs(2)).scope(async {
+//!         // This becomes `SELECT * FROM test TIMEOUT 2s` minus the time already spent
+//!         db.select_builder().what("test").field("*").to_query().await.unwrap();
+//!     }).await;
+//! }
+//! ```
+
+use std::future::Future;
+use std::time::{Duration, Instant};
+use surrealdb::sql::Timeout;
+use crate::query::config::ConfigStatement;
+
+tokio::task_local! {
+    static DEADLINE: Deadline;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Deadline(pub Instant);
+
+impl Deadline {
+    pub fn at(instant: Instant) -> Self {
+        Self(instant)
+    }
+
+    pub fn after(duration: Duration) -> Self {
+        Self(Instant::now() + duration)
+    }
+
+    /// The deadline of the current task if it runs inside a scope
+    pub fn current() -> Option<Self> {
+        DEADLINE.try_with(|d| *d).ok()
+    }
+
+    pub fn remaining(&self) -> Duration {
+        self.0.saturating_duration_since(Instant::now())
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.remaining().is_zero()
+    }
+
+    /// Runs the future with this deadline
+    pub async fn scope<F: Future>(self, f: F) -> F::Output {
+        DEADLINE.scope(self, f).await
+    }
+
+    /// `TIMEOUT` for the remaining time, an expired deadline still gets the smallest possible timeout so the query fails fast
+    pub fn timeout(&self) -> Timeout {
+        let mut timeout = Timeout::default();
+        timeout.0 = self.remaining().max(Duration::from_millis(1)).into();
+
+        timeout
+    }
+}
+
+/// Sets the timeout of the statement to the current deadline when no timeout is set
+pub(crate) fn apply<S: ConfigStatement>(mut statement: S) -> S {
+    let timeout = statement.timeout_mut();
+
+    if timeout.is_none() {
+        *timeout = Deadline::current().map(|d| d.timeout());
+    }
+
+    statement
+}
+
+#[cfg(test)]
+mod test {
+    use surrealdb::engine::any::connect;
+    use surrealdb::sql::statements::SelectStatement;
+    use crate::query::statement::StatementBuilder;
+    use super::*;
+
+    #[tokio::test]
+    async fn no_scope() {
+        assert!(Deadline::current().is_none());
+
+        let statement = apply(SelectStatement::default());
+
+        assert!(statement.timeout.is_none());
+    }
+
+    #[tokio::test]
+    async fn scope_sets_timeout() {
+        Deadline::after(Duration::from_secs(5)).scope(async {
+            let statement = apply(SelectStatement::default());
+
+            let timeout = statement.timeout.unwrap();
+
+            assert!(timeout.0.0 <= Duration::from_secs(5));
+            assert!(timeout.0.0 > Duration::from_secs(4));
+        }).await;
+    }
+
+    #[tokio::test]
+    async fn explicit_timeout_wins() {
+        let db = connect("mem://").await.unwrap();
+
+        Deadline::after(Duration::from_secs(5)).scope(async {
+            let select = db.select_builder().what("test").field("*").timeout(Duration::from_secs(30));
+
+            let statement = apply(select.statement);
+
+            assert_eq!(statement.timeout.unwrap().0.0, Duration::from_secs(30));
+        }).await;
+    }
+
+    #[test]
+    fn expired() {
+        let deadline = Deadline::at(Instant::now() - Duration::from_secs(1));
+
+        assert!(deadline.is_expired());
+        assert_eq!(deadline.timeout().0.0, Duration::from_millis(1));
+    }
+}
@@ -0,0 +1,185 @@
+//! Parsed `EXPLAIN` output
+//!
+//! `SelectBuilder::execute_explain` runs the select with `EXPLAIN` and returns the plan as a `QueryPlan`,
+//! e.g. to assert in a test that a query uses an index instead of scanning the table.
+//!
+//! # Example
+//!
+//! ```rust
+//! use surrealdb::engine::any::connect;
+//! use surrealdb::sql::{idiom, Field, Operator, Value};
+//! use surrealdb_extra::cond_vec;
+//! use surrealdb_extra::query::statement::StatementBuilder;
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let db = connect("mem://").await.unwrap();
+//!     db.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     db.query("DEFINE INDEX email ON user FIELDS email").await.unwrap();
+//!
+//!     let plan = db.select_builder().what("user").field(Field::All)
+//!         .condition(cond_vec![(Value::Idiom(idiom("email").unwrap()), Operator::Equal, Value::from("a"))])
+//!         .explain_full()
+//!         .execute_explain().await.unwrap();
+//!
+//!     assert!(plan.uses_index("email"));
+//!     assert!(!plan.is_table_scan());
+//! }
+//! ```
+
+use surrealdb::sql::{Object, Value};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ExplainError {
+    #[error("Unexpected explain output `{0}`")]
+    InvalidPlan(String),
+    #[error("{0}")]
+    Db(#[from] surrealdb::Error),
+}
+
+/// One step of the plan, e.g. `Iterate Index`, `Iterate Table`, `Collector` or `Fetch` (only with `EXPLAIN FULL`)
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlanOperation {
+    pub operation: String,
+    pub detail: Object,
+}
+
+impl PlanOperation {
+    /// Name of the index for `Iterate Index` operations
+    pub fn index(&self) -> Option<String> {
+        match self.detail.get("plan")? {
+            Value::Object(plan) => match plan.get("index")? {
+                Value::Strand(index) => Some(index.0.clone()),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    pub fn table(&self) -> Option<String> {
+        match self.detail.get("table")? {
+            Value::Strand(table) => Some(table.0.clone()),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct QueryPlan {
+    pub operations: Vec<PlanOperation>,
+}
+
+impl QueryPlan {
+    /// Indexes used by the plan
+    pub fn indexes(&self) -> Vec<String> {
+        self.operations.iter().filter_map(|o| o.index()).collect()
+    }
+
+    pub fn uses_index(&self, index: &str) -> bool {
+        self.operations.iter().any(|o| o.index().as_deref() == Some(index))
+    }
+
+    /// True when a whole table is iterated
+    pub fn is_table_scan(&self) -> bool {
+        self.operations.iter().any(|o| o.operation == "Iterate Table")
+    }
+
+    /// Number of fetched records, only set with `EXPLAIN FULL`
+    pub fn fetched(&self) -> Option<u64> {
+        let fetch = self.operations.iter().find(|o| o.operation == "Fetch")?;
+
+        match fetch.detail.get("count")? {
+            Value::Number(count) => u64::try_from(count.to_int()).ok(),
+            _ => None,
+        }
+    }
+}
+
+impl TryFrom<Value> for QueryPlan {
+    type Error = ExplainError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        let Value::Array(operations) = value else {
+            return Err(ExplainError::InvalidPlan(value.to_string()));
+        };
+
+        let mut plan = Self::default();
+
+        for operation in operations.0 {
+            let Value::Object(mut operation) = operation else {
+                return Err(ExplainError::InvalidPlan(operation.to_string()));
+            };
+
+            let name = match operation.remove("operation") {
+                Some(Value::Strand(name)) => name.0,
+                _ => return Err(ExplainError::InvalidPlan(operation.to_string())),
+            };
+
+            let detail = match operation.remove("detail") {
+                Some(Value::Object(detail)) => detail,
+                _ => Object::default(),
+            };
+
+            plan.operations.push(PlanOperation { operation: name, detail });
+        }
+
+        Ok(plan)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use surrealdb::engine::any::Any;
+    use surrealdb::sql::{idiom, Field, Operator};
+    use surrealdb::Surreal;
+    use crate::cond_vec;
+    use crate::query::statement::StatementBuilder;
+    use super::*;
+
+    async fn db() -> Surreal<Any> {
+        let db = crate::testing::db().await;
+
+        db.query("
+            DEFINE INDEX email ON user FIELDS email;
+            CREATE user SET email = 'a', name = 'a';
+            CREATE user SET email = 'b', name = 'b';
+        ").await.unwrap().check().unwrap();
+
+        db
+    }
+
+    #[tokio::test]
+    async fn index_scan() {
+        let db = db().await;
+
+        let plan = db.select_builder().what("user").field(Field::All)
+            .condition(cond_vec![(Value::Idiom(idiom("email").unwrap()), Operator::Equal, Value::from("a"))])
+            .explain_full()
+            .execute_explain().await.unwrap();
+
+        assert_eq!(plan.indexes(), vec!["email".to_string()]);
+        assert!(!plan.is_table_scan());
+        assert_eq!(plan.fetched(), Some(1));
+    }
+
+    #[tokio::test]
+    async fn table_scan() {
+        let db = db().await;
+
+        let plan = db.select_builder().what("user").field(Field::All)
+            .condition(cond_vec![(Value::Idiom(idiom("name").unwrap()), Operator::Equal, Value::from("a"))])
+            .execute_explain().await.unwrap();
+
+        assert!(plan.is_table_scan());
+        assert_eq!(plan.operations[0].table(), Some("user".to_string()));
+        assert!(plan.indexes().is_empty());
+        assert_eq!(plan.fetched(), None);
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(QueryPlan::try_from(Value::from("plan")).is_err());
+    }
+}
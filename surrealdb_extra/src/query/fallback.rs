@@ -0,0 +1,195 @@
+//! Fallbacks for clauses the engine doesn't support
+//!
+//! Engines differ in which clauses they support, e.g. `VERSION` only works on engines with versioned storage and fails on `mem://`.
+//! `Capabilities::probe` runs a read only query for every clause and `to_query_with` on the builders drops the clauses
+//! that aren't supported with a warning through the `log` crate instead of failing the query.
+//!
+//! `PARALLEL` and `TEMPFILES` don't change the result so dropping them is the same as running without them,
+//! a select without `VERSION` reads the latest data.
+//! `EXPLAIN` returns the plan instead of the rows, so an unsupported `EXPLAIN` is a `FallbackError::Unsupported` instead of being dropped.
+//!
+//! # Example
+//!
+//! ```rust
+//! use chrono::Utc;
+//! use surrealdb::engine::any::connect;
+//! use surrealdb::sql::Field;
+//! use surrealdb_extra::query::fallback::Capabilities;
+//! use surrealdb_extra::query::statement::StatementBuilder;
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let db = connect("mem://").await.unwrap();
+//!     db.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     let capabilities = Capabilities::probe(&db).await.unwrap();
+//!     assert!(!capabilities.version);
+//!
+//!     // Runs without the VERSION clause on mem:// and with it on engines that support it
+//!     db.select_builder().what("test").field(Field::All).version(Utc::now()).to_query_with(&capabilities).unwrap().await.unwrap().check().unwrap();
+//! }
+//! ```
+
+use anyhow::Result;
+use surrealdb::{Connection, Surreal};
+use surrealdb::sql::statements::{CreateStatement, RelateStatement, SelectStatement, UpdateStatement};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum FallbackError {
+    #[error("{0} is not supported by the engine and can't be dropped from the {1} statement without changing the result")]
+    Unsupported(&'static str, &'static str),
+}
+
+/// Clauses the engine supports, the default assumes every clause is supported
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Capabilities {
+    pub parallel: bool,
+    pub tempfiles: bool,
+    pub explain: bool,
+    pub version: bool,
+}
+
+impl Default for Capabilities {
+    fn default() -> Self {
+        Self {
+            parallel: true,
+            tempfiles: true,
+            explain: true,
+            version: true,
+        }
+    }
+}
+
+impl Capabilities {
+    /// Runs a read only query for every clause, the table in the version query doesn't need to exist
+    pub async fn probe<C: Connection>(db: &Surreal<C>) -> Result<Self> {
+        Ok(Self {
+            parallel: supports(db, "SELECT * FROM [] PARALLEL").await?,
+            tempfiles: supports(db, "SELECT * FROM [] TEMPFILES").await?,
+            explain: supports(db, "SELECT * FROM [] EXPLAIN").await?,
+            version: supports(db, "SELECT * FROM capability_probe VERSION d'2024-01-01T00:00:00Z'").await?,
+        })
+    }
+}
+
+/// Errors of the statement mean the clause isn't supported, errors of the connection are returned
+async fn supports<C: Connection>(db: &Surreal<C>, query: &str) -> Result<bool> {
+    let res = db.query(query).await?;
+
+    Ok(res.check().is_ok())
+}
+
+pub(crate) trait Fallback {
+    fn fallback(self, capabilities: &Capabilities) -> Result<Self, FallbackError> where Self: Sized;
+}
+
+fn drop_clause(clause: &str, statement: &str) {
+    log::warn!("{clause} is not supported by the engine and was dropped from the {statement} statement");
+}
+
+impl Fallback for SelectStatement {
+    fn fallback(mut self, capabilities: &Capabilities) -> Result<Self, FallbackError> {
+        if self.parallel && !capabilities.parallel {
+            drop_clause("PARALLEL", "SELECT");
+            self.parallel = false;
+        }
+
+        if self.tempfiles && !capabilities.tempfiles {
+            drop_clause("TEMPFILES", "SELECT");
+            self.tempfiles = false;
+        }
+
+        if self.explain.is_some() && !capabilities.explain {
+            return Err(FallbackError::Unsupported("EXPLAIN", "SELECT"));
+        }
+
+        if self.version.is_some() && !capabilities.version {
+            drop_clause("VERSION", "SELECT");
+            self.version = None;
+        }
+
+        Ok(self)
+    }
+}
+
+macro_rules! create_parallel_fallback {
+    ($x:ty, $s:literal) => {
+        impl Fallback for $x {
+            fn fallback(mut self, capabilities: &Capabilities) -> Result<Self, FallbackError> {
+                if self.parallel && !capabilities.parallel {
+                    drop_clause("PARALLEL", $s);
+                    self.parallel = false;
+                }
+
+                Ok(self)
+            }
+        }
+    };
+}
+
+create_parallel_fallback!(UpdateStatement, "UPDATE");
+create_parallel_fallback!(CreateStatement, "CREATE");
+create_parallel_fallback!(RelateStatement, "RELATE");
+
+/// Drops the clauses of the statement that aren't supported, fails for the ones that change the result
+pub(crate) fn apply<S: Fallback>(statement: S, capabilities: &Capabilities) -> Result<S, FallbackError> {
+    statement.fallback(capabilities)
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::Utc;
+    use surrealdb::engine::any::connect;
+    use crate::query::statement::StatementBuilder;
+    use crate::testing::db;
+    use super::*;
+
+    #[tokio::test]
+    async fn probe_mem() {
+        let db = db().await;
+
+        let capabilities = Capabilities::probe(&db).await.unwrap();
+
+        assert!(capabilities.parallel);
+        assert!(capabilities.explain);
+        assert!(!capabilities.version);
+    }
+
+    #[tokio::test]
+    async fn drops_unsupported() {
+        let db = connect("mem://").await.unwrap();
+
+        let capabilities = Capabilities { parallel: false, version: false, ..Default::default() };
+
+        let select = db.select_builder().what("test").field("test").parallel().explain().version(Utc::now());
+
+        let statement = apply(select.statement, &capabilities).unwrap();
+
+        assert!(!statement.parallel);
+        assert!(statement.version.is_none());
+        assert!(statement.explain.is_some());
+    }
+
+    #[tokio::test]
+    async fn unsupported_explain() {
+        let db = connect("mem://").await.unwrap();
+
+        let capabilities = Capabilities { explain: false, ..Default::default() };
+
+        assert!(db.select_builder().what("test").field("test").explain().to_query_with(&capabilities).is_err());
+        assert!(db.select_builder().what("test").field("test").to_query_with(&capabilities).is_ok());
+    }
+
+    #[tokio::test]
+    async fn runs_on_mem() {
+        let db = db().await;
+
+        let capabilities = Capabilities::probe(&db).await.unwrap();
+
+        let select = || db.select_builder().what("test").field("test").version(Utc::now());
+
+        assert!(select().to_query().await.unwrap().check().is_err());
+        assert!(select().to_query_with(&capabilities).unwrap().await.unwrap().check().is_ok());
+    }
+}
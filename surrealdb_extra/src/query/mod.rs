@@ -8,3 +8,23 @@ pub use builder::*;
 pub mod statement;
 pub mod parsing;
 pub mod states;
+pub mod explain;
+pub mod changefeed;
+pub mod raw;
+pub mod batch;
+pub mod prepared;
+pub mod observer;
+pub mod scope;
+pub mod config;
+
+#[cfg_attr(docsrs, doc(cfg(feature = "deadline")))]
+#[cfg(feature = "deadline")]
+pub mod deadline;
+
+#[cfg_attr(docsrs, doc(cfg(feature = "fallback")))]
+#[cfg(feature = "fallback")]
+pub mod fallback;
+
+#[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
+#[cfg(feature = "stream")]
+pub mod stream;
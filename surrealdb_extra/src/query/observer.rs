@@ -0,0 +1,282 @@
+//! Hooks around the execution of the builders, e.g. for query latency metrics
+//!
+//! `observed` on the select, create, update and relate builders runs the query like `to_query().await` followed by `check()`
+//! and calls the observers with the kind of statement, its tables and its SurrealQL.
+//! The global observer is called for every observed query, an observer that is passed to `observer` only for that query.
+//!
+//! With the `metrics` feature `MetricsObserver` records a `surrealdb_query_duration_seconds` histogram and
+//! a `surrealdb_queries_total` counter with the labels `kind`, `table` and `status`.
+//!
+//! # Example
+//!
+//! ```rust
+//! use std::sync::Arc;
+//! use std::sync::atomic::{AtomicUsize, Ordering};
+//! use std::time::Duration;
+//! use surrealdb::engine::any::connect;
+//! use surrealdb::sql::Field;
+//! use surrealdb_extra::query::observer::{QueryInfo, QueryObserver};
+//! use surrealdb_extra::query::statement::StatementBuilder;
+//!
+//! #[derive(Default)]
+//! struct SlowQueries(AtomicUsize);
+//!
+//! impl QueryObserver for SlowQueries {
+//!     fn on_complete(&self, _info: &QueryInfo, elapsed: Duration) {
+//!         if elapsed > Duration::from_millis(100) {
+//!             self.0.fetch_add(1, Ordering::Relaxed);
+//!         }
+//!     }
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let db = connect("mem://").await.unwrap();
+//!     db.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     let slow = Arc::new(SlowQueries::default());
+//!
+//!     db.select_builder().what("user").field(Field::All).observed().observer(slow.clone()).await.unwrap();
+//!
+//!     assert_eq!(slow.0.load(Ordering::Relaxed), 0);
+//! }
+//! ```
+
+use std::future::{Future, IntoFuture};
+use std::pin::Pin;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use surrealdb::{Connection, Response};
+use surrealdb::method::Query;
+use surrealdb::sql::Value;
+
+#[cfg(not(feature = "wasm"))]
+use std::time::Instant;
+
+#[cfg(feature = "wasm")]
+use web_time::Instant;
+
+static GLOBAL: RwLock<Option<Arc<dyn QueryObserver>>> = RwLock::new(None);
+
+/// The statement that is observed
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryInfo {
+    /// `SELECT`, `CREATE`, `UPDATE` or `RELATE`
+    pub kind: &'static str,
+    /// The tables of the records or tables of the statement, parameters and subqueries are left out
+    pub tables: Vec<String>,
+    pub surql: String,
+}
+
+impl QueryInfo {
+    pub fn new<'a>(kind: &'static str, what: impl IntoIterator<Item = &'a Value>, surql: String) -> Self {
+        let mut tables: Vec<String> = vec![];
+
+        for table in what.into_iter().filter_map(table_name) {
+            if !tables.contains(&table) {
+                tables.push(table);
+            }
+        }
+
+        Self {
+            kind,
+            tables,
+            surql,
+        }
+    }
+}
+
+fn table_name(value: &Value) -> Option<String> {
+    match value {
+        Value::Table(table) => Some(table.0.clone()),
+        Value::Thing(thing) => Some(thing.tb.clone()),
+        _ => None,
+    }
+}
+
+/// All functions do nothing by default
+pub trait QueryObserver: Send + Sync {
+    fn on_start(&self, _info: &QueryInfo) {}
+
+    fn on_complete(&self, _info: &QueryInfo, _elapsed: Duration) {}
+
+    /// The request failed or the statement returned an error
+    fn on_error(&self, _info: &QueryInfo, _elapsed: Duration, _error: &surrealdb::Error) {}
+}
+
+impl<T: QueryObserver + ?Sized> QueryObserver for Arc<T> {
+    fn on_start(&self, info: &QueryInfo) {
+        (**self).on_start(info)
+    }
+
+    fn on_complete(&self, info: &QueryInfo, elapsed: Duration) {
+        (**self).on_complete(info, elapsed)
+    }
+
+    fn on_error(&self, info: &QueryInfo, elapsed: Duration, error: &surrealdb::Error) {
+        (**self).on_error(info, elapsed, error)
+    }
+}
+
+/// Sets the observer of all observed queries, replaces the previous one
+pub fn set_global_observer(observer: impl QueryObserver + 'static) {
+    *GLOBAL.write().unwrap_or_else(|e| e.into_inner()) = Some(Arc::new(observer));
+}
+
+pub fn clear_global_observer() {
+    *GLOBAL.write().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+fn global_observer() -> Option<Arc<dyn QueryObserver>> {
+    GLOBAL.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// A query of a builder with observers, await it to run it
+pub struct Observed<'r, Client>
+    where Client: Connection
+{
+    pub info: QueryInfo,
+    query: Query<'r, Client>,
+    observer: Option<Arc<dyn QueryObserver>>,
+}
+
+impl<'r, Client> Observed<'r, Client>
+    where Client: Connection
+{
+    pub fn new(info: QueryInfo, query: Query<'r, Client>) -> Self {
+        Self {
+            info,
+            query,
+            observer: None,
+        }
+    }
+
+    /// Observer for this query only, the global observer is still called
+    pub fn observer(mut self, observer: impl QueryObserver + 'static) -> Self {
+        self.observer = Some(Arc::new(observer));
+
+        self
+    }
+}
+
+impl<'r, Client> IntoFuture for Observed<'r, Client>
+    where Client: Connection
+{
+    type Output = surrealdb::Result<Response>;
+    type IntoFuture = Pin<Box<dyn Future<Output = Self::Output> + Send + 'r>>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        let Self { info, query, observer } = self;
+
+        let observers: Vec<Arc<dyn QueryObserver>> = global_observer().into_iter().chain(observer).collect();
+
+        Box::pin(async move {
+            for observer in &observers {
+                observer.on_start(&info);
+            }
+
+            let start = Instant::now();
+
+            let res = match query.await {
+                Ok(res) => res.check(),
+                Err(e) => Err(e),
+            };
+
+            let elapsed = start.elapsed();
+
+            for observer in &observers {
+                match &res {
+                    Ok(_) => observer.on_complete(&info, elapsed),
+                    Err(e) => observer.on_error(&info, elapsed, e),
+                }
+            }
+
+            res
+        })
+    }
+}
+
+/// Records the duration and count of the queries with the `metrics` crate
+#[cfg_attr(docsrs, doc(cfg(feature = "metrics")))]
+#[cfg(feature = "metrics")]
+#[derive(Debug, Clone, Default)]
+pub struct MetricsObserver;
+
+#[cfg(feature = "metrics")]
+impl MetricsObserver {
+    fn record(info: &QueryInfo, elapsed: Duration, status: &'static str) {
+        let table = info.tables.join(",");
+
+        metrics::histogram!("surrealdb_query_duration_seconds", "kind" => info.kind, "table" => table.clone(), "status" => status)
+            .record(elapsed.as_secs_f64());
+        metrics::counter!("surrealdb_queries_total", "kind" => info.kind, "table" => table, "status" => status)
+            .increment(1);
+    }
+}
+
+#[cfg(feature = "metrics")]
+impl QueryObserver for MetricsObserver {
+    fn on_complete(&self, info: &QueryInfo, elapsed: Duration) {
+        Self::record(info, elapsed, "ok");
+    }
+
+    fn on_error(&self, info: &QueryInfo, elapsed: Duration, _error: &surrealdb::Error) {
+        Self::record(info, elapsed, "error");
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Mutex;
+    use surrealdb::sql::{Field, Operator, Thing};
+    use crate::query::statement::StatementBuilder;
+    use crate::testing::db;
+    use super::*;
+
+    #[derive(Default)]
+    struct Recorder(Mutex<Vec<String>>);
+
+    impl QueryObserver for Recorder {
+        fn on_start(&self, info: &QueryInfo) {
+            self.0.lock().unwrap().push(format!("start {} {}", info.kind, info.tables.join(",")));
+        }
+
+        fn on_complete(&self, info: &QueryInfo, _elapsed: Duration) {
+            self.0.lock().unwrap().push(format!("complete {}", info.kind));
+        }
+
+        fn on_error(&self, info: &QueryInfo, _elapsed: Duration, _error: &surrealdb::Error) {
+            self.0.lock().unwrap().push(format!("error {}", info.kind));
+        }
+    }
+
+    #[test]
+    fn info_tables() {
+        let what = [Value::from(Thing::from(("user", "one"))), Value::Table("user".into()), Value::Table("post".into()), Value::Param("p".into())];
+
+        let info = QueryInfo::new("SELECT", &what, String::new());
+
+        assert_eq!(info.tables, ["user", "post"]);
+    }
+
+    #[tokio::test]
+    async fn observed() {
+        let db = db().await;
+
+        let recorder = Arc::new(Recorder::default());
+
+        db.create_builder().what("user").set(vec![("name", Operator::Equal, "one")])
+            .observed().observer(recorder.clone()).await.unwrap();
+
+        let select = db.select_builder().what("user").field(Field::All).timeout(Duration::from_nanos(1)).parallel();
+        assert_eq!(select.clone().observed().info.surql, select.to_surql());
+
+        db.query("DEFINE TABLE post SCHEMAFULL; DEFINE FIELD n ON post TYPE int").await.unwrap();
+
+        let res = db.create_builder().what("post").set(vec![("n", Operator::Equal, "not a number")])
+            .observed().observer(recorder.clone()).await;
+        assert!(res.is_err());
+
+        assert_eq!(*recorder.0.lock().unwrap(), ["start CREATE user", "complete CREATE", "start CREATE post", "error CREATE"]);
+    }
+}
@@ -0,0 +1,190 @@
+//! Aggregate functions as fields
+//!
+//! The constructors build the function call directly, so the field doesn't depend on a string being parsed as the right value.
+//!
+//! # Example
+//!
+//! ```rust
+//! use surrealdb::engine::any::connect;
+//! use surrealdb_extra::query::parsing::aggregate::Agg;
+//! use surrealdb_extra::query::statement::StatementBuilder;
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let db = connect("mem://").await.unwrap();
+//!     db.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     db.select_builder().what("order")
+//!         .field(Agg::count().alias("orders"))
+//!         .field(Agg::sum("price").alias("revenue"))
+//!         .field(Agg::array_group("tags"))
+//!         .group("customer")
+//!         .to_query();
+//!     // The above builder becomes `SELECT count() AS orders, math::sum(price) AS revenue, array::group(tags) FROM order GROUP BY customer`
+//! }
+//! ```
+
+use surrealdb::sql::{Field, Function, Idiom, Value};
+use crate::query::parsing::field::ExtraField;
+use crate::query::parsing::idiom::ExtraIdiom;
+
+#[derive(Debug, Clone)]
+pub struct Agg {
+    pub function: Function,
+    pub alias: Option<Idiom>,
+}
+
+impl Agg {
+    /// Any function that takes the field as its only argument e.g. `Agg::function("math::interquartile", "score")`
+    pub fn function(name: impl Into<String>, field: impl Into<ExtraIdiom>) -> Self {
+        Self::new(name, vec![Value::Idiom(field.into().0)])
+    }
+
+    fn new(name: impl Into<String>, args: Vec<Value>) -> Self {
+        Self {
+            function: Function::Normal(name.into(), args),
+            alias: None,
+        }
+    }
+
+    pub fn alias(mut self, alias: impl Into<ExtraIdiom>) -> Self {
+        self.alias = Some(alias.into().0);
+
+        self
+    }
+
+    /// `count()`
+    pub fn count() -> Self {
+        Self::new("count", vec![])
+    }
+
+    /// `count(field)`, counts the records where the field is truthy
+    pub fn count_field(field: impl Into<ExtraIdiom>) -> Self {
+        Self::function("count", field)
+    }
+
+    /// `math::sum(field)`
+    pub fn sum(field: impl Into<ExtraIdiom>) -> Self {
+        Self::function("math::sum", field)
+    }
+
+    /// `math::mean(field)`
+    pub fn math_mean(field: impl Into<ExtraIdiom>) -> Self {
+        Self::function("math::mean", field)
+    }
+
+    /// `math::median(field)`
+    pub fn math_median(field: impl Into<ExtraIdiom>) -> Self {
+        Self::function("math::median", field)
+    }
+
+    /// `math::min(field)`
+    pub fn math_min(field: impl Into<ExtraIdiom>) -> Self {
+        Self::function("math::min", field)
+    }
+
+    /// `math::max(field)`
+    pub fn math_max(field: impl Into<ExtraIdiom>) -> Self {
+        Self::function("math::max", field)
+    }
+
+    /// `math::stddev(field)`
+    pub fn math_stddev(field: impl Into<ExtraIdiom>) -> Self {
+        Self::function("math::stddev", field)
+    }
+
+    /// `math::variance(field)`
+    pub fn math_variance(field: impl Into<ExtraIdiom>) -> Self {
+        Self::function("math::variance", field)
+    }
+
+    /// `time::min(field)`
+    pub fn time_min(field: impl Into<ExtraIdiom>) -> Self {
+        Self::function("time::min", field)
+    }
+
+    /// `time::max(field)`
+    pub fn time_max(field: impl Into<ExtraIdiom>) -> Self {
+        Self::function("time::max", field)
+    }
+
+    /// `array::group(field)`, the unique values of all records flattened into one array
+    pub fn array_group(field: impl Into<ExtraIdiom>) -> Self {
+        Self::function("array::group", field)
+    }
+
+    /// `array::distinct(field)`
+    pub fn array_distinct(field: impl Into<ExtraIdiom>) -> Self {
+        Self::function("array::distinct", field)
+    }
+}
+
+impl From<Agg> for Value {
+    fn from(value: Agg) -> Self {
+        Value::Function(Box::new(value.function))
+    }
+}
+
+impl From<Agg> for ExtraField {
+    fn from(value: Agg) -> Self {
+        let alias = value.alias.clone();
+
+        Self(Field::Single {
+            expr: value.into(),
+            alias,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde::Deserialize;
+    use surrealdb::sql::idiom;
+    use crate::query::parsing::order::OrderDirection;
+    use crate::query::statement::StatementBuilder;
+    use crate::testing::db;
+    use super::*;
+
+    #[test]
+    fn to_field() {
+        let field = ExtraField::from(Agg::math_mean("score").alias("mean")).0;
+
+        assert_eq!(field.to_string(), "math::mean(score) AS mean");
+        assert_eq!(ExtraField::from(Agg::count()).0.to_string(), "count()");
+    }
+
+    #[tokio::test]
+    async fn group_by() {
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct Stats {
+            category: String,
+            count: i64,
+            total: f64,
+            tags: Vec<String>,
+        }
+
+        let db = db().await;
+
+        db.query("
+            CREATE product SET category = 'a', price = 1.5, tags = ['x'];
+            CREATE product SET category = 'a', price = 2.5, tags = ['x', 'y'];
+            CREATE product SET category = 'b', price = 4.0, tags = ['z'];
+        ").await.unwrap().check().unwrap();
+
+        let mut res = db.select_builder().what("product")
+            .field(Value::Idiom(idiom("category").unwrap()))
+            .field(Agg::count())
+            .field(Agg::sum("price").alias("total"))
+            .field(Agg::array_group("tags").alias("tags"))
+            .group("category")
+            .order(("category", OrderDirection::ASC))
+            .to_query().await.unwrap();
+
+        let stats: Vec<Stats> = res.take(0).unwrap();
+
+        assert_eq!(stats, vec![
+            Stats { category: "a".to_string(), count: 2, total: 4.0, tags: vec!["x".to_string(), "y".to_string()] },
+            Stats { category: "b".to_string(), count: 1, total: 4.0, tags: vec!["z".to_string()] },
+        ]);
+    }
+}
@@ -1,7 +1,9 @@
 use surrealdb::sql::statements::SelectStatement;
-use surrealdb::sql::{Expression, Operator, Subquery, Value};
+use surrealdb::sql::{Array, Expression, Operator, Subquery, Value};
 use crate::query::parsing::cond::ExtraCond;
+use crate::query::parsing::idiom::ExtraIdiom;
 use crate::query::parsing::str_to_value;
+use crate::query::parsing::typed::TypedValue;
 
 #[derive(Debug, Clone, Default)]
 pub enum Condition {
@@ -47,6 +49,43 @@ impl Condition {
         }
     }
 
+    /// `field = NONE`, the field is missing
+    pub fn is_none(field: impl Into<ExtraIdiom>) -> Self {
+        Self::ValOpVal(Value::Idiom(field.into().0), Operator::Equal, Value::None)
+    }
+
+    /// `field != NONE`, the field is set, `NULL` included
+    pub fn is_some(field: impl Into<ExtraIdiom>) -> Self {
+        Self::ValOpVal(Value::Idiom(field.into().0), Operator::NotEqual, Value::None)
+    }
+
+    /// `field = NULL`
+    pub fn is_null(field: impl Into<ExtraIdiom>) -> Self {
+        Self::ValOpVal(Value::Idiom(field.into().0), Operator::Equal, Value::Null)
+    }
+
+    /// `field != NULL`, a missing field is not `NULL` and matches
+    pub fn is_not_null(field: impl Into<ExtraIdiom>) -> Self {
+        Self::ValOpVal(Value::Idiom(field.into().0), Operator::NotEqual, Value::Null)
+    }
+
+    /// `(field >= from AND field <= to)`, both ends are included
+    pub fn between(field: impl Into<ExtraIdiom>, from: impl Into<Value>, to: impl Into<Value>) -> Self {
+        let field = Value::Idiom(field.into().0);
+
+        let from = Value::Expression(Box::new(Expression::Binary { l: field.clone(), o: Operator::MoreThanOrEqual, r: from.into() }));
+        let to = Value::Expression(Box::new(Expression::Binary { l: field, o: Operator::LessThanOrEqual, r: to.into() }));
+
+        Self::SubCond(ExtraCond::from(Expression::Binary { l: from, o: Operator::And, r: to }))
+    }
+
+    /// `field INSIDE [values]`
+    pub fn in_list<V: Into<Value>>(field: impl Into<ExtraIdiom>, values: impl IntoIterator<Item = V>) -> Self {
+        let values: Vec<Value> = values.into_iter().map(Into::into).collect();
+
+        Self::ValOpVal(Value::Idiom(field.into().0), Operator::Inside, Value::Array(Array::from(values)))
+    }
+
     pub fn is_value(&self) -> bool {
         match self {
             Condition::Value(..) => true,
@@ -135,6 +174,51 @@ create_from_condition_string_select!(String);
 create_from_condition_value_string!(&str);
 create_from_condition_value_string!(String);
 
+macro_rules! create_from_condition_typed {
+    ($r:ty) => {
+        impl From<(&str, Operator, $r)> for Condition {
+            fn from(value: (&str, Operator, $r)) -> Self {
+                Self::ValOpVal(str_to_value(value.0), value.1, TypedValue::from(value.2).0)
+            }
+        }
+
+        impl From<(String, Operator, $r)> for Condition {
+            fn from(value: (String, Operator, $r)) -> Self {
+                Self::ValOpVal(str_to_value(value.0), value.1, TypedValue::from(value.2).0)
+            }
+        }
+
+        impl From<(Value, Operator, $r)> for Condition {
+            fn from(value: (Value, Operator, $r)) -> Self {
+                Self::ValOpVal(value.0, value.1, TypedValue::from(value.2).0)
+            }
+        }
+    };
+}
+
+create_from_condition_typed!(TypedValue);
+create_from_condition_typed!(chrono::DateTime<chrono::Utc>);
+create_from_condition_typed!(chrono::NaiveDateTime);
+create_from_condition_typed!(chrono::NaiveDate);
+#[cfg(feature = "uuid")]
+create_from_condition_typed!(::uuid::Uuid);
+#[cfg(feature = "rust_decimal")]
+create_from_condition_typed!(::rust_decimal::Decimal);
+#[cfg(feature = "geo")]
+create_from_condition_typed!(::geo_types::Point<f64>);
+#[cfg(feature = "geo")]
+create_from_condition_typed!(::geo_types::LineString<f64>);
+#[cfg(feature = "geo")]
+create_from_condition_typed!(::geo_types::Polygon<f64>);
+#[cfg(feature = "geo")]
+create_from_condition_typed!(::geo_types::MultiPoint<f64>);
+#[cfg(feature = "geo")]
+create_from_condition_typed!(::geo_types::MultiLineString<f64>);
+#[cfg(feature = "geo")]
+create_from_condition_typed!(::geo_types::MultiPolygon<f64>);
+#[cfg(feature = "geo")]
+create_from_condition_typed!(::geo_types::Rect<f64>);
+
 impl From<&str> for Condition {
     fn from(value: &str) -> Self {
         let val = str_to_value(value);
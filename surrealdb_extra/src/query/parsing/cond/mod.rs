@@ -2,6 +2,7 @@
 
 mod condition;
 
+use std::borrow::Cow;
 use std::collections::VecDeque;
 use surrealdb::sql::{Cond, Value, Expression};
 use crate::query::parsing::str_to_value;
@@ -47,12 +48,13 @@ impl From<&str> for ExtraCond {
 
 impl From<String> for ExtraCond {
     fn from(value: String) -> Self {
-        let val = str_to_value(value);
-
-        let mut cond = Cond::default();
-        cond.0 = val;
+        value.as_str().into()
+    }
+}
 
-        Self(cond)
+impl From<Cow<'_, str>> for ExtraCond {
+    fn from(value: Cow<'_, str>) -> Self {
+        value.as_ref().into()
     }
 }
 
@@ -117,14 +119,16 @@ impl From<Condition> for ExtraCond {
 #[cfg(test)]
 mod test {
     use serde::{Deserialize, Serialize};
-    use surrealdb::opt::RecordId;
-    use surrealdb::{engine::any::connect, sql::Part};
+    use surrealdb::sql::Thing as RecordId;
+    use surrealdb::sql::Part;
     use surrealdb::sql::{Field, Operator};
-    use crate::{cond_vec, op};
+    use crate::{cond, cond_vec, op};
     use crate::query::statement::StatementBuilder;
+    use crate::query::parsing::order::OrderDirection;
 
     use crate::table::Table;
 
+    use crate::testing::db;
     use super::*;
 
     #[derive(Debug, Table, Serialize, Deserialize, PartialEq, Clone)]
@@ -135,11 +139,42 @@ mod test {
         n: i64
     }
 
+    #[test]
+    fn condition_helpers() {
+        let surql = |cond: Condition| ExtraCond::from(cond).0.to_string();
+
+        assert_eq!(surql(Condition::is_none("name")), "WHERE name = NONE");
+        assert_eq!(surql(Condition::is_some("name")), "WHERE name != NONE");
+        assert_eq!(surql(Condition::is_null("name")), "WHERE name = NULL");
+        assert_eq!(surql(Condition::is_not_null("name")), "WHERE name != NULL");
+        assert_eq!(surql(Condition::between("n", 1, 5)), "WHERE (n >= 1 AND n <= 5)");
+        assert_eq!(surql(Condition::in_list("name", ["a", "b"])), "WHERE name INSIDE ['a', 'b']");
+
+        let cond = ExtraCond::from(vec![Condition::is_some("name"), Condition::from(Operator::And), Condition::between("n", 1, 5)]);
+        assert_eq!(cond.0.to_string(), "WHERE name != NONE AND (n >= 1 AND n <= 5)");
+    }
+
     #[tokio::test]
-    async fn query_and_1cond_builder() {
-        let db = connect("mem://").await.unwrap();
+    async fn condition_helpers_select() {
+        let db = db().await;
 
-        db.use_ns("test").use_db("test").await.unwrap();
+        for n in [1, 5, 10] {
+            Test { id: None, name: n.to_string(), n }.create(&db).await.unwrap();
+        }
+
+        let between: Vec<i64> = db.select_builder().what("test").value("n").condition(Condition::between("n", 2, 10)).order(("n", OrderDirection::ASC)).execute_values().await.unwrap();
+        assert_eq!(between, [5, 10]);
+
+        let listed: Vec<i64> = db.select_builder().what("test").value("n").condition(Condition::in_list("name", ["1", "10"])).order(("n", OrderDirection::ASC)).execute_values().await.unwrap();
+        assert_eq!(listed, [1, 10]);
+
+        let missing: Vec<i64> = db.select_builder().what("test").value("n").condition(Condition::is_none("missing")).execute_values().await.unwrap();
+        assert_eq!(missing.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn query_and_1cond_builder() {
+        let db = db().await;
 
         let t1 = Test {
             id: None,
@@ -173,9 +208,7 @@ mod test {
 
     #[tokio::test]
     async fn select_with_macro() {
-        let db = connect("mem://").await.unwrap();
-
-        db.use_ns("test").use_db("test").await.unwrap();
+        let db = db().await;
 
         let t1 = Test {
             id: None,
@@ -253,4 +286,46 @@ mod test {
 
         assert_eq!(cond1, cond2);
     }
+
+    #[test]
+    fn cond_macro() {
+        let cond = cond!(age > $age && (name = $name || !archived));
+
+        assert_eq!(cond.0.to_string(), "WHERE age > $age AND (name = $name OR !archived)");
+    }
+
+    #[test]
+    fn cond_macro_precedence() {
+        let cond = cond!(a || b && c.d[0] IN [1, "x"] && e NOT IN [] || f + 2 * 3 >= -g);
+
+        let Value::Expression(or) = cond.0.0 else {
+            panic!()
+        };
+
+        let Expression::Binary { l, o: Operator::Or, r } = *or else {
+            panic!()
+        };
+
+        assert_eq!(l.to_string(), "a OR b AND c.d[0] INSIDE [1, 'x'] AND e NOTINSIDE []");
+        assert_eq!(r.to_string(), "f + 2 * 3 >= -g");
+    }
+
+    #[tokio::test]
+    async fn cond_macro_select() {
+        let db = db().await;
+
+        db.query("CREATE test SET name = 'a', n = 8; CREATE test SET name = 'a', n = 21; CREATE test SET name = 'b', n = 55")
+            .await.unwrap().check().unwrap();
+
+        let min = 10;
+
+        let select = db.select_builder().what(Test::TABLE_NAME).field(Field::All)
+            .condition(cond!((name = $name && n > {min}) || n >= 50))
+            .to_query()
+            .bind(("name", "a"));
+
+        let vec: Vec<Test> = select.await.unwrap().take(0).unwrap();
+
+        assert_eq!(vec.len(), 2)
+    }
 }
@@ -1,5 +1,6 @@
 use surrealdb::sql::{Fetch, Idiom, Value};
 use crate::query::parsing::idiom::ExtraIdiom;
+use crate::table::path::TablePath;
 
 #[derive(Debug, Clone)]
 pub struct ExtraFetch(pub Fetch);
@@ -49,3 +50,9 @@ impl From<Value> for ExtraFetch {
         Self(fetch)
     }
 }
+
+impl<P: TablePath> From<P> for ExtraFetch {
+    fn from(value: P) -> Self {
+        Self::from(value.to_idiom())
+    }
+}
@@ -1,6 +1,8 @@
+use std::borrow::Cow;
 use surrealdb::sql::{Field, Value};
 use crate::query::parsing::idiom::ExtraIdiom;
 use crate::query::parsing::str_to_value;
+use crate::table::path::TablePath;
 
 #[derive(Debug, Clone)]
 pub struct ExtraField(pub Field);
@@ -27,15 +29,13 @@ impl From<&str> for ExtraField {
 
 impl From<String> for ExtraField {
     fn from(value: String) -> Self {
+        value.as_str().into()
+    }
+}
 
-        let val = str_to_value(value);
-
-        let field = Field::Single {
-            expr: val,
-            alias: None,
-        };
-
-        Self(field)
+impl From<Cow<'_, str>> for ExtraField {
+    fn from(value: Cow<'_, str>) -> Self {
+        value.as_ref().into()
     }
 }
 
@@ -114,3 +114,37 @@ impl From<(String, &str)> for ExtraField {
     }
 }
 
+
+impl From<(Value, &str)> for ExtraField {
+    fn from(value: (Value, &str)) -> Self {
+
+        let alias_idiom = ExtraIdiom::from(value.1);
+
+        let field = Field::Single {
+            expr: value.0,
+            alias: Some(alias_idiom.0),
+        };
+
+        Self(field)
+    }
+}
+
+impl From<(Value, String)> for ExtraField {
+    fn from(value: (Value, String)) -> Self {
+
+        let alias_idiom = ExtraIdiom::from(value.1);
+
+        let field = Field::Single {
+            expr: value.0,
+            alias: Some(alias_idiom.0),
+        };
+
+        Self(field)
+    }
+}
+
+impl<P: TablePath> From<P> for ExtraField {
+    fn from(value: P) -> Self {
+        Self::from(Value::Idiom(value.to_idiom()))
+    }
+}
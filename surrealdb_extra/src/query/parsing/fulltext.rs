@@ -0,0 +1,148 @@
+//! Full-text search conditions and fields
+//!
+//! `matches` is the `@@` operator, the reference numbers the match so `score`, `highlight` and `offsets` can refer to it.
+//! The searched field needs a `SEARCH` index, the reference only works when the query uses that index.
+//!
+//! # Example
+//!
+//! ```rust
+//! use serde::Deserialize;
+//! use surrealdb::engine::any::connect;
+//! use surrealdb::sql::Field;
+//! use surrealdb_extra::query::parsing::fulltext;
+//! use surrealdb_extra::query::parsing::order::OrderDirection;
+//! use surrealdb_extra::query::statement::StatementBuilder;
+//!
+//! #[derive(Deserialize)]
+//! struct Post {
+//!     title: String,
+//!     score: f64,
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let db = connect("mem://").await.unwrap();
+//!     db.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     db.query("
+//!         DEFINE ANALYZER simple TOKENIZERS blank, class FILTERS lowercase;
+//!         DEFINE INDEX post_title ON post FIELDS title SEARCH ANALYZER simple BM25 HIGHLIGHTS;
+//!         CREATE post SET title = 'Hello Rust';
+//!     ").await.unwrap();
+//!
+//!     // This becomes `SELECT *, search::score(1) AS score FROM post WHERE title @1@ 'rust' ORDER BY score DESC`
+//!     let posts: Vec<Post> = db.select_builder().what("post")
+//!         .field(Field::All)
+//!         .field((fulltext::score(1), "score"))
+//!         .search("title", "rust", 1)
+//!         .order(("score", OrderDirection::DESC))
+//!         .to_query().await.unwrap().take(0).unwrap();
+//!
+//!     assert_eq!(posts[0].title, "Hello Rust");
+//! }
+//! ```
+
+use surrealdb::sql::{Function, Operator, Value};
+use crate::query::parsing::cond::Condition;
+use crate::query::parsing::idiom::ExtraIdiom;
+
+/// `field @reference@ query`, without a reference it is `field @@ query`
+pub fn matches(field: impl Into<ExtraIdiom>, query: impl Into<Value>, reference: impl Into<Option<u8>>) -> Condition {
+    Condition::ValOpVal(Value::Idiom(field.into().0), Operator::Matches(reference.into()), query.into())
+}
+
+/// `search::score(reference)`
+pub fn score(reference: u8) -> Value {
+    function("search::score", vec![Value::from(reference as i64)])
+}
+
+/// `search::highlight(prefix, suffix, reference)`, the index needs `HIGHLIGHTS`
+pub fn highlight(prefix: impl Into<String>, suffix: impl Into<String>, reference: u8) -> Value {
+    function("search::highlight", vec![Value::from(prefix.into()), Value::from(suffix.into()), Value::from(reference as i64)])
+}
+
+/// `search::offsets(reference)`, the index needs `HIGHLIGHTS`
+pub fn offsets(reference: u8) -> Value {
+    function("search::offsets", vec![Value::from(reference as i64)])
+}
+
+fn function(name: &str, args: Vec<Value>) -> Value {
+    Value::Function(Box::new(Function::Normal(name.to_string(), args)))
+}
+
+#[cfg(test)]
+mod test {
+    use serde::Deserialize;
+    use surrealdb::engine::any::Any;
+    use surrealdb::sql::Field;
+    use surrealdb::Surreal;
+    use crate::cond_vec;
+    use crate::query::parsing::cond::ExtraCond;
+    use crate::query::parsing::order::OrderDirection;
+    use crate::query::statement::StatementBuilder;
+    use super::*;
+
+    #[derive(Debug, Deserialize)]
+    struct Hit {
+        title: String,
+        score: f64,
+        highlighted: String,
+    }
+
+    async fn db() -> Surreal<Any> {
+        let db = crate::testing::db().await;
+
+        db.query("
+            DEFINE ANALYZER simple TOKENIZERS blank, class FILTERS lowercase;
+            DEFINE INDEX post_title ON post FIELDS title SEARCH ANALYZER simple BM25 HIGHLIGHTS;
+            DEFINE INDEX post_body ON post FIELDS body SEARCH ANALYZER simple BM25 HIGHLIGHTS;
+            CREATE post SET title = 'Rust', body = 'Rust is fast and rust is safe';
+            CREATE post SET title = 'Go', body = 'Go is simple, rust is not';
+            CREATE post SET title = 'Zig', body = 'Zig is small';
+        ").await.unwrap().check().unwrap();
+
+        db
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(ExtraCond::from(matches("title", "rust", 1)).0.to_string(), "WHERE title @1@ 'rust'");
+        assert_eq!(ExtraCond::from(matches("title", "rust", None)).0.to_string(), "WHERE title @@ 'rust'");
+        assert_eq!(score(1).to_string(), "search::score(1)");
+        assert_eq!(highlight("<b>", "</b>", 0).to_string(), "search::highlight('<b>', '</b>', 0)");
+    }
+
+    #[tokio::test]
+    async fn search_builder() {
+        let db = db().await;
+
+        let hits: Vec<Hit> = db.select_builder().what("post")
+            .field(Field::All)
+            .field((score(0), "score"))
+            .field((highlight("<b>", "</b>", 0), "highlighted"))
+            .search("body", "rust", 0)
+            .order(("score", OrderDirection::DESC))
+            .to_query().await.unwrap().take(0).unwrap();
+
+        assert_eq!(hits.len(), 2);
+        assert!(hits[0].score >= hits[1].score);
+        assert!(hits.iter().all(|h| h.highlighted.to_lowercase().contains("<b>rust</b>")));
+    }
+
+    #[tokio::test]
+    async fn multiple_matches() {
+        let db = db().await;
+
+        let hits: Vec<Hit> = db.select_builder().what("post")
+            .field(Field::All)
+            .field((Value::from(0), "score"))
+            .field((highlight("", "", 1), "highlighted"))
+            .condition(cond_vec![matches("title", "zig", 1), Operator::Or, matches("body", "simple", 2)])
+            .to_query().await.unwrap().take(0).unwrap();
+
+        let mut titles: Vec<String> = hits.into_iter().map(|h| h.title).collect();
+        titles.sort();
+
+        assert_eq!(titles, vec!["Go", "Zig"]);
+    }
+}
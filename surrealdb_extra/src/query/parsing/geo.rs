@@ -0,0 +1,203 @@
+//! Geometry values and conditions for location based filters
+//!
+//! The `geo-types` types convert into `TypedValue`, so they can be used as the right-hand side of conditions and set clauses.
+//! GeoJSON objects are converted with `from_geojson`.
+//!
+//! # Example
+//!
+//! ```rust
+//! use geo_types::{point, polygon};
+//! use serde::Deserialize;
+//! use surrealdb::engine::any::connect;
+//! use surrealdb::sql::Field;
+//! use surrealdb_extra::cond_vec;
+//! use surrealdb_extra::query::parsing::geo;
+//! use surrealdb_extra::query::statement::StatementBuilder;
+//! use surrealdb::sql::Operator;
+//!
+//! #[derive(Deserialize)]
+//! struct Place {
+//!     name: String,
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let db = connect("mem://").await.unwrap();
+//!     db.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     db.query("CREATE place SET name = 'London', location = (-0.118092, 51.509865)").await.unwrap();
+//!
+//!     let area = polygon![(x: -1.0, y: 51.0), (x: 1.0, y: 51.0), (x: 1.0, y: 52.0), (x: -1.0, y: 52.0)];
+//!     let here = point!(x: -0.1, y: 51.5);
+//!
+//!     // This becomes `WHERE location INSIDE {polygon} AND geo::distance(location, (-0.1, 51.5)) <= 5000`
+//!     let places: Vec<Place> = db.select_builder().what("place").field(Field::All)
+//!         .condition(cond_vec![geo::inside("location", area), Operator::And, geo::within_distance("location", here, 5000.0)])
+//!         .to_query().await.unwrap().take(0).unwrap();
+//!
+//!     assert_eq!(places.len(), 1);
+//! }
+//! ```
+
+use geo_types::{Line, LineString, MultiLineString, MultiPoint, MultiPolygon, Point, Polygon, Rect};
+use surrealdb::sql::{Function, Geometry, Operator, Value};
+use crate::query::parsing::cond::Condition;
+use crate::query::parsing::idiom::ExtraIdiom;
+use crate::query::parsing::typed::TypedValue;
+
+macro_rules! create_from_geometry {
+    ($x:ty) => {
+        impl From<$x> for TypedValue {
+            fn from(value: $x) -> Self {
+                Self(Value::Geometry(Geometry::from(value)))
+            }
+        }
+    };
+}
+
+create_from_geometry!(Geometry);
+create_from_geometry!(Point<f64>);
+create_from_geometry!(LineString<f64>);
+create_from_geometry!(Polygon<f64>);
+create_from_geometry!(MultiPoint<f64>);
+create_from_geometry!(MultiLineString<f64>);
+create_from_geometry!(MultiPolygon<f64>);
+
+impl From<Line<f64>> for TypedValue {
+    fn from(value: Line<f64>) -> Self {
+        Self::from(LineString::from(value))
+    }
+}
+
+impl From<Rect<f64>> for TypedValue {
+    fn from(value: Rect<f64>) -> Self {
+        Self::from(value.to_polygon())
+    }
+}
+
+/// Geometry of a GeoJSON geometry object, `None` for other objects and for features
+pub fn from_geojson(json: &serde_json::Value) -> Option<Geometry> {
+    match surrealdb::sql::value(&json.to_string()).ok()? {
+        Value::Geometry(geometry) => Some(geometry),
+        _ => None,
+    }
+}
+
+/// `geo::distance(field, to)` in meters
+pub fn distance(field: impl Into<ExtraIdiom>, to: impl Into<TypedValue>) -> Value {
+    Value::Function(Box::new(Function::Normal(
+        "geo::distance".to_string(),
+        vec![Value::Idiom(field.into().0), to.into().0],
+    )))
+}
+
+/// `geo::distance(field, to) <= meters`
+pub fn within_distance(field: impl Into<ExtraIdiom>, to: impl Into<TypedValue>, meters: f64) -> Condition {
+    Condition::ValOpVal(distance(field, to), Operator::LessThanOrEqual, Value::from(meters))
+}
+
+/// `field INSIDE geometry`
+pub fn inside(field: impl Into<ExtraIdiom>, geometry: impl Into<TypedValue>) -> Condition {
+    Condition::ValOpVal(Value::Idiom(field.into().0), Operator::Inside, geometry.into().0)
+}
+
+/// `field OUTSIDE geometry`
+pub fn outside(field: impl Into<ExtraIdiom>, geometry: impl Into<TypedValue>) -> Condition {
+    Condition::ValOpVal(Value::Idiom(field.into().0), Operator::Outside, geometry.into().0)
+}
+
+/// `field INTERSECTS geometry`
+pub fn intersects(field: impl Into<ExtraIdiom>, geometry: impl Into<TypedValue>) -> Condition {
+    Condition::ValOpVal(Value::Idiom(field.into().0), Operator::Intersects, geometry.into().0)
+}
+
+#[cfg(test)]
+mod test {
+    use geo_types::{line_string, point, polygon};
+    use serde::Deserialize;
+    use serde_json::json;
+    use surrealdb::engine::any::Any;
+    use surrealdb::sql::Field;
+    use surrealdb::Surreal;
+    use crate::query::parsing::cond::ExtraCond;
+    use crate::query::parsing::order::OrderDirection;
+    use crate::query::statement::StatementBuilder;
+    use super::*;
+
+    #[derive(Debug, Deserialize)]
+    struct Place {
+        name: String,
+    }
+
+    async fn db() -> Surreal<Any> {
+        let db = crate::testing::db().await;
+
+        db.query("
+            CREATE place SET name = 'london', location = (-0.118092, 51.509865);
+            CREATE place SET name = 'paris', location = (2.352222, 48.856613);
+            CREATE place SET name = 'berlin', location = (13.404954, 52.520008);
+        ").await.unwrap().check().unwrap();
+
+        db
+    }
+
+    async fn names(db: &Surreal<Any>, cond: impl Into<ExtraCond>) -> Vec<String> {
+        let places: Vec<Place> = db.select_builder().what("place").field(Field::All)
+            .condition(cond)
+            .to_query().await.unwrap().take(0).unwrap();
+
+        let mut names: Vec<String> = places.into_iter().map(|p| p.name).collect();
+        names.sort();
+
+        names
+    }
+
+    #[tokio::test]
+    async fn inside_outside() {
+        let db = db().await;
+
+        let western_europe = polygon![(x: -5.0, y: 45.0), (x: 5.0, y: 45.0), (x: 5.0, y: 55.0), (x: -5.0, y: 55.0)];
+
+        assert_eq!(names(&db, inside("location", western_europe.clone())).await, vec!["london", "paris"]);
+        assert_eq!(names(&db, outside("location", western_europe)).await, vec!["berlin"]);
+    }
+
+    #[tokio::test]
+    async fn intersects_line() {
+        let db = db().await;
+
+        let line = line_string![(x: 2.352222, y: 48.856613), (x: 13.404954, y: 52.520008)];
+
+        assert_eq!(names(&db, intersects("location", line)).await, vec!["berlin", "paris"]);
+    }
+
+    #[tokio::test]
+    async fn within_distance_of_point() {
+        let db = db().await;
+
+        let london = point!(x: -0.1, y: 51.5);
+
+        assert_eq!(names(&db, within_distance("location", london, 400_000.0)).await, vec!["london", "paris"]);
+    }
+
+    #[tokio::test]
+    async fn distance_order() {
+        let db = db().await;
+
+        let places: Vec<Place> = db.select_builder().what("place")
+            .field(Field::All)
+            .field((distance("location", point!(x: 13.4, y: 52.5)), "distance"))
+            .order(("distance", OrderDirection::ASC))
+            .to_query().await.unwrap().take(0).unwrap();
+
+        assert_eq!(places[0].name, "berlin");
+    }
+
+    #[test]
+    fn geojson() {
+        let point = from_geojson(&json!({ "type": "Point", "coordinates": [1.0, 2.0] }));
+        assert_eq!(point, Some(Geometry::from(point!(x: 1.0, y: 2.0))));
+
+        assert_eq!(from_geojson(&json!({ "type": "Feature" })), None);
+    }
+}
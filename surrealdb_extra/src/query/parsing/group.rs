@@ -1,5 +1,6 @@
 use surrealdb::sql::{Group, Idiom};
 use crate::query::parsing::idiom::ExtraIdiom;
+use crate::table::path::TablePath;
 
 #[derive(Debug, Clone)]
 pub struct ExtraGroup(pub Group);
@@ -40,3 +41,9 @@ impl From<Idiom> for ExtraGroup {
         Self(group)
     }
 }
+
+impl<P: TablePath> From<P> for ExtraGroup {
+    fn from(value: P) -> Self {
+        Self::from(value.to_idiom())
+    }
+}
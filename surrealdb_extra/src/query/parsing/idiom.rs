@@ -1,4 +1,5 @@
 use surrealdb::sql::{Idiom, Part};
+use crate::table::path::TablePath;
 
 #[derive(Debug, Clone)]
 pub struct ExtraIdiom(pub Idiom);
@@ -38,3 +39,9 @@ impl From<String> for ExtraIdiom {
         Self(idiom)
     }
 }
+
+impl<P: TablePath> From<P> for ExtraIdiom {
+    fn from(value: P) -> Self {
+        Self(value.to_idiom())
+    }
+}
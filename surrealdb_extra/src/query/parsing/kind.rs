@@ -0,0 +1,49 @@
+use surrealdb::sql::{Kind, Value};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+#[error("Invalid type: {0}")]
+pub struct KindError(pub String);
+
+/// Type of a function argument or return value, `&str` is parsed like the type of a cast (`int`, `option<string>`, `record<user>`, ...)
+#[derive(Debug, Clone)]
+pub struct ExtraKind(pub Kind);
+
+impl From<Kind> for ExtraKind {
+    fn from(value: Kind) -> Self {
+        Self(value)
+    }
+}
+
+impl TryFrom<&str> for ExtraKind {
+    type Error = KindError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match surrealdb::sql::value(&format!("<{value}> NONE")) {
+            Ok(Value::Cast(cast)) => Ok(Self(cast.0)),
+            _ => Err(KindError(value.to_string())),
+        }
+    }
+}
+
+impl TryFrom<String> for ExtraKind {
+    type Error = KindError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::try_from(value.as_str())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use surrealdb::sql::Table;
+    use super::*;
+
+    #[test]
+    fn from_str() {
+        assert_eq!(ExtraKind::try_from("int").unwrap().0, Kind::Int);
+        assert_eq!(ExtraKind::try_from("option<string>").unwrap().0, Kind::Option(Box::new(Kind::String)));
+        assert_eq!(ExtraKind::try_from("record<user>").unwrap().0, Kind::Record(vec![Table::from("user")]));
+        assert!(ExtraKind::try_from("not a kind").is_err());
+    }
+}
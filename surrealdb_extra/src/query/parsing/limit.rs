@@ -1,4 +1,5 @@
 use surrealdb::sql::{Limit, Number, Value};
+use crate::query::parsing::str_to_value;
 
 #[derive(Debug, Clone)]
 pub struct ExtraLimit(pub Limit);
@@ -28,3 +29,16 @@ impl From<Value> for ExtraLimit {
         Self(limit)
     }
 }
+
+/// Parsed like a value, e.g. `$limit` for a parameter
+impl From<&str> for ExtraLimit {
+    fn from(value: &str) -> Self {
+        Self::from(str_to_value(value))
+    }
+}
+
+impl From<String> for ExtraLimit {
+    fn from(value: String) -> Self {
+        Self::from(str_to_value(value))
+    }
+}
@@ -0,0 +1,120 @@
+//! Lists that are bound as a parameter instead of written into the statement
+//!
+//! `BoundList` keeps the values of an `INSIDE` condition out of the statement text, the condition is `field INSIDE $name`
+//! and `bind` adds the array to the query. Large lists don't make the statement longer and the statement stays the same for every list.
+//!
+//! # Example
+//!
+//! ```rust
+//! use surrealdb::engine::any::connect;
+//! use surrealdb::sql::{Id, Thing};
+//! use surrealdb_extra::query::parsing::list::BoundList;
+//! use surrealdb_extra::query::statement::StatementBuilder;
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let db = connect("mem://").await.unwrap();
+//!     db.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     db.query("CREATE user:1 SET name = 'one'; CREATE user:2 SET name = 'two';").await.unwrap();
+//!
+//!     let ids = BoundList::new("ids", (1..=10_000i64).map(|n| Thing::from(("user", Id::from(n)))));
+//!
+//!     // This becomes `SELECT VALUE name FROM user WHERE id INSIDE $ids` with `$ids` bound to the 10000 ids
+//!     let select = db.select_builder().what("user").value("name").condition(ids.cond("id"));
+//!     assert_eq!(select.to_surql(), "SELECT VALUE name FROM user WHERE id INSIDE $ids");
+//!
+//!     let names: Vec<String> = ids.bind(select.to_query()).await.unwrap().take(0).unwrap();
+//!     assert_eq!(names.len(), 2);
+//! }
+//! ```
+
+use surrealdb::Connection;
+use surrealdb::method::Query;
+use surrealdb::sql::{Array, Operator, Param, Value};
+use crate::query::parsing::cond::Condition;
+use crate::query::parsing::idiom::ExtraIdiom;
+
+/// An array of values with the name of the parameter it is bound to
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoundList {
+    pub name: String,
+    pub values: Vec<Value>,
+}
+
+impl BoundList {
+    /// `name` is the parameter without `$`
+    pub fn new<V: Into<Value>>(name: impl Into<String>, values: impl IntoIterator<Item = V>) -> Self {
+        Self {
+            name: name.into(),
+            values: values.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// `$name`
+    pub fn param(&self) -> Value {
+        Value::Param(Param::from(self.name.as_str()))
+    }
+
+    /// `field INSIDE $name`
+    pub fn cond(&self, field: impl Into<ExtraIdiom>) -> Condition {
+        Condition::ValOpVal(Value::Idiom(field.into().0), Operator::Inside, self.param())
+    }
+
+    /// `field NOTINSIDE $name`
+    pub fn not_cond(&self, field: impl Into<ExtraIdiom>) -> Condition {
+        Condition::ValOpVal(Value::Idiom(field.into().0), Operator::NotInside, self.param())
+    }
+
+    /// The `(name, array)` pair for `Query::bind`
+    pub fn binding(&self) -> (String, Value) {
+        (self.name.clone(), Value::Array(Array::from(self.values.clone())))
+    }
+
+    /// Binds the array to the parameter of the query
+    pub fn bind<'r, C: Connection>(self, query: Query<'r, C>) -> Query<'r, C> {
+        query.bind((self.name, Value::Array(Array::from(self.values))))
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::query::parsing::cond::ExtraCond;
+    use crate::query::statement::StatementBuilder;
+    use crate::testing::db;
+    use super::*;
+
+    #[test]
+    fn bound_list_cond() {
+        let list = BoundList::new("names", ["a", "b"]);
+
+        assert_eq!(ExtraCond::from(list.cond("name")).0.to_string(), "WHERE name INSIDE $names");
+        assert_eq!(ExtraCond::from(list.not_cond("name")).0.to_string(), "WHERE name NOTINSIDE $names");
+        assert_eq!(list.binding(), ("names".to_string(), Value::from(vec!["a", "b"])));
+    }
+
+    #[tokio::test]
+    async fn bound_list_select() {
+        let db = db().await;
+
+        db.query("CREATE test:1 SET n = 1; CREATE test:2 SET n = 2; CREATE test:3 SET n = 3;").await.unwrap();
+
+        let list = BoundList::new("n", (0..10_000).map(|n| n * 2));
+
+        let select = db.select_builder().what("test").value("n").condition(list.cond("n"));
+        let surql = select.to_surql();
+
+        let n: Vec<i64> = list.bind(select.to_query()).await.unwrap().take(0).unwrap();
+
+        assert_eq!(surql, "SELECT VALUE n FROM test WHERE n INSIDE $n");
+        assert_eq!(n, [2]);
+    }
+}
@@ -1,4 +1,5 @@
-use surrealdb::sql::{value, Value};
+use surrealdb::sql::{parse, Idiom, Statement, Value};
+use crate::query::parsing::idiom::ExtraIdiom;
 
 pub mod what;
 pub mod idiom;
@@ -21,9 +22,62 @@ pub mod unset_expression;
 pub mod value;
 pub mod table;
 pub mod operator;
+pub mod subquery;
+pub mod statement;
+pub mod aggregate;
+pub mod typed;
+pub mod fulltext;
+pub mod vector;
+pub mod kind;
+pub mod list;
+#[cfg_attr(docsrs, doc(cfg(feature = "geo")))]
+#[cfg(feature = "geo")]
+pub mod geo;
+
+/// Parses the SurrealQL value like the condition of a `WHERE`, so identifiers are fields and not tables.
+/// `NULL` when it can't be parsed. Field paths like `address.city` are built without the parser.
+pub fn str_to_value(val: impl AsRef<str>) -> Value {
+    let val = val.as_ref();
+
+    if let Some(idiom) = plain_idiom(val) {
+        return Value::Idiom(idiom);
+    }
+
+    field_value(val).unwrap_or(Value::Null)
+}
+
+/// `sql::value` reads identifiers as tables, e.g. `age > 30` as the table `age`, the condition of a select reads them as fields
+fn field_value(val: &str) -> Option<Value> {
+    let mut statements = parse(&format!("SELECT * FROM t WHERE {val}")).ok()?.0.0;
+
+    let (Some(Statement::Select(mut select)), true) = (statements.pop(), statements.is_empty()) else {
+        return None;
+    };
+
+    let cond = select.cond.take()?;
+
+    // Clauses after the value, e.g. `a LIMIT 1`, are not part of it
+    if select.to_string() != "SELECT * FROM t" {
+        return None;
+    }
+
+    Some(cond.0)
+}
+
+/// At least two identifiers separated by `.`, a single identifier can be a keyword like `true` and is left to the parser
+fn plain_idiom(val: &str) -> Option<Idiom> {
+    let is_ident = |part: &str| {
+        let mut chars = part.chars();
+
+        chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+            && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+    };
+
+    if !val.contains('.') || !val.split('.').all(is_ident) {
+        return None;
+    }
 
-pub fn str_to_value(val: impl Into<String>) -> Value {
-    value(&val.into()).unwrap_or_else(|_| Value::Null)
+    Some(ExtraIdiom::from(val).0)
 }
 
 #[cfg(test)]
@@ -89,6 +143,34 @@ mod test {
         assert!(matches!(val, Value::Function(..)))
     }
 
+    #[test]
+    fn plain_idiom_as_parsed() {
+        for i in ["address.city", "_private.id", "user_2.name"] {
+            assert_eq!(str_to_value(i), field_value(i).unwrap(), "{i}");
+        }
+
+        for i in ["id", "true", "NONE", "1d", "$p", "a.*", "a->b", "a..b"] {
+            assert!(plain_idiom(i).is_none(), "{i}");
+        }
+
+        assert_eq!(str_to_value("true"), Value::Bool(true));
+    }
+
+    #[test]
+    fn identifiers_are_fields() {
+        assert!(matches!(str_to_value("name"), Value::Idiom(..)));
+
+        let Value::Expression(expression) = str_to_value("hidden != true") else {
+            panic!("not an expression");
+        };
+
+        assert_eq!(expression.to_string(), "hidden != true");
+        assert!(matches!(*expression, surrealdb::sql::Expression::Binary { l: Value::Idiom(..), .. }));
+
+        assert_eq!(str_to_value("a LIMIT 1"), Value::Null);
+        assert_eq!(str_to_value("a; DELETE b"), Value::Null);
+    }
+
     #[test]
     fn is_not_func() {
         let i = "id";
@@ -1,4 +1,5 @@
 use surrealdb::sql::Idiom;
+use crate::table::path::TablePath;
 
 #[derive(Debug, Clone)]
 pub struct ExtraOmit(pub Idiom);
@@ -8,3 +9,9 @@ impl From<Idiom> for ExtraOmit {
         Self(value)
     }
 }
+
+impl<P: TablePath> From<P> for ExtraOmit {
+    fn from(value: P) -> Self {
+        Self(value.to_idiom())
+    }
+}
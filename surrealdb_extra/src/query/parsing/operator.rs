@@ -6,6 +6,8 @@
 ///                                 Reason being macro rules pattern matching (Could not find a better solution suggestion are welcome))
 /// 
 /// For contain key words use double quotes with the symbol
+/// or the SurrealQL keyword in upper or lower case, e.g. op!(CONTAINSNOT) == op!("∌") == Operator::NotContain.
+/// op!(IS) and op!(IS NOT) are Operator::Equal and Operator::NotEqual like in SurrealQL.
 #[macro_export]
 macro_rules! op {
     (--) => { ::surrealdb::sql::Operator::Neg };
@@ -14,12 +16,14 @@ macro_rules! op {
     (||) => { ::surrealdb::sql::Operator::Or };
     (&&) => { ::surrealdb::sql::Operator::And };
     (?) => { ::surrealdb::sql::Operator::Tco };
+    (?:) => { ::surrealdb::sql::Operator::Tco };
     (??) => { ::surrealdb::sql::Operator::Nco };
 
     (+) => { ::surrealdb::sql::Operator::Add };
     (-) => { ::surrealdb::sql::Operator::Sub };
     (*) => { ::surrealdb::sql::Operator::Mul };
     (/) => { ::surrealdb::sql::Operator::Div };
+    (%) => { ::surrealdb::sql::Operator::Rem };
     (**) => { ::surrealdb::sql::Operator::Pow };
     (+=) => { ::surrealdb::sql::Operator::Inc };
     (-=) => { ::surrealdb::sql::Operator::Dec };
@@ -30,6 +34,10 @@ macro_rules! op {
     (!=) => { ::surrealdb::sql::Operator::NotEqual };
     (*=) => { ::surrealdb::sql::Operator::AllEqual };
     (?=) => { ::surrealdb::sql::Operator::AnyEqual };
+    (IS NOT) => { ::surrealdb::sql::Operator::NotEqual };
+    (is not) => { ::surrealdb::sql::Operator::NotEqual };
+    (IS) => { ::surrealdb::sql::Operator::Equal };
+    (is) => { ::surrealdb::sql::Operator::Equal };
 
     (~) => { ::surrealdb::sql::Operator::Like };
     (!~) => { ::surrealdb::sql::Operator::NotLike };
@@ -54,6 +62,35 @@ macro_rules! op {
     ("⊂") => { ::surrealdb::sql::Operator::AnyInside };
     ("⊄") => { ::surrealdb::sql::Operator::NoneInside };
 
+    (CONTAINS) => { ::surrealdb::sql::Operator::Contain };
+    (contains) => { ::surrealdb::sql::Operator::Contain };
+    (CONTAINSNOT) => { ::surrealdb::sql::Operator::NotContain };
+    (containsnot) => { ::surrealdb::sql::Operator::NotContain };
+    (CONTAINSALL) => { ::surrealdb::sql::Operator::ContainAll };
+    (containsall) => { ::surrealdb::sql::Operator::ContainAll };
+    (CONTAINSANY) => { ::surrealdb::sql::Operator::ContainAny };
+    (containsany) => { ::surrealdb::sql::Operator::ContainAny };
+    (CONTAINSNONE) => { ::surrealdb::sql::Operator::ContainNone };
+    (containsnone) => { ::surrealdb::sql::Operator::ContainNone };
+    (INSIDE) => { ::surrealdb::sql::Operator::Inside };
+    (inside) => { ::surrealdb::sql::Operator::Inside };
+    (IN) => { ::surrealdb::sql::Operator::Inside };
+    (in) => { ::surrealdb::sql::Operator::Inside };
+    (NOT IN) => { ::surrealdb::sql::Operator::NotInside };
+    (not in) => { ::surrealdb::sql::Operator::NotInside };
+    (NOTINSIDE) => { ::surrealdb::sql::Operator::NotInside };
+    (notinside) => { ::surrealdb::sql::Operator::NotInside };
+    (ALLINSIDE) => { ::surrealdb::sql::Operator::AllInside };
+    (allinside) => { ::surrealdb::sql::Operator::AllInside };
+    (ANYINSIDE) => { ::surrealdb::sql::Operator::AnyInside };
+    (anyinside) => { ::surrealdb::sql::Operator::AnyInside };
+    (NONEINSIDE) => { ::surrealdb::sql::Operator::NoneInside };
+    (noneinside) => { ::surrealdb::sql::Operator::NoneInside };
+    (OUTSIDE) => { ::surrealdb::sql::Operator::Outside };
+    (outside) => { ::surrealdb::sql::Operator::Outside };
+    (INTERSECTS) => { ::surrealdb::sql::Operator::Intersects };
+    (intersects) => { ::surrealdb::sql::Operator::Intersects };
+
     (<$x:tt>) => { ::surrealdb::sql::Operator::Knn($x, None) };
     (<$x:tt>, $t:expr) => { ::surrealdb::sql::Operator::Knn($x, Some($t)) };
 
@@ -66,8 +103,9 @@ macro_rules! op {
 
 #[cfg(test)]
 mod test {
-    use surrealdb::sql::Operator;
+    use surrealdb::sql::{Expression, Operator, Value};
     use surrealdb::sql::index::Distance;
+    use crate::query::parsing::cond::{Condition, ExtraCond};
 
     #[test]
     fn op_and() {
@@ -83,6 +121,79 @@ mod test {
         assert_eq!(op!("∋"), Operator::Contain);
     }
 
+    #[test]
+    fn op_keywords() {
+        assert_eq!(op!(CONTAINS), Operator::Contain);
+        assert_eq!(op!(containsnot), Operator::NotContain);
+        assert_eq!(op!(CONTAINSALL), Operator::ContainAll);
+        assert_eq!(op!(CONTAINSANY), Operator::ContainAny);
+        assert_eq!(op!(CONTAINSNONE), Operator::ContainNone);
+        assert_eq!(op!(INSIDE), Operator::Inside);
+        assert_eq!(op!(in), Operator::Inside);
+        assert_eq!(op!(NOTINSIDE), Operator::NotInside);
+        assert_eq!(op!(NOT IN), Operator::NotInside);
+        assert_eq!(op!(ALLINSIDE), Operator::AllInside);
+        assert_eq!(op!(anyinside), Operator::AnyInside);
+        assert_eq!(op!(NONEINSIDE), Operator::NoneInside);
+        assert_eq!(op!(OUTSIDE), Operator::Outside);
+        assert_eq!(op!(INTERSECTS), Operator::Intersects);
+        assert_eq!(op!(IS), Operator::Equal);
+        assert_eq!(op!(IS NOT), Operator::NotEqual);
+        assert_eq!(op!(is not), Operator::NotEqual);
+        assert_eq!(op!(%), Operator::Rem);
+        assert_eq!(op!(?:), Operator::Tco);
+    }
+
+    #[test]
+    fn op_renders() {
+        let ops = [
+            (op!(CONTAINS), "CONTAINS"),
+            (op!(CONTAINSNOT), "CONTAINSNOT"),
+            (op!(CONTAINSALL), "CONTAINSALL"),
+            (op!(CONTAINSANY), "CONTAINSANY"),
+            (op!(CONTAINSNONE), "CONTAINSNONE"),
+            (op!(INSIDE), "INSIDE"),
+            (op!(NOTINSIDE), "NOTINSIDE"),
+            (op!(ALLINSIDE), "ALLINSIDE"),
+            (op!(ANYINSIDE), "ANYINSIDE"),
+            (op!(NONEINSIDE), "NONEINSIDE"),
+            (op!(OUTSIDE), "OUTSIDE"),
+            (op!(INTERSECTS), "INTERSECTS"),
+            (op!(IS), "="),
+            (op!(IS NOT), "!="),
+            (op!(~), "~"),
+            (op!(!~), "!~"),
+            (op!(*~), "*~"),
+            (op!(?~), "?~"),
+        ];
+
+        for (op, surql) in ops {
+            let cond = ExtraCond::from(vec![Condition::from(("tags", op, "$tags"))]);
+
+            assert_eq!(cond.0.to_string(), format!("WHERE tags {surql} $tags"));
+        }
+    }
+
+    #[test]
+    fn op_parsed_conditions() {
+        let conds = [
+            ("tags CONTAINSNOT 'a'", Operator::NotContain),
+            ("tags CONTAINSALL ['a', 'b']", Operator::ContainAll),
+            ("'a' NOTINSIDE tags", Operator::NotInside),
+            ("['a'] ANYINSIDE tags", Operator::AnyInside),
+            ("name IS NOT NONE", Operator::NotEqual),
+            ("name ~ 'jo'", Operator::Like),
+        ];
+
+        for (surql, op) in conds {
+            let Value::Expression(expression) = ExtraCond::from(surql).0.0 else {
+                panic!("{surql} is not an expression");
+            };
+
+            assert!(matches!(&*expression, Expression::Binary { o, .. } if *o == op), "{surql}");
+        }
+    }
+
     #[test]
     fn op_matches() {
         assert_eq!(op!(@@), Operator::Matches(None));
@@ -1,5 +1,6 @@
 use surrealdb::sql::{Idiom, Order};
 use crate::query::parsing::idiom::ExtraIdiom;
+use crate::table::path::TablePath;
 
 pub enum OrderDirection {
     ASC,
@@ -18,6 +19,31 @@ impl OrderDirection {
 #[derive(Debug, Clone)]
 pub struct ExtraOrder(pub Order);
 
+impl ExtraOrder {
+    /// `ORDER BY RAND()`
+    pub fn random() -> Self {
+        let mut order = Order::default();
+        order.random = true;
+        order.direction = true;
+
+        Self(order)
+    }
+
+    /// `COLLATE`, orders text by the unicode collation e.g. `é` next to `e`
+    pub fn collate(mut self) -> Self {
+        self.0.collate = true;
+
+        self
+    }
+
+    /// `NUMERIC`, orders text with numbers naturally e.g. `item2` before `item10`
+    pub fn numeric(mut self) -> Self {
+        self.0.numeric = true;
+
+        self
+    }
+}
+
 impl From<Order> for ExtraOrder {
     fn from(value: Order) -> Self {
         Self(value)
@@ -70,3 +96,9 @@ impl From<(Idiom, OrderDirection)> for ExtraOrder {
         Self(order)
     }
 }
+
+impl<P: TablePath> From<(P, OrderDirection)> for ExtraOrder {
+    fn from(value: (P, OrderDirection)) -> Self {
+        Self::from((value.0.to_idiom(), value.1))
+    }
+}
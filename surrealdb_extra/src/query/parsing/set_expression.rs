@@ -1,5 +1,6 @@
 use surrealdb::sql::{Data, Idiom, Operator, Value};
 use crate::query::parsing::idiom::ExtraIdiom;
+use crate::query::parsing::typed::TypedValue;
 
 #[derive(Debug, Clone)]
 pub struct SetExpression(pub Data);
@@ -14,3 +15,14 @@ impl<I: Into<ExtraIdiom>, V: Into<Value>> From<Vec<(I, Operator, V)>> for SetExp
         Self(Data::SetExpression(value))
     }
 }
+
+impl<I: Into<ExtraIdiom>> From<Vec<(I, Operator, TypedValue)>> for SetExpression {
+    fn from(value: Vec<(I, Operator, TypedValue)>) -> Self {
+        let value: Vec<(Idiom, Operator, Value)> = value.into_iter()
+            .map(|e| {
+                (e.0.into().0, e.1, e.2.0)
+            }).collect();
+
+        Self(Data::SetExpression(value))
+    }
+}
@@ -1,5 +1,6 @@
 use surrealdb::sql::{Idiom, Split};
 use crate::query::parsing::idiom::ExtraIdiom;
+use crate::table::path::TablePath;
 
 #[derive(Debug, Clone)]
 pub struct ExtraSplit(pub Split);
@@ -40,3 +41,9 @@ impl From<Idiom> for ExtraSplit {
         Self(split)
     }
 }
+
+impl<P: TablePath> From<P> for ExtraSplit {
+    fn from(value: P) -> Self {
+        Self::from(value.to_idiom())
+    }
+}
@@ -1,4 +1,5 @@
 use surrealdb::sql::{Number, Start, Value};
+use crate::query::parsing::str_to_value;
 
 #[derive(Debug, Clone)]
 pub struct ExtraStart(pub Start);
@@ -27,3 +28,16 @@ impl From<Value> for ExtraStart {
         Self(start)
     }
 }
+
+/// Parsed like a value, e.g. `$start` for a parameter
+impl From<&str> for ExtraStart {
+    fn from(value: &str) -> Self {
+        Self::from(str_to_value(value))
+    }
+}
+
+impl From<String> for ExtraStart {
+    fn from(value: String) -> Self {
+        Self::from(str_to_value(value))
+    }
+}
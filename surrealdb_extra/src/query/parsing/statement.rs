@@ -0,0 +1,154 @@
+use surrealdb::Connection;
+use surrealdb::sql::{Entry, Statement, Subquery, Value};
+use surrealdb::sql::statements::{BreakStatement, ContinueStatement, CreateStatement, DeleteStatement, ForeachStatement, IfelseStatement, RelateStatement, SelectStatement, SetStatement, UpdateStatement};
+use crate::query::create::CreateBuilder;
+use crate::query::foreach::ForEachBuilder;
+use crate::query::ifelse::IfElseBuilder;
+use crate::query::parsing::subquery::ExtraSubquery;
+use crate::query::relate::RelateBuilder;
+use crate::query::select::SelectBuilder;
+use crate::query::states::{CheckedCond, FilledCond, FilledFields, FilledRelation, FilledWhat};
+use crate::query::update::UpdateBuilder;
+
+/// A statement that can be used on its own or inside a block e.g. `FOR $item IN $items { ... }`
+#[derive(Debug, Clone)]
+pub struct ExtraStatement(pub Statement);
+
+impl From<ExtraSubquery> for ExtraStatement {
+    fn from(value: ExtraSubquery) -> Self {
+        let Value::Subquery(subquery) = value.0 else {
+            return Self(Statement::Value(value.0));
+        };
+
+        Self(match *subquery {
+            Subquery::Value(v) => Statement::Value(v),
+            Subquery::Ifelse(s) => Statement::Ifelse(s),
+            Subquery::Output(s) => Statement::Output(s),
+            Subquery::Select(s) => Statement::Select(s),
+            Subquery::Create(s) => Statement::Create(s),
+            Subquery::Update(s) => Statement::Update(s),
+            Subquery::Delete(s) => Statement::Delete(s),
+            Subquery::Relate(s) => Statement::Relate(s),
+            Subquery::Insert(s) => Statement::Insert(s),
+            Subquery::Define(s) => Statement::Define(s),
+            Subquery::Remove(s) => Statement::Remove(s),
+            Subquery::Rebuild(s) => Statement::Rebuild(s),
+            Subquery::Upsert(s) => Statement::Upsert(s),
+            Subquery::Alter(s) => Statement::Alter(s),
+            subquery => Statement::Value(Value::Subquery(Box::new(subquery))),
+        })
+    }
+}
+
+impl From<ExtraStatement> for Entry {
+    fn from(value: ExtraStatement) -> Self {
+        match value.0 {
+            Statement::Set(s) => Entry::Set(s),
+            Statement::Ifelse(s) => Entry::Ifelse(s),
+            Statement::Select(s) => Entry::Select(s),
+            Statement::Create(s) => Entry::Create(s),
+            Statement::Update(s) => Entry::Update(s),
+            Statement::Delete(s) => Entry::Delete(s),
+            Statement::Relate(s) => Entry::Relate(s),
+            Statement::Insert(s) => Entry::Insert(s),
+            Statement::Output(s) => Entry::Output(s),
+            Statement::Define(s) => Entry::Define(s),
+            Statement::Remove(s) => Entry::Remove(s),
+            Statement::Throw(s) => Entry::Throw(s),
+            Statement::Break(s) => Entry::Break(s),
+            Statement::Continue(s) => Entry::Continue(s),
+            Statement::Foreach(s) => Entry::Foreach(s),
+            Statement::Rebuild(s) => Entry::Rebuild(s),
+            Statement::Upsert(s) => Entry::Upsert(s),
+            Statement::Alter(s) => Entry::Alter(s),
+            Statement::Value(v) => Entry::Value(v),
+            // Statements like BEGIN or USE can't be inside a block and none of the From impls creates them
+            _ => Entry::Value(Value::None),
+        }
+    }
+}
+
+macro_rules! create_from_subquery {
+    ($x:ty) => {
+        impl From<$x> for ExtraStatement {
+            fn from(value: $x) -> Self {
+                ExtraSubquery::from(value).into()
+            }
+        }
+    };
+}
+
+create_from_subquery!(Value);
+create_from_subquery!(&str);
+create_from_subquery!(String);
+create_from_subquery!(SelectStatement);
+create_from_subquery!(CreateStatement);
+create_from_subquery!(UpdateStatement);
+create_from_subquery!(DeleteStatement);
+create_from_subquery!(RelateStatement);
+create_from_subquery!(IfelseStatement);
+
+macro_rules! create_from_statement {
+    ($x:ty, $s:ident) => {
+        impl From<$x> for ExtraStatement {
+            fn from(value: $x) -> Self {
+                Self(Statement::$s(value))
+            }
+        }
+    };
+}
+
+create_from_statement!(SetStatement, Set);
+create_from_statement!(ForeachStatement, Foreach);
+create_from_statement!(BreakStatement, Break);
+create_from_statement!(ContinueStatement, Continue);
+
+impl<'r, Client: Connection, C> From<SelectBuilder<'r, Client, FilledWhat, FilledFields, C>> for ExtraStatement {
+    fn from(value: SelectBuilder<'r, Client, FilledWhat, FilledFields, C>) -> Self {
+        crate::query::config::apply(value.statement).into()
+    }
+}
+
+impl<'r, Client: Connection, D> From<CreateBuilder<'r, Client, FilledWhat, D>> for ExtraStatement {
+    fn from(value: CreateBuilder<'r, Client, FilledWhat, D>) -> Self {
+        crate::query::config::apply(value.statement).into()
+    }
+}
+
+/// Like `to_query`, the update needs a condition or `all_records`:
+/// ```compile_fail
+/// use surrealdb::engine::any::connect;
+/// use surrealdb::sql::Operator;
+/// use surrealdb_extra::query::parsing::statement::ExtraStatement;
+/// use surrealdb_extra::query::update::UpdateBuilder;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let db = connect("mem://").await.unwrap();
+///
+///     ExtraStatement::from(UpdateBuilder::new(&db).what("test").set(vec![("test", Operator::Equal, "test")]));
+/// }
+/// ```
+impl<'r, Client: Connection, D, C: CheckedCond> From<UpdateBuilder<'r, Client, FilledWhat, D, C>> for ExtraStatement {
+    fn from(value: UpdateBuilder<'r, Client, FilledWhat, D, C>) -> Self {
+        crate::query::config::apply(value.statement).into()
+    }
+}
+
+impl<'r, Client: Connection, D> From<RelateBuilder<'r, Client, FilledRelation, D>> for ExtraStatement {
+    fn from(value: RelateBuilder<'r, Client, FilledRelation, D>) -> Self {
+        crate::query::config::apply(value.statement).into()
+    }
+}
+
+impl<'r, Client: Connection> From<IfElseBuilder<'r, Client, FilledCond>> for ExtraStatement {
+    fn from(value: IfElseBuilder<'r, Client, FilledCond>) -> Self {
+        value.statement.into()
+    }
+}
+
+impl<'r, Client: Connection> From<ForEachBuilder<'r, Client, FilledWhat>> for ExtraStatement {
+    fn from(value: ForEachBuilder<'r, Client, FilledWhat>) -> Self {
+        value.statement.into()
+    }
+}
@@ -0,0 +1,92 @@
+use surrealdb::{Connection, sql::{Subquery, Value}};
+use surrealdb::sql::statements::{CreateStatement, DeleteStatement, IfelseStatement, RelateStatement, SelectStatement, UpdateStatement};
+use crate::query::create::CreateBuilder;
+use crate::query::ifelse::IfElseBuilder;
+use crate::query::parsing::str_to_value;
+use crate::query::relate::RelateBuilder;
+use crate::query::select::SelectBuilder;
+use crate::query::states::{CheckedCond, FilledCond, FilledFields, FilledRelation, FilledWhat};
+use crate::query::update::UpdateBuilder;
+
+/// A value or a statement that is used as a value e.g. `(SELECT * FROM test)`
+#[derive(Debug, Clone)]
+pub struct ExtraSubquery(pub Value);
+
+impl From<Value> for ExtraSubquery {
+    fn from(value: Value) -> Self {
+        Self(value)
+    }
+}
+
+impl From<&str> for ExtraSubquery {
+    fn from(value: &str) -> Self {
+        Self(str_to_value(value))
+    }
+}
+
+impl From<String> for ExtraSubquery {
+    fn from(value: String) -> Self {
+        Self(str_to_value(value))
+    }
+}
+
+macro_rules! create_from_statement {
+    ($x:ty, $s:ident) => {
+        impl From<$x> for ExtraSubquery {
+            fn from(value: $x) -> Self {
+                Self(Value::Subquery(Box::new(Subquery::$s(value))))
+            }
+        }
+    };
+}
+
+create_from_statement!(SelectStatement, Select);
+create_from_statement!(CreateStatement, Create);
+create_from_statement!(UpdateStatement, Update);
+create_from_statement!(DeleteStatement, Delete);
+create_from_statement!(RelateStatement, Relate);
+create_from_statement!(IfelseStatement, Ifelse);
+
+impl<'r, Client: Connection, C> From<SelectBuilder<'r, Client, FilledWhat, FilledFields, C>> for ExtraSubquery {
+    fn from(value: SelectBuilder<'r, Client, FilledWhat, FilledFields, C>) -> Self {
+        crate::query::config::apply(value.statement).into()
+    }
+}
+
+impl<'r, Client: Connection, D> From<CreateBuilder<'r, Client, FilledWhat, D>> for ExtraSubquery {
+    fn from(value: CreateBuilder<'r, Client, FilledWhat, D>) -> Self {
+        crate::query::config::apply(value.statement).into()
+    }
+}
+
+/// Like `to_query`, the update needs a condition or `all_records`:
+/// ```compile_fail
+/// use surrealdb::engine::any::connect;
+/// use surrealdb::sql::Operator;
+/// use surrealdb_extra::query::parsing::subquery::ExtraSubquery;
+/// use surrealdb_extra::query::update::UpdateBuilder;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let db = connect("mem://").await.unwrap();
+///
+///     ExtraSubquery::from(UpdateBuilder::new(&db).what("test").set(vec![("test", Operator::Equal, "test")]));
+/// }
+/// ```
+impl<'r, Client: Connection, D, C: CheckedCond> From<UpdateBuilder<'r, Client, FilledWhat, D, C>> for ExtraSubquery {
+    fn from(value: UpdateBuilder<'r, Client, FilledWhat, D, C>) -> Self {
+        crate::query::config::apply(value.statement).into()
+    }
+}
+
+impl<'r, Client: Connection, D> From<RelateBuilder<'r, Client, FilledRelation, D>> for ExtraSubquery {
+    fn from(value: RelateBuilder<'r, Client, FilledRelation, D>) -> Self {
+        crate::query::config::apply(value.statement).into()
+    }
+}
+
+impl<'r, Client: Connection> From<IfElseBuilder<'r, Client, FilledCond>> for ExtraSubquery {
+    fn from(value: IfElseBuilder<'r, Client, FilledCond>) -> Self {
+        value.statement.into()
+    }
+}
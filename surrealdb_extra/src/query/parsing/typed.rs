@@ -0,0 +1,299 @@
+//! Conversions for chrono, uuid and rust_decimal values
+//!
+//! Conditions take these types directly as the right-hand side, e.g. `(field, Operator::MoreThan, Utc::now())`.
+//! For set clauses wrap the values in `TypedValue`, which also allows different types in one set clause.
+//!
+//! The types serialize as strings, so fields of `content` would be stored as strings.
+//! The `serde(with = "...")` modules store them as SurrealDB datetimes, uuids and decimals.
+//!
+//! `Uuid` needs the `uuid` feature and `Decimal` the `rust_decimal` feature.
+//!
+//! # Example
+//!
+//! ```rust
+//! use chrono::{NaiveDate, Utc};
+//! use serde::{Serialize, Deserialize};
+//! use surrealdb::engine::any::connect;
+//! use surrealdb::sql::{idiom, Field, Operator, Value};
+//! use surrealdb_extra::cond_vec;
+//! use surrealdb_extra::query::parsing::typed::{self, TypedValue};
+//! use surrealdb_extra::query::statement::StatementBuilder;
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Event {
+//!     #[serde(with = "typed::naive_date")]
+//!     day: NaiveDate,
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let db = connect("mem://").await.unwrap();
+//!     db.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     let day = NaiveDate::from_ymd_opt(2024, 5, 1).unwrap();
+//!
+//!     db.create_builder().what("event").content(Event { day }).to_query().await.unwrap();
+//!
+//!     db.update_builder().what("event")
+//!         .set(vec![("updated", Operator::Equal, TypedValue::from(Utc::now())), ("day", Operator::Equal, TypedValue::from(day))])
+//!         .all_records()
+//!         .to_query().await.unwrap();
+//!
+//!     let events: Vec<Event> = db.select_builder().what("event").field(Field::All)
+//!         .condition(cond_vec![(Value::Idiom(idiom("day").unwrap()), Operator::LessThan, Utc::now())])
+//!         .to_query().await.unwrap().take(0).unwrap();
+//!
+//!     assert_eq!(events.len(), 1);
+//! }
+//! ```
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use surrealdb::sql::{Datetime, Value};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypedValue(pub Value);
+
+impl From<Value> for TypedValue {
+    fn from(value: Value) -> Self {
+        Self(value)
+    }
+}
+
+impl From<DateTime<Utc>> for TypedValue {
+    fn from(value: DateTime<Utc>) -> Self {
+        Self(Value::Datetime(Datetime::from(value)))
+    }
+}
+
+/// Stored as UTC
+impl From<NaiveDateTime> for TypedValue {
+    fn from(value: NaiveDateTime) -> Self {
+        Self::from(value.and_utc())
+    }
+}
+
+/// Stored as midnight UTC
+impl From<NaiveDate> for TypedValue {
+    fn from(value: NaiveDate) -> Self {
+        Self::from(value.and_time(NaiveTime::MIN))
+    }
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "uuid")))]
+#[cfg(feature = "uuid")]
+impl From<::uuid::Uuid> for TypedValue {
+    fn from(value: ::uuid::Uuid) -> Self {
+        Self(Value::Uuid(surrealdb::sql::Uuid::from(value)))
+    }
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "rust_decimal")))]
+#[cfg(feature = "rust_decimal")]
+impl From<::rust_decimal::Decimal> for TypedValue {
+    fn from(value: ::rust_decimal::Decimal) -> Self {
+        Self(Value::Number(surrealdb::sql::Number::Decimal(value)))
+    }
+}
+
+macro_rules! create_from_typed_value {
+    ($x:ty) => {
+        impl From<$x> for TypedValue {
+            fn from(value: $x) -> Self {
+                Self(Value::from(value))
+            }
+        }
+    };
+}
+
+create_from_typed_value!(bool);
+create_from_typed_value!(i64);
+create_from_typed_value!(f64);
+create_from_typed_value!(&str);
+create_from_typed_value!(String);
+
+/// `serde(with)` for `DateTime<Utc>` stored as datetime
+pub mod datetime {
+    use chrono::{DateTime, Utc};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use surrealdb::sql::Datetime;
+
+    pub fn serialize<S: Serializer>(value: &DateTime<Utc>, serializer: S) -> Result<S::Ok, S::Error> {
+        Datetime::from(*value).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<DateTime<Utc>, D::Error> {
+        Ok(Datetime::deserialize(deserializer)?.0)
+    }
+}
+
+/// `serde(with)` for `NaiveDateTime` stored as UTC datetime
+pub mod naive_datetime {
+    use chrono::NaiveDateTime;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use surrealdb::sql::Datetime;
+
+    pub fn serialize<S: Serializer>(value: &NaiveDateTime, serializer: S) -> Result<S::Ok, S::Error> {
+        Datetime::from(value.and_utc()).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<NaiveDateTime, D::Error> {
+        Ok(Datetime::deserialize(deserializer)?.0.naive_utc())
+    }
+}
+
+/// `serde(with)` for `NaiveDate` stored as datetime at midnight UTC
+pub mod naive_date {
+    use chrono::{NaiveDate, NaiveTime};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use surrealdb::sql::Datetime;
+
+    pub fn serialize<S: Serializer>(value: &NaiveDate, serializer: S) -> Result<S::Ok, S::Error> {
+        Datetime::from(value.and_time(NaiveTime::MIN).and_utc()).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<NaiveDate, D::Error> {
+        Ok(Datetime::deserialize(deserializer)?.0.date_naive())
+    }
+}
+
+/// `serde(with)` for `Uuid` stored as uuid
+#[cfg_attr(docsrs, doc(cfg(feature = "uuid")))]
+#[cfg(feature = "uuid")]
+pub mod uuid {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &::uuid::Uuid, serializer: S) -> Result<S::Ok, S::Error> {
+        surrealdb::sql::Uuid::from(*value).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<::uuid::Uuid, D::Error> {
+        Ok(surrealdb::sql::Uuid::deserialize(deserializer)?.0)
+    }
+}
+
+/// `serde(with)` for `Decimal` stored as decimal
+#[cfg_attr(docsrs, doc(cfg(feature = "rust_decimal")))]
+#[cfg(feature = "rust_decimal")]
+pub mod decimal {
+    use std::str::FromStr;
+    use rust_decimal::Decimal;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use serde::de::Error;
+    use surrealdb::sql::Number;
+
+    /// Decimals of a response are strings
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Repr {
+        Number(Number),
+        String(String),
+        Int(i64),
+        Float(f64),
+    }
+
+    pub fn serialize<S: Serializer>(value: &Decimal, serializer: S) -> Result<S::Ok, S::Error> {
+        Number::Decimal(*value).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Decimal, D::Error> {
+        match Repr::deserialize(deserializer)? {
+            Repr::Number(n) => Ok(n.as_decimal()),
+            Repr::String(s) => Decimal::from_str(&s).map_err(D::Error::custom),
+            Repr::Int(i) => Ok(Decimal::from(i)),
+            Repr::Float(f) => Decimal::try_from(f).map_err(D::Error::custom),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "uuid", feature = "rust_decimal"))]
+mod test {
+    use serde::{Deserialize, Serialize};
+    use surrealdb::engine::any::Any;
+    use surrealdb::sql::{idiom, Field, Operator};
+    use surrealdb::Surreal;
+    use crate::cond_vec;
+    use crate::query::statement::StatementBuilder;
+    use super::*;
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct Typed {
+        #[serde(with = "datetime")]
+        at: DateTime<Utc>,
+        #[serde(with = "naive_date")]
+        day: NaiveDate,
+        #[serde(with = "uuid")]
+        key: ::uuid::Uuid,
+        #[serde(with = "decimal")]
+        price: ::rust_decimal::Decimal,
+    }
+
+    fn typed() -> Typed {
+        Typed {
+            at: DateTime::from_timestamp(1_700_000_000, 0).unwrap(),
+            day: NaiveDate::from_ymd_opt(2024, 5, 1).unwrap(),
+            key: ::uuid::Uuid::nil(),
+            price: ::rust_decimal::Decimal::new(1999, 2),
+        }
+    }
+
+    async fn db() -> Surreal<Any> {
+        let db = crate::testing::db().await;
+
+        db.create_builder().what("typed").content(typed()).to_query().await.unwrap().check().unwrap();
+
+        db
+    }
+
+    fn field(name: &str) -> Value {
+        Value::Idiom(idiom(name).unwrap())
+    }
+
+    #[tokio::test]
+    async fn stored_as_sql_types() {
+        let db = db().await;
+
+        let mut res = db.query("SELECT type::is::datetime(at) AND type::is::datetime(day) AND type::is::uuid(key) AND type::is::decimal(price) AS typed FROM ONLY typed LIMIT 1").await.unwrap();
+
+        let is_typed: Option<bool> = res.take("typed").unwrap();
+        assert_eq!(is_typed, Some(true));
+
+        let rows: Vec<Typed> = db.select_builder().what("typed").field(Field::All).to_query().await.unwrap().take(0).unwrap();
+        assert_eq!(rows, vec![typed()]);
+    }
+
+    #[tokio::test]
+    async fn conditions() {
+        let db = db().await;
+
+        let select = db.select_builder().what("typed").field(Field::All).condition(cond_vec![
+            (field("at"), Operator::Equal, typed().at),
+            Operator::And,
+            (field("day"), Operator::Equal, typed().day),
+            Operator::And,
+            (field("key"), Operator::Equal, typed().key),
+            Operator::And,
+            (field("price"), Operator::MoreThan, ::rust_decimal::Decimal::new(10, 0)),
+        ]);
+
+        let rows: Vec<Typed> = select.to_query().await.unwrap().take(0).unwrap();
+
+        assert_eq!(rows.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn set_clause() {
+        let db = db().await;
+
+        let day = NaiveDate::from_ymd_opt(2025, 1, 2).unwrap();
+
+        db.update_builder().what("typed")
+            .set(vec![("day", Operator::Equal, TypedValue::from(day)), ("price", Operator::Equal, TypedValue::from(::rust_decimal::Decimal::ONE))])
+            .all_records()
+            .to_query().await.unwrap().check().unwrap();
+
+        let rows: Vec<Typed> = db.select_builder().what("typed").field(Field::All).to_query().await.unwrap().take(0).unwrap();
+
+        assert_eq!(rows[0].day, day);
+        assert_eq!(rows[0].price, ::rust_decimal::Decimal::ONE);
+    }
+}
@@ -1,5 +1,6 @@
 use surrealdb::{sql::Value};
-use surrealdb::sql::Thing;
+use surrealdb::sql::{Table, Thing};
+use crate::query::parsing::str_to_value;
 
 #[derive(Debug, Clone)]
 pub struct ExtraValue(pub Value);
@@ -9,3 +10,23 @@ impl From<Thing> for ExtraValue {
         ExtraValue(Value::Thing(value))
     }
 }
+
+/// A table name, or a parameter when it starts with `$`
+impl From<&str> for ExtraValue {
+    fn from(value: &str) -> Self {
+        value.to_string().into()
+    }
+}
+
+impl From<String> for ExtraValue {
+    fn from(value: String) -> Self {
+        if value.starts_with('$') {
+            return ExtraValue(str_to_value(value));
+        }
+
+        let mut table = Table::default();
+        table.0 = value;
+
+        ExtraValue(Value::Table(table))
+    }
+}
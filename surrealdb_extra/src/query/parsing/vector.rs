@@ -0,0 +1,210 @@
+//! Vector similarity conditions and fields
+//!
+//! `knn` is the `<|K|>` operator for the `K` nearest neighbours of an embedding:
+//! - `k` uses the `MTREE` index of the field
+//! - `(k, ef)` uses the `HNSW` index of the field with `ef` as the size of the candidate list
+//! - `(k, Distance)` does a brute force search without an index
+//!
+//! `distance()` is the distance that was computed by the knn operator, `similarity` and `distance_to` compute the
+//! `vector::similarity::*` and `vector::distance::*` functions for every record.
+//!
+//! # Example
+//!
+//! ```rust
+//! use serde::Deserialize;
+//! use surrealdb::engine::any::connect;
+//! use surrealdb::sql::Field;
+//! use surrealdb::sql::index::Distance;
+//! use surrealdb_extra::query::statement::StatementBuilder;
+//!
+//! #[derive(Deserialize)]
+//! struct Doc {
+//!     distance: f64,
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let db = connect("mem://").await.unwrap();
+//!     db.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     db.query("CREATE doc SET embedding = [1.0, 0.0]; CREATE doc SET embedding = [0.0, 1.0];").await.unwrap();
+//!
+//!     // This becomes `SELECT *, vector::distance::knn() AS distance FROM doc WHERE embedding <|1,EUCLIDEAN|> [1.0, 0.5] ORDER BY distance ASC`
+//!     let docs: Vec<Doc> = db.select_builder().what("doc").field(Field::All)
+//!         .knn("embedding", [1.0, 0.5], (1, Distance::Euclidean))
+//!         .to_query().await.unwrap().take(0).unwrap();
+//!
+//!     assert_eq!(docs.len(), 1);
+//! }
+//! ```
+
+use surrealdb::sql::index::Distance;
+use surrealdb::sql::{Function, Number, Operator, Value};
+use crate::query::parsing::cond::Condition;
+use crate::query::parsing::idiom::ExtraIdiom;
+
+#[derive(Debug, Clone)]
+pub struct ExtraKnn(pub Operator);
+
+impl From<u32> for ExtraKnn {
+    fn from(value: u32) -> Self {
+        Self(Operator::Knn(value, None))
+    }
+}
+
+impl From<(u32, Distance)> for ExtraKnn {
+    fn from(value: (u32, Distance)) -> Self {
+        Self(Operator::Knn(value.0, Some(value.1)))
+    }
+}
+
+impl From<(u32, u32)> for ExtraKnn {
+    fn from(value: (u32, u32)) -> Self {
+        Self(Operator::Ann(value.0, value.1))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Similarity {
+    Cosine,
+    Jaccard,
+    Pearson,
+    Spearman,
+}
+
+impl Similarity {
+    fn function(&self) -> &'static str {
+        match self {
+            Self::Cosine => "vector::similarity::cosine",
+            Self::Jaccard => "vector::similarity::jaccard",
+            Self::Pearson => "vector::similarity::pearson",
+            Self::Spearman => "vector::similarity::spearman",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Metric {
+    Chebyshev,
+    Euclidean,
+    Hamming,
+    Manhattan,
+}
+
+impl Metric {
+    fn function(&self) -> &'static str {
+        match self {
+            Self::Chebyshev => "vector::distance::chebyshev",
+            Self::Euclidean => "vector::distance::euclidean",
+            Self::Hamming => "vector::distance::hamming",
+            Self::Manhattan => "vector::distance::manhattan",
+        }
+    }
+}
+
+/// `field <|k|> embedding`
+pub fn knn<N: Into<Number>>(field: impl Into<ExtraIdiom>, embedding: impl IntoIterator<Item = N>, k: impl Into<ExtraKnn>) -> Condition {
+    Condition::ValOpVal(Value::Idiom(field.into().0), k.into().0, embedding_value(embedding))
+}
+
+/// `vector::distance::knn()`, the distance computed by the knn condition
+pub fn distance() -> Value {
+    function("vector::distance::knn", vec![])
+}
+
+/// `vector::similarity::*(field, embedding)`
+pub fn similarity<N: Into<Number>>(similarity: Similarity, field: impl Into<ExtraIdiom>, embedding: impl IntoIterator<Item = N>) -> Value {
+    function(similarity.function(), vec![Value::Idiom(field.into().0), embedding_value(embedding)])
+}
+
+/// `vector::distance::*(field, embedding)`
+pub fn distance_to<N: Into<Number>>(metric: Metric, field: impl Into<ExtraIdiom>, embedding: impl IntoIterator<Item = N>) -> Value {
+    function(metric.function(), vec![Value::Idiom(field.into().0), embedding_value(embedding)])
+}
+
+fn embedding_value<N: Into<Number>>(embedding: impl IntoIterator<Item = N>) -> Value {
+    Value::from(embedding.into_iter().map(Into::into).collect::<Vec<Number>>())
+}
+
+fn function(name: &str, args: Vec<Value>) -> Value {
+    Value::Function(Box::new(Function::Normal(name.to_string(), args)))
+}
+
+#[cfg(test)]
+mod test {
+    use serde::Deserialize;
+    use surrealdb::engine::any::Any;
+    use surrealdb::sql::{Field, Thing};
+    use surrealdb::Surreal;
+    use crate::query::parsing::cond::ExtraCond;
+    use crate::query::parsing::order::OrderDirection;
+    use crate::query::statement::StatementBuilder;
+    use super::*;
+
+    #[derive(Debug, Deserialize)]
+    struct Doc {
+        id: Thing,
+        distance: f64,
+    }
+
+    async fn db() -> Surreal<Any> {
+        let db = crate::testing::db().await;
+
+        db.query("
+            CREATE doc:1 SET embedding = [1.0, 0.0];
+            CREATE doc:2 SET embedding = [0.0, 1.0];
+            CREATE doc:3 SET embedding = [0.9, 0.1];
+        ").await.unwrap().check().unwrap();
+
+        db
+    }
+
+    fn ids(docs: &[Doc]) -> Vec<String> {
+        docs.iter().map(|d| d.id.to_string()).collect()
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(ExtraCond::from(knn("embedding", [1.0, 0.0], 3)).0.to_string(), "WHERE embedding <|3|> [1f, 0f]");
+        assert_eq!(ExtraCond::from(knn("embedding", [1, 0], (3, Distance::Cosine))).0.to_string(), "WHERE embedding <|3,COSINE|> [1, 0]");
+        assert_eq!(ExtraCond::from(knn("embedding", [1, 0], (3, 40))).0.to_string(), "WHERE embedding <|3,40|> [1, 0]");
+        assert_eq!(similarity(Similarity::Cosine, "embedding", [1, 0]).to_string(), "vector::similarity::cosine(embedding, [1, 0])");
+    }
+
+    #[tokio::test]
+    async fn brute_force() {
+        let db = db().await;
+
+        let docs: Vec<Doc> = db.select_builder().what("doc").field(Field::All)
+            .knn("embedding", [1.0, 0.0], (2, Distance::Euclidean))
+            .to_query().await.unwrap().take(0).unwrap();
+
+        assert_eq!(ids(&docs), vec!["doc:1", "doc:3"]);
+        assert_eq!(docs[0].distance, 0.0);
+    }
+
+    #[tokio::test]
+    async fn hnsw_index() {
+        let db = db().await;
+
+        db.query("DEFINE INDEX doc_embedding ON doc FIELDS embedding HNSW DIMENSION 2 DIST EUCLIDEAN").await.unwrap().check().unwrap();
+
+        let docs: Vec<Doc> = db.select_builder().what("doc").field(Field::All)
+            .knn("embedding", [0.0, 1.0], (1, 40))
+            .to_query().await.unwrap().take(0).unwrap();
+
+        assert_eq!(ids(&docs), vec!["doc:2"]);
+    }
+
+    #[tokio::test]
+    async fn similarity_order() {
+        let db = db().await;
+
+        let docs: Vec<Doc> = db.select_builder().what("doc").field(Field::All)
+            .field((similarity(Similarity::Cosine, "embedding", [1.0, 0.0]), "distance"))
+            .order(("distance", OrderDirection::DESC))
+            .to_query().await.unwrap().take(0).unwrap();
+
+        assert_eq!(ids(&docs), vec!["doc:1", "doc:3", "doc:2"]);
+    }
+}
@@ -1,4 +1,5 @@
-use surrealdb::{sql::{Table, Value, Values, Thing as RecordId}};
+use surrealdb::{sql::{Idiom, Param, Table, Value, Values, Thing as RecordId}};
+use crate::query::parsing::str_to_value;
 
 #[derive(Debug, Clone)]
 pub struct ExtraValue(pub Values);
@@ -11,23 +12,24 @@ impl From<Values> for ExtraValue {
 
 impl From<&str> for ExtraValue {
     fn from(value: &str) -> Self {
-        let mut table = Table::default();
-        table.0 = value.to_string();
-
-        let mut values = Values::default();
-        values.0 = vec![Value::Table(table)];
-
-        ExtraValue(values)
+        value.to_string().into()
     }
 }
 
 impl From<String> for ExtraValue {
     fn from(value: String) -> Self {
-        let mut table = Table::default();
-        table.0 = value;
+        let value = match value.starts_with('$') {
+            true => str_to_value(value),
+            false => {
+                let mut table = Table::default();
+                table.0 = value;
+
+                Value::Table(table)
+            }
+        };
 
         let mut values = Values::default();
-        values.0 = vec![Value::Table(table)];
+        values.0 = vec![value];
 
         ExtraValue(values)
     }
@@ -42,3 +44,23 @@ impl From<RecordId> for ExtraValue {
         ExtraValue(values)
     }
 }
+
+impl From<Param> for ExtraValue {
+    fn from(value: Param) -> Self {
+        let mut values = Values::default();
+
+        values.0 = vec![Value::Param(value)];
+
+        ExtraValue(values)
+    }
+}
+
+impl From<Idiom> for ExtraValue {
+    fn from(value: Idiom) -> Self {
+        let mut values = Values::default();
+
+        values.0 = vec![Value::Idiom(value)];
+
+        ExtraValue(values)
+    }
+}
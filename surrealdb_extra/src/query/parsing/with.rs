@@ -3,6 +3,13 @@ use surrealdb::sql::With;
 #[derive(Debug, Clone)]
 pub struct ExtraWith(pub With);
 
+impl ExtraWith {
+    /// `WITH NOINDEX`
+    pub fn no_index() -> Self {
+        Self(With::NoIndex)
+    }
+}
+
 impl From<With> for ExtraWith {
     fn from(value: With) -> Self {
         Self(value)
@@ -17,3 +24,32 @@ impl From<Vec<String>> for ExtraWith {
         Self(with)
     }
 }
+
+impl From<Vec<&str>> for ExtraWith {
+    fn from(value: Vec<&str>) -> Self {
+        Self::from(value.into_iter().map(String::from).collect::<Vec<_>>())
+    }
+}
+
+impl<const N: usize> From<[&str; N]> for ExtraWith {
+    fn from(value: [&str; N]) -> Self {
+        Self::from(Vec::from(value))
+    }
+}
+
+/// A single index, `NOINDEX` becomes `WITH NOINDEX`
+impl From<&str> for ExtraWith {
+    fn from(value: &str) -> Self {
+        if value.eq_ignore_ascii_case("NOINDEX") {
+            return Self::no_index();
+        }
+
+        Self::from(vec![value.to_string()])
+    }
+}
+
+impl From<String> for ExtraWith {
+    fn from(value: String) -> Self {
+        Self::from(value.as_str())
+    }
+}
@@ -0,0 +1,263 @@
+//! Statements that are built once and run many times
+//!
+//! `PreparedStatement` freezes the statement of a builder, running it binds the parameters to the frozen AST.
+//! The values that change between runs have to be parameters, e.g. `condition("name = $name")`, a value written into the builder is frozen with it.
+//!
+//! `prepared(statement)` keeps the statements in a global LRU cache keyed by their SurrealQL, so builders that build the same AST
+//! share one `PreparedStatement`. The cache holds `DEFAULT_CAPACITY` statements, `set_prepared_capacity` changes it.
+//!
+//! # Example
+//!
+//! ```rust
+//! use serde::Deserialize;
+//! use surrealdb::engine::any::connect;
+//! use surrealdb::sql::Field;
+//! use surrealdb_extra::query::prepared::prepared;
+//! use surrealdb_extra::query::statement::StatementBuilder;
+//!
+//! #[derive(Deserialize)]
+//! struct User {
+//!     name: String,
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let db = connect("mem://").await.unwrap();
+//!     db.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     db.query("CREATE user SET name = 'john'").await.unwrap();
+//!
+//!     for name in ["john", "jane"] {
+//!         let by_name = prepared(db.select_builder().what("user").field(Field::All).condition("name = $name"));
+//!
+//!         let users: Vec<User> = by_name.query(&db).bind(("name", name)).await.unwrap().take_vec(0).unwrap();
+//!         assert_eq!(users.len(), usize::from(name == "john"));
+//!     }
+//! }
+//! ```
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+use surrealdb::{Connection, Surreal};
+use surrealdb::sql::{Statement, Statements};
+use crate::query::parsing::statement::ExtraStatement;
+use crate::query::raw::RawQuery;
+
+pub const DEFAULT_CAPACITY: usize = 256;
+
+static CACHE: Mutex<Option<PreparedCache>> = Mutex::new(None);
+
+/// Frozen statements, cloning it shares the statements
+#[derive(Debug, Clone)]
+pub struct PreparedStatement {
+    statements: Arc<Vec<Statement>>,
+}
+
+impl PreparedStatement {
+    /// Freezes a builder or statement
+    pub fn new(statement: impl Into<ExtraStatement>) -> Self {
+        Self::from_statements(vec![statement.into().0])
+    }
+
+    /// Freezes several statements, e.g. of a script or a batch
+    pub fn from_statements(statements: Vec<Statement>) -> Self {
+        Self {
+            statements: Arc::new(statements),
+        }
+    }
+
+    pub fn statements(&self) -> &[Statement] {
+        &self.statements
+    }
+
+    /// The SurrealQL that `query` runs, parameters stay `$name` placeholders for the bindings
+    pub fn to_surql(&self) -> String {
+        let mut statements = Statements::default();
+        statements.0 = self.statements.as_ref().clone();
+
+        statements.to_string()
+    }
+
+    /// Starts a query with the statements, bind the parameters and await it to run it
+    ///
+    /// The sdk takes the statements by value, they are moved into the query when this is the last clone and copied otherwise
+    pub fn query<'r, Client: Connection>(self, db: &'r Surreal<Client>) -> RawQuery<'r, Client> {
+        RawQuery {
+            query: db.query(Arc::unwrap_or_clone(self.statements)),
+        }
+    }
+}
+
+/// LRU cache of prepared statements keyed by their shape, the SurrealQL of the statements
+#[derive(Debug)]
+pub struct PreparedCache {
+    capacity: usize,
+    tick: u64,
+    entries: HashMap<String, (PreparedStatement, u64)>,
+    /// The shapes by the tick of their last use, the first one is the least recently used
+    order: BTreeMap<u64, String>,
+}
+
+impl Default for PreparedCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_CAPACITY)
+    }
+}
+
+impl PreparedCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            tick: 0,
+            entries: HashMap::new(),
+            order: BTreeMap::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The statement of the shape, it becomes the most recently used
+    pub fn get(&mut self, shape: &str) -> Option<PreparedStatement> {
+        self.tick += 1;
+        let tick = self.tick;
+
+        let (statement, used) = self.entries.get_mut(shape)?;
+        let shape = self.order.remove(used)?;
+        *used = tick;
+        self.order.insert(tick, shape);
+
+        Some(statement.clone())
+    }
+
+    /// Adds the statement, the least recently used one is removed when the cache is full
+    pub fn insert(&mut self, shape: impl Into<String>, statement: PreparedStatement) {
+        self.tick += 1;
+        let shape = shape.into();
+
+        if let Some((_, used)) = self.entries.insert(shape.clone(), (statement, self.tick)) {
+            self.order.remove(&used);
+        }
+
+        self.order.insert(self.tick, shape);
+        self.evict();
+    }
+
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        self.evict();
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.order.clear();
+    }
+
+    fn evict(&mut self) {
+        while self.entries.len() > self.capacity {
+            let Some((_, oldest)) = self.order.pop_first() else {
+                break;
+            };
+
+            self.entries.remove(&oldest);
+        }
+    }
+}
+
+/// The cached statement with the same SurrealQL as `statement`, it is cached when there is none
+pub fn prepared(statement: impl Into<ExtraStatement>) -> PreparedStatement {
+    let statement = PreparedStatement::new(statement);
+    let shape = statement.to_surql();
+
+    with_cache(|cache| match cache.get(&shape) {
+        Some(cached) => cached,
+        None => {
+            cache.insert(shape, statement.clone());
+            statement
+        }
+    })
+}
+
+pub fn set_prepared_capacity(capacity: usize) {
+    with_cache(|cache| cache.set_capacity(capacity));
+}
+
+pub fn clear_prepared() {
+    with_cache(PreparedCache::clear);
+}
+
+fn with_cache<R>(f: impl FnOnce(&mut PreparedCache) -> R) -> R {
+    let mut cache = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+
+    f(cache.get_or_insert_with(PreparedCache::default))
+}
+
+#[cfg(test)]
+mod test {
+    use serde::Deserialize;
+    use surrealdb::sql::{Field, Operator};
+    use crate::query::parsing::str_to_value;
+    use crate::query::statement::StatementBuilder;
+    use crate::testing::db;
+    use super::*;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct User {
+        name: String,
+    }
+
+    fn statement(value: &str) -> PreparedStatement {
+        PreparedStatement::new(value)
+    }
+
+    #[test]
+    fn lru() {
+        let mut cache = PreparedCache::new(2);
+
+        cache.insert("a", statement("1"));
+        cache.insert("b", statement("2"));
+        assert!(cache.get("a").is_some());
+
+        cache.insert("c", statement("3"));
+
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get("b").is_none());
+        assert_eq!(cache.get("a").unwrap().to_surql(), "1;");
+
+        cache.set_capacity(1);
+        assert!(cache.get("c").is_none());
+
+        cache.insert("a", statement("4"));
+        assert_eq!(cache.len(), 1);
+        assert_eq!(cache.get("a").unwrap().to_surql(), "4;");
+    }
+
+    #[tokio::test]
+    async fn prepared_query() {
+        let db = db().await;
+
+        let create = PreparedStatement::new(db.create_builder().what("user").set(vec![("name", Operator::Equal, str_to_value("$name"))]));
+        assert_eq!(create.to_surql(), "CREATE user SET name = $name;");
+
+        for name in ["one", "two"] {
+            create.clone().query(&db).bind(("name", name)).await.unwrap().take_one::<User>(0).unwrap();
+        }
+
+        let mut shared = Vec::new();
+
+        for name in ["one", "two"] {
+            let by_name = prepared(db.select_builder().what("user").field(Field::All).condition("name = $name"));
+            shared.push(by_name.statements.clone());
+
+            let user: User = by_name.query(&db).bind(("name", name)).await.unwrap().take_one(0).unwrap();
+            assert_eq!(user.name, name);
+        }
+
+        assert!(Arc::ptr_eq(&shared[0], &shared[1]));
+    }
+}
@@ -0,0 +1,235 @@
+//! Hand-written SurrealQL with typed results
+//!
+//! `raw_query` runs a query string with bindings and returns a `TypedResponse`, a `Response` of a builder can be
+//! converted with `TypedResponse::from`. The errors say which statement failed or could not be deserialized.
+//!
+//! - `take_one` takes exactly one record, e.g. of `SELECT ... WHERE id = ...` or `CREATE ...`
+//! - `take_vec` takes all records, a single record or `NONE` are returned as a `Vec` as well
+//! - `take_scalar` takes a single value, e.g. of `RETURN count(SELECT * FROM user)` or `SELECT VALUE name FROM ONLY user:one`
+//!
+//! # Example
+//!
+//! ```rust
+//! use serde::Deserialize;
+//! use surrealdb::engine::any::connect;
+//! use surrealdb_extra::query::raw::raw_query;
+//!
+//! #[derive(Deserialize)]
+//! struct User {
+//!     name: String,
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let db = connect("mem://").await.unwrap();
+//!     db.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     let mut res = raw_query(&db, "
+//!         CREATE user:one SET name = $name;
+//!         SELECT * FROM user;
+//!         RETURN count(SELECT * FROM user);
+//!     ").bind(("name", "one")).await.unwrap();
+//!
+//!     let created: User = res.take_one(0).unwrap();
+//!     let users: Vec<User> = res.take_vec(1).unwrap();
+//!     let count: u64 = res.take_scalar(2).unwrap();
+//!
+//!     assert_eq!(created.name, "one");
+//!     assert_eq!(users.len(), 1);
+//!     assert_eq!(count, 1);
+//! }
+//! ```
+
+use std::future::{Future, IntoFuture};
+use std::pin::Pin;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use surrealdb::{Connection, Response, Surreal};
+use surrealdb::method::Query;
+use surrealdb::sql::{from_value, Value};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum RawQueryError {
+    #[error("Statement {index} failed: {error}")]
+    Statement { index: usize, error: surrealdb::Error },
+    #[error("Statement {0} returned no result")]
+    NoResult(usize),
+    #[error("Statement {index} returned {count} results, expected one")]
+    TooManyResults { index: usize, count: usize },
+    #[error("The result of statement {index} can't be deserialized: {message}")]
+    Deserialize { index: usize, message: String },
+    #[error("{0}")]
+    Db(#[from] surrealdb::Error),
+}
+
+/// Starts a query from a SurrealQL string, await it to run it
+pub fn raw_query<'r, Client: Connection>(db: &'r Surreal<Client>, query: impl Into<String>) -> RawQuery<'r, Client> {
+    RawQuery {
+        query: db.query(query.into()),
+    }
+}
+
+pub struct RawQuery<'r, Client>
+    where Client: Connection
+{
+    pub query: Query<'r, Client>,
+}
+
+impl<'r, Client> RawQuery<'r, Client>
+    where Client: Connection
+{
+    /// Binds parameters like `Query::bind`, e.g. `("name", "one")` or a struct
+    pub fn bind(mut self, bindings: impl Serialize + 'static) -> Self {
+        self.query = self.query.bind(bindings);
+
+        self
+    }
+}
+
+impl<'r, Client> IntoFuture for RawQuery<'r, Client>
+    where Client: Connection
+{
+    type Output = anyhow::Result<TypedResponse>;
+    type IntoFuture = Pin<Box<dyn Future<Output = Self::Output> + Send + Sync + 'r>>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        Box::pin(async move {
+            let res = self.query.await.map_err(RawQueryError::from)?;
+
+            Ok(TypedResponse(res))
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct TypedResponse(pub Response);
+
+impl From<Response> for TypedResponse {
+    fn from(value: Response) -> Self {
+        Self(value)
+    }
+}
+
+impl TypedResponse {
+    pub fn num_statements(&self) -> usize {
+        self.0.num_statements()
+    }
+
+    pub fn into_inner(self) -> Response {
+        self.0
+    }
+
+    /// Takes exactly one record, fails when the statement returned none or more than one
+    pub fn take_one<T: DeserializeOwned>(&mut self, index: usize) -> anyhow::Result<T> {
+        let val = match self.take_value(index)? {
+            Value::None | Value::Null => return Err(RawQueryError::NoResult(index).into()),
+            Value::Array(mut arr) => match arr.len() {
+                0 => return Err(RawQueryError::NoResult(index).into()),
+                1 => arr.0.remove(0),
+                count => return Err(RawQueryError::TooManyResults { index, count }.into()),
+            },
+            val => val,
+        };
+
+        deserialize(index, val)
+    }
+
+    /// Takes all records of the statement
+    pub fn take_vec<T: DeserializeOwned>(&mut self, index: usize) -> anyhow::Result<Vec<T>> {
+        let vals = match self.take_value(index)? {
+            Value::None => vec![],
+            Value::Array(arr) => arr.0,
+            val => vec![val],
+        };
+
+        vals.into_iter().map(|val| deserialize(index, val)).collect()
+    }
+
+    /// Takes a single value, an array with one value is unwrapped and `NONE` can be taken as an `Option`
+    pub fn take_scalar<T: DeserializeOwned>(&mut self, index: usize) -> anyhow::Result<T> {
+        let val = match self.take_value(index)? {
+            Value::Array(mut arr) if arr.len() == 1 => arr.0.remove(0),
+            val => val,
+        };
+
+        deserialize(index, val)
+    }
+
+    fn take_value(&mut self, index: usize) -> anyhow::Result<Value> {
+        let val: surrealdb::Value = self.0.take(index)
+            .map_err(|error| RawQueryError::Statement { index, error })?;
+
+        Ok(val.into_inner())
+    }
+}
+
+fn deserialize<T: DeserializeOwned>(index: usize, val: Value) -> anyhow::Result<T> {
+    from_value(val).map_err(|e| RawQueryError::Deserialize { index, message: e.to_string() }.into())
+}
+
+#[cfg(test)]
+mod test {
+    use serde::Deserialize;
+    use surrealdb::sql::Field;
+    use crate::query::statement::StatementBuilder;
+    use crate::testing::db;
+    use super::*;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct User {
+        name: String,
+    }
+
+    fn error(res: anyhow::Result<impl std::fmt::Debug>) -> RawQueryError {
+        res.unwrap_err().downcast::<RawQueryError>().unwrap()
+    }
+
+    #[tokio::test]
+    async fn take() {
+        let db = db().await;
+
+        let mut res = raw_query(&db, "
+            CREATE user:one SET name = 'one';
+            CREATE user:two SET name = 'two';
+            SELECT * FROM user;
+            SELECT * FROM user WHERE name = 'three';
+            RETURN 5;
+            RETURN NONE;
+            SELECT * FROM ONLY user:one;
+            SELECT VALUE name FROM ONLY user:one;
+        ").await.unwrap();
+
+        assert_eq!(res.num_statements(), 8);
+        assert_eq!(res.take_one::<User>(0).unwrap(), User { name: "one".to_string() });
+        assert_eq!(res.take_vec::<User>(1).unwrap(), vec![User { name: "two".to_string() }]);
+        assert!(matches!(error(res.take_one::<User>(2)), RawQueryError::TooManyResults { index: 2, count: 2 }));
+        assert!(matches!(error(res.take_one::<User>(3)), RawQueryError::NoResult(3)));
+        assert_eq!(res.take_scalar::<i64>(4).unwrap(), 5);
+        assert_eq!(res.take_scalar::<Option<i64>>(5).unwrap(), None);
+        assert!(matches!(error(res.take_scalar::<i64>(6)), RawQueryError::Deserialize { index: 6, .. }));
+        assert_eq!(res.take_scalar::<String>(7).unwrap(), "one");
+    }
+
+    #[tokio::test]
+    async fn statement_error() {
+        let db = db().await;
+
+        let mut res = raw_query(&db, "RETURN $a; THROW 'oops'").bind(("a", 1)).await.unwrap();
+
+        assert_eq!(res.take_scalar::<i64>(0).unwrap(), 1);
+        assert!(matches!(error(res.take_scalar::<i64>(1)), RawQueryError::Statement { index: 1, .. }));
+    }
+
+    #[tokio::test]
+    async fn from_builder() {
+        let db = db().await;
+
+        db.query("CREATE user:one SET name = 'one'").await.unwrap();
+
+        let res = db.select_builder().what("user").field(Field::All).to_query().await.unwrap();
+
+        let users: Vec<User> = TypedResponse::from(res).take_vec(0).unwrap();
+        assert_eq!(users.len(), 1);
+    }
+}
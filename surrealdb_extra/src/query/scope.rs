@@ -0,0 +1,150 @@
+//! Running a single statement in another namespace and database
+//!
+//! `use_ns_db` on the select, create, update and relate builders returns a `Scoped` query that becomes
+//! `USE NS ns DB db; <statement>`, so multi-tenant apps don't need a client per namespace or database.
+//! The `USE` only applies to the statements of that query, the client keeps its namespace and database.
+//! The result of the statement is at `STATEMENT_INDEX` of the response.
+//!
+//! `Table` has the same with `get_all_in`, `get_by_id_in`, `create_in` and `delete_in`.
+//!
+//! # Example
+//!
+//! ```rust
+//! use surrealdb::engine::any::connect;
+//! use surrealdb::sql::Operator;
+//! use surrealdb_extra::query::scope::STATEMENT_INDEX;
+//! use surrealdb_extra::query::statement::StatementBuilder;
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let db = connect("mem://").await.unwrap();
+//!     db.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     // This becomes `USE NS tenant DB one; CREATE user SET name = 'one'`
+//!     db.create_builder().what("user").set(vec![("name", Operator::Equal, "one")]).use_ns_db("tenant", "one").to_query().await.unwrap();
+//!
+//!     let names: Vec<String> = db.select_builder().what("user").value("name").use_ns_db("tenant", "one")
+//!         .to_query().await.unwrap().take(STATEMENT_INDEX).unwrap();
+//!     assert_eq!(names, ["one"]);
+//!
+//!     let names: Vec<String> = db.select_builder().what("user").value("name").to_query().await.unwrap().take(0).unwrap();
+//!     assert!(names.is_empty());
+//! }
+//! ```
+
+use serde::de::DeserializeOwned;
+use surrealdb::{Connection, Surreal};
+use surrealdb::method::Query;
+use surrealdb::sql::{Statement, Statements};
+use surrealdb::sql::statements::UseStatement;
+use crate::query::parsing::statement::ExtraStatement;
+use crate::query::raw::{RawQueryError, TypedResponse};
+
+/// Index of the scoped statement in the response, index `0` is the `USE`
+pub const STATEMENT_INDEX: usize = 1;
+
+/// A statement that runs in another namespace and database
+#[derive(Debug, Clone)]
+pub struct Scoped<'r, Client>
+    where Client: Connection
+{
+    pub ns: String,
+    pub database: String,
+    pub statement: Statement,
+    pub(crate) db: &'r Surreal<Client>,
+}
+
+impl<'r, Client> Scoped<'r, Client>
+    where Client: Connection
+{
+    pub fn new(db: &'r Surreal<Client>, ns: impl Into<String>, database: impl Into<String>, statement: impl Into<ExtraStatement>) -> Self {
+        Self {
+            ns: ns.into(),
+            database: database.into(),
+            statement: statement.into().0,
+            db,
+        }
+    }
+
+    fn statements(&self) -> Vec<Statement> {
+        let mut use_statement = UseStatement::default();
+        use_statement.ns = Some(self.ns.clone());
+        use_statement.db = Some(self.database.clone());
+
+        vec![Statement::Use(use_statement), self.statement.clone()]
+    }
+
+    /// The SurrealQL that `to_query` runs, parameters stay `$name` placeholders for the bindings
+    pub fn to_surql(&self) -> String {
+        let mut statements = Statements::default();
+        statements.0 = self.statements();
+
+        statements.to_string()
+    }
+
+    /// Converts to a query, the result of the statement is at `STATEMENT_INDEX`
+    pub fn to_query(self) -> Query<'r, Client> {
+        self.db.query(self.statements())
+    }
+
+    /// Runs the query and deserializes every row of the statement
+    pub async fn execute<T: DeserializeOwned>(self) -> anyhow::Result<Vec<T>> {
+        let res = self.to_query().await.map_err(RawQueryError::from)?;
+
+        TypedResponse::from(res).take_vec(STATEMENT_INDEX)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde::{Deserialize, Serialize};
+    use surrealdb::engine::any::connect;
+    use surrealdb::sql::{Operator, Thing as RecordId};
+    use crate::query::statement::StatementBuilder;
+    use crate::table::Table;
+    use crate::testing::db;
+
+    #[derive(Debug, Table, Serialize, Deserialize, Clone, PartialEq)]
+    #[table(name = "user")]
+    struct User {
+        id: Option<RecordId>,
+        name: String,
+    }
+
+    #[tokio::test]
+    async fn scoped_to_surql() {
+        let db = connect("mem://").await.unwrap();
+
+        let scoped = db.select_builder().what("user").field("name").use_ns_db("tenant", "one");
+
+        assert_eq!(scoped.to_surql(), "USE NS tenant DB one;\nSELECT name FROM user;");
+    }
+
+    #[tokio::test]
+    async fn scoped_execute() {
+        let db = db().await;
+
+        db.create_builder().what("user").set(vec![("n", Operator::Equal, 1)]).use_ns_db("test", "other").to_query().await.unwrap().check().unwrap();
+
+        let other: Vec<i64> = db.select_builder().what("user").value("n").use_ns_db("test", "other").execute().await.unwrap();
+        assert_eq!(other, [1]);
+
+        let current: Vec<i64> = db.select_builder().what("user").value("n").execute_values().await.unwrap();
+        assert!(current.is_empty());
+    }
+
+    #[tokio::test]
+    async fn table_in() {
+        let db = db().await;
+
+        let user = User { id: None, name: "one".to_string() }.create_in(&db, "test", "other").await.unwrap().unwrap();
+        let id = user.id.clone().unwrap();
+
+        assert_eq!(User::get_all_in(&db, "test", "other").await.unwrap(), vec![user.clone()]);
+        assert_eq!(User::get_by_id_in(&db, "test", "other", id.clone()).await.unwrap(), Some(user.clone()));
+        assert!(User::get_all(&db).await.unwrap().is_empty());
+
+        assert_eq!(User::delete_in(&db, "test", "other", id).await.unwrap(), Some(user));
+        assert!(User::get_all_in(&db, "test", "other").await.unwrap().is_empty());
+    }
+}
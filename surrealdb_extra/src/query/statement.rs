@@ -1,18 +1,35 @@
 use std::marker::PhantomData;
 use surrealdb::{Connection, Surreal};
 use crate::query::create::CreateBuilder;
+use crate::query::dyn_select::DynSelectBuilder;
+use crate::query::foreach::ForEachBuilder;
+use crate::query::function::{DefineFunctionBuilder, FunctionBuilder};
+use crate::query::ifelse::IfElseBuilder;
+use crate::query::info::InfoBuilder;
 use crate::query::relate::RelateBuilder;
+use crate::query::report::ReportBuilder;
+use crate::query::script::QueryScriptBuilder;
 use crate::query::select::SelectBuilder;
-use crate::query::states::{NoCond, NoData, NoFields, NoRelation, NoWhat};
+use crate::query::show::ShowChangesBuilder;
+use crate::query::states::{ForDb, NoCond, NoData, NoFields, NoRelation, NoSince, NoWhat};
 use crate::query::update::UpdateBuilder;
 
 pub trait StatementBuilder<Client>
     where Client: Connection
 {
     fn select_builder(&self) -> SelectBuilder<Client, NoWhat, NoFields, NoCond>;
+    fn dyn_select_builder(&self) -> DynSelectBuilder<'_, Client>;
     fn update_builder(&self) -> UpdateBuilder<Client, NoWhat, NoData, NoCond>;
     fn relate_builder(&self) -> RelateBuilder<Client, NoRelation, NoData>;
     fn create_builder(&self) -> CreateBuilder<Client, NoWhat, NoData>;
+    fn ifelse_builder(&self) -> IfElseBuilder<'_, Client, NoCond>;
+    fn script_builder(&self) -> QueryScriptBuilder<'_, Client>;
+    fn foreach_builder(&self) -> ForEachBuilder<'_, Client, NoWhat>;
+    fn report_builder(&self) -> ReportBuilder<'_, Client>;
+    fn show_changes_builder(&self) -> ShowChangesBuilder<'_, Client, NoSince>;
+    fn function_builder(&self, name: impl Into<String>) -> FunctionBuilder<'_, Client>;
+    fn define_function_builder(&self, name: impl Into<String>) -> DefineFunctionBuilder<'_, Client>;
+    fn info_builder(&self) -> InfoBuilder<'_, Client, ForDb>;
 }
 
 impl<Client: Connection> StatementBuilder<Client> for Surreal<Client>
@@ -28,6 +45,10 @@ impl<Client: Connection> StatementBuilder<Client> for Surreal<Client>
         }
     }
 
+    fn dyn_select_builder(&self) -> DynSelectBuilder<'_, Client> {
+        DynSelectBuilder::new(self)
+    }
+
     fn update_builder(&self) -> UpdateBuilder<Client, NoWhat, NoData, NoCond> {
         UpdateBuilder {
             statement: Default::default(),
@@ -55,6 +76,49 @@ impl<Client: Connection> StatementBuilder<Client> for Surreal<Client>
             data_state: PhantomData,
         }
     }
+
+    fn ifelse_builder(&self) -> IfElseBuilder<'_, Client, NoCond> {
+        IfElseBuilder {
+            statement: Default::default(),
+            db: self,
+            cond_state: PhantomData,
+        }
+    }
+
+    fn script_builder(&self) -> QueryScriptBuilder<'_, Client> {
+        QueryScriptBuilder {
+            statements: vec![],
+            db: self,
+        }
+    }
+
+    fn foreach_builder(&self) -> ForEachBuilder<'_, Client, NoWhat> {
+        ForEachBuilder {
+            statement: Default::default(),
+            db: self,
+            what_state: PhantomData,
+        }
+    }
+
+    fn report_builder(&self) -> ReportBuilder<'_, Client> {
+        ReportBuilder::new(self)
+    }
+
+    fn show_changes_builder(&self) -> ShowChangesBuilder<'_, Client, NoSince> {
+        ShowChangesBuilder::new(self)
+    }
+
+    fn function_builder(&self, name: impl Into<String>) -> FunctionBuilder<'_, Client> {
+        FunctionBuilder::new(self, name)
+    }
+
+    fn define_function_builder(&self, name: impl Into<String>) -> DefineFunctionBuilder<'_, Client> {
+        DefineFunctionBuilder::new(self, name)
+    }
+
+    fn info_builder(&self) -> InfoBuilder<'_, Client, ForDb> {
+        InfoBuilder::new(self)
+    }
 }
 
 #[cfg(test)]
@@ -86,4 +150,52 @@ mod test {
 
         let _create_builder = db.create_builder();
     }
+    #[tokio::test]
+    async fn ifelse_builder() {
+        let db = connect("mem://").await.unwrap();
+
+        let _ifelse_builder = db.ifelse_builder();
+    }
+    #[tokio::test]
+    async fn script_builder() {
+        let db = connect("mem://").await.unwrap();
+
+        let _script_builder = db.script_builder();
+    }
+    #[tokio::test]
+    async fn foreach_builder() {
+        let db = connect("mem://").await.unwrap();
+
+        let _foreach_builder = db.foreach_builder();
+    }
+    #[tokio::test]
+    async fn report_builder() {
+        let db = connect("mem://").await.unwrap();
+
+        let _report_builder = db.report_builder();
+    }
+    #[tokio::test]
+    async fn show_changes_builder() {
+        let db = connect("mem://").await.unwrap();
+
+        let _show_changes_builder = db.show_changes_builder();
+    }
+    #[tokio::test]
+    async fn function_builder() {
+        let db = connect("mem://").await.unwrap();
+
+        let _function_builder = db.function_builder("test");
+    }
+    #[tokio::test]
+    async fn define_function_builder() {
+        let db = connect("mem://").await.unwrap();
+
+        let _define_function_builder = db.define_function_builder("test");
+    }
+    #[tokio::test]
+    async fn info_builder() {
+        let db = connect("mem://").await.unwrap();
+
+        let _info_builder = db.info_builder();
+    }
 }
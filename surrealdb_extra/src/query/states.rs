@@ -18,6 +18,22 @@ pub struct NoCond;
 #[allow(dead_code)]
 #[derive(Debug, Clone)]
 pub struct FilledCond;
+/// No `WHERE` on purpose, see `UpdateBuilder::all_records`
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub struct AllRecords;
+
+/// No `WHERE` and `what` is a record or a parameter, not a table, so there is nothing to confirm
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub struct NoTable;
+
+/// The cond states that can be run, `NoCond` has to be confirmed with `AllRecords` first
+pub trait CheckedCond {}
+
+impl CheckedCond for FilledCond {}
+impl CheckedCond for AllRecords {}
+impl CheckedCond for NoTable {}
 
 #[allow(dead_code)]
 #[derive(Debug, Clone)]
@@ -33,3 +49,20 @@ pub struct NoData;
 #[derive(Debug, Clone)]
 pub struct FilledData;
 
+
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub struct NoSince;
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub struct FilledSince;
+
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub struct ForNs;
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub struct ForDb;
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub struct ForTable;
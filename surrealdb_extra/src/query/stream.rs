@@ -0,0 +1,206 @@
+//! Streaming select results
+//!
+//! `SelectBuilder::stream` runs the select in chunks with `START`/`LIMIT` and yields the rows one by one,
+//! so only one chunk is in memory at a time. A `START` or `LIMIT` set on the builder is kept as the range of the whole stream.
+//!
+//! Add an `ORDER BY` for a stable order, without it rows can be skipped or repeated when the table changes between chunks.
+//!
+//! # Example
+//!
+//! ```rust
+//! use futures::TryStreamExt;
+//! use serde::Deserialize;
+//! use surrealdb::engine::any::connect;
+//! use surrealdb::sql::{Field, Thing};
+//! use surrealdb_extra::query::statement::StatementBuilder;
+//!
+//! #[derive(Deserialize)]
+//! struct Test {
+//!     id: Thing,
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let db = connect("mem://").await.unwrap();
+//!     db.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     db.query("FOR $i IN 1..=10 { CREATE test }").await.unwrap();
+//!
+//!     let mut rows = db.select_builder().what("test").field(Field::All).stream_chunks::<Test>(3);
+//!
+//!     let mut count = 0;
+//!     while let Some(_row) = rows.try_next().await.unwrap() {
+//!         count += 1;
+//!     }
+//!
+//!     assert_eq!(count, 10);
+//! }
+//! ```
+
+use anyhow::Result;
+use futures::{stream, StreamExt, TryStreamExt};
+use futures::stream::BoxStream;
+use serde::de::DeserializeOwned;
+use surrealdb::{Connection, Surreal};
+use surrealdb::sql::{Limit, Start, Value};
+use surrealdb::sql::statements::SelectStatement;
+use thiserror::Error;
+
+pub const DEFAULT_CHUNK_SIZE: u64 = 1000;
+
+#[derive(Debug, Error)]
+pub enum StreamError {
+    #[error("{0} of a streamed select must be a number")]
+    NotNumeric(&'static str),
+    #[error("{0}")]
+    Db(#[from] surrealdb::Error),
+}
+
+struct Chunks<'r, C: Connection> {
+    db: &'r Surreal<C>,
+    statement: SelectStatement,
+    start: u64,
+    remaining: Option<u64>,
+    chunk_size: u64,
+}
+
+/// Rows of the select fetched `chunk_size` rows at a time
+pub(crate) fn stream<'r, C, T>(db: &'r Surreal<C>, statement: SelectStatement, chunk_size: u64) -> BoxStream<'r, Result<T>>
+    where C: Connection, T: DeserializeOwned + Send + 'r
+{
+    let chunks = range(&statement).map(|(start, remaining)| Chunks {
+        db,
+        statement,
+        start,
+        remaining,
+        chunk_size: chunk_size.max(1),
+    });
+
+    stream::once(async { chunks })
+        .map_ok(|chunks| stream::try_unfold(Some(chunks), next_chunk))
+        .try_flatten()
+        .map_ok(|rows: Vec<T>| stream::iter(rows.into_iter().map(Ok)))
+        .try_flatten()
+        .boxed()
+}
+
+async fn next_chunk<C: Connection, T: DeserializeOwned>(chunks: Option<Chunks<'_, C>>) -> Result<Option<(Vec<T>, Option<Chunks<'_, C>>)>> {
+    let Some(mut chunks) = chunks else {
+        return Ok(None);
+    };
+
+    let limit = chunks.remaining.map_or(chunks.chunk_size, |r| r.min(chunks.chunk_size));
+
+    if limit == 0 {
+        return Ok(None);
+    }
+
+    let mut start = Start::default();
+    start.0 = Value::from(chunks.start);
+
+    let mut limit_clause = Limit::default();
+    limit_clause.0 = Value::from(limit);
+
+    let mut statement = chunks.statement.clone();
+    statement.start = Some(start);
+    statement.limit = Some(limit_clause);
+
+    #[cfg(feature = "deadline")]
+    let statement = crate::query::deadline::apply(statement);
+
+    let rows: Vec<T> = chunks.db.query(statement).await
+        .map_err(StreamError::from)?
+        .take(0).map_err(StreamError::from)?;
+
+    let fetched = rows.len() as u64;
+
+    chunks.start += fetched;
+    chunks.remaining = chunks.remaining.map(|r| r - fetched.min(r));
+
+    let next = (fetched == limit).then_some(chunks);
+
+    Ok(Some((rows, next)))
+}
+
+/// Start and row count of the whole stream from the clauses on the statement
+fn range(statement: &SelectStatement) -> Result<(u64, Option<u64>)> {
+    let start = match &statement.start {
+        None => 0,
+        Some(start) => number(&start.0).ok_or(StreamError::NotNumeric("START"))?,
+    };
+
+    let remaining = match &statement.limit {
+        None => None,
+        Some(limit) => Some(number(&limit.0).ok_or(StreamError::NotNumeric("LIMIT"))?),
+    };
+
+    Ok((start, remaining))
+}
+
+fn number(value: &Value) -> Option<u64> {
+    match value {
+        Value::Number(n) if n.is_int() => u64::try_from(n.to_int()).ok(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde::Deserialize;
+    use surrealdb::engine::any::Any;
+    use surrealdb::sql::Field;
+    use crate::query::parsing::order::OrderDirection;
+    use crate::query::statement::StatementBuilder;
+    use super::*;
+
+    #[derive(Debug, Deserialize)]
+    struct Num {
+        n: i64,
+    }
+
+    async fn db() -> Surreal<Any> {
+        let db = crate::testing::db().await;
+
+        db.query("FOR $i IN 0..10 { CREATE num SET n = $i }").await.unwrap().check().unwrap();
+
+        db
+    }
+
+    #[tokio::test]
+    async fn all_rows_in_chunks() {
+        let db = db().await;
+
+        let rows: Vec<Num> = db.select_builder().what("num").field(Field::All)
+            .order(("n", OrderDirection::ASC))
+            .stream_chunks(3)
+            .try_collect().await.unwrap();
+
+        assert_eq!(rows.iter().map(|r| r.n).collect::<Vec<_>>(), (0..10).collect::<Vec<_>>());
+    }
+
+    #[tokio::test]
+    async fn keeps_start_and_limit() {
+        let db = db().await;
+
+        let rows: Vec<Num> = db.select_builder().what("num").field(Field::All)
+            .order(("n", OrderDirection::ASC))
+            .start(2)
+            .limit(5)
+            .stream_chunks(2)
+            .try_collect().await.unwrap();
+
+        assert_eq!(rows.iter().map(|r| r.n).collect::<Vec<_>>(), vec![2, 3, 4, 5, 6]);
+    }
+
+    #[tokio::test]
+    async fn not_numeric_limit() {
+        let db = db().await;
+
+        let res: Result<Vec<Num>> = db.select_builder().what("num").field(Field::All)
+            .limit(Value::Param("limit".into()))
+            .stream()
+            .try_collect().await;
+
+        assert!(res.is_err());
+    }
+}
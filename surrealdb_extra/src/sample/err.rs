@@ -0,0 +1,9 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum SampleError {
+    #[error("`{0}` is not a valid weight field")]
+    InvalidField(String),
+    #[error("{0}")]
+    Db(#[from] surrealdb::Error),
+}
@@ -0,0 +1,200 @@
+//! Random samples of table records
+//!
+//! `weighted` lets the database pick the records, every record is picked with a probability proportional to its weight
+//! (Efraimidis-Spirakis: ordered by `rand::float() ^ (1 / weight)`).
+//!
+//! `reservoir` scans the matching records in pages and keeps a uniform sample on the client,
+//! so only `n` records are kept in memory no matter how many records match.
+//!
+//! # Example
+//!
+//! ```rust
+//! use serde::{Serialize, Deserialize};
+//! use surrealdb::engine::any::connect;
+//! use surrealdb::sql::{idiom, Expression, Operator, Thing as RecordId, Value};
+//! use surrealdb_extra::table::Table;
+//! use surrealdb_extra::sample;
+//!
+//! #[derive(Table, Serialize, Deserialize, Clone)]
+//! #[table(name = "variant")]
+//! struct Variant {
+//!     id: Option<RecordId>,
+//!     weight: f64,
+//!     active: bool
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let db = connect("mem://").await.unwrap();
+//!     db.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     db.query("CREATE variant:a SET weight = 0.9, active = true; CREATE variant:b SET weight = 0.1, active = true;").await.unwrap();
+//!
+//!     // Variant a is picked 9 out of 10 times
+//!     let picked: Vec<Variant> = sample::weighted(&db, "weight", 1).await.unwrap();
+//!
+//!     let active = Expression::Binary { l: Value::Idiom(idiom("active").unwrap()), o: Operator::Equal, r: Value::Bool(true) };
+//!
+//!     let sample: Vec<Variant> = sample::reservoir(&db, active, 1).await.unwrap();
+//! }
+//! ```
+
+pub mod err;
+
+use anyhow::Result;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use surrealdb::sql::Field;
+use surrealdb::{Connection, Surreal};
+use crate::query::parsing::cond::ExtraCond;
+use crate::query::parsing::order::OrderDirection;
+use crate::query::select::SelectBuilder;
+use crate::table::Table;
+pub use crate::sample::err::SampleError;
+
+/// Number of records fetched per page by `reservoir`
+pub const PAGE_SIZE: i64 = 1000;
+
+/// Picks `n` records without replacement, records with a weight of zero or less are never picked
+pub async fn weighted<T: Table, C: Connection>(db: &Surreal<C>, weight_field: &str, n: usize) -> Result<Vec<T>> {
+    let valid = !weight_field.is_empty() && weight_field.split('.')
+        .all(|part| !part.is_empty() && part.chars().all(|c| c.is_alphanumeric() || c == '_'));
+
+    if !valid {
+        return Err(SampleError::InvalidField(weight_field.to_string()).into());
+    }
+
+    let query = format!(
+        "SELECT * OMIT _sample_key FROM (\
+            SELECT *, math::pow(rand::float(), 1.0 / {weight_field}) AS _sample_key FROM type::table($table) WHERE {weight_field} > 0\
+        ) ORDER BY _sample_key DESC LIMIT $n"
+    );
+
+    let mut res = db.query(query)
+        .bind(("table", T::TABLE_NAME))
+        .bind(("n", n as i64))
+        .await.map_err(SampleError::from)?;
+
+    let sample: Vec<T> = res.take(0).map_err(SampleError::from)?;
+
+    Ok(sample)
+}
+
+/// Picks `n` records uniformly from the records matching the condition
+pub async fn reservoir<T: Table, C: Connection>(db: &Surreal<C>, cond: impl Into<ExtraCond>, n: usize) -> Result<Vec<T>> {
+    let cond = cond.into();
+    let mut rng = StdRng::from_entropy();
+
+    let mut sample: Vec<T> = Vec::with_capacity(n);
+    let mut seen = 0usize;
+    let mut start = 0i64;
+
+    loop {
+        let mut res = SelectBuilder::new(db)
+            .what(T::TABLE_NAME)
+            .field(Field::All)
+            .condition(cond.clone())
+            .order(("id", OrderDirection::ASC))
+            .limit(PAGE_SIZE)
+            .start(start)
+            .to_query()
+            .await.map_err(SampleError::from)?;
+
+        let page: Vec<T> = res.take(0).map_err(SampleError::from)?;
+        let len = page.len() as i64;
+
+        for record in page {
+            if sample.len() < n {
+                sample.push(record);
+            } else {
+                let i = rng.gen_range(0..=seen);
+
+                if i < n {
+                    sample[i] = record;
+                }
+            }
+
+            seen += 1;
+        }
+
+        if len < PAGE_SIZE {
+            break;
+        }
+
+        start += len;
+    }
+
+    Ok(sample)
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+    use serde::{Deserialize, Serialize};
+    use surrealdb::engine::any::Any;
+    use surrealdb::sql::{idiom, Expression, Operator, Thing as RecordId, Value};
+    use super::*;
+
+    #[derive(Debug, Table, Serialize, Deserialize, Clone, PartialEq)]
+    #[table(name = "item")]
+    struct Item {
+        id: Option<RecordId>,
+        weight: f64,
+        n: i64,
+    }
+
+    async fn db() -> Surreal<Any> {
+        let db = crate::testing::db().await;
+
+        db.query("
+            CREATE item:a SET weight = 100, n = 1;
+            CREATE item:b SET weight = 1, n = 2;
+            CREATE item:c SET weight = 0, n = 3;
+        ").await.unwrap().check().unwrap();
+
+        db
+    }
+
+    #[tokio::test]
+    async fn weighted_sample() {
+        let db = db().await;
+
+        let mut picked: HashMap<i64, usize> = HashMap::new();
+
+        for _ in 0..50 {
+            let sample: Vec<Item> = weighted(&db, "weight", 1).await.unwrap();
+            assert_eq!(sample.len(), 1);
+
+            *picked.entry(sample[0].n).or_default() += 1;
+        }
+
+        assert!(picked.get(&1).copied().unwrap_or_default() > 40);
+        assert!(!picked.contains_key(&3));
+
+        let all: Vec<Item> = weighted(&db, "weight", 5).await.unwrap();
+        assert_eq!(all.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn weighted_invalid_field() {
+        let db = db().await;
+
+        assert!(weighted::<Item, _>(&db, "weight; DELETE item", 1).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn reservoir_sample() {
+        let db = db().await;
+
+        let cond = Expression::Binary { l: Value::Idiom(idiom("n").unwrap()), o: Operator::MoreThan, r: Value::from(1) };
+
+        for _ in 0..10 {
+            let sample: Vec<Item> = reservoir(&db, cond.clone(), 1).await.unwrap();
+            assert_eq!(sample.len(), 1);
+            assert!(sample[0].n > 1);
+        }
+
+        let sample: Vec<Item> = reservoir(&db, Value::Bool(true), 10).await.unwrap();
+        assert_eq!(sample.len(), 3);
+    }
+}
@@ -0,0 +1,11 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum SearchError {
+    #[error("The name `{0}` can only have alphanumeric and/or `_` characters")]
+    InvalidName(String),
+    #[error("The search of table `{0}` has no fields")]
+    NoFields(String),
+    #[error("{0}")]
+    Db(#[from] surrealdb::Error),
+}
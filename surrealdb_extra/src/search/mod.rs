@@ -0,0 +1,329 @@
+//! Full-text search over multiple tables
+//!
+//! `across` runs the search of every table in one request and merges the hits by score into one list.
+//! Every config converts its records into the same type, e.g. an enum with a variant for every table.
+//!
+//! `search_cfg` searches all `String` fields of the table, `fields` replaces them.
+//! The fields need a `SEARCH` index, `define_indexes` defines them with a default analyzer.
+//!
+//! The scores are BM25 scores that are summed over the matching fields, they are comparable but not normalized between tables.
+//!
+//! # Example
+//!
+//! ```rust
+//! use serde::{Serialize, Deserialize};
+//! use surrealdb::engine::any::connect;
+//! use surrealdb::sql::Thing as RecordId;
+//! use surrealdb_extra::table::Table;
+//! use surrealdb_extra::search;
+//! use surrealdb_extra::search::SearchHit;
+//!
+//! #[derive(Table, Serialize, Deserialize, Clone)]
+//! #[table(name = "post")]
+//! struct Post {
+//!     id: Option<RecordId>,
+//!     title: String,
+//!     body: String
+//! }
+//!
+//! #[derive(Table, Serialize, Deserialize, Clone)]
+//! #[table(name = "user")]
+//! struct User {
+//!     id: Option<RecordId>,
+//!     name: String
+//! }
+//!
+//! enum Hit {
+//!     Post(Post),
+//!     User(User),
+//! }
+//!
+//! impl From<Post> for Hit {
+//!     fn from(post: Post) -> Self {
+//!         Hit::Post(post)
+//!     }
+//! }
+//!
+//! impl From<User> for Hit {
+//!     fn from(user: User) -> Self {
+//!         Hit::User(user)
+//!     }
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let db = connect("mem://").await.unwrap();
+//!     db.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     Post::search_cfg::<Hit>().define_indexes(&db).await.unwrap();
+//!     User::search_cfg::<Hit>().define_indexes(&db).await.unwrap();
+//!
+//!     Post { id: None, title: "Rust".to_string(), body: "Hello".to_string() }.create(&db).await.unwrap();
+//!     User { id: None, name: "Rust fan".to_string() }.create(&db).await.unwrap();
+//!
+//!     let hits: Vec<SearchHit<Hit>> = search::across(&db, [Post::search_cfg(), User::search_cfg()], "rust").await.unwrap();
+//!
+//!     assert_eq!(hits.len(), 2);
+//! }
+//! ```
+
+pub mod err;
+
+use anyhow::Result;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use surrealdb::{Connection, Response, Surreal};
+use crate::table::Table;
+pub use crate::search::err::SearchError;
+
+pub const DEFAULT_LIMIT: u64 = 20;
+
+/// Analyzer used by `define_indexes`
+pub const DEFAULT_ANALYZER: &str = "search_default";
+
+/// A record found by the search with the summed score of its fields
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchHit<H> {
+    pub table: &'static str,
+    pub score: f64,
+    pub record: H,
+}
+
+/// The search of one table, `H` is the type the records are converted into
+pub struct SearchConfig<H> {
+    pub table: &'static str,
+    pub fields: Vec<String>,
+    pub limit: u64,
+    take: TakeHits<H>,
+}
+
+/// Takes the hits of one statement with their score
+type TakeHits<H> = fn(&mut Response, usize) -> Result<Vec<(f64, H)>>;
+
+#[derive(Deserialize)]
+struct Scored<T> {
+    score: f64,
+    record: T,
+}
+
+fn take_hits<T: Table, H: From<T>>(res: &mut Response, index: usize) -> Result<Vec<(f64, H)>> {
+    let scored: Vec<Scored<T>> = res.take(index).map_err(SearchError::from)?;
+
+    Ok(scored.into_iter().map(|s| (s.score, H::from(s.record))).collect())
+}
+
+impl<H> SearchConfig<H> {
+    /// Searches all `String` and `Option<String>` fields of the table
+    pub fn of<T: Table + DeserializeOwned>() -> Self where H: From<T> {
+        let fields = T::meta().fields.iter()
+            .filter(|f| matches!(f.rust_type, "String" | "Option<String>"))
+            .map(|f| f.name.to_string())
+            .collect();
+
+        Self {
+            table: T::TABLE_NAME,
+            fields,
+            limit: DEFAULT_LIMIT,
+            take: take_hits::<T, H>,
+        }
+    }
+
+    pub fn fields(mut self, fields: &[&str]) -> Self {
+        self.fields = fields.iter().map(|f| f.to_string()).collect();
+
+        self
+    }
+
+    /// Maximum number of hits of this table
+    pub fn limit(mut self, limit: u64) -> Self {
+        self.limit = limit;
+
+        self
+    }
+
+    fn check(&self) -> Result<()> {
+        if self.fields.is_empty() {
+            return Err(SearchError::NoFields(self.table.to_string()).into());
+        }
+
+        for name in self.fields.iter().map(String::as_str).chain([self.table]) {
+            if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+                return Err(SearchError::InvalidName(name.to_string()).into());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// `SELECT` that matches every field with its own match reference and sums the scores
+    pub fn query(&self) -> Result<String> {
+        self.check()?;
+
+        let matches: Vec<String> = self.fields.iter().enumerate()
+            .map(|(i, field)| format!("{field} @{i}@ $query"))
+            .collect();
+
+        let scores: Vec<String> = (0..self.fields.len())
+            .map(|i| format!("search::score({i})"))
+            .collect();
+
+        Ok(format!(
+            "SELECT $this AS record, ({scores}) AS score FROM {table} WHERE {matches} ORDER BY score DESC LIMIT {limit}",
+            scores = scores.join(" + "),
+            table = self.table,
+            matches = matches.join(" OR "),
+            limit = self.limit,
+        ))
+    }
+
+    /// Defines the default analyzer and a `SEARCH` index for every field
+    pub async fn define_indexes<C: Connection>(&self, db: &Surreal<C>) -> Result<()> {
+        self.check()?;
+
+        let mut query = format!("DEFINE ANALYZER OVERWRITE {DEFAULT_ANALYZER} TOKENIZERS blank, class FILTERS lowercase, ascii;");
+
+        for field in &self.fields {
+            query.push_str(&format!(
+                "DEFINE INDEX OVERWRITE {table}_{field}_search ON TABLE {table} FIELDS {field} SEARCH ANALYZER {DEFAULT_ANALYZER} BM25;",
+                table = self.table,
+            ));
+        }
+
+        db.query(query).await.map_err(SearchError::from)?.check().map_err(SearchError::from)?;
+
+        Ok(())
+    }
+}
+
+/// Searches every table in one request and returns the hits of all tables ordered by score
+pub async fn across<C: Connection, H>(db: &Surreal<C>, configs: impl IntoIterator<Item = SearchConfig<H>>, query: impl Into<String>) -> Result<Vec<SearchHit<H>>> {
+    let configs: Vec<SearchConfig<H>> = configs.into_iter().collect();
+
+    if configs.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let mut statements = vec![];
+    for config in &configs {
+        statements.push(config.query()?);
+    }
+
+    let mut res = db.query(statements.join(";"))
+        .bind(("query", query.into()))
+        .await.map_err(SearchError::from)?
+        .check().map_err(SearchError::from)?;
+
+    let mut hits = vec![];
+
+    for (i, config) in configs.iter().enumerate() {
+        let found = (config.take)(&mut res, i)?;
+
+        hits.extend(found.into_iter().map(|(score, record)| SearchHit {
+            table: config.table,
+            score,
+            record,
+        }));
+    }
+
+    hits.sort_by(|a, b| b.score.total_cmp(&a.score));
+
+    Ok(hits)
+}
+
+#[cfg(test)]
+mod test {
+    use serde::Serialize;
+    use surrealdb::engine::any::Any;
+    use surrealdb::sql::Thing;
+    use super::*;
+
+    #[derive(Debug, Table, Serialize, Deserialize, Clone, PartialEq)]
+    #[table(name = "post")]
+    struct Post {
+        id: Option<Thing>,
+        title: String,
+        body: Option<String>,
+        views: i64,
+    }
+
+    #[derive(Debug, Table, Serialize, Deserialize, Clone, PartialEq)]
+    #[table(name = "user")]
+    struct User {
+        id: Option<Thing>,
+        name: String,
+    }
+
+    #[derive(Debug, PartialEq)]
+    enum Hit {
+        Post(Post),
+        User(User),
+    }
+
+    impl From<Post> for Hit {
+        fn from(post: Post) -> Self {
+            Hit::Post(post)
+        }
+    }
+
+    impl From<User> for Hit {
+        fn from(user: User) -> Self {
+            Hit::User(user)
+        }
+    }
+
+    async fn db() -> Surreal<Any> {
+        let db = crate::testing::db().await;
+
+        Post::search_cfg::<Hit>().define_indexes(&db).await.unwrap();
+        User::search_cfg::<Hit>().define_indexes(&db).await.unwrap();
+
+        db
+    }
+
+    #[test]
+    fn string_fields() {
+        let config = Post::search_cfg::<Hit>();
+
+        assert_eq!(config.fields, vec!["title".to_string(), "body".to_string()]);
+        assert_eq!(
+            config.query().unwrap(),
+            "SELECT $this AS record, (search::score(0) + search::score(1)) AS score FROM post WHERE title @0@ $query OR body @1@ $query ORDER BY score DESC LIMIT 20"
+        );
+    }
+
+    #[test]
+    fn invalid_field() {
+        let config = Post::search_cfg::<Hit>().fields(&["title; DELETE post"]);
+
+        assert!(config.query().is_err());
+    }
+
+    #[tokio::test]
+    async fn merged_by_score() {
+        let db = db().await;
+
+        Post { id: None, title: "Rust".to_string(), body: Some("rust and surrealdb".to_string()), views: 1 }.create(&db).await.unwrap();
+        Post { id: None, title: "Go".to_string(), body: None, views: 1 }.create(&db).await.unwrap();
+        User { id: None, name: "rust fan".to_string() }.create(&db).await.unwrap();
+
+        let hits = across(&db, [Post::search_cfg(), User::search_cfg()], "rust").await.unwrap();
+
+        assert_eq!(hits.len(), 2);
+        assert!(hits[0].score >= hits[1].score);
+        assert!(hits.iter().any(|h| h.table == "post" && matches!(&h.record, Hit::Post(p) if p.title == "Rust")));
+        assert!(hits.iter().any(|h| h.table == "user" && matches!(&h.record, Hit::User(u) if u.name == "rust fan")));
+    }
+
+    #[tokio::test]
+    async fn limit() {
+        let db = db().await;
+
+        for i in 0..5 {
+            User { id: None, name: format!("rust {i}") }.create(&db).await.unwrap();
+        }
+
+        let hits = across(&db, [User::search_cfg::<Hit>().limit(2)], "rust").await.unwrap();
+
+        assert_eq!(hits.len(), 2);
+    }
+}
@@ -0,0 +1,16 @@
+use std::path::PathBuf;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum SeedError {
+    #[error("Seed file `{0}` has no known extension, use `.json` or `.toml`")]
+    UnknownFormat(PathBuf),
+    #[error("Seed file could not be parsed: {0}")]
+    Parse(String),
+    #[error("Seed record {index} of table `{table}` is invalid: {error}")]
+    InvalidRecord { table: String, index: usize, error: String },
+    #[error("{0}")]
+    Io(#[from] std::io::Error),
+    #[error("{0}")]
+    Db(#[from] surrealdb::Error),
+}
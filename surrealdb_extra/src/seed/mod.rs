@@ -0,0 +1,275 @@
+//! Seed data from JSON and TOML files
+//!
+//! A JSON seed file is an array of records, a TOML seed file has the records in a `[[records]]` array.
+//! Every record is deserialized into the `Table` struct and checked with `validate_record` before anything is written,
+//! ids are written as strings, either the id part (`"john"`) or the whole record id (`"user:john"`).
+//!
+//! `SeedSet` collects the records of several tables and inserts all of them in one transaction,
+//! so a broken file leaves the database as it was.
+//!
+//! # Example
+//!
+//! ```rust
+//! use serde::{Serialize, Deserialize};
+//! use surrealdb::engine::any::connect;
+//! use surrealdb::sql::Thing as RecordId;
+//! use surrealdb_extra::seed::{SeedFormat, SeedSet};
+//! use surrealdb_extra::table::Table;
+//!
+//! #[derive(Table, Serialize, Deserialize, Clone)]
+//! #[table(name = "user")]
+//! struct User {
+//!     id: Option<RecordId>,
+//!     name: String
+//! }
+//!
+//! #[derive(Table, Serialize, Deserialize, Clone)]
+//! #[table(name = "post")]
+//! struct Post {
+//!     id: Option<RecordId>,
+//!     title: String
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let db = connect("mem://").await.unwrap();
+//!     db.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     // Usually `SeedSet::new().file::<User>("seed/users.json")`
+//!     let inserted = SeedSet::new()
+//!         .parse::<User>(r#"[{ "id": "john", "name": "John" }]"#, SeedFormat::Json).unwrap()
+//!         .parse::<Post>("[[records]]\nid = 1\ntitle = \"Hello\"", SeedFormat::Toml).unwrap()
+//!         .load(&db).await.unwrap();
+//!
+//!     assert_eq!(inserted, 2);
+//! }
+//! ```
+
+pub mod err;
+
+use std::path::Path;
+use anyhow::Result;
+use surrealdb::{Connection, Surreal};
+use surrealdb::sql::{Id, Thing, Value};
+use crate::table::{Table, TableError};
+use crate::table::id::IntoTableId;
+use crate::table::write::write_content;
+pub use crate::seed::err::SeedError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeedFormat {
+    Json,
+    Toml,
+}
+
+impl SeedFormat {
+    /// From the extension of the file
+    pub fn from_path(path: &Path) -> Result<Self> {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("json") => Ok(Self::Json),
+            Some("toml") => Ok(Self::Toml),
+            _ => Err(SeedError::UnknownFormat(path.to_path_buf()).into()),
+        }
+    }
+}
+
+/// Reads and checks the records of a seed file, the format is taken from the extension
+pub fn read<T: Table>(path: impl AsRef<Path>) -> Result<Vec<T>> {
+    let path = path.as_ref();
+
+    read_as(path, SeedFormat::from_path(path)?)
+}
+
+pub fn read_as<T: Table>(path: impl AsRef<Path>, format: SeedFormat) -> Result<Vec<T>> {
+    let content = std::fs::read_to_string(path).map_err(SeedError::from)?;
+
+    parse(&content, format)
+}
+
+/// Parses and checks the records of a seed
+pub fn parse<T: Table>(content: &str, format: SeedFormat) -> Result<Vec<T>> {
+    let records = match format {
+        SeedFormat::Json => serde_json::from_str::<serde_json::Value>(content).map_err(|e| SeedError::Parse(e.to_string()))?,
+        SeedFormat::Toml => {
+            let mut table = toml::from_str::<toml::Table>(content).map_err(|e| SeedError::Parse(e.to_string()))?;
+            let records = table.remove("records").unwrap_or(toml::Value::Array(vec![]));
+
+            serde_json::to_value(records).map_err(|e| SeedError::Parse(e.to_string()))?
+        }
+    };
+
+    let serde_json::Value::Array(records) = records else {
+        return Err(SeedError::Parse("the records must be an array".to_string()).into());
+    };
+
+    records.into_iter().enumerate().map(|(index, record)| {
+        let invalid = |error: String| SeedError::InvalidRecord { table: T::TABLE_NAME.to_string(), index, error };
+
+        let mut record = surrealdb::sql::to_value(record).map_err(|e| invalid(e.to_string()))?;
+
+        if let Value::Object(object) = &mut record {
+            if let Some(id) = object.remove("id") {
+                object.insert("id".to_string(), Value::Thing(record_id::<T>(id).map_err(|e| invalid(e.to_string()))?));
+            }
+        }
+
+        let t: T = surrealdb::sql::from_value(record).map_err(|e| invalid(e.to_string()))?;
+
+        t.validate_record().map_err(|e| invalid(e.to_string()))?;
+
+        Ok(t)
+    }).collect()
+}
+
+/// The record id of a seed id, strings with the table name are parsed as record ids
+fn record_id<T: Table>(id: Value) -> Result<Thing> {
+    match id {
+        Value::Strand(id) if id.0.contains(':') => {
+            let thing = surrealdb::sql::thing(&id.0).map_err(|e| TableError::Db(e.into()))?;
+
+            IntoTableId::<T>::into_table_id(thing)
+        }
+        Value::Strand(id) => Ok(T::create_record_id(id.0)),
+        Value::Number(n) => Ok(T::create_record_id(Id::from(n.as_int()))),
+        Value::Thing(thing) => IntoTableId::<T>::into_table_id(thing),
+        id => Err(SeedError::Parse(format!("`{id}` is not a record id")).into()),
+    }
+}
+
+/// Reads the file and inserts its records in one transaction, returns the number of inserted records
+pub async fn load_from_json<T: Table, C: Connection>(db: &Surreal<C>, path: impl AsRef<Path>) -> Result<usize> {
+    SeedSet::new().records(read_as::<T>(path, SeedFormat::Json)?)?.load(db).await
+}
+
+/// Reads the file and inserts its records in one transaction, returns the number of inserted records
+pub async fn load_from_toml<T: Table, C: Connection>(db: &Surreal<C>, path: impl AsRef<Path>) -> Result<usize> {
+    SeedSet::new().records(read_as::<T>(path, SeedFormat::Toml)?)?.load(db).await
+}
+
+/// Records of several tables that are inserted together
+#[derive(Debug, Clone, Default)]
+pub struct SeedSet {
+    /// The table and the records, in the order they are inserted
+    pub tables: Vec<(String, Vec<Value>)>,
+}
+
+impl SeedSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reads the records of a `.json` or `.toml` file
+    pub fn file<T: Table>(self, path: impl AsRef<Path>) -> Result<Self> {
+        self.records(read::<T>(path)?)
+    }
+
+    pub fn parse<T: Table>(self, content: &str, format: SeedFormat) -> Result<Self> {
+        self.records(parse::<T>(content, format)?)
+    }
+
+    /// Adds records that are already built, they are checked like the records of a file
+    pub fn records<T: Table>(mut self, records: Vec<T>) -> Result<Self> {
+        let records = records.into_iter().map(|t| {
+            t.validate_record().map_err(TableError::from)?;
+
+            write_content(t)
+        }).collect::<Result<Vec<Value>>>()?;
+
+        self.tables.push((T::TABLE_NAME.to_string(), records));
+
+        Ok(self)
+    }
+
+    /// The number of records of all tables
+    pub fn len(&self) -> usize {
+        self.tables.iter().map(|(_, records)| records.len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Inserts every record in one transaction, returns the number of inserted records
+    pub async fn load<C: Connection>(self, db: &Surreal<C>) -> Result<usize> {
+        if self.is_empty() {
+            return Ok(0);
+        }
+
+        let len = self.len();
+
+        let inserts: Vec<String> = self.tables.iter().enumerate()
+            .map(|(i, (table, _))| format!("INSERT INTO {} $seed_{i};", surrealdb::sql::Table::from(table.as_str())))
+            .collect();
+
+        let mut query = db.query(format!("BEGIN TRANSACTION;\n{}\nCOMMIT TRANSACTION;", inserts.join("\n")));
+
+        for (i, (_, records)) in self.tables.into_iter().enumerate() {
+            query = query.bind((format!("seed_{i}"), Value::from(records)));
+        }
+
+        query.await.map_err(SeedError::from)?.check().map_err(SeedError::from)?;
+
+        Ok(len)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde::{Deserialize, Serialize};
+    use surrealdb::sql::Thing as RecordId;
+    use crate::testing::db;
+    use super::*;
+
+    #[derive(Debug, Table, Serialize, Deserialize, Clone, PartialEq)]
+    #[table(name = "user")]
+    struct User {
+        id: Option<RecordId>,
+        name: String,
+    }
+
+    #[derive(Debug, Table, Serialize, Deserialize, Clone, PartialEq)]
+    #[table(name = "post")]
+    struct Post {
+        id: Option<RecordId>,
+        title: String,
+    }
+
+    #[test]
+    fn parse_records() {
+        let users = parse::<User>(r#"[{ "id": "john", "name": "John" }, { "id": "user:jane", "name": "Jane" }, { "name": "No id" }]"#, SeedFormat::Json).unwrap();
+
+        assert_eq!(users.iter().map(|u| u.id.clone().map(|id| id.to_raw())).collect::<Vec<_>>(), [Some("user:john".to_string()), Some("user:jane".to_string()), None]);
+
+        let posts = parse::<Post>("[[records]]\nid = 1\ntitle = \"One\"", SeedFormat::Toml).unwrap();
+
+        assert_eq!(posts[0].id, Some(RecordId::from(("post", Id::from(1)))));
+        assert_eq!(posts[0].title, "One");
+
+        assert!(parse::<User>(r#"[{ "id": "post:john", "name": "John" }]"#, SeedFormat::Json).is_err());
+        assert!(parse::<User>(r#"[{ "id": "john" }]"#, SeedFormat::Json).is_err());
+        assert!(parse::<User>(r#"{ "name": "John" }"#, SeedFormat::Json).is_err());
+        assert!(SeedFormat::from_path(Path::new("seed.yaml")).is_err());
+    }
+
+    #[tokio::test]
+    async fn load() {
+        let db = db().await;
+
+        let seed = SeedSet::new()
+            .parse::<User>(r#"[{ "id": "john", "name": "John" }]"#, SeedFormat::Json).unwrap()
+            .parse::<Post>(r#"[{ "title": "One" }, { "title": "Two" }]"#, SeedFormat::Json).unwrap();
+
+        assert_eq!(seed.len(), 3);
+        assert_eq!(seed.load(&db).await.unwrap(), 3);
+
+        assert_eq!(User::get_all(&db).await.unwrap().len(), 1);
+        assert_eq!(Post::get_all(&db).await.unwrap().len(), 2);
+
+        let existing = SeedSet::new()
+            .parse::<Post>(r#"[{ "title": "Three" }]"#, SeedFormat::Json).unwrap()
+            .parse::<User>(r#"[{ "id": "john", "name": "John" }]"#, SeedFormat::Json).unwrap();
+
+        assert!(existing.load(&db).await.is_err());
+        assert_eq!(Post::get_all(&db).await.unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,9 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ShardError {
+    #[error("No shards are added to the router")]
+    NoShards,
+    #[error("{0}")]
+    Db(#[from] surrealdb::Error),
+}
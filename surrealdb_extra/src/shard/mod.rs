@@ -0,0 +1,323 @@
+//! Record to shard assignment with consistent hashing
+//!
+//! `Router` holds a named connection for every shard and assigns every record id to one of them on a hash ring.
+//! Adding or removing a shard only moves the records of the neighbouring ring segments.
+//! The hash is FNV-1a over `table:id` with a murmur3 finalizer, so the assignment is the same across processes and versions.
+//!
+//! # Example
+//!
+//! ```rust
+//! use serde::{Serialize, Deserialize};
+//! use surrealdb::engine::any::connect;
+//! use surrealdb::sql::Thing as RecordId;
+//! use surrealdb_extra::table::Table;
+//! use surrealdb_extra::shard::Router;
+//!
+//! #[derive(Table, Serialize, Deserialize, Clone)]
+//! #[table(name = "user")]
+//! struct User {
+//!     id: Option<RecordId>,
+//!     name: String
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let eu = connect("mem://").await.unwrap();
+//!     eu.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     let us = connect("mem://").await.unwrap();
+//!     us.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     let router = Router::new().shard("eu", eu).shard("us", us);
+//!
+//!     let user = router.create(User { id: None, name: "name".to_string() }).await.unwrap().unwrap();
+//!     let id = user.id.unwrap();
+//!
+//!     let shard = router.shard_for(&id).unwrap();
+//!
+//!     let users: Vec<User> = router.get_by_ids([id]).await.unwrap();
+//! }
+//! ```
+
+pub mod err;
+
+use std::collections::{BTreeMap, HashMap};
+use anyhow::Result;
+use futures::future::try_join_all;
+use surrealdb::sql::{Id, Thing, Value};
+use surrealdb::{Connection, Surreal};
+use crate::table::{fetch, Table, TableError};
+use crate::table::id::{self, IdStrategy, IntoTableId, SEQUENCE_TABLE};
+pub use crate::shard::err::ShardError;
+
+/// Points on the ring per shard
+pub const DEFAULT_VIRTUAL_NODES: usize = 128;
+
+#[derive(Debug, Clone)]
+pub struct Router<C: Connection> {
+    shards: BTreeMap<String, Surreal<C>>,
+    ring: BTreeMap<u64, String>,
+    virtual_nodes: usize,
+}
+
+impl<C: Connection> Default for Router<C> {
+    fn default() -> Self {
+        Self {
+            shards: BTreeMap::new(),
+            ring: BTreeMap::new(),
+            virtual_nodes: DEFAULT_VIRTUAL_NODES,
+        }
+    }
+}
+
+impl<C: Connection> Router<C> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// More virtual nodes spread the records more evenly
+    pub fn virtual_nodes(mut self, virtual_nodes: usize) -> Self {
+        self.virtual_nodes = virtual_nodes.max(1);
+        self.rebuild();
+
+        self
+    }
+
+    /// Adds a shard, a shard with the same name is replaced
+    pub fn shard(mut self, name: impl Into<String>, db: Surreal<C>) -> Self {
+        self.shards.insert(name.into(), db);
+        self.rebuild();
+
+        self
+    }
+
+    pub fn remove_shard(&mut self, name: &str) -> Option<Surreal<C>> {
+        let db = self.shards.remove(name);
+        self.rebuild();
+
+        db
+    }
+
+    pub fn shards(&self) -> impl Iterator<Item = (&str, &Surreal<C>)> {
+        self.shards.iter().map(|(name, db)| (name.as_str(), db))
+    }
+
+    /// Name of the shard the record belongs to
+    pub fn shard_for(&self, id: &Thing) -> Result<&str> {
+        let hash = hash(id.to_string().as_bytes());
+
+        self.ring.range(hash..)
+            .chain(self.ring.iter())
+            .next()
+            .map(|(_, name)| name.as_str())
+            .ok_or(ShardError::NoShards.into())
+    }
+
+    /// Connection of the shard the record belongs to
+    pub fn db_for(&self, id: &Thing) -> Result<&Surreal<C>> {
+        let name = self.shard_for(id)?;
+
+        self.shards.get(name).ok_or(ShardError::NoShards.into())
+    }
+
+    /// Records without an id get one from the id strategy of the table so they can be routed, a random one for `IdStrategy::Server`.
+    /// The sequence of `IdStrategy::Incremental` is kept on the shard of the sequence record, so every shard counts on the same one
+    pub async fn create<T: Table>(&self, mut t: T) -> Result<Option<T>> {
+        if t.get_id().is_none() {
+            let id = match T::ID_STRATEGY {
+                IdStrategy::Server => Some(Id::rand()),
+                IdStrategy::Incremental => {
+                    let sequence = Thing::from((SEQUENCE_TABLE, T::TABLE_NAME));
+
+                    id::next_id::<T, C>(self.db_for(&sequence)?).await?
+                }
+                strategy => strategy.generate(),
+            };
+
+            t.set_id(id.ok_or(TableError::IdEmpty)?);
+        }
+
+        let id = t.get_id().clone().ok_or(TableError::IdEmpty)?;
+
+        t.create(self.db_for(&id)?).await
+    }
+
+    pub async fn update<T: Table>(&self, t: T) -> Result<Option<T>> {
+        let id = t.get_id().clone().ok_or(TableError::IdEmpty)?;
+
+        t.update(self.db_for(&id)?).await
+    }
+
+    pub async fn delete<T: Table>(&self, id: impl IntoTableId<T> + Send) -> Result<Option<T>> {
+        let id = id.into_table_id()?;
+
+        T::delete(self.db_for(&id)?, id).await
+    }
+
+    pub async fn get_by_id<T: Table>(&self, id: impl IntoTableId<T> + Send) -> Result<Option<T>> {
+        let id = id.into_table_id()?;
+
+        T::get_by_id(self.db_for(&id)?, id).await
+    }
+
+    /// Selects the records from all shards at the same time, records that don't exist are left out
+    pub async fn get_by_ids<T: Table>(&self, ids: impl IntoIterator<Item = impl IntoTableId<T>>) -> Result<Vec<T>> {
+        let mut by_shard: HashMap<&str, Vec<Value>> = HashMap::new();
+
+        for id in ids {
+            let id = id.into_table_id()?;
+
+            by_shard.entry(self.shard_for(&id)?).or_default().push(Value::Thing(id));
+        }
+
+        let selects = by_shard.into_iter().map(|(name, ids)| async move {
+            let db = self.shards.get(name).ok_or(ShardError::NoShards)?;
+
+            let statement = fetch::select(ids, false, T::FETCH_FIELDS);
+
+            let records: Vec<T> = db.query(statement).await?.take(0)?;
+
+            Ok::<_, ShardError>(records)
+        });
+
+        let records = try_join_all(selects).await?;
+
+        Ok(records.into_iter().flatten().collect())
+    }
+
+    fn rebuild(&mut self) {
+        self.ring.clear();
+
+        for name in self.shards.keys() {
+            for node in 0..self.virtual_nodes {
+                self.ring.insert(hash(format!("{name}#{node}").as_bytes()), name.clone());
+            }
+        }
+    }
+}
+
+/// 64 bit FNV-1a with the murmur3 finalizer, FNV alone clusters similar keys on the ring
+fn hash(bytes: &[u8]) -> u64 {
+    const OFFSET: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+
+    let mut hash = bytes.iter().fold(OFFSET, |hash, byte| (hash ^ *byte as u64).wrapping_mul(PRIME));
+
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(0xff51afd7ed558ccd);
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(0xc4ceb9fe1a85ec53);
+    hash ^= hash >> 33;
+
+    hash
+}
+
+#[cfg(test)]
+mod test {
+    use serde::{Deserialize, Serialize};
+    use surrealdb::engine::any::Any;
+    use surrealdb::sql::Thing as RecordId;
+    use crate::testing::db;
+    use super::*;
+
+    #[derive(Debug, Table, Serialize, Deserialize, Clone, PartialEq)]
+    #[table(name = "user")]
+    struct User {
+        id: Option<RecordId>,
+        name: String,
+    }
+
+    async fn router() -> Router<Any> {
+        Router::new()
+            .shard("a", db().await)
+            .shard("b", db().await)
+            .shard("c", db().await)
+    }
+
+    #[tokio::test]
+    async fn no_shards() {
+        let router: Router<Any> = Router::new();
+
+        assert!(router.shard_for(&RecordId::from(("user", "a"))).is_err());
+    }
+
+    #[tokio::test]
+    async fn stable_assignment() {
+        let router = router().await;
+
+        let mut moved = 0;
+        let mut counts: HashMap<String, usize> = HashMap::new();
+
+        let mut smaller = router.clone();
+        smaller.remove_shard("c");
+
+        for i in 0..300 {
+            let id = RecordId::from(("user", i.to_string().as_str()));
+            let shard = router.shard_for(&id).unwrap().to_string();
+
+            assert_eq!(router.shard_for(&id).unwrap(), shard);
+
+            if shard != "c" && smaller.shard_for(&id).unwrap() != shard {
+                moved += 1;
+            }
+
+            *counts.entry(shard).or_default() += 1;
+        }
+
+        // Only the records of the removed shard move
+        assert_eq!(moved, 0);
+        assert_eq!(counts.len(), 3);
+        assert!(counts.values().all(|c| *c > 50));
+    }
+
+    #[tokio::test]
+    async fn scatter_gather() {
+        let router = router().await;
+
+        let mut ids = vec![];
+
+        for i in 0..20 {
+            let user = router.create(User { id: None, name: i.to_string() }).await.unwrap().unwrap();
+            let id = user.id.unwrap();
+
+            let on_shard = User::get_by_id(router.db_for(&id).unwrap(), id.clone()).await.unwrap();
+            assert!(on_shard.is_some());
+
+            ids.push(id);
+        }
+
+        let users: Vec<User> = router.get_by_ids(ids.clone()).await.unwrap();
+        assert_eq!(users.len(), 20);
+
+        router.delete::<User>(ids[0].clone()).await.unwrap();
+        assert!(router.get_by_id::<User>(ids[0].clone()).await.unwrap().is_none());
+    }
+
+    #[derive(Debug, Table, Serialize, Deserialize, Clone, PartialEq)]
+    #[table(name = "invoice", id = "incremental")]
+    struct Invoice {
+        id: Option<RecordId>,
+    }
+
+    #[derive(Debug, Table, Serialize, Deserialize, Clone, PartialEq)]
+    #[table(name = "order", id = "ulid")]
+    struct Order {
+        id: Option<RecordId>,
+    }
+
+    #[tokio::test]
+    async fn id_strategy() {
+        let router = router().await;
+
+        for n in 1..=5 {
+            let invoice = router.create(Invoice { id: None }).await.unwrap().unwrap();
+
+            assert_eq!(invoice.id.unwrap().id, Id::from(n));
+        }
+
+        let order = router.create(Order { id: None }).await.unwrap().unwrap();
+        let Id::String(ulid) = order.id.unwrap().id else { panic!() };
+
+        assert_eq!(ulid.len(), 26);
+    }
+}
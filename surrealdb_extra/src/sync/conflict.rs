@@ -0,0 +1,110 @@
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
+use surrealdb::sql::{Idiom, Value};
+use crate::query::parsing::idiom::ExtraIdiom;
+
+pub type ConflictCallback = Arc<dyn Fn(&Value, &Value) -> Value + Send + Sync>;
+
+/// Decides what is written to the remote when a queued change hits a record that already exists there
+#[derive(Clone)]
+pub enum ConflictStrategy {
+    /// Keeps the side with the greatest value in the given field, local wins on a tie
+    LastWriteWins(Idiom),
+    /// Takes the remote record and overwrites it with every field of the local one
+    FieldMerge,
+    /// Called with `(local, remote)` and returns the record to write
+    Callback(ConflictCallback),
+}
+
+impl ConflictStrategy {
+    pub fn last_write_wins(field: impl Into<ExtraIdiom>) -> Self {
+        Self::LastWriteWins(field.into().0)
+    }
+
+    pub fn callback(f: impl Fn(&Value, &Value) -> Value + Send + Sync + 'static) -> Self {
+        Self::Callback(Arc::new(f))
+    }
+
+    pub fn resolve(&self, local: &Value, remote: &Value) -> Value {
+        match self {
+            Self::LastWriteWins(field) => {
+                if remote.pick(field) > local.pick(field) {
+                    return remote.clone();
+                }
+
+                local.clone()
+            }
+            Self::FieldMerge => {
+                let (Value::Object(local), Value::Object(remote)) = (local, remote) else {
+                    return local.clone();
+                };
+
+                let mut merged = remote.clone();
+                for (k, v) in local.iter() {
+                    merged.insert(k.clone(), v.clone());
+                }
+
+                Value::Object(merged)
+            }
+            Self::Callback(f) => f(local, remote),
+        }
+    }
+}
+
+impl Debug for ConflictStrategy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::LastWriteWins(field) => f.debug_tuple("LastWriteWins").field(field).finish(),
+            Self::FieldMerge => f.write_str("FieldMerge"),
+            Self::Callback(_) => f.write_str("Callback"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use surrealdb::sql::{Datetime, Object, Value};
+    use super::*;
+
+    fn object(fields: Vec<(&str, Value)>) -> Value {
+        let mut obj = Object::default();
+        for (k, v) in fields {
+            obj.insert(k.to_string(), v);
+        }
+
+        Value::Object(obj)
+    }
+
+    #[test]
+    fn last_write_wins_remote_newer() {
+        let local = object(vec![("name", "local".into()), ("at", Value::Datetime(Datetime::from(chrono::DateTime::from_timestamp(1, 0).unwrap())))]);
+        let remote = object(vec![("name", "remote".into()), ("at", Value::Datetime(Datetime::from(chrono::DateTime::from_timestamp(2, 0).unwrap())))]);
+
+        assert_eq!(ConflictStrategy::last_write_wins("at").resolve(&local, &remote), remote);
+    }
+
+    #[test]
+    fn last_write_wins_tie() {
+        let local = object(vec![("name", "local".into())]);
+        let remote = object(vec![("name", "remote".into())]);
+
+        assert_eq!(ConflictStrategy::last_write_wins("at").resolve(&local, &remote), local);
+    }
+
+    #[test]
+    fn field_merge() {
+        let local = object(vec![("name", "local".into())]);
+        let remote = object(vec![("name", "remote".into()), ("n", 5.into())]);
+
+        let merged = ConflictStrategy::FieldMerge.resolve(&local, &remote);
+
+        assert_eq!(merged, object(vec![("name", "local".into()), ("n", 5.into())]));
+    }
+
+    #[test]
+    fn callback() {
+        let strategy = ConflictStrategy::callback(|_, remote| remote.clone());
+
+        assert_eq!(strategy.resolve(&Value::from(1), &Value::from(2)), Value::from(2));
+    }
+}
@@ -0,0 +1,11 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum SyncError {
+    #[error("Queued operation is malformed: {0}")]
+    MalformedOperation(String),
+    #[error("Table name `{0}` is not a valid identifier")]
+    InvalidTable(String),
+    #[error("{0}")]
+    Db(#[from] surrealdb::Error),
+}
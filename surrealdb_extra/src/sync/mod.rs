@@ -0,0 +1,395 @@
+//! Offline first sync between a local (embedded) database and a remote one
+//!
+//! Mutations done through the `SyncEngine` are written to the local database and queued in the same transaction.
+//! `push` replays the queue on the remote and resolves conflicts with the configured `ConflictStrategy`,
+//! `pull` reads the remote change feeds (`SHOW CHANGES`) and applies them locally.
+//!
+//! The version of every record that was last pushed or pulled is kept in `SYNCED_TABLE`, a remote record is only a conflict
+//! when it changed since then, so the records this engine pushed itself are overwritten without resolving.
+//!
+//! The synced tables must be defined with a change feed on the remote, e.g. `DEFINE TABLE task CHANGEFEED 7d`
+//!
+//! # Example
+//!
+//! ```rust
+//! use surrealdb::engine::any::connect;
+//! use surrealdb::sql::Thing;
+//! use surrealdb_extra::sync::{ConflictStrategy, SyncEngine};
+//!
+//! #[derive(serde::Serialize)]
+//! struct Task {
+//!     name: String
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let local = connect("mem://").await.unwrap();
+//!     local.use_ns("ns").use_db("db").await.unwrap();
+//!     let remote = connect("mem://").await.unwrap();
+//!     remote.use_ns("ns").use_db("db").await.unwrap();
+//!     remote.query("DEFINE TABLE task CHANGEFEED 1d").await.unwrap();
+//!
+//!     let sync = SyncEngine::new(&local, &remote, ConflictStrategy::FieldMerge).table("task");
+//!
+//!     sync.create(Thing::from(("task", "1")), Task { name: "offline".to_string() }).await.unwrap();
+//!
+//!     let report = sync.sync().await.unwrap();
+//! }
+//! ```
+
+pub mod err;
+pub mod conflict;
+pub mod queue;
+
+use anyhow::Result;
+use serde::Serialize;
+use surrealdb::sql::{to_value, Part, Thing, Value};
+use surrealdb::{Connection, Surreal};
+pub use crate::sync::conflict::ConflictStrategy;
+pub use crate::sync::err::SyncError;
+pub use crate::sync::queue::{QueuedOp, SyncAction, QUEUE_TABLE};
+
+pub const STATE_TABLE: &str = "_sync_state";
+/// The version of every record this engine last pushed to or pulled from the remote
+pub const SYNCED_TABLE: &str = "_sync_synced";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SyncReport {
+    pub pushed: usize,
+    pub conflicts: usize,
+    pub pulled: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct SyncEngine<'r, L, R>
+    where L: Connection, R: Connection
+{
+    pub(crate) local: &'r Surreal<L>,
+    pub(crate) remote: &'r Surreal<R>,
+    pub(crate) strategy: ConflictStrategy,
+    pub(crate) tables: Vec<String>,
+}
+
+impl<'r, L, R> SyncEngine<'r, L, R>
+    where L: Connection, R: Connection
+{
+    pub fn new(local: &'r Surreal<L>, remote: &'r Surreal<R>, strategy: ConflictStrategy) -> Self {
+        Self {
+            local,
+            remote,
+            strategy,
+            tables: vec![],
+        }
+    }
+
+    /// Adds a table that is pulled from the remote change feed
+    pub fn table(mut self, table: impl Into<String>) -> Self {
+        self.tables.push(table.into());
+
+        self
+    }
+
+    pub async fn create(&self, record: Thing, content: impl Serialize + 'static) -> Result<()> {
+        let content = to_value(content)?;
+
+        self.write_and_enqueue("CREATE $record CONTENT $content; LET $queued = $content", record, SyncAction::Create, content).await
+    }
+
+    /// Merges the content into the local record and queues the full record for the remote
+    pub async fn update(&self, record: Thing, content: impl Serialize + 'static) -> Result<()> {
+        let content = to_value(content)?;
+
+        self.write_and_enqueue("LET $queued = (UPDATE ONLY $record MERGE $content)", record, SyncAction::Update, content).await
+    }
+
+    pub async fn delete(&self, record: Thing) -> Result<()> {
+        self.write_and_enqueue("DELETE $record; LET $queued = NONE", record, SyncAction::Delete, Value::None).await
+    }
+
+    /// Runs the local write and queues `$queued` in one transaction, so a failed write is not queued and the other way around
+    async fn write_and_enqueue(&self, write: &str, record: Thing, action: SyncAction, content: Value) -> Result<()> {
+        self.local.query(format!("BEGIN TRANSACTION; {write}; {}; COMMIT TRANSACTION;", queue::enqueue_statement()))
+            .bind(("record", record))
+            .bind(("action", action.as_str()))
+            .bind(("content", content))
+            .await.map_err(SyncError::from)?
+            .check().map_err(SyncError::from)?;
+
+        Ok(())
+    }
+
+    /// Pushes the queue and then pulls the remote changes
+    pub async fn sync(&self) -> Result<SyncReport> {
+        let mut report = self.push().await?;
+        report.pulled = self.pull().await?;
+
+        Ok(report)
+    }
+
+    /// Replays every queued operation on the remote in order
+    ///
+    /// An operation is only removed from the queue once the remote accepted it, so a failed push can be retried later
+    pub async fn push(&self) -> Result<SyncReport> {
+        let mut report = SyncReport::default();
+
+        for op in queue::pending(self.local).await? {
+            match op.action {
+                SyncAction::Delete => {
+                    self.remote.query("DELETE $record")
+                        .bind(("record", op.record.clone()))
+                        .await.map_err(SyncError::from)?
+                        .check().map_err(SyncError::from)?;
+
+                    self.set_synced(&op.record, Value::None).await?;
+                }
+                SyncAction::Create | SyncAction::Update => {
+                    let mut res = self.remote.query("SELECT * FROM ONLY $record")
+                        .bind(("record", op.record.clone()))
+                        .await.map_err(SyncError::from)?;
+
+                    let remote: surrealdb::Value = res.take(0).map_err(SyncError::from)?;
+                    let remote = remote.into_inner();
+
+                    // Unchanged since this engine pushed or pulled it
+                    let content = if remote.is_none_or_null() || remote == self.synced(&op.record).await? {
+                        op.content.clone()
+                    } else {
+                        report.conflicts += 1;
+                        self.strategy.resolve(&op.content, &remote)
+                    };
+
+                    let mut res = self.remote.query("UPSERT ONLY $record CONTENT $content")
+                        .bind(("record", op.record.clone()))
+                        .bind(("content", content))
+                        .await.map_err(SyncError::from)?;
+
+                    let pushed: surrealdb::Value = res.take(0).map_err(SyncError::from)?;
+
+                    self.set_synced(&op.record, pushed.into_inner()).await?;
+                }
+            }
+
+            queue::ack(self.local, &op).await?;
+            report.pushed += 1;
+        }
+
+        Ok(report)
+    }
+
+    /// Applies the remote change feed of every registered table on the local database
+    ///
+    /// Records that still have queued operations are skipped, those are resolved on the next push
+    pub async fn pull(&self) -> Result<usize> {
+        let queued: Vec<Thing> = queue::pending(self.local).await?.into_iter().map(|op| op.record).collect();
+
+        let mut pulled = 0;
+        for table in &self.tables {
+            if !table.chars().all(|c| c.is_alphanumeric() || c == '_') {
+                return Err(SyncError::InvalidTable(table.clone()).into());
+            }
+
+            let since = self.versionstamp(table).await?;
+
+            let mut res = self.remote.query(format!("SHOW CHANGES FOR TABLE {table} SINCE {since}"))
+                .await.map_err(SyncError::from)?;
+
+            let changesets: surrealdb::Value = res.take(0).map_err(SyncError::from)?;
+            let Value::Array(changesets) = changesets.into_inner() else {
+                continue;
+            };
+
+            let mut last = None;
+            for changeset in changesets.0 {
+                if let Value::Number(vs) = changeset.pick(&[Part::from("versionstamp")]) {
+                    last = Some(vs.as_int());
+                }
+
+                let Value::Array(changes) = changeset.pick(&[Part::from("changes")]) else {
+                    continue;
+                };
+
+                for change in changes.0 {
+                    let (query, value) = match (change.pick(&[Part::from("update")]), change.pick(&[Part::from("delete")])) {
+                        (v @ Value::Object(_), _) => ("UPSERT $record CONTENT $content", v),
+                        (_, v @ Value::Object(_)) => ("DELETE $record", v),
+                        _ => continue,
+                    };
+
+                    let Value::Thing(record) = value.pick(&[Part::from("id")]) else {
+                        continue;
+                    };
+
+                    if queued.contains(&record) {
+                        continue;
+                    }
+
+                    let synced = match query.starts_with("DELETE") {
+                        true => Value::None,
+                        false => value.clone(),
+                    };
+
+                    self.local.query(query)
+                        .bind(("record", record.clone()))
+                        .bind(("content", value))
+                        .await.map_err(SyncError::from)?
+                        .check().map_err(SyncError::from)?;
+
+                    self.set_synced(&record, synced).await?;
+
+                    pulled += 1;
+                }
+            }
+
+            if let Some(last) = last {
+                self.local.query(format!("UPSERT type::thing('{STATE_TABLE}', $table) SET versionstamp = $vs"))
+                    .bind(("table", table.clone()))
+                    .bind(("vs", last + 1))
+                    .await.map_err(SyncError::from)?
+                    .check().map_err(SyncError::from)?;
+            }
+        }
+
+        Ok(pulled)
+    }
+
+    /// The version of the record that was last pushed or pulled
+    async fn synced(&self, record: &Thing) -> Result<Value> {
+        let mut res = self.local.query(format!("SELECT VALUE version FROM ONLY type::thing('{SYNCED_TABLE}', [$record])"))
+            .bind(("record", record.clone()))
+            .await.map_err(SyncError::from)?;
+
+        let version: surrealdb::Value = res.take(0).map_err(SyncError::from)?;
+
+        Ok(version.into_inner())
+    }
+
+    async fn set_synced(&self, record: &Thing, version: Value) -> Result<()> {
+        self.local.query(format!("UPSERT type::thing('{SYNCED_TABLE}', [$record]) SET version = $version"))
+            .bind(("record", record.clone()))
+            .bind(("version", version))
+            .await.map_err(SyncError::from)?
+            .check().map_err(SyncError::from)?;
+
+        Ok(())
+    }
+
+    async fn versionstamp(&self, table: &str) -> Result<i64> {
+        let mut res = self.local.query(format!("SELECT VALUE versionstamp FROM ONLY type::thing('{STATE_TABLE}', $table)"))
+            .bind(("table", table.to_string()))
+            .await.map_err(SyncError::from)?;
+
+        let vs: Option<i64> = res.take(0).map_err(SyncError::from)?;
+
+        Ok(vs.unwrap_or(0))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde::Deserialize;
+    use crate::testing::db;
+    use super::*;
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct Task {
+        name: String,
+        done: bool,
+    }
+
+    #[tokio::test]
+    async fn queue_while_offline() {
+        let local = db().await;
+        let remote = db().await;
+
+        let sync = SyncEngine::new(&local, &remote, ConflictStrategy::FieldMerge);
+
+        sync.create(Thing::from(("task", "1")), Task { name: "a".to_string(), done: false }).await.unwrap();
+        sync.update(Thing::from(("task", "1")), Task { name: "b".to_string(), done: true }).await.unwrap();
+
+        let pending = queue::pending(&local).await.unwrap();
+
+        assert_eq!(pending.len(), 2);
+        assert_eq!(pending[0].action, SyncAction::Create);
+        assert_eq!(pending[1].action, SyncAction::Update);
+    }
+
+    #[tokio::test]
+    async fn push_replays_and_resolves() {
+        let local = db().await;
+        let remote = db().await;
+
+        remote.query("CREATE type::thing('task', '2') SET name = 'remote', done = false, extra = 1").await.unwrap();
+
+        let sync = SyncEngine::new(&local, &remote, ConflictStrategy::FieldMerge);
+
+        sync.create(Thing::from(("task", "1")), Task { name: "a".to_string(), done: false }).await.unwrap();
+        sync.create(Thing::from(("task", "2")), Task { name: "local".to_string(), done: true }).await.unwrap();
+
+        let report = sync.push().await.unwrap();
+
+        assert_eq!(report.pushed, 2);
+        assert_eq!(report.conflicts, 1);
+        assert!(queue::pending(&local).await.unwrap().is_empty());
+
+        let mut res = remote.query("SELECT VALUE extra FROM ONLY type::thing('task', '2'); SELECT VALUE name FROM ONLY type::thing('task', '2')").await.unwrap();
+        let extra: Option<i64> = res.take(0).unwrap();
+        let name: Option<String> = res.take(1).unwrap();
+
+        assert_eq!(extra, Some(1));
+        assert_eq!(name, Some("local".to_string()));
+    }
+
+    #[tokio::test]
+    async fn own_pushes_are_not_conflicts() {
+        let local = db().await;
+        let remote = db().await;
+
+        let sync = SyncEngine::new(&local, &remote, ConflictStrategy::FieldMerge);
+        let record = Thing::from(("task", "1"));
+
+        sync.create(record.clone(), Task { name: "a".to_string(), done: false }).await.unwrap();
+        sync.update(record.clone(), Task { name: "b".to_string(), done: false }).await.unwrap();
+        assert_eq!(sync.push().await.unwrap().conflicts, 0);
+
+        sync.update(record.clone(), Task { name: "c".to_string(), done: true }).await.unwrap();
+        assert_eq!(sync.push().await.unwrap().conflicts, 0);
+
+        remote.query("UPDATE task:⟨1⟩ SET name = 'remote'").await.unwrap().check().unwrap();
+
+        sync.update(record.clone(), Task { name: "d".to_string(), done: true }).await.unwrap();
+        assert_eq!(sync.push().await.unwrap().conflicts, 1);
+    }
+
+    #[tokio::test]
+    async fn failed_write_is_not_queued() {
+        let local = db().await;
+        let remote = db().await;
+
+        let sync = SyncEngine::new(&local, &remote, ConflictStrategy::FieldMerge);
+
+        sync.create(Thing::from(("task", "1")), Task { name: "a".to_string(), done: false }).await.unwrap();
+        assert!(sync.create(Thing::from(("task", "1")), Task { name: "b".to_string(), done: false }).await.is_err());
+
+        assert_eq!(queue::pending(&local).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn pull_applies_change_feed() {
+        let local = db().await;
+        let remote = db().await;
+
+        remote.query("DEFINE TABLE task CHANGEFEED 1d; CREATE task:1 SET name = 'remote', done = false").await.unwrap();
+
+        let sync = SyncEngine::new(&local, &remote, ConflictStrategy::FieldMerge).table("task");
+
+        let pulled = sync.pull().await.unwrap();
+
+        assert_eq!(pulled, 1);
+
+        let task: Option<Task> = local.query("SELECT name, done FROM ONLY task:1").await.unwrap().take(0).unwrap();
+
+        assert_eq!(task, Some(Task { name: "remote".to_string(), done: false }));
+
+        assert_eq!(sync.pull().await.unwrap(), 0);
+    }
+}
@@ -0,0 +1,100 @@
+use anyhow::Result;
+use surrealdb::sql::{Thing, Value};
+use surrealdb::{Connection, Surreal};
+use crate::sync::err::SyncError;
+
+pub const QUEUE_TABLE: &str = "_sync_queue";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncAction {
+    Create,
+    Update,
+    Delete,
+}
+
+impl SyncAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Create => "create",
+            Self::Update => "update",
+            Self::Delete => "delete",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "create" => Some(Self::Create),
+            "update" => Some(Self::Update),
+            "delete" => Some(Self::Delete),
+            _ => None,
+        }
+    }
+}
+
+/// A local mutation waiting to be replayed on the remote
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueuedOp {
+    pub id: Thing,
+    pub record: Thing,
+    pub action: SyncAction,
+    pub content: Value,
+}
+
+impl QueuedOp {
+    fn from_value(value: Value) -> Result<Self> {
+        let Value::Object(mut obj) = value else {
+            return Err(SyncError::MalformedOperation(value.to_string()).into());
+        };
+
+        let malformed = |field: &str| SyncError::MalformedOperation(format!("missing `{field}`"));
+
+        let Some(Value::Thing(id)) = obj.remove("id") else {
+            return Err(malformed("id").into());
+        };
+        let Some(Value::Thing(record)) = obj.remove("record") else {
+            return Err(malformed("record").into());
+        };
+        let action = match obj.remove("action") {
+            Some(Value::Strand(s)) => SyncAction::parse(&s).ok_or_else(|| malformed("action"))?,
+            _ => return Err(malformed("action").into()),
+        };
+        let content = obj.remove("content").unwrap_or_default();
+
+        Ok(Self { id, record, action, content })
+    }
+}
+
+/// Queues `$queued` as the content of `$action` on `$record`
+pub(crate) fn enqueue_statement() -> String {
+    format!("CREATE {QUEUE_TABLE} CONTENT {{ record: $record, action: $action, content: $queued, at: time::now() }}")
+}
+
+pub async fn enqueue<C: Connection>(db: &Surreal<C>, record: Thing, action: SyncAction, content: Value) -> Result<()> {
+    db.query(enqueue_statement())
+        .bind(("record", record))
+        .bind(("action", action.as_str()))
+        .bind(("queued", content))
+        .await.map_err(SyncError::from)?
+        .check().map_err(SyncError::from)?;
+
+    Ok(())
+}
+
+/// Every queued operation, oldest first
+pub async fn pending<C: Connection>(db: &Surreal<C>) -> Result<Vec<QueuedOp>> {
+    let mut res = db.query(format!("SELECT * FROM {QUEUE_TABLE} ORDER BY at ASC")).await.map_err(SyncError::from)?;
+
+    let value: surrealdb::Value = res.take(0).map_err(SyncError::from)?;
+
+    let Value::Array(arr) = value.into_inner() else {
+        return Ok(vec![]);
+    };
+
+    arr.0.into_iter().map(QueuedOp::from_value).collect()
+}
+
+pub async fn ack<C: Connection>(db: &Surreal<C>, op: &QueuedOp) -> Result<()> {
+    db.query("DELETE $id").bind(("id", op.id.clone())).await.map_err(SyncError::from)?.check().map_err(SyncError::from)?;
+
+    Ok(())
+}
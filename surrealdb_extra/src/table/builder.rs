@@ -0,0 +1,146 @@
+//! Builders for new records
+//!
+//! The derive generates `<Struct>::builder()`, so a new record doesn't need `id: None` and every `None` spelled out.
+//! Fields that are not an `Option` are required, `build` and `create` only exist once all of them are set,
+//! a missing field is a compile error instead of a runtime error.
+//!
+//! - `Option<T>` fields are optional and their setter takes a `T`.
+//! - Computed fields and the version of a versioned table have no setter and are `Default::default()`.
+//! - `#[field(skip)]` and `#[field(readonly)]` fields are set like the others, `create` leaves them out of the payload.
+//! - `id` sets the id of the record in the table, without it the database generates one.
+//!
+//! # Example
+//!
+//! ```rust
+//! use serde::{Serialize, Deserialize};
+//! use surrealdb::engine::any::connect;
+//! use surrealdb::sql::Thing as RecordId;
+//! use surrealdb_extra::table::Table;
+//!
+//! #[derive(Table, Serialize, Deserialize, Clone)]
+//! #[table(name = "user")]
+//! struct User {
+//!     id: Option<RecordId>,
+//!     name: String,
+//!     age: i64,
+//!     nickname: Option<String>
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let db = connect("mem://").await.unwrap();
+//!     db.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     let user = User::builder()
+//!         .name("name")
+//!         .age(20)
+//!         .create(&db)
+//!         .await.unwrap().unwrap();
+//!
+//!     assert_eq!(user.nickname, None);
+//!
+//!     let user = User::builder().id("john").name("john").age(30).nickname("j").build();
+//!
+//!     assert_eq!(user.id.unwrap().to_string(), "user:john");
+//! }
+//! ```
+//!
+//! A required field that is not set doesn't compile:
+//!
+//! ```rust,compile_fail
+//! # use serde::{Serialize, Deserialize};
+//! # use surrealdb::sql::Thing as RecordId;
+//! # use surrealdb_extra::table::Table;
+//! #
+//! # #[derive(Table, Serialize, Deserialize, Clone)]
+//! # #[table(name = "user")]
+//! # struct User {
+//! #     id: Option<RecordId>,
+//! #     name: String,
+//! #     age: i64
+//! # }
+//! #
+//! let user = User::builder().name("name").build();
+//! ```
+
+/// State of a required field that is not set yet
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Missing;
+
+/// State of a required field that is set
+#[derive(Debug, Clone)]
+pub struct Set<T>(pub T);
+
+#[cfg(test)]
+mod test {
+    use serde::{Deserialize, Serialize};
+    use surrealdb::sql::Thing;
+    use crate::table::computed::Computed;
+    use crate::table::Table;
+    use crate::testing::db;
+
+    #[derive(Debug, Table, Serialize, Deserialize, Clone, PartialEq)]
+    #[table(name = "account", versioned)]
+    struct Account {
+        id: Option<Thing>,
+        owner: String,
+        balance: i64,
+        note: Option<String>,
+        #[field(readonly)]
+        #[serde(default)]
+        login_count: i64,
+        #[field(computed = "balance * 2")]
+        #[serde(default)]
+        doubled: Computed<i64>,
+        version: u64,
+    }
+
+    #[derive(Debug, Table, Serialize, Deserialize, Clone, PartialEq)]
+    #[table(name = "tag")]
+    struct Tag {
+        id: Option<Thing>,
+        label: Option<String>,
+    }
+
+    #[derive(Debug, Table, Serialize, Deserialize, Clone, PartialEq)]
+    #[table(name = "post")]
+    struct Post {
+        id: Option<Thing>,
+        #[field(readonly)]
+        owner: Thing,
+    }
+
+    #[test]
+    fn build() {
+        let account = Account::builder().balance(5).owner("john").note("n").login_count(3).build();
+
+        assert_eq!(account, Account {
+            id: None,
+            owner: "john".to_string(),
+            balance: 5,
+            note: Some("n".to_string()),
+            login_count: 3,
+            doubled: Computed::default(),
+            version: 0,
+        });
+
+        let tag = Tag::builder().id("rust").build();
+
+        assert_eq!(tag.id.unwrap().to_string(), "tag:rust");
+        assert_eq!(tag.label, None);
+
+        let post = Post::builder().owner(Thing::from(("user", "john"))).build();
+
+        assert_eq!(post.owner.to_string(), "user:john");
+    }
+
+    #[tokio::test]
+    async fn create() {
+        let db = db().await;
+
+        let account = Account::builder().owner("john").balance(5).login_count(3).create(&db).await.unwrap().unwrap();
+
+        assert!(account.id.is_some());
+        assert_eq!((account.owner.as_str(), account.balance, account.login_count, account.version), ("john", 5, 0, 0));
+    }
+}
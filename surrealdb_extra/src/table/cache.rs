@@ -0,0 +1,353 @@
+//! Read through cache for `Table` records
+//!
+//! `CachedTable` keeps records by id in any `Cache` implementation and invalidates them when they are updated or deleted through it.
+//! `LruCache` is a bundled in memory implementation with an optional time to live.
+//!
+//! # Example
+//!
+//! ```rust
+//! use std::time::Duration;
+//! use serde::{Serialize, Deserialize};
+//! use surrealdb::engine::any::connect;
+//! use surrealdb::sql::Thing as RecordId;
+//! use surrealdb_extra::table::Table;
+//! use surrealdb_extra::table::cache::{CachedTable, LruCache};
+//!
+//! #[derive(Table, Serialize, Deserialize, Clone)]
+//! #[table(name = "user")]
+//! struct User {
+//!     id: Option<RecordId>,
+//!     name: String
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let db = connect("mem://").await.unwrap();
+//!     db.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     let users = CachedTable::<User, _, _>::new(&db, LruCache::new(1000).ttl(Duration::from_secs(60)));
+//!
+//!     let user = users.create(User { id: None, name: "name".to_string() }).await.unwrap().unwrap();
+//!
+//!     // Served from the cache
+//!     let user = users.get_by_id(user.id.unwrap()).await.unwrap();
+//! }
+//! ```
+
+use std::collections::{BTreeMap, HashMap};
+use std::marker::PhantomData;
+use std::sync::Mutex;
+use std::time::Duration;
+use anyhow::Result;
+use surrealdb::{Connection, Surreal};
+use surrealdb::sql::Thing;
+use crate::table::Table;
+use crate::table::id::IntoTableId;
+
+#[cfg(not(feature = "wasm"))]
+use std::time::Instant;
+
+#[cfg(feature = "wasm")]
+use web_time::Instant;
+
+pub trait Cache<T>: Send + Sync {
+    fn get(&self, key: &str) -> Option<T>;
+
+    fn insert(&self, key: String, value: T);
+
+    fn remove(&self, key: &str);
+
+    fn clear(&self);
+}
+
+#[derive(Debug)]
+struct Entry<T> {
+    value: T,
+    inserted: Instant,
+    used: u64,
+}
+
+#[derive(Debug)]
+struct LruState<T> {
+    entries: HashMap<String, Entry<T>>,
+    /// The keys by the tick of their last use, the first one is the least recently used
+    order: BTreeMap<u64, String>,
+    tick: u64,
+}
+
+impl<T> LruState<T> {
+    fn remove(&mut self, key: &str) {
+        if let Some(entry) = self.entries.remove(key) {
+            self.order.remove(&entry.used);
+        }
+    }
+}
+
+/// Least recently used cache with an optional time to live for every entry
+#[derive(Debug)]
+pub struct LruCache<T> {
+    state: Mutex<LruState<T>>,
+    capacity: usize,
+    ttl: Option<Duration>,
+}
+
+impl<T> LruCache<T> {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            state: Mutex::new(LruState {
+                entries: HashMap::new(),
+                order: BTreeMap::new(),
+                tick: 0,
+            }),
+            capacity,
+            ttl: None,
+        }
+    }
+
+    /// Entries older than the ttl are treated as missing
+    pub fn ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.state.lock().map(|s| s.entries.len()).unwrap_or_default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<T: Clone + Send> Cache<T> for LruCache<T> {
+    fn get(&self, key: &str) -> Option<T> {
+        let mut state = self.state.lock().ok()?;
+
+        state.tick += 1;
+        let tick = state.tick;
+
+        let expired = match state.entries.get_mut(key) {
+            None => return None,
+            Some(entry) => self.ttl.is_some_and(|ttl| entry.inserted.elapsed() > ttl),
+        };
+
+        if expired {
+            state.remove(key);
+            return None;
+        }
+
+        let entry = state.entries.get_mut(key)?;
+        let used = std::mem::replace(&mut entry.used, tick);
+        let value = entry.value.clone();
+
+        state.order.remove(&used);
+        state.order.insert(tick, key.to_string());
+
+        Some(value)
+    }
+
+    fn insert(&self, key: String, value: T) {
+        let Ok(mut state) = self.state.lock() else {
+            return;
+        };
+
+        if self.capacity == 0 {
+            return;
+        }
+
+        state.remove(&key);
+
+        if state.entries.len() >= self.capacity {
+            if let Some((_, oldest)) = state.order.pop_first() {
+                state.entries.remove(&oldest);
+            }
+        }
+
+        state.tick += 1;
+        let used = state.tick;
+
+        state.order.insert(used, key.clone());
+        state.entries.insert(key, Entry { value, inserted: Instant::now(), used });
+    }
+
+    fn remove(&self, key: &str) {
+        if let Ok(mut state) = self.state.lock() {
+            state.remove(key);
+        }
+    }
+
+    fn clear(&self) {
+        if let Ok(mut state) = self.state.lock() {
+            state.entries.clear();
+            state.order.clear();
+        }
+    }
+}
+
+/// Wraps the `Table` operations and keeps the results in a cache keyed by the raw record id e.g. `user:1`
+pub struct CachedTable<'r, T, C, K>
+    where T: Table + Clone, C: Connection, K: Cache<T>
+{
+    pub(crate) db: &'r Surreal<C>,
+    pub cache: K,
+    pub(crate) table: PhantomData<T>,
+}
+
+impl<'r, T, C, K> CachedTable<'r, T, C, K>
+    where T: Table + Clone, C: Connection, K: Cache<T>
+{
+    pub fn new(db: &'r Surreal<C>, cache: K) -> Self {
+        Self {
+            db,
+            cache,
+            table: PhantomData,
+        }
+    }
+
+    pub async fn get_by_id(&self, id: impl IntoTableId<T> + Send) -> Result<Option<T>> {
+        let id = id.into_table_id()?;
+
+        if let Some(t) = self.cache.get(&key(&id)) {
+            return Ok(Some(t));
+        }
+
+        let t = T::get_by_id(self.db, &id).await?;
+
+        if let Some(t) = &t {
+            self.cache.insert(key(&id), t.clone());
+        }
+
+        Ok(t)
+    }
+
+    pub async fn create(&self, t: T) -> Result<Option<T>> {
+        let t = t.create(self.db).await?;
+
+        self.store(&t);
+
+        Ok(t)
+    }
+
+    pub async fn update(&self, t: T) -> Result<Option<T>> {
+        if let Some(id) = t.get_id() {
+            self.cache.remove(&key(id));
+        }
+
+        let t = t.update(self.db).await?;
+
+        self.store(&t);
+
+        Ok(t)
+    }
+
+    pub async fn delete(&self, id: impl IntoTableId<T> + Send) -> Result<Option<T>> {
+        let id = id.into_table_id()?;
+
+        self.cache.remove(&key(&id));
+
+        T::delete(self.db, id).await
+    }
+
+    fn store(&self, t: &Option<T>) {
+        let Some(t) = t else {
+            return;
+        };
+
+        if let Some(id) = t.get_id() {
+            self.cache.insert(key(id), t.clone());
+        }
+    }
+}
+
+/// The key of a record for both caching and invalidation
+fn key(id: &Thing) -> String {
+    id.to_raw()
+}
+
+#[cfg(test)]
+mod test {
+    use std::thread::sleep;
+    use serde::{Deserialize, Serialize};
+    use surrealdb::sql::Id;
+    use crate::testing::db;
+    use super::*;
+
+    #[derive(Debug, Table, Serialize, Deserialize, Clone, PartialEq)]
+    #[table(name = "item")]
+    struct Item {
+        id: Option<Thing>,
+        name: String,
+    }
+
+    #[test]
+    fn lru_evicts_least_recently_used() {
+        let cache = LruCache::new(2);
+
+        cache.insert("a".to_string(), 1);
+        cache.insert("b".to_string(), 2);
+
+        assert_eq!(cache.get("a"), Some(1));
+
+        cache.insert("c".to_string(), 3);
+
+        assert_eq!(cache.get("a"), Some(1));
+        assert_eq!(cache.get("b"), None);
+        assert_eq!(cache.get("c"), Some(3));
+        assert_eq!(cache.len(), 2);
+    }
+
+    #[test]
+    fn lru_reinsert() {
+        let cache = LruCache::new(2);
+
+        cache.insert("a".to_string(), 1);
+        cache.insert("b".to_string(), 2);
+        cache.insert("a".to_string(), 3);
+        cache.insert("c".to_string(), 4);
+
+        assert_eq!(cache.get("a"), Some(3));
+        assert_eq!(cache.get("b"), None);
+        assert_eq!(cache.state.lock().unwrap().order.len(), 2);
+    }
+
+    #[test]
+    fn lru_ttl() {
+        let cache = LruCache::new(2).ttl(Duration::from_millis(5));
+
+        cache.insert("a".to_string(), 1);
+
+        sleep(Duration::from_millis(10));
+
+        assert_eq!(cache.get("a"), None);
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn lru_remove() {
+        let cache = LruCache::new(2);
+
+        cache.insert("a".to_string(), 1);
+        cache.remove("a");
+
+        assert_eq!(cache.get("a"), None);
+    }
+
+    #[tokio::test]
+    async fn invalidates_numeric_ids() {
+        let db = db().await;
+
+        let items = CachedTable::<Item, _, _>::new(&db, LruCache::new(10));
+        let id = Thing::from(("item", Id::from(1)));
+
+        let item = items.create(Item { id: Some(id.clone()), name: "one".to_string() }).await.unwrap().unwrap();
+        assert_eq!(items.cache.get("item:1"), Some(item.clone()));
+
+        items.update(Item { name: "two".to_string(), ..item }).await.unwrap();
+        assert_eq!(items.get_by_id(&id).await.unwrap().unwrap().name, "two");
+
+        items.delete(&id).await.unwrap();
+        assert!(items.cache.is_empty());
+        assert_eq!(items.get_by_id(&id).await.unwrap(), None);
+    }
+}
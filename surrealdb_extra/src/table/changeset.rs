@@ -0,0 +1,154 @@
+//! Partial updates without `Option` hacks on the record
+//!
+//! The derive generates a `<Struct>Changeset` with an `Option` of every field except the id, computed fields and
+//! the fields marked with `#[field(skip)]` or `#[field(readonly)]`.
+//! `apply_changeset` merges only the fields that are `Some` into the record and returns the updated record.
+//!
+//! For fields that are already an `Option` the changeset field is `Option<Option<T>>`, `Some(None)` removes the field.
+//! The hooks and the validation of the record don't run for a changeset, because the record is never loaded.
+//! For a versioned table the version is only checked when the changeset sets it.
+//!
+//! # Example
+//!
+//! ```rust
+//! use serde::{Serialize, Deserialize};
+//! use surrealdb::engine::any::connect;
+//! use surrealdb::sql::Thing as RecordId;
+//! use surrealdb_extra::table::Table;
+//!
+//! #[derive(Table, Serialize, Deserialize, Clone)]
+//! #[table(name = "user")]
+//! struct User {
+//!     id: Option<RecordId>,
+//!     name: String,
+//!     age: i64
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let db = connect("mem://").await.unwrap();
+//!     db.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     let user = User { id: None, name: "name".to_string(), age: 20 }.create(&db).await.unwrap().unwrap();
+//!
+//!     let changeset = UserChangeset { age: Some(21), ..Default::default() };
+//!
+//!     let user = User::apply_changeset(&db, user.id.unwrap(), changeset).await.unwrap().unwrap();
+//!
+//!     assert_eq!(user.name, "name");
+//!     assert_eq!(user.age, 21);
+//! }
+//! ```
+
+use anyhow::Result;
+use serde::Serialize;
+use surrealdb::sql::{to_value, Part, Thing};
+use surrealdb::{Connection, RecordId, Surreal};
+use crate::table::{config, version, Table, TableError};
+
+/// A partial update of `T`, implemented by the generated `<Struct>Changeset`
+pub trait Changeset<T: Table>: Serialize + Send + Sync {
+    /// `true` when no field is set
+    fn is_empty(&self) -> bool;
+}
+
+pub(crate) async fn apply<T: Table, C: Connection>(db: &Surreal<C>, id: Thing, changeset: impl Changeset<T> + 'static) -> Result<Option<T>> {
+    if changeset.is_empty() {
+        return T::get_by_id(db, id).await;
+    }
+
+    let content = to_value(changeset).map_err(|e| TableError::Db(e.into()))?;
+
+    if let Some(field) = T::VERSION_FIELD {
+        if !content.pick(&[Part::from(field)]).is_none() {
+            return version::update(db, id, field, content, None).await;
+        }
+    }
+
+    let s: Option<T> = if config::enabled() {
+        db.query(config::merge(id, content)).await?.take(0)?
+    } else {
+        db.update(RecordId::from_inner(id)).merge(content).await?
+    };
+
+    Ok(s)
+}
+
+#[cfg(test)]
+mod test {
+    use serde::Deserialize;
+    use crate::table::computed::Computed;
+    use crate::testing::db;
+    use super::*;
+
+    #[derive(Debug, Table, Serialize, Deserialize, Clone, PartialEq)]
+    #[table(name = "user")]
+    struct User {
+        id: Option<Thing>,
+        #[serde(rename = "fullName")]
+        name: String,
+        nickname: Option<String>,
+        #[field(readonly)]
+        #[serde(default)]
+        login_count: i64,
+        #[field(computed = "string::len(fullName)")]
+        #[serde(default)]
+        name_length: Computed<i64>,
+    }
+
+    #[derive(Debug, Table, Serialize, Deserialize, Clone, PartialEq)]
+    #[table(name = "account", versioned)]
+    struct Account {
+        id: Option<Thing>,
+        balance: i64,
+        version: u64,
+    }
+
+    #[test]
+    fn serializes_set_fields() {
+        let changeset = UserChangeset { name: Some("john".to_string()), ..Default::default() };
+
+        assert!(!changeset.is_empty());
+        assert_eq!(to_value(changeset).unwrap().to_string(), "{ fullName: 'john' }");
+        assert!(UserChangeset::default().is_empty());
+    }
+
+    #[tokio::test]
+    async fn merges_set_fields() {
+        let db = db().await;
+
+        let user = User { id: None, name: "john".to_string(), nickname: Some("j".to_string()), login_count: 0, name_length: Computed::default() }
+            .create(&db).await.unwrap().unwrap();
+        let id = user.id.clone().unwrap();
+
+        let user = User::apply_changeset(&db, id.clone(), UserChangeset { name: Some("johnny".to_string()), ..Default::default() })
+            .await.unwrap().unwrap();
+
+        assert_eq!(user.name, "johnny");
+        assert_eq!(user.nickname.as_deref(), Some("j"));
+
+        let user = User::apply_changeset(&db, id.clone(), UserChangeset { nickname: Some(None), ..Default::default() })
+            .await.unwrap().unwrap();
+
+        assert_eq!(user.name, "johnny");
+        assert_eq!(user.nickname, None);
+
+        let unchanged = User::apply_changeset(&db, id, UserChangeset::default()).await.unwrap();
+        assert_eq!(unchanged, Some(user));
+    }
+
+    #[tokio::test]
+    async fn checks_version() {
+        let db = db().await;
+
+        let account = Account { id: None, balance: 10, version: 0 }.create(&db).await.unwrap().unwrap();
+        let id = account.id.unwrap();
+
+        let account = Account::apply_changeset(&db, id.clone(), AccountChangeset { balance: Some(20), version: Some(0) })
+            .await.unwrap().unwrap();
+        assert_eq!((account.balance, account.version), (20, 1));
+
+        let err = Account::apply_changeset(&db, id, AccountChangeset { balance: Some(30), version: Some(0) }).await.unwrap_err();
+        assert!(matches!(err.downcast_ref::<TableError>(), Some(TableError::StaleRecord { .. })));
+    }
+}
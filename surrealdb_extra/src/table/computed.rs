@@ -0,0 +1,154 @@
+//! Fields computed by the database
+//!
+//! A field marked with `#[field(computed = "...")]` gets a `DEFINE FIELD ... VALUE <expr>` from `define_computed_fields`,
+//! so the database calculates it on every write.
+//! The field has to be of type `Computed<T>`, which can only be read and is always written as `NONE`,
+//! so a value set on the client can't end up in the database.
+//!
+//! Add `#[serde(default)]` to the field when the expression can return `NONE`, otherwise reading the record fails.
+//!
+//! # Example
+//!
+//! ```rust
+//! use serde::{Serialize, Deserialize};
+//! use surrealdb::engine::any::connect;
+//! use surrealdb::sql::Thing as RecordId;
+//! use surrealdb_extra::table::Table;
+//! use surrealdb_extra::table::computed::Computed;
+//!
+//! #[derive(Table, Serialize, Deserialize, Clone)]
+//! #[table(name = "line")]
+//! struct Line {
+//!     id: Option<RecordId>,
+//!     price: f64,
+//!     quantity: i64,
+//!     #[field(computed = "price * quantity")]
+//!     #[serde(default)]
+//!     total: Computed<f64>
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let db = connect("mem://").await.unwrap();
+//!     db.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     Line::define_computed_fields(&db).await.unwrap();
+//!
+//!     let line = Line { id: None, price: 2.5, quantity: 4, total: Computed::default() }.create(&db).await.unwrap().unwrap();
+//!
+//!     assert_eq!(line.total.get(), Some(&10.0));
+//! }
+//! ```
+
+use std::ops::Deref;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// A field that is calculated by the `value` expression
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ComputedField {
+    pub field: &'static str,
+    pub value: &'static str,
+}
+
+impl ComputedField {
+    /// `DEFINE FIELD` with the expression as `VALUE`
+    pub fn define_field(&self, table: &str) -> String {
+        let ComputedField { field, value } = self;
+
+        format!("DEFINE FIELD OVERWRITE {field} ON TABLE {table} VALUE {value}")
+    }
+}
+
+/// Read only value of a computed field, it is `None` until the record is read from the database
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Computed<T>(Option<T>);
+
+impl<T> Default for Computed<T> {
+    fn default() -> Self {
+        Self(None)
+    }
+}
+
+impl<T> Computed<T> {
+    pub fn get(&self) -> Option<&T> {
+        self.0.as_ref()
+    }
+
+    pub fn into_inner(self) -> Option<T> {
+        self.0
+    }
+}
+
+impl<T> Deref for Computed<T> {
+    type Target = Option<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> Serialize for Computed<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_none()
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Computed<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Option::<T>::deserialize(deserializer).map(Self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use surrealdb::sql::Thing;
+    use crate::table::Table;
+    use crate::testing::db;
+    use super::*;
+
+    #[derive(Debug, Table, Serialize, Deserialize, Clone)]
+    #[table(name = "line")]
+    struct Line {
+        id: Option<Thing>,
+        price: f64,
+        quantity: i64,
+        #[field(computed = "price * quantity")]
+        #[serde(rename = "sum", default)]
+        total: Computed<f64>,
+    }
+
+    #[test]
+    fn derived() {
+        assert_eq!(Line::COMPUTED_FIELDS, &[ComputedField { field: "sum", value: "price * quantity" }]);
+        assert_eq!(Line::COMPUTED_FIELDS[0].define_field("line"), "DEFINE FIELD OVERWRITE sum ON TABLE line VALUE price * quantity");
+    }
+
+    #[tokio::test]
+    async fn computed_on_write() {
+        let db = db().await;
+
+        Line::define_computed_fields(&db).await.unwrap();
+
+        let mut line = Line { id: None, price: 2.0, quantity: 3, total: Computed::default() }.create(&db).await.unwrap().unwrap();
+        assert_eq!(line.total.get(), Some(&6.0));
+
+        line.quantity = 5;
+        let line = line.update(&db).await.unwrap().unwrap();
+        assert_eq!(*line.total, Some(10.0));
+    }
+
+    #[tokio::test]
+    async fn client_value_is_not_written() {
+        let db = db().await;
+
+        Line::define_computed_fields(&db).await.unwrap();
+
+        let line = Line { id: None, price: 2.0, quantity: 3, total: Computed::default() }.create(&db).await.unwrap().unwrap();
+        assert_eq!(line.total.get(), Some(&6.0));
+
+        db.query("REMOVE FIELD sum ON TABLE line").await.unwrap().check().unwrap();
+
+        let line = line.update(&db).await.unwrap().unwrap();
+        assert_eq!(line.total.get(), None);
+    }
+}
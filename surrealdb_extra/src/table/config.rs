@@ -0,0 +1,151 @@
+//! The global `QueryConfig` and the deadline for the CRUD methods of `Table` and the batches of the write pipeline
+//!
+//! The methods of the sdk that `create`, `get_by_id`, `update` and `delete` use have no `TIMEOUT` or `PARALLEL`,
+//! so while a global config is set or a deadline is running they are sent as statements with the config applied.
+//! Without the `query` feature there is no config and nothing changes.
+
+use anyhow::Result;
+use surrealdb::sql::statements::{CreateStatement, DeleteStatement, UpdateStatement};
+use surrealdb::sql::{parse, Data, Field, Fields, Idiom, Output, Part, Statement, Thing, Value};
+use crate::table::TableError;
+
+#[cfg(feature = "pipeline")]
+use surrealdb::sql::{statements::InsertStatement, Array};
+
+/// A global config is set or a deadline is running
+#[cfg(feature = "query")]
+pub(crate) fn enabled() -> bool {
+    #[cfg(feature = "deadline")]
+    if crate::query::deadline::Deadline::current().is_some() {
+        return true;
+    }
+
+    crate::query::config::global_config().is_some()
+}
+
+#[cfg(not(feature = "query"))]
+pub(crate) fn enabled() -> bool {
+    false
+}
+
+#[cfg(feature = "query")]
+pub(crate) fn apply<S: crate::query::config::ConfigStatement>(statement: S) -> S {
+    crate::query::config::apply(statement)
+}
+
+#[cfg(not(feature = "query"))]
+pub(crate) fn apply<S>(statement: S) -> S {
+    statement
+}
+
+/// `CREATE ONLY table CONTENT content`
+pub(crate) fn create(table: &str, content: Value) -> CreateStatement {
+    let mut statement = CreateStatement::default();
+
+    statement.what.0 = vec![Value::Table(table.into())];
+    statement.data = Some(Data::ContentExpression(content));
+    statement.only = true;
+
+    apply(statement)
+}
+
+/// `CREATE ONLY table CONTENT content RETURN VALUE id`
+pub(crate) fn create_id(table: &str, content: Value) -> CreateStatement {
+    let mut fields = Fields::default();
+    fields.0 = vec![Field::Single { expr: Value::Idiom(Idiom::from(vec![Part::from("id")])), alias: None }];
+    fields.1 = true;
+
+    let mut statement = create(table, content);
+    statement.output = Some(Output::Fields(fields));
+
+    statement
+}
+
+/// `INSERT INTO table contents RETURN NONE`
+#[cfg(feature = "pipeline")]
+pub(crate) fn insert(table: &str, contents: Vec<Value>) -> InsertStatement {
+    let mut statement = InsertStatement::default();
+
+    statement.into = Some(Value::Table(table.into()));
+    statement.data = Data::SingleExpression(Value::Array(Array::from(contents)));
+    statement.output = Some(Output::None);
+
+    apply(statement)
+}
+
+/// An `UPDATE` with parameters that the other statements of the query use as well, e.g. `UPDATE $id MERGE $content`
+pub(crate) fn update(query: &str) -> Result<UpdateStatement> {
+    let query = parse(query).map_err(|e| TableError::Db(e.into()))?;
+
+    match query.0.0.into_iter().next() {
+        Some(Statement::Update(statement)) => Ok(apply(statement)),
+        _ => unreachable!("the callers only pass an UPDATE"),
+    }
+}
+
+/// `UPDATE ONLY id MERGE content`
+pub(crate) fn merge(id: Thing, content: Value) -> UpdateStatement {
+    let mut statement = UpdateStatement::default();
+
+    statement.what.0 = vec![Value::Thing(id)];
+    statement.data = Some(Data::MergeExpression(content));
+    statement.output = Some(Output::After);
+    statement.only = true;
+
+    apply(statement)
+}
+
+/// `DELETE ONLY id RETURN BEFORE`
+pub(crate) fn delete(id: Thing) -> DeleteStatement {
+    let mut statement = DeleteStatement::default();
+
+    statement.what.0 = vec![Value::Thing(id)];
+    statement.output = Some(Output::Before);
+    statement.only = true;
+
+    apply(statement)
+}
+
+#[cfg(all(test, feature = "deadline"))]
+mod test {
+    use std::time::Duration;
+    use serde::{Deserialize, Serialize};
+    use surrealdb::sql::Thing as RecordId;
+    use crate::query::deadline::Deadline;
+    use crate::table::Table;
+    use crate::testing::db;
+    use super::*;
+
+    #[derive(Debug, Table, Serialize, Deserialize, Clone, PartialEq)]
+    #[table(name = "user")]
+    struct User {
+        id: Option<RecordId>,
+        name: String,
+    }
+
+    #[tokio::test]
+    async fn crud_with_deadline() {
+        let db = db().await;
+
+        assert!(!enabled());
+        assert!(create("user", Value::None).timeout.is_none());
+
+        Deadline::after(Duration::from_secs(60)).scope(async {
+            assert!(enabled());
+            assert!(create("user", Value::None).timeout.is_some());
+            assert!(delete(RecordId::from(("user", "one"))).timeout.is_some());
+
+            let id = User { id: None, name: "one".to_string() }.create_get_id(&db).await.unwrap();
+            let user = User::get_by_id(&db, id.clone()).await.unwrap().unwrap();
+            assert_eq!(User::get_all(&db).await.unwrap(), vec![user.clone()]);
+
+            let renamed = User { name: "two".to_string(), ..user }.update(&db).await.unwrap().unwrap();
+            assert_eq!(renamed.name, "two");
+
+            let (updated, diff) = User { name: "three".to_string(), ..renamed }.update_with_diff(&db).await.unwrap();
+            assert!(!diff.ops.is_empty());
+
+            assert_eq!(User::delete(&db, id).await.unwrap(), updated);
+        }).await;
+    }
+}
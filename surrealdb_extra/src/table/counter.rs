@@ -0,0 +1,139 @@
+//! Counter caches kept up to date by the database
+//!
+//! A counter cache stores the number of child records that link to a parent record in a field of the parent,
+//! so pages don't have to run `count()` every time.
+//! `define_counter_caches` defines an event on the child table that increments the field on create, decrements it on delete
+//! and moves the count when the link changes, so every write is counted and not only the writes through `Table`.
+//!
+//! # Example
+//!
+//! ```rust
+//! use serde::{Serialize, Deserialize};
+//! use surrealdb::engine::any::connect;
+//! use surrealdb::sql::Thing as RecordId;
+//! use surrealdb_extra::table::Table;
+//!
+//! #[derive(Table, Serialize, Deserialize, Clone)]
+//! #[table(name = "post", counter_cache(field = "comment_count", on = Comment, via = "commented_on"))]
+//! struct Post {
+//!     id: Option<RecordId>,
+//!     comment_count: Option<i64>
+//! }
+//!
+//! #[derive(Table, Serialize, Deserialize, Clone)]
+//! #[table(name = "comment")]
+//! struct Comment {
+//!     id: Option<RecordId>,
+//!     commented_on: RecordId
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let db = connect("mem://").await.unwrap();
+//!     db.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     Post::define_counter_caches(&db).await.unwrap();
+//!
+//!     let post = Post { id: None, comment_count: Some(0) }.create(&db).await.unwrap().unwrap();
+//!     let post_id = post.id.unwrap();
+//!
+//!     Comment { id: None, commented_on: post_id.clone() }.create(&db).await.unwrap();
+//!
+//!     let post = Post::get_by_id(&db, post_id.id.to_raw()).await.unwrap().unwrap();
+//!     assert_eq!(post.comment_count, Some(1));
+//! }
+//! ```
+
+/// Counts the records of the `on` table that link to the parent with the `via` field into the `field` of the parent
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CounterCache {
+    pub field: &'static str,
+    pub on: &'static str,
+    pub via: &'static str,
+}
+
+impl CounterCache {
+    pub fn event_name(&self, table: &str) -> String {
+        format!("{table}_{}", self.field)
+    }
+
+    /// `DEFINE EVENT` on the child table that maintains the counter of the parent table
+    pub fn define_event(&self, table: &str) -> String {
+        let CounterCache { field, on, via } = self;
+
+        format!(
+            "DEFINE EVENT OVERWRITE {name} ON TABLE {on} \
+            WHEN $event = 'CREATE' OR $event = 'DELETE' OR ($event = 'UPDATE' AND $before.{via} != $after.{via}) THEN {{ \
+                IF $event != 'CREATE' AND record::tb($before.{via}) = '{table}' {{ UPDATE $before.{via} SET {field} -= 1 }}; \
+                IF $event != 'DELETE' AND record::tb($after.{via}) = '{table}' {{ UPDATE $after.{via} SET {field} += 1 }}; \
+            }}",
+            name = self.event_name(table),
+        )
+    }
+
+    /// `UPDATE` that sets the counter of every parent record to the actual count
+    pub fn recount(&self, table: &str) -> String {
+        let CounterCache { field, on, via } = self;
+
+        format!("UPDATE {table} SET {field} = count(SELECT id FROM {on} WHERE {via} = $parent.id)")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use surrealdb::engine::any::Any;
+    use surrealdb::Surreal;
+    use super::*;
+
+    const COUNTER: CounterCache = CounterCache { field: "comment_count", on: "comment", via: "post" };
+
+    async fn db() -> Surreal<Any> {
+        let db = crate::testing::db().await;
+
+        db.query("CREATE post:1 SET comment_count = 0; CREATE post:2 SET comment_count = 0;").await.unwrap().check().unwrap();
+
+        db
+    }
+
+    async fn count(db: &Surreal<Any>, post: &str) -> Option<i64> {
+        let mut res = db.query(format!("SELECT VALUE comment_count FROM ONLY {post}")).await.unwrap();
+
+        res.take(0).unwrap()
+    }
+
+    #[tokio::test]
+    async fn create_and_delete() {
+        let db = db().await;
+
+        db.query(COUNTER.define_event("post")).await.unwrap().check().unwrap();
+
+        db.query("CREATE comment:1 SET post = post:1; CREATE comment:2 SET post = post:1;").await.unwrap().check().unwrap();
+        assert_eq!(count(&db, "post:1").await, Some(2));
+
+        db.query("DELETE comment:1").await.unwrap().check().unwrap();
+        assert_eq!(count(&db, "post:1").await, Some(1));
+    }
+
+    #[tokio::test]
+    async fn moved_link() {
+        let db = db().await;
+
+        db.query(COUNTER.define_event("post")).await.unwrap().check().unwrap();
+
+        db.query("CREATE comment:1 SET post = post:1; UPDATE comment:1 SET post = post:2;").await.unwrap().check().unwrap();
+
+        assert_eq!(count(&db, "post:1").await, Some(0));
+        assert_eq!(count(&db, "post:2").await, Some(1));
+    }
+
+    #[tokio::test]
+    async fn recount() {
+        let db = db().await;
+
+        db.query("CREATE comment:1 SET post = post:2; CREATE comment:2 SET post = post:2;").await.unwrap().check().unwrap();
+        db.query(COUNTER.recount("post")).await.unwrap().check().unwrap();
+
+        assert_eq!(count(&db, "post:1").await, Some(0));
+        assert_eq!(count(&db, "post:2").await, Some(2));
+    }
+}
@@ -0,0 +1,245 @@
+//! CSV export and import of a table
+//!
+//! The columns are the fields of the struct in their order, named like in the database (`#[serde(rename)]` is followed).
+//! Strings and record ids are written as they are (`user:john`), empty cells are `NONE` and other values are written as SurrealQL,
+//! e.g. `[1, 2]` or `{ a: 1 }`. On import the cells of `String` fields are read as strings, the others are parsed as SurrealQL literals
+//! (numbers, arrays, objects, record ids...) before they fall back to a string. Ids without a table are ids of the table.
+//!
+//! Both read and write the records in batches of `BATCH_SIZE`, so the whole table is never in memory.
+//! Imported records are checked with `validate_record` and written without their `SKIP_FIELDS` and `READONLY_FIELDS`, the hooks are not called.
+//!
+//! # Example
+//!
+//! ```rust
+//! use serde::{Serialize, Deserialize};
+//! use surrealdb::engine::any::connect;
+//! use surrealdb::sql::Thing as RecordId;
+//! use surrealdb_extra::table::Table;
+//!
+//! #[derive(Table, Serialize, Deserialize, Clone)]
+//! #[table(name = "user")]
+//! struct User {
+//!     id: Option<RecordId>,
+//!     name: String,
+//!     age: u8
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let db = connect("mem://").await.unwrap();
+//!     db.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     let imported = User::import_csv(&db, "id,name,age\njohn,John,30\n".as_bytes()).await.unwrap();
+//!     assert_eq!(imported, 1);
+//!
+//!     let mut csv = vec![];
+//!     User::export_csv(&db, &mut csv).await.unwrap();
+//!     assert_eq!(String::from_utf8(csv).unwrap(), "id,name,age\nuser:john,John,30\n");
+//! }
+//! ```
+
+use std::io::{Read, Write};
+use anyhow::Result;
+use surrealdb::{Connection, Surreal};
+use surrealdb::sql::{Object, Strand, Value};
+use crate::table::{Table, TableError};
+use crate::table::id::IntoTableId;
+use crate::table::write::write_content;
+
+/// Number of records that are read or written at once
+pub const BATCH_SIZE: usize = 1000;
+
+/// Writes every record of the table ordered by id, returns the number of written records
+pub async fn export<T: Table, C: Connection, W: Write + Send>(db: &Surreal<C>, writer: W) -> Result<usize> {
+    let mut writer = ::csv::Writer::from_writer(writer);
+    let mut columns: Vec<String> = T::meta().fields.iter().map(|f| f.name.to_string()).collect();
+    let mut header = !columns.is_empty();
+    let mut count = 0;
+
+    if header {
+        writer.write_record(&columns).map_err(csv_error)?;
+    }
+
+    loop {
+        let records: surrealdb::Value = db.query("SELECT * FROM type::table($table) ORDER BY id LIMIT $limit START $start")
+            .bind(("table", T::TABLE_NAME))
+            .bind(("limit", BATCH_SIZE))
+            .bind(("start", count))
+            .await.map_err(TableError::from)?
+            .take(0).map_err(TableError::from)?;
+
+        let Value::Array(records) = records.into_inner() else {
+            break;
+        };
+
+        let len = records.len();
+
+        for record in records {
+            let Value::Object(record) = record else {
+                continue;
+            };
+
+            // Tables without derived metadata use the fields of the first record
+            if !header {
+                columns = record.keys().cloned().collect();
+                writer.write_record(&columns).map_err(csv_error)?;
+                header = true;
+            }
+
+            writer.write_record(columns.iter().map(|column| to_cell(record.get(column)))).map_err(csv_error)?;
+        }
+
+        count += len;
+
+        if len < BATCH_SIZE {
+            break;
+        }
+    }
+
+    writer.flush().map_err(|e| TableError::Csv(e.to_string()))?;
+
+    Ok(count)
+}
+
+/// Reads the records and inserts them in batches, returns the number of inserted records
+pub async fn import<T: Table, C: Connection, R: Read + Send>(db: &Surreal<C>, reader: R) -> Result<usize> {
+    let mut reader = ::csv::Reader::from_reader(reader);
+    let columns = reader.headers().map_err(csv_error)?.clone();
+    let meta = T::meta();
+    let mut batch = Vec::with_capacity(BATCH_SIZE);
+    let mut count = 0;
+
+    for row in reader.records() {
+        let row = row.map_err(csv_error)?;
+        let mut record = Object::default();
+
+        for (column, cell) in columns.iter().zip(row.iter()) {
+            let value = if column == "id" {
+                if cell.is_empty() {
+                    continue;
+                }
+
+                Value::Thing(record_id::<T>(cell)?)
+            } else {
+                from_cell(cell, meta.field(column).map(|f| f.rust_type).unwrap_or_default())
+            };
+
+            record.insert(column.to_string(), value);
+        }
+
+        let t: T = surrealdb::sql::from_value(Value::Object(record)).map_err(|e| TableError::Csv(format!("row {}: {e}", count + batch.len() + 1)))?;
+        t.validate_record().map_err(TableError::from)?;
+
+        batch.push(write_content(t)?);
+
+        if batch.len() == BATCH_SIZE {
+            count += insert::<T, C>(db, std::mem::take(&mut batch)).await?;
+        }
+    }
+
+    if !batch.is_empty() {
+        count += insert::<T, C>(db, batch).await?;
+    }
+
+    Ok(count)
+}
+
+async fn insert<T: Table, C: Connection>(db: &Surreal<C>, records: Vec<Value>) -> Result<usize> {
+    let len = records.len();
+
+    db.query(format!("INSERT INTO {} $records RETURN NONE", surrealdb::sql::Table::from(T::TABLE_NAME)))
+        .bind(("records", Value::from(records)))
+        .await.map_err(TableError::from)?
+        .check().map_err(TableError::from)?;
+
+    Ok(len)
+}
+
+fn to_cell(value: Option<&Value>) -> String {
+    match value {
+        None | Some(Value::None) | Some(Value::Null) => String::new(),
+        Some(Value::Strand(s)) => s.0.clone(),
+        Some(Value::Thing(thing)) => thing.to_raw(),
+        Some(Value::Datetime(datetime)) => datetime.to_raw(),
+        Some(value) => value.to_string(),
+    }
+}
+
+fn from_cell(cell: &str, rust_type: &str) -> Value {
+    if cell.is_empty() {
+        return Value::None;
+    }
+
+    let rust_type = rust_type.strip_prefix("Option<").and_then(|t| t.strip_suffix('>')).unwrap_or(rust_type);
+
+    if rust_type == "String" {
+        return Value::Strand(Strand::from(cell));
+    }
+
+    // Plain words are parsed as field names, only literals are kept
+    match surrealdb::sql::value(cell) {
+        Ok(value @ (Value::Bool(_) | Value::Number(_) | Value::Strand(_) | Value::Array(_) | Value::Object(_)
+            | Value::Thing(_) | Value::Datetime(_) | Value::Duration(_) | Value::Uuid(_) | Value::Null)) => value,
+        _ => Value::Strand(Strand::from(cell)),
+    }
+}
+
+fn record_id<T: Table>(cell: &str) -> Result<surrealdb::sql::Thing> {
+    match surrealdb::sql::thing(cell) {
+        Ok(thing) => IntoTableId::<T>::into_table_id(thing),
+        Err(_) => Ok(T::create_record_id(cell)),
+    }
+}
+
+fn csv_error(e: ::csv::Error) -> TableError {
+    TableError::Csv(e.to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use serde::{Deserialize, Serialize};
+    use surrealdb::sql::Thing as RecordId;
+    use crate::testing::db;
+    use super::*;
+
+    #[derive(Debug, Table, Serialize, Deserialize, Clone, PartialEq)]
+    #[table(name = "user")]
+    struct User {
+        id: Option<RecordId>,
+        name: String,
+        age: Option<u8>,
+        tags: Vec<String>,
+        friend: Option<RecordId>,
+    }
+
+    #[test]
+    fn cells() {
+        assert_eq!(from_cell("123", "String"), Value::from("123"));
+        assert_eq!(from_cell("123", "Option<u8>"), Value::from(123));
+        assert_eq!(from_cell("", "Option<u8>"), Value::None);
+        assert_eq!(from_cell("user:john", "Option<RecordId>"), Value::Thing(RecordId::from(("user", "john"))));
+
+        assert_eq!(to_cell(Some(&Value::from("a,b"))), "a,b");
+        assert_eq!(to_cell(Some(&Value::Thing(RecordId::from(("user", "john"))))), "user:john");
+        assert_eq!(to_cell(None), "");
+    }
+
+    #[tokio::test]
+    async fn export_import() {
+        let db = db().await;
+
+        let csv = "id,name,age,tags,friend\njohn,\"Doe, John\",30,\"['a', 'b']\",user:jane\nuser:jane,007,,[],\n";
+
+        assert_eq!(User::import_csv(&db, csv.as_bytes()).await.unwrap(), 2);
+
+        let john = User::get_by_id(&db, "john").await.unwrap().unwrap();
+        assert_eq!((john.name.as_str(), john.age, john.tags.len()), ("Doe, John", Some(30), 2));
+        assert_eq!(john.friend, Some(RecordId::from(("user", "jane"))));
+
+        let mut exported = vec![];
+        assert_eq!(User::export_csv(&db, &mut exported).await.unwrap(), 2);
+        assert_eq!(String::from_utf8(exported).unwrap(), "id,name,age,tags,friend\nuser:jane,007,,[],\nuser:john,\"Doe, John\",30,\"['a', 'b']\",user:jane\n");
+
+        assert!(User::import_csv(&db, "id,name,age,tags,friend\npost:one,One,,[],\n".as_bytes()).await.is_err());
+    }
+}
@@ -0,0 +1,61 @@
+//! Where the `Table` methods run: a `Surreal<C>`, or with the `pool` feature a `SurrealPool<C>` or a checked out `PooledConnection`
+//!
+//! The methods take `&impl Database<C>` and check out one connection for the whole call, so the hooks and the query of
+//! a `create` or `update` run on the same connection. The retry methods check out a connection for every attempt.
+//!
+//! # Example
+//!
+//! ```rust
+//! use serde::{Serialize, Deserialize};
+//! use surrealdb::engine::any::{connect, Any};
+//! use surrealdb::sql::Thing as RecordId;
+//! use surrealdb_extra::table::Table;
+//! use surrealdb_extra::table::database::Database;
+//!
+//! #[derive(Table, Serialize, Deserialize, Clone)]
+//! #[table(name = "user")]
+//! struct User {
+//!     id: Option<RecordId>,
+//!     name: String
+//! }
+//!
+//! async fn names(db: &impl Database<Any>) -> Vec<String> {
+//!     User::get_all(db).await.unwrap().into_iter().map(|u| u.name).collect()
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let db = connect("mem://").await.unwrap();
+//!     db.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     User { id: None, name: "name".to_string() }.create(&db).await.unwrap();
+//!
+//!     assert_eq!(names(&db).await, ["name"]);
+//! }
+//! ```
+
+use std::ops::Deref;
+use surrealdb::{Connection, Surreal};
+
+pub trait Database<C: Connection>: Sync {
+    /// Dereferences to the connection, a pooled connection counts as busy until it is dropped
+    type Connection<'a>: Deref<Target = Surreal<C>> + Send + Sync where Self: 'a;
+
+    fn connection(&self) -> Self::Connection<'_>;
+}
+
+impl<C: Connection> Database<C> for Surreal<C> {
+    type Connection<'a> = &'a Surreal<C>;
+
+    fn connection(&self) -> Self::Connection<'_> {
+        self
+    }
+}
+
+impl<C: Connection, D: Database<C> + ?Sized> Database<C> for &D {
+    type Connection<'a> = D::Connection<'a> where Self: 'a;
+
+    fn connection(&self) -> Self::Connection<'_> {
+        (**self).connection()
+    }
+}
@@ -0,0 +1,99 @@
+//! Default values set by the database
+//!
+//! A field marked with `#[field(default = "...")]` gets a `DEFINE FIELD ... DEFAULT <expr>` from `define_default_fields`,
+//! so the database fills in the value when a record is created without it.
+//!
+//! The default is only used when the field is `NONE`, so the field is usually an `Option<T>`, which is sent as `NONE` when it is `None`,
+//! or a field marked with `#[field(skip)]`.
+//!
+//! # Example
+//!
+//! ```rust
+//! use serde::{Serialize, Deserialize};
+//! use surrealdb::engine::any::connect;
+//! use surrealdb::sql::Thing as RecordId;
+//! use surrealdb_extra::table::Table;
+//!
+//! #[derive(Table, Serialize, Deserialize, Clone)]
+//! #[table(name = "user")]
+//! struct User {
+//!     id: Option<RecordId>,
+//!     name: String,
+//!     #[field(default = "'member'")]
+//!     role: Option<String>
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let db = connect("mem://").await.unwrap();
+//!     db.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     User::define_default_fields(&db).await.unwrap();
+//!
+//!     let user = User { id: None, name: "name".to_string(), role: None }.create(&db).await.unwrap().unwrap();
+//!
+//!     assert_eq!(user.role.as_deref(), Some("member"));
+//! }
+//! ```
+
+/// A field that is set to the `value` expression when it is missing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DefaultField {
+    pub field: &'static str,
+    pub value: &'static str,
+}
+
+impl DefaultField {
+    /// `DEFINE FIELD` with the expression as `DEFAULT`
+    pub fn define_field(&self, table: &str) -> String {
+        let DefaultField { field, value } = self;
+
+        format!("DEFINE FIELD OVERWRITE {field} ON TABLE {table} DEFAULT {value}")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde::{Deserialize, Serialize};
+    use surrealdb::sql::Thing;
+    use crate::table::Table;
+    use crate::testing::db;
+    use super::*;
+
+    #[derive(Debug, Table, Serialize, Deserialize, Clone)]
+    #[table(name = "user")]
+    struct User {
+        id: Option<Thing>,
+        name: String,
+        #[field(default = "'member'")]
+        role: Option<String>,
+        #[field(default = "time::now()", skip)]
+        #[serde(rename = "createdAt", default)]
+        created_at: Option<surrealdb::sql::Datetime>,
+    }
+
+    #[test]
+    fn derived() {
+        assert_eq!(User::DEFAULT_FIELDS, &[
+            DefaultField { field: "role", value: "'member'" },
+            DefaultField { field: "createdAt", value: "time::now()" },
+        ]);
+        assert_eq!(User::DEFAULT_FIELDS[0].define_field("user"), "DEFINE FIELD OVERWRITE role ON TABLE user DEFAULT 'member'");
+    }
+
+    #[tokio::test]
+    async fn default_on_create() {
+        let db = db().await;
+
+        User::init_schema(&db).await.unwrap();
+
+        let user = User { id: None, name: "name".to_string(), role: None, created_at: None }.create(&db).await.unwrap().unwrap();
+
+        assert_eq!(user.role.as_deref(), Some("member"));
+        assert!(user.created_at.is_some());
+
+        let admin = User { id: None, name: "name".to_string(), role: Some("admin".to_string()), created_at: None }.create(&db).await.unwrap().unwrap();
+
+        assert_eq!(admin.role.as_deref(), Some("admin"));
+    }
+}
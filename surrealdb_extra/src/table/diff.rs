@@ -0,0 +1,292 @@
+//! Typed `RETURN DIFF` output
+//!
+//! `RETURN DIFF` returns the changes of an update as JSON patch operations, `Diff` parses them into `DiffOp`s
+//! with the path split into its fields, so audit logs and events don't have to pick the values apart.
+//! `Table::update_with_diff` merges the record like `update` and returns the updated record with its diff.
+//!
+//! Text fields can change with the `change` op, its value is a diff-match-patch of the text instead of the new text.
+//! The version of `#[table(versioned)]` is not checked by `update_with_diff`.
+//!
+//! # Example
+//!
+//! ```rust
+//! use serde::{Serialize, Deserialize};
+//! use surrealdb::engine::any::connect;
+//! use surrealdb::sql::{Thing as RecordId, Value};
+//! use surrealdb_extra::table::Table;
+//! use surrealdb_extra::table::diff::DiffOpKind;
+//!
+//! #[derive(Table, Serialize, Deserialize, Clone)]
+//! #[table(name = "user")]
+//! struct User {
+//!     id: Option<RecordId>,
+//!     name: String,
+//!     age: u8
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let db = connect("mem://").await.unwrap();
+//!     db.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     let mut user = User { id: None, name: "John".to_string(), age: 20 }.create(&db).await.unwrap().unwrap();
+//!     user.age = 21;
+//!
+//!     let (user, diff) = user.update_with_diff(&db).await.unwrap();
+//!     assert_eq!(user.unwrap().age, 21);
+//!
+//!     assert_eq!(diff.changed_fields(), ["age"]);
+//!     assert_eq!(diff.ops[0].op, DiffOpKind::Replace);
+//!     assert_eq!(diff.ops[0].path.to_string(), "age");
+//!     assert_eq!(diff.ops[0].value, Some(Value::from(21)));
+//! }
+//! ```
+
+use std::fmt::{Display, Formatter};
+use anyhow::Result;
+use surrealdb::sql::{Idiom, Part, Thing, Value};
+use surrealdb::{Connection, Surreal};
+use crate::table::{config, Table, TableError};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffOpKind {
+    Add,
+    Remove,
+    Replace,
+    /// A diff-match-patch of a text field
+    Change,
+    Copy,
+    Move,
+    Test,
+}
+
+impl DiffOpKind {
+    pub fn parse(op: &str) -> Option<Self> {
+        let op = match op {
+            "add" => Self::Add,
+            "remove" => Self::Remove,
+            "replace" => Self::Replace,
+            "change" => Self::Change,
+            "copy" => Self::Copy,
+            "move" => Self::Move,
+            "test" => Self::Test,
+            _ => return None,
+        };
+
+        Some(op)
+    }
+}
+
+/// A JSON pointer split into its fields and array indexes, `/tags/0` is `["tags", "0"]`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct DiffPath(pub Vec<String>);
+
+impl DiffPath {
+    /// Parses a JSON pointer, `~1` is `/` and `~0` is `~`
+    pub fn parse(pointer: &str) -> Self {
+        let segments = pointer.strip_prefix('/').unwrap_or(pointer)
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+            .map(|segment| segment.replace("~1", "/").replace("~0", "~"))
+            .collect();
+
+        Self(segments)
+    }
+
+    /// The top level field, `None` when the whole record changed
+    pub fn field(&self) -> Option<&str> {
+        self.0.first().map(String::as_str)
+    }
+
+    /// The path as an idiom, numbers are array indexes
+    pub fn to_idiom(&self) -> Idiom {
+        let parts: Vec<Part> = self.0.iter()
+            .map(|segment| match segment.parse::<i64>() {
+                Ok(index) => Part::Index(index.into()),
+                Err(_) => Part::from(segment.as_str()),
+            })
+            .collect();
+
+        let mut idiom = Idiom::default();
+        idiom.0 = parts;
+
+        idiom
+    }
+}
+
+impl Display for DiffPath {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0.join("."))
+    }
+}
+
+/// One operation of the diff, `value` is empty for `remove` and `from` is only set for `copy` and `move`
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiffOp {
+    pub op: DiffOpKind,
+    pub path: DiffPath,
+    pub value: Option<Value>,
+    pub from: Option<DiffPath>,
+}
+
+impl TryFrom<Value> for DiffOp {
+    type Error = TableError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        let Value::Object(mut op) = value else {
+            return Err(TableError::InvalidDiff(value.to_string()));
+        };
+
+        let kind = match op.remove("op") {
+            Some(Value::Strand(kind)) => DiffOpKind::parse(&kind.0),
+            _ => None,
+        };
+
+        let (Some(kind), Some(Value::Strand(path))) = (kind, op.remove("path")) else {
+            return Err(TableError::InvalidDiff(op.to_string()));
+        };
+
+        let from = match op.remove("from") {
+            Some(Value::Strand(from)) => Some(DiffPath::parse(&from.0)),
+            _ => None,
+        };
+
+        Ok(Self {
+            op: kind,
+            path: DiffPath::parse(&path.0),
+            value: op.remove("value"),
+            from,
+        })
+    }
+}
+
+/// The operations of one record in order
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Diff {
+    pub ops: Vec<DiffOp>,
+}
+
+impl Diff {
+    pub fn is_empty(&self) -> bool {
+        self.ops.is_empty()
+    }
+
+    /// The top level fields with an operation, sorted and without duplicates
+    pub fn changed_fields(&self) -> Vec<String> {
+        let mut fields: Vec<String> = self.ops.iter()
+            .filter(|op| op.op != DiffOpKind::Test)
+            .filter_map(|op| op.path.field().map(str::to_string))
+            .collect();
+
+        fields.sort();
+        fields.dedup();
+
+        fields
+    }
+
+    /// The operations on the field and the fields nested in it
+    pub fn field<'a>(&'a self, field: &'a str) -> impl Iterator<Item = &'a DiffOp> {
+        self.ops.iter().filter(move |op| op.path.field() == Some(field))
+    }
+}
+
+impl TryFrom<Value> for Diff {
+    type Error = TableError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        let Value::Array(ops) = value else {
+            return Err(TableError::InvalidDiff(value.to_string()));
+        };
+
+        let ops = ops.0.into_iter().map(DiffOp::try_from).collect::<Result<_, _>>()?;
+
+        Ok(Self { ops })
+    }
+}
+
+/// `UPDATE $id MERGE $content RETURN DIFF` and the record after the update
+pub(crate) async fn update<T: Table, C: Connection>(db: &Surreal<C>, id: Thing, content: Value) -> Result<(Option<T>, Diff)> {
+    let update = config::update("UPDATE $id MERGE $content RETURN DIFF")?;
+
+    let mut res = db.query(update).query("SELECT * FROM ONLY $id")
+        .bind(("id", id))
+        .bind(("content", content))
+        .await.map_err(TableError::from)?
+        .check().map_err(TableError::from)?;
+
+    let diffs: surrealdb::Value = res.take(0).map_err(TableError::from)?;
+
+    // One diff per updated record, none when the record doesn't exist
+    let diff = match diffs.into_inner() {
+        Value::Array(mut diffs) if !diffs.is_empty() => Diff::try_from(diffs.0.remove(0))?,
+        Value::Array(_) => return Ok((None, Diff::default())),
+        value => Diff::try_from(value)?,
+    };
+
+    let t: Option<T> = res.take(1).map_err(TableError::from)?;
+
+    Ok((t, diff))
+}
+
+#[cfg(test)]
+mod test {
+    use serde::{Deserialize, Serialize};
+    use surrealdb::sql::Thing as RecordId;
+    use crate::testing::db;
+    use super::*;
+
+    #[derive(Debug, Table, Serialize, Deserialize, Clone, PartialEq)]
+    #[table(name = "user")]
+    struct User {
+        id: Option<RecordId>,
+        name: String,
+        tags: Vec<String>,
+        nickname: Option<String>,
+    }
+
+    #[test]
+    fn diff_paths() {
+        let path = DiffPath::parse("/tags/0");
+
+        assert_eq!(path.0, ["tags", "0"]);
+        assert_eq!(path.field(), Some("tags"));
+        assert_eq!(path.to_idiom().to_string(), "tags[0]");
+        assert_eq!(DiffPath::parse("/a~1b/c~0d").0, ["a/b", "c~d"]);
+        assert_eq!(DiffPath::parse("").field(), None);
+    }
+
+    #[test]
+    fn parse_diff() {
+        let value = surrealdb::sql::value("[{ op: 'replace', path: '/name', value: 'b' }, { op: 'remove', path: '/tags/1' }, { op: 'move', from: '/a', path: '/b' }]").unwrap();
+        let diff = Diff::try_from(value).unwrap();
+
+        assert_eq!(diff.ops, [
+            DiffOp { op: DiffOpKind::Replace, path: DiffPath::parse("/name"), value: Some(Value::from("b")), from: None },
+            DiffOp { op: DiffOpKind::Remove, path: DiffPath::parse("/tags/1"), value: None, from: None },
+            DiffOp { op: DiffOpKind::Move, path: DiffPath::parse("/b"), value: None, from: Some(DiffPath::parse("/a")) },
+        ]);
+        assert_eq!(diff.changed_fields(), ["b", "name", "tags"]);
+
+        assert!(Diff::try_from(surrealdb::sql::value("[{ op: 'drop', path: '/name' }]").unwrap()).is_err());
+    }
+
+    #[tokio::test]
+    async fn update_with_diff() {
+        let db = db().await;
+
+        let user = User { id: None, name: "a".to_string(), tags: vec!["x".to_string()], nickname: None }.create(&db).await.unwrap().unwrap();
+
+        let (updated, diff) = User { tags: vec!["x".to_string(), "y".to_string()], nickname: Some("n".to_string()), ..user.clone() }
+            .update_with_diff(&db).await.unwrap();
+
+        assert_eq!(updated.unwrap().tags, ["x", "y"]);
+        assert_eq!(diff.changed_fields(), ["nickname", "tags"]);
+        assert_eq!(diff.field("nickname").next().unwrap().value, Some(Value::from("n")));
+        assert!(diff.field("name").next().is_none());
+
+        let (missing, diff) = User { id: Some(RecordId::from(("user", "missing"))), ..user }.update_with_diff(&db).await.unwrap();
+
+        assert_eq!(missing, None);
+        assert!(diff.is_empty());
+    }
+}
@@ -1,4 +1,5 @@
 use thiserror::Error;
+use crate::table::validate::ValidationError;
 
 #[derive(Debug, Error)]
 pub enum TableError {
@@ -7,5 +8,25 @@ pub enum TableError {
     #[error("{0}")]
     Db(#[from] surrealdb::Error),
     #[error("Empty table")]
-    EmptyTable
+    EmptyTable,
+    #[error("Record id of table `{found}` used for table `{expected}`")]
+    WrongTable { expected: String, found: String },
+    #[error("Invalid table or field name `{0}`")]
+    InvalidName(String),
+    #[error("{0}")]
+    Validation(#[from] ValidationError),
+    #[error("Record `{id}` was changed since version {version}")]
+    StaleRecord { id: String, version: u64 },
+    #[error("Table `{0}` has no tenant field")]
+    NoTenantField(String),
+    #[error("`{0}` is not an array or object record id")]
+    InvalidCompositeId(String),
+    #[error("Record `{0}` already exists")]
+    RecordExists(String),
+    #[error("CSV: {0}")]
+    Csv(String),
+    #[error("NDJSON: {0}")]
+    Ndjson(String),
+    #[error("Unexpected diff output `{0}`")]
+    InvalidDiff(String),
 }
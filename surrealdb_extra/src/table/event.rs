@@ -0,0 +1,97 @@
+//! Table events from the derive
+//!
+//! Every `#[table(event(name = "...", when = "...", then = "..."))]` becomes a `DEFINE EVENT` on the table,
+//! applied with `define_events` or together with the rest of the schema with `init_schema`.
+//! `when` is optional and defaults to `true`, `then` can be a statement or a block.
+//!
+//! # Example
+//!
+//! ```rust
+//! use serde::{Serialize, Deserialize};
+//! use surrealdb::engine::any::connect;
+//! use surrealdb::sql::Thing as RecordId;
+//! use surrealdb_extra::table::Table;
+//!
+//! #[derive(Table, Serialize, Deserialize, Clone)]
+//! #[table(name = "user", event(name = "audit", when = "$event != 'DELETE'", then = "CREATE audit SET user = $after.id, event = $event"))]
+//! struct User {
+//!     id: Option<RecordId>,
+//!     name: String
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let db = connect("mem://").await.unwrap();
+//!     db.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     User::init_schema(&db).await.unwrap();
+//!
+//!     User { id: None, name: "name".to_string() }.create(&db).await.unwrap();
+//!
+//!     let events: Vec<String> = db.query("SELECT VALUE event FROM audit").await.unwrap().take(0).unwrap();
+//!     assert_eq!(events, vec!["CREATE".to_string()]);
+//! }
+//! ```
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TableEvent {
+    pub name: &'static str,
+    pub when: &'static str,
+    pub then: &'static str,
+}
+
+impl TableEvent {
+    /// `DEFINE EVENT` on the table
+    pub fn define_event(&self, table: &str) -> String {
+        let TableEvent { name, when, then } = self;
+
+        format!("DEFINE EVENT OVERWRITE {name} ON TABLE {table} WHEN {when} THEN {then}")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde::{Deserialize, Serialize};
+    use surrealdb::sql::Thing;
+    use crate::table::Table;
+    use crate::testing::db;
+    use super::*;
+
+    #[derive(Debug, Table, Serialize, Deserialize, Clone)]
+    #[table(name = "user", event(name = "audit", then = "CREATE audit SET user = $value.id, event = $event"))]
+    #[table(event(name = "rename", when = "$event = 'UPDATE' AND $before.name != $after.name", then = "{ CREATE rename SET old = $before.name, new = $after.name }"))]
+    struct User {
+        id: Option<Thing>,
+        name: String,
+    }
+
+    #[test]
+    fn derived() {
+        assert_eq!(User::EVENTS, &[
+            TableEvent { name: "audit", when: "true", then: "CREATE audit SET user = $value.id, event = $event" },
+            TableEvent { name: "rename", when: "$event = 'UPDATE' AND $before.name != $after.name", then: "{ CREATE rename SET old = $before.name, new = $after.name }" },
+        ]);
+
+        assert_eq!(User::EVENTS[0].define_event("user"), "DEFINE EVENT OVERWRITE audit ON TABLE user WHEN true THEN CREATE audit SET user = $value.id, event = $event");
+    }
+
+    #[tokio::test]
+    async fn events_run() {
+        let db = db().await;
+
+        User::init_schema(&db).await.unwrap();
+
+        let mut user = User { id: None, name: "a".to_string() }.create(&db).await.unwrap().unwrap();
+        user.name = "b".to_string();
+        let user = user.update(&db).await.unwrap().unwrap();
+        User::delete(&db, user.id.unwrap()).await.unwrap();
+
+        let mut res = db.query("SELECT VALUE event FROM audit ORDER BY event; SELECT VALUE [old, new] FROM rename").await.unwrap();
+
+        let events: Vec<String> = res.take(0).unwrap();
+        assert_eq!(events, vec!["CREATE", "DELETE", "UPDATE"]);
+
+        let renames: Vec<Vec<String>> = res.take(1).unwrap();
+        assert_eq!(renames, vec![vec!["a".to_string(), "b".to_string()]]);
+    }
+}
@@ -0,0 +1,143 @@
+//! Eager loading of linked records
+//!
+//! Fields marked with `#[fetch]` are appended as `FETCH field` to `get_all`, `get_by_id` and `select_builder`,
+//! so the linked record is returned in place of the record id and deserialized into the nested struct.
+//!
+//! `create` and `update` return the record id of a fetched field,
+//! so the fetched struct is usually a separate read model of the same table.
+//!
+//! # Example
+//!
+//! ```rust
+//! use serde::{Serialize, Deserialize};
+//! use surrealdb::engine::any::connect;
+//! use surrealdb::sql::{Id, Thing as RecordId};
+//! use surrealdb_extra::table::Table;
+//!
+//! #[derive(Table, Serialize, Deserialize, Clone)]
+//! #[table(name = "user")]
+//! struct User {
+//!     id: Option<RecordId>,
+//!     name: String
+//! }
+//!
+//! #[derive(Table, Serialize, Deserialize, Clone)]
+//! #[table(name = "post")]
+//! struct PostWithAuthor {
+//!     id: Option<RecordId>,
+//!     #[fetch]
+//!     author: Option<User>
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let db = connect("mem://").await.unwrap();
+//!     db.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     db.query("CREATE user:john SET name = 'John'; CREATE post:1 SET author = user:john;").await.unwrap();
+//!
+//!     let post = PostWithAuthor::get_by_id(&db, RecordId::from(("post", Id::from(1)))).await.unwrap().unwrap();
+//!     assert_eq!(post.author.unwrap().name, "John");
+//! }
+//! ```
+
+use surrealdb::sql::statements::SelectStatement;
+use surrealdb::sql::{Fetch, Fetchs, Fields, Idiom, Part, Value, Values};
+
+pub(crate) fn fetchs(fields: &[&str]) -> Option<Fetchs> {
+    if fields.is_empty() {
+        return None;
+    }
+
+    let mut fetchs = Fetchs::default();
+
+    fetchs.0 = fields.iter().map(|field| {
+        let mut fetch = Fetch::default();
+        fetch.0 = Value::Idiom(Idiom::from(vec![Part::from(*field)]));
+
+        fetch
+    }).collect();
+
+    Some(fetchs)
+}
+
+/// `SELECT * FROM what FETCH fields`
+pub(crate) fn select(what: Vec<Value>, only: bool, fields: &[&str]) -> SelectStatement {
+    let mut statement = SelectStatement::default();
+
+    let mut values = Values::default();
+    values.0 = what;
+
+    statement.expr = Fields::all();
+    statement.what = values;
+    statement.only = only;
+    statement.fetch = fetchs(fields);
+
+    statement
+}
+
+#[cfg(test)]
+mod test {
+    use serde::{Deserialize, Serialize};
+    use surrealdb::sql::{Id, Thing as RecordId};
+    #[cfg(feature = "query")]
+    use surrealdb::sql::Field;
+    use crate::table::Table;
+    use crate::testing::db;
+
+    #[derive(Debug, Table, Serialize, Deserialize, Clone, PartialEq)]
+    #[table(name = "user")]
+    struct User {
+        id: Option<RecordId>,
+        name: String,
+    }
+
+    #[derive(Debug, Table, Serialize, Deserialize, Clone, PartialEq)]
+    #[table(name = "post")]
+    struct Post {
+        id: Option<RecordId>,
+        #[fetch]
+        author: Option<User>,
+        #[fetch]
+        #[serde(rename = "read_by")]
+        readers: Vec<User>,
+    }
+
+    #[test]
+    fn fetch_fields() {
+        assert_eq!(Post::FETCH_FIELDS, &["author", "read_by"]);
+        assert!(User::FETCH_FIELDS.is_empty());
+    }
+
+    #[tokio::test]
+    async fn fetched() {
+        let db = db().await;
+
+        db.query("
+            CREATE user:1 SET name = 'a';
+            CREATE user:2 SET name = 'b';
+            CREATE post:1 SET author = user:1, read_by = [user:1, user:2];
+        ").await.unwrap().check().unwrap();
+
+        let post = Post::get_by_id(&db, RecordId::from(("post", Id::from(1)))).await.unwrap().unwrap();
+        assert_eq!(post.author.unwrap().name, "a");
+        assert_eq!(post.readers.len(), 2);
+
+        let posts = Post::get_all(&db).await.unwrap();
+        assert_eq!(posts[0].readers[1].name, "b");
+    }
+
+    #[cfg(feature = "query")]
+    #[tokio::test]
+    async fn fetched_with_select_builder() {
+        let db = db().await;
+
+        db.query("
+            CREATE user:1 SET name = 'a';
+            CREATE post:1 SET author = user:1, read_by = [user:1];
+        ").await.unwrap().check().unwrap();
+
+        let posts: Vec<Post> = Post::select_builder(&db, None).field(Field::All).to_query().await.unwrap().take(0).unwrap();
+        assert_eq!(posts[0].readers[0].name, "a");
+    }
+}
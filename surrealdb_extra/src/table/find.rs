@@ -0,0 +1,167 @@
+//! Lookups by a single field
+//!
+//! Fields marked with `#[field(unique)]` get a `find_by_<field>` function that returns the first matching record,
+//! fields marked with `#[field(find_by)]` also get a `find_all_by_<field>` function that returns every matching record.
+//!
+//! # Example
+//!
+//! ```rust
+//! use serde::{Serialize, Deserialize};
+//! use surrealdb::engine::any::connect;
+//! use surrealdb::sql::Thing as RecordId;
+//! use surrealdb_extra::table::Table;
+//!
+//! #[derive(Table, Serialize, Deserialize, Clone)]
+//! #[table(name = "user")]
+//! struct User {
+//!     id: Option<RecordId>,
+//!     #[field(unique)]
+//!     email: String,
+//!     #[field(find_by)]
+//!     country: String
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let db = connect("mem://").await.unwrap();
+//!     db.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     User { id: None, email: "a@example.com".to_string(), country: "nl".to_string() }.create(&db).await.unwrap();
+//!
+//!     let user = User::find_by_email(&db, "a@example.com").await.unwrap();
+//!     assert!(user.is_some());
+//!
+//!     let users = User::find_all_by_country(&db, "nl").await.unwrap();
+//!     assert_eq!(users.len(), 1);
+//! }
+//! ```
+
+use anyhow::Result;
+use serde::Serialize;
+use surrealdb::{Connection, Surreal};
+use surrealdb::sql::{Cond, Expression, Idiom, Limit, Operator, Part, Value};
+use surrealdb::sql::statements::SelectStatement;
+use crate::table::{fetch, Table, TableError};
+
+/// `SELECT * FROM table WHERE field = $value`
+fn select<T: Table>(field: &str) -> SelectStatement {
+    let mut statement = fetch::select(vec![Value::Table(T::TABLE_NAME.into())], false, T::FETCH_FIELDS);
+
+    let mut cond = Cond::default();
+    cond.0 = Value::Expression(Box::new(Expression::Binary {
+        l: Value::Idiom(Idiom::from(vec![Part::from(field)])),
+        o: Operator::Equal,
+        r: Value::Param("value".into()),
+    }));
+
+    statement.cond = Some(cond);
+
+    statement
+}
+
+/// First record where the field equals the value
+pub async fn find_by<T: Table, C: Connection, V: Serialize + Send + 'static>(db: &Surreal<C>, field: &str, value: V) -> Result<Option<T>> {
+    let mut statement = select::<T>(field);
+
+    let mut limit = Limit::default();
+    limit.0 = Value::from(1);
+    statement.limit = Some(limit);
+
+    let found: Vec<T> = db.query(statement)
+        .bind(("value", value))
+        .await.map_err(TableError::from)?
+        .take(0).map_err(TableError::from)?;
+
+    Ok(found.into_iter().next())
+}
+
+/// Every record where the field equals the value
+pub async fn find_all_by<T: Table, C: Connection, V: Serialize + Send + 'static>(db: &Surreal<C>, field: &str, value: V) -> Result<Vec<T>> {
+    let found: Vec<T> = db.query(select::<T>(field))
+        .bind(("value", value))
+        .await.map_err(TableError::from)?
+        .take(0).map_err(TableError::from)?;
+
+    Ok(found)
+}
+
+#[cfg(test)]
+mod test {
+    use serde::Deserialize;
+    use surrealdb::engine::any::Any;
+    use surrealdb::sql::Thing;
+    use super::*;
+
+    #[derive(Debug, Table, Serialize, Deserialize, Clone, PartialEq)]
+    #[table(name = "user")]
+    struct User {
+        id: Option<Thing>,
+        #[field(unique)]
+        #[serde(rename = "mail")]
+        email: String,
+        #[field(find_by)]
+        country: String,
+        age: Option<i64>,
+    }
+
+    async fn db() -> Surreal<Any> {
+        let db = crate::testing::db().await;
+
+        User { id: None, email: "a@example.com".to_string(), country: "nl".to_string(), age: None }.create(&db).await.unwrap();
+        User { id: None, email: "b@example.com".to_string(), country: "nl".to_string(), age: None }.create(&db).await.unwrap();
+        User { id: None, email: "c@example.com".to_string(), country: "be".to_string(), age: None }.create(&db).await.unwrap();
+
+        db
+    }
+
+    #[tokio::test]
+    async fn unique() {
+        let db = db().await;
+
+        let user = User::find_by_email(&db, "b@example.com").await.unwrap().unwrap();
+        assert_eq!(user.email, "b@example.com");
+
+        assert!(User::find_by_email(&db, "d@example.com").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn find_all() {
+        let db = db().await;
+
+        assert_eq!(User::find_all_by_country(&db, "nl").await.unwrap().len(), 2);
+        assert_eq!(User::find_by_country(&db, "be").await.unwrap().unwrap().email, "c@example.com");
+        assert!(User::find_all_by_country(&db, "de").await.unwrap().is_empty());
+    }
+
+    #[cfg(feature = "query")]
+    #[tokio::test]
+    async fn delete_where() {
+        let db = db().await;
+
+        let deleted = User::delete_where(&db, crate::cond!(country = "nl")).await.unwrap();
+        assert_eq!(deleted.len(), 2);
+        assert!(deleted.iter().all(|user| user.country == "nl"));
+
+        let left = User::get_all(&db).await.unwrap();
+        assert_eq!(left.len(), 1);
+        assert_eq!(left[0].country, "be");
+    }
+
+    #[cfg(feature = "query")]
+    #[tokio::test]
+    async fn update_where() {
+        #[derive(Serialize)]
+        struct Age {
+            age: i64,
+        }
+
+        let db = db().await;
+
+        let updated = User::update_where(&db, crate::cond!(country = "nl"), Age { age: 30 }).await.unwrap();
+        assert_eq!(updated.len(), 2);
+        assert!(updated.iter().all(|user| user.age == Some(30)));
+
+        let user = User::find_by_country(&db, "be").await.unwrap().unwrap();
+        assert_eq!(user.age, None);
+    }
+}
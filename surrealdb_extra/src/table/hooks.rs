@@ -0,0 +1,147 @@
+//! Lifecycle hooks of the `Table` operations
+//!
+//! Override `before_create`, `after_create`, `before_update`, `after_update` or `before_delete` to run code around
+//! `create`, `update` and `delete`, e.g. to fill in denormalized fields or to invalidate a cache.
+//! An error returned by a hook stops the operation, an error of an `after_` hook is returned after the record was written.
+//!
+//! The `before_` hooks run before the validation of `#[table(validate)]`, so they can fill in fields that are validated.
+//! The builders don't call the hooks.
+//!
+//! Implement the hooks in an `impl Table` block with `#[table_impl]`, it is `#[async_trait]` with the `Send` bound
+//! that matches the `Table` trait, which has no `Send` bound with the `wasm` feature.
+//!
+//! # Example
+//!
+//! ```rust
+//! use anyhow::Result;
+//! use serde::{Serialize, Deserialize};
+//! use surrealdb::engine::any::connect;
+//! use surrealdb::sql::Thing as RecordId;
+//! use surrealdb::{Connection, Surreal};
+//! use surrealdb_extra::table::{table_impl, Table};
+//!
+//! #[derive(Serialize, Deserialize, Clone)]
+//! struct User {
+//!     id: Option<RecordId>,
+//!     name: String,
+//!     search_name: String
+//! }
+//!
+//! #[table_impl]
+//! impl Table for User {
+//!     const TABLE_NAME: &'static str = "user";
+//!
+//!     fn get_id(&self) -> &Option<RecordId> {
+//!         &self.id
+//!     }
+//!
+//!     fn set_id(&mut self, id: impl Into<surrealdb::sql::Id>) {
+//!         self.id = Some(Self::create_record_id(id));
+//!     }
+//!
+//!     async fn before_create<C: Connection>(&mut self, _db: &Surreal<C>) -> Result<()> {
+//!         self.search_name = self.name.to_lowercase();
+//!
+//!         Ok(())
+//!     }
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let db = connect("mem://").await.unwrap();
+//!     db.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     let user = User { id: None, name: "John".to_string(), search_name: String::new() }.create(&db).await.unwrap().unwrap();
+//!
+//!     assert_eq!(user.search_name, "john");
+//! }
+//! ```
+
+#[cfg(test)]
+mod test {
+    use anyhow::{bail, Result};
+    use serde::{Deserialize, Serialize};
+    use surrealdb::sql::{Id, Thing};
+    use surrealdb::{Connection, Surreal};
+    use crate::table::{table_impl, Table};
+    use crate::testing::db;
+
+    #[derive(Debug, Serialize, Deserialize, Clone)]
+    struct Post {
+        id: Option<Thing>,
+        title: String,
+        slug: String,
+        locked: bool,
+    }
+
+    #[table_impl]
+    impl Table for Post {
+        const TABLE_NAME: &'static str = "post";
+
+        fn get_id(&self) -> &Option<Thing> {
+            &self.id
+        }
+
+        fn set_id(&mut self, id: impl Into<Id>) {
+            self.id = Some(Self::create_record_id(id));
+        }
+
+        async fn before_create<C: Connection>(&mut self, _db: &Surreal<C>) -> Result<()> {
+            self.slug = self.title.to_lowercase().replace(' ', "-");
+
+            Ok(())
+        }
+
+        async fn after_create<C: Connection>(&self, db: &Surreal<C>) -> Result<()> {
+            db.query("UPSERT stats:posts SET count += 1").await?.check()?;
+
+            Ok(())
+        }
+
+        async fn before_update<C: Connection>(&mut self, _db: &Surreal<C>) -> Result<()> {
+            self.slug = self.title.to_lowercase().replace(' ', "-");
+
+            Ok(())
+        }
+
+        async fn after_update<C: Connection>(&self, db: &Surreal<C>) -> Result<()> {
+            db.query("UPSERT stats:posts SET updates += 1").await?.check()?;
+
+            Ok(())
+        }
+
+        async fn before_delete<C: Connection>(db: &Surreal<C>, id: &Thing) -> Result<()> {
+            let locked: Option<bool> = db.query("RETURN $id.locked").bind(("id", id.clone())).await?.take(0)?;
+
+            if locked == Some(true) {
+                bail!("Post is locked");
+            }
+
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn hooks() {
+        let db = db().await;
+
+        let mut post = Post { id: None, title: "Hello World".to_string(), slug: String::new(), locked: false }
+            .create(&db).await.unwrap().unwrap();
+
+        assert_eq!(post.slug, "hello-world");
+
+        post.title = "Hello Again".to_string();
+        post.locked = true;
+
+        let post = post.update(&db).await.unwrap().unwrap();
+
+        assert_eq!(post.slug, "hello-again");
+
+        let stats: Vec<i64> = db.query("RETURN [stats:posts.count, stats:posts.updates]").await.unwrap().take(0).unwrap();
+        assert_eq!(stats, [1, 1]);
+
+        let err = Post::delete(&db, post.id.clone().unwrap()).await.unwrap_err();
+        assert_eq!(err.to_string(), "Post is locked");
+        assert!(Post::get_by_id(&db, post.id.unwrap()).await.unwrap().is_some());
+    }
+}
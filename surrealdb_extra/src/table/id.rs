@@ -0,0 +1,414 @@
+//! Record ids that belong to a table
+//!
+//! The `Table` derive generates a `<Struct>Id` newtype for every table that can only hold a record id of that table.
+//! `get_by_id` and `delete` accept it, the plain record id of the table and strings, which are used as the id part.
+//!
+//! # Example
+//!
+//! ```rust
+//! use serde::{Serialize, Deserialize};
+//! use surrealdb::engine::any::connect;
+//! use surrealdb::sql::Thing as RecordId;
+//! use surrealdb_extra::table::Table;
+//!
+//! #[derive(Table, Serialize, Deserialize, Clone)]
+//! #[table(name = "user")]
+//! struct User {
+//!     id: Option<RecordId>,
+//!     name: String
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let db = connect("mem://").await.unwrap();
+//!     db.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     let id = UserId::new("john");
+//!
+//!     let user = User::get_by_id(&db, id).await.unwrap();
+//!
+//!     // Record ids of other tables are rejected
+//!     assert!(UserId::try_from(RecordId::from(("post", "john"))).is_err());
+//! }
+//! ```
+//!
+//! # Composite ids
+//!
+//! `#[table(id_type = "...")]` implements `CompositeId` with the type as key, so records with array or object ids
+//! are read and deleted by the key instead of a string.
+//!
+//! ```rust
+//! use serde::{Serialize, Deserialize};
+//! use surrealdb::engine::any::connect;
+//! use surrealdb::sql::Thing as RecordId;
+//! use surrealdb_extra::table::Table;
+//! use surrealdb_extra::table::id::CompositeId;
+//!
+//! #[derive(Table, Serialize, Deserialize, Clone)]
+//! #[table(name = "order", id_type = "(String, i64)")]
+//! struct Order {
+//!     id: Option<RecordId>,
+//!     total: i64
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let db = connect("mem://").await.unwrap();
+//!     db.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     let key = ("acme".to_string(), 1);
+//!     let id = Order::composite_record_id(&key).unwrap();
+//!     Order { id: Some(id), total: 10 }.create(&db).await.unwrap();
+//!
+//!     let order = Order::get_by_composite_id(&db, &key).await.unwrap().unwrap();
+//!     assert_eq!(order.get_composite_id().unwrap(), Some(key));
+//! }
+//! ```
+//!
+//! # Id strategies
+//!
+//! By default the database generates the id on `create`. With `#[table(id = "ulid" | "uuid" | "rand" | "incremental")]`
+//! `create` sets the id before the record is sent, when the record has no id yet. `incremental` counts up in the record
+//! `id_sequence:<table>`, which costs an extra query, the others are generated on the client.
+//!
+//! ```rust
+//! use serde::{Serialize, Deserialize};
+//! use surrealdb::engine::any::connect;
+//! use surrealdb::sql::{Id, Thing as RecordId};
+//! use surrealdb_extra::table::Table;
+//!
+//! #[derive(Table, Serialize, Deserialize, Clone)]
+//! #[table(name = "invoice", id = "incremental")]
+//! struct Invoice {
+//!     id: Option<RecordId>,
+//!     total: i64
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let db = connect("mem://").await.unwrap();
+//!     db.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     let first = Invoice { id: None, total: 10 }.create(&db).await.unwrap().unwrap();
+//!     let second = Invoice { id: None, total: 20 }.create(&db).await.unwrap().unwrap();
+//!
+//!     assert_eq!(first.id.unwrap().id, Id::from(1));
+//!     assert_eq!(second.id.unwrap().id, Id::from(2));
+//! }
+//! ```
+
+use ::async_trait::async_trait;
+use anyhow::Result;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use surrealdb::{Connection, Surreal};
+use surrealdb::sql::{Id, Thing, Value};
+use crate::table::{Table, TableError};
+
+/// The table the `incremental` strategy counts in
+pub const SEQUENCE_TABLE: &str = "id_sequence";
+
+/// How `create` gets the id of a new record, set with `#[table(id = "...")]`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IdStrategy {
+    /// The database generates the id
+    #[default]
+    Server,
+    Ulid,
+    Uuid,
+    Rand,
+    /// `1`, `2`, `3`... counted in `id_sequence:<table>`
+    Incremental,
+}
+
+impl IdStrategy {
+    /// A new id for the strategies that are generated on the client
+    pub fn generate(&self) -> Option<Id> {
+        match self {
+            Self::Ulid => Some(Id::ulid()),
+            Self::Uuid => Some(Id::uuid()),
+            Self::Rand => Some(Id::rand()),
+            Self::Server | Self::Incremental => None,
+        }
+    }
+}
+
+/// The next id of the table with its `ID_STRATEGY`, `None` for `Server`
+pub async fn next_id<T: Table, C: Connection>(db: &Surreal<C>) -> Result<Option<Id>> {
+    if T::ID_STRATEGY != IdStrategy::Incremental {
+        return Ok(T::ID_STRATEGY.generate());
+    }
+
+    let n: Option<i64> = db.query("UPSERT ONLY type::thing($sequence, $table) SET n += 1 RETURN VALUE n")
+        .bind(("sequence", SEQUENCE_TABLE))
+        .bind(("table", T::TABLE_NAME))
+        .await.map_err(TableError::from)?
+        .take(0).map_err(TableError::from)?;
+
+    Ok(n.map(Id::from))
+}
+
+/// Sets the next id on a record without an id
+pub(crate) async fn assign_id<T: Table, C: Connection>(t: &mut T, db: &Surreal<C>) -> Result<()> {
+    if t.get_id().is_some() {
+        return Ok(());
+    }
+
+    if let Some(id) = next_id::<T, C>(db).await? {
+        t.set_id(id);
+    }
+
+    Ok(())
+}
+
+/// Typed array or object record ids, generated by `#[table(id_type = "...")]`
+///
+/// Tuples, arrays and vecs become array ids like `order:['acme', 1]`, structs and maps become object ids
+/// like `order:{ org: 'acme', n: 1 }`.
+#[cfg_attr(not(feature = "wasm"), async_trait)]
+#[cfg_attr(feature = "wasm", async_trait(?Send))]
+pub trait CompositeId: Table {
+    type Key: Serialize + DeserializeOwned + Clone + Send + Sync + 'static;
+
+    /// Returns `TableError::InvalidCompositeId` for keys that are not serialized to an array or an object
+    fn composite_record_id(key: &Self::Key) -> Result<Thing> {
+        let id = match surrealdb::sql::to_value(key.clone()).map_err(|e| TableError::Db(e.into()))? {
+            Value::Array(array) => Id::Array(array),
+            Value::Object(object) => Id::Object(object),
+            value => return Err(TableError::InvalidCompositeId(value.to_string()).into()),
+        };
+
+        Ok(Thing::from((Self::TABLE_NAME, id)))
+    }
+
+    /// The key of the record id, `None` without an id
+    fn get_composite_id(&self) -> Result<Option<Self::Key>> {
+        let Some(thing) = self.get_id() else {
+            return Ok(None);
+        };
+
+        let value = match &thing.id {
+            Id::Array(array) => Value::Array(array.clone()),
+            Id::Object(object) => Value::Object(object.clone()),
+            id => return Err(TableError::InvalidCompositeId(id.to_string()).into()),
+        };
+
+        Ok(Some(surrealdb::sql::from_value(value).map_err(|e| TableError::Db(e.into()))?))
+    }
+
+    async fn get_by_composite_id<C: Connection>(db: &Surreal<C>, key: &Self::Key) -> Result<Option<Self>> {
+        Self::get_by_id(db, Self::composite_record_id(key)?).await
+    }
+
+    async fn delete_by_composite_id<C: Connection>(db: &Surreal<C>, key: &Self::Key) -> Result<Option<Self>> {
+        Self::delete(db, Self::composite_record_id(key)?).await
+    }
+}
+
+/// Conversion into a record id of the table `T`
+pub trait IntoTableId<T: Table> {
+    fn into_table_id(self) -> Result<Thing>;
+}
+
+impl<T: Table> IntoTableId<T> for Thing {
+    fn into_table_id(self) -> Result<Thing> {
+        check_table::<T>(self)
+    }
+}
+
+impl<T: Table> IntoTableId<T> for &Thing {
+    fn into_table_id(self) -> Result<Thing> {
+        check_table::<T>(self.clone())
+    }
+}
+
+impl<T: Table> IntoTableId<T> for String {
+    fn into_table_id(self) -> Result<Thing> {
+        Ok(Thing::from((T::TABLE_NAME, self.as_str())))
+    }
+}
+
+impl<T: Table> IntoTableId<T> for &String {
+    fn into_table_id(self) -> Result<Thing> {
+        Ok(Thing::from((T::TABLE_NAME, self.as_str())))
+    }
+}
+
+impl<T: Table> IntoTableId<T> for &str {
+    fn into_table_id(self) -> Result<Thing> {
+        Ok(Thing::from((T::TABLE_NAME, self)))
+    }
+}
+
+fn check_table<T: Table>(thing: Thing) -> Result<Thing> {
+    if thing.tb != T::TABLE_NAME {
+        return Err(TableError::WrongTable {
+            expected: T::TABLE_NAME.to_string(),
+            found: thing.tb,
+        }.into());
+    }
+
+    Ok(thing)
+}
+
+#[cfg(test)]
+mod test {
+    use serde::{Deserialize, Serialize};
+    use surrealdb::sql::Thing as RecordId;
+    use crate::testing::db;
+    use super::*;
+
+    #[derive(Debug, Table, Serialize, Deserialize, Clone, PartialEq)]
+    #[table(name = "user")]
+    struct User {
+        id: Option<RecordId>,
+        name: String,
+    }
+
+    #[derive(Debug, Table, Serialize, Deserialize, Clone, PartialEq)]
+    #[table(name = "counted", id = "incremental")]
+    struct Counted {
+        id: Option<RecordId>,
+    }
+
+    #[derive(Debug, Table, Serialize, Deserialize, Clone, PartialEq)]
+    #[table(name = "ulid", id = "ulid")]
+    struct WithUlid {
+        id: Option<RecordId>,
+    }
+
+    #[tokio::test]
+    async fn id_strategies() {
+        let db = db().await;
+
+        assert_eq!(User::ID_STRATEGY, IdStrategy::Server);
+        assert_eq!(WithUlid::ID_STRATEGY, IdStrategy::Ulid);
+
+        let ulid = WithUlid { id: None }.create(&db).await.unwrap().unwrap();
+        assert!(matches!(ulid.id.unwrap().id, Id::String(s) if s.len() == 26));
+
+        let first = Counted { id: None }.create(&db).await.unwrap().unwrap();
+        let second = Counted { id: None }.create(&db).await.unwrap().unwrap();
+        let explicit = Counted { id: Some(RecordId::from(("counted", "x"))) }.create(&db).await.unwrap().unwrap();
+
+        assert_eq!(first.id.unwrap().id, Id::from(1));
+        assert_eq!(second.id.unwrap().id, Id::from(2));
+        assert_eq!(explicit.id.unwrap().id, Id::from("x"));
+    }
+
+    #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+    struct OrderKey {
+        org: String,
+        n: i64,
+    }
+
+    #[derive(Debug, Table, Serialize, Deserialize, Clone, PartialEq)]
+    #[table(name = "order", id_type = "OrderKey")]
+    struct Order {
+        id: Option<RecordId>,
+    }
+
+    #[derive(Debug, Table, Serialize, Deserialize, Clone, PartialEq)]
+    #[table(name = "line", id_type = "(String, i64)")]
+    struct Line {
+        id: Option<RecordId>,
+    }
+
+    #[test]
+    fn composite_record_id() {
+        let key = OrderKey { org: "acme".to_string(), n: 1 };
+
+        assert_eq!(Order::composite_record_id(&key).unwrap().to_string(), "order:{ n: 1, org: 'acme' }");
+        assert_eq!(Line::composite_record_id(&("acme".to_string(), 1)).unwrap().to_string(), "line:['acme', 1]");
+
+        let order = Order { id: Some(Order::composite_record_id(&key).unwrap()) };
+        assert_eq!(order.get_composite_id().unwrap(), Some(key));
+
+        let order = Order { id: Some(RecordId::from(("order", "plain"))) };
+        assert!(order.get_composite_id().is_err());
+    }
+
+    #[tokio::test]
+    async fn get_by_composite_id() {
+        let db = db().await;
+
+        let key = ("acme".to_string(), 1);
+        let line = Line { id: Some(Line::composite_record_id(&key).unwrap()) }.create(&db).await.unwrap().unwrap();
+
+        assert_eq!(Line::get_by_composite_id(&db, &key).await.unwrap(), Some(line.clone()));
+        assert_eq!(Line::delete_by_composite_id(&db, &key).await.unwrap(), Some(line));
+        assert_eq!(Line::get_by_composite_id(&db, &key).await.unwrap(), None);
+    }
+
+    #[derive(Debug, Table, Serialize, Deserialize, Clone, PartialEq)]
+    #[table(name = "item")]
+    struct Item {
+        id: Option<RecordId>,
+        name: String,
+    }
+
+    #[derive(Debug, Table, Serialize, Deserialize, Clone, PartialEq)]
+    #[table(name = "entry", id_type = "(String, i64)")]
+    struct Entry {
+        id: Option<RecordId>,
+        name: String,
+    }
+
+    #[tokio::test]
+    async fn update_numeric_and_composite_ids() {
+        let db = db().await;
+
+        let mut item = Item { id: Some(RecordId::from(("item", Id::from(1)))), name: "one".to_string() }.create(&db).await.unwrap().unwrap();
+        item.name = "updated".to_string();
+
+        assert_eq!(item.clone().update(&db).await.unwrap(), Some(item.clone()));
+        assert_eq!(Item::get_all(&db).await.unwrap(), vec![item]);
+
+        let key = ("acme".to_string(), 1);
+        let mut entry = Entry { id: Some(Entry::composite_record_id(&key).unwrap()), name: "one".to_string() }.create(&db).await.unwrap().unwrap();
+        entry.name = "updated".to_string();
+
+        assert_eq!(entry.clone().update(&db).await.unwrap(), Some(entry.clone()));
+        assert_eq!(Entry::get_all(&db).await.unwrap(), vec![entry]);
+    }
+
+    #[test]
+    fn typed_id() {
+        let id = UserId::new("john");
+
+        assert_eq!(id.thing(), &RecordId::from(("user", "john")));
+        assert_eq!(UserId::try_from(RecordId::from(("user", "john"))).unwrap(), id);
+        assert!(UserId::try_from(RecordId::from(("post", "john"))).is_err());
+    }
+
+    #[tokio::test]
+    async fn wrong_table_is_an_error() {
+        let db = db().await;
+
+        let user = User { id: None, name: "john".to_string() }.create(&db).await.unwrap().unwrap();
+        let id = user.id.clone().unwrap();
+
+        let by_typed_id = User::get_by_id(&db, UserId::try_from(id.clone()).unwrap()).await.unwrap();
+        assert_eq!(by_typed_id, Some(user.clone()));
+
+        let by_str = User::get_by_id(&db, id.id.to_raw()).await.unwrap();
+        assert_eq!(by_str, Some(user));
+
+        let wrong = User::get_by_id(&db, RecordId::from(("post", id.id.clone()))).await;
+        assert!(wrong.is_err());
+    }
+
+    #[tokio::test]
+    async fn get_by_ids_in_order() {
+        let db = db().await;
+
+        let john = User { id: Some(RecordId::from(("user", "john"))), name: "john".to_string() }.create(&db).await.unwrap().unwrap();
+        let jane = User { id: Some(RecordId::from(("user", "jane"))), name: "jane".to_string() }.create(&db).await.unwrap().unwrap();
+
+        let users = User::get_by_ids(&db, [UserId::new("jane"), UserId::new("missing"), UserId::new("john"), UserId::new("jane")]).await.unwrap();
+        assert_eq!(users, vec![Some(jane.clone()), None, Some(john), Some(jane)]);
+
+        assert!(User::get_by_ids(&db, Vec::<UserId>::new()).await.unwrap().is_empty());
+        assert!(User::get_by_ids(&db, [RecordId::from(("user", "john")), RecordId::from(("post", "john"))]).await.is_err());
+    }
+}
@@ -0,0 +1,201 @@
+//! JSON Schema of the `Table` types
+//!
+//! With the `schemars` feature `#[table(json_schema)]` makes the `Table` derive implement `JsonSchema` for the struct and its `<Struct>Id`.
+//! Record ids don't implement `JsonSchema`, so `id`, `Thing`/`RecordId` fields, `Link<T>` and `Links<T>` are described as strings
+//! with the `record-id` format, e.g. `user:john`, with a pattern for the table when it is known.
+//! The other fields use the `JsonSchema` of their type, fields that are not `Option` are required.
+//!
+//! # Example
+//!
+//! ```rust
+//! use serde::{Serialize, Deserialize};
+//! use surrealdb::sql::Thing as RecordId;
+//! use surrealdb_extra::schemars::schema_for;
+//! use surrealdb_extra::table::Table;
+//!
+//! #[derive(Table, Serialize, Deserialize, Clone)]
+//! #[table(name = "user", json_schema)]
+//! struct User {
+//!     id: Option<RecordId>,
+//!     name: String,
+//!     age: Option<u8>
+//! }
+//!
+//! let schema = serde_json::to_value(schema_for!(User)).unwrap();
+//!
+//! assert_eq!(schema["properties"]["id"]["format"], "record-id");
+//! assert_eq!(schema["properties"]["id"]["pattern"], "^user:");
+//! assert_eq!(schema["required"], serde_json::json!(["name"]));
+//! ```
+
+use schemars::gen::SchemaGenerator;
+use schemars::schema::{ArrayValidation, InstanceType, Metadata, Schema, SchemaObject, StringValidation};
+use schemars::JsonSchema;
+use crate::table::link::{Link, Links};
+use crate::table::Table;
+
+pub const RECORD_ID_FORMAT: &str = "record-id";
+
+/// A string with the `record-id` format, only ids of `table` match the pattern
+pub fn record_id(table: Option<&str>) -> Schema {
+    let pattern = table.map(|table| format!("^{}:", regex_escape(table)));
+
+    let description = match table {
+        Some(table) => format!("Record id of the `{table}` table"),
+        None => "Record id".to_string(),
+    };
+
+    Schema::Object(SchemaObject {
+        instance_type: Some(InstanceType::String.into()),
+        format: Some(RECORD_ID_FORMAT.to_string()),
+        metadata: Some(Box::new(Metadata {
+            description: Some(description),
+            ..Default::default()
+        })),
+        string: Some(Box::new(StringValidation {
+            pattern,
+            ..Default::default()
+        })),
+        ..Default::default()
+    })
+}
+
+/// An array of record ids of `table`
+pub fn record_ids(table: Option<&str>) -> Schema {
+    array(record_id(table))
+}
+
+/// An object with the `(name, schema, required)` properties, used by the derive
+pub fn object(properties: Vec<(&str, Schema, bool)>) -> Schema {
+    let mut schema = SchemaObject {
+        instance_type: Some(InstanceType::Object.into()),
+        ..Default::default()
+    };
+
+    let object = schema.object();
+
+    for (name, property, required) in properties {
+        object.properties.insert(name.to_string(), property);
+
+        if required {
+            object.required.insert(name.to_string());
+        }
+    }
+
+    Schema::Object(schema)
+}
+
+fn array(items: Schema) -> Schema {
+    Schema::Object(SchemaObject {
+        instance_type: Some(InstanceType::Array.into()),
+        array: Some(Box::new(ArrayValidation {
+            items: Some(items.into()),
+            ..Default::default()
+        })),
+        ..Default::default()
+    })
+}
+
+fn regex_escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+
+    for c in value.chars() {
+        if "\\.+*?()|[]{}^$".contains(c) {
+            escaped.push('\\');
+        }
+
+        escaped.push(c);
+    }
+
+    escaped
+}
+
+impl<T: Table> JsonSchema for Link<T> {
+    fn schema_name() -> String {
+        format!("Link_{}", T::TABLE_NAME)
+    }
+
+    fn is_referenceable() -> bool {
+        false
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        record_id(Some(T::TABLE_NAME))
+    }
+}
+
+impl<T: Table> JsonSchema for Links<T> {
+    fn schema_name() -> String {
+        format!("Links_{}", T::TABLE_NAME)
+    }
+
+    fn is_referenceable() -> bool {
+        false
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        record_ids(Some(T::TABLE_NAME))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde::{Deserialize, Serialize};
+    use serde_json::json;
+    use surrealdb::sql::Thing as RecordId;
+    use schemars::schema_for;
+    use super::*;
+
+    #[derive(Debug, Table, Serialize, Deserialize, Clone)]
+    #[table(name = "team")]
+    struct Team {
+        id: Option<RecordId>,
+        name: String,
+    }
+
+    #[derive(Debug, Table, Serialize, Deserialize, Clone)]
+    #[table(name = "user", json_schema)]
+    struct User {
+        id: Option<RecordId>,
+        #[serde(rename = "displayName")]
+        display_name: String,
+        age: Option<u8>,
+        team: Link<Team>,
+        teams: Links<Team>,
+        friends: Vec<RecordId>,
+    }
+
+    #[test]
+    fn table_schema() {
+        let schema = serde_json::to_value(schema_for!(User)).unwrap();
+        let properties = &schema["properties"];
+
+        assert_eq!(properties["id"]["type"], "string");
+        assert_eq!(properties["id"]["format"], RECORD_ID_FORMAT);
+        assert_eq!(properties["id"]["pattern"], "^user:");
+        assert_eq!(properties["displayName"]["type"], "string");
+        assert_eq!(properties["team"]["pattern"], "^team:");
+        assert_eq!(properties["teams"]["items"]["pattern"], "^team:");
+        assert_eq!(properties["friends"]["items"]["format"], RECORD_ID_FORMAT);
+        assert!(properties["friends"]["items"].get("pattern").is_none());
+
+        assert_eq!(schema["required"], json!(["displayName", "friends", "team", "teams"]));
+    }
+
+    #[test]
+    fn table_id_schema() {
+        let schema = serde_json::to_value(schema_for!(UserId)).unwrap();
+
+        assert_eq!(schema["format"], RECORD_ID_FORMAT);
+        assert_eq!(schema["pattern"], "^user:");
+    }
+
+    #[test]
+    fn escaped_pattern() {
+        let Schema::Object(schema) = record_id(Some("a.b")) else {
+            panic!("record id is not an object schema");
+        };
+
+        assert_eq!(schema.string.unwrap().pattern.unwrap(), "^a\\.b:");
+    }
+}
@@ -0,0 +1,439 @@
+//! Record links that are loaded on demand
+//!
+//! `Link<T>` is stored as a record link and holds either the record id or the loaded record.
+//! It deserializes from both, so the same field works with and without `#[fetch]`.
+//!
+//! # Example
+//!
+//! ```rust
+//! use serde::{Serialize, Deserialize};
+//! use surrealdb::engine::any::connect;
+//! use surrealdb::sql::Thing as RecordId;
+//! use surrealdb_extra::table::Table;
+//! use surrealdb_extra::table::link::Link;
+//!
+//! #[derive(Table, Serialize, Deserialize, Clone)]
+//! #[table(name = "user")]
+//! struct User {
+//!     id: Option<RecordId>,
+//!     name: String
+//! }
+//!
+//! #[derive(Table, Serialize, Deserialize, Clone)]
+//! #[table(name = "post")]
+//! struct Post {
+//!     id: Option<RecordId>,
+//!     author: Link<User>
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let db = connect("mem://").await.unwrap();
+//!     db.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     let user = User { id: None, name: "John".to_string() }.create(&db).await.unwrap().unwrap();
+//!
+//!     // Stored as `author: user:...`
+//!     let post = Post { id: None, author: Link::from(user) }.create(&db).await.unwrap().unwrap();
+//!
+//!     let mut author = post.author;
+//!     let user = author.load(&db).await.unwrap().unwrap();
+//!
+//!     assert_eq!(user.name, "John");
+//! }
+//! ```
+//!
+//! `Links<T>` is the same for arrays of record links, `load_all` selects every record that is not loaded yet with one query.
+//!
+//! ```rust
+//! use serde::{Serialize, Deserialize};
+//! use surrealdb::engine::any::connect;
+//! use surrealdb::sql::Thing as RecordId;
+//! use surrealdb_extra::table::Table;
+//! use surrealdb_extra::table::link::Links;
+//!
+//! #[derive(Table, Serialize, Deserialize, Clone)]
+//! #[table(name = "tag")]
+//! struct Tag {
+//!     id: Option<RecordId>,
+//!     name: String
+//! }
+//!
+//! #[derive(Table, Serialize, Deserialize, Clone)]
+//! #[table(name = "post")]
+//! struct Post {
+//!     id: Option<RecordId>,
+//!     tags: Links<Tag>
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let db = connect("mem://").await.unwrap();
+//!     db.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     let rust = Tag { id: None, name: "rust".to_string() }.create(&db).await.unwrap().unwrap();
+//!     let db_tag = Tag { id: None, name: "db".to_string() }.create(&db).await.unwrap().unwrap();
+//!
+//!     // Stored as `tags: [tag:..., tag:...]`
+//!     let post = Post { id: None, tags: Links::from(vec![rust, db_tag]) }.create(&db).await.unwrap().unwrap();
+//!
+//!     let mut tags = post.tags;
+//!     let names: Vec<&str> = tags.load_all(&db).await.unwrap().iter().map(|t| t.name.as_str()).collect();
+//!
+//!     assert_eq!(names, ["rust", "db"]);
+//! }
+//! ```
+
+use anyhow::Result;
+use std::collections::HashMap;
+use std::fmt::Formatter;
+use std::marker::PhantomData;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde::de::{DeserializeSeed, Error as DeError, IntoDeserializer, MapAccess, Visitor};
+use serde::de::value::MapAccessDeserializer;
+use serde::ser::Error as SerError;
+use surrealdb::sql::{Id, Thing};
+use surrealdb::{Connection, Surreal};
+use crate::table::Table;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Link<T: Table> {
+    Id(Thing),
+    Loaded(T),
+}
+
+impl<T: Table> Link<T> {
+    /// Record id of the link, `None` for a loaded record without an id
+    pub fn id(&self) -> Option<&Thing> {
+        match self {
+            Link::Id(id) => Some(id),
+            Link::Loaded(t) => t.get_id().as_ref(),
+        }
+    }
+
+    pub fn is_loaded(&self) -> bool {
+        matches!(self, Link::Loaded(_))
+    }
+
+    pub fn loaded(&self) -> Option<&T> {
+        match self {
+            Link::Id(_) => None,
+            Link::Loaded(t) => Some(t),
+        }
+    }
+
+    pub fn into_loaded(self) -> Option<T> {
+        match self {
+            Link::Id(_) => None,
+            Link::Loaded(t) => Some(t),
+        }
+    }
+
+    /// Selects the record if it is not loaded yet, returns `None` if the linked record doesn't exist
+    pub async fn load<C: Connection>(&mut self, db: &Surreal<C>) -> Result<Option<&T>> {
+        if let Link::Id(id) = self {
+            match T::get_by_id(db, id.clone()).await? {
+                Some(t) => *self = Link::Loaded(t),
+                None => return Ok(None),
+            }
+        }
+
+        Ok(self.loaded())
+    }
+}
+
+impl<T: Table> From<Thing> for Link<T> {
+    fn from(id: Thing) -> Self {
+        Link::Id(id)
+    }
+}
+
+impl<T: Table> From<T> for Link<T> {
+    fn from(t: T) -> Self {
+        Link::Loaded(t)
+    }
+}
+
+/// An array of record links, each is either the record id or the loaded record
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(transparent, bound = "")]
+pub struct Links<T: Table>(pub Vec<Link<T>>);
+
+impl<T: Table> Default for Links<T> {
+    fn default() -> Self {
+        Self(vec![])
+    }
+}
+
+impl<T: Table> Links<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record ids of the links, loaded records without an id are left out
+    pub fn ids(&self) -> Vec<&Thing> {
+        self.0.iter().filter_map(Link::id).collect()
+    }
+
+    /// True when every link is loaded
+    pub fn is_loaded(&self) -> bool {
+        self.0.iter().all(Link::is_loaded)
+    }
+
+    /// The loaded records in the order of the links
+    pub fn loaded(&self) -> Vec<&T> {
+        self.0.iter().filter_map(Link::loaded).collect()
+    }
+
+    pub fn into_loaded(self) -> Vec<T> {
+        self.0.into_iter().filter_map(Link::into_loaded).collect()
+    }
+
+    pub fn push(&mut self, link: impl Into<Link<T>>) {
+        self.0.push(link.into());
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Link<T>> {
+        self.0.iter()
+    }
+
+    /// Selects the records that are not loaded yet with one query, links to missing records stay ids
+    pub async fn load_all<C: Connection>(&mut self, db: &Surreal<C>) -> Result<Vec<&T>> where T: Clone {
+        let ids: Vec<Thing> = self.0.iter()
+            .filter_map(|link| match link {
+                Link::Id(id) => Some(id.clone()),
+                Link::Loaded(_) => None,
+            })
+            .collect();
+
+        let records: HashMap<String, T> = T::get_by_ids(db, ids.clone()).await?.into_iter()
+            .zip(ids)
+            .filter_map(|(record, id)| Some((id.to_string(), record?)))
+            .collect();
+
+        for link in &mut self.0 {
+            if let Link::Id(id) = link {
+                if let Some(t) = records.get(&id.to_string()) {
+                    *link = Link::Loaded(t.clone());
+                }
+            }
+        }
+
+        Ok(self.loaded())
+    }
+}
+
+impl<T: Table> From<Vec<Thing>> for Links<T> {
+    fn from(ids: Vec<Thing>) -> Self {
+        Self(ids.into_iter().map(Link::Id).collect())
+    }
+}
+
+impl<T: Table> From<Vec<T>> for Links<T> {
+    fn from(records: Vec<T>) -> Self {
+        Self(records.into_iter().map(Link::Loaded).collect())
+    }
+}
+
+impl<T: Table> FromIterator<Link<T>> for Links<T> {
+    fn from_iter<I: IntoIterator<Item = Link<T>>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl<T: Table> IntoIterator for Links<T> {
+    type Item = Link<T>;
+    type IntoIter = std::vec::IntoIter<Link<T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<T: Table> Serialize for Link<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.id() {
+            Some(id) => id.serialize(serializer),
+            None => Err(S::Error::custom(format!("Link to `{}` has no record id", T::TABLE_NAME))),
+        }
+    }
+}
+
+impl<'de, T: Table> Deserialize<'de> for Link<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(LinkVisitor(PhantomData))
+    }
+}
+
+/// Both a record id and a record are maps, a record id is the only one starting with `tb` because records always have an `id`
+struct LinkVisitor<T>(PhantomData<T>);
+
+impl<'de, T: Table> Visitor<'de> for LinkVisitor<T> {
+    type Value = Link<T>;
+
+    fn expecting(&self, formatter: &mut Formatter) -> std::fmt::Result {
+        formatter.write_str("a record id or a record")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let Some(key) = map.next_key::<String>()? else {
+            return T::deserialize(MapAccessDeserializer::new(map)).map(Link::Loaded);
+        };
+
+        if key != "tb" {
+            return T::deserialize(MapAccessDeserializer::new(FirstKey { key: Some(key), map })).map(Link::Loaded);
+        }
+
+        let tb: String = map.next_value()?;
+
+        match map.next_key::<String>()? {
+            Some(key) if key == "id" => {
+                let id: Id = map.next_value()?;
+
+                Ok(Link::Id(Thing::from((tb.as_str(), id))))
+            }
+            _ => Err(A::Error::missing_field("id")),
+        }
+    }
+}
+
+/// Gives back the key that was read to tell a record id and a record apart
+struct FirstKey<A> {
+    key: Option<String>,
+    map: A,
+}
+
+impl<'de, A: MapAccess<'de>> MapAccess<'de> for FirstKey<A> {
+    type Error = A::Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error> {
+        match self.key.take() {
+            Some(key) => seed.deserialize(IntoDeserializer::<A::Error>::into_deserializer(key)).map(Some),
+            None => self.map.next_key_seed(seed),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Self::Error> {
+        self.map.next_value_seed(seed)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde::{Deserialize, Serialize};
+    use surrealdb::sql::{Id, Thing as RecordId};
+    use crate::testing::db;
+    use super::*;
+
+    #[derive(Debug, Table, Serialize, Deserialize, Clone, PartialEq)]
+    #[table(name = "user")]
+    struct User {
+        id: Option<RecordId>,
+        name: String,
+    }
+
+    #[derive(Debug, Table, Serialize, Deserialize, Clone, PartialEq)]
+    #[table(name = "post")]
+    struct Post {
+        id: Option<RecordId>,
+        author: Link<User>,
+    }
+
+    #[derive(Debug, Table, Serialize, Deserialize, Clone, PartialEq)]
+    #[table(name = "post")]
+    struct FetchedPost {
+        id: Option<RecordId>,
+        #[fetch]
+        author: Link<User>,
+    }
+
+    #[tokio::test]
+    async fn stored_as_link() {
+        let db = db().await;
+
+        let user = User { id: None, name: "a".to_string() }.create(&db).await.unwrap().unwrap();
+        let user_id = user.id.clone().unwrap();
+
+        let post = Post { id: None, author: Link::from(user.clone()) }.create(&db).await.unwrap().unwrap();
+        assert_eq!(post.author, Link::Id(user_id.clone()));
+
+        let mut res = db.query("SELECT VALUE author.name FROM ONLY $post").bind(("post", post.id.clone().unwrap())).await.unwrap();
+        let name: Option<String> = res.take(0).unwrap();
+        assert_eq!(name.as_deref(), Some("a"));
+
+        let mut author = post.author.clone();
+        assert_eq!(author.load(&db).await.unwrap(), Some(&user));
+        assert!(author.is_loaded());
+
+        let fetched = FetchedPost::get_by_id(&db, post.id.unwrap()).await.unwrap().unwrap();
+        assert_eq!(fetched.author, Link::Loaded(user));
+    }
+
+    #[derive(Debug, Table, Serialize, Deserialize, Clone, PartialEq)]
+    #[table(name = "team")]
+    struct Team {
+        id: Option<RecordId>,
+        members: Links<User>,
+    }
+
+    #[derive(Debug, Table, Serialize, Deserialize, Clone, PartialEq)]
+    #[table(name = "team")]
+    struct FetchedTeam {
+        id: Option<RecordId>,
+        #[fetch]
+        members: Links<User>,
+    }
+
+    #[tokio::test]
+    async fn links() {
+        let db = db().await;
+
+        let a = User { id: None, name: "a".to_string() }.create(&db).await.unwrap().unwrap();
+        let b = User { id: None, name: "b".to_string() }.create(&db).await.unwrap().unwrap();
+        let missing = RecordId::from(("user", "missing"));
+
+        let mut members = Links::from(vec![a.id.clone().unwrap(), missing.clone()]);
+        members.push(b.clone());
+
+        let team = Team { id: None, members }.create(&db).await.unwrap().unwrap();
+        assert_eq!(team.members.ids(), [a.id.as_ref().unwrap(), &missing, b.id.as_ref().unwrap()]);
+        assert!(!team.members.iter().any(Link::is_loaded));
+
+        let mut members = team.members.clone();
+        assert_eq!(members.load_all(&db).await.unwrap(), [&a, &b]);
+        assert!(!members.is_loaded());
+        assert_eq!(members.0[1], Link::Id(missing));
+
+        let team = Team { id: None, members: Links::from(vec![a.clone(), b.clone()]) }.create(&db).await.unwrap().unwrap();
+        let fetched = FetchedTeam::get_by_id(&db, team.id.unwrap()).await.unwrap().unwrap();
+        assert_eq!(fetched.members.loaded(), [&a, &b]);
+        assert_eq!(FetchedTeam::meta().relation("members").map(|r| (r.table, r.many)), Some(("user", true)));
+    }
+
+    #[tokio::test]
+    async fn missing_record() {
+        let db = db().await;
+
+        let mut link: Link<User> = Link::from(RecordId::from(("user", Id::from("missing"))));
+
+        assert!(link.load(&db).await.unwrap().is_none());
+        assert!(!link.is_loaded());
+    }
+
+    #[tokio::test]
+    async fn loaded_without_id() {
+        let db = db().await;
+
+        let post = Post { id: None, author: Link::from(User { id: None, name: "a".to_string() }) };
+
+        assert!(post.create(&db).await.is_err());
+    }
+}
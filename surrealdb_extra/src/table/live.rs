@@ -0,0 +1,214 @@
+//! Typed live queries of a table
+//!
+//! `T::live(db)` starts a `LIVE SELECT` on the table and returns a `LiveQuery<T>` that yields a `LiveEvent<T>` for every change.
+//! Dropping the handle kills the live query.
+//!
+//! The remote engines drop every live query when the connection is lost, the handle starts a new live query
+//! once the connection is back. Changes made while the connection was down are not delivered.
+//!
+//! # Example
+//!
+//! ```rust
+//! use futures::StreamExt;
+//! use serde::{Serialize, Deserialize};
+//! use surrealdb::engine::any::connect;
+//! use surrealdb::sql::Thing as RecordId;
+//! use surrealdb_extra::table::Table;
+//! use surrealdb_extra::table::live::LiveEvent;
+//!
+//! #[derive(Table, Serialize, Deserialize, Clone)]
+//! #[table(name = "user")]
+//! struct User {
+//!     id: Option<RecordId>,
+//!     name: String
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let db = connect("mem://").await.unwrap();
+//!     db.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     let mut live = User::live(&db).await.unwrap();
+//!
+//!     User { id: None, name: "name".to_string() }.create(&db).await.unwrap();
+//!
+//!     match live.next().await.unwrap().unwrap() {
+//!         LiveEvent::Create(user) => assert_eq!(user.name, "name"),
+//!         _ => unreachable!(),
+//!     }
+//! }
+//! ```
+
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+use anyhow::Result;
+use futures::stream::BoxStream;
+use futures::{Stream, StreamExt};
+use surrealdb::method::Stream as LiveStream;
+use surrealdb::{Action, Connection, Notification, Surreal};
+use crate::table::{Table, TableError};
+
+/// Delay before the first attempt to start the live query again, doubled after every failed attempt
+const RESUBSCRIBE_DELAY: Duration = Duration::from_millis(100);
+
+const MAX_RESUBSCRIBE_DELAY: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LiveEvent<T> {
+    Create(T),
+    Update(T),
+    /// The record as it was before it was deleted
+    Delete(T),
+}
+
+impl<T> LiveEvent<T> {
+    pub fn record(&self) -> &T {
+        match self {
+            Self::Create(record) | Self::Update(record) | Self::Delete(record) => record,
+        }
+    }
+
+    pub fn into_record(self) -> T {
+        match self {
+            Self::Create(record) | Self::Update(record) | Self::Delete(record) => record,
+        }
+    }
+
+    fn from_notification(notification: Notification<T>) -> Option<Self> {
+        match notification.action {
+            Action::Create => Some(Self::Create(notification.data)),
+            Action::Update => Some(Self::Update(notification.data)),
+            Action::Delete => Some(Self::Delete(notification.data)),
+            _ => None,
+        }
+    }
+}
+
+/// Handle of a live query, the live query is killed when the handle is dropped
+#[must_use = "streams do nothing unless you poll them"]
+pub struct LiveQuery<T> {
+    events: BoxStream<'static, Result<LiveEvent<T>>>,
+}
+
+struct State<C: Connection, T> {
+    db: Surreal<C>,
+    stream: Option<LiveStream<Vec<T>>>,
+}
+
+impl<T: Table + Unpin> LiveQuery<T> {
+    /// Starts the live query, fails when the engine does not support live queries
+    pub async fn start<C: Connection>(db: &Surreal<C>) -> Result<Self> {
+        let stream = subscribe::<C, T>(db).await?;
+
+        let state = State { db: db.clone(), stream: Some(stream) };
+
+        let events = futures::stream::unfold(state, |mut state| async move {
+            loop {
+                let stream = match state.stream.as_mut() {
+                    Some(stream) => stream,
+                    None => {
+                        state.stream = Some(resubscribe::<C, T>(&state.db).await);
+
+                        continue;
+                    }
+                };
+
+                match stream.next().await {
+                    Some(Ok(notification)) => {
+                        if let Some(event) = LiveEvent::from_notification(notification) {
+                            return Some((Ok(event), state));
+                        }
+                    }
+                    Some(Err(e)) => return Some((Err(TableError::from(e).into()), state)),
+                    None => state.stream = None,
+                }
+            }
+        });
+
+        Ok(Self { events: events.boxed() })
+    }
+}
+
+impl<T> Stream for LiveQuery<T> {
+    type Item = Result<LiveEvent<T>>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.events.poll_next_unpin(cx)
+    }
+}
+
+async fn subscribe<C: Connection, T: Table + Unpin>(db: &Surreal<C>) -> Result<LiveStream<Vec<T>>> {
+    let stream: LiveStream<Vec<T>> = db.select(T::TABLE_NAME).live().await.map_err(TableError::from)?;
+
+    Ok(stream)
+}
+
+/// Starts the live query again until it succeeds
+async fn resubscribe<C: Connection, T: Table + Unpin>(db: &Surreal<C>) -> LiveStream<Vec<T>> {
+    let mut delay = RESUBSCRIBE_DELAY;
+
+    loop {
+        tokio::time::sleep(delay).await;
+
+        if let Ok(stream) = subscribe::<C, T>(db).await {
+            return stream;
+        }
+
+        delay = (delay * 2).min(MAX_RESUBSCRIBE_DELAY);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde::{Deserialize, Serialize};
+    use surrealdb::engine::any::Any;
+    use surrealdb::sql::{Part, Thing, Value};
+    use crate::testing::db;
+    use super::*;
+
+    #[derive(Debug, Table, Serialize, Deserialize, Clone, PartialEq)]
+    #[table(name = "user")]
+    struct User {
+        id: Option<Thing>,
+        name: String,
+    }
+
+    async fn lives(db: &Surreal<Any>) -> usize {
+        let info: surrealdb::Value = db.query("INFO FOR TABLE user").await.unwrap().take(0).unwrap();
+
+        match info.into_inner().pick(&[Part::from("lives")]) {
+            Value::Object(lives) => lives.len(),
+            _ => 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn events() {
+        let db = db().await;
+
+        let mut live = User::live(&db).await.unwrap();
+
+        let mut user = User { id: None, name: "a".to_string() }.create(&db).await.unwrap().unwrap();
+        user.name = "b".to_string();
+        let user = user.update(&db).await.unwrap().unwrap();
+        User::delete(&db, user.id.clone().unwrap()).await.unwrap();
+
+        assert!(matches!(live.next().await.unwrap().unwrap(), LiveEvent::Create(u) if u.name == "a"));
+        assert!(matches!(live.next().await.unwrap().unwrap(), LiveEvent::Update(u) if u.name == "b"));
+        assert_eq!(live.next().await.unwrap().unwrap(), LiveEvent::Delete(user));
+    }
+
+    #[tokio::test]
+    async fn kill_on_drop() {
+        let db = db().await;
+
+        let live = User::live(&db).await.unwrap();
+        assert_eq!(lives(&db).await, 1);
+
+        drop(live);
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        assert_eq!(lives(&db).await, 0);
+    }
+}
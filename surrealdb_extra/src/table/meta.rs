@@ -0,0 +1,219 @@
+//! Runtime metadata of a table
+//!
+//! The `Table` derive generates `T::meta()` from the struct and its attributes:
+//! the fields with their Rust types, the indexes from `#[table(index(...))]`
+//! and the relations of `Link<T>` and `#[fetch]` fields.
+//!
+//! # Example
+//!
+//! ```rust
+//! use serde::{Serialize, Deserialize};
+//! use surrealdb::sql::Thing as RecordId;
+//! use surrealdb_extra::table::Table;
+//! use surrealdb_extra::table::link::Link;
+//!
+//! #[derive(Table, Serialize, Deserialize, Clone)]
+//! #[table(name = "user", index(name = "user_email", fields = ["email"], unique))]
+//! struct User {
+//!     id: Option<RecordId>,
+//!     email: String,
+//!     #[serde(rename = "displayName")]
+//!     display_name: Option<String>,
+//!     friends: Vec<Link<User>>
+//! }
+//!
+//! let meta = User::meta();
+//!
+//! assert_eq!(meta.name, "user");
+//! assert_eq!(meta.field("displayName").unwrap().rust_type, "Option<String>");
+//! assert!(meta.indexes[0].unique);
+//! assert_eq!(meta.relations[0].table, "user");
+//! ```
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TableMeta {
+    pub name: &'static str,
+    pub id: IdMeta,
+    pub fields: &'static [FieldMeta],
+    pub indexes: &'static [IndexMeta],
+    pub relations: &'static [RelationMeta],
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IdMeta {
+    pub field: &'static str,
+    pub rust_type: &'static str,
+}
+
+/// A field as it is stored in the database, `name` follows `#[serde(rename = "...")]`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FieldMeta {
+    pub name: &'static str,
+    pub rust_type: &'static str,
+    pub optional: bool,
+    pub fetch: bool,
+    /// Fields of a `#[field(flatten)]` field, from the `Embedded` derive of its type
+    pub nested: &'static [FieldMeta],
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IndexMeta {
+    pub name: &'static str,
+    pub fields: &'static [&'static str],
+    pub unique: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RelationMeta {
+    pub field: &'static str,
+    pub table: &'static str,
+    pub many: bool,
+}
+
+impl TableMeta {
+    /// Metadata with only the table name, used by `Table` implementations that are not derived
+    pub const fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            id: IdMeta {
+                field: "id",
+                rust_type: "",
+            },
+            fields: &[],
+            indexes: &[],
+            relations: &[],
+        }
+    }
+
+    pub fn field(&self, name: &str) -> Option<&'static FieldMeta> {
+        self.fields.iter().find(|f| f.name == name)
+    }
+
+    pub fn field_names(&self) -> impl Iterator<Item = &'static str> {
+        self.fields.iter().map(|f| f.name)
+    }
+
+    pub fn relation(&self, field: &str) -> Option<&'static RelationMeta> {
+        self.relations.iter().find(|r| r.field == field)
+    }
+
+    /// The nested fields of the flattened fields with their dotted path, e.g. `address.city`
+    pub fn nested_fields(&self) -> Vec<(String, &'static FieldMeta)> {
+        let mut nested = vec![];
+
+        for field in self.fields {
+            push_nested(field.name, field.nested, &mut nested);
+        }
+
+        nested
+    }
+
+    /// Every field and nested field as a path
+    pub fn paths(&self) -> Vec<String> {
+        let mut paths: Vec<String> = self.field_names().map(str::to_string).collect();
+
+        paths.extend(self.nested_fields().into_iter().map(|(path, _)| path));
+
+        paths
+    }
+}
+
+fn push_nested(prefix: &str, fields: &'static [FieldMeta], nested: &mut Vec<(String, &'static FieldMeta)>) {
+    for field in fields {
+        let path = format!("{prefix}.{}", field.name);
+
+        nested.push((path.clone(), field));
+
+        push_nested(&path, field.nested, nested);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde::{Deserialize, Serialize};
+    use surrealdb::sql::Thing as RecordId;
+    use crate::table::link::Link;
+    use crate::table::{Embedded, Table};
+    use super::*;
+
+    #[derive(Debug, Table, Serialize, Deserialize, Clone, PartialEq)]
+    #[table(name = "user")]
+    struct User {
+        id: Option<RecordId>,
+        name: String,
+    }
+
+    #[derive(Debug, Table, Serialize, Deserialize, Clone, PartialEq)]
+    #[table(name = "post", index(name = "post_slug", fields = ["slug"], unique), index(name = "post_author_date", fields = ["author", "date"]))]
+    struct Post {
+        id: Option<RecordId>,
+        slug: String,
+        #[serde(rename = "createdAt")]
+        date: Option<String>,
+        author: Link<User>,
+        readers: Option<Vec<Link<User>>>,
+        #[fetch]
+        editor: Option<User>,
+    }
+
+    #[test]
+    fn derived() {
+        let meta = Post::meta();
+
+        assert_eq!(meta.name, "post");
+        assert_eq!(meta.id, IdMeta { field: "id", rust_type: "Option<RecordId>" });
+        assert_eq!(meta.field_names().collect::<Vec<_>>(), vec!["id", "slug", "createdAt", "author", "readers", "editor"]);
+        assert_eq!(meta.field("createdAt"), Some(&FieldMeta { name: "createdAt", rust_type: "Option<String>", optional: true, fetch: false, nested: &[] }));
+        assert!(meta.field("editor").unwrap().fetch);
+
+        assert_eq!(meta.indexes, &[
+            IndexMeta { name: "post_slug", fields: &["slug"], unique: true },
+            IndexMeta { name: "post_author_date", fields: &["author", "date"], unique: false },
+        ]);
+
+        assert_eq!(meta.relations, &[
+            RelationMeta { field: "author", table: "user", many: false },
+            RelationMeta { field: "readers", table: "user", many: true },
+            RelationMeta { field: "editor", table: "user", many: false },
+        ]);
+    }
+
+    #[test]
+    fn without_attributes() {
+        let meta = User::meta();
+
+        assert_eq!(meta.fields.len(), 2);
+        assert!(meta.indexes.is_empty());
+        assert!(meta.relations.is_empty());
+    }
+
+    #[derive(Debug, Embedded, Serialize, Deserialize, Clone, PartialEq)]
+    struct Geo {
+        lat: f64,
+        lng: f64,
+    }
+
+    #[derive(Debug, Embedded, Serialize, Deserialize, Clone, PartialEq)]
+    struct Address {
+        city: String,
+        #[field(flatten)]
+        geo: Option<Geo>,
+    }
+
+    #[derive(Debug, Table, Serialize, Deserialize, Clone, PartialEq)]
+    #[table(name = "shop")]
+    struct Shop {
+        id: Option<RecordId>,
+        #[field(flatten)]
+        address: Address,
+    }
+
+    #[test]
+    fn flattened() {
+        let meta = Shop::meta();
+
+        assert_eq!(meta.paths(), ["id", "address", "address.city", "address.geo", "address.geo.lat", "address.geo.lng"]);
+        assert_eq!(meta.nested_fields()[1].1.rust_type, "Option<Geo>");
+        assert!(meta.field("id").unwrap().nested.is_empty());
+    }
+}
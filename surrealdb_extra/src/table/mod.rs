@@ -52,21 +52,118 @@
 //!     Ok(())
 //! }
 //! ```
+//!
+//! # WASM
+//!
+//! The table and the query builder layers compile for `wasm32-unknown-unknown` with the default features,
+//! e.g. for a frontend that talks to SurrealDB over HTTP or WebSocket.
+//! With the `wasm` feature the async methods of `Table` don't require `Send` futures,
+//! so hooks can hold browser values that are not `Send`. Implement them with `#[table_impl]`,
+//! it picks the matching `async_trait` for the feature. `Instant` comes from `web-time`, `std::time::Instant` panics in the browser.
+//!
+//! The features that spawn tokio tasks or block (`retry`, `retention`, `pipeline`, `live`, `deadline` and `blocking`) need a tokio runtime
+//! and are not meant for the browser.
 
 pub mod err;
+pub mod cache;
+pub mod counter;
+pub mod id;
+pub mod fetch;
+pub mod link;
+pub mod meta;
+pub mod computed;
+pub mod default;
+pub mod find;
+pub mod retention;
+pub mod vector;
+pub mod event;
+pub mod permissions;
+pub mod path;
+pub mod write;
+pub mod validate;
+pub mod hooks;
+pub mod version;
+pub mod changeset;
+pub mod builder;
+pub mod tenant;
+pub mod store;
+pub mod serde_helpers;
+pub mod diff;
+pub mod database;
+
+pub(crate) mod config;
+
+#[cfg_attr(docsrs, doc(cfg(feature = "query")))]
+#[cfg(feature = "query")]
+pub mod schema;
+
+#[cfg_attr(docsrs, doc(cfg(feature = "query")))]
+#[cfg(feature = "query")]
+pub mod projection;
+
+#[cfg_attr(docsrs, doc(cfg(feature = "retry")))]
+#[cfg(feature = "retry")]
+pub mod retry;
+
+#[cfg_attr(docsrs, doc(cfg(feature = "live")))]
+#[cfg(feature = "live")]
+pub mod live;
+
+#[cfg_attr(docsrs, doc(cfg(feature = "csv")))]
+#[cfg(feature = "csv")]
+pub mod csv;
+
+#[cfg_attr(docsrs, doc(cfg(feature = "ndjson")))]
+#[cfg(feature = "ndjson")]
+pub mod ndjson;
+
+#[cfg_attr(docsrs, doc(cfg(feature = "schemars")))]
+#[cfg(feature = "schemars")]
+pub mod json_schema;
 
 #[cfg(feature = "derive")]
 pub use ::surrealdb_extra_derive::Table;
 
+#[cfg(feature = "derive")]
+pub use ::surrealdb_extra_derive::Embedded;
+
+#[cfg(feature = "derive")]
+pub use ::surrealdb_extra_derive::table_impl;
+
+use std::collections::HashMap;
 use anyhow::Result;
 use ::async_trait::async_trait;
 use ::serde::de::DeserializeOwned;
 use ::serde::Serialize;
-use ::surrealdb::{Connection, Surreal};
+use ::surrealdb::{Connection, RecordId, Surreal};
 pub use crate::table::err::TableError;
+use crate::table::counter::CounterCache;
+use crate::table::computed::ComputedField;
+use crate::table::default::DefaultField;
+use crate::table::retention::{Retention, RetentionPolicy, RetentionReport};
+use crate::table::vector::VectorIndex;
+use crate::table::event::TableEvent;
+use crate::table::permissions::TablePermissions;
+use crate::table::id::{IdStrategy, IntoTableId};
+use crate::table::changeset::Changeset;
+use crate::table::meta::TableMeta;
+use crate::table::write::write_content;
+use crate::table::validate::ValidationError;
+use crate::table::diff::Diff;
+use crate::table::database::Database;
 
-#[cfg(feature = "query")]
-use surrealdb::sql::Thing as RecordId;
+
+#[cfg(feature = "retry")]
+use crate::table::retry::RetryPolicy;
+
+#[cfg(feature = "live")]
+use crate::table::live::LiveQuery;
+
+#[cfg(feature = "search")]
+use crate::search::SearchConfig;
+
+#[cfg(feature = "outbox")]
+use crate::outbox::{Outbox, OutboxEvent};
 
 #[cfg(feature = "query")]
 use crate::query::{
@@ -74,14 +171,72 @@ use crate::query::{
     update::UpdateBuilder,
     create::CreateBuilder,
     statement::StatementBuilder,
-    states::{FilledWhat, NoFields, NoCond, FilledData}
+    states::{FilledWhat, NoFields, NoCond, FilledData},
+    parsing::cond::ExtraCond,
+    scope::{Scoped, STATEMENT_INDEX}
 };
 
-#[async_trait]
+#[cfg(feature = "query")]
+use crate::table::schema::SchemaDiff;
+
+#[cfg(feature = "query")]
+use ::surrealdb::sql::{statements::{CreateStatement, DeleteStatement, UpdateStatement}, Data, Output};
+
+#[cfg_attr(not(feature = "wasm"), async_trait)]
+#[cfg_attr(feature = "wasm", async_trait(?Send))]
 pub trait Table: Serialize + DeserializeOwned + Send + Sync where Self: 'static
 {
     const TABLE_NAME: &'static str;
 
+    /// Set with `#[table(counter_cache(field = "...", on = Child, via = "..."))]`
+    const COUNTER_CACHES: &'static [CounterCache] = &[];
+
+    /// Fields marked with `#[fetch]`
+    const FETCH_FIELDS: &'static [&'static str] = &[];
+
+    /// Set with `#[field(computed = "...")]` on `Computed<T>` fields
+    const COMPUTED_FIELDS: &'static [ComputedField] = &[];
+
+    /// Set with `#[field(default = "...")]`
+    const DEFAULT_FIELDS: &'static [DefaultField] = &[];
+
+    /// Set with `#[table(retention(...))]`
+    const RETENTION: &'static [RetentionPolicy] = &[];
+
+    /// Set with `#[index(vector(...))]` on embedding fields
+    const VECTOR_INDEXES: &'static [VectorIndex] = &[];
+
+    /// Set with `#[table(event(name = "...", when = "...", then = "..."))]`
+    const EVENTS: &'static [TableEvent] = &[];
+
+    /// Set with `#[table(permissions(select = "...", create = "...", update = "...", delete = "..."))]`
+    const PERMISSIONS: Option<TablePermissions> = None;
+
+    /// Fields marked with `#[field(skip)]`, they are never sent on create or update
+    const SKIP_FIELDS: &'static [&'static str] = &[];
+
+    /// Fields marked with `#[field(readonly)]`, they are read but never sent on create or update
+    const READONLY_FIELDS: &'static [&'static str] = &[];
+
+    /// Set with `#[table(versioned)]`, `update` checks and increments the version in this field
+    const VERSION_FIELD: Option<&'static str> = None;
+
+    /// Set with `#[table(tenant_field = "...")]`, the field that `Tenant` scopes every operation to
+    const TENANT_FIELD: Option<&'static str> = None;
+
+    /// Set with `#[table(id = "...")]`, how `create` gets the id of a new record
+    const ID_STRATEGY: IdStrategy = IdStrategy::Server;
+
+    /// Called by `create` and `update`, set with `#[table(validate)]` to use the `Validate` implementation
+    fn validate_record(&self) -> std::result::Result<(), ValidationError> {
+        Ok(())
+    }
+
+    /// Generated by the derive, implementations that are not derived only have the table name
+    fn meta() -> TableMeta {
+        TableMeta::new(Self::TABLE_NAME)
+    }
+
     fn get_id(&self) -> &Option<::surrealdb::sql::Thing>;
 
     fn set_id(&mut self, id: impl Into<::surrealdb::sql::Id>);
@@ -90,26 +245,239 @@ pub trait Table: Serialize + DeserializeOwned + Send + Sync where Self: 'static
         ::surrealdb::sql::Thing::from((Self::TABLE_NAME, id.into()))
     }
 
-    async fn create<C: Connection>(self, db: &Surreal<C>) -> Result<Option<Self>> {
-        let s: Option<Self> = db.create(Self::TABLE_NAME).content(self).await?;
+    /// Called by `create` before the record is validated and sent
+    async fn before_create<C: Connection>(&mut self, _db: &Surreal<C>) -> Result<()> {
+        Ok(())
+    }
+
+    /// Called by `create` with the created record
+    async fn after_create<C: Connection>(&self, _db: &Surreal<C>) -> Result<()> {
+        Ok(())
+    }
+
+    /// Called by `update` before the record is validated and sent
+    async fn before_update<C: Connection>(&mut self, _db: &Surreal<C>) -> Result<()> {
+        Ok(())
+    }
+
+    /// Called by `update` with the updated record
+    async fn after_update<C: Connection>(&self, _db: &Surreal<C>) -> Result<()> {
+        Ok(())
+    }
+
+    /// Called by `delete` with the id of the record before it is deleted
+    async fn before_delete<C: Connection>(_db: &Surreal<C>, _id: &::surrealdb::sql::Thing) -> Result<()> {
+        Ok(())
+    }
+
+    async fn create<C: Connection>(mut self, db: &impl Database<C>) -> Result<Option<Self>> {
+        let db = &db.connection();
+
+        self.before_create(db).await?;
+        self.validate_record().map_err(TableError::from)?;
+        id::assign_id(&mut self, db).await?;
+
+        let content = write_content(self)?;
+
+        let s: Option<Self> = if config::enabled() {
+            db.query(config::create(Self::TABLE_NAME, content)).await?.take(0)?
+        } else {
+            db.create(Self::TABLE_NAME).content(content).await?
+        };
+
+        if let Some(s) = &s {
+            s.after_create(db).await?;
+        }
+
+        Ok(s)
+    }
+
+    /// Creates the record like `create` but only returns its id with `RETURN VALUE id`,
+    /// `after_create` is not called because the created record is never read
+    async fn create_get_id<C: Connection>(mut self, db: &impl Database<C>) -> Result<::surrealdb::sql::Thing> {
+        let db = &db.connection();
+
+        self.before_create(db).await?;
+        self.validate_record().map_err(TableError::from)?;
+        id::assign_id(&mut self, db).await?;
+
+        let id: Option<::surrealdb::sql::Thing> = db.query(config::create_id(Self::TABLE_NAME, write_content(self)?))
+            .await.map_err(TableError::from)?
+            .take(0).map_err(TableError::from)?;
+
+        Ok(id.ok_or(TableError::IdEmpty)?)
+    }
+
+    async fn delete<C: Connection>(db: &impl Database<C>, id: impl IntoTableId<Self> + Send) -> Result<Option<Self>> {
+        let db = &db.connection();
+
+        let id = id.into_table_id()?;
+
+        Self::before_delete(db, &id).await?;
+
+        let s: Option<Self> = if config::enabled() {
+            db.query(config::delete(id)).await?.take(0)?
+        } else {
+            db.delete(RecordId::from_inner(id)).await?
+        };
+
+        Ok(s)
+    }
+
+    /// `DELETE table WHERE cond`, returns the deleted records
+    #[cfg(feature = "query")]
+    async fn delete_where<C: Connection>(db: &impl Database<C>, cond: impl Into<ExtraCond> + Send) -> Result<Vec<Self>> {
+        let db = &db.connection();
+
+        let mut statement = DeleteStatement::default();
+
+        statement.what.0 = vec![::surrealdb::sql::Value::Table(Self::TABLE_NAME.into())];
+        statement.cond = Some(cond.into().0);
+        statement.output = Some(Output::Before);
+
+        let s: Vec<Self> = db.query(config::apply(statement)).await?.take(0)?;
 
         Ok(s)
     }
 
-    async fn delete<C: Connection>(db: &Surreal<C>, id: impl Into<String> + Send) -> Result<Option<Self>> {
-        let s: Option<Self> = db.delete((Self::TABLE_NAME, id.into())).await?;
+    /// `UPDATE table MERGE data WHERE cond`, returns the updated records
+    #[cfg(feature = "query")]
+    async fn update_where<C: Connection>(db: &impl Database<C>, cond: impl Into<ExtraCond> + Send, data: impl Serialize + Send + 'static) -> Result<Vec<Self>> {
+        let db = &db.connection();
+
+        let data = ::surrealdb::sql::to_value(data).map_err(|e| TableError::Db(e.into()))?;
+
+        let mut statement = UpdateStatement::default();
+
+        statement.what.0 = vec![::surrealdb::sql::Value::Table(Self::TABLE_NAME.into())];
+        statement.data = Some(Data::MergeExpression(data));
+        statement.cond = Some(cond.into().0);
+        statement.output = Some(Output::After);
+
+        let s: Vec<Self> = db.query(config::apply(statement)).await?.take(0)?;
 
         Ok(s)
     }
 
-    async fn get_all<C: Connection>(db: &Surreal<C>) -> Result<Vec<Self>> {
+    async fn get_all<C: Connection>(db: &impl Database<C>) -> Result<Vec<Self>> {
+        let db = &db.connection();
+
+        if !Self::FETCH_FIELDS.is_empty() || config::enabled() {
+            let statement = fetch::select(vec![::surrealdb::sql::Value::Table(Self::TABLE_NAME.into())], false, Self::FETCH_FIELDS);
+
+            let vec_s: Vec<Self> = db.query(config::apply(statement)).await?.take(0)?;
+
+            return Ok(vec_s);
+        }
+
         let vec_s: Vec<Self> = db.select(Self::TABLE_NAME).await?;
 
         Ok(vec_s)
     }
 
-    async fn get_by_id<C: Connection>(db: &Surreal<C>, id: impl Into<String> + Send) -> Result<Option<Self>> {
-        let s: Option<Self> = db.select((Self::TABLE_NAME, id.into())).await?;
+    async fn get_by_id<C: Connection>(db: &impl Database<C>, id: impl IntoTableId<Self> + Send) -> Result<Option<Self>> {
+        let db = &db.connection();
+
+        let id = id.into_table_id()?;
+
+        if !Self::FETCH_FIELDS.is_empty() || config::enabled() {
+            let statement = fetch::select(vec![::surrealdb::sql::Value::Thing(id)], true, Self::FETCH_FIELDS);
+
+            let s: Option<Self> = db.query(config::apply(statement)).await?.take(0)?;
+
+            return Ok(s);
+        }
+
+        let s: Option<Self> = db.select(RecordId::from_inner(id)).await?;
+
+        Ok(s)
+    }
+
+    /// Selects the records with one query, the result is in the order of the ids with `None` for records that don't exist
+    async fn get_by_ids<C: Connection>(db: &impl Database<C>, ids: impl IntoIterator<Item = impl IntoTableId<Self>> + Send) -> Result<Vec<Option<Self>>> where Self: Clone {
+        let db = &db.connection();
+
+        let ids = ids.into_iter().map(|id| id.into_table_id()).collect::<Result<Vec<_>>>()?;
+
+        if ids.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let statement = fetch::select(ids.iter().cloned().map(::surrealdb::sql::Value::Thing).collect(), false, Self::FETCH_FIELDS);
+
+        let records: Vec<Self> = db.query(config::apply(statement)).await?.take(0)?;
+
+        let records: HashMap<String, Self> = records.into_iter()
+            .filter_map(|record| Some((record.get_id().as_ref()?.to_string(), record)))
+            .collect();
+
+        Ok(ids.iter().map(|id| records.get(&id.to_string()).cloned()).collect())
+    }
+
+    /// `create` in another namespace and database, see `query::scope`
+    #[cfg(feature = "query")]
+    async fn create_in<C: Connection>(mut self, db: &impl Database<C>, ns: &str, database: &str) -> Result<Option<Self>> {
+        let db = &db.connection();
+
+        self.before_create(db).await?;
+        self.validate_record().map_err(TableError::from)?;
+        id::assign_id(&mut self, db).await?;
+
+        let mut statement = CreateStatement::default();
+
+        statement.what.0 = vec![::surrealdb::sql::Value::Table(Self::TABLE_NAME.into())];
+        statement.data = Some(Data::ContentExpression(write_content(self)?));
+        statement.only = true;
+
+        let s: Option<Self> = Scoped::new(db, ns, database, config::apply(statement)).to_query().await?.take(STATEMENT_INDEX)?;
+
+        if let Some(s) = &s {
+            s.after_create(db).await?;
+        }
+
+        Ok(s)
+    }
+
+    /// `delete` in another namespace and database, see `query::scope`
+    #[cfg(feature = "query")]
+    async fn delete_in<C: Connection>(db: &impl Database<C>, ns: &str, database: &str, id: impl IntoTableId<Self> + Send) -> Result<Option<Self>> {
+        let db = &db.connection();
+
+        let id = id.into_table_id()?;
+
+        Self::before_delete(db, &id).await?;
+
+        let mut statement = DeleteStatement::default();
+
+        statement.what.0 = vec![::surrealdb::sql::Value::Thing(id)];
+        statement.output = Some(Output::Before);
+        statement.only = true;
+
+        let s: Option<Self> = Scoped::new(db, ns, database, config::apply(statement)).to_query().await?.take(STATEMENT_INDEX)?;
+
+        Ok(s)
+    }
+
+    /// `get_all` in another namespace and database, see `query::scope`
+    #[cfg(feature = "query")]
+    async fn get_all_in<C: Connection>(db: &impl Database<C>, ns: &str, database: &str) -> Result<Vec<Self>> {
+        let db = &db.connection();
+
+        let statement = fetch::select(vec![::surrealdb::sql::Value::Table(Self::TABLE_NAME.into())], false, Self::FETCH_FIELDS);
+
+        let vec_s: Vec<Self> = Scoped::new(db, ns, database, config::apply(statement)).to_query().await?.take(STATEMENT_INDEX)?;
+
+        Ok(vec_s)
+    }
+
+    /// `get_by_id` in another namespace and database, see `query::scope`
+    #[cfg(feature = "query")]
+    async fn get_by_id_in<C: Connection>(db: &impl Database<C>, ns: &str, database: &str, id: impl IntoTableId<Self> + Send) -> Result<Option<Self>> {
+        let db = &db.connection();
+
+        let statement = fetch::select(vec![::surrealdb::sql::Value::Thing(id.into_table_id()?)], true, Self::FETCH_FIELDS);
+
+        let s: Option<Self> = Scoped::new(db, ns, database, config::apply(statement)).to_query().await?.take(STATEMENT_INDEX)?;
 
         Ok(s)
     }
@@ -131,39 +499,283 @@ pub trait Table: Serialize + DeserializeOwned + Send + Sync where Self: 'static
     ///     id: Option<RecordId>,
     /// }
     /// ```
-    async fn update<C: Connection>(self, db: &Surreal<C>) -> Result<Option<Self>> {
-        let s: Option<Self> = db
-            .update(
-                (
-                    Self::TABLE_NAME,
-                    self.get_id().clone().ok_or(TableError::IdEmpty)?.id.to_owned().to_raw()
-                )
-            )
-            .merge(self)
-            .await?;
+    async fn update<C: Connection>(mut self, db: &impl Database<C>) -> Result<Option<Self>> {
+        let db = &db.connection();
+
+        self.before_update(db).await?;
+        self.validate_record().map_err(TableError::from)?;
+
+        let s: Option<Self> = match Self::VERSION_FIELD {
+            Some(field) => {
+                let id = self.get_id().clone().ok_or(TableError::IdEmpty)?;
+
+                version::update(db, id, field, write_content(self)?, None).await?
+            }
+            None => {
+                let id = self.get_id().clone().ok_or(TableError::IdEmpty)?;
+
+                let content = write_content(self)?;
+
+                if config::enabled() {
+                    db.query(config::merge(id, content)).await?.take(0)?
+                } else {
+                    db.update(RecordId::from_inner(id)).merge(content).await?
+                }
+            }
+        };
+
+        if let Some(s) = &s {
+            s.after_update(db).await?;
+        }
 
         Ok(s)
     }
 
+    /// Same as `update` with the `RETURN DIFF` of the record, the version of `#[table(versioned)]` is not checked
+    async fn update_with_diff<C: Connection>(mut self, db: &impl Database<C>) -> Result<(Option<Self>, Diff)> {
+        let db = &db.connection();
+
+        self.before_update(db).await?;
+        self.validate_record().map_err(TableError::from)?;
+
+        let id = self.get_id().clone().ok_or(TableError::IdEmpty)?;
+
+        let (s, diff) = diff::update::<Self, C>(db, id, write_content(self)?).await?;
+
+        if let Some(s) = &s {
+            s.after_update(db).await?;
+        }
+
+        Ok((s, diff))
+    }
+
+    /// Merges the fields that are set in the changeset into the record, returns the updated record
+    async fn apply_changeset<C: Connection>(db: &impl Database<C>, id: impl IntoTableId<Self> + Send, changeset: impl Changeset<Self> + 'static) -> Result<Option<Self>> {
+        let db = &db.connection();
+
+        changeset::apply(db, id.into_table_id()?, changeset).await
+    }
+
+    /// Defines the events that keep the counter caches up to date, call this once when setting up the schema
+    async fn define_counter_caches<C: Connection>(db: &impl Database<C>) -> Result<()> {
+        let db = &db.connection();
+
+        for counter_cache in Self::COUNTER_CACHES {
+            db.query(counter_cache.define_event(Self::TABLE_NAME)).await?.check()?;
+        }
+
+        Ok(())
+    }
+
+    /// Sets every counter cache to the actual count, e.g. for records that existed before the events were defined
+    async fn recount_counter_caches<C: Connection>(db: &impl Database<C>) -> Result<()> {
+        let db = &db.connection();
+
+        for counter_cache in Self::COUNTER_CACHES {
+            db.query(counter_cache.recount(Self::TABLE_NAME)).await?.check()?;
+        }
+
+        Ok(())
+    }
+
+    /// Full-text search of this table for `search::across`, converting the records into `H`
+    #[cfg(feature = "search")]
+    fn search_cfg<H: From<Self>>() -> SearchConfig<H> {
+        SearchConfig::of::<Self>()
+    }
+
+    /// Defines the computed fields with their `VALUE` expression, call this once when setting up the schema
+    async fn define_computed_fields<C: Connection>(db: &impl Database<C>) -> Result<()> {
+        let db = &db.connection();
+
+        for computed_field in Self::COMPUTED_FIELDS {
+            db.query(computed_field.define_field(Self::TABLE_NAME)).await?.check()?;
+        }
+
+        Ok(())
+    }
+
+    /// Defines the fields with their `DEFAULT` expression, call this once when setting up the schema
+    async fn define_default_fields<C: Connection>(db: &impl Database<C>) -> Result<()> {
+        let db = &db.connection();
+
+        for default_field in Self::DEFAULT_FIELDS {
+            db.query(default_field.define_field(Self::TABLE_NAME)).await?.check()?;
+        }
+
+        Ok(())
+    }
+
+    /// Defines the `HNSW`/`MTREE` indexes of the embedding fields, call this once when setting up the schema
+    async fn define_vector_indexes<C: Connection>(db: &impl Database<C>) -> Result<()> {
+        let db = &db.connection();
+
+        for vector_index in Self::VECTOR_INDEXES {
+            db.query(vector_index.define_index(Self::TABLE_NAME)).await?.check()?;
+        }
+
+        Ok(())
+    }
+
+    /// Defines the events of the table, call this once when setting up the schema
+    async fn define_events<C: Connection>(db: &impl Database<C>) -> Result<()> {
+        let db = &db.connection();
+
+        for event in Self::EVENTS {
+            db.query(event.define_event(Self::TABLE_NAME)).await?.check()?;
+        }
+
+        Ok(())
+    }
+
+    /// Sets the permissions of the table, does nothing for tables without permissions
+    async fn define_permissions<C: Connection>(db: &impl Database<C>) -> Result<()> {
+        let db = &db.connection();
+
+        if let Some(permissions) = Self::PERMISSIONS {
+            db.query(permissions.define_permissions(Self::TABLE_NAME)).await?.check()?;
+        }
+
+        Ok(())
+    }
+
+    /// Defines everything the derive generated for the table: permissions, counter caches, computed fields, default fields, vector indexes and events
+    async fn init_schema<C: Connection>(db: &impl Database<C>) -> Result<()> {
+        Self::define_permissions(db).await?;
+        Self::define_counter_caches(db).await?;
+        Self::define_computed_fields(db).await?;
+        Self::define_default_fields(db).await?;
+        Self::define_vector_indexes(db).await?;
+        Self::define_events(db).await?;
+
+        Ok(())
+    }
+
+    /// Compares the derived fields and indexes with `INFO FOR TABLE`
     #[cfg(feature = "query")]
-    fn select_builder<C: Connection>(db: &Surreal<C>, id: Option<String>) -> SelectBuilder<C, FilledWhat, NoFields, NoCond> {
-        if let Some(id) = id {
-            return db.select_builder().what(RecordId::from((Self::TABLE_NAME, id.as_str())))
+    async fn schema_diff<C: Connection>(db: &impl Database<C>) -> Result<SchemaDiff> {
+        let db = &db.connection();
+
+        schema::diff::<Self, C>(db).await
+    }
+
+    /// Defines the fields and indexes that are missing in the database, returns the diff from before
+    #[cfg(feature = "query")]
+    async fn sync_schema<C: Connection>(db: &impl Database<C>) -> Result<SchemaDiff> {
+        let db = &db.connection();
+
+        schema::sync::<Self, C>(db).await
+    }
+
+    /// Deletes the records that are not kept by the retention policies, a dry run only counts them
+    async fn apply_retention<C: Connection>(db: &impl Database<C>, dry_run: bool) -> Result<Vec<RetentionReport>> {
+        let db = &db.connection();
+
+        let mut reports = vec![];
+
+        for job in Retention::of::<Self>() {
+            reports.push(job.run(db, dry_run).await?);
         }
 
-        db.select_builder().what(Self::TABLE_NAME)
+        Ok(reports)
+    }
+
+    /// Live query of the table that yields a typed event for every change, see `table::live`
+    #[cfg(feature = "live")]
+    async fn live<C: Connection>(db: &impl Database<C>) -> Result<LiveQuery<Self>> where Self: Unpin {
+        let db = &db.connection();
+
+        LiveQuery::start(db).await
+    }
+
+    /// Creates the record and an outbox message in the default outbox table in one statement, see `outbox`
+    #[cfg(feature = "outbox")]
+    async fn create_with_outbox<C: Connection>(self, db: &impl Database<C>, event: OutboxEvent) -> Result<Option<Self>> {
+        let db = &db.connection();
+
+        Outbox::new().create(db, self, event).await
+    }
+
+    /// Writes every record as CSV, see `table::csv`
+    #[cfg(feature = "csv")]
+    async fn export_csv<C: Connection, W: std::io::Write + Send>(db: &impl Database<C>, writer: W) -> Result<usize> {
+        let db = &db.connection();
+
+        csv::export::<Self, C, W>(db, writer).await
+    }
+
+    /// Inserts the records of a CSV, see `table::csv`
+    #[cfg(feature = "csv")]
+    async fn import_csv<C: Connection, R: std::io::Read + Send>(db: &impl Database<C>, reader: R) -> Result<usize> {
+        let db = &db.connection();
+
+        csv::import::<Self, C, R>(db, reader).await
+    }
+
+    /// Writes every record as a line of JSON, see `table::ndjson`
+    #[cfg(feature = "ndjson")]
+    async fn dump_ndjson<C: Connection, W: std::io::Write + Send>(db: &impl Database<C>, writer: W) -> Result<usize> {
+        let db = &db.connection();
+
+        ndjson::dump::<Self, C, W>(db, writer, ndjson::BATCH_SIZE).await
+    }
+
+    /// Inserts the records of a `dump_ndjson`, see `table::ndjson`
+    #[cfg(feature = "ndjson")]
+    async fn restore_ndjson<C: Connection, R: std::io::Read + Send>(db: &impl Database<C>, reader: R) -> Result<usize> {
+        let db = &db.connection();
+
+        ndjson::restore::<Self, C, R>(db, reader, ndjson::BATCH_SIZE).await
+    }
+
+    /// A `CREATE` is only retried with `RetryPolicy::retry_non_idempotent`, see `RetryPolicy::run_non_idempotent`
+    #[cfg(feature = "retry")]
+    async fn create_with_policy<C: Connection>(self, db: &impl Database<C>, policy: &RetryPolicy) -> Result<Option<Self>> where Self: Clone {
+        policy.run_non_idempotent(|| self.clone().create(db)).await
+    }
+
+    #[cfg(feature = "retry")]
+    async fn delete_with_policy<C: Connection>(db: &impl Database<C>, id: impl IntoTableId<Self> + Clone + Send + Sync, policy: &RetryPolicy) -> Result<Option<Self>> {
+        policy.run(|| Self::delete(db, id.clone())).await
+    }
+
+    #[cfg(feature = "retry")]
+    async fn get_all_with_policy<C: Connection>(db: &impl Database<C>, policy: &RetryPolicy) -> Result<Vec<Self>> {
+        policy.run(|| Self::get_all(db)).await
+    }
+
+    #[cfg(feature = "retry")]
+    async fn get_by_id_with_policy<C: Connection>(db: &impl Database<C>, id: impl IntoTableId<Self> + Clone + Send + Sync, policy: &RetryPolicy) -> Result<Option<Self>> {
+        policy.run(|| Self::get_by_id(db, id.clone())).await
+    }
+
+    #[cfg(feature = "retry")]
+    async fn update_with_policy<C: Connection>(self, db: &impl Database<C>, policy: &RetryPolicy) -> Result<Option<Self>> where Self: Clone {
+        policy.run(|| self.clone().update(db)).await
+    }
+
+    #[cfg(feature = "query")]
+    fn select_builder<C: Connection>(db: &Surreal<C>, id: Option<String>) -> SelectBuilder<C, FilledWhat, NoFields, NoCond> {
+        let mut builder = match id {
+            Some(id) => db.select_builder().what(::surrealdb::sql::Thing::from((Self::TABLE_NAME, id.as_str()))),
+            None => db.select_builder().what(Self::TABLE_NAME),
+        };
+
+        builder.statement.fetch = fetch::fetchs(Self::FETCH_FIELDS);
+
+        builder
     }
 
     // It auto fills the content if this is not what you want use the `UpdateBuilder`
     #[cfg(feature = "query")]
-    fn update_builder<C: Connection>(self, db: &Surreal<C>) -> UpdateBuilder<C, FilledWhat, FilledData, NoCond> {
-        db.update_builder().what(Self::TABLE_NAME).content(self)
+    fn update_builder<C: Connection>(self, db: &Surreal<C>) -> Result<UpdateBuilder<'_, C, FilledWhat, FilledData, NoCond>> {
+        Ok(db.update_builder().what(Self::TABLE_NAME).content(write_content(self)?))
     }
 
 
     // It auto fills the content if this is not what you want use the `CreateBuilder`
     #[cfg(feature = "query")]
-    fn create_builder<C: Connection>(self, db: &Surreal<C>) -> CreateBuilder<C, FilledWhat, FilledData> {
-        db.create_builder().what(Self::TABLE_NAME).content(self)
+    fn create_builder<C: Connection>(self, db: &Surreal<C>) -> Result<CreateBuilder<'_, C, FilledWhat, FilledData>> {
+        Ok(db.create_builder().what(Self::TABLE_NAME).content(write_content(self)?))
     }
 }
@@ -0,0 +1,163 @@
+//! Newline delimited JSON dump and restore of a table
+//!
+//! `dump` writes every record as one line of JSON through the `Table` struct, ordered by id and read in batches,
+//! `restore` reads the lines back into the struct and inserts them in batches, so a table can be backed up with its type.
+//! Record ids are written the way serde writes a `Thing`, the dump is meant to be read back by `restore`.
+//!
+//! Restored records are written with every field, `SKIP_FIELDS` and `READONLY_FIELDS` included, and the hooks are not called.
+//!
+//! # Example
+//!
+//! ```rust
+//! use serde::{Serialize, Deserialize};
+//! use surrealdb::engine::any::connect;
+//! use surrealdb::sql::Thing as RecordId;
+//! use surrealdb_extra::table::Table;
+//!
+//! #[derive(Table, Serialize, Deserialize, Clone)]
+//! #[table(name = "user")]
+//! struct User {
+//!     id: Option<RecordId>,
+//!     name: String
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let db = connect("mem://").await.unwrap();
+//!     db.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     User { id: None, name: "name".to_string() }.create(&db).await.unwrap();
+//!
+//!     let mut dump = vec![];
+//!     assert_eq!(User::dump_ndjson(&db, &mut dump).await.unwrap(), 1);
+//!
+//!     let other = connect("mem://").await.unwrap();
+//!     other.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     assert_eq!(User::restore_ndjson(&other, dump.as_slice()).await.unwrap(), 1);
+//! }
+//! ```
+
+use std::io::{BufRead, BufReader, Read, Write};
+use anyhow::Result;
+use surrealdb::{Connection, Surreal};
+use surrealdb::sql::Value;
+use crate::table::{Table, TableError};
+
+/// Batch size of `Table::dump_ndjson` and `Table::restore_ndjson`
+pub const BATCH_SIZE: usize = 1000;
+
+/// Writes every record of the table as a line, returns the number of written records
+pub async fn dump<T: Table, C: Connection, W: Write + Send>(db: &Surreal<C>, mut writer: W, batch_size: usize) -> Result<usize> {
+    let batch_size = batch_size.max(1);
+    let mut count = 0;
+
+    loop {
+        let records: Vec<T> = db.query("SELECT * FROM type::table($table) ORDER BY id LIMIT $limit START $start")
+            .bind(("table", T::TABLE_NAME))
+            .bind(("limit", batch_size))
+            .bind(("start", count))
+            .await.map_err(TableError::from)?
+            .take(0).map_err(TableError::from)?;
+
+        let len = records.len();
+
+        for record in records {
+            serde_json::to_writer(&mut writer, &record).map_err(ndjson_error)?;
+            writer.write_all(b"\n").map_err(|e| TableError::Ndjson(e.to_string()))?;
+        }
+
+        count += len;
+
+        if len < batch_size {
+            break;
+        }
+    }
+
+    writer.flush().map_err(|e| TableError::Ndjson(e.to_string()))?;
+
+    Ok(count)
+}
+
+/// Inserts the records of the lines, empty lines are skipped, returns the number of inserted records
+pub async fn restore<T: Table, C: Connection, R: Read + Send>(db: &Surreal<C>, reader: R, batch_size: usize) -> Result<usize> {
+    let batch_size = batch_size.max(1);
+    let mut batch = Vec::with_capacity(batch_size);
+    let mut count = 0;
+
+    for (index, line) in BufReader::new(reader).lines().enumerate() {
+        let line = line.map_err(|e| TableError::Ndjson(e.to_string()))?;
+
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let t: T = serde_json::from_str(&line).map_err(|e| TableError::Ndjson(format!("line {}: {e}", index + 1)))?;
+
+        batch.push(surrealdb::sql::to_value(t).map_err(|e| TableError::Db(e.into()))?);
+
+        if batch.len() == batch_size {
+            count += insert::<T, C>(db, std::mem::take(&mut batch)).await?;
+        }
+    }
+
+    if !batch.is_empty() {
+        count += insert::<T, C>(db, batch).await?;
+    }
+
+    Ok(count)
+}
+
+async fn insert<T: Table, C: Connection>(db: &Surreal<C>, records: Vec<Value>) -> Result<usize> {
+    let len = records.len();
+
+    db.query(format!("INSERT INTO {} $records RETURN NONE", surrealdb::sql::Table::from(T::TABLE_NAME)))
+        .bind(("records", Value::from(records)))
+        .await.map_err(TableError::from)?
+        .check().map_err(TableError::from)?;
+
+    Ok(len)
+}
+
+fn ndjson_error(e: serde_json::Error) -> TableError {
+    TableError::Ndjson(e.to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use serde::{Deserialize, Serialize};
+    use surrealdb::engine::any::connect;
+    use surrealdb::sql::Thing as RecordId;
+    use crate::testing::db;
+    use super::*;
+
+    #[derive(Debug, Table, Serialize, Deserialize, Clone, PartialEq)]
+    #[table(name = "user")]
+    struct User {
+        id: Option<RecordId>,
+        name: String,
+        #[field(readonly)]
+        #[serde(default)]
+        logins: i64,
+    }
+
+    #[tokio::test]
+    async fn dump_restore() {
+        let db = db().await;
+
+        db.query("CREATE user:a SET name = 'a', logins = 3; CREATE user:b SET name = 'b'; CREATE user:c SET name = 'c'").await.unwrap().check().unwrap();
+
+        let mut lines = vec![];
+        assert_eq!(dump::<User, _, _>(&db, &mut lines, 2).await.unwrap(), 3);
+        assert_eq!(String::from_utf8(lines.clone()).unwrap().lines().count(), 3);
+
+        let other = connect("mem://").await.unwrap();
+        other.use_ns("test").use_db("other").await.unwrap();
+
+        assert_eq!(restore::<User, _, _>(&other, lines.as_slice(), 2).await.unwrap(), 3);
+        assert_eq!(User::get_all(&other).await.unwrap(), User::get_all(&db).await.unwrap());
+        assert_eq!(User::get_by_id(&other, "a").await.unwrap().unwrap().logins, 3);
+
+        assert!(User::restore_ndjson(&other, "\n{ \"name\": 1 }\n".as_bytes()).await.is_err());
+    }
+}
@@ -0,0 +1,312 @@
+//! Field paths that are checked at compile time
+//!
+//! The `Table` derive generates a `<Struct>Fields` enum with a variant for every field, which can be used instead of strings
+//! in `field`, `omit`, `fetch`, `split`, `group` and `order` of the select builder.
+//! `then`, `field`, `index`, `all`, `last` and `filter` build nested paths from them, e.g. `UserFields::Address.then(AddressFields::City)` is `address.city`.
+//!
+//! Structs that are only stored inside a table derive `Embedded` for their fields enum. With `#[field(flatten)]` on the field
+//! the fields enum of the table gets a function for the nested paths and `T::meta()` and `schema_diff` get the nested fields.
+//!
+#![cfg_attr(feature = "query", doc = "```rust")]
+#![cfg_attr(not(feature = "query"), doc = "```ignore")]
+//! use serde::{Serialize, Deserialize};
+//! use surrealdb::sql::{Operator, Thing as RecordId};
+//! use surrealdb_extra::table::path::TablePath;
+//! use surrealdb_extra::table::{Embedded, Table};
+//!
+//! #[derive(Embedded, Serialize, Deserialize, Clone)]
+//! struct Address {
+//!     city: String
+//! }
+//!
+//! #[derive(Table, Serialize, Deserialize, Clone)]
+//! #[table(name = "user")]
+//! struct User {
+//!     id: Option<RecordId>,
+//!     #[field(flatten)]
+//!     address: Address
+//! }
+//!
+//! assert_eq!(UserFields::address(AddressFields::City).to_idiom().to_string(), "address.city");
+//! assert_eq!(User::meta().paths(), ["id", "address", "address.city"]);
+//!
+//! let cond = UserFields::address(AddressFields::City).cond(Operator::Equal, "Paris");
+//! assert_eq!(cond.0.to_string(), "WHERE address.city = 'Paris'");
+//! ```
+//!
+//! `Path` builds the same paths from names for fields without a struct, e.g. `Path::field("a").index(0).all().field("b")` is `a[0][*].b`.
+//! The names are not parsed, so `Path::field("a.b")` is one field with a dot in its name and not `a.b`.
+//!
+#![cfg_attr(feature = "query", doc = "```rust")]
+#![cfg_attr(not(feature = "query"), doc = "```ignore")]
+//! use surrealdb::sql::Operator;
+//! use surrealdb_extra::table::path::{Path, TablePath};
+//!
+//! let path = Path::field("orders").all().field("items").last().field("price");
+//! assert_eq!(path.to_idiom().to_string(), "orders[*].items[$].price");
+//!
+//! let cond = Path::field("tags").index(0).cond(Operator::Equal, "rust");
+//! assert_eq!(cond.0.to_string(), "WHERE tags[0] = 'rust'");
+//! ```
+//!
+//! # Example
+//!
+#![cfg_attr(feature = "query", doc = "```rust")]
+#![cfg_attr(not(feature = "query"), doc = "```ignore")]
+//! use serde::{Serialize, Deserialize};
+//! use surrealdb::engine::any::connect;
+//! use surrealdb::sql::Thing as RecordId;
+//! use surrealdb_extra::query::statement::StatementBuilder;
+//! use surrealdb_extra::table::path::TablePath;
+//! use surrealdb_extra::table::Table;
+//!
+//! #[derive(Table, Serialize, Deserialize, Clone)]
+//! #[table(name = "address")]
+//! struct Address {
+//!     id: Option<RecordId>,
+//!     city: String
+//! }
+//!
+//! #[derive(Table, Serialize, Deserialize, Clone)]
+//! #[table(name = "user")]
+//! struct User {
+//!     id: Option<RecordId>,
+//!     name: String,
+//!     address: Address,
+//!     tags: Vec<String>
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let db = connect("mem://").await.unwrap();
+//!
+//!     let select = db.select_builder().what("user")
+//!         .field(UserFields::Name)
+//!         .field(UserFields::Address.then(AddressFields::City))
+//!         .omit(UserFields::Tags.index(0))
+//!         .group(UserFields::Name);
+//!
+//!     assert_eq!(select.to_surql(), "SELECT name, address.city OMIT tags[0] FROM user GROUP BY name");
+//! }
+//! ```
+
+use surrealdb::sql::{Idiom, Part};
+use crate::table::meta::FieldMeta;
+
+#[cfg(feature = "query")]
+use surrealdb::sql::{Expression, Operator, Value};
+
+#[cfg(feature = "query")]
+use crate::query::parsing::cond::ExtraCond;
+
+/// A path to a field of a table, implemented by the generated `<Struct>Fields` enums and `FieldPath`
+pub trait TablePath {
+    fn to_idiom(&self) -> Idiom;
+
+    /// A field of this field, `a.b`
+    fn then(&self, field: impl TablePath) -> FieldPath where Self: Sized {
+        let mut idiom = self.to_idiom();
+        idiom.0.extend(field.to_idiom().0);
+
+        FieldPath(idiom)
+    }
+
+    /// A field of this field by name, `a.b`
+    fn field(&self, name: &str) -> FieldPath where Self: Sized {
+        let mut idiom = self.to_idiom();
+        idiom.0.push(Part::from(name));
+
+        FieldPath(idiom)
+    }
+
+    /// An element of this array, `a[0]`
+    fn index(&self, index: usize) -> FieldPath where Self: Sized {
+        let mut idiom = self.to_idiom();
+        idiom.0.push(Part::from(index));
+
+        FieldPath(idiom)
+    }
+
+    /// All elements of this array, `a[*]`
+    fn all(&self) -> FieldPath where Self: Sized {
+        let mut idiom = self.to_idiom();
+        idiom.0.push(Part::All);
+
+        FieldPath(idiom)
+    }
+
+    /// The last element of this array, `a[$]`
+    fn last(&self) -> FieldPath where Self: Sized {
+        let mut idiom = self.to_idiom();
+        idiom.0.push(Part::Last);
+
+        FieldPath(idiom)
+    }
+
+    /// The elements of this array that match the condition, `a[WHERE cond]`
+    #[cfg(feature = "query")]
+    fn filter(&self, cond: impl Into<ExtraCond>) -> FieldPath where Self: Sized {
+        let mut idiom = self.to_idiom();
+        idiom.0.push(Part::Where(cond.into().0.0));
+
+        FieldPath(idiom)
+    }
+
+    /// `path operator value` as a condition, e.g. `UserFields::address(AddressFields::City).cond(Operator::Equal, "Paris")`
+    #[cfg(feature = "query")]
+    fn cond(&self, operator: Operator, value: impl Into<Value>) -> ExtraCond where Self: Sized {
+        ExtraCond::from(Expression::Binary {
+            l: Value::Idiom(self.to_idiom()),
+            o: operator,
+            r: value.into(),
+        })
+    }
+}
+
+/// Structs that are stored as nested objects, implemented by `#[derive(Embedded)]`
+///
+/// `#[field(flatten)]` on a field of this type adds `FIELDS` as nested fields to the metadata of the table
+/// and a function named like the field to the fields enum, e.g. `UserFields::address(AddressFields::City)` is `address.city`.
+pub trait Embedded {
+    const FIELDS: &'static [FieldMeta];
+}
+
+/// A nested path built from the generated field enums or from names with `Path`
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldPath(pub Idiom);
+
+/// Builds paths from names, `Path::field("a").index(0).all().field("b")` is `a[0][*].b`
+pub type Path = FieldPath;
+
+impl FieldPath {
+    /// A top level field, the start of the path
+    pub fn field(name: &str) -> Self {
+        Self(Idiom::from(vec![Part::from(name)]))
+    }
+}
+
+impl TablePath for FieldPath {
+    fn to_idiom(&self) -> Idiom {
+        self.0.clone()
+    }
+}
+
+impl From<FieldPath> for Idiom {
+    fn from(value: FieldPath) -> Self {
+        value.0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde::{Deserialize, Serialize};
+    use surrealdb::sql::Thing as RecordId;
+    #[cfg(feature = "query")]
+    use surrealdb::engine::any::connect;
+    #[cfg(feature = "query")]
+    use crate::query::parsing::order::OrderDirection;
+    #[cfg(feature = "query")]
+    use crate::query::statement::StatementBuilder;
+    use crate::table::{Embedded, Table};
+    use super::*;
+
+    #[derive(Table, Serialize, Deserialize, Clone)]
+    #[table(name = "address")]
+    struct Address {
+        id: Option<RecordId>,
+        city: String,
+        zip_code: String,
+    }
+
+    #[derive(Table, Serialize, Deserialize, Clone)]
+    #[table(name = "user")]
+    struct User {
+        id: Option<RecordId>,
+        #[serde(rename = "mail")]
+        email: String,
+        r#type: String,
+        addresses: Vec<Address>,
+    }
+
+    #[test]
+    fn field_names() {
+        assert_eq!(UserFields::Email.as_str(), "mail");
+        assert_eq!(UserFields::Type.to_string(), "type");
+        assert_eq!(AddressFields::ZipCode.to_idiom().to_string(), "zip_code");
+    }
+
+    #[derive(Embedded, Serialize, Deserialize, Clone)]
+    struct Settings {
+        #[serde(rename = "darkMode")]
+        dark_mode: bool,
+    }
+
+    #[derive(Table, Serialize, Deserialize, Clone)]
+    #[table(name = "account")]
+    struct Account {
+        id: Option<RecordId>,
+        #[field(flatten)]
+        settings: Option<Settings>,
+    }
+
+    #[test]
+    fn flattened_paths() {
+        assert_eq!(AccountFields::settings(SettingsFields::DarkMode).to_idiom().to_string(), "settings.darkMode");
+    }
+
+    #[cfg(feature = "query")]
+    #[test]
+    fn flattened_path_cond() {
+        assert_eq!(AccountFields::settings(SettingsFields::DarkMode).cond(Operator::Equal, true).0.to_string(), "WHERE settings.darkMode = true");
+    }
+
+    #[test]
+    fn nested_paths() {
+        assert_eq!(UserFields::Addresses.all().then(AddressFields::City).to_idiom().to_string(), "addresses[*].city");
+        assert_eq!(UserFields::Addresses.index(1).then(AddressFields::ZipCode).to_idiom().to_string(), "addresses[1].zip_code");
+    }
+
+    #[test]
+    fn path_builder() {
+        assert_eq!(Path::field("a").index(0).all().field("b").to_idiom().to_string(), "a[0][*].b");
+        assert_eq!(Path::field("a.b").last().to_idiom().0, [Part::from("a.b"), Part::Last]);
+        assert_eq!(UserFields::Addresses.all().field("street").to_idiom().to_string(), "addresses[*].street");
+    }
+
+    #[cfg(feature = "query")]
+    #[test]
+    fn path_filter() {
+        assert_eq!(Path::field("items").filter("price > 10").field("name").to_idiom().to_string(), "items[WHERE price > 10].name");
+    }
+
+    #[cfg(feature = "query")]
+    #[tokio::test]
+    async fn select_with_path_builder() {
+        let db = connect("mem://").await.unwrap();
+
+        let select = db.select_builder().what("user")
+            .field(Path::field("orders").all().field("total"))
+            .omit(Path::field("tags").index(0))
+            .condition(Path::field("address").field("city").cond(Operator::Equal, "Paris"))
+            .fetch(Path::field("orders").all().field("product"));
+
+        assert_eq!(select.to_surql(), "SELECT orders[*].total OMIT tags[0] FROM user WHERE address.city = 'Paris' FETCH orders[*].product");
+    }
+
+    #[cfg(feature = "query")]
+    #[tokio::test]
+    async fn select_with_paths() {
+        let db = connect("mem://").await.unwrap();
+
+        let select = db.select_builder().what("user")
+            .field(UserFields::Email)
+            .field(UserFields::Addresses.all().then(AddressFields::City))
+            .omit(UserFields::Type)
+            .split(UserFields::Addresses)
+            .group(UserFields::Email)
+            .order((UserFields::Email, OrderDirection::DESC))
+            .fetch(UserFields::Addresses);
+
+        assert_eq!(select.to_surql(), "SELECT mail, addresses[*].city OMIT type FROM user SPLIT ON addresses GROUP BY mail ORDER BY mail DESC FETCH addresses");
+    }
+}
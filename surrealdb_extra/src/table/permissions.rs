@@ -0,0 +1,128 @@
+//! Table permissions from the derive
+//!
+//! `#[table(permissions(select = "...", create = "...", update = "...", delete = "..."))]` sets the `PERMISSIONS` of the table,
+//! applied with `define_permissions` or `init_schema`. They only apply to record users, root users can always do everything.
+//!
+//! Every permission is `FULL`, `NONE` or a condition like `user = $auth.id` (with or without `WHERE`),
+//! the permissions that are left out are `NONE`. Other options of the table like `SCHEMAFULL` are kept.
+//!
+//! # Example
+//!
+//! ```rust
+//! use serde::{Serialize, Deserialize};
+//! use surrealdb::engine::any::connect;
+//! use surrealdb::sql::Thing as RecordId;
+//! use surrealdb_extra::table::Table;
+//!
+//! #[derive(Table, Serialize, Deserialize, Clone)]
+//! #[table(name = "post", permissions(select = "FULL", create = "author = $auth.id", update = "author = $auth.id"))]
+//! struct Post {
+//!     id: Option<RecordId>,
+//!     author: RecordId,
+//!     text: String
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let db = connect("mem://").await.unwrap();
+//!     db.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     Post::init_schema(&db).await.unwrap();
+//! }
+//! ```
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TablePermissions {
+    pub select: Option<&'static str>,
+    pub create: Option<&'static str>,
+    pub update: Option<&'static str>,
+    pub delete: Option<&'static str>,
+}
+
+impl TablePermissions {
+    /// `PERMISSIONS FOR select ... FOR create ... FOR update ... FOR delete ...`
+    pub fn clause(&self) -> String {
+        let permissions = [
+            ("select", self.select),
+            ("create", self.create),
+            ("update", self.update),
+            ("delete", self.delete),
+        ];
+
+        let permissions: Vec<String> = permissions.iter()
+            .map(|(action, permission)| format!("FOR {action} {}", permission_value(*permission)))
+            .collect();
+
+        format!("PERMISSIONS {}", permissions.join(" "))
+    }
+
+    /// Defines the table when it does not exist and sets its permissions
+    pub fn define_permissions(&self, table: &str) -> String {
+        format!("DEFINE TABLE IF NOT EXISTS {table}; ALTER TABLE {table} {}", self.clause())
+    }
+}
+
+fn permission_value(permission: Option<&str>) -> String {
+    let Some(permission) = permission.map(str::trim) else {
+        return "NONE".to_string();
+    };
+
+    if permission.eq_ignore_ascii_case("full") || permission.eq_ignore_ascii_case("none") {
+        return permission.to_uppercase();
+    }
+
+    match permission.get(..6) {
+        Some(prefix) if prefix.eq_ignore_ascii_case("where ") => permission.to_string(),
+        _ => format!("WHERE {permission}"),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde::{Deserialize, Serialize};
+    use surrealdb::sql::{Part, Thing, Value};
+    use crate::table::Table;
+    use crate::testing::db;
+    use super::*;
+
+    #[derive(Debug, Table, Serialize, Deserialize, Clone)]
+    #[table(name = "post", permissions(select = "full", create = "WHERE author = $auth.id", update = "author = $auth.id"))]
+    struct Post {
+        id: Option<Thing>,
+        author: Thing,
+    }
+
+    #[test]
+    fn derived() {
+        assert_eq!(Post::PERMISSIONS, Some(TablePermissions {
+            select: Some("full"),
+            create: Some("WHERE author = $auth.id"),
+            update: Some("author = $auth.id"),
+            delete: None,
+        }));
+
+        assert_eq!(
+            Post::PERMISSIONS.unwrap().clause(),
+            "PERMISSIONS FOR select FULL FOR create WHERE author = $auth.id FOR update WHERE author = $auth.id FOR delete NONE"
+        );
+    }
+
+    #[tokio::test]
+    async fn keeps_table_options() {
+        let db = db().await;
+
+        db.query("DEFINE TABLE post SCHEMALESS CHANGEFEED 1h").await.unwrap().check().unwrap();
+
+        Post::init_schema(&db).await.unwrap();
+
+        let info: surrealdb::Value = db.query("INFO FOR DB").await.unwrap().take(0).unwrap();
+        let Value::Strand(definition) = info.into_inner().pick(&[Part::from("tables"), Part::from("post")]) else {
+            panic!("post is not defined");
+        };
+
+        assert!(definition.0.contains("CHANGEFEED 1h"));
+        assert!(definition.0.contains("FOR select FULL"));
+        assert!(definition.0.contains("FOR create, update WHERE author = $auth.id"));
+        assert!(definition.0.contains("FOR delete NONE"));
+    }
+}
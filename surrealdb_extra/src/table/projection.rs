@@ -0,0 +1,126 @@
+//! Selecting a table into a smaller struct
+//!
+//! `#[derive(Projection)]` with `#[projection(of = User)]` selects only the fields of the projection from the table of `User`.
+//! Every field has to be a field of `User` with the same name, otherwise it doesn't compile,
+//! the check uses the variants of the `UserFields` enum of the `Table` derive, so `User` and `UserFields` have to be in scope.
+//! The types of the fields are not checked, they only have to deserialize from the values of the table.
+//!
+//! The derive generates `select_builder`, which can be extended like any select, and `select`, which returns every row.
+//!
+//! # Example
+//!
+//! ```rust
+//! use serde::{Serialize, Deserialize};
+//! use surrealdb::engine::any::connect;
+//! use surrealdb::sql::Thing as RecordId;
+//! use surrealdb_extra::table::Table;
+//! use surrealdb_extra::table::projection::Projection;
+//!
+//! #[derive(Table, Serialize, Deserialize, Clone)]
+//! #[table(name = "user")]
+//! struct User {
+//!     id: Option<RecordId>,
+//!     name: String,
+//!     email: String,
+//!     bio: String
+//! }
+//!
+//! #[derive(Projection, Deserialize)]
+//! #[projection(of = User)]
+//! struct UserSummary {
+//!     id: RecordId,
+//!     name: String
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let db = connect("mem://").await.unwrap();
+//!     db.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     User { id: None, name: "John".to_string(), email: "john@example.com".to_string(), bio: "...".to_string() }.create(&db).await.unwrap();
+//!
+//!     assert_eq!(UserSummary::select_builder(&db).to_surql(), "SELECT id, name FROM user");
+//!
+//!     let users = UserSummary::select(&db).await.unwrap();
+//!     assert_eq!(users[0].name, "John");
+//! }
+//! ```
+
+use anyhow::Result;
+use serde::de::DeserializeOwned;
+use surrealdb::sql::Value;
+use surrealdb::{Connection, Surreal};
+use crate::query::parsing::field::ExtraField;
+use crate::query::parsing::idiom::ExtraIdiom;
+use crate::query::select::SelectBuilder;
+use crate::query::states::{FilledFields, FilledWhat, NoCond};
+use crate::query::statement::StatementBuilder;
+use crate::table::Table;
+
+#[cfg(feature = "derive")]
+pub use ::surrealdb_extra_derive::Projection;
+
+/// A struct with some of the fields of `Of`, implemented by `#[derive(Projection)]`
+pub trait Projection: DeserializeOwned + Send + Sync + 'static {
+    type Of: Table;
+
+    /// The names of the fields in the database
+    const FIELDS: &'static [&'static str];
+
+    /// `SELECT <fields> FROM <table>`
+    fn select_builder<C: Connection>(db: &Surreal<C>) -> SelectBuilder<'_, C, FilledWhat, FilledFields, NoCond> {
+        let mut fields = Self::FIELDS.iter().map(|name| ExtraField::from(Value::Idiom(ExtraIdiom::from(*name).0)));
+
+        // The derive doesn't allow projections without fields
+        let first = fields.next().unwrap_or_else(|| ExtraField::from(Value::Idiom(ExtraIdiom::from("id").0)));
+
+        fields.fold(db.select_builder().what(Self::Of::TABLE_NAME).field(first), |select, field| select.field(field))
+    }
+}
+
+/// Every row of the table as the projection
+pub async fn select<P: Projection, C: Connection>(db: &Surreal<C>) -> Result<Vec<P>> {
+    P::select_builder(db).execute_values().await
+}
+
+#[cfg(test)]
+mod test {
+    use serde::{Deserialize, Serialize};
+    use surrealdb::sql::Thing as RecordId;
+    use crate::testing::db;
+    use super::*;
+
+    #[derive(Debug, Table, Serialize, Deserialize, Clone)]
+    #[table(name = "user")]
+    struct User {
+        id: Option<RecordId>,
+        #[serde(rename = "displayName")]
+        display_name: String,
+        email: String,
+        age: u8,
+    }
+
+    #[derive(Debug, Projection, Deserialize, PartialEq)]
+    #[projection(of = User)]
+    struct UserName {
+        #[serde(rename = "displayName")]
+        display_name: String,
+        age: Option<u8>,
+    }
+
+    #[tokio::test]
+    async fn select_projection() {
+        let db = db().await;
+
+        User { id: None, display_name: "a".to_string(), email: "a@example.com".to_string(), age: 20 }.create(&db).await.unwrap();
+
+        assert_eq!(UserName::FIELDS, ["displayName", "age"]);
+        assert_eq!(UserName::select_builder(&db).to_surql(), "SELECT displayName, age FROM user");
+
+        let names = UserName::select(&db).await.unwrap();
+        assert_eq!(names, [UserName { display_name: "a".to_string(), age: Some(20) }]);
+
+        let filtered: Vec<UserName> = UserName::select_builder(&db).condition("age > 30").execute_values().await.unwrap();
+        assert!(filtered.is_empty());
+    }
+}
@@ -0,0 +1,373 @@
+//! Data retention policies
+//!
+//! A table declares its policies with `#[table(retention(...))]` or by building `Retention` jobs,
+//! `apply_retention` then deletes the records that are no longer kept in batches:
+//! - `retention(keep_days = 90, by = "created_at")` deletes the records where `created_at` is older than 90 days
+//! - `retention(keep_last = 10, per = "user", by = "created_at")` keeps the 10 newest records by `created_at` of every `user`
+//!
+//! A dry run only counts the records that would be deleted.
+//! With the `retention` feature `RetentionScheduler` runs the jobs at an interval.
+//!
+//! # Example
+//!
+//! ```rust
+//! use serde::{Serialize, Deserialize};
+//! use surrealdb::engine::any::connect;
+//! use surrealdb::sql::{Datetime, Thing as RecordId};
+//! use surrealdb_extra::table::Table;
+//!
+//! #[derive(Table, Serialize, Deserialize, Clone)]
+//! #[table(name = "login", retention(keep_days = 90, by = "created_at"))]
+//! #[table(retention(keep_last = 2, per = "user", by = "created_at"))]
+//! struct Login {
+//!     id: Option<RecordId>,
+//!     user: String,
+//!     created_at: Datetime
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let db = connect("mem://").await.unwrap();
+//!     db.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     for _ in 0..3 {
+//!         Login { id: None, user: "a".to_string(), created_at: Datetime::default() }.create(&db).await.unwrap();
+//!     }
+//!
+//!     let reports = Login::apply_retention(&db, true).await.unwrap();
+//!     assert_eq!(reports[1].records, 1);
+//!
+//!     Login::apply_retention(&db, false).await.unwrap();
+//!     assert_eq!(Login::get_all(&db).await.unwrap().len(), 2);
+//! }
+//! ```
+
+use std::time::Duration;
+use anyhow::Result;
+use chrono::Utc;
+use surrealdb::{Connection, Surreal};
+use surrealdb::sql::{Datetime, Value};
+use crate::table::{Table, TableError};
+
+pub const DEFAULT_BATCH_SIZE: u64 = 1000;
+
+/// Which records of a table are kept, every other record is deleted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RetentionPolicy {
+    /// Keeps the records where `by` is newer than `max_age`, records without `by` are kept
+    MaxAge { by: &'static str, max_age: Duration },
+    /// Keeps the `n` newest records by `by` for every value of `per`
+    KeepLast { n: u64, per: &'static str, by: &'static str },
+}
+
+impl RetentionPolicy {
+    pub const fn keep_days(days: u64, by: &'static str) -> Self {
+        Self::MaxAge { by, max_age: Duration::from_secs(days * 24 * 60 * 60) }
+    }
+
+    pub const fn keep_last(n: u64, per: &'static str, by: &'static str) -> Self {
+        Self::KeepLast { n, per, by }
+    }
+
+    fn check(&self, table: &str) -> Result<()> {
+        let names = match self {
+            Self::MaxAge { by, .. } => vec![table, *by],
+            Self::KeepLast { per, by, .. } => vec![table, *per, *by],
+        };
+
+        for name in names {
+            if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+                return Err(TableError::InvalidName(name.to_string()).into());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// A policy applied to a table
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Retention {
+    pub table: String,
+    pub policy: RetentionPolicy,
+    pub batch_size: u64,
+}
+
+/// Records deleted by a job, or for a dry run the records that would be deleted
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetentionReport {
+    pub table: String,
+    pub policy: RetentionPolicy,
+    pub records: u64,
+    pub dry_run: bool,
+}
+
+impl Retention {
+    pub fn new(table: impl Into<String>, policy: RetentionPolicy) -> Self {
+        Self {
+            table: table.into(),
+            policy,
+            batch_size: DEFAULT_BATCH_SIZE,
+        }
+    }
+
+    /// The policies declared on the table
+    pub fn of<T: Table>() -> Vec<Self> {
+        T::RETENTION.iter().map(|policy| Self::new(T::TABLE_NAME, *policy)).collect()
+    }
+
+    /// Maximum number of records deleted by one query
+    pub fn batch_size(mut self, batch_size: u64) -> Self {
+        self.batch_size = batch_size.max(1);
+
+        self
+    }
+
+    pub async fn run<C: Connection>(&self, db: &Surreal<C>, dry_run: bool) -> Result<RetentionReport> {
+        self.policy.check(&self.table)?;
+
+        let records = match self.policy {
+            RetentionPolicy::MaxAge { by, max_age } => self.max_age(db, by, max_age, dry_run).await?,
+            RetentionPolicy::KeepLast { n, per, by } => self.keep_last(db, n, per, by, dry_run).await?,
+        };
+
+        Ok(RetentionReport {
+            table: self.table.clone(),
+            policy: self.policy,
+            records,
+            dry_run,
+        })
+    }
+
+    async fn max_age<C: Connection>(&self, db: &Surreal<C>, by: &str, max_age: Duration, dry_run: bool) -> Result<u64> {
+        let cutoff = Datetime::from(Utc::now() - chrono::Duration::from_std(max_age).unwrap_or(chrono::Duration::MAX));
+        let cond = format!("{by} != NONE AND {by} < $cutoff");
+
+        if dry_run {
+            return count(db, &self.table, &cond, ("cutoff", Value::from(cutoff))).await;
+        }
+
+        let select = format!("SELECT id FROM {} WHERE {cond} LIMIT {}", self.table, self.batch_size);
+
+        delete_batches(db, &select, self.batch_size, ("cutoff", Value::from(cutoff))).await
+    }
+
+    async fn keep_last<C: Connection>(&self, db: &Surreal<C>, n: u64, per: &str, by: &str, dry_run: bool) -> Result<u64> {
+        let mut res = db.query(format!("RETURN array::distinct((SELECT VALUE {per} FROM {}))", self.table))
+            .await.map_err(TableError::from)?;
+
+        let groups: surrealdb::Value = res.take(0).map_err(TableError::from)?;
+
+        let Value::Array(groups) = groups.into_inner() else {
+            return Ok(0);
+        };
+
+        let mut records = 0;
+
+        for group in groups.0 {
+            if dry_run {
+                let in_group = count(db, &self.table, &format!("{per} = $group"), ("group", group)).await?;
+
+                records += in_group.saturating_sub(n);
+                continue;
+            }
+
+            let select = format!(
+                "SELECT id, {by} FROM {} WHERE {per} = $group ORDER BY {by} DESC START {n} LIMIT {}",
+                self.table, self.batch_size,
+            );
+
+            records += delete_batches(db, &select, self.batch_size, ("group", group)).await?;
+        }
+
+        Ok(records)
+    }
+}
+
+async fn count<C: Connection>(db: &Surreal<C>, table: &str, cond: &str, param: (&'static str, Value)) -> Result<u64> {
+    let mut res = db.query(format!("RETURN count((SELECT id FROM {table} WHERE {cond}))"))
+        .bind(param)
+        .await.map_err(TableError::from)?;
+
+    let count: Option<u64> = res.take(0).map_err(TableError::from)?;
+
+    Ok(count.unwrap_or_default())
+}
+
+/// Deletes the records of the select until a batch is smaller than the batch size
+async fn delete_batches<C: Connection>(db: &Surreal<C>, select: &str, batch_size: u64, param: (&'static str, Value)) -> Result<u64> {
+    let mut deleted = 0;
+
+    loop {
+        let mut res = db.query(format!("LET $records = ({select}); DELETE $records.id; RETURN array::len($records);"))
+            .bind(param.clone())
+            .await.map_err(TableError::from)?
+            .check().map_err(TableError::from)?;
+
+        let batch: Option<u64> = res.take(2).map_err(TableError::from)?;
+        let batch = batch.unwrap_or_default();
+
+        deleted += batch;
+
+        if batch < batch_size {
+            return Ok(deleted);
+        }
+    }
+}
+
+/// Runs retention jobs at an interval on the tokio runtime
+#[cfg_attr(docsrs, doc(cfg(feature = "retention")))]
+#[cfg(feature = "retention")]
+pub struct RetentionScheduler<C: Connection> {
+    db: Surreal<C>,
+    interval: Duration,
+    jobs: Vec<Retention>,
+    dry_run: bool,
+    on_report: Option<Box<dyn Fn(Result<RetentionReport>) + Send + Sync>>,
+}
+
+#[cfg(feature = "retention")]
+impl<C: Connection> RetentionScheduler<C> {
+    pub fn new(db: Surreal<C>, interval: Duration) -> Self {
+        Self {
+            db,
+            interval,
+            jobs: vec![],
+            dry_run: false,
+            on_report: None,
+        }
+    }
+
+    /// Adds the policies declared on the table
+    pub fn table<T: Table>(mut self) -> Self {
+        self.jobs.extend(Retention::of::<T>());
+
+        self
+    }
+
+    pub fn job(mut self, job: Retention) -> Self {
+        self.jobs.push(job);
+
+        self
+    }
+
+    /// Only reports the records that would be deleted
+    pub fn dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+
+        self
+    }
+
+    /// Called with the result of every job, e.g. to log it
+    pub fn on_report(mut self, on_report: impl Fn(Result<RetentionReport>) + Send + Sync + 'static) -> Self {
+        self.on_report = Some(Box::new(on_report));
+
+        self
+    }
+
+    /// Runs every job once
+    pub async fn run_once(&self) {
+        for job in &self.jobs {
+            let report = job.run(&self.db, self.dry_run).await;
+
+            if let Some(on_report) = &self.on_report {
+                on_report(report);
+            }
+        }
+    }
+
+    /// Runs the jobs right away and then at every interval until the handle is aborted
+    pub fn spawn(self) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(self.interval);
+
+            loop {
+                interval.tick().await;
+
+                self.run_once().await;
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde::{Deserialize, Serialize};
+    use surrealdb::engine::any::Any;
+    use surrealdb::sql::Thing;
+    use super::*;
+
+    #[allow(clippy::duplicated_attributes)]
+    #[derive(Debug, Table, Serialize, Deserialize, Clone, PartialEq)]
+    #[table(name = "event", retention(keep_days = 30, by = "created_at"))]
+    #[table(retention(keep_last = 2, per = "user", by = "created_at"))]
+    struct Event {
+        id: Option<Thing>,
+        user: Thing,
+        created_at: Option<Datetime>,
+    }
+
+    async fn db() -> Surreal<Any> {
+        let db = crate::testing::db().await;
+
+        db.query("
+            CREATE event:1 SET user = user:1, created_at = time::now() - 40d;
+            CREATE event:2 SET user = user:1, created_at = time::now() - 3d;
+            CREATE event:3 SET user = user:1, created_at = time::now() - 2d;
+            CREATE event:4 SET user = user:1, created_at = time::now() - 1d;
+            CREATE event:5 SET user = user:2, created_at = time::now() - 1d;
+            CREATE event:6 SET user = user:2;
+        ").await.unwrap().check().unwrap();
+
+        db
+    }
+
+    #[test]
+    fn derived() {
+        assert_eq!(Event::RETENTION, &[
+            RetentionPolicy::keep_days(30, "created_at"),
+            RetentionPolicy::keep_last(2, "user", "created_at"),
+        ]);
+    }
+
+    #[tokio::test]
+    async fn max_age() {
+        let db = db().await;
+
+        let job = Retention::new("event", RetentionPolicy::keep_days(30, "created_at"));
+
+        assert_eq!(job.run(&db, true).await.unwrap().records, 1);
+        assert_eq!(job.run(&db, false).await.unwrap().records, 1);
+        assert_eq!(Event::get_all(&db).await.unwrap().len(), 5);
+    }
+
+    #[tokio::test]
+    async fn keep_last_batched() {
+        let db = db().await;
+
+        let job = Retention::new("event", RetentionPolicy::keep_last(1, "user", "created_at")).batch_size(1);
+
+        assert_eq!(job.run(&db, true).await.unwrap().records, 4);
+        assert_eq!(job.run(&db, false).await.unwrap().records, 4);
+
+        let mut ids: Vec<String> = Event::get_all(&db).await.unwrap().into_iter().map(|e| e.id.unwrap().id.to_raw()).collect();
+        ids.sort();
+
+        assert_eq!(ids, vec!["4".to_string(), "5".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn apply_declared() {
+        let db = db().await;
+
+        let reports = Event::apply_retention(&db, false).await.unwrap();
+
+        assert_eq!(reports.iter().map(|r| r.records).collect::<Vec<_>>(), vec![1, 1]);
+        assert_eq!(Event::get_all(&db).await.unwrap().len(), 4);
+    }
+
+    #[test]
+    fn invalid_name() {
+        assert!(RetentionPolicy::keep_days(1, "created_at; DELETE user").check("event").is_err());
+    }
+}
@@ -0,0 +1,265 @@
+//! Retry with exponential backoff for transient errors
+//!
+//! Only connection errors (dropped WebSocket/HTTP connections), query timeouts and failed transactions are retried,
+//! every other error is returned right away.
+//!
+//! After a dropped connection or a timeout it is unknown whether the statement ran, so a retried `CREATE` or `RELATE`
+//! can create the record twice. `run` is for idempotent operations (selects, updates and deletes of a record id),
+//! `run_non_idempotent` and `create_with_policy` only retry when the policy opts in with `retry_non_idempotent`.
+//!
+//! # Example
+//!
+//! ```rust
+//! use std::time::Duration;
+//! use serde::{Serialize, Deserialize};
+//! use surrealdb::engine::any::connect;
+//! use surrealdb::sql::Thing as RecordId;
+//! use surrealdb_extra::table::Table;
+//! use surrealdb_extra::table::retry::RetryPolicy;
+//!
+//! #[derive(Table, Serialize, Deserialize, Clone)]
+//! #[table(name = "user")]
+//! struct User {
+//!     id: Option<RecordId>,
+//!     name: String
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let db = connect("mem://").await.unwrap();
+//!     db.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     let policy = RetryPolicy::new(5).base_delay(Duration::from_millis(50));
+//!
+//!     let users = User::get_all_with_policy(&db, &policy).await.unwrap();
+//!
+//!     // Not retried, a retry could create a second user
+//!     let user = User { id: None, name: "name".to_string() }.create_with_policy(&db, &policy).await.unwrap();
+//!
+//!     // Retried, e.g. when the table has a unique index that rejects a second user
+//!     let user = User { id: None, name: "name".to_string() }.create_with_policy(&db, &policy.retry_non_idempotent(true)).await.unwrap();
+//! }
+//! ```
+
+use std::future::Future;
+use std::time::Duration;
+use anyhow::Result;
+use rand::Rng;
+use surrealdb::error::{Api, Db};
+use crate::table::TableError;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+    pub jitter: bool,
+    /// `run_non_idempotent` retries as well
+    pub non_idempotent: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(5),
+            jitter: true,
+            non_idempotent: false,
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn new(max_retries: u32) -> Self {
+        Self {
+            max_retries,
+            ..Self::default()
+        }
+    }
+
+    pub fn base_delay(mut self, base_delay: Duration) -> Self {
+        self.base_delay = base_delay;
+
+        self
+    }
+
+    pub fn max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+
+        self
+    }
+
+    /// With jitter the delay is a random duration between zero and the backoff delay
+    pub fn jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+
+        self
+    }
+
+    /// Retries operations that must not run twice, e.g. `CREATE` without an id or `RELATE`.
+    /// Only opt in when a second run is harmless, e.g. because a unique index rejects a duplicate
+    pub fn retry_non_idempotent(mut self, non_idempotent: bool) -> Self {
+        self.non_idempotent = non_idempotent;
+
+        self
+    }
+
+    /// Delay before the given retry, starting at 0 for the first retry
+    pub fn delay(&self, retry: u32) -> Duration {
+        let delay = self.base_delay
+            .saturating_mul(2u32.saturating_pow(retry))
+            .min(self.max_delay);
+
+        if self.jitter && !delay.is_zero() {
+            return rand::thread_rng().gen_range(Duration::ZERO..=delay);
+        }
+
+        delay
+    }
+
+    /// Runs the operation until it succeeds, fails with a non transient error or runs out of retries.
+    /// The operation has to be idempotent, see `run_non_idempotent`
+    pub async fn run<T, F, Fut>(&self, mut f: F) -> Result<T>
+        where F: FnMut() -> Fut, Fut: Future<Output = Result<T>>
+    {
+        let mut retry = 0;
+
+        loop {
+            match f().await {
+                Err(e) if retry < self.max_retries && is_transient(&e) => {
+                    tokio::time::sleep(self.delay(retry)).await;
+                    retry += 1;
+                }
+                res => return res,
+            }
+        }
+    }
+
+    /// Like `run` for operations that must not run twice, they run once unless the policy has `retry_non_idempotent`
+    pub async fn run_non_idempotent<T, F, Fut>(&self, mut f: F) -> Result<T>
+        where F: FnMut() -> Fut, Fut: Future<Output = Result<T>>
+    {
+        if !self.non_idempotent {
+            return f().await;
+        }
+
+        self.run(f).await
+    }
+}
+
+pub fn is_transient(e: &anyhow::Error) -> bool {
+    let e = match e.downcast_ref::<TableError>() {
+        Some(TableError::Db(e)) => e,
+        Some(_) => return false,
+        None => match e.downcast_ref::<surrealdb::Error>() {
+            Some(e) => e,
+            None => return false,
+        }
+    };
+
+    match e {
+        surrealdb::Error::Api(e) => matches!(e, Api::Ws(_) | Api::Http(_) | Api::ConnectionUninitialised),
+        surrealdb::Error::Db(e) => matches!(e, Db::QueryTimedout | Db::TxFailure | Db::Tx(_) | Db::Ds(_)),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use super::*;
+
+    fn transient() -> anyhow::Error {
+        surrealdb::Error::Api(Api::Ws("connection reset".to_string())).into()
+    }
+
+    #[test]
+    fn backoff() {
+        let policy = RetryPolicy::new(5).base_delay(Duration::from_millis(10)).max_delay(Duration::from_millis(50)).jitter(false);
+
+        assert_eq!(policy.delay(0), Duration::from_millis(10));
+        assert_eq!(policy.delay(1), Duration::from_millis(20));
+        assert_eq!(policy.delay(2), Duration::from_millis(40));
+        assert_eq!(policy.delay(3), Duration::from_millis(50));
+    }
+
+    #[test]
+    fn jitter_within_delay() {
+        let policy = RetryPolicy::new(5).base_delay(Duration::from_millis(10));
+
+        for _ in 0..20 {
+            assert!(policy.delay(1) <= Duration::from_millis(20));
+        }
+    }
+
+    #[tokio::test]
+    async fn retries_transient() {
+        let calls = AtomicU32::new(0);
+        let policy = RetryPolicy::new(3).base_delay(Duration::from_millis(1));
+
+        let res = policy.run(|| async {
+            if calls.fetch_add(1, Ordering::SeqCst) < 2 {
+                return Err(transient());
+            }
+
+            Ok(5)
+        }).await;
+
+        assert_eq!(res.unwrap(), 5);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn gives_up() {
+        let calls = AtomicU32::new(0);
+        let policy = RetryPolicy::new(2).base_delay(Duration::from_millis(1));
+
+        let res: Result<()> = policy.run(|| async {
+            calls.fetch_add(1, Ordering::SeqCst);
+
+            Err(transient())
+        }).await;
+
+        assert!(res.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn non_idempotent_opt_in() {
+        let calls = AtomicU32::new(0);
+        let policy = RetryPolicy::new(2).base_delay(Duration::from_millis(1));
+
+        let res: Result<()> = policy.run_non_idempotent(|| async {
+            calls.fetch_add(1, Ordering::SeqCst);
+
+            Err(transient())
+        }).await;
+
+        assert!(res.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        let res: Result<()> = policy.retry_non_idempotent(true).run_non_idempotent(|| async {
+            calls.fetch_add(1, Ordering::SeqCst);
+
+            Err(transient())
+        }).await;
+
+        assert!(res.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test]
+    async fn does_not_retry_other_errors() {
+        let calls = AtomicU32::new(0);
+        let policy = RetryPolicy::new(2).base_delay(Duration::from_millis(1));
+
+        let res: Result<()> = policy.run(|| async {
+            calls.fetch_add(1, Ordering::SeqCst);
+
+            Err(TableError::IdEmpty.into())
+        }).await;
+
+        assert!(res.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,338 @@
+//! Differences between the derived metadata and the live schema
+//!
+//! `schema_diff` compares `T::meta()` with `INFO FOR TABLE` and returns the fields and indexes that are missing in the database,
+//! the ones that only exist in the database and the indexes that are defined differently.
+//! `sync_schema` applies the safe part of the diff: it defines the missing fields and indexes and never removes or changes anything.
+//!
+//! Missing fields are defined as `option<...>` of the type that matches the Rust type,
+//! so existing records without the field stay valid. Fields with a Rust type that has no matching type are defined without a `TYPE`,
+//! in a `SCHEMAFULL` table the nested fields of those still need their own definitions.
+//! Computed and default fields are defined with their `VALUE` or `DEFAULT` clause.
+//! `#[field(flatten)]` fields are defined as `option<object>` and their nested fields are diffed and defined like the top level ones.
+//!
+//! # Example
+//!
+//! ```rust
+//! use serde::{Serialize, Deserialize};
+//! use surrealdb::engine::any::connect;
+//! use surrealdb::sql::Thing as RecordId;
+//! use surrealdb_extra::table::Table;
+//!
+//! #[derive(Table, Serialize, Deserialize, Clone)]
+//! #[table(name = "user", index(name = "user_email", fields = ["email"], unique))]
+//! struct User {
+//!     id: Option<RecordId>,
+//!     email: String,
+//!     age: Option<i64>
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let db = connect("mem://").await.unwrap();
+//!     db.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     db.query("DEFINE TABLE user SCHEMAFULL; DEFINE FIELD email ON user TYPE string; DEFINE FIELD nickname ON user TYPE string").await.unwrap();
+//!
+//!     let diff = User::schema_diff(&db).await.unwrap();
+//!
+//!     assert_eq!(diff.missing_fields.iter().map(|f| f.name).collect::<Vec<_>>(), ["age"]);
+//!     assert_eq!(diff.extra_fields, ["nickname"]);
+//!     assert_eq!(diff.missing_indexes[0].name, "user_email");
+//!
+//!     User::sync_schema(&db).await.unwrap();
+//!
+//!     let diff = User::schema_diff(&db).await.unwrap();
+//!
+//!     assert!(diff.missing_fields.is_empty() && diff.missing_indexes.is_empty());
+//! }
+//! ```
+
+use anyhow::Result;
+use surrealdb::{Connection, Surreal};
+use crate::query::statement::StatementBuilder;
+use crate::table::meta::{FieldMeta, IndexMeta};
+use crate::table::Table;
+use crate::table::vector::VectorIndex;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SchemaDiff {
+    /// Fields of the struct that are not defined
+    pub missing_fields: Vec<FieldMeta>,
+    /// Nested fields of `#[field(flatten)]` fields that are not defined, with their path
+    pub missing_nested_fields: Vec<(String, FieldMeta)>,
+    /// Top level fields that are defined but not in the struct
+    pub extra_fields: Vec<String>,
+    /// Indexes of `#[table(index(...))]` that are not defined
+    pub missing_indexes: Vec<IndexMeta>,
+    /// Vector indexes of `#[index(vector(...))]` that are not defined
+    pub missing_vector_indexes: Vec<VectorIndex>,
+    /// Indexes that are defined with other fields or uniqueness, `sync_schema` doesn't change them
+    pub changed_indexes: Vec<IndexMeta>,
+    /// Indexes that are defined but not in the struct
+    pub extra_indexes: Vec<String>,
+}
+
+impl SchemaDiff {
+    pub fn is_empty(&self) -> bool {
+        self.missing_fields.is_empty()
+            && self.missing_nested_fields.is_empty()
+            && self.extra_fields.is_empty()
+            && self.missing_indexes.is_empty()
+            && self.missing_vector_indexes.is_empty()
+            && self.changed_indexes.is_empty()
+            && self.extra_indexes.is_empty()
+    }
+
+    /// The `DEFINE` statements that add the missing fields and indexes
+    pub fn additions<T: Table>(&self) -> Vec<String> {
+        let table = T::TABLE_NAME;
+
+        let fields = self.missing_fields.iter().map(|field| {
+            if let Some(computed) = T::COMPUTED_FIELDS.iter().find(|c| c.field == field.name) {
+                return computed.define_field(table);
+            }
+
+            if let Some(default) = T::DEFAULT_FIELDS.iter().find(|d| d.field == field.name) {
+                return default.define_field(table);
+            }
+
+            define_field(table, field.name, field)
+        });
+
+        let nested_fields = self.missing_nested_fields.iter().map(|(path, field)| define_field(table, path, field));
+
+        let indexes = self.missing_indexes.iter().map(|index| {
+            format!("DEFINE INDEX IF NOT EXISTS {} ON TABLE {table} FIELDS {}{}", index.name, index.fields.join(", "), unique(index))
+        });
+
+        let vector_indexes = self.missing_vector_indexes.iter().map(|index| index.define_index(table));
+
+        fields.chain(nested_fields).chain(indexes).chain(vector_indexes).collect()
+    }
+}
+
+pub(crate) async fn diff<T: Table, C: Connection>(db: &Surreal<C>) -> Result<SchemaDiff> {
+    let meta = T::meta();
+    let info = db.info_builder().table(T::TABLE_NAME).execute().await?;
+
+    let fields: Vec<FieldMeta> = meta.fields.iter().filter(|f| f.name != meta.id.field).copied().collect();
+
+    let vector_indexes: Vec<String> = T::VECTOR_INDEXES.iter().map(|v| v.name(T::TABLE_NAME)).collect();
+
+    let mut diff = SchemaDiff {
+        missing_fields: fields.iter().filter(|f| !info.fields.contains_key(f.name)).copied().collect(),
+        missing_nested_fields: meta.nested_fields().into_iter()
+            .filter(|(path, _)| !info.fields.contains_key(path))
+            .map(|(path, field)| (path, *field))
+            .collect(),
+        extra_fields: info.fields.keys()
+            .filter(|name| !name.contains(['.', '[']) && fields.iter().all(|f| f.name != name.as_str()))
+            .cloned()
+            .collect(),
+        extra_indexes: info.indexes.keys()
+            .filter(|name| meta.indexes.iter().all(|i| i.name != name.as_str()) && !vector_indexes.contains(name))
+            .cloned()
+            .collect(),
+        ..Default::default()
+    };
+
+    for index in meta.indexes {
+        match info.indexes.get(index.name) {
+            None => diff.missing_indexes.push(*index),
+            Some(definition) if *definition != format!("DEFINE INDEX {} ON {} FIELDS {}{}", index.name, T::TABLE_NAME, index.fields.join(", "), unique(index)) => {
+                diff.changed_indexes.push(*index)
+            }
+            _ => {}
+        }
+    }
+
+    diff.missing_vector_indexes = T::VECTOR_INDEXES.iter()
+        .filter(|v| !info.indexes.contains_key(&v.name(T::TABLE_NAME)))
+        .copied()
+        .collect();
+
+    Ok(diff)
+}
+
+pub(crate) async fn sync<T: Table, C: Connection>(db: &Surreal<C>) -> Result<SchemaDiff> {
+    let diff = diff::<T, C>(db).await?;
+
+    let additions = diff.additions::<T>();
+
+    if !additions.is_empty() {
+        db.query(additions.join(";\n")).await?.check()?;
+    }
+
+    Ok(diff)
+}
+
+/// Flattened fields are objects, their nested fields get their own definitions
+fn define_field(table: &str, path: &str, field: &FieldMeta) -> String {
+    let kind = match field.nested.is_empty() {
+        true => surql_kind(field.rust_type),
+        false => Some("object".to_string()),
+    };
+
+    match kind {
+        Some(kind) => format!("DEFINE FIELD IF NOT EXISTS {path} ON TABLE {table} TYPE option<{kind}>"),
+        None => format!("DEFINE FIELD IF NOT EXISTS {path} ON TABLE {table}"),
+    }
+}
+
+fn unique(index: &IndexMeta) -> &'static str {
+    if index.unique { " UNIQUE" } else { "" }
+}
+
+/// The SurrealQL type of a Rust type, `None` when there is no matching type
+fn surql_kind(rust_type: &str) -> Option<String> {
+    let rust_type: String = rust_type.chars().filter(|c| !c.is_whitespace()).collect();
+
+    if let Some((wrapper, inner)) = rust_type.split_once('<') {
+        let inner = inner.strip_suffix('>')?;
+        let wrapper = wrapper.rsplit("::").next()?;
+
+        return match wrapper {
+            "Option" | "Computed" | "Box" => surql_kind(inner),
+            "Vec" | "VecDeque" => Some(surql_kind(inner).map(|k| format!("array<{k}>")).unwrap_or("array".to_string())),
+            "HashSet" | "BTreeSet" => Some(surql_kind(inner).map(|k| format!("set<{k}>")).unwrap_or("set".to_string())),
+            "HashMap" | "BTreeMap" => Some("object".to_string()),
+            "Link" => Some("record".to_string()),
+            "Links" => Some("array<record>".to_string()),
+            "DateTime" => Some("datetime".to_string()),
+            _ => None,
+        };
+    }
+
+    let kind = match rust_type.rsplit("::").next()? {
+        "String" | "str" | "&str" | "Strand" => "string",
+        "i8" | "i16" | "i32" | "i64" | "isize" | "u8" | "u16" | "u32" | "u64" | "usize" => "int",
+        "f32" | "f64" => "float",
+        "bool" => "bool",
+        "Decimal" => "decimal",
+        "Thing" | "RecordId" => "record",
+        "Datetime" => "datetime",
+        "Duration" => "duration",
+        "Uuid" => "uuid",
+        "Bytes" => "bytes",
+        _ => return None,
+    };
+
+    Some(kind.to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use serde::{Deserialize, Serialize};
+    use surrealdb::sql::Thing;
+    use crate::table::computed::Computed;
+    use crate::table::Embedded;
+    use crate::testing::db;
+    use super::*;
+
+    #[derive(Debug, Table, Serialize, Deserialize, Clone)]
+    #[table(name = "user", index(name = "user_email", fields = ["email"], unique), index(name = "user_name", fields = ["name"]))]
+    struct User {
+        id: Option<Thing>,
+        email: String,
+        name: String,
+        tags: Vec<String>,
+        #[field(computed = "string::len(name)")]
+        #[serde(default)]
+        name_length: Computed<i64>,
+        settings: Settings,
+    }
+
+    #[derive(Debug, Serialize, Deserialize, Clone)]
+    struct Settings {
+        #[serde(default)]
+        dark: bool,
+    }
+
+    #[test]
+    fn kinds() {
+        assert_eq!(surql_kind("String").as_deref(), Some("string"));
+        assert_eq!(surql_kind("Option<Vec<i64>>").as_deref(), Some("array<int>"));
+        assert_eq!(surql_kind("Vec<Settings>").as_deref(), Some("array"));
+        assert_eq!(surql_kind("chrono::DateTime<Utc>").as_deref(), Some("datetime"));
+        assert_eq!(surql_kind("Links<User>").as_deref(), Some("array<record>"));
+        assert_eq!(surql_kind("Settings"), None);
+    }
+
+    #[tokio::test]
+    async fn diff_and_sync() {
+        let db = db().await;
+
+        db.query("
+            DEFINE TABLE user SCHEMAFULL;
+            DEFINE FIELD email ON user TYPE string;
+            DEFINE FIELD legacy ON user TYPE option<object>;
+            DEFINE FIELD legacy.nested ON user TYPE string;
+            DEFINE FIELD name ON user TYPE string;
+            DEFINE INDEX user_name ON user FIELDS name UNIQUE;
+            DEFINE INDEX user_legacy ON user FIELDS legacy;
+        ").await.unwrap().check().unwrap();
+
+        let diff = User::schema_diff(&db).await.unwrap();
+
+        assert_eq!(diff.missing_fields.iter().map(|f| f.name).collect::<Vec<_>>(), ["tags", "name_length", "settings"]);
+        assert_eq!(diff.extra_fields, ["legacy"]);
+        assert_eq!(diff.missing_indexes.iter().map(|i| i.name).collect::<Vec<_>>(), ["user_email"]);
+        assert_eq!(diff.changed_indexes.iter().map(|i| i.name).collect::<Vec<_>>(), ["user_name"]);
+        assert_eq!(diff.extra_indexes, ["user_legacy"]);
+
+        let synced = User::sync_schema(&db).await.unwrap();
+        assert_eq!(synced, diff);
+
+        let diff = User::schema_diff(&db).await.unwrap();
+
+        assert!(diff.missing_fields.is_empty());
+        assert!(diff.missing_indexes.is_empty());
+        assert_eq!(diff.changed_indexes.len(), 1);
+
+        let user: Option<User> = db.query("CREATE user SET email = 'a@b.c', name = 'john', tags = ['a'], settings = { dark: true }")
+            .await.unwrap().take(0).unwrap();
+        assert_eq!(user.unwrap().name_length.get(), Some(&4));
+    }
+
+    #[tokio::test]
+    async fn in_sync() {
+        let db = db().await;
+
+        User::sync_schema(&db).await.unwrap();
+
+        let diff = User::schema_diff(&db).await.unwrap();
+        assert!(diff.is_empty(), "{diff:?}");
+    }
+
+    #[derive(Debug, Embedded, Serialize, Deserialize, Clone)]
+    struct Address {
+        city: String,
+        zip: Option<String>,
+    }
+
+    #[derive(Debug, Table, Serialize, Deserialize, Clone)]
+    #[table(name = "shop")]
+    struct Shop {
+        id: Option<Thing>,
+        #[field(flatten)]
+        address: Address,
+    }
+
+    #[tokio::test]
+    async fn flattened_fields() {
+        let db = db().await;
+
+        db.query("DEFINE TABLE shop SCHEMAFULL; DEFINE FIELD address.city ON shop TYPE string").await.unwrap().check().unwrap();
+
+        let diff = Shop::schema_diff(&db).await.unwrap();
+
+        assert_eq!(diff.missing_nested_fields.iter().map(|(path, _)| path.as_str()).collect::<Vec<_>>(), ["address.zip"]);
+        assert_eq!(diff.additions::<Shop>()[..2], [
+            "DEFINE FIELD IF NOT EXISTS address ON TABLE shop TYPE option<object>".to_string(),
+            "DEFINE FIELD IF NOT EXISTS address.zip ON TABLE shop TYPE option<string>".to_string(),
+        ]);
+
+        Shop::sync_schema(&db).await.unwrap();
+        assert!(Shop::schema_diff(&db).await.unwrap().is_empty());
+    }
+}
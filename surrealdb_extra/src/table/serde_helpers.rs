@@ -0,0 +1,162 @@
+//! Record ids as `table:id` strings at API boundaries
+//!
+//! `Thing` serializes as `{ tb, id }` with serde_json, `thing_as_string` and `option_thing_as_string` write it as `user:john` instead
+//! and read it back from the same string, for `#[serde(with = "...")]` fields of API types.
+//! Formats that are not human readable, like the one of surrealdb, still get the `Thing` itself,
+//! so the same struct can be written to the database and returned by an API.
+//!
+//! With `#[table(id_string)]` the `<Struct>Id` of the derive is also a `table:id` string and only accepts ids of its table.
+//!
+//! # Example
+//!
+//! ```rust
+//! use serde::{Serialize, Deserialize};
+//! use surrealdb::sql::Thing as RecordId;
+//! use surrealdb_extra::table::Table;
+//!
+//! #[derive(Table, Serialize, Deserialize, Clone)]
+//! #[table(name = "user", id_string)]
+//! struct User {
+//!     #[serde(with = "surrealdb_extra::table::serde_helpers::option_thing_as_string")]
+//!     id: Option<RecordId>,
+//!     name: String
+//! }
+//!
+//! let user = User { id: Some(RecordId::from(("user", "john"))), name: "John".to_string() };
+//! assert_eq!(serde_json::to_string(&user).unwrap(), r#"{"id":"user:john","name":"John"}"#);
+//!
+//! let id: UserId = serde_json::from_str(r#""user:john""#).unwrap();
+//! assert_eq!(id.to_string(), "user:john");
+//!
+//! // Ids of other tables are rejected
+//! assert!(serde_json::from_str::<UserId>(r#""post:john""#).is_err());
+//! ```
+
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use surrealdb::sql::Thing;
+
+/// Parses `table:id`, also `table:⟨id⟩` and ids that are not valid SurrealQL like `user:john doe`
+pub fn parse_thing(value: &str) -> Option<Thing> {
+    if let Ok(thing) = surrealdb::sql::thing(value) {
+        return Some(thing);
+    }
+
+    match value.split_once(':') {
+        Some((tb, id)) if !tb.is_empty() && !id.is_empty() => Some(Thing::from((tb, id))),
+        _ => None,
+    }
+}
+
+/// `Thing` as a `table:id` string
+pub mod thing_as_string {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(thing: &Thing, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&thing.to_string())
+        } else {
+            thing.serialize(serializer)
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Thing, D::Error> {
+        if !deserializer.is_human_readable() {
+            return Thing::deserialize(deserializer);
+        }
+
+        let value = String::deserialize(deserializer)?;
+
+        parse_thing(&value).ok_or_else(|| D::Error::custom(format!("`{value}` is not a record id, expected `table:id`")))
+    }
+}
+
+/// `Option<Thing>` as a `table:id` string or `null`
+pub mod option_thing_as_string {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(thing: &Option<Thing>, serializer: S) -> Result<S::Ok, S::Error> {
+        match thing {
+            Some(thing) => serializer.serialize_some(&AsString(thing)),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Thing>, D::Error> {
+        Ok(Option::<FromString>::deserialize(deserializer)?.map(|thing| thing.0))
+    }
+}
+
+struct AsString<'a>(&'a Thing);
+
+impl Serialize for AsString<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        thing_as_string::serialize(self.0, serializer)
+    }
+}
+
+struct FromString(Thing);
+
+impl<'de> Deserialize<'de> for FromString {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        thing_as_string::deserialize(deserializer).map(Self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde::{Deserialize, Serialize};
+    use surrealdb::sql::Thing as RecordId;
+    use crate::table::Table;
+    use crate::testing::db;
+    use super::*;
+
+    #[derive(Debug, Table, Serialize, Deserialize, Clone, PartialEq)]
+    #[table(name = "user", id_string)]
+    struct User {
+        #[serde(with = "option_thing_as_string", default)]
+        id: Option<RecordId>,
+        #[serde(with = "thing_as_string")]
+        friend: RecordId,
+    }
+
+    #[test]
+    fn parse_things() {
+        assert_eq!(parse_thing("user:john"), Some(RecordId::from(("user", "john"))));
+        assert_eq!(parse_thing("user:⟨john doe⟩"), Some(RecordId::from(("user", "john doe"))));
+        assert_eq!(parse_thing("user:john doe"), Some(RecordId::from(("user", "john doe"))));
+        assert_eq!(parse_thing("john"), None);
+    }
+
+    #[test]
+    fn json_strings() {
+        let user = User { id: None, friend: RecordId::from(("user", "jane")) };
+        assert_eq!(serde_json::to_string(&user).unwrap(), r#"{"id":null,"friend":"user:jane"}"#);
+
+        let user: User = serde_json::from_str(r#"{"id":"user:john","friend":"user:jane"}"#).unwrap();
+        assert_eq!(user.id, Some(RecordId::from(("user", "john"))));
+        assert_eq!(serde_json::to_string(&user).unwrap(), r#"{"id":"user:john","friend":"user:jane"}"#);
+
+        assert!(serde_json::from_str::<User>(r#"{"friend":"jane"}"#).is_err());
+    }
+
+    #[test]
+    fn id_string() {
+        let id = UserId::new("john");
+
+        assert_eq!(serde_json::to_string(&id).unwrap(), r#""user:john""#);
+        assert_eq!(serde_json::from_str::<UserId>(r#""user:john""#).unwrap(), id);
+        assert!(serde_json::from_str::<UserId>(r#""post:john""#).is_err());
+    }
+
+    #[tokio::test]
+    async fn database_things() {
+        let db = db().await;
+
+        let user = User { id: None, friend: RecordId::from(("user", "jane")) }.create(&db).await.unwrap().unwrap();
+
+        let friend: Option<RecordId> = db.query("SELECT VALUE friend FROM ONLY $id").bind(("id", user.id.clone().unwrap())).await.unwrap().take(0).unwrap();
+        assert_eq!(friend, Some(RecordId::from(("user", "jane"))));
+        assert_eq!(User::get_by_id(&db, user.id.clone().unwrap()).await.unwrap(), Some(user));
+    }
+}
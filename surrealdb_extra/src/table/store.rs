@@ -0,0 +1,293 @@
+//! CRUD of a table behind a trait, for services that are unit tested without a database
+//!
+//! `TableStore<T>` has the create, update, delete and get functions of `Table`.
+//! `SurrealStore` runs them with the `Table` functions and `MockTableStore` keeps the records in a `HashMap`,
+//! so a service that takes a `TableStore` can be tested without starting a `mem://` database.
+//!
+//! `MockTableStore` only mirrors the behaviour that doesn't need a database: `validate_record` is called,
+//! ids are generated with the `ID_STRATEGY` of the table (random for `Server`) and `update` replaces the record
+//! instead of merging it. The hooks, `SKIP_FIELDS`, `READONLY_FIELDS` and `VERSION_FIELD` are not used.
+//!
+//! # Example
+//!
+//! ```rust
+//! use serde::{Serialize, Deserialize};
+//! use surrealdb::engine::any::connect;
+//! use surrealdb::sql::Thing as RecordId;
+//! use surrealdb_extra::table::Table;
+//! use surrealdb_extra::table::store::{MockTableStore, SurrealStore, TableStore};
+//!
+//! #[derive(Table, Serialize, Deserialize, Clone)]
+//! #[table(name = "user")]
+//! struct User {
+//!     id: Option<RecordId>,
+//!     name: String
+//! }
+//!
+//! async fn register(store: &dyn TableStore<User>, name: &str) -> anyhow::Result<RecordId> {
+//!     let user = store.create(User { id: None, name: name.to_string() }).await?.unwrap();
+//!
+//!     Ok(user.id.unwrap())
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let mock = MockTableStore::<User>::new();
+//!     let id = register(&mock, "name").await.unwrap();
+//!     assert_eq!(mock.get_by_id(id).await.unwrap().unwrap().name, "name");
+//!
+//!     let db = connect("mem://").await.unwrap();
+//!     db.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     let store = SurrealStore::new(db);
+//!     let id = register(&store, "name").await.unwrap();
+//!     assert_eq!(TableStore::<User>::get_by_id(&store, id).await.unwrap().unwrap().name, "name");
+//! }
+//! ```
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use ::async_trait::async_trait;
+use anyhow::Result;
+use surrealdb::{Connection, Surreal};
+use surrealdb::sql::{Id, Thing};
+use crate::table::{Table, TableError};
+use crate::table::id::{IdStrategy, IntoTableId};
+
+/// The CRUD functions of `Table` for the table `T`
+#[cfg_attr(not(feature = "wasm"), async_trait)]
+#[cfg_attr(feature = "wasm", async_trait(?Send))]
+pub trait TableStore<T: Table>: Send + Sync {
+    async fn create(&self, t: T) -> Result<Option<T>>;
+
+    async fn update(&self, t: T) -> Result<Option<T>>;
+
+    async fn delete(&self, id: Thing) -> Result<Option<T>>;
+
+    async fn get_all(&self) -> Result<Vec<T>>;
+
+    async fn get_by_id(&self, id: Thing) -> Result<Option<T>>;
+}
+
+/// Runs the `Table` functions on the database
+#[derive(Debug, Clone)]
+pub struct SurrealStore<C>
+    where C: Connection
+{
+    pub db: Surreal<C>,
+}
+
+impl<C: Connection> SurrealStore<C> {
+    pub fn new(db: Surreal<C>) -> Self {
+        Self {
+            db,
+        }
+    }
+}
+
+#[cfg_attr(not(feature = "wasm"), async_trait)]
+#[cfg_attr(feature = "wasm", async_trait(?Send))]
+impl<T: Table, C: Connection> TableStore<T> for SurrealStore<C> {
+    async fn create(&self, t: T) -> Result<Option<T>> {
+        t.create(&self.db).await
+    }
+
+    async fn update(&self, t: T) -> Result<Option<T>> {
+        t.update(&self.db).await
+    }
+
+    async fn delete(&self, id: Thing) -> Result<Option<T>> {
+        T::delete(&self.db, id).await
+    }
+
+    async fn get_all(&self) -> Result<Vec<T>> {
+        T::get_all(&self.db).await
+    }
+
+    async fn get_by_id(&self, id: Thing) -> Result<Option<T>> {
+        T::get_by_id(&self.db, id).await
+    }
+}
+
+/// Keeps the records in memory, see the module docs for what is left out
+pub struct MockTableStore<T>
+    where T: Table
+{
+    pub(crate) records: Mutex<HashMap<Thing, T>>,
+    pub(crate) sequence: Mutex<i64>,
+}
+
+impl<T: Table> Default for MockTableStore<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Table> MockTableStore<T> {
+    pub fn new() -> Self {
+        Self {
+            records: Mutex::new(HashMap::new()),
+            sequence: Mutex::new(0),
+        }
+    }
+
+    /// A store with the records, records without an id get one like on `create`
+    pub fn with_records(records: impl IntoIterator<Item = T>) -> Self {
+        let store = Self::new();
+
+        for mut t in records {
+            let id = store.record_id(&mut t);
+            store.records().insert(id, t);
+        }
+
+        store
+    }
+
+    pub fn len(&self) -> usize {
+        self.records().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records().is_empty()
+    }
+
+    fn records(&self) -> std::sync::MutexGuard<'_, HashMap<Thing, T>> {
+        self.records.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// The id of the record, a new one is generated and set when it has none
+    fn record_id(&self, t: &mut T) -> Thing {
+        if let Some(id) = t.get_id() {
+            return id.clone();
+        }
+
+        let id = match T::ID_STRATEGY {
+            IdStrategy::Incremental => {
+                let mut sequence = self.sequence.lock().unwrap_or_else(|e| e.into_inner());
+                *sequence += 1;
+
+                Id::from(*sequence)
+            }
+            strategy => strategy.generate().unwrap_or_else(Id::rand),
+        };
+
+        t.set_id(id.clone());
+
+        T::create_record_id(id)
+    }
+}
+
+#[cfg_attr(not(feature = "wasm"), async_trait)]
+#[cfg_attr(feature = "wasm", async_trait(?Send))]
+impl<T: Table + Clone> TableStore<T> for MockTableStore<T> {
+    /// Returns `TableError::RecordExists` when the id is already used
+    async fn create(&self, mut t: T) -> Result<Option<T>> {
+        t.validate_record().map_err(TableError::from)?;
+
+        let id = IntoTableId::<T>::into_table_id(self.record_id(&mut t))?;
+        let mut records = self.records();
+
+        if records.contains_key(&id) {
+            return Err(TableError::RecordExists(id.to_raw()).into());
+        }
+
+        records.insert(id, t.clone());
+
+        Ok(Some(t))
+    }
+
+    async fn update(&self, t: T) -> Result<Option<T>> {
+        t.validate_record().map_err(TableError::from)?;
+
+        let id = IntoTableId::<T>::into_table_id(t.get_id().clone().ok_or(TableError::IdEmpty)?)?;
+
+        Ok(self.records().get_mut(&id).map(|record| {
+            *record = t.clone();
+
+            t
+        }))
+    }
+
+    async fn delete(&self, id: Thing) -> Result<Option<T>> {
+        let id = IntoTableId::<T>::into_table_id(id)?;
+
+        Ok(self.records().remove(&id))
+    }
+
+    /// Sorted by id
+    async fn get_all(&self) -> Result<Vec<T>> {
+        let mut records: Vec<(Thing, T)> = self.records().iter().map(|(id, t)| (id.clone(), t.clone())).collect();
+
+        records.sort_by_key(|(id, _)| id.to_raw());
+
+        Ok(records.into_iter().map(|(_, t)| t).collect())
+    }
+
+    async fn get_by_id(&self, id: Thing) -> Result<Option<T>> {
+        let id = IntoTableId::<T>::into_table_id(id)?;
+
+        Ok(self.records().get(&id).cloned())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde::{Deserialize, Serialize};
+    use surrealdb::sql::Thing as RecordId;
+    use crate::testing::db;
+    use super::*;
+
+    #[derive(Debug, Table, Serialize, Deserialize, Clone, PartialEq)]
+    #[table(name = "user", id = "incremental")]
+    struct User {
+        id: Option<RecordId>,
+        name: String,
+    }
+
+    /// Runs the same steps on both stores
+    async fn crud(store: &dyn TableStore<User>) {
+        let one = store.create(User { id: None, name: "one".to_string() }).await.unwrap().unwrap();
+        let two = store.create(User { id: None, name: "two".to_string() }).await.unwrap().unwrap();
+
+        assert_eq!(one.id, Some(RecordId::from(("user", Id::from(1)))));
+        assert!(store.create(one.clone()).await.is_err());
+
+        assert_eq!(store.get_all().await.unwrap(), [one.clone(), two.clone()]);
+
+        let changed = User { name: "changed".to_string(), ..one.clone() };
+        assert_eq!(store.update(changed.clone()).await.unwrap(), Some(changed.clone()));
+        assert_eq!(store.get_by_id(one.id.clone().unwrap()).await.unwrap(), Some(changed.clone()));
+
+        assert_eq!(store.delete(one.id.clone().unwrap()).await.unwrap(), Some(changed.clone()));
+        assert_eq!(store.update(changed).await.unwrap(), None);
+        assert_eq!(store.get_by_id(one.id.unwrap()).await.unwrap(), None);
+
+        assert!(store.get_by_id(RecordId::from(("post", "one"))).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn mock_store() {
+        let store = MockTableStore::<User>::new();
+
+        crud(&store).await;
+
+        assert_eq!(store.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn surreal_store() {
+        let db = db().await;
+
+        crud(&SurrealStore::new(db)).await;
+    }
+
+    #[tokio::test]
+    async fn with_records() {
+        let store = MockTableStore::with_records([
+            User { id: Some(RecordId::from(("user", "a"))), name: "a".to_string() },
+            User { id: None, name: "b".to_string() },
+        ]);
+
+        assert_eq!(store.get_all().await.unwrap().iter().map(|u| u.id.clone().unwrap().to_raw()).collect::<Vec<_>>(), ["user:1", "user:a"]);
+    }
+}
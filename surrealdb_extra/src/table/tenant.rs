@@ -0,0 +1,308 @@
+//! Tenant scoped `Table` operations
+//!
+//! `Tenant` wraps the `Table` operations of one tenant, every select, update and delete gets `WHERE field = tenant`
+//! and every create and update writes the tenant into the field, so records of other tenants can't be read or changed through it.
+//! Otherwise the operations are the same as the ones of `Table`, e.g. `update` checks the version of `#[table(versioned)]`.
+//! The field is set with `#[table(tenant_field = "...")]` or passed to `Tenant::with_field`.
+//!
+//! # Example
+//!
+//! ```rust
+//! use serde::{Serialize, Deserialize};
+//! use surrealdb::engine::any::connect;
+//! use surrealdb::sql::Thing as RecordId;
+//! use surrealdb_extra::table::Table;
+//! use surrealdb_extra::table::tenant::Tenant;
+//!
+//! #[derive(Table, Serialize, Deserialize, Clone)]
+//! #[table(name = "project", tenant_field = "org_id")]
+//! struct Project {
+//!     id: Option<RecordId>,
+//!     org_id: String,
+//!     name: String
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let db = connect("mem://").await.unwrap();
+//!     db.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     let acme = Tenant::<Project, _>::new(&db, "acme").unwrap();
+//!     let other = Tenant::<Project, _>::new(&db, "other").unwrap();
+//!
+//!     // `org_id` is set to `acme` whatever the struct has
+//!     let project = acme.create(Project { id: None, org_id: String::new(), name: "name".to_string() }).await.unwrap().unwrap();
+//!     assert_eq!(project.org_id, "acme");
+//!
+//!     let id = project.id.unwrap();
+//!     assert!(acme.get_by_id(id.clone()).await.unwrap().is_some());
+//!     assert!(other.get_by_id(id.clone()).await.unwrap().is_none());
+//!     assert!(other.delete(id).await.unwrap().is_none());
+//! }
+//! ```
+
+use std::marker::PhantomData;
+use anyhow::Result;
+use surrealdb::{Connection, Surreal};
+use surrealdb::sql::{Cond, Expression, Idiom, Operator, Part, Value};
+use crate::table::{config, fetch, version, Table, TableError};
+use crate::table::id::{self, IntoTableId};
+use crate::table::write::write_content;
+
+#[cfg(feature = "query")]
+use surrealdb::sql::{Field, Subquery};
+
+#[cfg(feature = "query")]
+use crate::query::{
+    select::SelectBuilder,
+    states::{FilledCond, FilledFields, FilledWhat},
+    parsing::cond::ExtraCond,
+};
+
+/// Wraps the `Table` operations and scopes them to one tenant
+pub struct Tenant<'r, T, C>
+    where T: Table, C: Connection
+{
+    pub(crate) db: &'r Surreal<C>,
+    pub field: String,
+    pub tenant: Value,
+    pub(crate) table: PhantomData<T>,
+}
+
+impl<'r, T, C> Tenant<'r, T, C>
+    where T: Table, C: Connection
+{
+    /// Uses the field of `#[table(tenant_field = "...")]`, returns `TableError::NoTenantField` for tables without one
+    pub fn new(db: &'r Surreal<C>, tenant: impl Into<Value>) -> Result<Self> {
+        let field = T::TENANT_FIELD.ok_or_else(|| TableError::NoTenantField(T::TABLE_NAME.to_string()))?;
+
+        Ok(Self::with_field(db, field, tenant))
+    }
+
+    pub fn with_field(db: &'r Surreal<C>, field: impl Into<String>, tenant: impl Into<Value>) -> Self {
+        Self {
+            db,
+            field: field.into(),
+            tenant: tenant.into(),
+            table: PhantomData,
+        }
+    }
+
+    /// `field = tenant`
+    pub fn cond(&self) -> Cond {
+        let mut cond = Cond::default();
+        cond.0 = Value::Expression(Box::new(Expression::Binary {
+            l: Value::Idiom(Idiom::from(vec![Part::from(self.field.as_str())])),
+            o: Operator::Equal,
+            r: self.tenant.clone(),
+        }));
+
+        cond
+    }
+
+    /// The content of the record with the tenant in the field
+    fn content(&self, t: T) -> Result<Value> {
+        let mut content = write_content(t)?;
+
+        content.put(&[Part::from(self.field.as_str())], self.tenant.clone());
+
+        Ok(content)
+    }
+
+    pub async fn get_all(&self) -> Result<Vec<T>> {
+        let mut statement = fetch::select(vec![Value::Table(T::TABLE_NAME.into())], false, T::FETCH_FIELDS);
+        statement.cond = Some(self.cond());
+
+        let vec_t: Vec<T> = self.db.query(config::apply(statement)).await?.take(0)?;
+
+        Ok(vec_t)
+    }
+
+    /// `None` for records of other tenants
+    pub async fn get_by_id(&self, id: impl IntoTableId<T>) -> Result<Option<T>> {
+        let mut statement = fetch::select(vec![Value::Thing(id.into_table_id()?)], false, T::FETCH_FIELDS);
+        statement.cond = Some(self.cond());
+
+        let vec_t: Vec<T> = self.db.query(config::apply(statement)).await?.take(0)?;
+
+        Ok(vec_t.into_iter().next())
+    }
+
+    /// Creates the record with the tenant in the field
+    pub async fn create(&self, mut t: T) -> Result<Option<T>> {
+        t.before_create(self.db).await?;
+        t.validate_record().map_err(TableError::from)?;
+        id::assign_id(&mut t, self.db).await?;
+
+        let content = self.content(t)?;
+
+        let t: Option<T> = if config::enabled() {
+            self.db.query(config::create(T::TABLE_NAME, content)).await?.take(0)?
+        } else {
+            self.db.create(T::TABLE_NAME).content(content).await?
+        };
+
+        if let Some(t) = &t {
+            t.after_create(self.db).await?;
+        }
+
+        Ok(t)
+    }
+
+    /// Merges the record like `Table::update`, records of other tenants are not updated and `None` is returned,
+    /// for a stale version of a record of this tenant it returns `TableError::StaleRecord`
+    pub async fn update(&self, mut t: T) -> Result<Option<T>> {
+        t.before_update(self.db).await?;
+        t.validate_record().map_err(TableError::from)?;
+
+        let id = t.get_id().clone().ok_or(TableError::IdEmpty)?;
+        let content = self.content(t)?;
+
+        let t: Option<T> = match T::VERSION_FIELD {
+            Some(field) => version::update(self.db, id, field, content, Some(self.cond())).await?,
+            None => {
+                // Without `ONLY`, a record of another tenant is no result instead of an error
+                let mut statement = config::merge(id, content);
+                statement.cond = Some(self.cond());
+                statement.only = false;
+
+                let vec_t: Vec<T> = self.db.query(statement).await?.take(0)?;
+
+                vec_t.into_iter().next()
+            }
+        };
+
+        if let Some(t) = &t {
+            t.after_update(self.db).await?;
+        }
+
+        Ok(t)
+    }
+
+    /// Records of other tenants are not deleted and `None` is returned
+    pub async fn delete(&self, id: impl IntoTableId<T>) -> Result<Option<T>> {
+        let id = id.into_table_id()?;
+
+        T::before_delete(self.db, &id).await?;
+
+        let mut statement = config::delete(id);
+        statement.cond = Some(self.cond());
+        statement.only = false;
+
+        let vec_t: Vec<T> = self.db.query(statement).await?.take(0)?;
+
+        Ok(vec_t.into_iter().next())
+    }
+
+    /// `SELECT * FROM table WHERE field = tenant`
+    #[cfg(feature = "query")]
+    pub fn select_builder(&self) -> SelectBuilder<'r, C, FilledWhat, FilledFields, FilledCond> {
+        T::select_builder(self.db, None).field(Field::All).condition(self.cond())
+    }
+
+    /// `SELECT * FROM table WHERE field = tenant AND (cond)`
+    #[cfg(feature = "query")]
+    pub fn select_builder_where(&self, cond: impl Into<ExtraCond>) -> SelectBuilder<'r, C, FilledWhat, FilledFields, FilledCond> {
+        let cond = Expression::Binary {
+            l: self.cond().0,
+            o: Operator::And,
+            r: Value::Subquery(Box::new(Subquery::Value(cond.into().0.0))),
+        };
+
+        T::select_builder(self.db, None).field(Field::All).condition(cond)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde::{Deserialize, Serialize};
+    use surrealdb::sql::Thing as RecordId;
+    use crate::testing::db;
+    use super::*;
+
+    #[derive(Debug, Table, Serialize, Deserialize, Clone, PartialEq)]
+    #[table(name = "project", tenant_field = "org")]
+    struct Project {
+        id: Option<RecordId>,
+        org: String,
+        name: String,
+    }
+
+    #[derive(Debug, Table, Serialize, Deserialize, Clone, PartialEq)]
+    #[table(name = "doc", tenant_field = "org", versioned)]
+    struct Doc {
+        id: Option<RecordId>,
+        org: String,
+        version: u64,
+    }
+
+    #[derive(Debug, Table, Serialize, Deserialize, Clone, PartialEq)]
+    #[table(name = "user")]
+    struct User {
+        id: Option<RecordId>,
+    }
+
+    #[test]
+    fn tenant_field() {
+        assert_eq!(Project::TENANT_FIELD, Some("org"));
+        assert_eq!(User::TENANT_FIELD, None);
+    }
+
+    #[tokio::test]
+    async fn tenant_scoped() {
+        let db = db().await;
+
+        assert!(Tenant::<User, _>::new(&db, "a").is_err());
+
+        let a = Tenant::<Project, _>::new(&db, "a").unwrap();
+        let b = Tenant::<Project, _>::new(&db, "b").unwrap();
+
+        let project = a.create(Project { id: None, org: "b".to_string(), name: "one".to_string() }).await.unwrap().unwrap();
+        assert_eq!(project.org, "a");
+
+        b.create(Project { id: None, org: String::new(), name: "two".to_string() }).await.unwrap();
+
+        assert_eq!(a.get_all().await.unwrap(), vec![project.clone()]);
+        assert_eq!(b.get_by_id(project.id.clone().unwrap()).await.unwrap(), None);
+
+        let mut changed = project.clone();
+        changed.name = "changed".to_string();
+        assert_eq!(b.update(changed.clone()).await.unwrap(), None);
+
+        changed.org = "b".to_string();
+        let updated = a.update(changed).await.unwrap().unwrap();
+        assert_eq!((updated.org.as_str(), updated.name.as_str()), ("a", "changed"));
+
+        assert_eq!(b.delete(project.id.clone().unwrap()).await.unwrap(), None);
+        assert!(a.delete(project.id.unwrap()).await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn tenant_versioned() {
+        let db = db().await;
+
+        let a = Tenant::<Doc, _>::new(&db, "a").unwrap();
+        let b = Tenant::<Doc, _>::new(&db, "b").unwrap();
+
+        let doc = a.create(Doc { id: None, org: String::new(), version: 0 }).await.unwrap().unwrap();
+
+        assert_eq!(b.update(doc.clone()).await.unwrap(), None);
+
+        let updated = a.update(doc.clone()).await.unwrap().unwrap();
+        assert_eq!(updated.version, 1);
+
+        let err = a.update(doc).await.unwrap_err();
+        assert!(matches!(err.downcast_ref::<TableError>(), Some(TableError::StaleRecord { version: 0, .. })));
+    }
+
+    #[cfg(feature = "query")]
+    #[tokio::test]
+    async fn tenant_select_builder() {
+        let db = db().await;
+
+        let b = Tenant::<Project, _>::new(&db, "b").unwrap();
+
+        assert_eq!(b.select_builder().to_surql(), "SELECT * FROM project WHERE org = 'b'");
+        assert_eq!(b.select_builder_where("name = 'one' OR name = 'two'").to_surql(), "SELECT * FROM project WHERE org = 'b' AND (name = 'one' OR name = 'two')");
+    }
+}
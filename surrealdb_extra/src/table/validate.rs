@@ -0,0 +1,228 @@
+//! Validation before a record is written
+//!
+//! With `#[table(validate)]` the derive calls `Validate::validate` in `create` and `update`,
+//! an invalid record returns `TableError::Validation` without sending a query to the database.
+//! With the `validator` feature `#[table(validate = "validator")]` uses the `validator::Validate` implementation instead.
+//!
+//! `create_builder` and `update_builder` don't validate.
+//!
+//! # Example
+//!
+//! ```rust
+//! use serde::{Serialize, Deserialize};
+//! use surrealdb::engine::any::connect;
+//! use surrealdb::sql::Thing as RecordId;
+//! use surrealdb_extra::table::{Table, TableError};
+//! use surrealdb_extra::table::validate::{Validate, ValidationError};
+//!
+//! #[derive(Debug, Table, Serialize, Deserialize, Clone)]
+//! #[table(name = "user", validate)]
+//! struct User {
+//!     id: Option<RecordId>,
+//!     name: String,
+//!     age: u32
+//! }
+//!
+//! impl Validate for User {
+//!     fn validate(&self) -> Result<(), ValidationError> {
+//!         let mut errors = ValidationError::default();
+//!
+//!         if self.name.is_empty() {
+//!             errors.add("name", "must not be empty");
+//!         }
+//!
+//!         if self.age < 18 {
+//!             errors.add("age", "must be at least 18");
+//!         }
+//!
+//!         errors.into_result()
+//!     }
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let db = connect("mem://").await.unwrap();
+//!     db.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     let err = User { id: None, name: "".to_string(), age: 12 }.create(&db).await.unwrap_err();
+//!
+//!     let Some(TableError::Validation(errors)) = err.downcast_ref::<TableError>() else { panic!() };
+//!     assert_eq!(errors.messages("age").collect::<Vec<_>>(), ["must be at least 18"]);
+//! }
+//! ```
+
+use std::fmt::{Display, Formatter};
+
+pub trait Validate {
+    fn validate(&self) -> Result<(), ValidationError>;
+}
+
+/// A failed validation of a single field
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
+
+/// Every field error of a record, it is valid when there are none
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ValidationError {
+    pub errors: Vec<FieldError>,
+}
+
+impl ValidationError {
+    pub fn add(&mut self, field: impl Into<String>, message: impl Into<String>) {
+        self.errors.push(FieldError {
+            field: field.into(),
+            message: message.into(),
+        });
+    }
+
+    pub fn field(mut self, field: impl Into<String>, message: impl Into<String>) -> Self {
+        self.add(field, message);
+
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// The messages of the field in the order they were added
+    pub fn messages<'a>(&'a self, field: &'a str) -> impl Iterator<Item = &'a str> {
+        self.errors.iter().filter(move |e| e.field == field).map(|e| e.message.as_str())
+    }
+
+    /// `Ok` when there are no errors
+    pub fn into_result(self) -> Result<(), Self> {
+        if self.is_empty() {
+            return Ok(());
+        }
+
+        Err(self)
+    }
+}
+
+impl Display for ValidationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Validation failed")?;
+
+        for (i, error) in self.errors.iter().enumerate() {
+            let separator = if i == 0 { ": " } else { ", " };
+
+            write!(f, "{separator}`{}` {}", error.field, error.message)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "validator")))]
+#[cfg(feature = "validator")]
+impl From<validator::ValidationErrors> for ValidationError {
+    fn from(value: validator::ValidationErrors) -> Self {
+        let mut fields: Vec<_> = value.field_errors().into_iter().collect();
+        fields.sort_by_key(|(field, _)| *field);
+
+        let mut errors = Self::default();
+
+        for (field, field_errors) in fields {
+            for error in field_errors {
+                let message = error.message.as_ref().unwrap_or(&error.code);
+
+                errors.add(field, message.to_string());
+            }
+        }
+
+        errors
+    }
+}
+
+/// Runs the `validator::Validate` implementation, used by `#[table(validate = "validator")]`
+#[cfg_attr(docsrs, doc(cfg(feature = "validator")))]
+#[cfg(feature = "validator")]
+pub fn validator<T: validator::Validate>(record: &T) -> Result<(), ValidationError> {
+    record.validate().map_err(ValidationError::from)
+}
+
+#[cfg(test)]
+mod test {
+    use serde::{Deserialize, Serialize};
+    use surrealdb::sql::Thing as RecordId;
+    use crate::table::{Table, TableError};
+    use crate::testing::db;
+    use super::*;
+
+    #[derive(Debug, Table, Serialize, Deserialize, Clone)]
+    #[table(name = "user", validate)]
+    struct User {
+        id: Option<RecordId>,
+        name: String,
+    }
+
+    impl Validate for User {
+        fn validate(&self) -> Result<(), ValidationError> {
+            let mut errors = ValidationError::default();
+
+            if self.name.len() < 3 {
+                errors.add("name", "is too short");
+            }
+
+            errors.into_result()
+        }
+    }
+
+    #[test]
+    fn display() {
+        let errors = ValidationError::default().field("name", "is too short").field("age", "is too low");
+
+        assert_eq!(errors.to_string(), "Validation failed: `name` is too short, `age` is too low");
+    }
+
+    #[tokio::test]
+    async fn create_and_update() {
+        let db = db().await;
+
+        let err = User { id: None, name: "a".to_string() }.create(&db).await.unwrap_err();
+
+        assert!(matches!(err.downcast_ref::<TableError>(), Some(TableError::Validation(_))));
+        assert!(User::get_all(&db).await.unwrap().is_empty());
+
+        let mut user = User { id: None, name: "name".to_string() }.create(&db).await.unwrap().unwrap();
+
+        user.name = "b".to_string();
+
+        let err = user.update(&db).await.unwrap_err();
+
+        let Some(TableError::Validation(errors)) = err.downcast_ref::<TableError>() else { panic!() };
+        assert_eq!(errors.messages("name").collect::<Vec<_>>(), ["is too short"]);
+    }
+
+    #[cfg(feature = "validator")]
+    #[tokio::test]
+    async fn with_validator() {
+        #[derive(Debug, Table, Serialize, Deserialize, Clone, validator::Validate)]
+        #[table(name = "account", validate = "validator")]
+        struct Account {
+            id: Option<RecordId>,
+            #[validate(email)]
+            email: String,
+            #[validate(range(min = 18, message = "must be an adult"))]
+            age: u32,
+        }
+
+        let db = db().await;
+
+        let err = Account { id: None, email: "nope".to_string(), age: 3 }.create(&db).await.unwrap_err();
+
+        let Some(TableError::Validation(errors)) = err.downcast_ref::<TableError>() else { panic!() };
+        assert_eq!(errors.errors, vec![
+            FieldError { field: "age".to_string(), message: "must be an adult".to_string() },
+            FieldError { field: "email".to_string(), message: "email".to_string() },
+        ]);
+
+        Account { id: None, email: "a@b.com".to_string(), age: 30 }.create(&db).await.unwrap();
+    }
+}
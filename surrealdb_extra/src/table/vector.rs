@@ -0,0 +1,205 @@
+//! Vector indexes for embedding fields
+//!
+//! A field marked with `#[index(vector(dim = 768, dist = "cosine"))]` gets a `HNSW` index from `define_vector_indexes`,
+//! add `mtree` for a `MTREE` index instead: `#[index(vector(dim = 768, dist = "cosine", mtree))]`.
+//!
+//! The derive generates a `find_similar_by_<field>` function for every vector field, which returns the `k` nearest records ordered by their distance.
+//! A table with only one vector field also gets `find_similar`.
+//!
+//! # Example
+//!
+//! ```rust
+//! use serde::{Serialize, Deserialize};
+//! use surrealdb::engine::any::connect;
+//! use surrealdb::sql::Thing as RecordId;
+//! use surrealdb_extra::table::Table;
+//!
+//! #[derive(Table, Serialize, Deserialize, Clone)]
+//! #[table(name = "doc")]
+//! struct Doc {
+//!     id: Option<RecordId>,
+//!     text: String,
+//!     #[index(vector(dim = 3, dist = "cosine"))]
+//!     embedding: Vec<f32>
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let db = connect("mem://").await.unwrap();
+//!     db.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     Doc::define_vector_indexes(&db).await.unwrap();
+//!
+//!     Doc { id: None, text: "a".to_string(), embedding: vec![1.0, 0.0, 0.0] }.create(&db).await.unwrap();
+//!     Doc { id: None, text: "b".to_string(), embedding: vec![0.0, 1.0, 0.0] }.create(&db).await.unwrap();
+//!
+//!     let similar = Doc::find_similar(&db, vec![0.9, 0.1, 0.0], 1).await.unwrap();
+//!
+//!     assert_eq!(similar[0].record.text, "a");
+//! }
+//! ```
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use surrealdb::{Connection, Surreal};
+use crate::table::{Table, TableError};
+
+/// Size of the candidate list for `HNSW` searches, raised to `k` when `k` is larger
+pub const DEFAULT_EF: u32 = 40;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VectorIndexKind {
+    Hnsw,
+    Mtree,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VectorDistance {
+    Chebyshev,
+    Cosine,
+    Euclidean,
+    Hamming,
+    Jaccard,
+    Manhattan,
+    Pearson,
+}
+
+impl VectorDistance {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Chebyshev => "CHEBYSHEV",
+            Self::Cosine => "COSINE",
+            Self::Euclidean => "EUCLIDEAN",
+            Self::Hamming => "HAMMING",
+            Self::Jaccard => "JACCARD",
+            Self::Manhattan => "MANHATTAN",
+            Self::Pearson => "PEARSON",
+        }
+    }
+}
+
+/// A vector index on an embedding field
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VectorIndex {
+    pub field: &'static str,
+    pub dimension: u16,
+    pub distance: VectorDistance,
+    pub kind: VectorIndexKind,
+}
+
+/// A record found by `find_similar` with its distance to the embedding
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Similar<T> {
+    pub distance: f64,
+    pub record: T,
+}
+
+impl VectorIndex {
+    pub fn name(&self, table: &str) -> String {
+        format!("{table}_{}_vector", self.field)
+    }
+
+    /// `DEFINE INDEX` with the `HNSW` or `MTREE` parameters
+    pub fn define_index(&self, table: &str) -> String {
+        let VectorIndex { field, dimension, distance, kind } = self;
+
+        let kind = match kind {
+            VectorIndexKind::Hnsw => "HNSW",
+            VectorIndexKind::Mtree => "MTREE",
+        };
+
+        format!(
+            "DEFINE INDEX OVERWRITE {name} ON TABLE {table} FIELDS {field} {kind} DIMENSION {dimension} DIST {distance}",
+            name = self.name(table),
+            distance = distance.as_str(),
+        )
+    }
+
+    /// `SELECT` of the `k` nearest records, the embedding is bound as `$embedding`
+    pub fn query(&self, table: &str, k: u32) -> String {
+        let knn = match self.kind {
+            VectorIndexKind::Hnsw => format!("<|{k},{}|>", k.max(DEFAULT_EF)),
+            VectorIndexKind::Mtree => format!("<|{k}|>"),
+        };
+
+        format!(
+            "SELECT $this AS record, vector::distance::knn() AS distance FROM {table} WHERE {field} {knn} $embedding ORDER BY distance",
+            field = self.field,
+        )
+    }
+}
+
+/// The `k` records nearest to the embedding using the vector index
+pub async fn find_similar<T: Table, C: Connection, E: Serialize + Send + 'static>(db: &Surreal<C>, index: &VectorIndex, embedding: E, k: u32) -> Result<Vec<Similar<T>>> {
+    let similar: Vec<Similar<T>> = db.query(index.query(T::TABLE_NAME, k))
+        .bind(("embedding", embedding))
+        .await.map_err(TableError::from)?
+        .take(0).map_err(TableError::from)?;
+
+    Ok(similar)
+}
+
+#[cfg(test)]
+mod test {
+    use surrealdb::engine::any::Any;
+    use surrealdb::sql::Thing;
+    use super::*;
+
+    #[derive(Debug, Table, Serialize, Deserialize, Clone, PartialEq)]
+    #[table(name = "doc")]
+    struct Doc {
+        id: Option<Thing>,
+        text: String,
+        #[index(vector(dim = 2, dist = "cosine"))]
+        embedding: Vec<f32>,
+        #[index(vector(dim = 2, dist = "euclidean", mtree))]
+        #[serde(rename = "pos")]
+        position: Vec<f64>,
+    }
+
+    async fn db() -> Surreal<Any> {
+        let db = crate::testing::db().await;
+
+        Doc::define_vector_indexes(&db).await.unwrap();
+
+        Doc { id: None, text: "a".to_string(), embedding: vec![1.0, 0.0], position: vec![0.0, 0.0] }.create(&db).await.unwrap();
+        Doc { id: None, text: "b".to_string(), embedding: vec![0.0, 1.0], position: vec![5.0, 5.0] }.create(&db).await.unwrap();
+        Doc { id: None, text: "c".to_string(), embedding: vec![0.9, 0.1], position: vec![1.0, 1.0] }.create(&db).await.unwrap();
+
+        db
+    }
+
+    fn texts(similar: &[Similar<Doc>]) -> Vec<&str> {
+        similar.iter().map(|s| s.record.text.as_str()).collect()
+    }
+
+    #[test]
+    fn derived() {
+        assert_eq!(Doc::VECTOR_INDEXES, &[
+            VectorIndex { field: "embedding", dimension: 2, distance: VectorDistance::Cosine, kind: VectorIndexKind::Hnsw },
+            VectorIndex { field: "pos", dimension: 2, distance: VectorDistance::Euclidean, kind: VectorIndexKind::Mtree },
+        ]);
+
+        assert_eq!(Doc::VECTOR_INDEXES[0].define_index("doc"), "DEFINE INDEX OVERWRITE doc_embedding_vector ON TABLE doc FIELDS embedding HNSW DIMENSION 2 DIST COSINE");
+        assert_eq!(Doc::VECTOR_INDEXES[1].define_index("doc"), "DEFINE INDEX OVERWRITE doc_pos_vector ON TABLE doc FIELDS pos MTREE DIMENSION 2 DIST EUCLIDEAN");
+    }
+
+    #[tokio::test]
+    async fn hnsw() {
+        let db = db().await;
+
+        let similar = Doc::find_similar_by_embedding(&db, vec![1.0, 0.0], 2).await.unwrap();
+
+        assert_eq!(texts(&similar), vec!["a", "c"]);
+        assert!(similar[0].distance <= similar[1].distance);
+    }
+
+    #[tokio::test]
+    async fn mtree() {
+        let db = db().await;
+
+        let similar = Doc::find_similar_by_position(&db, vec![4.0, 4.0], 1).await.unwrap();
+
+        assert_eq!(texts(&similar), vec!["b"]);
+    }
+}
@@ -0,0 +1,150 @@
+//! Optimistic locking with a version field
+//!
+//! With `#[table(versioned)]` `update` only writes the record when the `version` field in the database still equals the
+//! version of the struct and increments it, otherwise it returns `TableError::StaleRecord` and nothing is written.
+//! This prevents lost updates when two clients change the same record.
+//!
+//! Records without a version are treated as version `0`. `update_builder` doesn't check the version.
+//!
+//! # Example
+//!
+//! ```rust
+//! use serde::{Serialize, Deserialize};
+//! use surrealdb::engine::any::connect;
+//! use surrealdb::sql::Thing as RecordId;
+//! use surrealdb_extra::table::{Table, TableError};
+//!
+//! #[derive(Debug, Table, Serialize, Deserialize, Clone)]
+//! #[table(name = "account", versioned)]
+//! struct Account {
+//!     id: Option<RecordId>,
+//!     balance: i64,
+//!     version: u64
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let db = connect("mem://").await.unwrap();
+//!     db.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     let account = Account { id: None, balance: 10, version: 0 }.create(&db).await.unwrap().unwrap();
+//!
+//!     let mut first = account.clone();
+//!     first.balance = 20;
+//!     let first = first.update(&db).await.unwrap().unwrap();
+//!     assert_eq!(first.version, 1);
+//!
+//!     let mut second = account;
+//!     second.balance = 30;
+//!     let err = second.update(&db).await.unwrap_err();
+//!     assert!(matches!(err.downcast_ref::<TableError>(), Some(TableError::StaleRecord { .. })));
+//! }
+//! ```
+
+use anyhow::Result;
+use surrealdb::sql::{Cond, Number, Part, Thing, Value};
+use surrealdb::{Connection, Surreal};
+use crate::table::{config, Table, TableError};
+
+/// `UPDATE ... MERGE ... WHERE version = $version` with the incremented version in the content,
+/// a `scope` like the cond of a `Tenant` is added to the version check and records outside of it are `None`
+pub(crate) async fn update<T: Table, C: Connection>(db: &Surreal<C>, id: Thing, field: &str, mut content: Value, scope: Option<Cond>) -> Result<Option<T>> {
+    let version = match content.pick(&[Part::from(field)]) {
+        Value::Number(n) => n.as_int() as u64,
+        _ => 0,
+    };
+
+    content.put(&[Part::from(field)], Value::Number(Number::Int(version as i64 + 1)));
+
+    let (and_scope, where_scope) = match scope {
+        Some(scope) => (format!(" AND ({})", scope.0), format!(" {scope}")),
+        None => (String::new(), String::new()),
+    };
+
+    let update = config::update(&format!("UPDATE $id MERGE $content WHERE ({field} ?? 0) = $version{and_scope}"))?;
+
+    let mut res = db.query(update).query(format!("SELECT VALUE id FROM $id{where_scope}"))
+        .bind(("id", id.clone()))
+        .bind(("content", content))
+        .bind(("version", version as i64))
+        .await.map_err(TableError::from)?
+        .check().map_err(TableError::from)?;
+
+    let mut updated: Vec<T> = res.take(0).map_err(TableError::from)?;
+
+    if let Some(updated) = updated.pop() {
+        return Ok(Some(updated));
+    }
+
+    let exists: Vec<Thing> = res.take(1).map_err(TableError::from)?;
+
+    match exists.is_empty() {
+        false => Err(TableError::StaleRecord { id: id.to_string(), version }.into()),
+        true => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde::{Deserialize, Serialize};
+    use surrealdb::sql::Thing as RecordId;
+    use crate::testing::db;
+    use super::*;
+
+    #[derive(Debug, Table, Serialize, Deserialize, Clone, PartialEq)]
+    #[table(name = "account", versioned)]
+    struct Account {
+        id: Option<RecordId>,
+        balance: i64,
+        #[serde(rename = "rev", default)]
+        version: u64,
+    }
+
+    #[test]
+    fn derived() {
+        assert_eq!(Account::VERSION_FIELD, Some("rev"));
+    }
+
+    #[tokio::test]
+    async fn stale_record() {
+        let db = db().await;
+
+        let account = Account { id: None, balance: 10, version: 0 }.create(&db).await.unwrap().unwrap();
+
+        let mut first = account.clone();
+        first.balance = 20;
+        let mut first = first.update(&db).await.unwrap().unwrap();
+        assert_eq!(first.version, 1);
+
+        let mut second = account;
+        second.balance = 30;
+        let err = second.update(&db).await.unwrap_err();
+
+        let Some(TableError::StaleRecord { version, .. }) = err.downcast_ref::<TableError>() else { panic!() };
+        assert_eq!(*version, 0);
+
+        first.balance = 40;
+        let first = first.update(&db).await.unwrap().unwrap();
+        assert_eq!(first.version, 2);
+
+        let stored = Account::get_by_id(&db, first.id.clone().unwrap()).await.unwrap().unwrap();
+        assert_eq!(stored, first);
+    }
+
+    #[tokio::test]
+    async fn missing_version_and_record() {
+        let db = db().await;
+
+        db.query("CREATE account:old SET balance = 5").await.unwrap().check().unwrap();
+
+        let mut old = Account::get_by_id(&db, RecordId::from(("account", "old"))).await.unwrap().unwrap();
+        assert_eq!(old.version, 0);
+
+        old.balance = 6;
+        let old = old.update(&db).await.unwrap().unwrap();
+        assert_eq!(old.version, 1);
+
+        let missing = Account { id: Some(RecordId::from(("account", "missing"))), balance: 1, version: 0 };
+        assert!(missing.update(&db).await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,153 @@
+//! Fields that are read but never written
+//!
+//! Fields marked with `#[field(skip)]` or `#[field(readonly)]` are removed from the payload of `create`, `update`,
+//! `create_builder` and `update_builder`, so the value in the database is never overwritten by the client.
+//!
+//! - `skip` is meant for fields the database fills in itself, like a `VALUE` or a `DEFAULT` clause.
+//! - `readonly` is meant for fields that are owned by another writer, like an event or another service.
+//!
+//! Both are deserialized as usual when the record is read,
+//! add `#[serde(default)]` when the field can be missing in the database.
+//!
+//! # Example
+//!
+//! ```rust
+//! use serde::{Serialize, Deserialize};
+//! use surrealdb::engine::any::connect;
+//! use surrealdb::sql::Thing as RecordId;
+//! use surrealdb_extra::table::Table;
+//!
+//! #[derive(Table, Serialize, Deserialize, Clone)]
+//! #[table(name = "user")]
+//! struct User {
+//!     id: Option<RecordId>,
+//!     name: String,
+//!     #[field(skip)]
+//!     #[serde(default)]
+//!     created_at: Option<String>,
+//!     #[field(readonly)]
+//!     #[serde(default)]
+//!     login_count: i64
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let db = connect("mem://").await.unwrap();
+//!     db.use_ns("ns").use_db("db").await.unwrap();
+//!
+//!     db.query("DEFINE FIELD created_at ON user DEFAULT '2024-01-01'").await.unwrap();
+//!
+//!     let user = User { id: None, name: "name".to_string(), created_at: None, login_count: 10 }.create(&db).await.unwrap().unwrap();
+//!
+//!     assert_eq!(user.created_at.as_deref(), Some("2024-01-01"));
+//!     assert_eq!(user.login_count, 0);
+//! }
+//! ```
+
+use anyhow::Result;
+use serde::Serialize;
+use surrealdb::sql::{to_value, Value};
+use crate::table::{Table, TableError};
+
+#[cfg(feature = "pipeline")]
+use std::sync::Arc;
+
+/// The record as a value without its `SKIP_FIELDS` and `READONLY_FIELDS`
+pub fn write_content<T: Table>(record: T) -> Result<Value> {
+    content::<T>(record)
+}
+
+/// Same as `write_content` for a record that is kept after the write, e.g. to report it when the write fails
+#[cfg(feature = "pipeline")]
+pub(crate) fn write_shared_content<T: Table>(record: Arc<T>) -> Result<Value> {
+    content::<T>(Shared(record))
+}
+
+/// `to_value` needs an owned value, this serializes the shared record
+#[cfg(feature = "pipeline")]
+struct Shared<T>(Arc<T>);
+
+#[cfg(feature = "pipeline")]
+impl<T: Serialize> Serialize for Shared<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+
+fn content<T: Table>(record: impl Serialize + 'static) -> Result<Value> {
+    let mut value = to_value(record).map_err(|e| TableError::Db(e.into()))?;
+
+    if let Value::Object(object) = &mut value {
+        for field in T::SKIP_FIELDS.iter().chain(T::READONLY_FIELDS) {
+            object.remove(*field);
+        }
+    }
+
+    Ok(value)
+}
+
+#[cfg(test)]
+mod test {
+    use serde::{Deserialize, Serialize};
+    use surrealdb::sql::Thing as RecordId;
+    use crate::testing::db;
+    use super::*;
+
+    #[derive(Debug, Table, Serialize, Deserialize, Clone, PartialEq)]
+    #[table(name = "user")]
+    struct User {
+        id: Option<RecordId>,
+        name: String,
+        #[field(skip)]
+        #[serde(default, rename = "createdAt")]
+        created_at: Option<String>,
+        #[field(readonly)]
+        #[serde(default)]
+        login_count: i64,
+    }
+
+    #[test]
+    fn consts() {
+        assert_eq!(User::SKIP_FIELDS, &["createdAt"]);
+        assert_eq!(User::READONLY_FIELDS, &["login_count"]);
+    }
+
+    #[tokio::test]
+    async fn never_written() {
+        let db = db().await;
+
+        db.query("DEFINE FIELD createdAt ON user DEFAULT 'now'").await.unwrap().check().unwrap();
+
+        let mut user = User { id: None, name: "name".to_string(), created_at: Some("client".to_string()), login_count: 5 }
+            .create(&db).await.unwrap().unwrap();
+
+        assert_eq!(user.created_at.as_deref(), Some("now"));
+        assert_eq!(user.login_count, 0);
+
+        db.query("UPDATE $id SET login_count = 3").bind(("id", user.id.clone().unwrap())).await.unwrap().check().unwrap();
+
+        user.name = "changed".to_string();
+        user.created_at = Some("client".to_string());
+        user.login_count = 100;
+
+        let user = user.update(&db).await.unwrap().unwrap();
+
+        assert_eq!(user.name, "changed");
+        assert_eq!(user.created_at.as_deref(), Some("now"));
+        assert_eq!(user.login_count, 3);
+    }
+
+    #[tokio::test]
+    async fn create_get_id() {
+        let db = db().await;
+
+        let id = User { id: None, name: "name".to_string(), created_at: None, login_count: 5 }.create_get_id(&db).await.unwrap();
+        assert_eq!(id.tb, "user");
+
+        let user = User::get_by_id(&db, id.clone()).await.unwrap().unwrap();
+        assert_eq!((user.id, user.login_count), (Some(id), 0));
+
+        let id = User { id: Some(RecordId::from(("user", "given"))), name: "name".to_string(), created_at: None, login_count: 0 }.create_get_id(&db).await.unwrap();
+        assert_eq!(id, RecordId::from(("user", "given")));
+    }
+}
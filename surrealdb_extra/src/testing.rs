@@ -0,0 +1,12 @@
+//! Helpers shared by the tests of the crate
+
+use surrealdb::engine::any::{connect, Any};
+use surrealdb::Surreal;
+
+/// An empty in memory database with the `test` namespace and database selected
+pub(crate) async fn db() -> Surreal<Any> {
+    let db = connect("mem://").await.unwrap();
+    db.use_ns("test").use_db("test").await.unwrap();
+
+    db
+}
@@ -0,0 +1,184 @@
+use ::syn::{Data, DeriveInput, Fields};
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::__private::Span;
+use syn::Error;
+use crate::field::is_computed;
+use crate::meta::generic_inner;
+
+/// How a field is filled in by the builder
+enum BuilderField<'a> {
+    /// Needs to be set before `build`
+    Required(&'a syn::Type),
+    /// `Option<T>` that is `None` unless it is set
+    Optional(&'a syn::Type),
+    /// Computed or the version, always `Default::default()`
+    Default,
+}
+
+/// Generates `<Struct>::builder()` with a typed builder that only has `build` and `create` once every required field is set
+pub(crate) fn builder_struct(input: &DeriveInput, versioned: bool) -> Result<TokenStream, Error> {
+    let Data::Struct(data) = &input.data else {
+        return Err(Error::new(Span::call_site(), "Table can only be derived for structs"));
+    };
+
+    let Fields::Named(named) = &data.fields else {
+        return Ok(quote! {});
+    };
+
+    let struct_name = &input.ident;
+    let vis = &input.vis;
+    let builder_name = format_ident!("{}Builder", struct_name);
+
+    let mut fields = vec![];
+
+    for field in &named.named {
+        let Some(ident) = &field.ident else {
+            continue;
+        };
+
+        if ident == "id" {
+            continue;
+        }
+
+        // Skipped and readonly fields are left out of the payload, but their type needn't have a default, so they are set like the others
+        let kind = if is_computed(&field.ty) || (versioned && ident == "version") {
+            BuilderField::Default
+        } else if let Some(inner) = generic_inner(&field.ty, "Option") {
+            BuilderField::Optional(inner)
+        } else {
+            BuilderField::Required(&field.ty)
+        };
+
+        fields.push((ident, &field.ty, kind));
+    }
+
+    let required: Vec<_> = fields.iter()
+        .filter_map(|(ident, _, kind)| match kind {
+            BuilderField::Required(ty) => Some((*ident, *ty)),
+            _ => None,
+        })
+        .collect();
+
+    let generics: Vec<_> = (0..required.len()).map(|i| format_ident!("__F{}", i)).collect();
+
+    let builder_fields = fields.iter().filter_map(|(ident, ty, kind)| match kind {
+        BuilderField::Required(_) => {
+            let i = required.iter().position(|(r, _)| r == ident)?;
+            let generic = &generics[i];
+
+            Some(quote! { #ident: #generic })
+        }
+        BuilderField::Optional(_) => Some(quote! { #ident: #ty }),
+        BuilderField::Default => None,
+    });
+
+    let builder_idents: Vec<_> = fields.iter()
+        .filter(|(_, _, kind)| !matches!(kind, BuilderField::Default))
+        .map(|(ident, _, _)| *ident)
+        .collect();
+
+    let initial_fields = fields.iter().filter_map(|(ident, _, kind)| match kind {
+        BuilderField::Required(_) => Some(quote! { #ident: ::surrealdb_extra::table::builder::Missing }),
+        BuilderField::Optional(_) => Some(quote! { #ident: None }),
+        BuilderField::Default => None,
+    });
+
+    let missing = required.iter().map(|_| quote! { ::surrealdb_extra::table::builder::Missing });
+
+    let required_setters = required.iter().enumerate().map(|(i, (ident, ty))| {
+        let other_generics: Vec<_> = generics.iter().enumerate().filter(|(j, _)| *j != i).map(|(_, g)| g).collect();
+
+        let before = generics.iter().enumerate().map(|(j, g)| if i == j {
+            quote! { ::surrealdb_extra::table::builder::Missing }
+        } else {
+            quote! { #g }
+        });
+
+        let after: Vec<_> = generics.iter().enumerate().map(|(j, g)| if i == j {
+            quote! { ::surrealdb_extra::table::builder::Set<#ty> }
+        } else {
+            quote! { #g }
+        }).collect();
+
+        let moved = builder_idents.iter().filter(|other| *other != ident);
+
+        quote! {
+            impl<#(#other_generics),*> #builder_name<#(#before),*> {
+                pub fn #ident(self, #ident: impl Into<#ty>) -> #builder_name<#(#after),*> {
+                    #builder_name {
+                        id: self.id,
+                        #ident: ::surrealdb_extra::table::builder::Set(#ident.into()),
+                        #(#moved: self.#moved),*
+                    }
+                }
+            }
+        }
+    });
+
+    let optional_setters = fields.iter().filter_map(|(ident, _, kind)| match kind {
+        BuilderField::Optional(inner) => Some(quote! {
+            pub fn #ident(mut self, #ident: impl Into<#inner>) -> Self {
+                self.#ident = Some(#ident.into());
+
+                self
+            }
+        }),
+        _ => None,
+    });
+
+    let set = required.iter().map(|(_, ty)| quote! { ::surrealdb_extra::table::builder::Set<#ty> });
+
+    let record_fields = fields.iter().map(|(ident, _, kind)| match kind {
+        BuilderField::Required(_) => quote! { #ident: self.#ident.0 },
+        BuilderField::Optional(_) => quote! { #ident: self.#ident },
+        BuilderField::Default => quote! { #ident: Default::default() },
+    });
+
+    Ok(quote! {
+        /// Builder for a new
+        #[doc = concat!("[`", stringify!(#struct_name), "`]")]
+        ///
+        /// `build` and `create` are only available once every required field is set
+        #vis struct #builder_name<#(#generics),*> {
+            id: Option<::surrealdb::sql::Thing>,
+            #(#builder_fields),*
+        }
+
+        impl #struct_name {
+            /// Builder for a new record without an id
+            pub fn builder() -> #builder_name<#(#missing),*> {
+                #builder_name {
+                    id: None,
+                    #(#initial_fields),*
+                }
+            }
+        }
+
+        #(#required_setters)*
+
+        impl<#(#generics),*> #builder_name<#(#generics),*> {
+            /// Id of the new record, otherwise the database generates one
+            pub fn id(mut self, id: impl Into<::surrealdb::sql::Id>) -> Self {
+                self.id = Some(::surrealdb::sql::Thing::from((<#struct_name as Table>::TABLE_NAME, id.into())));
+
+                self
+            }
+
+            #(#optional_setters)*
+        }
+
+        impl #builder_name<#(#set),*> {
+            pub fn build(self) -> #struct_name {
+                #struct_name {
+                    id: self.id,
+                    #(#record_fields),*
+                }
+            }
+
+            pub async fn create<C: ::surrealdb::Connection>(self, db: &impl ::surrealdb_extra::table::database::Database<C>) -> ::surrealdb_extra::anyhow::Result<Option<#struct_name>> {
+                <#struct_name as Table>::create(self.build(), db).await
+            }
+        }
+    })
+}
@@ -0,0 +1,60 @@
+use ::syn::{Data, DeriveInput, Fields};
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::__private::Span;
+use syn::Error;
+use crate::field::{is_computed, FieldAttr};
+use crate::meta::serde_rename;
+
+/// Generates `<Struct>Changeset` with an `Option` of every field that can be written
+pub(crate) fn changeset_struct(input: &DeriveInput, field_attrs: &[FieldAttr]) -> Result<TokenStream, Error> {
+    let Data::Struct(data) = &input.data else {
+        return Err(Error::new(Span::call_site(), "Table can only be derived for structs"));
+    };
+
+    let struct_name = &input.ident;
+    let vis = &input.vis;
+    let changeset_name = format_ident!("{}Changeset", struct_name);
+
+    let mut fields = vec![];
+    let mut idents = vec![];
+
+    if let Fields::Named(named) = &data.fields {
+        for field in &named.named {
+            let Some(ident) = &field.ident else {
+                continue;
+            };
+
+            let not_written = field_attrs.iter().any(|f| &f.ident == ident && (f.skip || f.readonly));
+
+            if ident == "id" || not_written || is_computed(&field.ty) {
+                continue;
+            }
+
+            let ty = &field.ty;
+            let name = serde_rename(field)?.unwrap_or_else(|| ident.to_string().trim_start_matches("r#").to_string());
+
+            fields.push(quote! {
+                #[serde(rename = #name, skip_serializing_if = "Option::is_none")]
+                pub #ident: Option<#ty>
+            });
+            idents.push(ident);
+        }
+    }
+
+    Ok(quote! {
+        /// Partial update of
+        #[doc = concat!("[`", stringify!(#struct_name), "`]")]
+        /// where only the fields that are `Some` are written
+        #[derive(Default, ::serde::Serialize)]
+        #vis struct #changeset_name {
+            #(#fields),*
+        }
+
+        impl ::surrealdb_extra::table::changeset::Changeset<#struct_name> for #changeset_name {
+            fn is_empty(&self) -> bool {
+                true #(&& self.#idents.is_none())*
+            }
+        }
+    })
+}
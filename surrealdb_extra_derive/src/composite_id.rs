@@ -0,0 +1,47 @@
+use ::syn::{DeriveInput, Meta, Token, Type};
+use ::syn::punctuated::Punctuated;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::__private::Span;
+use syn::Error;
+use crate::meta::lit_str;
+
+/// Implements `CompositeId` for `#[table(id_type = "...")]` with the type as key
+pub(crate) fn composite_id(input: &DeriveInput) -> Result<TokenStream, Error> {
+    let mut id_type = None;
+
+    for attr in &input.attrs {
+        if !attr.path().is_ident("table") {
+            continue;
+        }
+
+        let nested = attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)?;
+
+        for meta in nested {
+            if !meta.path().is_ident("id_type") {
+                continue;
+            }
+
+            if id_type.is_some() {
+                return Err(Error::new(Span::call_site(), "id_type can only be set once"));
+            }
+
+            id_type = Some(lit_str(&meta.require_name_value()?.value)?);
+        }
+    }
+
+    let Some(id_type) = id_type else {
+        return Ok(quote! {});
+    };
+
+    let ty: Type = syn::parse_str(&id_type)
+        .map_err(|_| Error::new(Span::call_site(), format!("table(id_type) `{id_type}` is not a type")))?;
+
+    let struct_name = &input.ident;
+
+    Ok(quote! {
+        impl ::surrealdb_extra::table::id::CompositeId for #struct_name {
+            type Key = #ty;
+        }
+    })
+}
@@ -0,0 +1,313 @@
+use proc_macro2::{Delimiter, Spacing, Span, TokenStream, TokenTree};
+use quote::quote;
+use syn::{Error, Lit};
+
+const OR: u8 = 1;
+const AND: u8 = 2;
+const COMPARE: u8 = 3;
+const ADD: u8 = 4;
+const MUL: u8 = 5;
+const POW: u8 = 6;
+
+const PUNCT_OPERATORS: &[(&str, &str, u8)] = &[
+    ("||", "Or", OR),
+    ("&&", "And", AND),
+    ("==", "Exact", COMPARE),
+    ("!=", "NotEqual", COMPARE),
+    ("*=", "AllEqual", COMPARE),
+    ("?=", "AnyEqual", COMPARE),
+    ("!~", "NotLike", COMPARE),
+    ("*~", "AllLike", COMPARE),
+    ("?~", "AnyLike", COMPARE),
+    ("<=", "LessThanOrEqual", COMPARE),
+    (">=", "MoreThanOrEqual", COMPARE),
+    ("**", "Pow", POW),
+    ("=", "Equal", COMPARE),
+    ("~", "Like", COMPARE),
+    ("<", "LessThan", COMPARE),
+    (">", "MoreThan", COMPARE),
+    ("+", "Add", ADD),
+    ("-", "Sub", ADD),
+    ("*", "Mul", MUL),
+    ("/", "Div", MUL),
+    ("%", "Rem", MUL),
+];
+
+const WORD_OPERATORS: &[(&str, &str, u8)] = &[
+    ("OR", "Or", OR),
+    ("AND", "And", AND),
+    ("CONTAINS", "Contain", COMPARE),
+    ("CONTAINSNOT", "NotContain", COMPARE),
+    ("CONTAINSALL", "ContainAll", COMPARE),
+    ("CONTAINSANY", "ContainAny", COMPARE),
+    ("CONTAINSNONE", "ContainNone", COMPARE),
+    ("IN", "Inside", COMPARE),
+    ("INSIDE", "Inside", COMPARE),
+    ("NOTINSIDE", "NotInside", COMPARE),
+    ("ALLINSIDE", "AllInside", COMPARE),
+    ("ANYINSIDE", "AnyInside", COMPARE),
+    ("NONEINSIDE", "NoneInside", COMPARE),
+];
+
+pub(crate) fn cond(input: TokenStream) -> Result<TokenStream, Error> {
+    let value = Parser::new(input).parse()?;
+
+    Ok(quote! {
+        ::surrealdb_extra::query::parsing::cond::ExtraCond::from(#value)
+    })
+}
+
+struct Parser {
+    tokens: Vec<TokenTree>,
+    pos: usize,
+}
+
+impl Parser {
+    fn new(input: TokenStream) -> Self {
+        Self {
+            tokens: input.into_iter().collect(),
+            pos: 0,
+        }
+    }
+
+    /// Parses the whole input as one expression
+    fn parse(mut self) -> Result<TokenStream, Error> {
+        let value = self.expr(0)?;
+
+        if let Some(token) = self.tokens.get(self.pos) {
+            return Err(Error::new(token.span(), "Expected an operator"));
+        }
+
+        Ok(value)
+    }
+
+    fn expr(&mut self, min: u8) -> Result<TokenStream, Error> {
+        let mut l = self.operand()?;
+
+        while let Some((o, len, precedence)) = self.operator()? {
+            if precedence < min {
+                break;
+            }
+
+            self.pos += len;
+
+            let r = self.expr(precedence + 1)?;
+
+            l = quote! {
+                ::surrealdb::sql::Value::Expression(Box::new(::surrealdb::sql::Expression::Binary {
+                    l: #l,
+                    o: ::surrealdb::sql::Operator::#o,
+                    r: #r,
+                }))
+            };
+        }
+
+        Ok(l)
+    }
+
+    /// The binary operator at the cursor with the number of tokens and the precedence
+    fn operator(&self) -> Result<Option<(syn::Ident, usize, u8)>, Error> {
+        let Some(token) = self.tokens.get(self.pos) else {
+            return Ok(None);
+        };
+
+        let found = match token {
+            TokenTree::Punct(_) => {
+                let mut puncts = String::new();
+
+                for token in &self.tokens[self.pos..] {
+                    let TokenTree::Punct(punct) = token else {
+                        break;
+                    };
+
+                    puncts.push(punct.as_char());
+
+                    if punct.spacing() == Spacing::Alone {
+                        break;
+                    }
+                }
+
+                PUNCT_OPERATORS.iter()
+                    .find(|(op, _, _)| puncts.starts_with(op))
+                    .map(|(op, o, precedence)| (*o, op.len(), *precedence))
+            }
+            TokenTree::Ident(ident) => {
+                let word = ident.to_string().to_uppercase();
+
+                let not_in = word == "NOT" && matches!(
+                    self.tokens.get(self.pos + 1),
+                    Some(TokenTree::Ident(next)) if next.to_string().to_uppercase() == "IN"
+                );
+
+                if not_in {
+                    Some(("NotInside", 2, COMPARE))
+                } else {
+                    WORD_OPERATORS.iter()
+                        .find(|(op, _, _)| *op == word)
+                        .map(|(_, o, precedence)| (*o, 1, *precedence))
+                }
+            }
+            _ => None,
+        };
+
+        match found {
+            Some((o, len, precedence)) => Ok(Some((syn::Ident::new(o, token.span()), len, precedence))),
+            None => Err(Error::new(token.span(), "Expected an operator")),
+        }
+    }
+
+    fn operand(&mut self) -> Result<TokenStream, Error> {
+        let Some(token) = self.tokens.get(self.pos).cloned() else {
+            return Err(Error::new(Span::call_site(), "Expected a value"));
+        };
+
+        self.pos += 1;
+
+        match token {
+            TokenTree::Punct(punct) if punct.as_char() == '!' || punct.as_char() == '-' => {
+                let o = syn::Ident::new(if punct.as_char() == '!' { "Not" } else { "Neg" }, punct.span());
+                let v = self.operand()?;
+
+                Ok(quote! {
+                    ::surrealdb::sql::Value::Expression(Box::new(::surrealdb::sql::Expression::Unary {
+                        o: ::surrealdb::sql::Operator::#o,
+                        v: #v,
+                    }))
+                })
+            }
+            TokenTree::Punct(punct) if punct.as_char() == '$' => {
+                let Some(TokenTree::Ident(name)) = self.tokens.get(self.pos).cloned() else {
+                    return Err(Error::new(punct.span(), "Expected a parameter name after `$`"));
+                };
+
+                self.pos += 1;
+
+                let name = name.to_string().trim_start_matches("r#").to_string();
+
+                Ok(quote! {
+                    ::surrealdb::sql::Value::Param(::surrealdb::sql::Param::from(#name))
+                })
+            }
+            TokenTree::Group(group) => match group.delimiter() {
+                Delimiter::Parenthesis => {
+                    let v = Parser::new(group.stream()).parse()?;
+
+                    Ok(quote! {
+                        ::surrealdb::sql::Value::Subquery(Box::new(::surrealdb::sql::Subquery::Value(#v)))
+                    })
+                }
+                Delimiter::Bracket => {
+                    let values = split_commas(group.stream())
+                        .into_iter()
+                        .map(|value| Parser::new(value).parse())
+                        .collect::<Result<Vec<_>, _>>()?;
+
+                    Ok(quote! {
+                        ::surrealdb::sql::Value::Array(::surrealdb::sql::Array::from(::std::vec::Vec::<::surrealdb::sql::Value>::from([#(#values),*])))
+                    })
+                }
+                _ if group.stream().is_empty() => Err(Error::new(group.span(), "Expected a rust expression")),
+                _ => {
+                    let expr = group.stream();
+
+                    Ok(quote! {
+                        ::surrealdb::sql::Value::from(#expr)
+                    })
+                }
+            },
+            TokenTree::Ident(ident) => {
+                let name = ident.to_string();
+
+                match name.as_str() {
+                    "true" | "false" => {
+                        let b = name == "true";
+
+                        return Ok(quote! { ::surrealdb::sql::Value::Bool(#b) });
+                    }
+                    "NONE" => return Ok(quote! { ::surrealdb::sql::Value::None }),
+                    "NULL" => return Ok(quote! { ::surrealdb::sql::Value::Null }),
+                    _ => {}
+                }
+
+                self.idiom(name)
+            }
+            TokenTree::Literal(literal) => match Lit::new(literal) {
+                Lit::Str(s) => {
+                    let s = s.value();
+
+                    Ok(quote! { ::surrealdb::sql::Value::from(#s) })
+                }
+                Lit::Int(i) => {
+                    let i = i.base10_parse::<i64>()?;
+
+                    Ok(quote! { ::surrealdb::sql::Value::from(#i) })
+                }
+                Lit::Float(f) => {
+                    let f = f.base10_parse::<f64>()?;
+
+                    Ok(quote! { ::surrealdb::sql::Value::from(#f) })
+                }
+                lit => Err(Error::new(lit.span(), "Only string, integer and float literals are supported")),
+            },
+            token => Err(Error::new(token.span(), "Expected a value")),
+        }
+    }
+
+    /// Field path like `a.b[0].c`
+    fn idiom(&mut self, first: String) -> Result<TokenStream, Error> {
+        let mut parts = vec![field_part(&first)];
+
+        loop {
+            match self.tokens.get(self.pos).cloned() {
+                Some(TokenTree::Punct(punct)) if punct.as_char() == '.' => {
+                    let Some(TokenTree::Ident(field)) = self.tokens.get(self.pos + 1).cloned() else {
+                        return Err(Error::new(punct.span(), "Expected a field name after `.`"));
+                    };
+
+                    self.pos += 2;
+
+                    parts.push(field_part(&field.to_string()));
+                }
+                Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Bracket => {
+                    let index = match syn::parse2::<Lit>(group.stream()) {
+                        Ok(Lit::Int(index)) => index.base10_parse::<usize>()?,
+                        _ => return Err(Error::new(group.span(), "Expected an index")),
+                    };
+
+                    self.pos += 1;
+
+                    parts.push(quote! { ::surrealdb::sql::Part::from(#index) });
+                }
+                _ => break,
+            }
+        }
+
+        Ok(quote! {
+            ::surrealdb::sql::Value::Idiom(::surrealdb::sql::Idiom::from(vec![#(#parts),*]))
+        })
+    }
+}
+
+fn field_part(name: &str) -> TokenStream {
+    let name = name.trim_start_matches("r#");
+
+    quote! { ::surrealdb::sql::Part::from(#name) }
+}
+
+fn split_commas(input: TokenStream) -> Vec<TokenStream> {
+    let mut values = vec![];
+    let mut value = TokenStream::new();
+
+    for token in input {
+        match token {
+            TokenTree::Punct(punct) if punct.as_char() == ',' => values.push(std::mem::take(&mut value)),
+            token => value.extend([token]),
+        }
+    }
+
+    if !value.is_empty() {
+        values.push(value);
+    }
+
+    values
+}
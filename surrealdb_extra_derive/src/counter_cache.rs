@@ -0,0 +1,77 @@
+use ::syn::{DeriveInput, Meta, Token, Expr, Lit, Path};
+use ::syn::punctuated::Punctuated;
+use syn::__private::Span;
+use syn::Error;
+
+pub(crate) struct CounterCacheAttr {
+    pub(crate) field: String,
+    pub(crate) on: Path,
+    pub(crate) via: String,
+}
+
+pub(crate) fn get_counter_caches(input: &DeriveInput) -> Result<Vec<CounterCacheAttr>, Error> {
+    let mut counter_caches = vec![];
+
+    for attr in &input.attrs {
+        if !attr.path().is_ident("table") {
+            continue;
+        }
+
+        let nested = attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)?;
+
+        for meta in nested {
+            if !meta.path().is_ident("counter_cache") {
+                continue;
+            }
+
+            let args = meta.require_list()?.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)?;
+
+            let mut field = None;
+            let mut on = None;
+            let mut via = None;
+
+            for arg in args {
+                let mnv = arg.require_name_value()?;
+
+                if mnv.path.is_ident("on") {
+                    let Expr::Path(path) = &mnv.value else {
+                        return Err(Error::new(Span::call_site(), "counter_cache(on) must be a type"));
+                    };
+
+                    on = Some(path.path.clone());
+                } else if mnv.path.is_ident("field") {
+                    field = Some(get_ident_str(&mnv.value, "field")?);
+                } else if mnv.path.is_ident("via") {
+                    via = Some(get_ident_str(&mnv.value, "via")?);
+                } else {
+                    return Err(Error::new(Span::call_site(), "counter_cache only accepts field, on and via"));
+                }
+            }
+
+            match (field, on, via) {
+                (Some(field), Some(on), Some(via)) => counter_caches.push(CounterCacheAttr { field, on, via }),
+                _ => return Err(Error::new(Span::call_site(), "counter_cache needs field, on and via")),
+            }
+        }
+    }
+
+    Ok(counter_caches)
+}
+
+fn get_ident_str(expr: &Expr, attr_name: &str) -> Result<String, Error> {
+    let Expr::Lit(expr_lit) = expr else {
+        return Err(Error::new_spanned(expr, "Wrong expression"));
+    };
+
+    let Lit::Str(lit) = &expr_lit.lit else {
+        return Err(Error::new_spanned(&expr_lit.lit, "Wrong type"));
+    };
+
+    let v = lit.value();
+
+    if v.is_empty() || !v.chars().all(|x| x.is_alphanumeric() || "_".contains(x)) {
+        return Err(Error::new_spanned(lit, format!("counter_cache({}) attribute can only have alphanumeric and/or `_` characters", attr_name)));
+    }
+
+    Ok(v)
+}
@@ -0,0 +1,42 @@
+use ::syn::{Data, DeriveInput, Fields};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::__private::Span;
+use syn::Error;
+use crate::meta::{field_meta, serde_rename};
+use crate::path::fields_enum;
+
+/// Generates the `<Struct>Fields` enum and the `Embedded` implementation of a nested struct
+pub(crate) fn embedded(input: &DeriveInput) -> Result<TokenStream, Error> {
+    let Data::Struct(data) = &input.data else {
+        return Err(Error::new(Span::call_site(), "Embedded can only be derived for structs"));
+    };
+
+    let Fields::Named(named) = &data.fields else {
+        return Err(Error::new(Span::call_site(), "Embedded needs named fields"));
+    };
+
+    let mut fields = vec![];
+
+    for field in &named.named {
+        let Some(ident) = &field.ident else {
+            continue;
+        };
+
+        let name = serde_rename(field)?.unwrap_or_else(|| ident.to_string().trim_start_matches("r#").to_string());
+
+        fields.push(field_meta(field, &name, false)?);
+    }
+
+    let struct_name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let fields_enum = fields_enum(input)?;
+
+    Ok(quote! {
+        impl #impl_generics ::surrealdb_extra::table::path::Embedded for #struct_name #ty_generics #where_clause {
+            const FIELDS: &'static [::surrealdb_extra::table::meta::FieldMeta] = &[#(#fields),*];
+        }
+
+        #fields_enum
+    })
+}
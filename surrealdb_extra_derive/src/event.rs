@@ -0,0 +1,65 @@
+use ::syn::{DeriveInput, Meta, Token};
+use ::syn::punctuated::Punctuated;
+use syn::__private::Span;
+use syn::Error;
+use crate::meta::lit_str;
+
+pub(crate) struct EventAttr {
+    pub(crate) name: String,
+    pub(crate) when: String,
+    pub(crate) then: String,
+}
+
+pub(crate) fn get_events(input: &DeriveInput) -> Result<Vec<EventAttr>, Error> {
+    let mut events = vec![];
+
+    for attr in &input.attrs {
+        if !attr.path().is_ident("table") {
+            continue;
+        }
+
+        let nested = attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)?;
+
+        for meta in nested {
+            if !meta.path().is_ident("event") {
+                continue;
+            }
+
+            let args = meta.require_list()?.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)?;
+
+            let mut name = None;
+            let mut when = None;
+            let mut then = None;
+
+            for arg in args {
+                let mnv = arg.require_name_value()?;
+
+                if mnv.path.is_ident("name") {
+                    name = Some(lit_str(&mnv.value)?);
+                } else if mnv.path.is_ident("when") {
+                    when = Some(lit_str(&mnv.value)?);
+                } else if mnv.path.is_ident("then") {
+                    then = Some(lit_str(&mnv.value)?);
+                } else {
+                    return Err(Error::new(Span::call_site(), "event only accepts name, when and then"));
+                }
+            }
+
+            let Some(name) = name.filter(|n| !n.is_empty() && n.chars().all(|c| c.is_alphanumeric() || c == '_')) else {
+                return Err(Error::new(Span::call_site(), "event needs a name of letters, digits and underscores"));
+            };
+
+            let Some(then) = then.filter(|t| !t.trim().is_empty()) else {
+                return Err(Error::new(Span::call_site(), "event needs then"));
+            };
+
+            events.push(EventAttr {
+                name,
+                when: when.unwrap_or_else(|| "true".to_string()),
+                then,
+            });
+        }
+    }
+
+    Ok(events)
+}
@@ -0,0 +1,26 @@
+use ::syn::{Data, DeriveInput, Fields};
+use syn::__private::Span;
+use syn::Error;
+use crate::meta::serde_rename;
+
+pub(crate) fn get_fetch_fields(input: &DeriveInput) -> Result<Vec<String>, Error> {
+    let Data::Struct(data) = &input.data else {
+        return Err(Error::new(Span::call_site(), "Table can only be derived for structs"));
+    };
+
+    let Fields::Named(fields) = &data.fields else {
+        return Ok(vec![]);
+    };
+
+    let mut fetch_fields = vec![];
+
+    for field in fields.named.iter().filter(|field| field.attrs.iter().any(|attr| attr.path().is_ident("fetch"))) {
+        let Some(ident) = &field.ident else {
+            continue;
+        };
+
+        fetch_fields.push(serde_rename(field)?.unwrap_or_else(|| ident.to_string().trim_start_matches("r#").to_string()));
+    }
+
+    Ok(fetch_fields)
+}
@@ -0,0 +1,136 @@
+use ::syn::{Data, DeriveInput, Fields, Ident, Meta, Token, Type};
+use ::syn::punctuated::Punctuated;
+use syn::__private::Span;
+use syn::Error;
+use crate::meta::{lit_str, serde_rename};
+
+/// Options of `#[field(...)]`
+pub(crate) struct FieldAttr {
+    pub(crate) ident: Ident,
+    pub(crate) name: String,
+    pub(crate) ty: Type,
+    pub(crate) computed: Option<String>,
+    pub(crate) default: Option<String>,
+    pub(crate) unique: bool,
+    pub(crate) find_by: bool,
+    pub(crate) skip: bool,
+    pub(crate) readonly: bool,
+}
+
+/// Fields that have a `#[field(...)]` attribute
+pub(crate) fn get_field_attrs(input: &DeriveInput) -> Result<Vec<FieldAttr>, Error> {
+    let Data::Struct(data) = &input.data else {
+        return Err(Error::new(Span::call_site(), "Table can only be derived for structs"));
+    };
+
+    let Fields::Named(fields) = &data.fields else {
+        return Ok(vec![]);
+    };
+
+    let mut field_attrs = vec![];
+
+    for field in &fields.named {
+        let Some(ident) = &field.ident else {
+            continue;
+        };
+
+        let mut computed = None;
+        let mut default = None;
+        let mut unique = false;
+        let mut find_by = false;
+        let mut skip = false;
+        let mut readonly = false;
+        let mut has_attr = false;
+
+        for attr in &field.attrs {
+            if !attr.path().is_ident("field") {
+                continue;
+            }
+
+            has_attr = true;
+
+            let nested = attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)?;
+
+            for meta in nested {
+                match meta {
+                    Meta::Path(path) if path.is_ident("unique") => unique = true,
+                    Meta::Path(path) if path.is_ident("find_by") => find_by = true,
+                    Meta::Path(path) if path.is_ident("skip") => skip = true,
+                    Meta::Path(path) if path.is_ident("readonly") => readonly = true,
+                    // Read by `is_flatten`, it applies to the metadata and the field paths
+                    Meta::Path(path) if path.is_ident("flatten") => {}
+                    Meta::NameValue(mnv) if mnv.path.is_ident("computed") => {
+                        if !is_computed(&field.ty) {
+                            return Err(Error::new(Span::call_site(), "field(computed) can only be used on fields of type Computed<T>"));
+                        }
+
+                        let value = lit_str(&mnv.value)?;
+
+                        if value.trim().is_empty() {
+                            return Err(Error::new(Span::call_site(), "field(computed) needs an expression"));
+                        }
+
+                        computed = Some(value);
+                    }
+                    Meta::NameValue(mnv) if mnv.path.is_ident("default") => {
+                        let value = lit_str(&mnv.value)?;
+
+                        if value.trim().is_empty() {
+                            return Err(Error::new(Span::call_site(), "field(default) needs an expression"));
+                        }
+
+                        default = Some(value);
+                    }
+                    _ => return Err(Error::new(Span::call_site(), "field only accepts computed, default, unique, find_by, skip, readonly and flatten")),
+                }
+            }
+        }
+
+        if !has_attr {
+            continue;
+        }
+
+        if computed.is_some() && default.is_some() {
+            return Err(Error::new(Span::call_site(), "field(computed) and field(default) can't be used on the same field"));
+        }
+
+        field_attrs.push(FieldAttr {
+            ident: ident.clone(),
+            name: serde_rename(field)?.unwrap_or_else(|| ident.to_string().trim_start_matches("r#").to_string()),
+            ty: field.ty.clone(),
+            computed,
+            default,
+            unique,
+            find_by,
+            skip,
+            readonly,
+        });
+    }
+
+    Ok(field_attrs)
+}
+
+pub(crate) fn is_computed(ty: &Type) -> bool {
+    let Type::Path(path) = ty else {
+        return false;
+    };
+
+    path.path.segments.last().is_some_and(|segment| segment.ident == "Computed")
+}
+
+/// True for fields with `#[field(flatten)]`
+pub(crate) fn is_flatten(field: &syn::Field) -> Result<bool, Error> {
+    for attr in &field.attrs {
+        if !attr.path().is_ident("field") {
+            continue;
+        }
+
+        let nested = attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)?;
+
+        if nested.iter().any(|meta| matches!(meta, Meta::Path(path) if path.is_ident("flatten"))) {
+            return Ok(true);
+        }
+    }
+
+    Ok(false)
+}
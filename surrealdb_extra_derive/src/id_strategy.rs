@@ -0,0 +1,48 @@
+use ::syn::{DeriveInput, Meta, Token};
+use ::syn::punctuated::Punctuated;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::__private::Span;
+use syn::Error;
+use crate::meta::lit_str;
+
+/// Generates `ID_STRATEGY` for `#[table(id = "ulid" | "uuid" | "rand" | "incremental")]`
+pub(crate) fn id_strategy(input: &DeriveInput) -> Result<TokenStream, Error> {
+    let mut strategy = None;
+
+    for attr in &input.attrs {
+        if !attr.path().is_ident("table") {
+            continue;
+        }
+
+        let nested = attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)?;
+
+        for meta in nested {
+            if !meta.path().is_ident("id") {
+                continue;
+            }
+
+            if strategy.is_some() {
+                return Err(Error::new(Span::call_site(), "id can only be set once"));
+            }
+
+            strategy = Some(lit_str(&meta.require_name_value()?.value)?);
+        }
+    }
+
+    let Some(strategy) = strategy else {
+        return Ok(quote! {});
+    };
+
+    let variant = match strategy.as_str() {
+        "ulid" => quote! { Ulid },
+        "uuid" => quote! { Uuid },
+        "rand" => quote! { Rand },
+        "incremental" => quote! { Incremental },
+        _ => return Err(Error::new(Span::call_site(), format!("table(id) must be `ulid`, `uuid`, `rand` or `incremental`, not `{strategy}`"))),
+    };
+
+    Ok(quote! {
+        const ID_STRATEGY: ::surrealdb_extra::table::id::IdStrategy = ::surrealdb_extra::table::id::IdStrategy::#variant;
+    })
+}
@@ -0,0 +1,24 @@
+use ::syn::{DeriveInput, Meta, Token};
+use ::syn::punctuated::Punctuated;
+use syn::Error;
+
+/// True for `#[table(id_string)]`, the `<Struct>Id` is then a `table:id` string in human readable formats
+pub(crate) fn is_id_string(input: &DeriveInput) -> Result<bool, Error> {
+    for attr in &input.attrs {
+        if !attr.path().is_ident("table") {
+            continue;
+        }
+
+        let nested = attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)?;
+
+        for meta in nested {
+            if meta.path().is_ident("id_string") {
+                meta.require_path_only()?;
+
+                return Ok(true);
+            }
+        }
+    }
+
+    Ok(false)
+}
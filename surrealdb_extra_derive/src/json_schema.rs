@@ -0,0 +1,114 @@
+use ::syn::{Data, DeriveInput, Fields, Meta, Token, Type};
+use ::syn::punctuated::Punctuated;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::__private::Span;
+use syn::Error;
+use crate::meta::{generic_inner, serde_rename};
+
+/// The `#[table(json_schema)]` attribute
+fn json_schema_attr(input: &DeriveInput) -> Result<Option<Meta>, Error> {
+    for attr in &input.attrs {
+        if !attr.path().is_ident("table") {
+            continue;
+        }
+
+        let nested = attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)?;
+
+        for meta in nested {
+            if meta.path().is_ident("json_schema") {
+                meta.require_path_only()?;
+
+                return Ok(Some(meta));
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+/// Generates the `JsonSchema` implementations of the struct and its id for `#[table(json_schema)]`, which needs the `schemars` feature
+pub(crate) fn json_schema_impl(input: &DeriveInput, id_name: &syn::Ident) -> Result<TokenStream, Error> {
+    let Some(attr) = json_schema_attr(input)? else {
+        return Ok(quote! {});
+    };
+
+    if !cfg!(feature = "schemars") {
+        return Err(Error::new_spanned(attr, "table(json_schema) needs the schemars feature of surrealdb_extra"));
+    }
+
+    let Data::Struct(data) = &input.data else {
+        return Err(Error::new(Span::call_site(), "Table can only be derived for structs"));
+    };
+
+    let struct_name = &input.ident;
+    let mut properties = vec![];
+
+    if let Fields::Named(named) = &data.fields {
+        for field in &named.named {
+            let Some(ident) = &field.ident else {
+                continue;
+            };
+
+            let name = serde_rename(field)?.unwrap_or_else(|| ident.to_string().trim_start_matches("r#").to_string());
+
+            let optional = generic_inner(&field.ty, "Option");
+            let required = optional.is_none();
+            let ty = optional.unwrap_or(&field.ty);
+
+            let table = if name == "id" {
+                quote! { Some(<#struct_name as Table>::TABLE_NAME) }
+            } else {
+                quote! { None }
+            };
+
+            let schema = if is_record_id(ty) {
+                quote! { ::surrealdb_extra::table::json_schema::record_id(#table) }
+            } else if generic_inner(ty, "Vec").is_some_and(is_record_id) {
+                quote! { ::surrealdb_extra::table::json_schema::record_ids(None) }
+            } else {
+                let field_ty = &field.ty;
+
+                quote! { generator.subschema_for::<#field_ty>() }
+            };
+
+            properties.push(quote! { (#name, #schema, #required) });
+        }
+    }
+
+    Ok(quote! {
+        impl ::surrealdb_extra::schemars::JsonSchema for #struct_name {
+            fn schema_name() -> ::std::string::String {
+                stringify!(#struct_name).to_string()
+            }
+
+            #[allow(unused_variables)]
+            fn json_schema(generator: &mut ::surrealdb_extra::schemars::gen::SchemaGenerator) -> ::surrealdb_extra::schemars::schema::Schema {
+                ::surrealdb_extra::table::json_schema::object(vec![#(#properties),*])
+            }
+        }
+
+        impl ::surrealdb_extra::schemars::JsonSchema for #id_name {
+            fn schema_name() -> ::std::string::String {
+                stringify!(#id_name).to_string()
+            }
+
+            fn is_referenceable() -> bool {
+                false
+            }
+
+            fn json_schema(_: &mut ::surrealdb_extra::schemars::gen::SchemaGenerator) -> ::surrealdb_extra::schemars::schema::Schema {
+                ::surrealdb_extra::table::json_schema::record_id(Some(<#struct_name as Table>::TABLE_NAME))
+            }
+        }
+    })
+}
+
+/// `Thing` and `RecordId`, which have no `JsonSchema`
+fn is_record_id(ty: &Type) -> bool {
+    let Type::Path(path) = ty else {
+        return false;
+    };
+
+    path.path.segments.last().is_some_and(|segment| segment.ident == "Thing" || segment.ident == "RecordId")
+}
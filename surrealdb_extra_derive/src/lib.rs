@@ -1,28 +1,604 @@
 mod table_name;
+mod counter_cache;
+mod fetch;
+mod meta;
+mod field;
+mod retention;
+mod cond;
+mod vector;
+mod event;
+mod permissions;
+mod path;
+mod validate;
+mod version;
+mod tenant;
+mod id_strategy;
+mod composite_id;
+mod embedded;
+mod changeset;
+mod builder;
+mod json_schema;
+mod id_string;
+mod projection;
 
 use proc_macro::TokenStream;
-use quote::quote;
+use quote::{format_ident, quote};
 use syn::{parse_macro_input, DeriveInput};
 use crate::table_name::get_table_name;
+use crate::counter_cache::get_counter_caches;
+use crate::fetch::get_fetch_fields;
+use crate::meta::{get_indexes, meta_fn};
+use crate::field::get_field_attrs;
+use crate::retention::{get_retention, RetentionAttr};
+use crate::vector::get_vector_indexes;
+use crate::event::get_events;
+use crate::permissions::get_permissions;
+use crate::path::fields_enum;
+use crate::validate::validate_fn;
+use crate::version::version_field;
+use crate::tenant::tenant_field;
+use crate::id_strategy::id_strategy;
+use crate::composite_id::composite_id;
+use crate::changeset::changeset_struct;
+use crate::builder::builder_struct;
+use crate::json_schema::json_schema_impl;
+use crate::id_string::is_id_string;
 
-#[proc_macro_derive(Table, attributes(table))]
+#[proc_macro_derive(Table, attributes(table, fetch, field, index))]
 pub fn table(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
 
     let struct_name = &input.ident;
-    let table_name = get_table_name(&input).unwrap();
+    let vis = &input.vis;
+    let id_name = format_ident!("{}Id", struct_name);
+    let table_name = match get_table_name(&input) {
+        Ok(table_name) => table_name,
+        Err(e) => return e.to_compile_error().into(),
+    };
+    let counter_caches = match get_counter_caches(&input) {
+        Ok(counter_caches) => counter_caches,
+        Err(e) => return e.to_compile_error().into(),
+    };
+    let fetch_fields = match get_fetch_fields(&input) {
+        Ok(fetch_fields) => fetch_fields,
+        Err(e) => return e.to_compile_error().into(),
+    };
+    let indexes = match get_indexes(&input) {
+        Ok(indexes) => indexes,
+        Err(e) => return e.to_compile_error().into(),
+    };
+    let meta = match meta_fn(&input, &table_name, &indexes) {
+        Ok(meta) => meta,
+        Err(e) => return e.to_compile_error().into(),
+    };
+    let field_attrs = match get_field_attrs(&input) {
+        Ok(field_attrs) => field_attrs,
+        Err(e) => return e.to_compile_error().into(),
+    };
+    let retention = match get_retention(&input) {
+        Ok(retention) => retention,
+        Err(e) => return e.to_compile_error().into(),
+    };
+    let vector_indexes = match get_vector_indexes(&input) {
+        Ok(vector_indexes) => vector_indexes,
+        Err(e) => return e.to_compile_error().into(),
+    };
+    let events = match get_events(&input) {
+        Ok(events) => events,
+        Err(e) => return e.to_compile_error().into(),
+    };
+    let permissions = match get_permissions(&input) {
+        Ok(permissions) => permissions,
+        Err(e) => return e.to_compile_error().into(),
+    };
+    let fields = match fields_enum(&input) {
+        Ok(fields) => fields,
+        Err(e) => return e.to_compile_error().into(),
+    };
+    let validate = match validate_fn(&input) {
+        Ok(validate) => validate,
+        Err(e) => return e.to_compile_error().into(),
+    };
+    let version_field = match version_field(&input) {
+        Ok(version_field) => version_field,
+        Err(e) => return e.to_compile_error().into(),
+    };
+    let tenant_field = match tenant_field(&input) {
+        Ok(tenant_field) => tenant_field,
+        Err(e) => return e.to_compile_error().into(),
+    };
+    let id_strategy = match id_strategy(&input) {
+        Ok(id_strategy) => id_strategy,
+        Err(e) => return e.to_compile_error().into(),
+    };
+    let composite_id = match composite_id(&input) {
+        Ok(composite_id) => composite_id,
+        Err(e) => return e.to_compile_error().into(),
+    };
+    let changeset = match changeset_struct(&input, &field_attrs) {
+        Ok(changeset) => changeset,
+        Err(e) => return e.to_compile_error().into(),
+    };
+    let builder = match builder_struct(&input, !version_field.is_empty()) {
+        Ok(builder) => builder,
+        Err(e) => return e.to_compile_error().into(),
+    };
+    let json_schema = match json_schema_impl(&input, &id_name) {
+        Ok(json_schema) => json_schema,
+        Err(e) => return e.to_compile_error().into(),
+    };
+    let id_string = match is_id_string(&input) {
+        Ok(id_string) => id_string,
+        Err(e) => return e.to_compile_error().into(),
+    };
+
+    let counter_caches = if counter_caches.is_empty() {
+        quote! {}
+    } else {
+        let counter_caches = counter_caches.iter().map(|c| {
+            let field = &c.field;
+            let on = &c.on;
+            let via = &c.via;
+
+            quote! {
+                ::surrealdb_extra::table::counter::CounterCache {
+                    field: #field,
+                    on: <#on as Table>::TABLE_NAME,
+                    via: #via,
+                }
+            }
+        });
+
+        quote! {
+            const COUNTER_CACHES: &'static [::surrealdb_extra::table::counter::CounterCache] = &[#(#counter_caches),*];
+        }
+    };
+
+    let fetch_fields = if fetch_fields.is_empty() {
+        quote! {}
+    } else {
+        quote! {
+            const FETCH_FIELDS: &'static [&'static str] = &[#(#fetch_fields),*];
+        }
+    };
+
+    let computed_fields: Vec<_> = field_attrs.iter()
+        .filter_map(|f| f.computed.as_ref().map(|value| (&f.name, value)))
+        .collect();
+
+    let computed_fields = if computed_fields.is_empty() {
+        quote! {}
+    } else {
+        let computed_fields = computed_fields.iter().map(|(field, value)| {
+
+            quote! {
+                ::surrealdb_extra::table::computed::ComputedField {
+                    field: #field,
+                    value: #value,
+                }
+            }
+        });
+
+        quote! {
+            const COMPUTED_FIELDS: &'static [::surrealdb_extra::table::computed::ComputedField] = &[#(#computed_fields),*];
+        }
+    };
+
+    let default_fields: Vec<_> = field_attrs.iter()
+        .filter_map(|f| f.default.as_ref().map(|value| (&f.name, value)))
+        .collect();
+
+    let default_fields = if default_fields.is_empty() {
+        quote! {}
+    } else {
+        let default_fields = default_fields.iter().map(|(field, value)| {
+            quote! {
+                ::surrealdb_extra::table::default::DefaultField {
+                    field: #field,
+                    value: #value,
+                }
+            }
+        });
+
+        quote! {
+            const DEFAULT_FIELDS: &'static [::surrealdb_extra::table::default::DefaultField] = &[#(#default_fields),*];
+        }
+    };
+
+    let skip_fields: Vec<_> = field_attrs.iter().filter(|f| f.skip).map(|f| &f.name).collect();
+
+    let skip_fields = if skip_fields.is_empty() {
+        quote! {}
+    } else {
+        quote! {
+            const SKIP_FIELDS: &'static [&'static str] = &[#(#skip_fields),*];
+        }
+    };
+
+    let readonly_fields: Vec<_> = field_attrs.iter().filter(|f| f.readonly).map(|f| &f.name).collect();
+
+    let readonly_fields = if readonly_fields.is_empty() {
+        quote! {}
+    } else {
+        quote! {
+            const READONLY_FIELDS: &'static [&'static str] = &[#(#readonly_fields),*];
+        }
+    };
+
+    let retention = if retention.is_empty() {
+        quote! {}
+    } else {
+        let retention = retention.iter().map(|r| match r {
+            RetentionAttr::KeepDays { days, by } => quote! {
+                ::surrealdb_extra::table::retention::RetentionPolicy::keep_days(#days, #by)
+            },
+            RetentionAttr::KeepLast { n, per, by } => quote! {
+                ::surrealdb_extra::table::retention::RetentionPolicy::keep_last(#n, #per, #by)
+            },
+        });
+
+        quote! {
+            const RETENTION: &'static [::surrealdb_extra::table::retention::RetentionPolicy] = &[#(#retention),*];
+        }
+    };
+
+    let vector_index_consts = if vector_indexes.is_empty() {
+        quote! {}
+    } else {
+        let vector_indexes = vector_indexes.iter().map(|v| {
+            let field = &v.name;
+            let dimension = v.dimension;
+            let distance = &v.distance;
+            let kind = if v.mtree { format_ident!("Mtree") } else { format_ident!("Hnsw") };
+
+            quote! {
+                ::surrealdb_extra::table::vector::VectorIndex {
+                    field: #field,
+                    dimension: #dimension,
+                    distance: ::surrealdb_extra::table::vector::VectorDistance::#distance,
+                    kind: ::surrealdb_extra::table::vector::VectorIndexKind::#kind,
+                }
+            }
+        });
+
+        quote! {
+            const VECTOR_INDEXES: &'static [::surrealdb_extra::table::vector::VectorIndex] = &[#(#vector_indexes),*];
+        }
+    };
+
+    let events = if events.is_empty() {
+        quote! {}
+    } else {
+        let events = events.iter().map(|e| {
+            let name = &e.name;
+            let when = &e.when;
+            let then = &e.then;
+
+            quote! {
+                ::surrealdb_extra::table::event::TableEvent {
+                    name: #name,
+                    when: #when,
+                    then: #then,
+                }
+            }
+        });
+
+        quote! {
+            const EVENTS: &'static [::surrealdb_extra::table::event::TableEvent] = &[#(#events),*];
+        }
+    };
+
+    let permissions = match permissions {
+        None => quote! {},
+        Some(p) => {
+            let [select, create, update, delete] = [&p.select, &p.create, &p.update, &p.delete].map(|permission| match permission {
+                Some(permission) => quote! { Some(#permission) },
+                None => quote! { None },
+            });
+
+            quote! {
+                const PERMISSIONS: Option<::surrealdb_extra::table::permissions::TablePermissions> = Some(::surrealdb_extra::table::permissions::TablePermissions {
+                    select: #select,
+                    create: #create,
+                    update: #update,
+                    delete: #delete,
+                });
+            }
+        }
+    };
 
     let expanded = quote! {
         impl Table for #struct_name {
             const TABLE_NAME: &'static str = #table_name;
 
-            fn get_id(&self) -> &Option<::surrealdb::opt::RecordId> {
+            #counter_caches
+
+            #fetch_fields
+
+            #computed_fields
+
+            #default_fields
+
+            #skip_fields
+
+            #readonly_fields
+
+            #version_field
+
+            #tenant_field
+
+            #id_strategy
+
+            #retention
+
+            #vector_index_consts
+
+            #events
+
+            #permissions
+
+            #validate
+
+            #meta
+
+            fn get_id(&self) -> &Option<::surrealdb::sql::Thing> {
                 &self.id
             }
 
             fn set_id(&mut self, id: impl Into<::surrealdb::sql::Id>) {
-                self.id = Some(::surrealdb::opt::RecordId::from((Self::TABLE_NAME, id.into())));
+                self.id = Some(::surrealdb::sql::Thing::from((Self::TABLE_NAME, id.into())));
+            }
+        }
+    };
+
+    let find_by = field_attrs.iter().filter(|f| f.unique || f.find_by).map(|f| {
+        let name = &f.name;
+        let ty = &f.ty;
+        let field_ident = f.ident.to_string().trim_start_matches("r#").to_string();
+        let find_by_ident = format_ident!("find_by_{}", field_ident);
+        let find_all_by_ident = format_ident!("find_all_by_{}", field_ident);
+
+        let find_all_by = if f.unique {
+            quote! {}
+        } else {
+            quote! {
+                /// Every record where the field equals the value
+                pub async fn #find_all_by_ident<C: ::surrealdb::Connection>(db: &impl ::surrealdb_extra::table::database::Database<C>, value: impl Into<#ty>) -> ::surrealdb_extra::anyhow::Result<Vec<Self>> {
+                    ::surrealdb_extra::table::find::find_all_by::<Self, C, #ty>(&db.connection(), #name, value.into()).await
+                }
+            }
+        };
+
+        quote! {
+            /// The first record where the field equals the value
+            pub async fn #find_by_ident<C: ::surrealdb::Connection>(db: &impl ::surrealdb_extra::table::database::Database<C>, value: impl Into<#ty>) -> ::surrealdb_extra::anyhow::Result<Option<Self>> {
+                ::surrealdb_extra::table::find::find_by::<Self, C, #ty>(&db.connection(), #name, value.into()).await
+            }
+
+            #find_all_by
+        }
+    }).collect::<Vec<_>>();
+
+    let find_similar = vector_indexes.iter().enumerate().map(|(i, v)| {
+        let embedding = &v.embedding;
+        let field_ident = v.ident.to_string().trim_start_matches("r#").to_string();
+        let find_similar_by_ident = format_ident!("find_similar_by_{}", field_ident);
+
+        let find_similar = if vector_indexes.len() == 1 {
+            quote! {
+                /// The `k` records with the nearest embedding ordered by their distance
+                pub async fn find_similar<C: ::surrealdb::Connection>(db: &impl ::surrealdb_extra::table::database::Database<C>, embedding: #embedding, k: u32) -> ::surrealdb_extra::anyhow::Result<Vec<::surrealdb_extra::table::vector::Similar<Self>>> {
+                    Self::#find_similar_by_ident(db, embedding, k).await
+                }
+            }
+        } else {
+            quote! {}
+        };
+
+        quote! {
+            /// The `k` records with the nearest embedding in the field ordered by their distance
+            pub async fn #find_similar_by_ident<C: ::surrealdb::Connection>(db: &impl ::surrealdb_extra::table::database::Database<C>, embedding: #embedding, k: u32) -> ::surrealdb_extra::anyhow::Result<Vec<::surrealdb_extra::table::vector::Similar<Self>>> {
+                ::surrealdb_extra::table::vector::find_similar::<Self, C, #embedding>(&db.connection(), &<Self as Table>::VECTOR_INDEXES[#i], embedding, k).await
+            }
+
+            #find_similar
+        }
+    });
+
+    let functions = find_by.into_iter().chain(find_similar).collect::<Vec<_>>();
+
+    let functions = if functions.is_empty() {
+        quote! {}
+    } else {
+        quote! {
+            impl #struct_name {
+                #(#functions)*
+            }
+        }
+    };
+
+    // `#[table(id_string)]` ids are `table:id` strings in human readable formats
+    let id_derive = if id_string {
+        quote! {
+            #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+        }
+    } else {
+        quote! {
+            #[derive(Debug, Clone, PartialEq, Eq, Hash, ::serde::Serialize, ::serde::Deserialize)]
+            #[serde(try_from = "::surrealdb::sql::Thing", into = "::surrealdb::sql::Thing")]
+        }
+    };
+
+    let id_string_serde = if id_string {
+        quote! {
+            impl ::serde::Serialize for #id_name {
+                fn serialize<S: ::serde::Serializer>(&self, serializer: S) -> ::core::result::Result<S::Ok, S::Error> {
+                    ::surrealdb_extra::table::serde_helpers::thing_as_string::serialize(&self.0, serializer)
+                }
+            }
+
+            impl<'de> ::serde::Deserialize<'de> for #id_name {
+                fn deserialize<D: ::serde::Deserializer<'de>>(deserializer: D) -> ::core::result::Result<Self, D::Error> {
+                    let thing = ::surrealdb_extra::table::serde_helpers::thing_as_string::deserialize(deserializer)?;
+
+                    Self::try_from(thing).map_err(<D::Error as ::serde::de::Error>::custom)
+                }
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    let expanded_id = quote! {
+        /// Record id that can only belong to the table of
+        #[doc = concat!("[`", stringify!(#struct_name), "`]")]
+        #id_derive
+        #vis struct #id_name(::surrealdb::sql::Thing);
+
+        #id_string_serde
+
+        impl #id_name {
+            pub fn new(id: impl Into<::surrealdb::sql::Id>) -> Self {
+                Self(::surrealdb::sql::Thing::from((<#struct_name as Table>::TABLE_NAME, id.into())))
+            }
+
+            pub fn thing(&self) -> &::surrealdb::sql::Thing {
+                &self.0
             }
+
+            pub fn into_inner(self) -> ::surrealdb::sql::Thing {
+                self.0
+            }
+        }
+
+        impl TryFrom<::surrealdb::sql::Thing> for #id_name {
+            type Error = ::surrealdb_extra::table::TableError;
+
+            fn try_from(thing: ::surrealdb::sql::Thing) -> ::core::result::Result<Self, Self::Error> {
+                if thing.tb != <#struct_name as Table>::TABLE_NAME {
+                    return Err(::surrealdb_extra::table::TableError::WrongTable {
+                        expected: <#struct_name as Table>::TABLE_NAME.to_string(),
+                        found: thing.tb,
+                    });
+                }
+
+                Ok(Self(thing))
+            }
+        }
+
+        impl From<#id_name> for ::surrealdb::sql::Thing {
+            fn from(id: #id_name) -> Self {
+                id.0
+            }
+        }
+
+        impl ::std::fmt::Display for #id_name {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                ::std::fmt::Display::fmt(&self.0, f)
+            }
+        }
+
+        impl ::surrealdb_extra::table::id::IntoTableId<#struct_name> for #id_name {
+            fn into_table_id(self) -> ::surrealdb_extra::anyhow::Result<::surrealdb::sql::Thing> {
+                Ok(self.0)
+            }
+        }
+    };
+
+    TokenStream::from(quote! {
+        #expanded
+
+        #expanded_id
+
+        #composite_id
+
+        #fields
+
+        #changeset
+
+        #builder
+
+        #json_schema
+
+        #functions
+    })
+}
+
+/// Field paths and metadata of a struct that is stored as a nested object of a table
+///
+/// It generates the `<Struct>Fields` enum and implements `Embedded`, so a `#[field(flatten)]` field of this type
+/// adds its fields as nested fields to the metadata and a path function to the fields enum of the table.
+#[proc_macro_derive(Embedded, attributes(field))]
+pub fn embedded(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    match embedded::embedded(&input) {
+        Ok(embedded) => embedded.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+/// Derives `Projection` for a struct with some of the fields of a `Table`
+///
+/// `#[projection(of = User)]` is required and every field has to be a field of `User`, checked with the variants of `UserFields`.
+/// See `surrealdb_extra::table::projection`.
+#[proc_macro_derive(Projection, attributes(projection))]
+pub fn projection(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    match projection::projection(&input) {
+        Ok(projection) => projection.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+/// Builds an `ExtraCond` from a SurrealQL like expression, checked at compile time
+///
+/// - fields and paths: `name`, `address.city`, `tags[0]`
+/// - parameters: `$age`
+/// - literals: `"text"`, `5`, `1.5`, `true`, `NONE`, `NULL` and arrays `[1, 2]`
+/// - rust values in braces, converted with `Value::from`: `{min_age}`
+/// - operators: `&&`/`AND`, `||`/`OR`, `!`, `=`, `==`, `!=`, `<`, `<=`, `>`, `>=`, `~`, `!~`, `?=`, `*=`, `+`, `-`, `*`, `/`, `**`,
+///   `CONTAINS`, `CONTAINSNOT`, `CONTAINSALL`, `CONTAINSANY`, `CONTAINSNONE`, `IN`, `NOT IN`, `INSIDE`, `NOTINSIDE`, `ALLINSIDE`, `ANYINSIDE` and `NONEINSIDE`
+///
+/// ```rust
+/// use surrealdb_extra::cond;
+///
+/// let min_age = 18;
+///
+/// let cond = cond!(age >= {min_age} && (name = $name || !archived));
+///
+/// assert_eq!(cond.0.to_string(), "WHERE age >= 18 AND (name = $name OR !archived)");
+/// ```
+#[proc_macro]
+pub fn cond(input: TokenStream) -> TokenStream {
+    match cond::cond(input.into()) {
+        Ok(cond) => cond.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+/// `async_trait` for manual `impl Table` blocks that override the async methods, like the hooks
+///
+/// It expands to `#[async_trait]` or to `#[async_trait(?Send)]` with the `wasm` feature,
+/// so the implementation matches the `Table` trait on every target.
+#[proc_macro_attribute]
+pub fn table_impl(attr: TokenStream, item: TokenStream) -> TokenStream {
+    if !attr.is_empty() {
+        return syn::Error::new(proc_macro2::Span::call_site(), "table_impl doesn't take arguments").to_compile_error().into();
+    }
+
+    let item = proc_macro2::TokenStream::from(item);
+
+    let expanded = if cfg!(feature = "wasm") {
+        quote! {
+            #[::surrealdb_extra::async_trait::async_trait(?Send)]
+            #item
+        }
+    } else {
+        quote! {
+            #[::surrealdb_extra::async_trait::async_trait]
+            #item
         }
     };
 
@@ -0,0 +1,233 @@
+use ::syn::{Data, DeriveInput, Expr, Fields, GenericArgument, Lit, Meta, PathArguments, Token, Type};
+use ::syn::punctuated::Punctuated;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::__private::Span;
+use syn::Error;
+use crate::field::is_flatten;
+
+pub(crate) struct IndexAttr {
+    pub(crate) name: String,
+    pub(crate) fields: Vec<String>,
+    pub(crate) unique: bool,
+}
+
+pub(crate) fn get_indexes(input: &DeriveInput) -> Result<Vec<IndexAttr>, Error> {
+    let mut indexes = vec![];
+
+    for attr in &input.attrs {
+        if !attr.path().is_ident("table") {
+            continue;
+        }
+
+        let nested = attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)?;
+
+        for meta in nested {
+            if !meta.path().is_ident("index") {
+                continue;
+            }
+
+            let args = meta.require_list()?.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)?;
+
+            let mut name = None;
+            let mut fields = vec![];
+            let mut unique = false;
+
+            for arg in args {
+                match arg {
+                    Meta::Path(path) if path.is_ident("unique") => unique = true,
+                    Meta::NameValue(mnv) if mnv.path.is_ident("name") => name = Some(lit_str(&mnv.value)?),
+                    Meta::NameValue(mnv) if mnv.path.is_ident("fields") => {
+                        let Expr::Array(array) = &mnv.value else {
+                            return Err(Error::new(Span::call_site(), "index(fields) must be an array of strings"));
+                        };
+
+                        for field in &array.elems {
+                            fields.push(lit_str(field)?);
+                        }
+                    }
+                    _ => return Err(Error::new(Span::call_site(), "index only accepts name, fields and unique")),
+                }
+            }
+
+            match name {
+                Some(name) if !fields.is_empty() => indexes.push(IndexAttr { name, fields, unique }),
+                _ => return Err(Error::new(Span::call_site(), "index needs a name and fields")),
+            }
+        }
+    }
+
+    Ok(indexes)
+}
+
+/// Generates the `meta` function of the `Table` trait
+pub(crate) fn meta_fn(input: &DeriveInput, table_name: &str, indexes: &[IndexAttr]) -> Result<TokenStream, Error> {
+    let Data::Struct(data) = &input.data else {
+        return Err(Error::new(Span::call_site(), "Table can only be derived for structs"));
+    };
+
+    let mut fields = vec![];
+    let mut relations = vec![];
+    let mut id_type = String::new();
+
+    if let Fields::Named(named) = &data.fields {
+        for field in &named.named {
+            let Some(ident) = &field.ident else {
+                continue;
+            };
+
+            let name = serde_rename(field)?.unwrap_or_else(|| ident.to_string().trim_start_matches("r#").to_string());
+            let fetch = field.attrs.iter().any(|attr| attr.path().is_ident("fetch"));
+
+            if name == "id" {
+                id_type = type_string(&field.ty);
+            }
+
+            if let Some((target, many)) = relation_target(&field.ty, fetch) {
+                relations.push(quote! {
+                    ::surrealdb_extra::table::meta::RelationMeta {
+                        field: #name,
+                        table: <#target as Table>::TABLE_NAME,
+                        many: #many,
+                    }
+                });
+            }
+
+            fields.push(field_meta(field, &name, fetch)?);
+        }
+    }
+
+    let indexes = indexes.iter().map(|index| {
+        let name = &index.name;
+        let index_fields = &index.fields;
+        let unique = index.unique;
+
+        quote! {
+            ::surrealdb_extra::table::meta::IndexMeta {
+                name: #name,
+                fields: &[#(#index_fields),*],
+                unique: #unique,
+            }
+        }
+    });
+
+    Ok(quote! {
+        fn meta() -> ::surrealdb_extra::table::meta::TableMeta {
+            ::surrealdb_extra::table::meta::TableMeta {
+                name: #table_name,
+                id: ::surrealdb_extra::table::meta::IdMeta {
+                    field: "id",
+                    rust_type: #id_type,
+                },
+                fields: &[#(#fields),*],
+                indexes: &[#(#indexes),*],
+                relations: &[#(#relations),*],
+            }
+        }
+    })
+}
+
+/// A `FieldMeta`, `#[field(flatten)]` fields get the fields of their `Embedded` type as nested fields
+pub(crate) fn field_meta(field: &syn::Field, name: &str, fetch: bool) -> Result<TokenStream, Error> {
+    let rust_type = type_string(&field.ty);
+    let optional = generic_inner(&field.ty, "Option").is_some();
+
+    let nested = if is_flatten(field)? {
+        let ty = generic_inner(&field.ty, "Option").unwrap_or(&field.ty);
+
+        quote! { <#ty as ::surrealdb_extra::table::path::Embedded>::FIELDS }
+    } else {
+        quote! { &[] }
+    };
+
+    Ok(quote! {
+        ::surrealdb_extra::table::meta::FieldMeta {
+            name: #name,
+            rust_type: #rust_type,
+            optional: #optional,
+            fetch: #fetch,
+            nested: #nested,
+        }
+    })
+}
+
+pub(crate) fn lit_str(expr: &Expr) -> Result<String, Error> {
+    match expr {
+        Expr::Lit(expr_lit) => match &expr_lit.lit {
+            Lit::Str(lit) => Ok(lit.value()),
+            _ => Err(Error::new(Span::call_site(), "Wrong type")),
+        },
+        _ => Err(Error::new(Span::call_site(), "Wrong expression")),
+    }
+}
+
+/// Name of the field after `#[serde(rename = "...")]`, other serde attributes are left to serde
+pub(crate) fn serde_rename(field: &syn::Field) -> Result<Option<String>, Error> {
+    for attr in &field.attrs {
+        if !attr.path().is_ident("serde") {
+            continue;
+        }
+
+        let Ok(nested) = attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated) else {
+            continue;
+        };
+
+        for meta in nested {
+            if let Meta::NameValue(mnv) = meta {
+                if mnv.path.is_ident("rename") {
+                    return lit_str(&mnv.value).map(Some);
+                }
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+fn type_string(ty: &Type) -> String {
+    quote!(#ty).to_string().replace(' ', "")
+}
+
+/// Inner type of `wrapper<T>` e.g. `Option<T>`
+pub(crate) fn generic_inner<'a>(ty: &'a Type, wrapper: &str) -> Option<&'a Type> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+
+    let segment = path.path.segments.last()?;
+
+    if segment.ident != wrapper {
+        return None;
+    }
+
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+
+    args.args.iter().find_map(|arg| match arg {
+        GenericArgument::Type(ty) => Some(ty),
+        _ => None,
+    })
+}
+
+/// Linked table of `Link<T>` fields and `#[fetch]` fields, with whether it links to many records
+fn relation_target(ty: &Type, fetch: bool) -> Option<(&Type, bool)> {
+    let ty = generic_inner(ty, "Option").unwrap_or(ty);
+
+    let (ty, many) = match generic_inner(ty, "Vec") {
+        Some(inner) => (inner, true),
+        None => (ty, false),
+    };
+
+    let ty = generic_inner(ty, "Option").unwrap_or(ty);
+
+    if let Some(target) = generic_inner(ty, "Links") {
+        return Some((target, true));
+    }
+
+    match generic_inner(ty, "Link") {
+        Some(target) => Some((target, many)),
+        None if fetch => Some((ty, many)),
+        None => None,
+    }
+}
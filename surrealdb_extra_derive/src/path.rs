@@ -0,0 +1,113 @@
+use ::syn::{Data, DeriveInput, Fields, Type};
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::__private::Span;
+use syn::Error;
+use crate::field::is_flatten;
+use crate::meta::{generic_inner, serde_rename};
+
+/// Generates the `<Struct>Fields` enum with a variant for every named field
+pub(crate) fn fields_enum(input: &DeriveInput) -> Result<TokenStream, Error> {
+    let Data::Struct(data) = &input.data else {
+        return Err(Error::new(Span::call_site(), "Table can only be derived for structs"));
+    };
+
+    let struct_name = &input.ident;
+    let vis = &input.vis;
+    let fields_name = format_ident!("{}Fields", struct_name);
+
+    let mut variants = vec![];
+    let mut names = vec![];
+    let mut nested_paths = vec![];
+
+    if let Fields::Named(named) = &data.fields {
+        for field in &named.named {
+            let Some(ident) = &field.ident else {
+                continue;
+            };
+
+            let fn_ident = ident;
+            let ident = ident.to_string().trim_start_matches("r#").to_string();
+            let variant = format_ident!("{}", upper_camel(&ident));
+
+            if is_flatten(field)? {
+                let nested_fields = nested_fields_enum(generic_inner(&field.ty, "Option").unwrap_or(&field.ty))?;
+
+                nested_paths.push(quote! {
+                    /// Path to a field of the flattened field
+                    pub fn #fn_ident(field: #nested_fields) -> ::surrealdb_extra::table::path::FieldPath {
+                        ::surrealdb_extra::table::path::TablePath::then(&Self::#variant, field)
+                    }
+                });
+            }
+
+            names.push(serde_rename(field)?.unwrap_or_else(|| ident.clone()));
+            variants.push(variant);
+        }
+    }
+
+    Ok(quote! {
+        /// Fields of
+        #[doc = concat!("[`", stringify!(#struct_name), "`]")]
+        /// that can be used as field paths in the query builders
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        #vis enum #fields_name {
+            #(#variants),*
+        }
+
+        impl #fields_name {
+            /// Name of the field in the database
+            pub fn as_str(&self) -> &'static str {
+                match *self {
+                    #(Self::#variants => #names),*
+                }
+            }
+
+            #(#nested_paths)*
+        }
+
+        impl ::std::fmt::Display for #fields_name {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+
+        impl ::surrealdb_extra::table::path::TablePath for #fields_name {
+            fn to_idiom(&self) -> ::surrealdb::sql::Idiom {
+                ::surrealdb::sql::Idiom::from(vec![::surrealdb::sql::Part::from(self.as_str())])
+            }
+        }
+    })
+}
+
+/// `<Type>Fields` next to the type, the enum generated for the flattened type
+pub(crate) fn nested_fields_enum(ty: &Type) -> Result<TokenStream, Error> {
+    let Type::Path(path) = ty else {
+        return Err(Error::new(Span::call_site(), "field(flatten) needs a struct type"));
+    };
+
+    let mut path = path.path.clone();
+
+    let Some(last) = path.segments.last_mut() else {
+        return Err(Error::new(Span::call_site(), "field(flatten) needs a struct type"));
+    };
+
+    last.ident = format_ident!("{}Fields", last.ident);
+    last.arguments = Default::default();
+
+    Ok(quote! { #path })
+}
+
+pub(crate) fn upper_camel(name: &str) -> String {
+    name.split('_')
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect::<String>(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
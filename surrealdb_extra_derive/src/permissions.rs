@@ -0,0 +1,65 @@
+use ::syn::{DeriveInput, Meta, Token};
+use ::syn::punctuated::Punctuated;
+use syn::__private::Span;
+use syn::Error;
+use crate::meta::lit_str;
+
+#[derive(Default)]
+pub(crate) struct PermissionsAttr {
+    pub(crate) select: Option<String>,
+    pub(crate) create: Option<String>,
+    pub(crate) update: Option<String>,
+    pub(crate) delete: Option<String>,
+}
+
+pub(crate) fn get_permissions(input: &DeriveInput) -> Result<Option<PermissionsAttr>, Error> {
+    let mut permissions = None;
+
+    for attr in &input.attrs {
+        if !attr.path().is_ident("table") {
+            continue;
+        }
+
+        let nested = attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)?;
+
+        for meta in nested {
+            if !meta.path().is_ident("permissions") {
+                continue;
+            }
+
+            if permissions.is_some() {
+                return Err(Error::new(Span::call_site(), "permissions can only be set once"));
+            }
+
+            let args = meta.require_list()?.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)?;
+
+            let mut attr = PermissionsAttr::default();
+
+            for arg in args {
+                let mnv = arg.require_name_value()?;
+
+                let permission = lit_str(&mnv.value)?;
+
+                if permission.trim().is_empty() {
+                    return Err(Error::new(Span::call_site(), "permission needs FULL, NONE or a condition"));
+                }
+
+                if mnv.path.is_ident("select") {
+                    attr.select = Some(permission);
+                } else if mnv.path.is_ident("create") {
+                    attr.create = Some(permission);
+                } else if mnv.path.is_ident("update") {
+                    attr.update = Some(permission);
+                } else if mnv.path.is_ident("delete") {
+                    attr.delete = Some(permission);
+                } else {
+                    return Err(Error::new(Span::call_site(), "permissions only accepts select, create, update and delete"));
+                }
+            }
+
+            permissions = Some(attr);
+        }
+    }
+
+    Ok(permissions)
+}
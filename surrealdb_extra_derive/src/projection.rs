@@ -0,0 +1,94 @@
+use ::syn::{Data, DeriveInput, Fields, Meta, Token, Type};
+use ::syn::punctuated::Punctuated;
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote, ToTokens};
+use syn::__private::Span;
+use syn::Error;
+use crate::meta::serde_rename;
+use crate::path::{nested_fields_enum, upper_camel};
+
+/// The type of `#[projection(of = ...)]`
+fn projection_of(input: &DeriveInput) -> Result<Type, Error> {
+    for attr in &input.attrs {
+        if !attr.path().is_ident("projection") {
+            continue;
+        }
+
+        let nested = attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)?;
+
+        if let Some(meta) = nested.into_iter().next() {
+            let Meta::NameValue(mnv) = meta.clone() else {
+                return Err(Error::new_spanned(meta, "projection only accepts of"));
+            };
+
+            if !mnv.path.is_ident("of") {
+                return Err(Error::new_spanned(mnv.path, "projection only accepts of"));
+            }
+
+            return syn::parse2(mnv.value.to_token_stream());
+        }
+    }
+
+    Err(Error::new(Span::call_site(), "Projection needs #[projection(of = Table)]"))
+}
+
+/// Generates the `Projection` implementation, the variants of the `<Table>Fields` enum check that the fields exist
+pub(crate) fn projection(input: &DeriveInput) -> Result<TokenStream, Error> {
+    let Data::Struct(data) = &input.data else {
+        return Err(Error::new(Span::call_site(), "Projection can only be derived for structs"));
+    };
+
+    let Fields::Named(named) = &data.fields else {
+        return Err(Error::new(Span::call_site(), "Projection needs named fields"));
+    };
+
+    if named.named.is_empty() {
+        return Err(Error::new(Span::call_site(), "Projection needs at least one field"));
+    }
+
+    let of = projection_of(input)?;
+    let fields_enum = nested_fields_enum(&of)?;
+
+    let mut names = vec![];
+    let mut variants = vec![];
+
+    for field in &named.named {
+        let Some(ident) = &field.ident else {
+            continue;
+        };
+
+        let ident = ident.to_string().trim_start_matches("r#").to_string();
+
+        names.push(serde_rename(field)?.unwrap_or_else(|| ident.clone()));
+        variants.push(format_ident!("{}", upper_camel(&ident), span = field.ident.as_ref().map_or(Span::call_site(), |i| i.span())));
+    }
+
+    let struct_name = &input.ident;
+
+    Ok(quote! {
+        const _: () = {
+            #[allow(dead_code)]
+            fn fields_of_table() {
+                #(let _ = #fields_enum::#variants;)*
+            }
+        };
+
+        impl ::surrealdb_extra::table::projection::Projection for #struct_name {
+            type Of = #of;
+
+            const FIELDS: &'static [&'static str] = &[#(#names),*];
+        }
+
+        impl #struct_name {
+            /// `SELECT` with the fields of the projection
+            pub fn select_builder<C: ::surrealdb::Connection>(db: &::surrealdb::Surreal<C>) -> ::surrealdb_extra::query::select::SelectBuilder<'_, C, ::surrealdb_extra::query::states::FilledWhat, ::surrealdb_extra::query::states::FilledFields, ::surrealdb_extra::query::states::NoCond> {
+                <Self as ::surrealdb_extra::table::projection::Projection>::select_builder(db)
+            }
+
+            /// Every row of the table as the projection
+            pub async fn select<C: ::surrealdb::Connection>(db: &impl ::surrealdb_extra::table::database::Database<C>) -> ::surrealdb_extra::anyhow::Result<Vec<Self>> {
+                ::surrealdb_extra::table::projection::select::<Self, C>(&db.connection()).await
+            }
+        }
+    })
+}
@@ -0,0 +1,88 @@
+use ::syn::{DeriveInput, Meta, Token, Expr, Lit};
+use ::syn::punctuated::Punctuated;
+use syn::__private::Span;
+use syn::Error;
+
+pub(crate) enum RetentionAttr {
+    KeepDays { days: u64, by: String },
+    KeepLast { n: u64, per: String, by: String },
+}
+
+pub(crate) fn get_retention(input: &DeriveInput) -> Result<Vec<RetentionAttr>, Error> {
+    let mut retention = vec![];
+
+    for attr in &input.attrs {
+        if !attr.path().is_ident("table") {
+            continue;
+        }
+
+        let nested = attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)?;
+
+        for meta in nested {
+            if !meta.path().is_ident("retention") {
+                continue;
+            }
+
+            let args = meta.require_list()?.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)?;
+
+            let mut keep_days = None;
+            let mut keep_last = None;
+            let mut per = None;
+            let mut by = None;
+
+            for arg in args {
+                let mnv = arg.require_name_value()?;
+
+                if mnv.path.is_ident("keep_days") {
+                    keep_days = Some(get_int(&mnv.value)?);
+                } else if mnv.path.is_ident("keep_last") {
+                    keep_last = Some(get_int(&mnv.value)?);
+                } else if mnv.path.is_ident("per") {
+                    per = Some(get_ident_str(&mnv.value, "per")?);
+                } else if mnv.path.is_ident("by") {
+                    by = Some(get_ident_str(&mnv.value, "by")?);
+                } else {
+                    return Err(Error::new(Span::call_site(), "retention only accepts keep_days, keep_last, per and by"));
+                }
+            }
+
+            match (keep_days, keep_last, per, by) {
+                (Some(days), None, None, Some(by)) => retention.push(RetentionAttr::KeepDays { days, by }),
+                (None, Some(n), Some(per), Some(by)) => retention.push(RetentionAttr::KeepLast { n, per, by }),
+                _ => return Err(Error::new(Span::call_site(), "retention needs keep_days and by, or keep_last, per and by")),
+            }
+        }
+    }
+
+    Ok(retention)
+}
+
+pub(crate) fn get_int(expr: &Expr) -> Result<u64, Error> {
+    let Expr::Lit(expr_lit) = expr else {
+        return Err(Error::new(Span::call_site(), "Wrong expression"));
+    };
+
+    let Lit::Int(lit) = &expr_lit.lit else {
+        return Err(Error::new(Span::call_site(), "Wrong type"));
+    };
+
+    lit.base10_parse()
+}
+
+fn get_ident_str(expr: &Expr, attr_name: &str) -> Result<String, Error> {
+    let Expr::Lit(expr_lit) = expr else {
+        return Err(Error::new_spanned(expr, "Wrong expression"));
+    };
+
+    let Lit::Str(lit) = &expr_lit.lit else {
+        return Err(Error::new_spanned(&expr_lit.lit, "Wrong type"));
+    };
+
+    let v = lit.value();
+
+    if v.is_empty() || !v.chars().all(|x| x.is_alphanumeric() || "_".contains(x)) {
+        return Err(Error::new_spanned(lit, format!("retention({}) attribute can only have alphanumeric and/or `_` characters", attr_name)));
+    }
+
+    Ok(v)
+}
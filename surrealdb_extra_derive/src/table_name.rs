@@ -7,7 +7,7 @@ pub(crate) fn get_table_name(input: &DeriveInput) -> Result<String, Error> {
     for attr in &input.attrs {
         if attr.path().is_ident("table") {
             let nested = attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated).unwrap();
-            if let Some(meta) = nested.into_iter().next() {
+            if let Some(meta) = nested.into_iter().find(|meta| meta.path().is_ident(attr_name)) {
                 let v = meta.require_name_value().and_then(|mnv| {
 
                     if !mnv.path.is_ident(attr_name) {
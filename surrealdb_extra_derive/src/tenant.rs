@@ -0,0 +1,63 @@
+use ::syn::{Data, DeriveInput, Fields, Meta, Token};
+use ::syn::punctuated::Punctuated;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::__private::Span;
+use syn::Error;
+use crate::meta::{lit_str, serde_rename};
+
+/// Generates `TENANT_FIELD` for `#[table(tenant_field = "...")]`, the struct needs a field with that name
+pub(crate) fn tenant_field(input: &DeriveInput) -> Result<TokenStream, Error> {
+    let mut tenant_field = None;
+
+    for attr in &input.attrs {
+        if !attr.path().is_ident("table") {
+            continue;
+        }
+
+        let nested = attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)?;
+
+        for meta in nested {
+            if !meta.path().is_ident("tenant_field") {
+                continue;
+            }
+
+            if tenant_field.is_some() {
+                return Err(Error::new(Span::call_site(), "tenant_field can only be set once"));
+            }
+
+            tenant_field = Some(lit_str(&meta.require_name_value()?.value)?);
+        }
+    }
+
+    let Some(tenant_field) = tenant_field else {
+        return Ok(quote! {});
+    };
+
+    let Data::Struct(data) = &input.data else {
+        return Err(Error::new(Span::call_site(), "Table can only be derived for structs"));
+    };
+
+    let Fields::Named(fields) = &data.fields else {
+        return Err(Error::new(Span::call_site(), "table(tenant_field) needs a named field"));
+    };
+
+    let mut found = false;
+
+    for field in &fields.named {
+        let name = match serde_rename(field)? {
+            Some(name) => name,
+            None => field.ident.as_ref().map(|ident| ident.to_string().trim_start_matches("r#").to_string()).unwrap_or_default(),
+        };
+
+        found |= name == tenant_field;
+    }
+
+    if !found {
+        return Err(Error::new(Span::call_site(), format!("table(tenant_field) needs a `{tenant_field}` field")));
+    }
+
+    Ok(quote! {
+        const TENANT_FIELD: Option<&'static str> = Some(#tenant_field);
+    })
+}
@@ -0,0 +1,50 @@
+use ::syn::{DeriveInput, Meta, Token};
+use ::syn::punctuated::Punctuated;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::__private::Span;
+use syn::Error;
+use crate::meta::lit_str;
+
+/// Generates `validate_record` for `#[table(validate)]` and `#[table(validate = "validator")]`
+pub(crate) fn validate_fn(input: &DeriveInput) -> Result<TokenStream, Error> {
+    let mut validate = None;
+
+    for attr in &input.attrs {
+        if !attr.path().is_ident("table") {
+            continue;
+        }
+
+        let nested = attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)?;
+
+        for meta in nested {
+            if !meta.path().is_ident("validate") {
+                continue;
+            }
+
+            if validate.is_some() {
+                return Err(Error::new(Span::call_site(), "validate can only be set once"));
+            }
+
+            validate = match meta {
+                Meta::Path(_) => Some(quote! {
+                    ::surrealdb_extra::table::validate::Validate::validate(self)
+                }),
+                Meta::NameValue(mnv) if lit_str(&mnv.value)? == "validator" => Some(quote! {
+                    ::surrealdb_extra::table::validate::validator(self)
+                }),
+                _ => return Err(Error::new(Span::call_site(), "validate only accepts no value or \"validator\"")),
+            };
+        }
+    }
+
+    let Some(validate) = validate else {
+        return Ok(quote! {});
+    };
+
+    Ok(quote! {
+        fn validate_record(&self) -> ::core::result::Result<(), ::surrealdb_extra::table::validate::ValidationError> {
+            #validate
+        }
+    })
+}
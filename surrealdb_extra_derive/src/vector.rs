@@ -0,0 +1,124 @@
+use ::syn::{Data, DeriveInput, Fields, Ident, Meta, Token, Type};
+use ::syn::punctuated::Punctuated;
+use syn::__private::Span;
+use syn::Error;
+use crate::meta::{generic_inner, lit_str, serde_rename};
+use crate::retention::get_int;
+
+const DISTANCES: &[(&str, &str)] = &[
+    ("chebyshev", "Chebyshev"),
+    ("cosine", "Cosine"),
+    ("euclidean", "Euclidean"),
+    ("hamming", "Hamming"),
+    ("jaccard", "Jaccard"),
+    ("manhattan", "Manhattan"),
+    ("pearson", "Pearson"),
+];
+
+/// Options of `#[index(vector(...))]`
+pub(crate) struct VectorAttr {
+    pub(crate) ident: Ident,
+    pub(crate) name: String,
+    pub(crate) embedding: Type,
+    pub(crate) dimension: u16,
+    pub(crate) distance: Ident,
+    pub(crate) mtree: bool,
+}
+
+/// Fields that have a `#[index(vector(...))]` attribute
+pub(crate) fn get_vector_indexes(input: &DeriveInput) -> Result<Vec<VectorAttr>, Error> {
+    let Data::Struct(data) = &input.data else {
+        return Err(Error::new(Span::call_site(), "Table can only be derived for structs"));
+    };
+
+    let Fields::Named(fields) = &data.fields else {
+        return Ok(vec![]);
+    };
+
+    let mut vector_indexes = vec![];
+
+    for field in &fields.named {
+        let Some(ident) = &field.ident else {
+            continue;
+        };
+
+        for attr in &field.attrs {
+            if !attr.path().is_ident("index") {
+                continue;
+            }
+
+            let nested = attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)?;
+
+            for meta in nested {
+                if !meta.path().is_ident("vector") {
+                    return Err(Error::new(Span::call_site(), "index only accepts vector"));
+                }
+
+                let Some(embedding) = embedding_type(&field.ty) else {
+                    return Err(Error::new(Span::call_site(), "index(vector) can only be used on fields of type Vec<f32> or Vec<f64>"));
+                };
+
+                let args = meta.require_list()?.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)?;
+
+                let mut dimension = None;
+                let mut distance = None;
+                let mut mtree = false;
+
+                for arg in args {
+                    match arg {
+                        Meta::Path(path) if path.is_ident("mtree") => mtree = true,
+                        Meta::Path(path) if path.is_ident("hnsw") => mtree = false,
+                        Meta::NameValue(mnv) if mnv.path.is_ident("dim") => {
+                            let dim = get_int(&mnv.value)?;
+
+                            dimension = Some(u16::try_from(dim).ok().filter(|dim| *dim > 0)
+                                .ok_or_else(|| Error::new(Span::call_site(), "vector(dim) must be between 1 and 65535"))?);
+                        }
+                        Meta::NameValue(mnv) if mnv.path.is_ident("dist") => {
+                            let dist = lit_str(&mnv.value)?.to_lowercase();
+
+                            let Some((_, variant)) = DISTANCES.iter().find(|(name, _)| *name == dist) else {
+                                return Err(Error::new(Span::call_site(), "vector(dist) must be chebyshev, cosine, euclidean, hamming, jaccard, manhattan or pearson"));
+                            };
+
+                            distance = Some(Ident::new(variant, Span::call_site()));
+                        }
+                        _ => return Err(Error::new(Span::call_site(), "vector only accepts dim, dist, hnsw and mtree")),
+                    }
+                }
+
+                let Some(dimension) = dimension else {
+                    return Err(Error::new(Span::call_site(), "vector needs dim"));
+                };
+
+                vector_indexes.push(VectorAttr {
+                    ident: ident.clone(),
+                    name: serde_rename(field)?.unwrap_or_else(|| ident.to_string().trim_start_matches("r#").to_string()),
+                    embedding: embedding.clone(),
+                    dimension,
+                    distance: distance.unwrap_or_else(|| Ident::new("Euclidean", Span::call_site())),
+                    mtree,
+                });
+            }
+        }
+    }
+
+    Ok(vector_indexes)
+}
+
+/// `Vec<f32>` or `Vec<f64>` of the field, also inside an `Option`
+fn embedding_type(ty: &Type) -> Option<&Type> {
+    let inner = generic_inner(ty, "Option").unwrap_or(ty);
+
+    let element = generic_inner(inner, "Vec")?;
+
+    let Type::Path(path) = element else {
+        return None;
+    };
+
+    if path.path.is_ident("f32") || path.path.is_ident("f64") {
+        return Some(inner);
+    }
+
+    None
+}
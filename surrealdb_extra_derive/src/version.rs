@@ -0,0 +1,52 @@
+use ::syn::{Data, DeriveInput, Fields, Meta, Token};
+use ::syn::punctuated::Punctuated;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::__private::Span;
+use syn::Error;
+use crate::meta::serde_rename;
+
+/// Generates `VERSION_FIELD` for `#[table(versioned)]`, the struct needs a `version` field
+pub(crate) fn version_field(input: &DeriveInput) -> Result<TokenStream, Error> {
+    let mut versioned = false;
+
+    for attr in &input.attrs {
+        if !attr.path().is_ident("table") {
+            continue;
+        }
+
+        let nested = attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)?;
+
+        for meta in nested {
+            if !meta.path().is_ident("versioned") {
+                continue;
+            }
+
+            meta.require_path_only()?;
+
+            versioned = true;
+        }
+    }
+
+    if !versioned {
+        return Ok(quote! {});
+    }
+
+    let Data::Struct(data) = &input.data else {
+        return Err(Error::new(Span::call_site(), "Table can only be derived for structs"));
+    };
+
+    let Fields::Named(fields) = &data.fields else {
+        return Err(Error::new(Span::call_site(), "table(versioned) needs a `version: u64` field"));
+    };
+
+    let Some(field) = fields.named.iter().find(|f| f.ident.as_ref().is_some_and(|ident| ident == "version")) else {
+        return Err(Error::new(Span::call_site(), "table(versioned) needs a `version: u64` field"));
+    };
+
+    let name = serde_rename(field)?.unwrap_or_else(|| "version".to_string());
+
+    Ok(quote! {
+        const VERSION_FIELD: Option<&'static str> = Some(#name);
+    })
+}
@@ -230,7 +230,7 @@ async fn create_builder() {
         ..Test::default()
     };
 
-    let query = t.create_builder(&db).only().output("name").to_query();
+    let query = t.create_builder(&db).unwrap().only().output("name").to_query();
 
     let res = query.await;
 